@@ -12,6 +12,24 @@
 #[cfg(feature = "cache_me")]
 pub mod cache_me;
 
+/// [`Cache`] bot adaptor which caches responses to idempotent requests.
+///
+/// [`Cache`]: cache::Cache
+#[cfg(feature = "cache")]
+pub mod cache;
+
+/// [`Dedup`] bot adaptor which drops duplicates of mutating requests.
+///
+/// [`Dedup`]: dedup::Dedup
+#[cfg(feature = "dedup")]
+pub mod dedup;
+
+/// [`Accounting`] bot adaptor which counts requests per method and per chat.
+///
+/// [`Accounting`]: accounting::Accounting
+#[cfg(feature = "accounting")]
+pub mod accounting;
+
 /// [`Trace`] bot adaptor which traces requests.
 ///
 /// [`Trace`]: trace::Trace
@@ -26,6 +44,12 @@ pub mod trace;
 #[cfg(feature = "erased")]
 pub mod erased;
 
+/// [`MigrateChats`] bot adaptor which replaces ids of migrated groups.
+///
+/// [`MigrateChats`]: migrate_chats::MigrateChats
+#[cfg(feature = "migrate_chats")]
+pub mod migrate_chats;
+
 /// [`Throttle`] bot adaptor which allows automatically throttle when hitting
 /// API limits.
 ///
@@ -33,14 +57,30 @@ pub mod erased;
 #[cfg(feature = "throttle")]
 pub mod throttle;
 
+/// [`Timeout`] bot adaptor which fails requests that don't complete in time.
+///
+/// [`Timeout`]: timeout::Timeout
+#[cfg(feature = "timeout")]
+pub mod timeout;
+
 mod parse_mode;
 
+#[cfg(feature = "accounting")]
+pub use accounting::Accounting;
+#[cfg(feature = "cache")]
+pub use cache::Cache;
 #[cfg(feature = "cache_me")]
 pub use cache_me::CacheMe;
+#[cfg(feature = "dedup")]
+pub use dedup::Dedup;
 #[cfg(feature = "erased")]
 pub use erased::ErasedRequester;
+#[cfg(feature = "migrate_chats")]
+pub use migrate_chats::MigrateChats;
 #[cfg(feature = "throttle")]
 pub use throttle::Throttle;
+#[cfg(feature = "timeout")]
+pub use timeout::Timeout;
 #[cfg(feature = "trace_adaptor")]
 pub use trace::Trace;
 
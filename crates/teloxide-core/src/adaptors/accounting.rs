@@ -0,0 +1,578 @@
+use std::{
+    any::Any,
+    collections::HashMap,
+    fmt,
+    future::{Future, IntoFuture},
+    sync::{Arc, Mutex, Weak},
+    time::{Duration, Instant},
+};
+
+use tokio::task::JoinHandle;
+use url::Url;
+
+use crate::{
+    payloads::*,
+    requests::{HasPayload, Output, Payload, Request, Requester},
+    types::*,
+};
+
+/// The number of chats listed by the [`Display`] implementation of
+/// [`TrafficReport`].
+///
+/// [`Display`]: std::fmt::Display
+const TOP_CHATS: usize = 10;
+
+/// Counts requests per method and per chat.
+///
+/// This helps to understand which features of a bot consume its rate budget.
+/// Requests are counted when they are sent, whether they succeed or not. The
+/// counts are aggregated over a window, which starts when the adaptor is
+/// created and is restarted by [`Accounting::take_report`]. A report can be
+/// emitted periodically with [`Accounting::spawn_reporter`], to the log, to a
+/// callback or as a Telegram message to the owner of the bot.
+///
+/// Requests are attributed to the chat from their `chat_id` parameter.
+///
+/// Clones of the adaptor share the counts.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use teloxide_core::{prelude::*, types::ChatId};
+///
+/// # async {
+/// let owner = ChatId(0);
+/// let bot = Bot::new("TOKEN").accounting();
+///
+/// // Sends the report to the owner every day. The report itself is sent
+/// // through the inner bot, so it isn't counted.
+/// let inner = bot.inner().clone();
+/// bot.spawn_reporter(Duration::from_secs(24 * 60 * 60), move |report| {
+///     let inner = inner.clone();
+///     async move {
+///         if let Err(err) = inner.send_message(owner, report.to_string()).await {
+///             log::error!("Failed to send the traffic report: {err}");
+///         }
+///     }
+/// });
+///
+/// // Or writes it to the log
+/// bot.spawn_reporter(Duration::from_secs(60 * 60), |report| async move {
+///     log::info!("{report}");
+/// });
+/// # };
+/// ```
+#[derive(Clone, Debug)]
+pub struct Accounting<B> {
+    bot: B,
+    stats: Arc<Mutex<Stats>>,
+}
+
+#[derive(Debug)]
+struct Stats {
+    since: Instant,
+    methods: HashMap<&'static str, u64>,
+    chats: HashMap<Recipient, u64>,
+}
+
+impl Stats {
+    fn new() -> Self {
+        Self { since: Instant::now(), methods: HashMap::new(), chats: HashMap::new() }
+    }
+
+    fn report(&self) -> TrafficReport {
+        fn sorted<K>(counts: impl IntoIterator<Item = (K, u64)>) -> Vec<(K, u64)> {
+            let mut counts: Vec<_> = counts.into_iter().collect();
+            counts.sort_by(|(_, a), (_, b)| b.cmp(a));
+            counts
+        }
+
+        TrafficReport {
+            window: self.since.elapsed(),
+            total: self.methods.values().sum(),
+            methods: sorted(self.methods.iter().map(|(&method, &n)| (method, n))),
+            chats: sorted(self.chats.iter().map(|(chat, &n)| (chat.clone(), n))),
+        }
+    }
+}
+
+impl<B> Accounting<B> {
+    /// Creates new adaptor, which starts counting requests right away.
+    ///
+    /// Note: it's recommended to use [`RequesterExt::accounting`] instead.
+    ///
+    /// [`RequesterExt::accounting`]: crate::requests::RequesterExt::accounting
+    pub fn new(bot: B) -> Self {
+        Self { bot, stats: Arc::new(Mutex::new(Stats::new())) }
+    }
+
+    /// Allows to access inner bot
+    pub fn inner(&self) -> &B {
+        &self.bot
+    }
+
+    /// Unwraps inner bot
+    pub fn into_inner(self) -> B {
+        self.bot
+    }
+
+    /// Returns the counts of the current window.
+    #[must_use]
+    pub fn report(&self) -> TrafficReport {
+        self.stats.lock().unwrap().report()
+    }
+
+    /// Returns the counts of the current window and starts a new one.
+    #[must_use]
+    pub fn take_report(&self) -> TrafficReport {
+        let mut stats = self.stats.lock().unwrap();
+        let report = stats.report();
+        *stats = Stats::new();
+        report
+    }
+
+    /// Spawns a task, which passes a report to `f` every `period`, starting a
+    /// new window each time (see [`Accounting::take_report`]).
+    ///
+    /// The task stops when all clones of the adaptor are dropped.
+    pub fn spawn_reporter<F, Fut>(&self, period: Duration, mut f: F) -> JoinHandle<()>
+    where
+        F: FnMut(TrafficReport) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send,
+    {
+        let stats = Arc::downgrade(&self.stats);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            // The first tick completes immediately
+            interval.tick().await;
+
+            loop {
+                interval.tick().await;
+                let Some(report) = take_report(&stats) else { break };
+                f(report).await;
+            }
+        })
+    }
+
+    fn wrap<R>(&self, inner: R) -> AccountingRequest<R> {
+        AccountingRequest { inner, stats: Arc::clone(&self.stats) }
+    }
+}
+
+fn take_report(stats: &Weak<Mutex<Stats>>) -> Option<TrafficReport> {
+    let stats = stats.upgrade()?;
+    let mut stats = stats.lock().unwrap();
+    let report = stats.report();
+    *stats = Stats::new();
+    Some(report)
+}
+
+/// Counts of requests sent through an [`Accounting`] adaptor.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct TrafficReport {
+    /// For how long the requests were counted.
+    pub window: Duration,
+
+    /// The total number of requests.
+    pub total: u64,
+
+    /// Numbers of requests per method (e.g. `SendMessage`), the most used
+    /// methods first.
+    pub methods: Vec<(&'static str, u64)>,
+
+    /// Numbers of requests per chat, the most active chats first. Requests
+    /// without a `chat_id` parameter aren't included.
+    pub chats: Vec<(Recipient, u64)>,
+}
+
+impl fmt::Display for TrafficReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} API calls in the last {}s", self.total, self.window.as_secs())?;
+
+        if !self.methods.is_empty() {
+            f.write_str("\n\nMethods:")?;
+            for (method, n) in &self.methods {
+                write!(f, "\n  {method}: {n}")?;
+            }
+        }
+
+        if !self.chats.is_empty() {
+            f.write_str("\n\nTop chats:")?;
+            for (chat, n) in self.chats.iter().take(TOP_CHATS) {
+                write!(f, "\n  {chat}: {n}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+macro_rules! fwd_accounting {
+    ($m:ident $this:ident ($($arg:ident : $T:ty),*)) => {
+        $this.wrap($this.inner().$m($($arg),*))
+    };
+}
+
+macro_rules! fty {
+    ($T:ident) => {
+        AccountingRequest<B::$T>
+    };
+}
+
+impl<B> Requester for Accounting<B>
+where
+    B: Requester,
+{
+    type Err = B::Err;
+
+    requester_forward! {
+        get_me,
+        log_out,
+        close,
+        get_updates,
+        set_webhook,
+        delete_webhook,
+        get_webhook_info,
+        forward_message,
+        forward_messages,
+        copy_message,
+        copy_messages,
+        send_message,
+        send_photo,
+        send_audio,
+        send_document,
+        send_video,
+        send_animation,
+        send_voice,
+        send_video_note,
+        send_paid_media,
+        send_media_group,
+        send_location,
+        edit_message_live_location,
+        edit_message_live_location_inline,
+        stop_message_live_location,
+        stop_message_live_location_inline,
+        edit_message_checklist,
+        send_venue,
+        send_contact,
+        send_poll,
+        send_checklist,
+        send_dice,
+        send_chat_action,
+        set_message_reaction,
+        get_user_profile_photos,
+        set_user_emoji_status,
+        get_file,
+        kick_chat_member,
+        ban_chat_member,
+        unban_chat_member,
+        restrict_chat_member,
+        promote_chat_member,
+        set_chat_administrator_custom_title,
+        ban_chat_sender_chat,
+        unban_chat_sender_chat,
+        set_chat_permissions,
+        export_chat_invite_link,
+        create_chat_invite_link,
+        edit_chat_invite_link,
+        create_chat_subscription_invite_link,
+        edit_chat_subscription_invite_link,
+        revoke_chat_invite_link,
+        set_chat_photo,
+        delete_chat_photo,
+        set_chat_title,
+        set_chat_description,
+        pin_chat_message,
+        unpin_chat_message,
+        unpin_all_chat_messages,
+        leave_chat,
+        get_chat,
+        get_chat_administrators,
+        get_chat_members_count,
+        get_chat_member_count,
+        get_chat_member,
+        set_chat_sticker_set,
+        delete_chat_sticker_set,
+        get_forum_topic_icon_stickers,
+        create_forum_topic,
+        edit_forum_topic,
+        close_forum_topic,
+        reopen_forum_topic,
+        delete_forum_topic,
+        unpin_all_forum_topic_messages,
+        edit_general_forum_topic,
+        close_general_forum_topic,
+        reopen_general_forum_topic,
+        hide_general_forum_topic,
+        unhide_general_forum_topic,
+        unpin_all_general_forum_topic_messages,
+        answer_callback_query,
+        get_user_chat_boosts,
+        set_my_commands,
+        get_business_connection,
+        get_my_commands,
+        set_my_name,
+        get_my_name,
+        set_my_description,
+        get_my_description,
+        set_my_short_description,
+        get_my_short_description,
+        set_chat_menu_button,
+        get_chat_menu_button,
+        set_my_default_administrator_rights,
+        get_my_default_administrator_rights,
+        delete_my_commands,
+        answer_inline_query,
+        answer_web_app_query,
+        save_prepared_inline_message,
+        edit_message_text,
+        edit_message_text_inline,
+        edit_message_caption,
+        edit_message_caption_inline,
+        edit_message_media,
+        edit_message_media_inline,
+        edit_message_reply_markup,
+        edit_message_reply_markup_inline,
+        stop_poll,
+        delete_message,
+        delete_messages,
+        send_sticker,
+        get_sticker_set,
+        get_custom_emoji_stickers,
+        upload_sticker_file,
+        create_new_sticker_set,
+        add_sticker_to_set,
+        set_sticker_position_in_set,
+        delete_sticker_from_set,
+        replace_sticker_in_set,
+        set_sticker_set_thumbnail,
+        set_custom_emoji_sticker_set_thumbnail,
+        set_sticker_set_title,
+        delete_sticker_set,
+        set_sticker_emoji_list,
+        set_sticker_keywords,
+        set_sticker_mask_position,
+        get_available_gifts,
+        send_gift,
+        send_gift_chat,
+        gift_premium_subscription,
+        verify_user,
+        verify_chat,
+        remove_user_verification,
+        remove_chat_verification,
+        read_business_message,
+        delete_business_messages,
+        set_business_account_name,
+        set_business_account_username,
+        set_business_account_bio,
+        set_business_account_profile_photo,
+        remove_business_account_profile_photo,
+        set_business_account_gift_settings,
+        get_business_account_star_balance,
+        transfer_business_account_stars,
+        get_business_account_gifts,
+        convert_gift_to_stars,
+        upgrade_gift,
+        transfer_gift,
+        post_story,
+        edit_story,
+        delete_story,
+        send_invoice,
+        create_invoice_link,
+        answer_shipping_query,
+        answer_pre_checkout_query,
+        get_my_star_balance,
+        get_star_transactions,
+        refund_star_payment,
+        edit_user_star_subscription,
+        set_passport_data_errors,
+        send_game,
+        set_game_score,
+        set_game_score_inline,
+        get_game_high_scores,
+        approve_chat_join_request,
+        decline_chat_join_request,
+        => fwd_accounting, fty
+    }
+}
+
+download_forward! {
+    B
+    Accounting<B>
+    { this => this.inner() }
+}
+
+/// Defines [`chat_of`], which returns the `chat_id` parameter of the listed
+/// payloads.
+macro_rules! chats {
+    (
+        recipient { $($Recipient:ident)* }
+        chat_id { $($ChatId:ident)* }
+        optional_chat_id { $($OptionalChatId:ident)* }
+    ) => {
+        /// Returns the chat `payload` is sent to, if it's known.
+        fn chat_of(payload: &dyn Any) -> Option<Recipient> {
+            $(
+                if let Some(p) = payload.downcast_ref::<$Recipient>() {
+                    return Some(p.chat_id.clone());
+                }
+            )*
+            $(
+                if let Some(p) = payload.downcast_ref::<$ChatId>() {
+                    return Some(p.chat_id.into());
+                }
+            )*
+            $(
+                if let Some(p) = payload.downcast_ref::<$OptionalChatId>() {
+                    return p.chat_id.map(Into::into);
+                }
+            )*
+            if let Some(p) = payload.downcast_ref::<SetGameScore>() {
+                return Some(ChatId(p.chat_id.into()).into());
+            }
+            None
+        }
+    };
+}
+
+chats! {
+    recipient {
+        ApproveChatJoinRequest BanChatMember BanChatSenderChat CloseForumTopic
+        CloseGeneralForumTopic CopyMessage CopyMessages CreateChatInviteLink
+        CreateChatSubscriptionInviteLink CreateForumTopic DeclineChatJoinRequest DeleteChatPhoto
+        DeleteChatStickerSet DeleteForumTopic DeleteMessage DeleteMessages EditChatInviteLink
+        EditChatSubscriptionInviteLink EditForumTopic EditGeneralForumTopic EditMessageCaption
+        EditMessageLiveLocation EditMessageMedia EditMessageReplyMarkup EditMessageText
+        ExportChatInviteLink ForwardMessage ForwardMessages GetChat GetChatAdministrators
+        GetChatMember GetChatMemberCount GetChatMembersCount GetUserChatBoosts HideGeneralForumTopic
+        KickChatMember LeaveChat PinChatMessage PromoteChatMember RemoveChatVerification
+        ReopenForumTopic ReopenGeneralForumTopic RestrictChatMember RevokeChatInviteLink
+        SendAnimation SendAudio SendChatAction SendContact SendDice SendDocument SendGiftChat
+        SendInvoice SendLocation SendMediaGroup SendMessage SendPaidMedia SendPhoto SendPoll
+        SendSticker SendVenue SendVideo SendVideoNote SendVoice SetChatAdministratorCustomTitle
+        SetChatDescription SetChatPermissions SetChatPhoto SetChatStickerSet SetChatTitle
+        SetMessageReaction StopMessageLiveLocation StopPoll UnbanChatMember UnbanChatSenderChat
+        UnhideGeneralForumTopic UnpinAllChatMessages UnpinAllForumTopicMessages
+        UnpinAllGeneralForumTopicMessages UnpinChatMessage VerifyChat
+    }
+    chat_id { EditMessageChecklist ReadBusinessMessage SendChecklist SendGame }
+    optional_chat_id { GetChatMenuButton SetChatMenuButton }
+}
+
+#[must_use = "Requests are lazy and do nothing unless sent"]
+#[derive(Clone)]
+pub struct AccountingRequest<R> {
+    inner: R,
+    stats: Arc<Mutex<Stats>>,
+}
+
+impl<R> AccountingRequest<R>
+where
+    R: Request,
+    R::Payload: 'static,
+{
+    fn count(&self) {
+        let chat = chat_of(self.inner.payload_ref());
+
+        let mut stats = self.stats.lock().unwrap();
+        *stats.methods.entry(<R::Payload as Payload>::NAME).or_default() += 1;
+        if let Some(chat) = chat {
+            *stats.chats.entry(chat).or_default() += 1;
+        }
+    }
+}
+
+impl<R> HasPayload for AccountingRequest<R>
+where
+    R: HasPayload,
+{
+    type Payload = R::Payload;
+
+    fn payload_mut(&mut self) -> &mut Self::Payload {
+        self.inner.payload_mut()
+    }
+
+    fn payload_ref(&self) -> &Self::Payload {
+        self.inner.payload_ref()
+    }
+}
+
+impl<R> Request for AccountingRequest<R>
+where
+    R: Request,
+    R::Payload: 'static,
+{
+    type Err = R::Err;
+
+    type Send = R::Send;
+
+    type SendRef = R::SendRef;
+
+    fn send(self) -> Self::Send {
+        self.count();
+        self.inner.send()
+    }
+
+    fn send_ref(&self) -> Self::SendRef {
+        self.count();
+        self.inner.send_ref()
+    }
+}
+
+impl<R> IntoFuture for AccountingRequest<R>
+where
+    R: Request,
+    R::Payload: 'static,
+{
+    type Output = Result<Output<Self>, <Self as Request>::Err>;
+    type IntoFuture = <Self as Request>::Send;
+
+    fn into_future(self) -> Self::IntoFuture {
+        self.send()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{requests::RequesterExt, test_utils::unreachable_bot};
+
+    #[tokio::test]
+    async fn counts_requests() {
+        // Requests fail, but are counted anyway
+        let bot = unreachable_bot().accounting();
+
+        bot.send_message(ChatId(1), "hi").await.unwrap_err();
+        bot.send_message(ChatId(1), "hi").await.unwrap_err();
+        bot.clone().delete_message(ChatId(2), MessageId(1)).send_ref().await.unwrap_err();
+        bot.get_me().await.unwrap_err();
+        // Not sent, so not counted
+        let _ = bot.send_message(ChatId(3), "hi");
+
+        let report = bot.take_report();
+        assert_eq!(report.total, 4);
+        assert_eq!(report.methods[0], ("SendMessage", 2));
+        assert_eq!(report.methods.len(), 3);
+        assert_eq!(report.chats, [(Recipient::Id(ChatId(1)), 2), (Recipient::Id(ChatId(2)), 1)]);
+        assert!(report.to_string().starts_with("4 API calls in the last 0s"));
+
+        assert_eq!(bot.report().total, 0);
+    }
+
+    #[test]
+    fn chat_of_payloads() {
+        let channel = Recipient::ChannelUsername("@channel".to_owned());
+
+        assert_eq!(chat_of(&SendMessage::new(channel.clone(), "hi")), Some(channel));
+        assert_eq!(chat_of(&SendGame::new(ChatId(5), "game")), Some(Recipient::Id(ChatId(5))));
+        assert_eq!(
+            chat_of(&GetChatMenuButton::new().chat_id(ChatId(6))),
+            Some(Recipient::Id(ChatId(6)))
+        );
+        assert_eq!(chat_of(&GetChatMenuButton::new()), None);
+        assert_eq!(chat_of(&GetMe::new()), None);
+    }
+}
@@ -0,0 +1,615 @@
+use std::{
+    collections::HashMap,
+    future::{Future, IntoFuture},
+    hash::Hash,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{self, Poll},
+    time::{Duration, Instant},
+};
+
+use futures::ready;
+use url::Url;
+
+use crate::{
+    requests::{HasPayload, Output, Request, Requester},
+    types::*,
+};
+
+/// The number of cached responses of a method after which expired responses
+/// are removed.
+const PRUNE_LEN: usize = 1024;
+
+/// Cache of responses to idempotent requests.
+///
+/// Responses to [`get_chat`], [`get_chat_member`] and [`get_file`] can be
+/// cached, each for its own time to live. Methods without a configured time to
+/// live aren't cached, so [`Cache::new`] alone doesn't change anything.
+///
+/// Cached responses may be outdated for up to their time to live. If you know
+/// that something has changed (e.g. after [`set_chat_title`]), use the
+/// `invalidate_*` methods. Note that chats are cached by the exact
+/// [`Recipient`], so `@username` and the id of the same chat are cached
+/// separately.
+///
+/// Clones of the adaptor share the cache.
+///
+/// To cache [`get_me`], use [`CacheMe`].
+///
+/// [`get_chat`]: crate::requests::Requester::get_chat
+/// [`get_chat_member`]: crate::requests::Requester::get_chat_member
+/// [`get_file`]: crate::requests::Requester::get_file
+/// [`set_chat_title`]: crate::requests::Requester::set_chat_title
+/// [`get_me`]: crate::requests::Requester::get_me
+/// [`CacheMe`]: crate::adaptors::CacheMe
+///
+/// ## Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use teloxide_core::{prelude::*, types::ChatId};
+///
+/// # async {
+/// let bot = Bot::new("TOKEN")
+///     .cache()
+///     .get_chat_ttl(Duration::from_secs(10 * 60))
+///     .get_chat_member_ttl(Duration::from_secs(60));
+///
+/// let chat = bot.get_chat(ChatId(-100123)).await?;
+/// // Doesn't send a request
+/// let chat = bot.get_chat(ChatId(-100123)).await?;
+/// # Ok::<_, teloxide_core::RequestError>(()) };
+/// ```
+#[derive(Clone, Debug)]
+pub struct Cache<B> {
+    bot: B,
+    chats: Responses<Recipient, ChatFullInfo>,
+    chat_members: Responses<(Recipient, UserId), ChatMember>,
+    files: Responses<FileId, File>,
+}
+
+impl<B> Cache<B> {
+    /// Creates new cache, which doesn't cache any method.
+    ///
+    /// Note: it's recommended to use [`RequesterExt::cache`] instead.
+    ///
+    /// [`RequesterExt::cache`]: crate::requests::RequesterExt::cache
+    pub fn new(bot: B) -> Self {
+        Self {
+            bot,
+            chats: Responses::default(),
+            chat_members: Responses::default(),
+            files: Responses::default(),
+        }
+    }
+
+    /// Caches responses to [`get_chat`] for `ttl`.
+    ///
+    /// [`get_chat`]: crate::requests::Requester::get_chat
+    #[must_use]
+    pub fn get_chat_ttl(self, ttl: Duration) -> Self {
+        Self { chats: self.chats.ttl(ttl), ..self }
+    }
+
+    /// Caches responses to [`get_chat_member`] for `ttl`.
+    ///
+    /// [`get_chat_member`]: crate::requests::Requester::get_chat_member
+    #[must_use]
+    pub fn get_chat_member_ttl(self, ttl: Duration) -> Self {
+        Self { chat_members: self.chat_members.ttl(ttl), ..self }
+    }
+
+    /// Caches responses to [`get_file`] for `ttl`.
+    ///
+    /// Note that download links are only guaranteed to be valid for an hour.
+    ///
+    /// [`get_file`]: crate::requests::Requester::get_file
+    #[must_use]
+    pub fn get_file_ttl(self, ttl: Duration) -> Self {
+        Self { files: self.files.ttl(ttl), ..self }
+    }
+
+    /// Removes the cached response to [`get_chat`] for the chat.
+    ///
+    /// [`get_chat`]: crate::requests::Requester::get_chat
+    pub fn invalidate_chat<C>(&self, chat_id: C)
+    where
+        C: Into<Recipient>,
+    {
+        self.chats.remove(&chat_id.into());
+    }
+
+    /// Removes the cached response to [`get_chat_member`] for the member of
+    /// the chat.
+    ///
+    /// [`get_chat_member`]: crate::requests::Requester::get_chat_member
+    pub fn invalidate_chat_member<C>(&self, chat_id: C, user_id: UserId)
+    where
+        C: Into<Recipient>,
+    {
+        self.chat_members.remove(&(chat_id.into(), user_id));
+    }
+
+    /// Removes the cached response to [`get_file`] for the file.
+    ///
+    /// [`get_file`]: crate::requests::Requester::get_file
+    pub fn invalidate_file(&self, file_id: &FileId) {
+        self.files.remove(file_id);
+    }
+
+    /// Removes all cached responses.
+    pub fn clear(&self) {
+        self.chats.clear();
+        self.chat_members.clear();
+        self.files.clear();
+    }
+
+    /// Allows to access inner bot
+    pub fn inner(&self) -> &B {
+        &self.bot
+    }
+
+    /// Unwraps inner bot
+    pub fn into_inner(self) -> B {
+        self.bot
+    }
+}
+
+macro_rules! f {
+    ($m:ident $this:ident ($($arg:ident : $T:ty),*)) => {
+        $this.inner().$m($($arg),*)
+    };
+}
+
+macro_rules! fty {
+    ($T:ident) => {
+        B::$T
+    };
+}
+
+impl<B> Requester for Cache<B>
+where
+    B: Requester,
+{
+    type Err = B::Err;
+
+    type GetChat = CachedRequest<B::GetChat, Recipient>;
+
+    fn get_chat<C>(&self, chat_id: C) -> Self::GetChat
+    where
+        C: Into<Recipient>,
+    {
+        CachedRequest {
+            inner: self.bot.get_chat(chat_id),
+            key: |payload| payload.chat_id.clone(),
+            responses: self.chats.clone(),
+        }
+    }
+
+    type GetChatMember = CachedRequest<B::GetChatMember, (Recipient, UserId)>;
+
+    fn get_chat_member<C>(&self, chat_id: C, user_id: UserId) -> Self::GetChatMember
+    where
+        C: Into<Recipient>,
+    {
+        CachedRequest {
+            inner: self.bot.get_chat_member(chat_id, user_id),
+            key: |payload| (payload.chat_id.clone(), payload.user_id),
+            responses: self.chat_members.clone(),
+        }
+    }
+
+    type GetFile = CachedRequest<B::GetFile, FileId>;
+
+    fn get_file(&self, file_id: FileId) -> Self::GetFile {
+        CachedRequest {
+            inner: self.bot.get_file(file_id),
+            key: |payload| payload.file_id.clone(),
+            responses: self.files.clone(),
+        }
+    }
+
+    requester_forward! {
+        get_me,
+        log_out,
+        close,
+        get_updates,
+        set_webhook,
+        delete_webhook,
+        get_webhook_info,
+        forward_message,
+        forward_messages,
+        copy_message,
+        copy_messages,
+        send_message,
+        send_photo,
+        send_audio,
+        send_document,
+        send_video,
+        send_animation,
+        send_voice,
+        send_video_note,
+        send_paid_media,
+        send_media_group,
+        send_location,
+        edit_message_live_location,
+        edit_message_live_location_inline,
+        stop_message_live_location,
+        stop_message_live_location_inline,
+        edit_message_checklist,
+        send_venue,
+        send_contact,
+        send_poll,
+        send_checklist,
+        send_dice,
+        send_chat_action,
+        set_message_reaction,
+        get_user_profile_photos,
+        set_user_emoji_status,
+        kick_chat_member,
+        ban_chat_member,
+        unban_chat_member,
+        restrict_chat_member,
+        promote_chat_member,
+        set_chat_administrator_custom_title,
+        ban_chat_sender_chat,
+        unban_chat_sender_chat,
+        set_chat_permissions,
+        export_chat_invite_link,
+        create_chat_invite_link,
+        edit_chat_invite_link,
+        create_chat_subscription_invite_link,
+        edit_chat_subscription_invite_link,
+        revoke_chat_invite_link,
+        set_chat_photo,
+        delete_chat_photo,
+        set_chat_title,
+        set_chat_description,
+        pin_chat_message,
+        unpin_chat_message,
+        unpin_all_chat_messages,
+        leave_chat,
+        get_chat_administrators,
+        get_chat_members_count,
+        get_chat_member_count,
+        set_chat_sticker_set,
+        delete_chat_sticker_set,
+        get_forum_topic_icon_stickers,
+        create_forum_topic,
+        edit_forum_topic,
+        close_forum_topic,
+        reopen_forum_topic,
+        delete_forum_topic,
+        unpin_all_forum_topic_messages,
+        edit_general_forum_topic,
+        close_general_forum_topic,
+        reopen_general_forum_topic,
+        hide_general_forum_topic,
+        unhide_general_forum_topic,
+        unpin_all_general_forum_topic_messages,
+        answer_callback_query,
+        get_user_chat_boosts,
+        set_my_commands,
+        get_business_connection,
+        get_my_commands,
+        set_my_name,
+        get_my_name,
+        set_my_description,
+        get_my_description,
+        set_my_short_description,
+        get_my_short_description,
+        set_chat_menu_button,
+        get_chat_menu_button,
+        set_my_default_administrator_rights,
+        get_my_default_administrator_rights,
+        delete_my_commands,
+        answer_inline_query,
+        answer_web_app_query,
+        save_prepared_inline_message,
+        edit_message_text,
+        edit_message_text_inline,
+        edit_message_caption,
+        edit_message_caption_inline,
+        edit_message_media,
+        edit_message_media_inline,
+        edit_message_reply_markup,
+        edit_message_reply_markup_inline,
+        stop_poll,
+        delete_message,
+        delete_messages,
+        send_sticker,
+        get_sticker_set,
+        get_custom_emoji_stickers,
+        upload_sticker_file,
+        create_new_sticker_set,
+        add_sticker_to_set,
+        set_sticker_position_in_set,
+        delete_sticker_from_set,
+        replace_sticker_in_set,
+        set_sticker_set_thumbnail,
+        set_custom_emoji_sticker_set_thumbnail,
+        set_sticker_set_title,
+        delete_sticker_set,
+        set_sticker_emoji_list,
+        set_sticker_keywords,
+        set_sticker_mask_position,
+        get_available_gifts,
+        send_gift,
+        send_gift_chat,
+        gift_premium_subscription,
+        verify_user,
+        verify_chat,
+        remove_user_verification,
+        remove_chat_verification,
+        read_business_message,
+        delete_business_messages,
+        set_business_account_name,
+        set_business_account_username,
+        set_business_account_bio,
+        set_business_account_profile_photo,
+        remove_business_account_profile_photo,
+        set_business_account_gift_settings,
+        get_business_account_star_balance,
+        transfer_business_account_stars,
+        get_business_account_gifts,
+        convert_gift_to_stars,
+        upgrade_gift,
+        transfer_gift,
+        post_story,
+        edit_story,
+        delete_story,
+        send_invoice,
+        create_invoice_link,
+        answer_shipping_query,
+        answer_pre_checkout_query,
+        get_my_star_balance,
+        get_star_transactions,
+        refund_star_payment,
+        edit_user_star_subscription,
+        set_passport_data_errors,
+        send_game,
+        set_game_score,
+        set_game_score_inline,
+        get_game_high_scores,
+        approve_chat_join_request,
+        decline_chat_join_request
+        => f, fty
+    }
+}
+
+download_forward! {
+    B
+    Cache<B>
+    { this => this.inner() }
+}
+
+/// Cached responses to a method.
+#[derive(Debug)]
+struct Responses<K, V> {
+    /// `None` if the method isn't cached.
+    ttl: Option<Duration>,
+    entries: Arc<Mutex<HashMap<K, (Instant, V)>>>,
+}
+
+impl<K, V> Responses<K, V>
+where
+    K: Hash + Eq,
+    V: Clone,
+{
+    fn ttl(self, ttl: Duration) -> Self {
+        Self { ttl: Some(ttl), ..self }
+    }
+
+    fn get(&self, key: &K) -> Option<V> {
+        let ttl = self.ttl?;
+        let mut entries = self.entries.lock().unwrap();
+
+        match entries.get(key) {
+            Some((cached_at, value)) if cached_at.elapsed() < ttl => Some(value.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn insert(&self, key: K, value: V) {
+        let Some(ttl) = self.ttl else { return };
+        let mut entries = self.entries.lock().unwrap();
+
+        if entries.len() >= PRUNE_LEN {
+            entries.retain(|_, (cached_at, _)| cached_at.elapsed() < ttl);
+        }
+        entries.insert(key, (Instant::now(), value));
+    }
+
+    fn remove(&self, key: &K) {
+        self.entries.lock().unwrap().remove(key);
+    }
+
+    fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
+
+impl<K, V> Clone for Responses<K, V> {
+    fn clone(&self) -> Self {
+        Self { ttl: self.ttl, entries: Arc::clone(&self.entries) }
+    }
+}
+
+impl<K, V> Default for Responses<K, V> {
+    fn default() -> Self {
+        Self { ttl: None, entries: <_>::default() }
+    }
+}
+
+#[must_use = "Requests are lazy and do nothing unless sent"]
+pub struct CachedRequest<R, K>
+where
+    R: Request,
+{
+    inner: R,
+    key: fn(&R::Payload) -> K,
+    responses: Responses<K, Output<R>>,
+}
+
+impl<R, K> CachedRequest<R, K>
+where
+    R: Request,
+    Output<R>: Clone,
+    K: Hash + Eq,
+{
+    /// Returns the cached response, or the key to cache the response with.
+    fn lookup(&self) -> Result<Output<R>, K> {
+        let key = (self.key)(self.inner.payload_ref());
+        self.responses.get(&key).ok_or(key)
+    }
+}
+
+impl<R, K> HasPayload for CachedRequest<R, K>
+where
+    R: Request,
+{
+    type Payload = R::Payload;
+
+    fn payload_mut(&mut self) -> &mut Self::Payload {
+        self.inner.payload_mut()
+    }
+
+    fn payload_ref(&self) -> &Self::Payload {
+        self.inner.payload_ref()
+    }
+}
+
+impl<R, K> Request for CachedRequest<R, K>
+where
+    R: Request,
+    Output<R>: Clone + std::marker::Send,
+    K: Hash + Eq + std::marker::Send,
+{
+    type Err = R::Err;
+
+    type Send = Send<R::Send, K, Output<R>>;
+
+    type SendRef = Send<R::SendRef, K, Output<R>>;
+
+    fn send(self) -> Self::Send {
+        match self.lookup() {
+            Ok(cached) => Send::cached(cached, self.responses),
+            Err(key) => Send::pending(self.inner.send(), key, self.responses),
+        }
+    }
+
+    fn send_ref(&self) -> Self::SendRef {
+        match self.lookup() {
+            Ok(cached) => Send::cached(cached, self.responses.clone()),
+            Err(key) => Send::pending(self.inner.send_ref(), key, self.responses.clone()),
+        }
+    }
+}
+
+impl<R, K> IntoFuture for CachedRequest<R, K>
+where
+    R: Request,
+    Output<R>: Clone + std::marker::Send,
+    K: Hash + Eq + std::marker::Send,
+{
+    type Output = Result<Output<Self>, <Self as Request>::Err>;
+    type IntoFuture = <Self as Request>::Send;
+
+    fn into_future(self) -> Self::IntoFuture {
+        self.send()
+    }
+}
+
+#[pin_project::pin_project]
+pub struct Send<F, K, V> {
+    cached: Option<V>,
+    #[pin]
+    inner: Option<F>,
+    key: Option<K>,
+    responses: Responses<K, V>,
+}
+
+impl<F, K, V> Send<F, K, V> {
+    fn cached(cached: V, responses: Responses<K, V>) -> Self {
+        Self { cached: Some(cached), inner: None, key: None, responses }
+    }
+
+    fn pending(inner: F, key: K, responses: Responses<K, V>) -> Self {
+        Self { cached: None, inner: Some(inner), key: Some(key), responses }
+    }
+}
+
+impl<F, K, V, E> Future for Send<F, K, V>
+where
+    F: Future<Output = Result<V, E>>,
+    K: Hash + Eq,
+    V: Clone,
+{
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+
+        if let Some(cached) = this.cached.take() {
+            return Poll::Ready(Ok(cached));
+        }
+
+        let inner = this.inner.as_pin_mut().expect("`Send` polled after completion");
+        let ret = ready!(inner.poll(cx));
+        if let (Ok(value), Some(key)) = (&ret, this.key.take()) {
+            this.responses.insert(key, value.clone());
+        }
+
+        Poll::Ready(ret)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{requests::RequesterExt, test_utils::unreachable_bot, Bot, RequestError};
+
+    fn file() -> File {
+        serde_json::from_str(
+            r#"{"file_id": "id", "file_unique_id": "unique", "file_size": 1, "file_path": "a"}"#,
+        )
+        .unwrap()
+    }
+
+    async fn respond(bot: &Cache<Bot>, file: File) {
+        let send = Send::pending(
+            futures::future::ready(Ok::<_, RequestError>(file.clone())),
+            file.id.clone(),
+            bot.files.clone(),
+        );
+        send.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn caches_responses() {
+        // Requests fail, unless they are cached
+        let bot = unreachable_bot();
+        let file = file();
+
+        let not_cached = bot.clone().cache();
+        respond(&not_cached, file.clone()).await;
+        not_cached.get_file(file.id.clone()).await.unwrap_err();
+
+        let cached = bot.clone().cache().get_file_ttl(Duration::from_secs(60));
+        respond(&cached, file.clone()).await;
+        assert_eq!(cached.get_file(file.id.clone()).await.unwrap(), file);
+        assert_eq!(cached.clone().get_file(file.id.clone()).send_ref().await.unwrap(), file);
+
+        cached.invalidate_file(&file.id);
+        cached.get_file(file.id.clone()).await.unwrap_err();
+
+        let expiring = bot.cache().get_file_ttl(Duration::ZERO);
+        respond(&expiring, file.clone()).await;
+        expiring.get_file(file.id.clone()).await.unwrap_err();
+    }
+}
@@ -1,4 +1,8 @@
-use std::{future::IntoFuture, pin::Pin, sync::Arc};
+use std::{
+    future::IntoFuture,
+    pin::Pin,
+    sync::{Arc, Mutex},
+};
 
 use futures::{
     future,
@@ -18,11 +22,17 @@ use crate::{
 /// `get_me` cache.
 ///
 /// Bot's user is hardly ever changed, so sometimes it's reasonable to cache
-/// response from `get_me` method.
+/// response from `get_me` method. If it does change (e.g. the name of the bot
+/// was changed with [`set_my_name`]), use [`invalidate`].
+///
+/// [`set_my_name`]: crate::requests::Requester::set_my_name
+/// [`invalidate`]: CacheMe::invalidate
 #[derive(Clone, Debug)]
 pub struct CacheMe<B> {
     bot: B,
-    me: Arc<OnceCell<Me>>,
+    // The cell is replaced on invalidation, so that pending requests don't fill
+    // the new cell with stale responses.
+    me: Arc<Mutex<Arc<OnceCell<Me>>>>,
 }
 
 impl<B> CacheMe<B> {
@@ -32,7 +42,7 @@ impl<B> CacheMe<B> {
     ///
     /// [`RequesterExt::cache_me`]: crate::requests::RequesterExt::cache_me
     pub fn new(bot: B) -> CacheMe<B> {
-        Self { bot, me: Arc::new(OnceCell::new()) }
+        Self { bot, me: <_>::default() }
     }
 
     /// Allows to access inner bot
@@ -49,10 +59,24 @@ impl<B> CacheMe<B> {
     ///
     /// Returns cached response from `get_me`, if it was cached.
     ///
-    /// Note: internally this uses [`Arc::make_mut`] so this will **not**
-    /// clear cache of clones of self.
+    /// Note: this replaces the cache of self with a new empty one, so this will
+    /// **not** clear cache of clones of self.
     pub fn clear(&mut self) -> Option<Me> {
-        Arc::make_mut(&mut self.me).take()
+        let me = std::mem::take(&mut self.me);
+        let cell = me.lock().unwrap();
+        cell.get().cloned()
+    }
+
+    /// Invalidates the cache, so that the next `get_me` call sends a request.
+    ///
+    /// Returns cached response from `get_me`, if it was cached.
+    ///
+    /// Unlike [`clear`], this also invalidates the cache of clones of self.
+    ///
+    /// [`clear`]: CacheMe::clear
+    pub fn invalidate(&self) -> Option<Me> {
+        let old = std::mem::take(&mut *self.me.lock().unwrap());
+        old.get().cloned()
     }
 }
 
@@ -77,12 +101,10 @@ where
     type GetMe = CachedMeRequest<B::GetMe>;
 
     fn get_me(&self) -> Self::GetMe {
-        match self.me.get() {
+        let cell = Arc::clone(&self.me.lock().unwrap());
+        match cell.get() {
             Some(me) => CachedMeRequest(Inner::Ready(me.clone()), GetMe::new()),
-            None => CachedMeRequest(
-                Inner::Pending(self.bot.get_me(), Arc::clone(&self.me)),
-                GetMe::new(),
-            ),
+            None => CachedMeRequest(Inner::Pending(self.bot.get_me(), cell), GetMe::new()),
         }
     }
 
@@ -362,3 +384,25 @@ impl<F: Future<Output = Result<T, E>>, T: Clone, E> Future for Init<F, T> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Bot;
+
+    #[test]
+    fn invalidate() {
+        let me = crate::test_utils::me();
+
+        let bot = CacheMe::new(Bot::new("TOKEN"));
+        *bot.me.lock().unwrap() = Arc::new(OnceCell::with_value(me.clone()));
+
+        let mut clone = bot.clone();
+        assert_eq!(clone.clear(), Some(me.clone()));
+        assert!(matches!(bot.get_me().0, Inner::Ready(_)));
+
+        assert_eq!(bot.clone().invalidate(), Some(me));
+        assert!(matches!(bot.get_me().0, Inner::Pending(..)));
+        assert_eq!(bot.invalidate(), None);
+    }
+}
@@ -0,0 +1,546 @@
+use std::{
+    any::Any,
+    collections::{hash_map::DefaultHasher, HashMap},
+    fmt,
+    future::{Future, IntoFuture},
+    hash::{Hash, Hasher},
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{self, Poll},
+    time::{Duration, Instant},
+};
+
+use futures::{
+    channel::oneshot,
+    future::{FutureExt, Shared},
+    ready,
+};
+use serde::Serialize;
+use url::Url;
+
+use crate::{
+    requests::{HasPayload, Output, Payload, Request, Requester},
+    types::*,
+};
+
+/// The number of remembered requests after which expired ones are removed.
+const PRUNE_LEN: usize = 1024;
+
+/// Drops duplicates of mutating requests.
+///
+/// A request is a duplicate if a request to the same method with exactly the
+/// same parameters was sent through this adaptor less than `window` ago.
+/// Duplicates aren't sent to Telegram, instead they resolve to the response to
+/// the original request, once it's received. This protects against sending
+/// the same message twice, when an update is delivered twice or when a user
+/// double-clicks an inline button.
+///
+/// If the original request fails, its duplicates are sent as usual.
+///
+/// `get_*` methods are never deduplicated. Requests uploading files are never
+/// considered duplicates, since each upload is sent as a new attachment.
+///
+/// Clones of the adaptor share the remembered requests.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use teloxide_core::{prelude::*, types::ChatId};
+///
+/// # async {
+/// let bot = Bot::new("TOKEN").dedup(Duration::from_secs(2));
+///
+/// let (first, second) = futures::join!(
+///     bot.send_message(ChatId(0), "Hi!").send(),
+///     // Isn't sent, resolves to the same message as the first request
+///     bot.send_message(ChatId(0), "Hi!").send(),
+/// );
+/// assert_eq!(first?.id, second?.id);
+/// # Ok::<_, teloxide_core::RequestError>(()) };
+/// ```
+#[derive(Clone, Debug)]
+pub struct Dedup<B> {
+    bot: B,
+    window: Duration,
+    sent: Sent,
+}
+
+impl<B> Dedup<B> {
+    /// Creates new adaptor, which drops duplicates sent within `window`.
+    ///
+    /// Note: it's recommended to use [`RequesterExt::dedup`] instead.
+    ///
+    /// [`RequesterExt::dedup`]: crate::requests::RequesterExt::dedup
+    pub fn new(bot: B, window: Duration) -> Self {
+        Self { bot, window, sent: Sent::default() }
+    }
+
+    /// Returns the window within which duplicates are dropped.
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// Allows to access inner bot
+    pub fn inner(&self) -> &B {
+        &self.bot
+    }
+
+    /// Unwraps inner bot
+    pub fn into_inner(self) -> B {
+        self.bot
+    }
+
+    fn wrap<R>(&self, inner: R) -> DedupRequest<R> {
+        DedupRequest { inner, window: self.window, sent: self.sent.clone() }
+    }
+}
+
+macro_rules! f {
+    ($m:ident $this:ident ($($arg:ident : $T:ty),*)) => {
+        $this.inner().$m($($arg),*)
+    };
+}
+
+macro_rules! fty {
+    ($T:ident) => {
+        B::$T
+    };
+}
+
+macro_rules! fwd_dedup {
+    ($m:ident $this:ident ($($arg:ident : $T:ty),*)) => {
+        $this.wrap($this.inner().$m($($arg),*))
+    };
+}
+
+macro_rules! fty_dedup {
+    ($T:ident) => {
+        DedupRequest<B::$T>
+    };
+}
+
+impl<B> Requester for Dedup<B>
+where
+    B: Requester,
+{
+    type Err = B::Err;
+
+    requester_forward! {
+        get_me,
+        get_updates,
+        get_webhook_info,
+        get_user_profile_photos,
+        get_file,
+        get_chat,
+        get_chat_administrators,
+        get_chat_members_count,
+        get_chat_member_count,
+        get_chat_member,
+        get_forum_topic_icon_stickers,
+        get_user_chat_boosts,
+        get_business_connection,
+        get_my_commands,
+        get_my_name,
+        get_my_description,
+        get_my_short_description,
+        get_chat_menu_button,
+        get_my_default_administrator_rights,
+        get_sticker_set,
+        get_custom_emoji_stickers,
+        get_available_gifts,
+        get_business_account_star_balance,
+        get_business_account_gifts,
+        get_my_star_balance,
+        get_star_transactions,
+        get_game_high_scores,
+        => f, fty
+    }
+
+    requester_forward! {
+        log_out,
+        close,
+        set_webhook,
+        delete_webhook,
+        forward_message,
+        forward_messages,
+        copy_message,
+        copy_messages,
+        send_message,
+        send_photo,
+        send_audio,
+        send_document,
+        send_video,
+        send_animation,
+        send_voice,
+        send_video_note,
+        send_paid_media,
+        send_media_group,
+        send_location,
+        edit_message_live_location,
+        edit_message_live_location_inline,
+        stop_message_live_location,
+        stop_message_live_location_inline,
+        edit_message_checklist,
+        send_venue,
+        send_contact,
+        send_poll,
+        send_checklist,
+        send_dice,
+        send_chat_action,
+        set_message_reaction,
+        set_user_emoji_status,
+        kick_chat_member,
+        ban_chat_member,
+        unban_chat_member,
+        restrict_chat_member,
+        promote_chat_member,
+        set_chat_administrator_custom_title,
+        ban_chat_sender_chat,
+        unban_chat_sender_chat,
+        set_chat_permissions,
+        export_chat_invite_link,
+        create_chat_invite_link,
+        edit_chat_invite_link,
+        create_chat_subscription_invite_link,
+        edit_chat_subscription_invite_link,
+        revoke_chat_invite_link,
+        set_chat_photo,
+        delete_chat_photo,
+        set_chat_title,
+        set_chat_description,
+        pin_chat_message,
+        unpin_chat_message,
+        unpin_all_chat_messages,
+        leave_chat,
+        set_chat_sticker_set,
+        delete_chat_sticker_set,
+        create_forum_topic,
+        edit_forum_topic,
+        close_forum_topic,
+        reopen_forum_topic,
+        delete_forum_topic,
+        unpin_all_forum_topic_messages,
+        edit_general_forum_topic,
+        close_general_forum_topic,
+        reopen_general_forum_topic,
+        hide_general_forum_topic,
+        unhide_general_forum_topic,
+        unpin_all_general_forum_topic_messages,
+        answer_callback_query,
+        set_my_commands,
+        set_my_name,
+        set_my_description,
+        set_my_short_description,
+        set_chat_menu_button,
+        set_my_default_administrator_rights,
+        delete_my_commands,
+        answer_inline_query,
+        answer_web_app_query,
+        save_prepared_inline_message,
+        edit_message_text,
+        edit_message_text_inline,
+        edit_message_caption,
+        edit_message_caption_inline,
+        edit_message_media,
+        edit_message_media_inline,
+        edit_message_reply_markup,
+        edit_message_reply_markup_inline,
+        stop_poll,
+        delete_message,
+        delete_messages,
+        send_sticker,
+        upload_sticker_file,
+        create_new_sticker_set,
+        add_sticker_to_set,
+        set_sticker_position_in_set,
+        delete_sticker_from_set,
+        replace_sticker_in_set,
+        set_sticker_set_thumbnail,
+        set_custom_emoji_sticker_set_thumbnail,
+        set_sticker_set_title,
+        delete_sticker_set,
+        set_sticker_emoji_list,
+        set_sticker_keywords,
+        set_sticker_mask_position,
+        send_gift,
+        send_gift_chat,
+        gift_premium_subscription,
+        verify_user,
+        verify_chat,
+        remove_user_verification,
+        remove_chat_verification,
+        read_business_message,
+        delete_business_messages,
+        set_business_account_name,
+        set_business_account_username,
+        set_business_account_bio,
+        set_business_account_profile_photo,
+        remove_business_account_profile_photo,
+        set_business_account_gift_settings,
+        transfer_business_account_stars,
+        convert_gift_to_stars,
+        upgrade_gift,
+        transfer_gift,
+        post_story,
+        edit_story,
+        delete_story,
+        send_invoice,
+        create_invoice_link,
+        answer_shipping_query,
+        answer_pre_checkout_query,
+        refund_star_payment,
+        edit_user_star_subscription,
+        set_passport_data_errors,
+        send_game,
+        set_game_score,
+        set_game_score_inline,
+        approve_chat_join_request,
+        decline_chat_join_request,
+        => fwd_dedup, fty_dedup
+    }
+}
+
+download_forward! {
+    B
+    Dedup<B>
+    { this => this.inner() }
+}
+
+/// Requests sent through a [`Dedup`].
+#[derive(Clone, Default)]
+struct Sent(Arc<Mutex<HashMap<Key, (Instant, Response)>>>);
+
+/// The method name and the hash of the parameters of a request.
+type Key = (&'static str, u64);
+
+/// A shared future resolving to the response to the original request.
+type Response = Box<dyn Any + std::marker::Send + Sync>;
+
+impl Sent {
+    /// Remembers the request, unless it's a duplicate of a request sent less
+    /// than `window` ago.
+    fn register<V>(&self, key: Key, window: Duration) -> Registration<V>
+    where
+        V: Clone + std::marker::Send + Sync + 'static,
+    {
+        let mut sent = self.0.lock().unwrap();
+
+        let original = sent
+            .get(&key)
+            .filter(|(sent_at, _)| sent_at.elapsed() < window)
+            .and_then(|(_, response)| response.downcast_ref::<Shared<oneshot::Receiver<V>>>())
+            // The original request has failed
+            .filter(|response| !matches!(response.peek(), Some(Err(_))));
+        if let Some(response) = original {
+            return Registration::Duplicate(response.clone());
+        }
+
+        if sent.len() >= PRUNE_LEN {
+            sent.retain(|_, (sent_at, _)| sent_at.elapsed() < window);
+        }
+
+        let (tx, rx) = oneshot::channel();
+        sent.insert(key, (Instant::now(), Box::new(rx.shared())));
+        Registration::Original(tx)
+    }
+}
+
+impl fmt::Debug for Sent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sent").field("len", &self.0.lock().unwrap().len()).finish()
+    }
+}
+
+enum Registration<V> {
+    /// The request is sent, the response is passed to the duplicates.
+    Original(oneshot::Sender<V>),
+    /// The request waits for the response to the original request.
+    Duplicate(Shared<oneshot::Receiver<V>>),
+    /// The request can't be deduplicated.
+    None,
+}
+
+#[must_use = "Requests are lazy and do nothing unless sent"]
+pub struct DedupRequest<R> {
+    inner: R,
+    window: Duration,
+    sent: Sent,
+}
+
+impl<R> DedupRequest<R>
+where
+    R: Request,
+    R::Payload: Serialize,
+    Output<R>: Clone + std::marker::Send + Sync + 'static,
+{
+    fn register(&self) -> Registration<Output<R>> {
+        let payload = self.inner.payload_ref();
+        let Ok(params) = serde_json::to_vec(payload) else { return Registration::None };
+
+        let mut hasher = DefaultHasher::new();
+        params.hash(&mut hasher);
+
+        self.sent.register((<R::Payload as Payload>::NAME, hasher.finish()), self.window)
+    }
+}
+
+impl<R> HasPayload for DedupRequest<R>
+where
+    R: HasPayload,
+{
+    type Payload = R::Payload;
+
+    fn payload_mut(&mut self) -> &mut Self::Payload {
+        self.inner.payload_mut()
+    }
+
+    fn payload_ref(&self) -> &Self::Payload {
+        self.inner.payload_ref()
+    }
+}
+
+impl<R> Request for DedupRequest<R>
+where
+    R: Request,
+    R::Payload: Serialize,
+    Output<R>: Clone + std::marker::Send + Sync + 'static,
+{
+    type Err = R::Err;
+
+    type Send = Send<R::Send, Output<R>>;
+
+    type SendRef = Send<R::SendRef, Output<R>>;
+
+    fn send(self) -> Self::Send {
+        let registration = self.register();
+        Send::new(self.inner.send(), registration)
+    }
+
+    fn send_ref(&self) -> Self::SendRef {
+        Send::new(self.inner.send_ref(), self.register())
+    }
+}
+
+impl<R> IntoFuture for DedupRequest<R>
+where
+    R: Request,
+    R::Payload: Serialize,
+    Output<R>: Clone + std::marker::Send + Sync + 'static,
+{
+    type Output = Result<Output<Self>, <Self as Request>::Err>;
+    type IntoFuture = <Self as Request>::Send;
+
+    fn into_future(self) -> Self::IntoFuture {
+        self.send()
+    }
+}
+
+#[pin_project::pin_project]
+pub struct Send<F, V> {
+    #[pin]
+    inner: F,
+    original: Option<oneshot::Sender<V>>,
+    duplicate: Option<Shared<oneshot::Receiver<V>>>,
+}
+
+impl<F, V> Send<F, V> {
+    fn new(inner: F, registration: Registration<V>) -> Self {
+        let (original, duplicate) = match registration {
+            Registration::Original(tx) => (Some(tx), None),
+            Registration::Duplicate(rx) => (None, Some(rx)),
+            Registration::None => (None, None),
+        };
+
+        Self { inner, original, duplicate }
+    }
+}
+
+impl<F, V, E> Future for Send<F, V>
+where
+    F: Future<Output = Result<V, E>>,
+    V: Clone,
+{
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+
+        if let Some(duplicate) = this.duplicate {
+            let response = ready!(Pin::new(duplicate).poll(cx));
+            *this.duplicate = None;
+
+            // If the original request has failed, this one is sent instead
+            if let Ok(response) = response {
+                return Poll::Ready(Ok(response));
+            }
+        }
+
+        let ret = ready!(this.inner.poll(cx));
+        if let (Ok(response), Some(tx)) = (&ret, this.original.take()) {
+            // Duplicates may be already dropped
+            let _ = tx.send(response.clone());
+        }
+
+        Poll::Ready(ret)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{requests::RequesterExt, test_utils::unreachable_bot, RequestError};
+
+    #[tokio::test]
+    async fn drops_duplicates() {
+        // Requests fail, unless they are dropped as duplicates
+        let bot = unreachable_bot().dedup(Duration::from_secs(60));
+
+        let (tx, rx) = oneshot::channel();
+        let original = Send::new(
+            async { rx.await.unwrap() },
+            bot.delete_message(ChatId(1), MessageId(1)).register(),
+        );
+        let duplicate = bot.delete_message(ChatId(1), MessageId(1)).send();
+        let duplicate_ref = bot.clone().delete_message(ChatId(1), MessageId(1)).send_ref();
+
+        bot.delete_message(ChatId(1), MessageId(2)).await.unwrap_err();
+        bot.inner()
+            .clone()
+            .dedup(Duration::from_secs(60))
+            .delete_message(ChatId(1), MessageId(1))
+            .await
+            .unwrap_err();
+
+        tx.send(Ok::<_, RequestError>(True)).unwrap();
+        let (original, duplicate, duplicate_ref) =
+            futures::join!(original, duplicate, duplicate_ref);
+        original.unwrap();
+        duplicate.unwrap();
+        duplicate_ref.unwrap();
+
+        // Duplicates of a failed request are sent
+        let (tx, rx) = oneshot::channel();
+        let original = Send::new(
+            async { rx.await.unwrap() },
+            bot.delete_message(ChatId(1), MessageId(3)).register(),
+        );
+        let duplicate = bot.delete_message(ChatId(1), MessageId(3)).send();
+        tx.send(Err(RequestError::InvalidJson {
+            source: Arc::new(serde_json::from_str::<True>("").unwrap_err()),
+            raw: "".into(),
+        }))
+        .unwrap();
+        original.await.unwrap_err();
+        duplicate.await.unwrap_err();
+
+        let expiring = bot.into_inner().dedup(Duration::ZERO);
+        let original = Send::new(
+            futures::future::ready(Ok::<_, RequestError>(True)),
+            expiring.delete_message(ChatId(1), MessageId(1)).register(),
+        );
+        original.await.unwrap();
+        expiring.delete_message(ChatId(1), MessageId(1)).await.unwrap_err();
+    }
+}
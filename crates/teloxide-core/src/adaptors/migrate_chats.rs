@@ -0,0 +1,648 @@
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    future::{Future, IntoFuture},
+    pin::Pin,
+    sync::{Arc, RwLock},
+    task::{self, Poll},
+};
+
+use futures::{future::BoxFuture, ready};
+use url::Url;
+
+use crate::{
+    errors::AsResponseParameters,
+    requests::{HasPayload, Output, Request, Requester},
+    types::*,
+};
+
+type MigrateCallback = Arc<dyn Fn(ChatId, ChatId) + std::marker::Send + Sync>;
+
+type SaveMigration =
+    Arc<dyn Fn(ChatId, ChatId) -> BoxFuture<'static, ()> + std::marker::Send + Sync>;
+
+/// A storage which persists migrations found by [`MigrateChats`], see
+/// [`MigrateChats::with_storage`].
+pub trait MigrationStorage {
+    type Error: Debug;
+
+    /// Returns all stored migrations, mapping old chat ids to new ones.
+    fn load(self: Arc<Self>) -> BoxFuture<'static, Result<HashMap<ChatId, ChatId>, Self::Error>>;
+
+    /// Stores a new migration of the chat `old` to `new`.
+    fn save(
+        self: Arc<Self>,
+        old: ChatId,
+        new: ChatId,
+    ) -> BoxFuture<'static, Result<(), Self::Error>>;
+}
+
+/// Automatically replaces ids of groups that were migrated to supergroups.
+///
+/// When a group is migrated to a supergroup, requests using the old chat id
+/// fail with [`RequestError::MigrateToChatId`]. This adaptor remembers the new
+/// chat ids from such errors and uses them in all subsequent requests with the
+/// `chat_id` (and `from_chat_id`) parameter.
+///
+/// Note that the request which has failed is not retried.
+///
+/// By default the migrations are only stored in memory. To keep them between
+/// restarts, use a [`MigrationStorage`] (see [`with_storage`]), or save them in
+/// the [`on_migrate`] callback and restore them with [`with_migrations`].
+///
+/// [`RequestError::MigrateToChatId`]: crate::RequestError::MigrateToChatId
+/// [`with_storage`]: MigrateChats::with_storage
+/// [`on_migrate`]: MigrateChats::on_migrate
+/// [`with_migrations`]: MigrateChats::with_migrations
+///
+/// ## Examples
+///
+/// ```no_run
+/// use teloxide_core::{prelude::*, types::ChatId};
+///
+/// # async {
+/// let bot = Bot::new("TOKEN")
+///     .migrate_chats()
+///     .with_migrations([(ChatId(-123), ChatId(-100123))])
+///     .on_migrate(|old, new| log::info!("Chat {old} was migrated to {new}"));
+///
+/// // Actually sent to `-100123`
+/// bot.send_message(ChatId(-123), "Hi!").await?;
+/// # Ok::<_, teloxide_core::RequestError>(()) };
+/// ```
+#[derive(Clone)]
+pub struct MigrateChats<B> {
+    inner: B,
+    migrations: Arc<RwLock<HashMap<ChatId, ChatId>>>,
+    on_migrate: Option<MigrateCallback>,
+    save: Option<SaveMigration>,
+}
+
+impl<B> MigrateChats<B> {
+    /// Creates new [`MigrateChats`].
+    ///
+    /// Note: it's recommended to use [`RequesterExt::migrate_chats`] instead.
+    ///
+    /// [`RequesterExt::migrate_chats`]: crate::requests::RequesterExt::migrate_chats
+    pub fn new(inner: B) -> Self {
+        Self { inner, migrations: <_>::default(), on_migrate: None, save: None }
+    }
+
+    /// Adds known migrations, as pairs of old and new chat ids.
+    #[must_use]
+    pub fn with_migrations<I>(self, migrations: I) -> Self
+    where
+        I: IntoIterator<Item = (ChatId, ChatId)>,
+    {
+        self.migrations.write().unwrap().extend(migrations);
+        self
+    }
+
+    /// Sets a callback that is called with old and new chat ids when a new
+    /// migration is found.
+    #[must_use]
+    pub fn on_migrate<F>(self, callback: F) -> Self
+    where
+        F: Fn(ChatId, ChatId) + std::marker::Send + Sync + 'static,
+    {
+        Self { on_migrate: Some(Arc::new(callback)), ..self }
+    }
+
+    /// Loads migrations from `storage`, and saves new migrations there.
+    ///
+    /// A request which has found a new migration completes after the migration
+    /// is saved. Errors of saving are logged.
+    pub async fn with_storage<S>(self, storage: Arc<S>) -> Result<Self, S::Error>
+    where
+        S: MigrationStorage + std::marker::Send + Sync + 'static,
+    {
+        let this = self.with_migrations(Arc::clone(&storage).load().await?);
+        let save: SaveMigration = Arc::new(move |old, new| {
+            let save = Arc::clone(&storage).save(old, new);
+            Box::pin(async move {
+                if let Err(err) = save.await {
+                    log::error!("Failed to save the migration of chat {old} to {new}: {err:?}");
+                }
+            })
+        });
+
+        Ok(Self { save: Some(save), ..this })
+    }
+
+    /// Returns the new id of the chat `old`, if it was migrated.
+    pub fn migrated(&self, old: ChatId) -> Option<ChatId> {
+        self.migrations.read().unwrap().get(&old).copied()
+    }
+
+    /// Returns all known migrations, as pairs of old and new chat ids.
+    pub fn migrations(&self) -> Vec<(ChatId, ChatId)> {
+        self.migrations.read().unwrap().iter().map(|(&old, &new)| (old, new)).collect()
+    }
+
+    /// Allows to access inner bot
+    pub fn inner(&self) -> &B {
+        &self.inner
+    }
+
+    /// Unwraps inner bot
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+
+    fn fix(&self, chat_id: &mut Recipient) {
+        if let Recipient::Id(id) = chat_id {
+            if let Some(new) = self.migrated(*id) {
+                *id = new;
+            }
+        }
+    }
+
+    fn wrap<R>(&self, inner: R, chat_id: fn(&R::Payload) -> &Recipient) -> MigrateChatsRequest<R>
+    where
+        R: HasPayload,
+    {
+        MigrateChatsRequest {
+            inner,
+            chat_id,
+            migrations: Arc::clone(&self.migrations),
+            on_migrate: self.on_migrate.clone(),
+            save: self.save.clone(),
+        }
+    }
+}
+
+macro_rules! f {
+    ($m:ident $this:ident ($($arg:ident : $T:ty),*)) => {{
+        let mut inner = $this.inner().$m($($arg),*);
+        $this.fix(&mut inner.payload_mut().chat_id);
+        $this.wrap(inner, |p| &p.chat_id)
+    }};
+}
+
+macro_rules! ffrom {
+    ($m:ident $this:ident ($($arg:ident : $T:ty),*)) => {{
+        let mut inner = $this.inner().$m($($arg),*);
+        let payload = inner.payload_mut();
+        $this.fix(&mut payload.chat_id);
+        $this.fix(&mut payload.from_chat_id);
+        $this.wrap(inner, |p| &p.chat_id)
+    }};
+}
+
+macro_rules! fty {
+    ($T:ident) => {
+        MigrateChatsRequest<B::$T>
+    };
+}
+
+macro_rules! fid {
+    ($m:ident $this:ident ($($arg:ident : $T:ty),*)) => {
+        $this.inner().$m($($arg),*)
+    };
+}
+
+macro_rules! ftyid {
+    ($T:ident) => {
+        B::$T
+    };
+}
+
+impl<B> Requester for MigrateChats<B>
+where
+    B: Requester,
+    B::Err: AsResponseParameters,
+{
+    type Err = B::Err;
+
+    requester_forward! {
+        forward_message,
+        forward_messages,
+        copy_message,
+        copy_messages
+        => ffrom, fty
+    }
+
+    requester_forward! {
+        send_message,
+        send_photo,
+        send_audio,
+        send_document,
+        send_video,
+        send_animation,
+        send_voice,
+        send_video_note,
+        send_paid_media,
+        send_media_group,
+        send_location,
+        edit_message_live_location,
+        stop_message_live_location,
+        send_venue,
+        send_contact,
+        send_poll,
+        send_dice,
+        send_chat_action,
+        set_message_reaction,
+        kick_chat_member,
+        ban_chat_member,
+        unban_chat_member,
+        restrict_chat_member,
+        promote_chat_member,
+        set_chat_administrator_custom_title,
+        ban_chat_sender_chat,
+        unban_chat_sender_chat,
+        set_chat_permissions,
+        export_chat_invite_link,
+        create_chat_invite_link,
+        edit_chat_invite_link,
+        create_chat_subscription_invite_link,
+        edit_chat_subscription_invite_link,
+        revoke_chat_invite_link,
+        set_chat_photo,
+        delete_chat_photo,
+        set_chat_title,
+        set_chat_description,
+        pin_chat_message,
+        unpin_chat_message,
+        unpin_all_chat_messages,
+        leave_chat,
+        get_chat,
+        get_chat_administrators,
+        get_chat_members_count,
+        get_chat_member_count,
+        get_chat_member,
+        set_chat_sticker_set,
+        delete_chat_sticker_set,
+        create_forum_topic,
+        edit_forum_topic,
+        close_forum_topic,
+        reopen_forum_topic,
+        delete_forum_topic,
+        unpin_all_forum_topic_messages,
+        edit_general_forum_topic,
+        close_general_forum_topic,
+        reopen_general_forum_topic,
+        hide_general_forum_topic,
+        unhide_general_forum_topic,
+        unpin_all_general_forum_topic_messages,
+        get_user_chat_boosts,
+        edit_message_text,
+        edit_message_caption,
+        edit_message_media,
+        edit_message_reply_markup,
+        stop_poll,
+        delete_message,
+        delete_messages,
+        send_sticker,
+        send_gift_chat,
+        verify_chat,
+        remove_chat_verification,
+        send_invoice,
+        approve_chat_join_request,
+        decline_chat_join_request
+        => f, fty
+    }
+
+    requester_forward! {
+        get_me,
+        log_out,
+        close,
+        get_updates,
+        set_webhook,
+        delete_webhook,
+        get_webhook_info,
+        edit_message_live_location_inline,
+        stop_message_live_location_inline,
+        edit_message_checklist,
+        send_checklist,
+        get_user_profile_photos,
+        set_user_emoji_status,
+        get_file,
+        get_forum_topic_icon_stickers,
+        answer_callback_query,
+        set_my_commands,
+        get_business_connection,
+        get_my_commands,
+        set_my_name,
+        get_my_name,
+        set_my_description,
+        get_my_description,
+        set_my_short_description,
+        get_my_short_description,
+        set_chat_menu_button,
+        get_chat_menu_button,
+        set_my_default_administrator_rights,
+        get_my_default_administrator_rights,
+        delete_my_commands,
+        answer_inline_query,
+        answer_web_app_query,
+        save_prepared_inline_message,
+        edit_message_text_inline,
+        edit_message_caption_inline,
+        edit_message_media_inline,
+        edit_message_reply_markup_inline,
+        get_sticker_set,
+        get_custom_emoji_stickers,
+        upload_sticker_file,
+        create_new_sticker_set,
+        add_sticker_to_set,
+        set_sticker_position_in_set,
+        delete_sticker_from_set,
+        replace_sticker_in_set,
+        set_sticker_set_thumbnail,
+        set_custom_emoji_sticker_set_thumbnail,
+        set_sticker_set_title,
+        delete_sticker_set,
+        set_sticker_emoji_list,
+        set_sticker_keywords,
+        set_sticker_mask_position,
+        get_available_gifts,
+        send_gift,
+        gift_premium_subscription,
+        verify_user,
+        remove_user_verification,
+        read_business_message,
+        delete_business_messages,
+        set_business_account_name,
+        set_business_account_username,
+        set_business_account_bio,
+        set_business_account_profile_photo,
+        remove_business_account_profile_photo,
+        set_business_account_gift_settings,
+        get_business_account_star_balance,
+        transfer_business_account_stars,
+        get_business_account_gifts,
+        convert_gift_to_stars,
+        upgrade_gift,
+        transfer_gift,
+        post_story,
+        edit_story,
+        delete_story,
+        create_invoice_link,
+        answer_shipping_query,
+        answer_pre_checkout_query,
+        get_my_star_balance,
+        get_star_transactions,
+        refund_star_payment,
+        edit_user_star_subscription,
+        set_passport_data_errors,
+        send_game,
+        set_game_score,
+        set_game_score_inline,
+        get_game_high_scores
+        => fid, ftyid
+    }
+}
+
+#[must_use = "Requests are lazy and do nothing unless sent"]
+#[derive(Clone)]
+pub struct MigrateChatsRequest<R>
+where
+    R: HasPayload,
+{
+    inner: R,
+    chat_id: fn(&R::Payload) -> &Recipient,
+    migrations: Arc<RwLock<HashMap<ChatId, ChatId>>>,
+    on_migrate: Option<MigrateCallback>,
+    save: Option<SaveMigration>,
+}
+
+impl<R> MigrateChatsRequest<R>
+where
+    R: HasPayload,
+{
+    fn old_chat_id(&self) -> Option<ChatId> {
+        match (self.chat_id)(self.inner.payload_ref()) {
+            Recipient::Id(id) => Some(*id),
+            Recipient::ChannelUsername(_) => None,
+        }
+    }
+}
+
+impl<R> HasPayload for MigrateChatsRequest<R>
+where
+    R: HasPayload,
+{
+    type Payload = R::Payload;
+
+    fn payload_mut(&mut self) -> &mut Self::Payload {
+        self.inner.payload_mut()
+    }
+
+    fn payload_ref(&self) -> &Self::Payload {
+        self.inner.payload_ref()
+    }
+}
+
+impl<R> Request for MigrateChatsRequest<R>
+where
+    R: Request,
+    R::Err: AsResponseParameters,
+    Output<R>: std::marker::Send,
+{
+    type Err = R::Err;
+
+    type Send = Send<R::Send>;
+
+    type SendRef = Send<R::SendRef>;
+
+    fn send(self) -> Self::Send {
+        let old = self.old_chat_id();
+
+        Send {
+            inner: self.inner.send(),
+            old,
+            migrations: self.migrations,
+            on_migrate: self.on_migrate,
+            save: self.save,
+            saving: None,
+        }
+    }
+
+    fn send_ref(&self) -> Self::SendRef {
+        Send {
+            inner: self.inner.send_ref(),
+            old: self.old_chat_id(),
+            migrations: Arc::clone(&self.migrations),
+            on_migrate: self.on_migrate.clone(),
+            save: self.save.clone(),
+            saving: None,
+        }
+    }
+}
+
+impl<R> IntoFuture for MigrateChatsRequest<R>
+where
+    R: Request,
+    R::Err: AsResponseParameters,
+    Output<R>: std::marker::Send,
+{
+    type Output = Result<Output<Self>, <Self as Request>::Err>;
+    type IntoFuture = <Self as Request>::Send;
+
+    fn into_future(self) -> Self::IntoFuture {
+        self.send()
+    }
+}
+
+#[pin_project::pin_project]
+pub struct Send<F>
+where
+    F: Future,
+{
+    #[pin]
+    inner: F,
+    old: Option<ChatId>,
+    migrations: Arc<RwLock<HashMap<ChatId, ChatId>>>,
+    on_migrate: Option<MigrateCallback>,
+    save: Option<SaveMigration>,
+    /// Saving of a found migration and the output to return after it.
+    saving: Option<(BoxFuture<'static, ()>, F::Output)>,
+}
+
+impl<F, T, E> Future for Send<F>
+where
+    F: Future<Output = Result<T, E>>,
+    E: AsResponseParameters,
+{
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+
+        if let Some((save, _)) = this.saving {
+            ready!(save.as_mut().poll(cx));
+            let (_, ret) = this.saving.take().unwrap();
+            return Poll::Ready(ret);
+        }
+
+        let ret = ready!(this.inner.poll(cx));
+        if let (Err(err), Some(old)) = (&ret, *this.old) {
+            if let Some(new) = err.migrate_to_chat_id() {
+                log::debug!("Chat {old} was migrated to {new}");
+                this.migrations.write().unwrap().insert(old, new);
+
+                if let Some(callback) = this.on_migrate {
+                    callback(old, new);
+                }
+
+                if let Some(save) = this.save {
+                    let mut save = save(old, new);
+                    if save.as_mut().poll(cx).is_pending() {
+                        *this.saving = Some((save, ret));
+                        return Poll::Pending;
+                    }
+                }
+            }
+        }
+
+        Poll::Ready(ret)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{convert::Infallible, sync::Mutex};
+
+    use super::*;
+    use crate::{requests::RequesterExt, Bot, RequestError};
+
+    /// Returns the future of sending `request`, which fails with a migration
+    /// of its chat to `new`.
+    fn migrated<R>(
+        request: &MigrateChatsRequest<R>,
+        new: ChatId,
+    ) -> Send<impl Future<Output = Result<(), RequestError>>>
+    where
+        R: HasPayload,
+    {
+        Send {
+            inner: futures::future::ready(Err(RequestError::MigrateToChatId(new))),
+            old: request.old_chat_id(),
+            migrations: Arc::clone(&request.migrations),
+            on_migrate: request.on_migrate.clone(),
+            save: request.save.clone(),
+            saving: None,
+        }
+    }
+
+    #[derive(Default)]
+    struct VecStorage(Mutex<Vec<(ChatId, ChatId)>>);
+
+    impl MigrationStorage for VecStorage {
+        type Error = Infallible;
+
+        fn load(
+            self: Arc<Self>,
+        ) -> BoxFuture<'static, Result<HashMap<ChatId, ChatId>, Infallible>> {
+            Box::pin(async move { Ok(self.0.lock().unwrap().iter().copied().collect()) })
+        }
+
+        fn save(
+            self: Arc<Self>,
+            old: ChatId,
+            new: ChatId,
+        ) -> BoxFuture<'static, Result<(), Infallible>> {
+            Box::pin(async move {
+                tokio::task::yield_now().await;
+                self.0.lock().unwrap().push((old, new));
+                Ok(())
+            })
+        }
+    }
+
+    #[test]
+    fn replaces_migrated_chat_ids() {
+        let bot = Bot::new("TOKEN").migrate_chats().with_migrations([(ChatId(-1), ChatId(-1001))]);
+
+        let request = bot.send_message(ChatId(-1), "hi");
+        assert_eq!(request.payload_ref().chat_id, Recipient::Id(ChatId(-1001)));
+
+        let request = bot.send_message(ChatId(-2), "hi");
+        assert_eq!(request.payload_ref().chat_id, Recipient::Id(ChatId(-2)));
+    }
+
+    #[test]
+    fn replaces_migrated_from_chat_ids() {
+        let bot = Bot::new("TOKEN").migrate_chats().with_migrations([(ChatId(-1), ChatId(-1001))]);
+
+        let request = bot.forward_message(ChatId(-2), ChatId(-1), MessageId(1));
+        assert_eq!(request.payload_ref().chat_id, Recipient::Id(ChatId(-2)));
+        assert_eq!(request.payload_ref().from_chat_id, Recipient::Id(ChatId(-1001)));
+
+        let request = bot.copy_messages(ChatId(-1), ChatId(-1), [MessageId(1)]);
+        assert_eq!(request.payload_ref().chat_id, Recipient::Id(ChatId(-1001)));
+        assert_eq!(request.payload_ref().from_chat_id, Recipient::Id(ChatId(-1001)));
+    }
+
+    #[tokio::test]
+    async fn learns_migrations_from_errors() {
+        let found = Arc::new(Mutex::new(Vec::new()));
+        let bot = Bot::new("TOKEN").migrate_chats().on_migrate({
+            let found = Arc::clone(&found);
+            move |old, new| found.lock().unwrap().push((old, new))
+        });
+
+        migrated(&bot.send_message(ChatId(-1), "hi"), ChatId(-1001)).await.unwrap_err();
+
+        assert_eq!(bot.migrated(ChatId(-1)), Some(ChatId(-1001)));
+        assert_eq!(bot.migrations(), [(ChatId(-1), ChatId(-1001))]);
+        assert_eq!(*found.lock().unwrap(), [(ChatId(-1), ChatId(-1001))]);
+        assert_eq!(bot.send_message(ChatId(-1), "hi").payload_ref().chat_id, ChatId(-1001).into());
+    }
+
+    #[tokio::test]
+    async fn storage() {
+        let storage = Arc::new(VecStorage::default());
+        storage.0.lock().unwrap().push((ChatId(-1), ChatId(-1001)));
+
+        let bot =
+            Bot::new("TOKEN").migrate_chats().with_storage(Arc::clone(&storage)).await.unwrap();
+        assert_eq!(bot.migrated(ChatId(-1)), Some(ChatId(-1001)));
+
+        migrated(&bot.send_message(ChatId(-2), "hi"), ChatId(-1002)).await.unwrap_err();
+        assert_eq!(
+            *storage.0.lock().unwrap(),
+            [(ChatId(-1), ChatId(-1001)), (ChatId(-2), ChatId(-1002))]
+        );
+    }
+}
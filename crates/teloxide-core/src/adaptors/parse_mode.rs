@@ -1,14 +1,16 @@
-use std::future::IntoFuture;
+use std::{future::IntoFuture, sync::Arc};
 
 use url::Url;
 
 use crate::{
     payloads::{
-        AnswerInlineQuery, AnswerWebAppQuery, CopyMessage, EditMessageCaption,
+        AnswerInlineQuery, AnswerWebAppQuery, CopyMessage, CopyMessages, EditMessageCaption,
         EditMessageCaptionInline, EditMessageChecklist, EditMessageMedia, EditMessageMediaInline,
-        EditMessageText, EditMessageTextInline, EditStory, GiftPremiumSubscription, PostStory,
-        SavePreparedInlineMessage, SendAnimation, SendAudio, SendChecklist, SendDocument, SendGift,
-        SendGiftChat, SendMediaGroup, SendMessage, SendPaidMedia, SendPhoto, SendPoll, SendVideo,
+        EditMessageText, EditMessageTextInline, EditStory, ForwardMessage, ForwardMessages,
+        GiftPremiumSubscription, PinChatMessage, PostStory, SavePreparedInlineMessage,
+        SendAnimation, SendAudio, SendChecklist, SendContact, SendDice, SendDocument, SendGame,
+        SendGift, SendGiftChat, SendInvoice, SendLocation, SendMediaGroup, SendMessage,
+        SendPaidMedia, SendPhoto, SendPoll, SendSticker, SendVenue, SendVideo, SendVideoNote,
         SendVoice,
     },
     prelude::Requester,
@@ -18,10 +20,40 @@ use crate::{
 
 /// Default parse mode adaptor, see
 /// [`RequesterExt::parse_mode`](crate::requests::RequesterExt::parse_mode).
+///
+/// Besides the parse mode, the adaptor can set default
+/// [`disable_notification`], [`protect_content`] and
+/// [`link_preview_options`] of sent messages. Like the parse mode, defaults
+/// are only used when the corresponding option of a request isn't set.
+///
+/// ## Examples
+///
+/// ```
+/// use teloxide_core::{
+///     prelude::*,
+///     types::{LinkPreviewOptions, ParseMode},
+/// };
+///
+/// let bot = Bot::new("TOKEN")
+///     .parse_mode(ParseMode::Html)
+///     .disable_notification(true)
+///     .link_preview_options(LinkPreviewOptions {
+///         is_disabled: true,
+///         url: None,
+///         prefer_small_media: false,
+///         prefer_large_media: false,
+///         show_above_text: false,
+///     });
+/// ```
+///
+/// [`disable_notification`]: DefaultParseMode::disable_notification
+/// [`protect_content`]: DefaultParseMode::protect_content
+/// [`link_preview_options`]: DefaultParseMode::link_preview_options
 #[derive(Clone, Debug)]
 pub struct DefaultParseMode<B> {
     bot: B,
     mode: ParseMode,
+    defaults: Arc<Defaults>,
 }
 
 /// Request returned by [`DefaultParseMode`] methods.
@@ -29,6 +61,15 @@ pub struct DefaultParseMode<B> {
 pub struct DefaultParseModeRequest<R> {
     req: R,
     mode: ParseMode,
+    defaults: Arc<Defaults>,
+}
+
+/// Default options of sent messages, other than the parse mode.
+#[derive(Clone, Debug, Default)]
+struct Defaults {
+    disable_notification: Option<bool>,
+    protect_content: Option<bool>,
+    link_preview_options: Option<LinkPreviewOptions>,
 }
 
 impl<B> DefaultParseMode<B> {
@@ -38,7 +79,33 @@ impl<B> DefaultParseMode<B> {
     ///
     /// [`RequesterExt::parse_mode`]: crate::requests::RequesterExt::parse_mode
     pub fn new(bot: B, parse_mode: ParseMode) -> Self {
-        Self { bot, mode: parse_mode }
+        Self { bot, mode: parse_mode, defaults: Arc::default() }
+    }
+
+    /// Sets the default value of `disable_notification`, used when a request
+    /// doesn't set it.
+    #[must_use]
+    pub fn disable_notification(self, value: bool) -> Self {
+        self.with_defaults(|defaults| defaults.disable_notification = Some(value))
+    }
+
+    /// Sets the default value of `protect_content`, used when a request
+    /// doesn't set it.
+    #[must_use]
+    pub fn protect_content(self, value: bool) -> Self {
+        self.with_defaults(|defaults| defaults.protect_content = Some(value))
+    }
+
+    /// Sets the default [`LinkPreviewOptions`], used when a request doesn't
+    /// set them.
+    #[must_use]
+    pub fn link_preview_options(self, options: LinkPreviewOptions) -> Self {
+        self.with_defaults(|defaults| defaults.link_preview_options = Some(options))
+    }
+
+    fn with_defaults(mut self, f: impl FnOnce(&mut Defaults)) -> Self {
+        f(Arc::make_mut(&mut self.defaults));
+        self
     }
 
     /// Allows to access the inner bot.
@@ -60,7 +127,7 @@ impl<B> DefaultParseMode<B> {
 impl<R> Request for DefaultParseModeRequest<R>
 where
     R: Request + Clone,
-    R::Payload: VisitParseModes,
+    R::Payload: VisitParseModes + ApplyDefaults,
 {
     type Err = R::Err;
     type Send = R::Send;
@@ -68,7 +135,9 @@ where
 
     // Required methods
     fn send(mut self) -> Self::Send {
-        self.req.payload_mut().visit_parse_modes(|mode| _ = mode.get_or_insert(self.mode));
+        let payload = self.req.payload_mut();
+        payload.visit_parse_modes(|mode| _ = mode.get_or_insert(self.mode));
+        payload.apply_defaults(&self.defaults);
         self.req.send()
     }
 
@@ -109,7 +178,7 @@ macro_rules! f {
     ($m:ident $this:ident ($($arg:ident : $T:ty),*)) => {
         {
             let req = $this.inner().$m($($arg),*);
-            DefaultParseModeRequest { req, mode: $this.mode }
+            DefaultParseModeRequest { req, mode: $this.mode, defaults: Arc::clone(&$this.defaults) }
         }
     };
 }
@@ -162,6 +231,18 @@ where
     B::GiftPremiumSubscription: Clone,
     B::SendGift: Clone,
     B::SendGiftChat: Clone,
+    B::ForwardMessage: Clone,
+    B::ForwardMessages: Clone,
+    B::CopyMessages: Clone,
+    B::SendVideoNote: Clone,
+    B::SendLocation: Clone,
+    B::SendVenue: Clone,
+    B::SendContact: Clone,
+    B::SendDice: Clone,
+    B::SendSticker: Clone,
+    B::PinChatMessage: Clone,
+    B::SendInvoice: Clone,
+    B::SendGame: Clone,
 {
     type Err = B::Err;
 
@@ -363,6 +444,7 @@ macro_rules! impl_visit_parse_modes {
     ) => {
         $(
             impl VisitParseModes for $T {
+                #[allow(unused_variables, unused_mut)]
                 fn visit_parse_modes(&mut self, mut visitor: impl FnMut(&mut Option<ParseMode>)) {
                     $(
                         visitor(&mut self.$field);
@@ -395,6 +477,18 @@ impl_visit_parse_modes! {
     PostStory => [parse_mode],
     EditStory => [parse_mode],
     SendPoll => [explanation_parse_mode],
+    ForwardMessage => [],
+    ForwardMessages => [],
+    CopyMessages => [],
+    SendVideoNote => [],
+    SendLocation => [],
+    SendVenue => [],
+    SendContact => [],
+    SendDice => [],
+    SendSticker => [],
+    PinChatMessage => [],
+    SendInvoice => [],
+    SendGame => [],
 }
 
 impl VisitParseModes for AnswerInlineQuery {
@@ -517,3 +611,112 @@ fn visit_parse_modes_in_input_media(
 
     visitor(parse_mode);
 }
+
+/// Sets options of a payload to their defaults, if they aren't set.
+trait ApplyDefaults {
+    fn apply_defaults(&mut self, defaults: &Defaults);
+}
+
+macro_rules! impl_apply_defaults {
+    (
+        $(
+            $T:ty => [
+                $(
+                    $field:ident
+                ),*
+            ]
+            ,
+        )*
+    ) => {
+        $(
+            impl ApplyDefaults for $T {
+                #[allow(unused_variables)]
+                fn apply_defaults(&mut self, defaults: &Defaults) {
+                    $(
+                        if self.$field.is_none() {
+                            self.$field.clone_from(&defaults.$field);
+                        }
+                    )*
+                }
+            }
+        )*
+    }
+}
+
+impl_apply_defaults! {
+    SendMessage => [disable_notification, protect_content, link_preview_options],
+    EditMessageText => [link_preview_options],
+    EditMessageTextInline => [link_preview_options],
+    SendPhoto => [disable_notification, protect_content],
+    SendVideo => [disable_notification, protect_content],
+    SendAudio => [disable_notification, protect_content],
+    SendDocument => [disable_notification, protect_content],
+    SendAnimation => [disable_notification, protect_content],
+    SendVoice => [disable_notification, protect_content],
+    SendVideoNote => [disable_notification, protect_content],
+    SendPaidMedia => [disable_notification, protect_content],
+    SendMediaGroup => [disable_notification, protect_content],
+    SendPoll => [disable_notification, protect_content],
+    SendChecklist => [disable_notification, protect_content],
+    SendLocation => [disable_notification, protect_content],
+    SendVenue => [disable_notification, protect_content],
+    SendContact => [disable_notification, protect_content],
+    SendDice => [disable_notification, protect_content],
+    SendSticker => [disable_notification, protect_content],
+    SendInvoice => [disable_notification, protect_content],
+    SendGame => [disable_notification, protect_content],
+    ForwardMessage => [disable_notification, protect_content],
+    ForwardMessages => [disable_notification, protect_content],
+    CopyMessage => [disable_notification, protect_content],
+    CopyMessages => [disable_notification, protect_content],
+    PinChatMessage => [disable_notification],
+    PostStory => [protect_content],
+    EditMessageCaption => [],
+    EditMessageCaptionInline => [],
+    EditMessageChecklist => [],
+    EditMessageMedia => [],
+    EditMessageMediaInline => [],
+    EditStory => [],
+    AnswerInlineQuery => [],
+    AnswerWebAppQuery => [],
+    SavePreparedInlineMessage => [],
+    GiftPremiumSubscription => [],
+    SendGift => [],
+    SendGiftChat => [],
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_dont_override_options() {
+        let defaults = Defaults {
+            disable_notification: Some(true),
+            protect_content: Some(true),
+            link_preview_options: None,
+        };
+
+        let mut payload = SendMessage::new(ChatId(1), "text");
+        payload.protect_content = Some(false);
+        payload.apply_defaults(&defaults);
+
+        assert_eq!(payload.disable_notification, Some(true));
+        assert_eq!(payload.protect_content, Some(false));
+        assert_eq!(payload.link_preview_options, None);
+    }
+
+    #[test]
+    fn inline_text_edits_get_link_preview_options() {
+        let defaults = Defaults {
+            disable_notification: None,
+            protect_content: None,
+            link_preview_options: Some(LinkPreviewOptions::disabled()),
+        };
+
+        let mut payload = EditMessageTextInline::new("id", "text");
+        payload.apply_defaults(&defaults);
+
+        assert_eq!(payload.link_preview_options, Some(LinkPreviewOptions::disabled()));
+    }
+}
@@ -12,6 +12,10 @@ mod worker;
 use std::{
     future::Future,
     hash::{Hash, Hasher},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
 };
 
 use tokio::sync::{
@@ -75,6 +79,8 @@ pub struct Throttle<B> {
     bot: B,
     // `RequestLock` allows to unlock requests (allowing them to be sent).
     queue: mpsc::Sender<(ChatIdHash, RequestLock)>,
+    // The number of requests taken from `queue` by the worker, but not sent yet.
+    worker_queue_len: Arc<AtomicUsize>,
     info_tx: mpsc::Sender<InfoMessage>,
 }
 
@@ -104,8 +110,10 @@ impl<B> Throttle<B> {
         let (tx, rx) = mpsc::channel(settings.limits.messages_per_sec_overall as usize);
         let (info_tx, info_rx) = mpsc::channel(2);
 
-        let worker = worker(settings, rx, info_rx, bot.clone());
-        let this = Self { bot, queue: tx, info_tx };
+        let worker_queue_len = Arc::new(AtomicUsize::new(0));
+
+        let worker = worker(settings, rx, info_rx, Arc::clone(&worker_queue_len), bot.clone());
+        let this = Self { bot, queue: tx, worker_queue_len, info_tx };
 
         (this, worker)
     }
@@ -164,6 +172,9 @@ impl<B> Throttle<B> {
 
     /// Sets new limits.
     ///
+    /// This can be used to tune limits of a running bot. Requests that are
+    /// already pending are sent according to the new limits.
+    ///
     /// Note: changes may not be applied immediately.
     pub async fn set_limits(&self, new: Limits) {
         let (tx, rx) = oneshot::channel();
@@ -172,6 +183,16 @@ impl<B> Throttle<B> {
 
         rx.await.ok();
     }
+
+    /// Returns the number of requests waiting to be sent.
+    ///
+    /// The number is approximate, as requests are sent concurrently. It's
+    /// useful to monitor whether the limits are high enough for the load of
+    /// the bot, see also [`Settings::on_delay`].
+    pub fn queue_len(&self) -> usize {
+        let in_channel = self.queue.max_capacity() - self.queue.capacity();
+        self.worker_queue_len.load(Ordering::Relaxed) + in_channel
+    }
 }
 
 /// An ID used in the worker.
@@ -214,3 +235,39 @@ impl From<&Recipient> for ChatIdHash {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{requests::Request, test_utils::unreachable_bot};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn metrics_and_live_limits() {
+        let bot = unreachable_bot();
+
+        let delayed = Arc::new(AtomicUsize::new(0));
+        let settings = Settings::default().on_delay({
+            let delayed = Arc::clone(&delayed);
+            move |pending| {
+                delayed.fetch_max(pending, Ordering::Relaxed);
+                async {}
+            }
+        });
+        let throttle = Throttle::spawn_with_settings(bot, settings);
+
+        // The worker is idle, but still answers
+        let limits = Limits { messages_per_sec_chat: 2, ..Limits::default() };
+        tokio::time::timeout(Duration::from_secs(1), throttle.set_limits(limits)).await.unwrap();
+        assert_eq!(throttle.limits().await, limits);
+        assert_eq!(throttle.queue_len(), 0);
+
+        let send = || throttle.send_message(ChatId(1), "text").send();
+        let _ = futures::join!(send(), send(), send());
+
+        assert_eq!(throttle.queue_len(), 0);
+        assert_eq!(delayed.load(Ordering::Relaxed), 1);
+    }
+}
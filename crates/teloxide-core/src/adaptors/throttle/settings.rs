@@ -1,6 +1,7 @@
 use std::pin::Pin;
 
 use futures::{future::ready, Future};
+use serde::{de::Error, Deserialize, Deserializer, Serialize};
 
 // Required to not trigger `clippy::type-complexity` lint
 type BoxedFnMut<I, O> = Box<dyn FnMut(I) -> O + Send>;
@@ -26,6 +27,7 @@ type BoxedFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
 pub struct Settings {
     pub limits: Limits,
     pub on_queue_full: BoxedFnMut<usize, BoxedFuture>,
+    pub on_delay: Option<BoxedFnMut<usize, BoxedFuture>>,
     pub retry: bool,
     pub check_slow_mode: bool,
 }
@@ -37,23 +39,52 @@ pub struct Settings {
 /// Note that you may ask telegram [@BotSupport] to increase limits for your
 /// particular bot if it has a lot of users (but they may or may not do that).
 ///
+/// Limits can be loaded from a config file. Missing fields are set to their
+/// defaults, zero limits are rejected.
+///
+/// ## Examples
+///
+/// ```
+/// use teloxide_core::adaptors::throttle::Limits;
+///
+/// let limits: Limits = serde_json::from_str(r#"{ "messages_per_sec_overall": 100 }"#).unwrap();
+/// assert_eq!(limits, Limits { messages_per_sec_overall: 100, ..Limits::default() });
+///
+/// assert!(serde_json::from_str::<Limits>(r#"{ "messages_per_sec_chat": 0 }"#).is_err());
+/// ```
+///
 /// [@BotSupport]: https://t.me/botsupport
 /// [`Throttle`]: crate::adaptors::throttle::Throttle
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Limits {
     /// Allowed messages in one chat per second.
+    #[serde(deserialize_with = "positive")]
     pub messages_per_sec_chat: u32,
 
     /// Allowed messages in one chat per minute.
+    #[serde(deserialize_with = "positive")]
     pub messages_per_min_chat: u32,
 
     /// Allowed messages in one channel or supergroup per minute.
+    #[serde(deserialize_with = "positive")]
     pub messages_per_min_channel_or_supergroup: u32,
 
     /// Allowed messages per second.
+    #[serde(deserialize_with = "positive")]
     pub messages_per_sec_overall: u32,
 }
 
+fn positive<'de, D>(deserializer: D) -> Result<u32, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match u32::deserialize(deserializer)? {
+        0 => Err(D::Error::custom("limits must be greater than zero")),
+        n => Ok(n),
+    }
+}
+
 impl Settings {
     pub fn limits(mut self, val: Limits) -> Self {
         self.limits = val;
@@ -69,6 +100,20 @@ impl Settings {
         self
     }
 
+    /// Sets a function which is called with the number of pending requests
+    /// when some requests can't be sent right away because of the limits.
+    ///
+    /// The function is called at most a few times per second, for as long as
+    /// requests are being delayed. By default, nothing is called.
+    pub fn on_delay<F, Fut>(mut self, mut val: F) -> Self
+    where
+        F: FnMut(usize) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.on_delay = Some(Box::new(move |pending| Box::pin(val(pending))));
+        self
+    }
+
     pub fn no_retry(mut self) -> Self {
         self.retry = false;
         self
@@ -88,6 +133,7 @@ impl Default for Settings {
                 log::warn!("Throttle queue is full ({pending} pending requests)");
                 Box::pin(ready(()))
             }),
+            on_delay: None,
             retry: true,
             check_slow_mode: false,
         }
@@ -1,6 +1,10 @@
 use std::{
     collections::{hash_map::Entry, HashMap, VecDeque},
     pin::pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
     time::{Duration, Instant},
 };
 
@@ -93,9 +97,10 @@ pub(super) struct FreezeUntil {
 // the request that it can be now executed, increase counts, add record to the
 // history.
 pub(super) async fn worker<B>(
-    Settings { mut limits, mut on_queue_full, retry, check_slow_mode }: Settings,
+    Settings { mut limits, mut on_queue_full, mut on_delay, retry, check_slow_mode }: Settings,
     mut rx: mpsc::Receiver<(ChatIdHash, RequestLock)>,
     mut info_rx: mpsc::Receiver<InfoMessage>,
+    queue_len: Arc<AtomicUsize>,
     bot: B,
 ) where
     B: Requester,
@@ -121,32 +126,32 @@ pub(super) async fn worker<B>(
     let (freeze_tx, mut freeze_rx) = mpsc::channel::<FreezeUntil>(1);
 
     while !rx_is_closed || !queue.is_empty() {
-        // FIXME(waffle):
-        // 1. If the `queue` is empty, `read_from_rx` call down below will 'block'
-        //    execution until a request is sent. While the execution is 'blocked' no
-        //    `InfoMessage`s could be answered.
-        //
-        // 2. If limits are decreased, ideally we want to shrink queue.
-        //
-        // *blocked in asynchronous way
+        // FIXME(waffle): If limits are decreased, ideally we want to shrink queue.
         answer_info(&mut info_rx, &mut limits);
 
+        // If the `queue` is empty, `read_from_rx` 'blocks'* until a request is sent,
+        // so `InfoMessage`s are also answered while waiting for it.
+        //
+        // *blocked in asynchronous way
         loop {
             let res = future::select(
-                pin!(freeze_rx.recv()),
+                pin!(future::select(pin!(freeze_rx.recv()), pin!(recv_info(&mut info_rx)))
+                    .map(either)),
                 pin!(read_from_rx(&mut rx, &mut queue, &mut rx_is_closed)),
             )
             .map(either)
             .await
-            .map_either(|l| l.0, |r| r.0);
+            .map_either(|l| l.0.map_either(|l| l.0, |r| r.0), |r| r.0);
 
             match res {
-                Either::Left(freeze_until) => {
+                Either::Left(Either::Left(freeze_until)) => {
                     freeze(&mut freeze_rx, slow_mode.as_mut(), &bot, freeze_until).await;
                 }
+                Either::Left(Either::Right(info)) => answer_info_message(info, &mut limits),
                 Either::Right(()) => break,
             }
         }
+        queue_len.store(queue.len(), Ordering::Relaxed);
         //debug_assert_eq!(queue.capacity(), limits.messages_per_sec_overall as usize);
 
         if queue.len() == queue.capacity() && last_queue_full.elapsed() > QUEUE_FULL_DELAY {
@@ -217,6 +222,10 @@ pub(super) async fn worker<B>(
         let mut allowed = limits.messages_per_sec_overall.saturating_sub(used);
 
         if allowed == 0 {
+            if let Some(on_delay) = &mut on_delay {
+                tokio::spawn(on_delay(queue.len()));
+            }
+
             requests_sent.per_sec.clear();
             tokio::time::sleep(DELAY).await;
             continue;
@@ -275,6 +284,12 @@ pub(super) async fn worker<B>(
                 }
             }
         }
+        drop(queue_removing);
+
+        queue_len.store(queue.len(), Ordering::Relaxed);
+        if let (Some(on_delay), false) = (&mut on_delay, queue.is_empty()) {
+            tokio::spawn(on_delay(queue.len()));
+        }
 
         // It's easier to just recompute last second stats, instead of keeping
         // track of it alongside with minute stats, so we just throw this away.
@@ -285,15 +300,27 @@ pub(super) async fn worker<B>(
 
 fn answer_info(rx: &mut mpsc::Receiver<InfoMessage>, limits: &mut Limits) {
     while let Ok(req) = rx.try_recv() {
-        // Errors are ignored with .ok(). Error means that the response channel
-        // is closed and the response isn't needed.
-        match req {
-            InfoMessage::GetLimits { response } => response.send(*limits).ok(),
-            InfoMessage::SetLimits { new, response } => {
-                *limits = new;
-                response.send(()).ok()
-            }
-        };
+        answer_info_message(req, limits);
+    }
+}
+
+fn answer_info_message(req: InfoMessage, limits: &mut Limits) {
+    // Errors are ignored with .ok(). Error means that the response channel
+    // is closed and the response isn't needed.
+    match req {
+        InfoMessage::GetLimits { response } => response.send(*limits).ok(),
+        InfoMessage::SetLimits { new, response } => {
+            *limits = new;
+            response.send(()).ok()
+        }
+    };
+}
+
+/// Waits for the next `InfoMessage`, or forever if all `Throttle`s are dropped.
+async fn recv_info(rx: &mut mpsc::Receiver<InfoMessage>) -> InfoMessage {
+    match rx.recv().await {
+        Some(req) => req,
+        None => future::pending().await,
     }
 }
 
@@ -0,0 +1,475 @@
+use std::{
+    future::{Future, IntoFuture},
+    pin::Pin,
+    task::{self, Poll},
+    time::Duration,
+};
+
+use thiserror::Error;
+use url::Url;
+
+use crate::{
+    errors::RequestError,
+    requests::{HasPayload, Output, Payload, Request, Requester},
+    types::*,
+};
+
+/// Names of methods which upload files.
+const UPLOADS: &[&str] = &[
+    "SetWebhook",
+    "SendPhoto",
+    "SendAudio",
+    "SendDocument",
+    "SendVideo",
+    "SendAnimation",
+    "SendVoice",
+    "SendVideoNote",
+    "SendPaidMedia",
+    "SendMediaGroup",
+    "SetChatPhoto",
+    "EditMessageMedia",
+    "EditMessageMediaInline",
+    "SendSticker",
+    "UploadStickerFile",
+    "CreateNewStickerSet",
+    "AddStickerToSet",
+    "ReplaceStickerInSet",
+    "SetStickerSetThumbnail",
+];
+
+/// Request timeout adaptor.
+///
+/// Fails requests which don't complete in time with [`TimedOut`], independently
+/// of the timeout of the HTTP client. This makes it possible to use a short
+/// timeout for ordinary requests while keeping the client timeout long.
+///
+/// Some requests get longer deadlines:
+/// - Requests which upload files use [`Timeout::upload_timeout`].
+/// - Requests which take a long time by design, e.g. [`GetUpdates`] with a long
+///   polling timeout, get that time in addition to the timeout.
+///
+/// Note that the HTTP client still applies its own timeout, which should be
+/// longer than the timeouts of this adaptor (see [`client_from_env`]).
+///
+/// [`GetUpdates`]: crate::payloads::GetUpdates
+/// [`client_from_env`]: crate::net::client_from_env
+///
+/// ## Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use teloxide_core::{requests::RequesterExt, Bot};
+///
+/// let bot =
+///     Bot::new("TOKEN").timeout(Duration::from_secs(5)).upload_timeout(Duration::from_secs(60));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Timeout<B> {
+    bot: B,
+    timeout: Duration,
+    upload_timeout: Duration,
+}
+
+/// An error returned when a request sent through [`Timeout`] doesn't complete
+/// in time.
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq, Hash)]
+#[error("the request timed out after {after:?}")]
+pub struct TimedOut {
+    /// The deadline of the request.
+    pub after: Duration,
+}
+
+impl From<TimedOut> for RequestError {
+    fn from(TimedOut { after }: TimedOut) -> Self {
+        RequestError::Timeout(after)
+    }
+}
+
+impl<B> Timeout<B> {
+    /// Creates new [`Timeout`], failing requests which take longer than
+    /// `timeout`.
+    ///
+    /// Requests which upload files use the same timeout, unless
+    /// [`upload_timeout`] is set.
+    ///
+    /// Note: it's recommended to use [`RequesterExt::timeout`] instead.
+    ///
+    /// [`upload_timeout`]: Timeout::upload_timeout
+    /// [`RequesterExt::timeout`]: crate::requests::RequesterExt::timeout
+    pub fn new(bot: B, timeout: Duration) -> Self {
+        Self { bot, timeout, upload_timeout: timeout }
+    }
+
+    /// Sets the timeout of requests which upload files.
+    #[must_use]
+    pub fn upload_timeout(self, timeout: Duration) -> Self {
+        Self { upload_timeout: timeout, ..self }
+    }
+
+    /// Allows to access the inner bot.
+    pub fn inner(&self) -> &B {
+        &self.bot
+    }
+
+    /// Unwraps the inner bot.
+    pub fn into_inner(self) -> B {
+        self.bot
+    }
+
+    fn wrap<R>(&self, inner: R) -> TimeoutRequest<R> {
+        TimeoutRequest { inner, timeout: self.timeout, upload_timeout: self.upload_timeout }
+    }
+}
+
+macro_rules! fty {
+    ($T:ident) => {
+        TimeoutRequest<B::$T>
+    };
+}
+
+macro_rules! fwd_inner {
+    ($m:ident $this:ident ($($arg:ident : $T:ty),*)) => {
+        $this.wrap($this.inner().$m($($arg),*))
+    };
+}
+
+impl<B> Requester for Timeout<B>
+where
+    B: Requester,
+    B::Err: From<TimedOut>,
+{
+    type Err = B::Err;
+
+    requester_forward! {
+        get_me,
+        log_out,
+        close,
+        get_updates,
+        set_webhook,
+        delete_webhook,
+        get_webhook_info,
+        forward_message,
+        forward_messages,
+        copy_message,
+        copy_messages,
+        send_message,
+        send_photo,
+        send_audio,
+        send_document,
+        send_video,
+        send_animation,
+        send_voice,
+        send_video_note,
+        send_paid_media,
+        send_media_group,
+        send_location,
+        edit_message_live_location,
+        edit_message_live_location_inline,
+        stop_message_live_location,
+        stop_message_live_location_inline,
+        edit_message_checklist,
+        send_venue,
+        send_contact,
+        send_poll,
+        send_checklist,
+        send_dice,
+        send_chat_action,
+        set_message_reaction,
+        get_user_profile_photos,
+        set_user_emoji_status,
+        get_file,
+        kick_chat_member,
+        ban_chat_member,
+        unban_chat_member,
+        restrict_chat_member,
+        promote_chat_member,
+        set_chat_administrator_custom_title,
+        ban_chat_sender_chat,
+        unban_chat_sender_chat,
+        set_chat_permissions,
+        export_chat_invite_link,
+        create_chat_invite_link,
+        edit_chat_invite_link,
+        create_chat_subscription_invite_link,
+        edit_chat_subscription_invite_link,
+        revoke_chat_invite_link,
+        set_chat_photo,
+        delete_chat_photo,
+        set_chat_title,
+        set_chat_description,
+        pin_chat_message,
+        unpin_chat_message,
+        unpin_all_chat_messages,
+        leave_chat,
+        get_chat,
+        get_chat_administrators,
+        get_chat_members_count,
+        get_chat_member_count,
+        get_chat_member,
+        set_chat_sticker_set,
+        delete_chat_sticker_set,
+        get_forum_topic_icon_stickers,
+        create_forum_topic,
+        edit_forum_topic,
+        close_forum_topic,
+        reopen_forum_topic,
+        delete_forum_topic,
+        unpin_all_forum_topic_messages,
+        edit_general_forum_topic,
+        close_general_forum_topic,
+        reopen_general_forum_topic,
+        hide_general_forum_topic,
+        unhide_general_forum_topic,
+        unpin_all_general_forum_topic_messages,
+        answer_callback_query,
+        get_user_chat_boosts,
+        set_my_commands,
+        get_business_connection,
+        get_my_commands,
+        set_my_name,
+        get_my_name,
+        set_my_description,
+        get_my_description,
+        set_my_short_description,
+        get_my_short_description,
+        set_chat_menu_button,
+        get_chat_menu_button,
+        set_my_default_administrator_rights,
+        get_my_default_administrator_rights,
+        delete_my_commands,
+        answer_inline_query,
+        answer_web_app_query,
+        save_prepared_inline_message,
+        edit_message_text,
+        edit_message_text_inline,
+        edit_message_caption,
+        edit_message_caption_inline,
+        edit_message_media,
+        edit_message_media_inline,
+        edit_message_reply_markup,
+        edit_message_reply_markup_inline,
+        stop_poll,
+        delete_message,
+        delete_messages,
+        send_sticker,
+        get_sticker_set,
+        get_custom_emoji_stickers,
+        upload_sticker_file,
+        create_new_sticker_set,
+        add_sticker_to_set,
+        set_sticker_position_in_set,
+        delete_sticker_from_set,
+        replace_sticker_in_set,
+        set_sticker_set_thumbnail,
+        set_custom_emoji_sticker_set_thumbnail,
+        set_sticker_set_title,
+        delete_sticker_set,
+        set_sticker_emoji_list,
+        set_sticker_keywords,
+        set_sticker_mask_position,
+        get_available_gifts,
+        send_gift,
+        send_gift_chat,
+        gift_premium_subscription,
+        verify_user,
+        verify_chat,
+        remove_user_verification,
+        remove_chat_verification,
+        read_business_message,
+        delete_business_messages,
+        set_business_account_name,
+        set_business_account_username,
+        set_business_account_bio,
+        set_business_account_profile_photo,
+        remove_business_account_profile_photo,
+        set_business_account_gift_settings,
+        get_business_account_star_balance,
+        transfer_business_account_stars,
+        get_business_account_gifts,
+        convert_gift_to_stars,
+        upgrade_gift,
+        transfer_gift,
+        post_story,
+        edit_story,
+        delete_story,
+        send_invoice,
+        create_invoice_link,
+        answer_shipping_query,
+        answer_pre_checkout_query,
+        get_my_star_balance,
+        get_star_transactions,
+        refund_star_payment,
+        edit_user_star_subscription,
+        set_passport_data_errors,
+        send_game,
+        set_game_score,
+        set_game_score_inline,
+        get_game_high_scores,
+        approve_chat_join_request,
+        decline_chat_join_request
+        => fwd_inner, fty
+    }
+}
+
+download_forward! {
+    B
+    Timeout<B>
+    { this => this.inner() }
+}
+
+/// Request returned by [`Timeout`] methods.
+#[derive(Clone)]
+#[must_use = "Requests are lazy and do nothing unless sent"]
+pub struct TimeoutRequest<R> {
+    inner: R,
+    timeout: Duration,
+    upload_timeout: Duration,
+}
+
+impl<R> TimeoutRequest<R>
+where
+    R: HasPayload,
+{
+    fn deadline(&self) -> Duration {
+        let payload = self.inner.payload_ref();
+        let base =
+            if UPLOADS.contains(&R::Payload::NAME) { self.upload_timeout } else { self.timeout };
+
+        base + payload.timeout_hint().unwrap_or_default()
+    }
+}
+
+impl<R> HasPayload for TimeoutRequest<R>
+where
+    R: HasPayload,
+{
+    type Payload = R::Payload;
+
+    fn payload_mut(&mut self) -> &mut Self::Payload {
+        self.inner.payload_mut()
+    }
+
+    fn payload_ref(&self) -> &Self::Payload {
+        self.inner.payload_ref()
+    }
+}
+
+impl<R> Request for TimeoutRequest<R>
+where
+    R: Request,
+    R::Err: From<TimedOut>,
+{
+    type Err = R::Err;
+
+    type Send = Send<R::Send>;
+
+    type SendRef = Send<R::SendRef>;
+
+    fn send(self) -> Self::Send {
+        let after = self.deadline();
+        Send { after, inner: tokio::time::timeout(after, self.inner.send()) }
+    }
+
+    fn send_ref(&self) -> Self::SendRef {
+        let after = self.deadline();
+        Send { after, inner: tokio::time::timeout(after, self.inner.send_ref()) }
+    }
+}
+
+impl<R> IntoFuture for TimeoutRequest<R>
+where
+    R: Request,
+    R::Err: From<TimedOut>,
+{
+    type Output = Result<Output<Self>, <Self as Request>::Err>;
+    type IntoFuture = <Self as Request>::Send;
+
+    fn into_future(self) -> Self::IntoFuture {
+        self.send()
+    }
+}
+
+/// Future returned by [`TimeoutRequest`].
+#[pin_project::pin_project]
+pub struct Send<F> {
+    after: Duration,
+    #[pin]
+    inner: tokio::time::Timeout<F>,
+}
+
+impl<F, T, E> Future for Send<F>
+where
+    F: Future<Output = Result<T, E>>,
+    E: From<TimedOut>,
+{
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let after = *this.after;
+
+        this.inner.poll(cx).map(|res| res.unwrap_or_else(|_| Err(TimedOut { after }.into())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    use crate::{
+        codegen::project_root,
+        payloads::GetUpdatesSetters,
+        test_utils::{silent_bot, unreachable_bot},
+    };
+
+    #[test]
+    fn deadlines() {
+        let bot = unreachable_bot();
+        let bot = Timeout::new(bot, Duration::from_secs(5)).upload_timeout(Duration::from_secs(60));
+
+        assert_eq!(bot.send_message(ChatId(1), "text").deadline(), Duration::from_secs(5));
+        assert_eq!(
+            bot.send_photo(ChatId(1), InputFile::memory("data")).deadline(),
+            Duration::from_secs(60)
+        );
+        assert_eq!(bot.get_updates().timeout(30).deadline(), Duration::from_secs(35));
+    }
+
+    #[test]
+    fn uploads_are_multipart_payloads() {
+        let root = project_root();
+
+        // Generated payloads which contain files are marked with `@[multipart]`
+        let mut multipart: Vec<String> = fs::read_dir(root.join("src/payloads"))
+            .unwrap()
+            .map(|entry| fs::read_to_string(entry.unwrap().path()).unwrap())
+            .filter(|file| file.lines().any(|line| line.starts_with("    @[multipart")))
+            .map(|file| {
+                let line = file.lines().find_map(|line| line.strip_prefix("    pub ")).unwrap();
+                line.split_whitespace().next().unwrap().to_owned()
+            })
+            .collect();
+
+        // Other payloads implement `MultipartPayload` by hand
+        let file = fs::read_to_string(root.join("src/requests/multipart_payload.rs")).unwrap();
+        multipart.extend(file.lines().filter_map(|line| {
+            let name = line.strip_prefix("impl MultipartPayload for payloads::")?;
+            Some(name.trim_end_matches(" {").to_owned())
+        }));
+
+        let mut uploads = UPLOADS.to_vec();
+        uploads.sort_unstable();
+        multipart.sort_unstable();
+        assert_eq!(uploads, multipart);
+    }
+
+    #[tokio::test]
+    async fn times_out() {
+        let bot = Timeout::new(silent_bot().await, Duration::from_millis(100));
+        let err = bot.get_me().await.unwrap_err();
+
+        assert!(matches!(err, RequestError::Timeout(after) if after == Duration::from_millis(100)));
+    }
+}
@@ -1,6 +1,6 @@
 use std::{future::Future, sync::Arc};
 
-use reqwest::Client;
+use reqwest::{header::HeaderMap, Client};
 use serde::{de::DeserializeOwned, Serialize};
 
 use crate::{
@@ -55,9 +55,11 @@ const TELOXIDE_API_URL: &str = "TELOXIDE_API_URL";
 #[must_use]
 #[derive(Debug, Clone)]
 pub struct Bot {
-    token: Arc<str>,
+    token: net::SecretString,
     api_url: Arc<reqwest::Url>,
     client: Client,
+    #[cfg(feature = "request_signing")]
+    signer: Option<net::RequestSigner>,
 }
 
 /// Constructors
@@ -91,13 +93,19 @@ impl Bot {
     where
         S: Into<String>,
     {
-        let token = Into::<String>::into(token).into();
+        let token = net::SecretString::new(token);
         let api_url = Arc::new(
             reqwest::Url::parse(net::TELEGRAM_API_URL)
                 .expect("Failed to parse the default TBA URL"),
         );
 
-        Self { token, api_url, client }
+        Self {
+            token,
+            api_url,
+            client,
+            #[cfg(feature = "request_signing")]
+            signer: None,
+        }
     }
 
     /// Creates a new `Bot` with the `TELOXIDE_TOKEN` & `TELOXIDE_API_URL` &
@@ -199,6 +207,14 @@ impl Bot {
         self.api_url = Arc::new(url);
         self
     }
+
+    /// Signs all requests with `signer`, see [`RequestSigner`] for more.
+    ///
+    /// [`RequestSigner`]: crate::net::RequestSigner
+    #[cfg(feature = "request_signing")]
+    pub fn sign_requests(self, signer: net::RequestSigner) -> Self {
+        Self { signer: Some(signer), ..self }
+    }
 }
 
 /// Getters
@@ -206,7 +222,27 @@ impl Bot {
     /// Returns currently used token.
     #[must_use]
     pub fn token(&self) -> &str {
-        &self.token
+        self.token.expose()
+    }
+
+    /// Returns a short hash of the token, which identifies the bot in logs
+    /// without exposing the token, see [`SecretString::fingerprint`].
+    ///
+    /// The token itself is never shown by the `Debug` implementation of `Bot`.
+    ///
+    /// ```
+    /// use teloxide_core::Bot;
+    ///
+    /// let bot = Bot::new("1234567890:ABCdefGHIjklMNOpqrSTUvwxYZ0123456789");
+    /// log::info!("Starting bot {}", bot.token_fingerprint());
+    ///
+    /// assert!(!format!("{bot:?}").contains("ABCdef"));
+    /// ```
+    ///
+    /// [`SecretString::fingerprint`]: crate::net::SecretString::fingerprint
+    #[must_use]
+    pub fn token_fingerprint(&self) -> String {
+        self.token.fingerprint()
     }
 
     /// Returns currently used http-client.
@@ -228,27 +264,35 @@ impl Bot {
         payload: &P,
     ) -> impl Future<Output = ResponseResult<P::Output>> + 'static
     where
-        P: Payload + Serialize,
+        P: Payload + Serialize + 'static,
         P::Output: DeserializeOwned + 'static,
     {
         let client = self.client.clone();
-        let token = Arc::clone(&self.token);
+        let token = self.token.clone();
         let api_url = Arc::clone(&self.api_url);
 
+        #[cfg(feature = "strict_validation")]
+        let validation = crate::validation::validate(payload);
+
         let timeout_hint = payload.timeout_hint();
         let params = stacker::maybe_grow(256 * 1024, 1024 * 1024, || serde_json::to_vec(payload))
             // this `expect` should be ok since we don't write request those may trigger error here
             .expect("serialization of request to be infallible");
+        let headers = self.signature_headers(P::NAME, &params);
 
         // async move to capture client&token&api_url&params
         async move {
+            #[cfg(feature = "strict_validation")]
+            validation?;
+
             net::request_json(
                 &client,
-                token.as_ref(),
+                token.expose(),
                 reqwest::Url::clone(&*api_url),
                 P::NAME,
                 params,
                 timeout_hint,
+                headers,
             )
             .await
         }
@@ -259,26 +303,34 @@ impl Bot {
         payload: &mut P,
     ) -> impl Future<Output = ResponseResult<P::Output>>
     where
-        P: MultipartPayload + Serialize,
+        P: MultipartPayload + Serialize + 'static,
         P::Output: DeserializeOwned + 'static,
     {
         let client = self.client.clone();
-        let token = Arc::clone(&self.token);
+        let token = self.token.clone();
         let api_url = Arc::clone(&self.api_url);
 
+        #[cfg(feature = "strict_validation")]
+        let validation = crate::validation::validate(payload);
+
         let timeout_hint = payload.timeout_hint();
+        let headers = self.multipart_signature_headers(P::NAME, payload);
         let params = serde_multipart::to_form(payload);
 
         // async move to capture client&token&api_url&params
         async move {
+            #[cfg(feature = "strict_validation")]
+            validation?;
+
             let params = params?.await;
             net::request_multipart(
                 &client,
-                token.as_ref(),
+                token.expose(),
                 reqwest::Url::clone(&*api_url),
                 P::NAME,
                 params,
                 timeout_hint,
+                headers,
             )
             .await
         }
@@ -289,32 +341,71 @@ impl Bot {
         payload: &P,
     ) -> impl Future<Output = ResponseResult<P::Output>>
     where
-        P: MultipartPayload + Serialize,
+        P: MultipartPayload + Serialize + 'static,
         P::Output: DeserializeOwned + 'static,
     {
         let client = self.client.clone();
-        let token = Arc::clone(&self.token);
+        let token = self.token.clone();
         let api_url = self.api_url.clone();
 
+        #[cfg(feature = "strict_validation")]
+        let validation = crate::validation::validate(payload);
+
         let timeout_hint = payload.timeout_hint();
+        let headers = self.multipart_signature_headers(P::NAME, payload);
         let params = serde_multipart::to_form_ref(payload);
 
         // async move to capture client&token&api_url&params
         async move {
+            #[cfg(feature = "strict_validation")]
+            validation?;
+
             let params = params?.await;
             net::request_multipart(
                 &client,
-                token.as_ref(),
+                token.expose(),
                 reqwest::Url::clone(&*api_url),
                 P::NAME,
                 params,
                 timeout_hint,
+                headers,
             )
             .await
         }
     }
 }
 
+impl Bot {
+    #[cfg_attr(not(feature = "request_signing"), allow(unused_variables))]
+    fn signature_headers(&self, method_name: &str, body: &[u8]) -> HeaderMap {
+        #[cfg(feature = "request_signing")]
+        if let Some(signer) = &self.signer {
+            // See `net::request_json` for why the suffix is removed
+            return signer.headers(method_name.trim_end_matches("Inline"), body);
+        }
+
+        HeaderMap::new()
+    }
+
+    /// Like [`Bot::signature_headers`], but signs the form fields of a
+    /// multipart request, see [`RequestSigner`].
+    ///
+    /// [`RequestSigner`]: net::RequestSigner
+    #[cfg_attr(not(feature = "request_signing"), allow(unused_variables))]
+    fn multipart_signature_headers<P>(&self, method_name: &str, payload: &P) -> HeaderMap
+    where
+        P: Serialize,
+    {
+        #[cfg(feature = "request_signing")]
+        if let Some(signer) = &self.signer {
+            let body = net::signing::form_fields(payload);
+            return signer.headers(method_name.trim_end_matches("Inline"), &body);
+        }
+
+        HeaderMap::new()
+    }
+}
+
 fn get_env(env: &'static str) -> String {
     std::env::var(env).unwrap_or_else(|_| panic!("Cannot get the {env} env variable"))
 }
@@ -23,7 +23,7 @@ impl Download for Bot {
         net::download_file(
             &self.client,
             reqwest::Url::clone(&*self.api_url),
-            &self.token,
+            self.token.expose(),
             path,
             destination,
         )
@@ -38,7 +38,7 @@ impl Download for Bot {
         net::download_file_stream(
             &self.client,
             reqwest::Url::clone(&*self.api_url),
-            &self.token,
+            self.token.expose(),
             path,
         )
         .map(|res| res.map_err(crate::errors::hide_token))
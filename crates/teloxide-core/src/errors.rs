@@ -1,6 +1,6 @@
 //! Possible error types.
 
-use std::{io, sync::Arc};
+use std::{io, sync::Arc, time::Duration};
 
 use thiserror::Error;
 
@@ -45,6 +45,91 @@ pub enum RequestError {
     /// Occurs when trying to send a file to Telegram.
     #[error("An I/O error: {0}")]
     Io(#[from] Arc<io::Error>),
+
+    /// The request violates Telegram limits, so it wasn't sent.
+    ///
+    /// Only returned with the `strict_validation` feature.
+    #[error("An invalid request: {0}")]
+    Validation(#[from] ValidationError),
+
+    /// The request didn't complete in time.
+    ///
+    /// Only returned by the [`Timeout`] bot adaptor.
+    ///
+    /// [`Timeout`]: crate::adaptors::Timeout
+    #[error("The request timed out after {0:?}")]
+    Timeout(Duration),
+}
+
+impl RequestError {
+    /// Returns `true` if sending the same request again may succeed.
+    ///
+    /// These are flood control errors (see [`RequestError::retry_after`]),
+    /// network errors, timeouts and API errors caused by problems on the
+    /// Telegram side (see [`ApiError::is_retryable`]).
+    ///
+    /// Note that a request that timed out or failed because of a network error
+    /// may have been executed, so retrying e.g. a `sendMessage` request may
+    /// send a message twice.
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::RetryAfter(_) | Self::Network(_) | Self::Timeout(_) => true,
+            Self::Api(err) => err.is_retryable(),
+            Self::MigrateToChatId(_)
+            | Self::InvalidJson { .. }
+            | Self::Io(_)
+            | Self::Validation(_) => false,
+        }
+    }
+
+    /// Returns the time to wait before the request can be repeated, if this is
+    /// a flood control error.
+    #[must_use]
+    pub fn retry_after(&self) -> Option<Seconds> {
+        match *self {
+            Self::RetryAfter(seconds) => Some(seconds),
+            _ => None,
+        }
+    }
+
+    /// Returns the Telegram API error, if this is one.
+    #[must_use]
+    pub fn api_error(&self) -> Option<&ApiError> {
+        match self {
+            Self::Api(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// An error caused by a request that violates Telegram limits, detected
+/// before sending it.
+///
+/// See the `strict_validation` feature.
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ValidationError {
+    /// A caption is longer than 1024 characters.
+    #[error("a caption must be at most 1024 characters long, got {0}")]
+    CaptionTooLong(usize),
+
+    /// A message text is longer than 4096 characters.
+    #[error("a message text must be at most 4096 characters long, got {0}")]
+    TextTooLong(usize),
+
+    /// An invoice title is empty or longer than 32 characters.
+    #[error("an invoice title must be 1 to 32 characters long, got {0}")]
+    InvalidTitle(usize),
+
+    /// A callback data of an inline keyboard button is empty or longer than
+    /// 64 bytes.
+    #[error("callback data must be 1 to 64 bytes long, got {0}")]
+    InvalidCallbackData(usize),
+
+    /// A row of an inline keyboard contains more than 8 buttons.
+    #[error("a row of an inline keyboard can contain at most 8 buttons, got {0}")]
+    TooManyButtonsInRow(usize),
 }
 
 /// An error caused by downloading a file.
@@ -775,51 +860,135 @@ pub(crate) fn hide_token(mut error: reqwest::Error) -> reqwest::Error {
         None => return error,
     };
 
-    if let Some(mut segments) = url.path_segments() {
-        // Usually the url looks like "bot<token>/..." or "file/bot<token>/...".
-        let (beginning, segment) = match segments.next() {
-            Some("file") => ("file/", segments.next()),
-            segment => ("", segment),
+    if redact_token(url) {
+        return error;
+    }
+
+    // couldn't find token in the url, hide the whole url
+    error.without_url()
+}
+
+/// Replaces path segments of `url` that look like `bot<token>` with
+/// `token:redacted`, returning `true` if any were found.
+///
+/// Usually the url looks like "bot<token>/..." or "file/bot<token>/...", but
+/// a custom API URL may have a path of its own before that.
+fn redact_token(url: &mut reqwest::Url) -> bool {
+    // The part before the : in the token is the id of the bot.
+    let id_character = |c: char| c.is_ascii_digit();
+
+    // The part after the : in the token is the secret.
+    //
+    // In all bot tokens we could find the secret is 35 characters long and is
+    // 0-9a-zA-Z_- only.
+    //
+    // It would be nice to research if TBA always has 35 character secrets or if it
+    // is just a coincidence.
+    const SECRET_LENGTH: usize = 35;
+    let secret_character = |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_';
+
+    // make sure that what we are about to delete looks like a bot token
+    let is_token = |segment: &str| {
+        let Some((id, secret)) = segment.strip_prefix("bot").and_then(|t| t.split_once(':')) else {
+            return false;
         };
 
-        if let Some(token) = segment.and_then(|s| s.strip_prefix("bot")) {
-            // make sure that what we are about to delete looks like a bot token
-            if let Some((id, secret)) = token.split_once(':') {
-                // The part before the : in the token is the id of the bot.
-                let id_character = |c: char| c.is_ascii_digit();
-
-                // The part after the : in the token is the secret.
-                //
-                // In all bot tokens we could find the secret is 35 characters long and is
-                // 0-9a-zA-Z_- only.
-                //
-                // It would be nice to research if TBA always has 35 character secrets or if it
-                // is just a coincidence.
-                const SECRET_LENGTH: usize = 35;
-                let secret_character = |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_';
-
-                if secret.len() >= SECRET_LENGTH
-                    && id.chars().all(id_character)
-                    && secret.chars().all(secret_character)
-                {
-                    // found token, hide only the token
-                    let without_token =
-                        &url.path()[(beginning.len() + "/bot".len() + token.len())..];
-                    let redacted = format!("{beginning}token:redacted{without_token}");
+        !id.is_empty()
+            && secret.len() >= SECRET_LENGTH
+            && id.chars().all(id_character)
+            && secret.chars().all(secret_character)
+    };
 
-                    url.set_path(&redacted);
-                    return error;
-                }
+    let Some(segments) = url.path_segments() else { return false };
+
+    let mut found = false;
+    let redacted = segments
+        .map(|segment| {
+            if is_token(segment) {
+                found = true;
+                "token:redacted"
+            } else {
+                segment
             }
-        }
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+
+    if found {
+        url.set_path(&redacted);
     }
+    found
+}
 
-    // couldn't find token in the url, hide the whole url
-    error.without_url()
+impl ApiError {
+    /// Returns `true` if this error is caused by a problem on the Telegram side
+    /// (e.g. an internal server error), so sending the same request again may
+    /// succeed.
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        const SERVER_ERRORS: &[&str] =
+            &["Internal Server Error", "Bad Gateway", "Service Unavailable", "Gateway Timeout"];
+
+        match self {
+            Self::Unknown(description) => {
+                SERVER_ERRORS.iter().any(|prefix| description.starts_with(prefix))
+            }
+            _ => false,
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    #[test]
+    fn redact_token() {
+        let token = "bot535362388:AAF7-g0gYncWnm5IyfZlpPRqRRv6kNAGlao";
+        let redact = |url: &str| {
+            let mut url = reqwest::Url::parse(url).unwrap();
+            super::redact_token(&mut url).then(|| url.to_string())
+        };
+
+        assert_eq!(
+            redact(&format!("https://api.telegram.org/{token}/getMe")).as_deref(),
+            Some("https://api.telegram.org/token:redacted/getMe")
+        );
+        assert_eq!(
+            redact(&format!("https://api.telegram.org/file/{token}/photos/1.jpg")).as_deref(),
+            Some("https://api.telegram.org/file/token:redacted/photos/1.jpg")
+        );
+        assert_eq!(
+            redact(&format!("https://example.com/telegram/{token}/getMe")).as_deref(),
+            Some("https://example.com/telegram/token:redacted/getMe")
+        );
+        assert_eq!(redact("https://api.telegram.org/botTOKEN/getMe"), None);
+    }
+
+    #[test]
+    fn retryable() {
+        use std::{sync::Arc, time::Duration};
+
+        use super::{ApiError, RequestError};
+        use crate::types::Seconds;
+
+        let flood = RequestError::RetryAfter(Seconds::from_seconds(3));
+        assert!(flood.is_retryable());
+        assert_eq!(flood.retry_after(), Some(Seconds::from_seconds(3)));
+
+        assert!(RequestError::Timeout(Duration::from_secs(1)).is_retryable());
+        assert!(
+            RequestError::Api(ApiError::Unknown("Internal Server Error".to_owned())).is_retryable()
+        );
+
+        let blocked = RequestError::Api(ApiError::BotBlocked);
+        assert!(!blocked.is_retryable());
+        assert_eq!(blocked.retry_after(), None);
+        assert_eq!(blocked.api_error(), Some(&ApiError::BotBlocked));
+
+        let io = RequestError::Io(Arc::new(std::io::Error::other("error")));
+        assert!(!io.is_retryable());
+        assert_eq!(io.api_error(), None);
+    }
+
     #[test]
     fn custom_result() {
         use super::ApiError;
@@ -43,6 +43,18 @@
 //! - `erased` — enables [`ErasedRequester`] bot adaptor
 //! - `throttle` — enables [`Throttle`] bot adaptor
 //! - `cache_me` — enables [`CacheMe`] bot adaptor
+//! - `cache` — enables [`Cache`] bot adaptor
+//! - `dedup` — enables [`Dedup`] bot adaptor
+//! - `accounting` — enables [`Accounting`] bot adaptor
+//! - `migrate_chats` — enables [`MigrateChats`] bot adaptor
+//! - `timeout` — enables [`Timeout`] bot adaptor
+//! - `strict_validation` — makes [`Bot`] check some Telegram limits (lengths of
+//!   captions, texts, invoice titles and callback data, sizes of inline
+//!   keyboard rows) before sending requests, returning
+//!   [`RequestError::Validation`] instead of sending invalid requests. Useful
+//!   in development, not included in `full`
+//! - `request_signing` — enables [`Bot::sign_requests`], signing requests with
+//!   HMAC for gateways in front of the Bot API
 //! - `full` — enables all features except `nightly` and tls-related
 //! - `nightly` — enables nightly-only features, currently:
 //!   - Removes some future boxing using `#![feature(type_alias_impl_trait)]`
@@ -52,6 +64,11 @@
 //! [`ErasedRequester`]: adaptors::ErasedRequester
 //! [`Throttle`]: adaptors::Throttle
 //! [`CacheMe`]: adaptors::CacheMe
+//! [`Cache`]: adaptors::Cache
+//! [`Dedup`]: adaptors::Dedup
+//! [`Accounting`]: adaptors::Accounting
+//! [`MigrateChats`]: adaptors::MigrateChats
+//! [`Timeout`]: adaptors::Timeout
 //! [`native-tls`]: https://docs.rs/native-tls
 //! [`rustls`]: https://docs.rs/rustls
 
@@ -129,6 +146,10 @@ mod bot;
 // implementation details
 mod serde_multipart;
 mod util;
+#[cfg(feature = "strict_validation")]
+mod validation;
 
 #[cfg(test)]
 mod codegen;
+#[cfg(test)]
+mod test_utils;
@@ -2,7 +2,12 @@
 
 use std::time::Duration;
 
-pub use self::download::{download_file, download_file_stream, Download};
+#[cfg(feature = "request_signing")]
+pub use self::signing::RequestSigner;
+pub use self::{
+    download::{download_file, download_file_stream, Download},
+    secret::SecretString,
+};
 
 pub(crate) use self::{
     request::{request_json, request_multipart},
@@ -11,6 +16,9 @@ pub(crate) use self::{
 
 mod download;
 mod request;
+mod secret;
+#[cfg(feature = "request_signing")]
+pub(crate) mod signing;
 mod telegram_response;
 
 /// The default Telegram API URL.
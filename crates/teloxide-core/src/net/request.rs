@@ -1,7 +1,7 @@
 use std::{any::TypeId, sync::Arc, time::Duration};
 
 use reqwest::{
-    header::{HeaderValue, CONTENT_TYPE},
+    header::{HeaderMap, HeaderValue, CONTENT_TYPE},
     Client, Response,
 };
 use serde::de::DeserializeOwned;
@@ -17,6 +17,7 @@ pub async fn request_multipart<T>(
     method_name: &str,
     params: reqwest::multipart::Form,
     _timeout_hint: Option<Duration>,
+    headers: HeaderMap,
 ) -> ResponseResult<T>
 where
     T: DeserializeOwned + 'static,
@@ -36,6 +37,7 @@ where
 
     let request = client
         .post(crate::net::method_url(api_url, token, method_name))
+        .headers(headers)
         .multipart(params)
         .build()?;
 
@@ -56,6 +58,7 @@ pub async fn request_json<T>(
     method_name: &str,
     params: Vec<u8>,
     _timeout_hint: Option<Duration>,
+    headers: HeaderMap,
 ) -> ResponseResult<T>
 where
     T: DeserializeOwned + 'static,
@@ -76,6 +79,7 @@ where
     let request = client
         .post(crate::net::method_url(api_url, token, method_name))
         .header(CONTENT_TYPE, HeaderValue::from_static("application/json"))
+        .headers(headers)
         .body(params)
         .build()?;
 
@@ -0,0 +1,104 @@
+use std::{fmt, sync::Arc};
+
+/// A string which is hidden from logs, such as a bot token.
+///
+/// [`Debug`] and [`Display`] implementations of `SecretString` don't show the
+/// string, so structures containing it (e.g. [`Bot`]) can be logged safely.
+/// Use [`SecretString::expose`] to get the string and
+/// [`SecretString::fingerprint`] to tell secrets apart in logs.
+///
+/// Clones share the same string.
+///
+/// ```
+/// use teloxide_core::net::SecretString;
+///
+/// let token = SecretString::new("1234567890:ABCdefGHIjklMNOpqrSTUvwxYZ0123456789");
+///
+/// assert_eq!(format!("{token:?}"), "SecretString([REDACTED])");
+/// assert_eq!(token.to_string(), "[REDACTED]");
+/// assert_eq!(token.expose(), "1234567890:ABCdefGHIjklMNOpqrSTUvwxYZ0123456789");
+/// ```
+///
+/// [`Display`]: fmt::Display
+/// [`Bot`]: crate::Bot
+#[derive(Clone, PartialEq, Eq)]
+pub struct SecretString(Arc<str>);
+
+impl SecretString {
+    /// Wraps `secret`.
+    #[must_use]
+    pub fn new<S>(secret: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self(Into::<String>::into(secret).into())
+    }
+
+    /// Returns the secret string.
+    ///
+    /// Be careful not to log the returned string.
+    #[must_use]
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns a short hash of the secret, which identifies it without
+    /// exposing it.
+    ///
+    /// The fingerprint consists of 8 hexadecimal digits and is the same across
+    /// runs and versions of the library, so it can be used to tell which bot
+    /// has written a log line.
+    #[must_use]
+    pub fn fingerprint(&self) -> String {
+        // 64-bit FNV-1a, folded to 32 bits
+        const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+        const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+        let hash = self
+            .0
+            .bytes()
+            .fold(OFFSET_BASIS, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(PRIME));
+
+        format!("{:08x}", (hash >> 32) as u32 ^ hash as u32)
+    }
+}
+
+impl From<String> for SecretString {
+    fn from(secret: String) -> Self {
+        Self(secret.into())
+    }
+}
+
+impl From<&str> for SecretString {
+    fn from(secret: &str) -> Self {
+        Self(secret.into())
+    }
+}
+
+impl fmt::Debug for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SecretString([REDACTED])")
+    }
+}
+
+impl fmt::Display for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("[REDACTED]")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fingerprint() {
+        let token = SecretString::new("1234567890:ABCdefGHIjklMNOpqrSTUvwxYZ0123456789");
+        let other = SecretString::new("1234567890:ABCdefGHIjklMNOpqrSTUvwxYZ0123456780");
+
+        assert_eq!(token.fingerprint().len(), 8);
+        assert_eq!(token.fingerprint(), token.clone().fingerprint());
+        assert_ne!(token.fingerprint(), other.fingerprint());
+        assert_eq!(SecretString::new("").fingerprint(), "4fd0bfc1");
+    }
+}
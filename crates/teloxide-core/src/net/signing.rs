@@ -0,0 +1,219 @@
+use std::{
+    fmt,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use hmac::{Hmac, Mac};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use serde::Serialize;
+use serde_json::Value;
+use sha2::Sha256;
+
+/// Signs requests sent by a [`Bot`], for gateways in front of the Bot API
+/// which require authentication beyond the bot token.
+///
+/// Every request gets two headers:
+/// - The timestamp header (`X-Bot-Api-Timestamp` by default) with the current
+///   UNIX time in seconds.
+/// - The signature header (`X-Bot-Api-Signature` by default) with the
+///   hex-encoded HMAC-SHA256 of `{timestamp}\n{method}\n{body}`, keyed with the
+///   shared secret.
+///
+/// `method` is the name of the Bot API method as used in the URL of the
+/// request (e.g. `SendMessage`) and `body` is the JSON body of the request.
+///
+/// The bodies of multipart requests (which upload files) are streamed, so
+/// instead of the body their form fields are signed: `body` is the list of
+/// `{name}={value}\n` lines of all text fields, sorted by name, with values
+/// exactly as they are sent. Fields with files to upload have values
+/// `attach://{part name}`, binding the names of the file parts, but the
+/// contents of the files aren't signed. File downloads aren't signed.
+///
+/// [`Bot`]: crate::Bot
+///
+/// ## Examples
+///
+/// ```
+/// use teloxide_core::{net::RequestSigner, Bot};
+///
+/// let bot = Bot::new("TOKEN").sign_requests(RequestSigner::hmac_sha256("shared secret"));
+/// ```
+#[derive(Clone)]
+pub struct RequestSigner {
+    secret: Arc<[u8]>,
+    signature_header: HeaderName,
+    timestamp_header: HeaderName,
+}
+
+impl RequestSigner {
+    /// Creates a signer computing HMAC-SHA256 with `secret`.
+    pub fn hmac_sha256<S>(secret: S) -> Self
+    where
+        S: Into<Vec<u8>>,
+    {
+        Self {
+            secret: secret.into().into(),
+            signature_header: HeaderName::from_static("x-bot-api-signature"),
+            timestamp_header: HeaderName::from_static("x-bot-api-timestamp"),
+        }
+    }
+
+    /// Sets the name of the header with the signature.
+    #[must_use]
+    pub fn signature_header(self, name: HeaderName) -> Self {
+        Self { signature_header: name, ..self }
+    }
+
+    /// Sets the name of the header with the timestamp.
+    #[must_use]
+    pub fn timestamp_header(self, name: HeaderName) -> Self {
+        Self { timestamp_header: name, ..self }
+    }
+
+    /// Returns the hex-encoded signature of a request.
+    ///
+    /// This can be used by gateways to verify requests.
+    #[must_use]
+    pub fn sign(&self, timestamp: u64, method: &str, body: &[u8]) -> String {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.secret).expect("HMAC can take a key of any size");
+        mac.update(timestamp.to_string().as_bytes());
+        mac.update(b"\n");
+        mac.update(method.as_bytes());
+        mac.update(b"\n");
+        mac.update(body);
+
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    pub(crate) fn headers(&self, method: &str, body: &[u8]) -> HeaderMap {
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        let signature = self.sign(timestamp, method, body);
+
+        let mut headers = HeaderMap::with_capacity(2);
+        headers.insert(self.timestamp_header.clone(), HeaderValue::from(timestamp));
+        headers.insert(
+            self.signature_header.clone(),
+            HeaderValue::from_str(&signature).expect("hex is a valid header value"),
+        );
+        headers
+    }
+}
+
+/// Returns the signed representation of the form fields of a multipart
+/// request, see [`RequestSigner`].
+pub(crate) fn form_fields<P>(payload: &P) -> Vec<u8>
+where
+    P: Serialize,
+{
+    // Top-level fields of the form are serialized like this by
+    // `serde_multipart`: strings as is, other values as JSON
+    let fields = match serde_json::to_value(payload) {
+        Ok(Value::Object(fields)) => fields,
+        _ => return Vec::new(),
+    };
+
+    let mut fields = fields
+        .into_iter()
+        .filter(|(_, value)| !value.is_null())
+        .map(|(name, value)| match value {
+            Value::String(value) => (name, value),
+            value => (name, value.to_string()),
+        })
+        .collect::<Vec<_>>();
+    fields.sort_unstable();
+
+    fields.into_iter().flat_map(|(name, value)| format!("{name}={value}\n").into_bytes()).collect()
+}
+
+impl fmt::Debug for RequestSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RequestSigner")
+            .field("secret", &"[redacted]")
+            .field("signature_header", &self.signature_header)
+            .field("timestamp_header", &self.timestamp_header)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signatures() {
+        let signer = RequestSigner::hmac_sha256("key");
+
+        let expected = "657530880d881ba5360073eb22ecc1521204167b63940f888dc0cd1b0e7ce33a";
+        assert_eq!(signer.sign(1_700_000_000, "getMe", b"{}"), expected);
+        assert_ne!(signer.sign(1_700_000_000, "getMe", b"{ }"), expected);
+        assert_ne!(
+            RequestSigner::hmac_sha256("other").sign(1_700_000_000, "getMe", b"{}"),
+            expected
+        );
+
+        let headers = signer.headers("getMe", b"{}");
+        let timestamp: u64 = headers["x-bot-api-timestamp"].to_str().unwrap().parse().unwrap();
+        assert_eq!(
+            headers["x-bot-api-signature"].to_str().unwrap(),
+            signer.sign(timestamp, "getMe", b"{}")
+        );
+
+        assert!(!format!("{signer:?}").contains("key"));
+    }
+
+    #[test]
+    fn multipart_signatures() {
+        use crate::{
+            payloads::{SendPhoto, SendPhotoSetters as _},
+            types::{ChatId, InputFile},
+        };
+
+        let signer = RequestSigner::hmac_sha256("key");
+        let photo = InputFile::memory(&b"photo"[..]);
+        let sign =
+            |payload: &SendPhoto| signer.sign(1_700_000_000, "SendPhoto", &form_fields(payload));
+
+        let payload = SendPhoto::new(ChatId(1), photo.clone()).caption("a");
+        let other_caption = SendPhoto::new(ChatId(1), photo.clone()).caption("b");
+        let other_chat = SendPhoto::new(ChatId(2), photo.clone()).caption("a");
+        let other_photo = SendPhoto::new(ChatId(1), InputFile::memory(&b"photo"[..])).caption("a");
+
+        assert_ne!(sign(&payload), sign(&other_caption));
+        assert_ne!(sign(&payload), sign(&other_chat));
+        assert_ne!(sign(&payload), sign(&other_photo));
+        assert_eq!(sign(&payload), sign(&payload.clone()));
+
+        let fields = String::from_utf8(form_fields(&payload)).unwrap();
+        assert_eq!(fields, format!("caption=a\nchat_id=1\nphoto=attach://{}\n", photo.id()));
+    }
+
+    #[tokio::test]
+    async fn bot_sends_signatures() {
+        use tokio::io::AsyncReadExt;
+
+        use crate::{requests::Requester, Bot};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap()).parse().unwrap();
+        let bot =
+            Bot::new("TOKEN").set_api_url(url).sign_requests(RequestSigner::hmac_sha256("key"));
+
+        // The request fails, since the connection is closed without a response
+        let request = tokio::spawn(async move { bot.get_me().await });
+
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut head = Vec::new();
+        while !head.ends_with(b"\r\n\r\n") {
+            head.push(stream.read_u8().await.unwrap());
+        }
+        drop(stream);
+        request.await.unwrap().unwrap_err();
+
+        let head = String::from_utf8(head).unwrap().to_lowercase();
+        assert!(head.starts_with("post /bottoken/getme "));
+        assert!(head.contains("x-bot-api-timestamp: "));
+        assert!(head.contains("x-bot-api-signature: "));
+    }
+}
@@ -15,9 +15,9 @@ impl_payload! {
             pub name: String [into],
         }
         optional {
-            /// Color of the topic icon in RGB format. Currently, must be one of 7322096 (`0x6FB9F0`), 16766590 (`0xFFD67E`), 13338331 (`0xCB86DB`), 9367192 (`0x8EEE98`), 16749490 (`0xFF93B2`), or 16478047 (`0xFB6F5F`). To construct color from these values use [`Rgb::from_u32`]
+            /// Color of the topic icon in RGB format. Currently, must be one of 7322096 (`0x6FB9F0`), 16766590 (`0xFFD67E`), 13338331 (`0xCB86DB`), 9367192 (`0x8EEE98`), 16749490 (`0xFF93B2`), or 16478047 (`0xFB6F5F`). These colors are available as [`Rgb::TOPIC_COLORS`]
             ///
-            /// [`Rgb::from_u32`]: crate::types::Rgb::from_u32
+            /// [`Rgb::TOPIC_COLORS`]: crate::types::Rgb::TOPIC_COLORS
             pub icon_color: Rgb,
             /// Unique identifier of the custom emoji shown as the topic icon. Use `getForumTopicIconStickers` to get all allowed custom emoji identifiers.
             pub icon_custom_emoji_id: CustomEmojiId,
@@ -1,17 +1,32 @@
 use crate::{adaptors::DefaultParseMode, requests::Requester, types::ParseMode};
 
+#[cfg(feature = "cache")]
+use crate::adaptors::Cache;
+
 #[cfg(feature = "cache_me")]
 use crate::adaptors::CacheMe;
 
+#[cfg(feature = "dedup")]
+use crate::adaptors::Dedup;
+
+#[cfg(feature = "accounting")]
+use crate::adaptors::Accounting;
+
 #[cfg(feature = "erased")]
 use crate::adaptors::ErasedRequester;
 
 #[cfg(feature = "trace_adaptor")]
 use crate::adaptors::trace::{Settings, Trace};
 
+#[cfg(feature = "migrate_chats")]
+use crate::adaptors::MigrateChats;
+
 #[cfg(feature = "throttle")]
 use crate::adaptors::throttle::{Limits, Throttle};
 
+#[cfg(feature = "timeout")]
+use crate::adaptors::Timeout;
+
 /// Extensions methods for [`Requester`].
 pub trait RequesterExt: Requester {
     /// Add `get_me` caching ability, see [`CacheMe`] for more.
@@ -24,6 +39,38 @@ pub trait RequesterExt: Requester {
         CacheMe::new(self)
     }
 
+    /// Add caching of responses to idempotent requests, see [`Cache`] for
+    /// more.
+    #[cfg(feature = "cache")]
+    #[must_use]
+    fn cache(self) -> Cache<Self>
+    where
+        Self: Sized,
+    {
+        Cache::new(self)
+    }
+
+    /// Drop duplicates of mutating requests sent within `window`, see
+    /// [`Dedup`] for more.
+    #[cfg(feature = "dedup")]
+    #[must_use]
+    fn dedup(self, window: std::time::Duration) -> Dedup<Self>
+    where
+        Self: Sized,
+    {
+        Dedup::new(self, window)
+    }
+
+    /// Count requests per method and per chat, see [`Accounting`] for more.
+    #[cfg(feature = "accounting")]
+    #[must_use]
+    fn accounting(self) -> Accounting<Self>
+    where
+        Self: Sized,
+    {
+        Accounting::new(self)
+    }
+
     /// Erase requester type.
     #[cfg(feature = "erased")]
     #[must_use]
@@ -59,6 +106,28 @@ pub trait RequesterExt: Requester {
         Throttle::new_spawn(self, limits)
     }
 
+    /// Replace ids of groups migrated to supergroups, see [`MigrateChats`]
+    /// for more.
+    #[cfg(feature = "migrate_chats")]
+    #[must_use]
+    fn migrate_chats(self) -> MigrateChats<Self>
+    where
+        Self: Sized,
+    {
+        MigrateChats::new(self)
+    }
+
+    /// Fail requests which don't complete in `timeout`, see [`Timeout`] for
+    /// more.
+    #[cfg(feature = "timeout")]
+    #[must_use]
+    fn timeout(self, timeout: std::time::Duration) -> Timeout<Self>
+    where
+        Self: Sized,
+    {
+        Timeout::new(self, timeout)
+    }
+
     /// Specifies default [`ParseMode`], which will be used during all calls to:
     ///
     ///  - [`send_message`]
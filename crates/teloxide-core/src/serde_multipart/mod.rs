@@ -13,12 +13,12 @@
 mod error;
 mod serializers;
 
-use std::future::Future;
+use std::{collections::HashSet, future::Future};
 
 use reqwest::multipart::Form;
 use serde::Serialize;
 
-use crate::requests::MultipartPayload;
+use crate::{requests::MultipartPayload, types::InputFile};
 use error::Error;
 use serializers::MultipartSerializer;
 
@@ -29,26 +29,12 @@ pub(crate) fn to_form<T>(val: &mut T) -> Result<impl Future<Output = Form>, Erro
 where
     T: Serialize + MultipartPayload,
 {
-    let mut form = val.serialize(MultipartSerializer::new())?;
+    let form = val.serialize(MultipartSerializer::new())?;
 
     let mut vec = Vec::with_capacity(1);
     val.move_files(&mut |f| vec.push(f));
-    let iter = vec.into_iter();
-
-    let fut = async move {
-        for file in iter {
-            if file.needs_attach() {
-                let id = file.id().to_owned();
-                if let Some(part) = file.into_part() {
-                    form = form.part(id, part.await);
-                }
-            }
-        }
-
-        form
-    };
 
-    Ok(fut)
+    Ok(attach_files(form, vec))
 }
 
 /// Serializes given value into [`Form`].
@@ -58,39 +44,51 @@ pub(crate) fn to_form_ref<T: ?Sized>(val: &T) -> Result<impl Future<Output = For
 where
     T: Serialize + MultipartPayload,
 {
-    let mut form = val.serialize(MultipartSerializer::new())?;
+    let form = val.serialize(MultipartSerializer::new())?;
     let mut vec = Vec::with_capacity(1);
     val.copy_files(&mut |f| vec.push(f));
 
-    let iter = vec.into_iter();
+    Ok(attach_files(form, vec))
+}
 
-    let fut = async move {
-        for file in iter {
-            if file.needs_attach() {
-                let id = file.id().to_owned();
-                if let Some(part) = file.into_part() {
-                    form = form.part(id, part.await);
-                }
-            }
+/// Adds parts with the contents of `files` to `form`.
+async fn attach_files(mut form: Form, files: Vec<InputFile>) -> Form {
+    for file in unique_attachments(files) {
+        let id = file.id().to_owned();
+        if let Some(part) = file.into_part() {
+            form = form.part(id, part.await);
         }
+    }
 
-        form
-    };
+    form
+}
 
-    Ok(fut)
+/// Filters out files that don't need to be attached and clones of the same
+/// file (e.g. a file used as both a media and its thumbnail).
+///
+/// Duplicates are dropped before bodies are created, so that the body of
+/// [`InputFile::read`] can be streamed instead of being buffered for the
+/// other copies.
+fn unique_attachments(files: Vec<InputFile>) -> Vec<InputFile> {
+    let mut ids = HashSet::new();
+    files
+        .into_iter()
+        .filter(|file| file.needs_attach() && ids.insert(file.id().to_owned()))
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use tokio::fs::File;
 
-    use super::to_form_ref;
+    use super::{to_form, to_form_ref};
     use crate::{
         payloads::{self, setters::*},
         types::{
             ChatId, InputFile, InputMedia, InputMediaAnimation, InputMediaAudio,
             InputMediaDocument, InputMediaPhoto, InputMediaVideo, InputSticker, MessageEntity,
-            MessageEntityKind, ParseMode, StickerFormat, UserId,
+            MessageEntityKind, MessageId, ParseMode, Recipient, ReplyParameters, StickerFormat,
+            UserId,
         },
     };
 
@@ -180,6 +178,113 @@ mod tests {
         .await;
     }
 
+    #[tokio::test]
+    async fn send_media_group_mixed_sources() {
+        let path = std::env::temp_dir().join("teloxide-core-mixed-sources.txt");
+        tokio::fs::write(&path, "file contents").await.unwrap();
+
+        let read = InputFile::read(&b"read contents"[..]);
+        let mut payload = payloads::SendMediaGroup::new(
+            ChatId(0),
+            [
+                InputMedia::Video(InputMediaVideo::new(read.clone()).thumbnail(read)),
+                InputMedia::Document(InputMediaDocument::new(InputFile::memory(
+                    &b"memory contents"[..],
+                ))),
+                InputMedia::Document(InputMediaDocument::new(InputFile::file(&path))),
+            ],
+        );
+        let body = form_body(to_form(&mut payload).unwrap().await).await;
+
+        // The file used twice is uploaded once
+        assert_eq!(body.matches("read contents").count(), 1, "{body}");
+        assert_eq!(body.matches("memory contents").count(), 1, "{body}");
+        assert_eq!(body.matches("file contents").count(), 1, "{body}");
+        assert_eq!(body.matches("filename=").count(), 3, "{body}");
+
+        tokio::fs::remove_file(path).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn read_sized_and_url_with_headers() {
+        use tokio::{
+            io::{AsyncReadExt, AsyncWriteExt},
+            net::TcpListener,
+        };
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/files/fetched.txt", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            while !request.ends_with(b"\r\n\r\n") {
+                request.push(stream.read_u8().await.unwrap());
+            }
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 16\r\n\r\nfetched contents")
+                .await
+                .unwrap();
+            String::from_utf8(request).unwrap()
+        });
+
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert("authorization", "Bearer secret".parse().unwrap());
+        let fetched = InputFile::url_with_headers(url.parse().unwrap(), headers);
+        assert!(!format!("{fetched:?}").contains("secret"));
+
+        let mut payload = payloads::SendMediaGroup::new(
+            ChatId(0),
+            [
+                InputMedia::Document(InputMediaDocument::new(InputFile::read_sized(
+                    &b"read contents"[..],
+                    "read.txt",
+                    13,
+                ))),
+                InputMedia::Document(InputMediaDocument::new(fetched)),
+            ],
+        );
+        let body = form_body(to_form(&mut payload).unwrap().await).await;
+
+        assert!(server.await.unwrap().contains("authorization: Bearer secret"));
+        assert!(body.contains("filename=\"read.txt\"\r\n\r\nread contents"), "{body}");
+        assert!(body.contains("filename=\"fetched.txt\"\r\n\r\nfetched contents"), "{body}");
+    }
+
+    #[tokio::test]
+    async fn recipient_serialization() {
+        let payload = payloads::SendPhoto::new(ChatId(-100123), InputFile::file_id("0".into()))
+            .reply_parameters(ReplyParameters {
+                chat_id: Some(Recipient::ChannelUsername("@channel".to_owned())),
+                ..ReplyParameters::new(MessageId(1))
+            });
+        let body = form_body(to_form_ref(&payload).unwrap().await).await;
+
+        // Top-level ids are sent as plain digits and nested ones as JSON numbers
+        assert!(body.contains("name=\"chat_id\"\r\n\r\n-100123\r\n"), "{body}");
+        assert!(body.contains(r#""chat_id":"@channel""#), "{body}");
+
+        let payload =
+            payloads::SendPhoto::new("@channel".to_owned(), InputFile::file_id("0".into()))
+                .reply_parameters(ReplyParameters {
+                    chat_id: Some(Recipient::Id(ChatId(-100123))),
+                    ..ReplyParameters::new(MessageId(1))
+                });
+        let body = form_body(to_form_ref(&payload).unwrap().await).await;
+
+        assert!(body.contains("name=\"chat_id\"\r\n\r\n@channel\r\n"), "{body}");
+        assert!(body.contains(r#""chat_id":-100123"#), "{body}");
+    }
+
+    async fn form_body(form: reqwest::multipart::Form) -> String {
+        use http_body_util::BodyExt;
+
+        let mut request =
+            reqwest::Client::new().post("http://localhost").multipart(form).build().unwrap();
+        let body = request.body_mut().take().unwrap();
+
+        String::from_utf8(body.collect().await.unwrap().to_bytes().to_vec()).unwrap()
+    }
+
     fn entities() -> impl Iterator<Item = MessageEntity> {
         <_>::into_iter([
             MessageEntity::new(MessageEntityKind::Url, 0, 0),
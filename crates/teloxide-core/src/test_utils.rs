@@ -0,0 +1,48 @@
+//! Fixtures shared by unit tests.
+
+use crate::types::Me;
+
+/// Returns a bot whose requests fail, since nothing listens on its API URL.
+#[cfg(any(
+    feature = "accounting",
+    feature = "cache",
+    feature = "dedup",
+    feature = "throttle",
+    feature = "timeout"
+))]
+pub(crate) fn unreachable_bot() -> crate::Bot {
+    crate::Bot::new("TOKEN").set_api_url("http://127.0.0.1:1".parse().unwrap())
+}
+
+/// Returns a bot whose requests never complete, since its API server accepts
+/// connections, but never responds.
+#[cfg(feature = "timeout")]
+pub(crate) async fn silent_bot() -> crate::Bot {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap()).parse().unwrap();
+
+    tokio::spawn(async move {
+        let mut connections = Vec::new();
+        while let Ok((stream, _)) = listener.accept().await {
+            connections.push(stream);
+        }
+    });
+
+    crate::Bot::new("TOKEN").set_api_url(url)
+}
+
+/// Returns the bot `@bot` with id `42`.
+pub(crate) fn me() -> Me {
+    serde_json::from_value(serde_json::json!({
+        "id": 42,
+        "is_bot": true,
+        "first_name": "Bot",
+        "username": "bot",
+        "can_join_groups": false,
+        "can_read_all_group_messages": false,
+        "supports_inline_queries": false,
+        "can_connect_to_business": false,
+        "has_main_web_app": false
+    }))
+    .unwrap()
+}
@@ -1,6 +1,7 @@
 //! Telegram API types.
 
 pub use accepted_gift_types::*;
+pub use admin_rights::*;
 pub use allowed_update::*;
 pub use animation::*;
 pub use audio::*;
@@ -44,6 +45,7 @@ pub use chat_type::*;
 pub use checklist::*;
 pub use chosen_inline_result::*;
 pub use contact::*;
+pub use copy_restriction::*;
 pub use copy_text_button::*;
 pub use dice::*;
 pub use dice_emoji::*;
@@ -193,6 +195,7 @@ pub use webhook_info::*;
 pub use write_access_allowed::*;
 
 mod accepted_gift_types;
+mod admin_rights;
 mod allowed_update;
 mod animation;
 mod audio;
@@ -235,6 +238,7 @@ mod chat_type;
 mod checklist;
 mod chosen_inline_result;
 mod contact;
+mod copy_restriction;
 mod copy_text_button;
 mod dice;
 mod dice_emoji;
@@ -608,7 +612,7 @@ pub(crate) mod option_msg_id_as_int {
 pub(crate) mod vec_msg_id_as_vec_int {
     use crate::types::MessageId;
 
-    use serde::{ser::SerializeSeq, Serializer};
+    use serde::{ser::SerializeSeq, Deserialize, Deserializer, Serializer};
 
     pub(crate) fn serialize<S>(msg_ids: &Vec<MessageId>, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -621,9 +625,16 @@ pub(crate) mod vec_msg_id_as_vec_int {
         seq.end()
     }
 
+    pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<Vec<MessageId>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Vec::<i32>::deserialize(deserializer).map(|ids| ids.into_iter().map(MessageId).collect())
+    }
+
     #[test]
     fn test() {
-        #[derive(serde::Serialize)]
+        #[derive(serde::Serialize, Deserialize)]
         struct Struct {
             #[serde(with = "crate::types::vec_msg_id_as_vec_int")]
             msg_ids: Vec<MessageId>,
@@ -633,6 +644,44 @@ pub(crate) mod vec_msg_id_as_vec_int {
             let s = Struct { msg_ids: vec![MessageId(1), MessageId(2)] };
             let json = serde_json::to_string(&s).unwrap();
             assert_eq!(json, "{\"msg_ids\":[1,2]}");
+
+            let s: Struct = serde_json::from_str(&json).unwrap();
+            assert_eq!(s.msg_ids, [MessageId(1), MessageId(2)]);
+        }
+    }
+}
+
+/// Helpers for one-line `Display` implementations of updates, meant for logs.
+pub(crate) mod compact_display {
+    use std::fmt;
+
+    use crate::types::{Chat, User};
+
+    /// The maximum number of characters of a text shown in logs.
+    const MAX_SNIPPET_LEN: usize = 64;
+
+    pub(crate) fn user(f: &mut fmt::Formatter<'_>, user: &User) -> fmt::Result {
+        write!(f, "user {}", user.id)?;
+        match &user.username {
+            Some(username) => write!(f, " @{username}"),
+            None => Ok(()),
+        }
+    }
+
+    pub(crate) fn chat(f: &mut fmt::Formatter<'_>, chat: &Chat) -> fmt::Result {
+        write!(f, "chat {}", chat.id)?;
+        match chat.username() {
+            Some(username) => write!(f, " @{username}"),
+            None => Ok(()),
+        }
+    }
+
+    /// Writes `text` quoted and escaped, shortened to [`MAX_SNIPPET_LEN`]
+    /// characters.
+    pub(crate) fn snippet(f: &mut fmt::Formatter<'_>, text: &str) -> fmt::Result {
+        match text.char_indices().nth(MAX_SNIPPET_LEN) {
+            Some((end, _)) => write!(f, "{:?}…", &text[..end]),
+            None => write!(f, "{text:?}"),
         }
     }
 }
@@ -0,0 +1,119 @@
+use crate::types::{Administrator, ChatAdministratorRights};
+
+bitflags::bitflags! {
+    /// Privileges of an administrator of a chat.
+    ///
+    /// This is a compact form of the privileges of [`Administrator`] and
+    /// [`ChatAdministratorRights`], convenient for checking several privileges
+    /// at once. See also [`ChatMemberKind::admin_rights`].
+    ///
+    /// [`ChatMemberKind::admin_rights`]: crate::types::ChatMemberKind::admin_rights
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use teloxide_core::types::AdminRights;
+    ///
+    /// let rights = AdminRights::DELETE_MESSAGES | AdminRights::RESTRICT_MEMBERS;
+    ///
+    /// assert!(rights.contains(AdminRights::DELETE_MESSAGES));
+    /// assert!(!rights.contains(AdminRights::DELETE_MESSAGES | AdminRights::PIN_MESSAGES));
+    /// ```
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    pub struct AdminRights: u16 {
+        /// Set if the administrator can access the chat event log, chat
+        /// statistics, see channel members and anonymous administrators, and
+        /// ignore slow mode.
+        const MANAGE_CHAT = 1;
+
+        /// Set if the administrator can change the chat title, photo and
+        /// other settings.
+        const CHANGE_INFO = 1 << 1;
+
+        /// Set if the administrator can post in the channel, channels only.
+        const POST_MESSAGES = 1 << 2;
+
+        /// Set if the administrator can edit messages of other users and can
+        /// pin messages, channels only.
+        const EDIT_MESSAGES = 1 << 3;
+
+        /// Set if the administrator can delete messages of other users.
+        const DELETE_MESSAGES = 1 << 4;
+
+        /// Set if the administrator can post stories to the chat.
+        const POST_STORIES = 1 << 5;
+
+        /// Set if the administrator can edit stories posted by other users.
+        const EDIT_STORIES = 1 << 6;
+
+        /// Set if the administrator can delete stories posted by other users.
+        const DELETE_STORIES = 1 << 7;
+
+        /// Set if the administrator can manage video chats.
+        const MANAGE_VIDEO_CHATS = 1 << 8;
+
+        /// Set if the administrator can invite new users to the chat.
+        const INVITE_USERS = 1 << 9;
+
+        /// Set if the administrator can restrict, ban or unban chat members.
+        const RESTRICT_MEMBERS = 1 << 10;
+
+        /// Set if the administrator can pin messages, supergroups only.
+        const PIN_MESSAGES = 1 << 11;
+
+        /// Set if the administrator can create, rename, close, and reopen
+        /// forum topics, supergroups only.
+        const MANAGE_TOPICS = 1 << 12;
+
+        /// Set if the administrator can add new administrators with a subset
+        /// of their own privileges or demote administrators that they have
+        /// promoted.
+        const PROMOTE_MEMBERS = 1 << 13;
+    }
+}
+
+impl From<&Administrator> for AdminRights {
+    fn from(admin: &Administrator) -> Self {
+        let flags = [
+            (admin.can_manage_chat, Self::MANAGE_CHAT),
+            (admin.can_change_info, Self::CHANGE_INFO),
+            (admin.can_post_messages, Self::POST_MESSAGES),
+            (admin.can_edit_messages, Self::EDIT_MESSAGES),
+            (admin.can_delete_messages, Self::DELETE_MESSAGES),
+            (admin.can_post_stories, Self::POST_STORIES),
+            (admin.can_edit_stories, Self::EDIT_STORIES),
+            (admin.can_delete_stories, Self::DELETE_STORIES),
+            (admin.can_manage_video_chats, Self::MANAGE_VIDEO_CHATS),
+            (admin.can_invite_users, Self::INVITE_USERS),
+            (admin.can_restrict_members, Self::RESTRICT_MEMBERS),
+            (admin.can_pin_messages, Self::PIN_MESSAGES),
+            (admin.can_manage_topics, Self::MANAGE_TOPICS),
+            (admin.can_promote_members, Self::PROMOTE_MEMBERS),
+        ];
+
+        flags.into_iter().filter(|&(set, _)| set).map(|(_, flag)| flag).collect()
+    }
+}
+
+impl From<&ChatAdministratorRights> for AdminRights {
+    fn from(rights: &ChatAdministratorRights) -> Self {
+        let flags = [
+            (rights.can_manage_chat, Self::MANAGE_CHAT),
+            (rights.can_change_info, Self::CHANGE_INFO),
+            (rights.can_post_messages.unwrap_or(false), Self::POST_MESSAGES),
+            (rights.can_edit_messages.unwrap_or(false), Self::EDIT_MESSAGES),
+            (rights.can_delete_messages, Self::DELETE_MESSAGES),
+            (rights.can_post_stories.unwrap_or(false), Self::POST_STORIES),
+            (rights.can_edit_stories.unwrap_or(false), Self::EDIT_STORIES),
+            (rights.can_delete_stories.unwrap_or(false), Self::DELETE_STORIES),
+            (rights.can_manage_video_chats, Self::MANAGE_VIDEO_CHATS),
+            (rights.can_invite_users, Self::INVITE_USERS),
+            (rights.can_restrict_members, Self::RESTRICT_MEMBERS),
+            (rights.can_pin_messages.unwrap_or(false), Self::PIN_MESSAGES),
+            (rights.can_manage_topics.unwrap_or(false), Self::MANAGE_TOPICS),
+            (rights.can_promote_members, Self::PROMOTE_MEMBERS),
+        ];
+
+        flags.into_iter().filter(|&(set, _)| set).map(|(_, flag)| flag).collect()
+    }
+}
@@ -27,3 +27,40 @@ pub struct BusinessConnection {
     /// `true`, if the connection is alive
     pub is_enabled: bool,
 }
+
+impl BusinessConnection {
+    /// Returns `true` if the connection is alive and the bot can send and edit
+    /// messages in the private chats that had incoming messages in the last 24
+    /// hours.
+    #[must_use]
+    pub fn can_reply(&self) -> bool {
+        self.is_enabled && self.rights.as_ref().is_some_and(|rights| rights.can_reply)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_reply() {
+        let json = r#"{
+            "id": "abc",
+            "user": { "id": 1, "is_bot": false, "first_name": "A" },
+            "user_chat_id": 1,
+            "date": 1721306082,
+            "rights": { "can_reply": true },
+            "is_enabled": true
+        }"#;
+
+        let mut connection = serde_json::from_str::<BusinessConnection>(json).unwrap();
+        assert!(connection.can_reply());
+
+        connection.is_enabled = false;
+        assert!(!connection.can_reply());
+
+        connection.is_enabled = true;
+        connection.rights = None;
+        assert!(!connection.can_reply());
+    }
+}
@@ -15,5 +15,23 @@ pub struct BusinessMessagesDeleted {
 
     /// The list of identifiers of deleted messages in the chat of the business
     /// account.
+    #[serde(with = "crate::types::vec_msg_id_as_vec_int")]
     pub message_ids: Vec<MessageId>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize() {
+        let json = r#"{
+            "business_connection_id": "abc",
+            "chat": { "id": 5, "first_name": "B", "type": "private" },
+            "message_ids": [1, 2]
+        }"#;
+
+        let deleted: BusinessMessagesDeleted = serde_json::from_str(json).unwrap();
+        assert_eq!(deleted.message_ids, [MessageId(1), MessageId(2)]);
+    }
+}
@@ -1,7 +1,9 @@
+use std::fmt;
+
 use derive_more::derive::From;
 use serde::{Deserialize, Serialize};
 
-use crate::types::{MaybeInaccessibleMessage, Message, User};
+use crate::types::{compact_display, MaybeInaccessibleMessage, Message, User};
 
 /// A unique identifier for this query.
 #[derive(
@@ -90,6 +92,34 @@ impl CallbackQuery {
     }
 }
 
+/// A short, one-line description of the query, meant for logs.
+///
+/// It includes ids of the query and the sender, the message with the button
+/// (if it's known) and the data, e.g.
+/// `callback query 123 from user 42 @alice on message 5 in chat 42: "like"`.
+impl fmt::Display for CallbackQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "callback query {} from ", self.id)?;
+        compact_display::user(f, &self.from)?;
+
+        if let Some(message) = &self.message {
+            write!(f, " on message {} in ", message.id())?;
+            compact_display::chat(f, message.chat())?;
+        } else if let Some(inline_message_id) = &self.inline_message_id {
+            write!(f, " on inline message {inline_message_id}")?;
+        }
+
+        if let Some(data) = &self.data {
+            f.write_str(": ")?;
+            compact_display::snippet(f, data)?;
+        } else if let Some(game_short_name) = &self.game_short_name {
+            write!(f, ": game {game_short_name}")?;
+        }
+
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::types::UserId;
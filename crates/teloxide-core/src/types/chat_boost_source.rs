@@ -64,6 +64,23 @@ impl ChatBoostSource {
             Self::Giveaway(giveaway) => return giveaway.user.as_ref(),
         })
     }
+
+    /// Returns how many times this source boosts the chat.
+    ///
+    /// Premium subscriptions boost the chat once, gift codes and Premium
+    /// giveaways 4 times, and Telegram Star giveaways once per 500 Stars of
+    /// the prize.
+    #[must_use]
+    pub fn boost_count(&self) -> u32 {
+        match self {
+            Self::Premium(_) => 1,
+            Self::GiftCode(_) => 4,
+            Self::Giveaway(ChatBoostSourceGiveaway { prize_star_count: Some(stars), .. }) => {
+                stars / 500
+            }
+            Self::Giveaway(_) => 4,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -124,4 +141,19 @@ mod tests {
         "#;
         serde_json::from_str::<ChatBoostSource>(data).unwrap();
     }
+
+    #[test]
+    fn boost_count() {
+        let giveaway = |prize_star_count| {
+            ChatBoostSource::Giveaway(ChatBoostSourceGiveaway {
+                giveaway_message_id: MessageId(1),
+                user: None,
+                prize_star_count,
+                is_unclaimed: false,
+            })
+        };
+
+        assert_eq!(giveaway(None).boost_count(), 4);
+        assert_eq!(giveaway(Some(2500)).boost_count(), 5);
+    }
 }
@@ -4,7 +4,9 @@ use serde::{Deserialize, Serialize};
 
 use crate::types::{
     AcceptedGiftTypes, Birthdate, BusinessIntro, BusinessLocation, BusinessOpeningHours, Chat,
-    ChatId, ChatLocation, ChatPermissions, ChatPhoto, Message, ReactionType, Seconds, User,
+    ChatId, ChatKind, ChatLocation, ChatPermissions, ChatPhoto, ChatPrivate, ChatPublic, Message,
+    PublicChatChannel, PublicChatKind, PublicChatSupergroup, ReactionType, Seconds, StickerSetName,
+    User,
 };
 
 /// Custom emoji identifier.
@@ -230,7 +232,7 @@ pub struct ChatFullInfoPublicSupergroup {
     pub is_forum: bool,
 
     /// For supergroups, name of group sticker set.
-    pub sticker_set_name: Option<String>,
+    pub sticker_set_name: Option<StickerSetName>,
 
     /// `true`, if the bot can change the group sticker set.
     #[serde(default, skip_serializing_if = "std::ops::Not::not")]
@@ -239,7 +241,7 @@ pub struct ChatFullInfoPublicSupergroup {
     /// For supergroups, the name of the group's custom emoji sticker set.
     /// Custom emoji from this set can be used by all users and bots in the
     /// group.
-    pub custom_emoji_sticker_set_name: Option<String>,
+    pub custom_emoji_sticker_set_name: Option<StickerSetName>,
 
     /// A default chat member permissions, for groups and supergroups.
     pub permissions: Option<ChatPermissions>,
@@ -410,6 +412,20 @@ impl ChatFullInfo {
         None
     }
 
+    /// `true`, if the supergroup chat is a forum (has [topics] enabled).
+    ///
+    /// [topics]: https://telegram.org/blog/topics-in-groups-collectible-usernames#topics-in-groups
+    #[must_use]
+    pub fn is_forum(&self) -> bool {
+        if let ChatFullInfoKind::Public(this) = &self.kind {
+            if let ChatFullInfoPublicKind::Supergroup(this) = &this.kind {
+                return this.is_forum;
+            }
+        }
+
+        false
+    }
+
     /// `true`, if the bot can change the group sticker set.
     #[must_use]
     pub fn can_set_sticker_set(&self) -> bool {
@@ -581,6 +597,47 @@ impl ChatFullInfo {
         }
     }
 
+    /// Returns the time after which all messages sent to the chat will be
+    /// automatically deleted, or `None` if auto-deletion is disabled.
+    ///
+    /// See also [`Message::auto_delete_date`].
+    #[must_use]
+    pub fn auto_delete_timer(&self) -> Option<Seconds> {
+        self.message_auto_delete_time.filter(|time| time.seconds() != 0)
+    }
+
+    /// Returns the slim [`Chat`] with the same identifier, type, title and
+    /// names, as it would appear in messages.
+    #[must_use]
+    pub fn to_chat(&self) -> Chat {
+        let kind = match &self.kind {
+            ChatFullInfoKind::Public(this) => ChatKind::Public(ChatPublic {
+                title: this.title.clone(),
+                kind: match &this.kind {
+                    ChatFullInfoPublicKind::Channel(this) => {
+                        PublicChatKind::Channel(PublicChatChannel {
+                            username: this.username.clone(),
+                        })
+                    }
+                    ChatFullInfoPublicKind::Group(_) => PublicChatKind::Group,
+                    ChatFullInfoPublicKind::Supergroup(this) => {
+                        PublicChatKind::Supergroup(PublicChatSupergroup {
+                            username: this.username.clone(),
+                            is_forum: this.is_forum,
+                        })
+                    }
+                },
+            }),
+            ChatFullInfoKind::Private(this) => ChatKind::Private(ChatPrivate {
+                username: this.username.clone(),
+                first_name: this.first_name.clone(),
+                last_name: this.last_name.clone(),
+            }),
+        };
+
+        Chat { id: self.id, kind }
+    }
+
     /// Returns all users that are "contained" in this `Chat`
     /// structure.
     ///
@@ -862,6 +919,35 @@ mod tests {
         assert_eq!(chat, chat2);
     }
 
+    #[test]
+    fn to_chat() {
+        let full: ChatFullInfo = from_str(
+            r#"{
+                "id": -1,
+                "type": "supergroup",
+                "title": "Forum",
+                "username": "forum",
+                "is_forum": true,
+                "description": "Not a part of `Chat`",
+                "max_reaction_count": 11,
+                "accepted_gift_types": {
+                    "unlimited_gifts": false,
+                    "limited_gifts": false,
+                    "unique_gifts": false,
+                    "premium_subscription": false
+                }
+            }"#,
+        )
+        .unwrap();
+        let chat: Chat = from_str(
+            r#"{"id":-1,"type":"supergroup","title":"Forum","username":"forum","is_forum":true}"#,
+        )
+        .unwrap();
+
+        assert!(full.is_forum());
+        assert_eq!(full.to_chat(), chat);
+    }
+
     #[test]
     fn private_chat_de_wrong_type_field() {
         assert!(from_str::<ChatFullInfo>(r#"{"id":0,"type":"WRONG"}"#).is_err());
@@ -2,7 +2,7 @@ use std::ops::Deref;
 
 use serde::{Deserialize, Serialize};
 
-use crate::types::{UntilDate, User};
+use crate::types::{AdminRights, ChatPermissions, UntilDate, User};
 
 /// This object contains information about one member of the chat.
 ///
@@ -582,6 +582,155 @@ impl ChatMemberKind {
             Self::Member(_) | Self::Restricted(_) | Self::Left | Self::Banned(_) => false,
         }
     }
+
+    /// Returns the administrator privileges of the user.
+    ///
+    /// I.e. returns
+    /// - [`AdminRights::all`] if the user is the owner of the chat
+    /// - the privileges of an administrator
+    /// - [`AdminRights::empty`] otherwise.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use teloxide_core::types::{AdminRights, ChatMemberKind};
+    ///
+    /// fn can_moderate(member: &ChatMemberKind) -> bool {
+    ///     member.admin_rights().contains(AdminRights::DELETE_MESSAGES | AdminRights::RESTRICT_MEMBERS)
+    /// }
+    /// ```
+    #[must_use]
+    pub fn admin_rights(&self) -> AdminRights {
+        match self {
+            Self::Owner(_) => AdminRights::all(),
+            Self::Administrator(admin) => admin.into(),
+            Self::Member(_) | Self::Restricted(_) | Self::Left | Self::Banned(_) => {
+                AdminRights::empty()
+            }
+        }
+    }
+}
+
+/// Methods for checking privileges which both administrators and restricted
+/// users may have.
+///
+/// Permissions of members which aren't restricted are the default permissions
+/// of the chat, which aren't known here, so these methods return `false` for
+/// them. Use [`ChatFullInfo::permissions`] to get the default permissions.
+///
+/// [`ChatFullInfo::permissions`]: crate::types::ChatFullInfo::permissions
+impl ChatMemberKind {
+    /// Returns `true` if the user can change the chat title, photo and other
+    /// settings.
+    ///
+    /// I.e. returns `true` if the user
+    /// - is the owner of the chat
+    /// - is an administrator or a restricted user with the `can_change_info`
+    ///   privilege.
+    ///
+    /// Returns `false` otherwise.
+    #[must_use]
+    pub fn can_change_info(&self) -> bool {
+        match self {
+            Self::Owner(_) => true,
+            Self::Administrator(Administrator { can_change_info, .. })
+            | Self::Restricted(Restricted { can_change_info, .. }) => *can_change_info,
+            Self::Member(_) | Self::Left | Self::Banned(_) => false,
+        }
+    }
+
+    /// Returns `true` if the user can invite new users to the chat.
+    ///
+    /// I.e. returns `true` if the user
+    /// - is the owner of the chat
+    /// - is an administrator or a restricted user with the `can_invite_users`
+    ///   privilege.
+    ///
+    /// Returns `false` otherwise.
+    #[must_use]
+    pub fn can_invite_users(&self) -> bool {
+        match self {
+            Self::Owner(_) => true,
+            Self::Administrator(Administrator { can_invite_users, .. })
+            | Self::Restricted(Restricted { can_invite_users, .. }) => *can_invite_users,
+            Self::Member(_) | Self::Left | Self::Banned(_) => false,
+        }
+    }
+
+    /// Returns `true` if the user can pin messages.
+    ///
+    /// I.e. returns `true` if the user
+    /// - is the owner of the chat
+    /// - is an administrator or a restricted user with the `can_pin_messages`
+    ///   privilege.
+    ///
+    /// Returns `false` otherwise.
+    #[must_use]
+    pub fn can_pin_messages(&self) -> bool {
+        match self {
+            Self::Owner(_) => true,
+            Self::Administrator(Administrator { can_pin_messages, .. })
+            | Self::Restricted(Restricted { can_pin_messages, .. }) => *can_pin_messages,
+            Self::Member(_) | Self::Left | Self::Banned(_) => false,
+        }
+    }
+
+    /// Returns `true` if the user can create, rename, close, and reopen forum
+    /// topics.
+    ///
+    /// I.e. returns `true` if the user
+    /// - is the owner of the chat
+    /// - is an administrator or a restricted user with the `can_manage_topics`
+    ///   privilege.
+    ///
+    /// Returns `false` otherwise.
+    #[must_use]
+    pub fn can_manage_topics(&self) -> bool {
+        match self {
+            Self::Owner(_) => true,
+            Self::Administrator(Administrator { can_manage_topics, .. })
+            | Self::Restricted(Restricted { can_manage_topics, .. }) => *can_manage_topics,
+            Self::Member(_) | Self::Left | Self::Banned(_) => false,
+        }
+    }
+
+    /// Returns the permissions of a restricted user.
+    ///
+    /// Returns `None` for other users: owners and administrators aren't
+    /// restricted by permissions, and other members have the default
+    /// permissions of the chat.
+    #[must_use]
+    pub fn permissions(&self) -> Option<ChatPermissions> {
+        let Self::Restricted(restricted) = self else {
+            return None;
+        };
+
+        let flags = [
+            (restricted.can_send_messages, ChatPermissions::SEND_MESSAGES),
+            (restricted.can_send_audios, ChatPermissions::SEND_AUDIOS),
+            (restricted.can_send_documents, ChatPermissions::SEND_DOCUMENTS),
+            (restricted.can_send_photos, ChatPermissions::SEND_PHOTOS),
+            (restricted.can_send_videos, ChatPermissions::SEND_VIDEOS),
+            (restricted.can_send_video_notes, ChatPermissions::SEND_VIDEO_NOTES),
+            (restricted.can_send_voice_notes, ChatPermissions::SEND_VOICE_NOTES),
+            (restricted.can_send_other_messages, ChatPermissions::SEND_OTHER_MESSAGES),
+            (restricted.can_add_web_page_previews, ChatPermissions::ADD_WEB_PAGE_PREVIEWS),
+            (restricted.can_change_info, ChatPermissions::CHANGE_INFO),
+            (restricted.can_invite_users, ChatPermissions::INVITE_USERS),
+            (restricted.can_pin_messages, ChatPermissions::PIN_MESSAGES),
+            (restricted.can_manage_topics, ChatPermissions::MANAGE_TOPICS),
+            (restricted.can_send_polls, ChatPermissions::SEND_POLLS),
+        ];
+
+        Some(flags.into_iter().filter(|&(set, _)| set).map(|(_, flag)| flag).collect())
+    }
+
+    /// Returns `true` if the status of the user is `status` or higher, see
+    /// [`ChatMemberStatus::is_at_least`].
+    #[must_use]
+    pub fn is_at_least(&self, status: ChatMemberStatus) -> bool {
+        self.status().is_at_least(status)
+    }
 }
 
 #[derive(Copy, Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
@@ -675,6 +824,36 @@ impl ChatMemberStatus {
     pub fn is_privileged(&self) -> bool {
         self.is_administrator() || self.is_owner()
     }
+
+    /// Returns `true` if this status is `other` or higher.
+    ///
+    /// Statuses are ordered from the highest to the lowest as follows: owner,
+    /// administrator, member, restricted, left, banned.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use teloxide_core::types::ChatMemberStatus;
+    ///
+    /// assert!(ChatMemberStatus::Owner.is_at_least(ChatMemberStatus::Administrator));
+    /// assert!(ChatMemberStatus::Member.is_at_least(ChatMemberStatus::Member));
+    /// assert!(!ChatMemberStatus::Restricted.is_at_least(ChatMemberStatus::Member));
+    /// ```
+    #[must_use]
+    pub fn is_at_least(&self, other: ChatMemberStatus) -> bool {
+        self.rank() >= other.rank()
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            Self::Banned => 0,
+            Self::Left => 1,
+            Self::Restricted => 2,
+            Self::Member => 3,
+            Self::Administrator => 4,
+            Self::Owner => 5,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -806,4 +985,75 @@ mod tests {
         let actual = serde_json::from_str::<ChatMember>(json).unwrap();
         assert_eq!(actual, expected)
     }
+
+    #[test]
+    fn rights() {
+        let admin = ChatMemberKind::Administrator(Administrator {
+            custom_title: None,
+            is_anonymous: false,
+            can_be_edited: false,
+            can_manage_chat: true,
+            can_change_info: false,
+            can_post_messages: false,
+            can_edit_messages: false,
+            can_delete_messages: true,
+            can_post_stories: false,
+            can_edit_stories: false,
+            can_delete_stories: false,
+            can_manage_video_chats: false,
+            can_invite_users: true,
+            can_restrict_members: true,
+            can_pin_messages: false,
+            can_manage_topics: false,
+            can_promote_members: false,
+        });
+        let rights = admin.admin_rights();
+        assert!(rights.contains(AdminRights::DELETE_MESSAGES | AdminRights::RESTRICT_MEMBERS));
+        assert!(!rights.contains(AdminRights::DELETE_MESSAGES | AdminRights::PIN_MESSAGES));
+        assert!(admin.can_invite_users());
+        assert!(!admin.can_change_info());
+        assert_eq!(admin.permissions(), None);
+        assert!(admin.is_at_least(ChatMemberStatus::Administrator));
+        assert!(!admin.is_at_least(ChatMemberStatus::Owner));
+
+        let owner = ChatMemberKind::Owner(Owner { custom_title: None, is_anonymous: false });
+        assert_eq!(owner.admin_rights(), AdminRights::all());
+        assert!(owner.can_pin_messages() && owner.can_manage_topics());
+        assert!(owner.is_at_least(ChatMemberStatus::Administrator));
+
+        let restricted = ChatMemberKind::Restricted(Restricted {
+            until_date: UntilDate::Forever,
+            is_member: true,
+            can_send_messages: true,
+            can_send_audios: false,
+            can_send_documents: false,
+            can_send_photos: false,
+            can_send_videos: false,
+            can_send_video_notes: false,
+            can_send_voice_notes: false,
+            can_send_other_messages: false,
+            can_add_web_page_previews: false,
+            can_change_info: false,
+            can_invite_users: false,
+            can_pin_messages: true,
+            can_manage_topics: false,
+            can_send_polls: true,
+        });
+        assert_eq!(restricted.admin_rights(), AdminRights::empty());
+        assert!(restricted.can_pin_messages());
+        assert!(!restricted.can_invite_users());
+        assert_eq!(
+            restricted.permissions(),
+            Some(
+                ChatPermissions::SEND_MESSAGES
+                    | ChatPermissions::SEND_POLLS
+                    | ChatPermissions::PIN_MESSAGES
+            )
+        );
+        assert!(!restricted.is_at_least(ChatMemberStatus::Member));
+        assert!(restricted.is_at_least(ChatMemberStatus::Left));
+
+        assert!(ChatMemberKind::Left.is_at_least(ChatMemberStatus::Banned));
+        assert!(!ChatMemberKind::Left.can_pin_messages());
+    }
 }
@@ -1,7 +1,9 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use crate::types::{Chat, ChatInviteLink, ChatMember, User};
+use crate::types::{
+    AdminRights, Chat, ChatInviteLink, ChatMember, ChatMemberKind, ChatPermissions, User,
+};
 
 /// This object represents changes in the status of a chat member.
 ///
@@ -60,4 +62,176 @@ impl ChatMemberUpdated {
         ]
         .into_iter()
     }
+
+    /// Returns `true` if the user has joined the chat (or was added to it),
+    /// i.e. wasn't [present] before the change and is present after it.
+    ///
+    /// [present]: ChatMemberKind::is_present
+    #[must_use]
+    pub fn is_join(&self) -> bool {
+        !self.old_chat_member.is_present() && self.new_chat_member.is_present()
+    }
+
+    /// Returns `true` if the user has left the chat, i.e. was [present] before
+    /// the change and isn't present after it.
+    ///
+    /// This includes the user being removed or banned, see
+    /// [`ChatMemberUpdated::is_ban`] to distinguish this case.
+    ///
+    /// [present]: ChatMemberKind::is_present
+    #[must_use]
+    pub fn is_leave(&self) -> bool {
+        self.old_chat_member.is_present() && !self.new_chat_member.is_present()
+    }
+
+    /// Returns `true` if the user was banned in the chat.
+    #[must_use]
+    pub fn is_ban(&self) -> bool {
+        !self.old_chat_member.is_banned() && self.new_chat_member.is_banned()
+    }
+
+    /// Returns `true` if the user was unbanned in the chat.
+    #[must_use]
+    pub fn is_unban(&self) -> bool {
+        self.old_chat_member.is_banned() && !self.new_chat_member.is_banned()
+    }
+
+    /// Returns `true` if the user has gained any administrator rights, e.g.
+    /// was made an administrator.
+    #[must_use]
+    pub fn is_promotion(&self) -> bool {
+        !self.newly_gained_admin_rights().is_empty()
+    }
+
+    /// Returns `true` if the user has lost any administrator rights, e.g. is
+    /// no longer an administrator.
+    #[must_use]
+    pub fn is_demotion(&self) -> bool {
+        !(self.old_chat_member.admin_rights() - self.new_chat_member.admin_rights()).is_empty()
+    }
+
+    /// Returns the administrator rights the user didn't have before the
+    /// change.
+    #[must_use]
+    pub fn newly_gained_admin_rights(&self) -> AdminRights {
+        self.new_chat_member.admin_rights() - self.old_chat_member.admin_rights()
+    }
+
+    /// Returns the permissions the user didn't have before the change.
+    ///
+    /// Users that are present in the chat and aren't restricted (including
+    /// administrators) are considered to have all permissions, even though
+    /// regular members are limited by the default permissions of the chat,
+    /// which aren't known here. Users that aren't present have no
+    /// permissions. So, for example, lifting restrictions of a user gains
+    /// them all permissions they were restricted from.
+    #[must_use]
+    pub fn newly_gained_permissions(&self) -> ChatPermissions {
+        permissions(&self.new_chat_member) - permissions(&self.old_chat_member)
+    }
+}
+
+fn permissions(member: &ChatMemberKind) -> ChatPermissions {
+    match member.permissions() {
+        Some(permissions) => permissions,
+        None if member.is_present() => ChatPermissions::all(),
+        None => ChatPermissions::empty(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update(old: serde_json::Value, new: serde_json::Value) -> ChatMemberUpdated {
+        let user = serde_json::json!({ "id": 2, "is_bot": false, "first_name": "B" });
+        let mut old = old;
+        let mut new = new;
+        old["user"] = user.clone();
+        new["user"] = user;
+
+        serde_json::from_value(serde_json::json!({
+            "chat": { "id": -1, "title": "G", "type": "supergroup" },
+            "from": { "id": 1, "is_bot": false, "first_name": "A" },
+            "date": 1,
+            "old_chat_member": old,
+            "new_chat_member": new,
+        }))
+        .unwrap()
+    }
+
+    fn restricted(can_send_messages: bool) -> serde_json::Value {
+        serde_json::json!({
+            "status": "restricted",
+            "until_date": 0,
+            "is_member": true,
+            "can_send_messages": can_send_messages,
+            "can_send_audios": false,
+            "can_send_documents": false,
+            "can_send_photos": true,
+            "can_send_videos": false,
+            "can_send_video_notes": false,
+            "can_send_voice_notes": false,
+            "can_send_other_messages": false,
+            "can_add_web_page_previews": false,
+            "can_change_info": false,
+            "can_invite_users": false,
+            "can_pin_messages": false,
+            "can_manage_topics": false,
+            "can_send_polls": false
+        })
+    }
+
+    fn admin(can_delete_messages: bool) -> serde_json::Value {
+        serde_json::json!({
+            "status": "administrator",
+            "can_be_edited": true,
+            "is_anonymous": false,
+            "can_manage_chat": true,
+            "can_delete_messages": can_delete_messages,
+            "can_manage_video_chats": false,
+            "can_restrict_members": false,
+            "can_promote_members": false,
+            "can_change_info": false,
+            "can_invite_users": false,
+            "can_post_stories": false,
+            "can_edit_stories": false,
+            "can_delete_stories": false
+        })
+    }
+
+    #[test]
+    fn classification() {
+        let left = || serde_json::json!({ "status": "left" });
+        let member = || serde_json::json!({ "status": "member" });
+        let banned = || serde_json::json!({ "status": "kicked", "until_date": 0 });
+
+        let join = update(left(), member());
+        assert!(join.is_join() && !join.is_leave() && !join.is_promotion());
+        assert_eq!(join.newly_gained_permissions(), ChatPermissions::all());
+
+        let ban = update(member(), banned());
+        assert!(ban.is_leave() && ban.is_ban() && !ban.is_join());
+        assert!(ban.newly_gained_permissions().is_empty());
+        assert!(update(banned(), left()).is_unban());
+
+        let promotion = update(member(), admin(false));
+        assert!(promotion.is_promotion() && !promotion.is_demotion() && !promotion.is_join());
+        assert_eq!(promotion.newly_gained_admin_rights(), AdminRights::MANAGE_CHAT);
+
+        let more_rights = update(admin(false), admin(true));
+        assert!(more_rights.is_promotion());
+        assert_eq!(more_rights.newly_gained_admin_rights(), AdminRights::DELETE_MESSAGES);
+        assert!(update(admin(true), member()).is_demotion());
+
+        let unmute = update(restricted(false), restricted(true));
+        assert_eq!(unmute.newly_gained_permissions(), ChatPermissions::SEND_MESSAGES);
+        assert!(!unmute.is_join() && !unmute.is_leave());
+
+        let unrestrict = update(restricted(false), member());
+        assert_eq!(
+            unrestrict.newly_gained_permissions(),
+            ChatPermissions::all() - ChatPermissions::SEND_PHOTOS
+        );
+    }
 }
@@ -100,6 +100,101 @@ bitflags::bitflags! {
     }
 }
 
+/// Presets, which can be adjusted with the methods of [`bitflags`] (e.g.
+/// [`union`] and [`difference`]).
+///
+/// [`bitflags`]: https://docs.rs/bitflags
+/// [`union`]: ChatPermissions::union
+/// [`difference`]: ChatPermissions::difference
+///
+/// ## Examples
+///
+/// ```
+/// use teloxide_core::types::ChatPermissions;
+///
+/// // Usual permissions without sending polls, but with pinning messages
+/// let permissions = ChatPermissions::standard_group()
+///     .difference(ChatPermissions::SEND_POLLS)
+///     .union(ChatPermissions::PIN_MESSAGES);
+///
+/// assert!(permissions.can_send_photos() && permissions.can_pin_messages());
+/// assert!(!permissions.can_send_polls() && !permissions.can_change_info());
+/// ```
+impl ChatPermissions {
+    /// Returns permissions of a user who can only read the chat, i.e. no
+    /// permissions.
+    ///
+    /// Pass it to [`RestrictChatMember`] to mute a user.
+    ///
+    /// [`RestrictChatMember`]: crate::payloads::RestrictChatMember
+    #[must_use]
+    pub const fn read_only() -> Self {
+        Self::empty()
+    }
+
+    /// Returns permissions usual for members of a group: sending all kinds of
+    /// messages, adding web page previews and inviting users.
+    ///
+    /// Changing the chat info, pinning messages and managing topics are left
+    /// to administrators.
+    #[must_use]
+    pub const fn standard_group() -> Self {
+        Self::SEND_MESSAGES
+            .union(Self::SEND_MEDIA_MESSAGES)
+            .union(Self::SEND_POLLS)
+            .union(Self::SEND_OTHER_MESSAGES)
+            .union(Self::ADD_WEB_PAGE_PREVIEWS)
+            .union(Self::INVITE_USERS)
+    }
+
+    /// Returns which permissions are granted and which are revoked when
+    /// changing these permissions to `other`.
+    ///
+    /// This is useful to describe a change of permissions, e.g. of the bot in
+    /// a `my_chat_member` handler, or before calling [`RestrictChatMember`].
+    ///
+    /// ```
+    /// use teloxide_core::types::ChatPermissions;
+    ///
+    /// let old = ChatPermissions::SEND_MESSAGES | ChatPermissions::SEND_POLLS;
+    /// let new = ChatPermissions::SEND_MESSAGES | ChatPermissions::SEND_PHOTOS;
+    /// let diff = old.diff(&new);
+    ///
+    /// assert_eq!(diff.granted, ChatPermissions::SEND_PHOTOS);
+    /// assert_eq!(diff.revoked, ChatPermissions::SEND_POLLS);
+    /// assert!(old.diff(&old).is_empty());
+    /// ```
+    ///
+    /// [`RestrictChatMember`]: crate::payloads::RestrictChatMember
+    #[must_use]
+    pub fn diff(&self, other: &Self) -> ChatPermissionsDiff {
+        ChatPermissionsDiff {
+            granted: other.clone().difference(self.clone()),
+            revoked: self.clone().difference(other.clone()),
+        }
+    }
+}
+
+/// A change of [`ChatPermissions`], returned by [`ChatPermissions::diff`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ChatPermissionsDiff {
+    /// Permissions which were not set before the change, but are set after
+    /// it.
+    pub granted: ChatPermissions,
+
+    /// Permissions which were set before the change, but are not set after
+    /// it.
+    pub revoked: ChatPermissions,
+}
+
+impl ChatPermissionsDiff {
+    /// Returns `true` if no permissions were changed.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.granted.is_empty() && self.revoked.is_empty()
+    }
+}
+
 impl ChatPermissions {
     /// Checks for [`SEND_MESSAGES`] permission.
     ///
@@ -371,6 +466,21 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn presets() {
+        assert_eq!(ChatPermissions::read_only(), ChatPermissions::empty());
+
+        let standard = ChatPermissions::standard_group();
+        assert!(standard.can_send_media_messages() && standard.can_invite_users());
+        assert_eq!(
+            ChatPermissions::all().diff(&standard).revoked,
+            ChatPermissions::CHANGE_INFO
+                | ChatPermissions::PIN_MESSAGES
+                | ChatPermissions::MANAGE_TOPICS
+        );
+        assert!(ChatPermissions::read_only().diff(&standard).revoked.is_empty());
+    }
+
     #[test]
     fn modfiy_permission() {
         let before = ChatPermissions::SEND_MESSAGES
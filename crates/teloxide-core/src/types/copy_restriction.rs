@@ -0,0 +1,37 @@
+use thiserror::Error;
+
+/// A reason why a message can't be copied or forwarded.
+///
+/// See [`Message::can_copy`] and [`Message::can_forward`].
+///
+/// [`Message::can_copy`]: crate::types::Message::can_copy
+/// [`Message::can_forward`]: crate::types::Message::can_forward
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Error)]
+pub enum CopyRestriction {
+    /// The message has protected content, see
+    /// [`Message::has_protected_content`].
+    ///
+    /// [`Message::has_protected_content`]: crate::types::Message::has_protected_content
+    #[error("the message has protected content")]
+    ProtectedContent,
+
+    /// The message is a service message (e.g. a pinned message notification).
+    #[error("service messages can't be copied or forwarded")]
+    ServiceMessage,
+
+    /// The message contains paid media.
+    #[error("paid media messages can't be copied")]
+    PaidMedia,
+
+    /// The message is a giveaway or a giveaway winners message.
+    #[error("giveaway messages can't be copied")]
+    Giveaway,
+
+    /// The message is an invoice.
+    #[error("invoice messages can't be copied")]
+    Invoice,
+
+    /// The message is a quiz whose correct option is not known to the bot.
+    #[error("a quiz can be copied only if its correct option is known to the bot")]
+    UnknownQuizAnswer,
+}
@@ -3,9 +3,9 @@ use futures::{
     future::{ready, Either},
     stream,
 };
-use once_cell::sync::OnceCell;
+use once_cell::sync::{Lazy, OnceCell};
 use rc_box::ArcBox;
-use reqwest::{multipart::Part, Body};
+use reqwest::{header::HeaderMap, multipart::Part, Body};
 use serde::Serialize;
 use takecell::TakeCell;
 use tokio::{
@@ -21,12 +21,18 @@ use std::{
 
 use crate::types::{self, InputSticker};
 
+/// The client used to download files created with
+/// [`InputFile::url_with_headers`].
+static FETCH_CLIENT: Lazy<reqwest::Client> = Lazy::new(crate::net::client_from_env);
+
 /// This object represents the contents of a file to be uploaded.
 ///
 /// [The official docs](https://core.telegram.org/bots/api#inputfile).
 #[derive(Debug, Clone)]
 pub struct InputFile {
-    id: OnceCell<Arc<str>>,
+    /// Shared between clones, so that a file used multiple times in a request
+    /// is uploaded once.
+    id: Arc<OnceCell<Arc<str>>>,
     file_name: Option<Cow<'static, str>>,
     inner: InnerFile,
 }
@@ -36,6 +42,8 @@ enum InnerFile {
     Read(Read),
     File(PathBuf),
     Bytes(bytes::Bytes),
+    // Boxed to keep `InputFile` small
+    Fetch(Box<(url::Url, HeaderMap)>),
     Url(url::Url),
     FileId(types::FileId),
 }
@@ -93,23 +101,62 @@ impl InputFile {
     }
 
     /// Set the file name for this file.
+    ///
+    /// Note that this makes the file distinct from its previous clones, so
+    /// they are uploaded separately if used in the same request.
     pub fn file_name(mut self, name: impl Into<Cow<'static, str>>) -> Self {
         self.file_name = Some(name.into());
+        self.id = Arc::default();
         self
     }
 
     /// Creates an `InputFile` from a in-memory bytes.
     ///
-    /// Note: in some cases (e.g. sending the same `InputFile` multiple times)
-    /// this may read the whole `impl AsyncRead` into memory.
+    /// Note: in some cases (e.g. sending the same `InputFile` in multiple
+    /// requests, or sending a request by reference) this may read the whole
+    /// `impl AsyncRead` into memory. Using clones of the file multiple times
+    /// in the same request doesn't, since it's uploaded once.
     pub fn read(it: impl AsyncRead + Send + Unpin + 'static) -> Self {
-        Self::new(Read(Read::new(Arc::new(TakeCell::new(it)))))
+        Self::new(Read(Read::new(Arc::new(TakeCell::new(it)), None)))
+    }
+
+    /// Creates an `InputFile` from an async reader of a known length, e.g. a
+    /// body of an HTTP response or an object from a cloud storage.
+    ///
+    /// Unlike [`InputFile::read`], this sets the file name and the length of
+    /// the file, which is then sent as the `Content-Length` of the upload.
+    /// `len` must match the number of bytes produced by `it`, otherwise the
+    /// request fails.
+    pub fn read_sized(
+        it: impl AsyncRead + Send + Unpin + 'static,
+        file_name: impl Into<Cow<'static, str>>,
+        len: u64,
+    ) -> Self {
+        Self::new(Read(Read::new(Arc::new(TakeCell::new(it)), Some(len)))).file_name(file_name)
+    }
+
+    /// Creates an `InputFile` that is downloaded from `url` by the bot and then
+    /// uploaded to Telegram, sending `headers` with the download request.
+    ///
+    /// Unlike [`InputFile::url`], which makes Telegram download the file, this
+    /// allows to send files that require authorization (e.g. with an
+    /// `Authorization` header), or that Telegram can't access. The download
+    /// is streamed to Telegram, without being buffered in memory.
+    ///
+    /// The download respects the `TELOXIDE_PROXY` environment variable, like
+    /// [`client_from_env`]. If it fails, the request to Telegram fails with
+    /// [`RequestError::Network`].
+    ///
+    /// [`client_from_env`]: crate::net::client_from_env
+    /// [`RequestError::Network`]: crate::RequestError::Network
+    pub fn url_with_headers(url: url::Url, headers: HeaderMap) -> Self {
+        Self::new(Fetch(Box::new((url, headers))))
     }
 
     /// Shorthand for `Self { file_name: None, inner, id: default() }`
     /// (private because `InnerFile` is private implementation detail)
     fn new(inner: InnerFile) -> Self {
-        Self { file_name: None, inner, id: OnceCell::new() }
+        Self { file_name: None, inner, id: Arc::default() }
     }
 
     /// Returns id of this file.
@@ -163,6 +210,10 @@ impl InputFile {
                 Some(name) => Cow::Owned(name.to_string_lossy().into_owned()),
                 None => Cow::Borrowed(""),
             },
+            Fetch(fetch) => match fetch.0.path_segments().and_then(Iterator::last) {
+                Some(name) => Cow::Owned(name.to_owned()),
+                None => Cow::Borrowed(""),
+            },
             _ => Cow::Borrowed(""),
         })
     }
@@ -175,6 +226,8 @@ impl fmt::Debug for InnerFile {
             File(path) => f.debug_struct("File").field("path", path).finish(),
             Bytes(bytes) if f.alternate() => f.debug_tuple("Memory").field(bytes).finish(),
             Bytes(_) => f.debug_struct("Memory").finish_non_exhaustive(),
+            // Headers are omitted, since they likely contain credentials
+            Fetch(fetch) => f.debug_struct("Fetch").field("url", &fetch.0).finish_non_exhaustive(),
             Url(url) => f.debug_tuple("Url").field(url).finish(),
             FileId(file_id) => f.debug_tuple("FileId").field(file_id).finish(),
         }
@@ -224,7 +277,38 @@ impl InputFile {
                 let stream = Part::stream(data).file_name(filename);
                 Some(Either::Right(Either::Left(ready(stream))))
             }
-            Read(read) => Some(Either::Right(Either::Right(read.into_part(filename)))),
+            Fetch(fetch) => {
+                let (url, headers) = *fetch;
+                let fut = async move {
+                    let response = FETCH_CLIENT
+                        .get(url)
+                        .headers(headers)
+                        .send()
+                        .await
+                        .and_then(reqwest::Response::error_for_status);
+
+                    match response {
+                        Ok(response) => {
+                            let len = response.content_length();
+                            let body = Body::wrap_stream(response.bytes_stream());
+                            match len {
+                                Some(len) => Part::stream_with_length(body, len),
+                                None => Part::stream(body),
+                            }
+                        }
+                        Err(err) => {
+                            let err = Err::<Bytes, _>(err);
+                            Part::stream(Body::wrap_stream(stream::iter([err])))
+                        }
+                    }
+                    .file_name(filename)
+                };
+
+                Some(Either::Right(Either::Right(Either::Left(fut))))
+            }
+            Read(read) => {
+                Some(Either::Right(Either::Right(Either::Right(read.into_part(filename)))))
+            }
         }
     }
 }
@@ -234,19 +318,26 @@ impl InputFile {
 #[derive(Clone)]
 struct Read {
     inner: Arc<TakeCell<dyn AsyncRead + Send + Unpin>>,
+    len: Option<u64>,
     buf: Arc<OnceCell<Result<Vec<Bytes>, Arc<io::Error>>>>,
     notify: Arc<watch::Sender<()>>,
     wait: watch::Receiver<()>,
 }
 
 impl Read {
-    fn new(it: Arc<TakeCell<dyn AsyncRead + Send + Unpin>>) -> Self {
+    fn new(it: Arc<TakeCell<dyn AsyncRead + Send + Unpin>>, len: Option<u64>) -> Self {
         let (tx, rx) = watch::channel(());
 
-        Self { inner: it, buf: Arc::default(), notify: Arc::new(tx), wait: rx }
+        Self { inner: it, len, buf: Arc::default(), notify: Arc::new(tx), wait: rx }
     }
 
     pub(crate) async fn into_part(mut self, filename: Cow<'static, str>) -> Part {
+        let len = self.len;
+        let part = |body| match len {
+            Some(len) => Part::stream_with_length(body, len),
+            None => Part::stream(body),
+        };
+
         if !self.inner.is_taken() {
             let res = ArcBox::<TakeCell<dyn AsyncRead + Send + Unpin>>::try_from(self.inner);
             match res {
@@ -256,7 +347,7 @@ impl Read {
                     let fr = FramedRead::new(ExclusiveArcAsyncRead(arc_box), BytesDecoder);
 
                     let body = Body::wrap_stream(fr);
-                    return Part::stream(body).file_name(filename);
+                    return part(body).file_name(filename);
                 }
                 // move the arc back into `self`
                 Err(i) => self.inner = i,
@@ -267,7 +358,7 @@ impl Read {
         // a buffer, or be the one who reads
         let body = self.into_shared_body().await;
 
-        Part::stream(body).file_name(filename)
+        part(body).file_name(filename)
     }
 
     async fn into_shared_body(mut self) -> Body {
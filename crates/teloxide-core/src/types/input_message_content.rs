@@ -1,5 +1,6 @@
 use reqwest::Url;
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
 use crate::types::{LabeledPrice, LinkPreviewOptions, LivePeriod, MessageEntity, ParseMode};
 
@@ -16,6 +17,136 @@ pub enum InputMessageContent {
     Contact(InputMessageContentContact),
     Invoice(InputMessageContentInvoice),
 }
+
+impl InputMessageContent {
+    /// Checks the limits documented by Telegram, so that mistakes are caught
+    /// before answering an inline query.
+    ///
+    /// The length of a text with a `parse_mode` is not checked, since it's
+    /// only known after Telegram parses the markup.
+    pub fn validate(&self) -> Result<(), InputMessageContentError> {
+        use InputMessageContentError::*;
+
+        match self {
+            Self::Text(text) => {
+                let len = text.message_text.chars().count();
+                if text.parse_mode.is_none() && !(1..=4096).contains(&len) {
+                    return Err(TextLength(len));
+                }
+            }
+            Self::Location(InputMessageContentLocation {
+                horizontal_accuracy,
+                heading,
+                proximity_alert_radius,
+                ..
+            }) => {
+                if let Some(accuracy) = *horizontal_accuracy {
+                    if !(0.0..=1500.0).contains(&accuracy) {
+                        return Err(HorizontalAccuracy(accuracy));
+                    }
+                }
+                if let Some(heading) = *heading {
+                    if !(1..=360).contains(&heading) {
+                        return Err(Heading(heading));
+                    }
+                }
+                if let Some(radius) = *proximity_alert_radius {
+                    if !(1..=100_000).contains(&radius) {
+                        return Err(ProximityAlertRadius(radius));
+                    }
+                }
+            }
+            Self::Invoice(invoice) => {
+                let len = invoice.title.chars().count();
+                if !(1..=32).contains(&len) {
+                    return Err(InvoiceTitleLength(len));
+                }
+                let len = invoice.description.chars().count();
+                if !(1..=255).contains(&len) {
+                    return Err(InvoiceDescriptionLength(len));
+                }
+                let len = invoice.payload.len();
+                if !(1..=128).contains(&len) {
+                    return Err(InvoicePayloadLength(len));
+                }
+                if invoice.prices.is_empty() {
+                    return Err(NoPrices);
+                }
+            }
+            Self::Venue(_) | Self::Contact(_) => {}
+        }
+
+        Ok(())
+    }
+}
+
+impl From<InputMessageContentText> for InputMessageContent {
+    fn from(content: InputMessageContentText) -> Self {
+        Self::Text(content)
+    }
+}
+
+impl From<InputMessageContentLocation> for InputMessageContent {
+    fn from(content: InputMessageContentLocation) -> Self {
+        Self::Location(content)
+    }
+}
+
+impl From<InputMessageContentVenue> for InputMessageContent {
+    fn from(content: InputMessageContentVenue) -> Self {
+        Self::Venue(content)
+    }
+}
+
+impl From<InputMessageContentContact> for InputMessageContent {
+    fn from(content: InputMessageContentContact) -> Self {
+        Self::Contact(content)
+    }
+}
+
+impl From<InputMessageContentInvoice> for InputMessageContent {
+    fn from(content: InputMessageContentInvoice) -> Self {
+        Self::Invoice(content)
+    }
+}
+
+/// An error returned from [`InputMessageContent::validate`].
+#[derive(Clone, Debug, PartialEq, Error)]
+#[non_exhaustive]
+pub enum InputMessageContentError {
+    /// The message text is not 1-4096 characters long.
+    #[error("message text must be 1-4096 characters long, got {0}")]
+    TextLength(usize),
+
+    /// The horizontal accuracy of a location is not between 0 and 1500 meters.
+    #[error("horizontal accuracy must be between 0 and 1500 meters, got {0}")]
+    HorizontalAccuracy(f64),
+
+    /// The heading of a live location is not between 1 and 360 degrees.
+    #[error("heading must be between 1 and 360 degrees, got {0}")]
+    Heading(u16),
+
+    /// The proximity alert radius is not between 1 and 100000 meters.
+    #[error("proximity alert radius must be between 1 and 100000 meters, got {0}")]
+    ProximityAlertRadius(u32),
+
+    /// The invoice title is not 1-32 characters long.
+    #[error("invoice title must be 1-32 characters long, got {0}")]
+    InvoiceTitleLength(usize),
+
+    /// The invoice description is not 1-255 characters long.
+    #[error("invoice description must be 1-255 characters long, got {0}")]
+    InvoiceDescriptionLength(usize),
+
+    /// The invoice payload is not 1-128 bytes long.
+    #[error("invoice payload must be 1-128 bytes long, got {0}")]
+    InvoicePayloadLength(usize),
+
+    /// The invoice has no prices.
+    #[error("invoice must have at least one price")]
+    NoPrices,
+}
+
 /// Represents the content of a text message to be sent as the result of an
 /// inline query.
 #[serde_with::skip_serializing_none]
@@ -80,6 +211,12 @@ impl InputMessageContentText {
         self.link_preview_options = Some(val);
         self
     }
+
+    /// Disables link previews for the message.
+    #[must_use]
+    pub fn disable_link_preview(self) -> Self {
+        self.link_preview_options(LinkPreviewOptions::disabled())
+    }
 }
 
 /// Represents the content of a location message to be sent as the result of an
@@ -141,6 +278,24 @@ impl InputMessageContentLocation {
         self.live_period = Some(val);
         self
     }
+
+    #[must_use]
+    pub const fn horizontal_accuracy(mut self, val: f64) -> Self {
+        self.horizontal_accuracy = Some(val);
+        self
+    }
+
+    #[must_use]
+    pub const fn heading(mut self, val: u16) -> Self {
+        self.heading = Some(val);
+        self
+    }
+
+    #[must_use]
+    pub const fn proximity_alert_radius(mut self, val: u32) -> Self {
+        self.proximity_alert_radius = Some(val);
+        self
+    }
 }
 
 /// Represents the content of a venue message to be sent as the result of
@@ -238,6 +393,22 @@ impl InputMessageContentVenue {
         self.foursquare_type = Some(val.into());
         self
     }
+
+    pub fn google_place_id<S>(mut self, val: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.google_place_id = Some(val.into());
+        self
+    }
+
+    pub fn google_place_type<S>(mut self, val: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.google_place_type = Some(val.into());
+        self
+    }
 }
 
 /// Represents the content of a contact message to be sent as the result of
@@ -656,4 +827,40 @@ mod tests {
         let actual_json = serde_json::to_string(&contact_content).unwrap();
         assert_eq!(expected_json, actual_json);
     }
+
+    #[test]
+    fn builders() {
+        let content: InputMessageContent =
+            InputMessageContentText::new("https://example.com").disable_link_preview().into();
+        assert_eq!(
+            serde_json::to_string(&content).unwrap(),
+            r#"{"message_text":"https://example.com","link_preview_options":{"is_disabled":true}}"#
+        );
+        assert_eq!(content.validate(), Ok(()));
+
+        let content: InputMessageContent =
+            InputMessageContentLocation::new(59.08, 38.4326).heading(361).into();
+        assert_eq!(content.validate(), Err(InputMessageContentError::Heading(361)));
+    }
+
+    #[test]
+    fn validate() {
+        use InputMessageContentError::*;
+
+        let text = InputMessageContentText::new("");
+        assert_eq!(InputMessageContent::from(text.clone()).validate(), Err(TextLength(0)));
+        let text = text.parse_mode(ParseMode::Html);
+        assert_eq!(InputMessageContent::from(text).validate(), Ok(()));
+
+        let invoice = InputMessageContentInvoice::new::<_, _, _, String, _, _>(
+            "Title",
+            "Description",
+            "payload",
+            "XTR",
+            [],
+        );
+        assert_eq!(InputMessageContent::from(invoice.clone()).validate(), Err(NoPrices));
+        let invoice = invoice.title("a".repeat(33));
+        assert_eq!(InputMessageContent::from(invoice).validate(), Err(InvoiceTitleLength(33)));
+    }
 }
@@ -24,33 +24,41 @@ impl From<InputPaidMedia> for InputFile {
     }
 }
 
+impl From<InputPaidMediaPhoto> for InputPaidMedia {
+    fn from(photo: InputPaidMediaPhoto) -> Self {
+        Self::Photo(photo)
+    }
+}
+
+impl From<InputPaidMediaVideo> for InputPaidMedia {
+    fn from(video: InputPaidMediaVideo) -> Self {
+        Self::Video(Box::new(video))
+    }
+}
+
 impl InputPaidMedia {
     /// Returns an iterator of all files in this input media
     pub(crate) fn files(&self) -> impl Iterator<Item = &InputFile> {
         use InputPaidMedia::*;
 
-        let (media, thumbnail) = match self {
-            Photo(InputPaidMediaPhoto { media, .. }) => (media, None),
-            Video(input_paid_media_video) => {
-                (&input_paid_media_video.media, input_paid_media_video.thumbnail.as_ref())
-            }
+        let (media, thumbnail, cover) = match self {
+            Photo(InputPaidMediaPhoto { media, .. }) => (media, None, None),
+            Video(video) => (&video.media, video.thumbnail.as_ref(), video.cover.as_ref()),
         };
 
-        iter::once(media).chain(thumbnail)
+        iter::once(media).chain(thumbnail).chain(cover)
     }
 
     /// Returns an iterator of all files in this input media
     pub(crate) fn files_mut(&mut self) -> impl Iterator<Item = &mut InputFile> {
         use InputPaidMedia::*;
 
-        let (media, thumbnail) = match self {
-            Photo(InputPaidMediaPhoto { media, .. }) => (media, None),
-            Video(input_paid_media_video) => {
-                (&mut input_paid_media_video.media, input_paid_media_video.thumbnail.as_mut())
-            }
+        let (media, thumbnail, cover) = match self {
+            Photo(InputPaidMediaPhoto { media, .. }) => (media, None, None),
+            Video(video) => (&mut video.media, video.thumbnail.as_mut(), video.cover.as_mut()),
         };
 
-        iter::once(media).chain(thumbnail)
+        iter::once(media).chain(thumbnail).chain(cover)
     }
 }
 
@@ -240,4 +248,14 @@ mod tests {
         let actual_json = serde_json::to_string(&video).unwrap();
         assert_eq!(expected_json, actual_json);
     }
+
+    #[test]
+    fn video_cover_is_a_file() {
+        let video: InputPaidMedia = InputPaidMediaVideo::new(InputFile::file_id("1".into()))
+            .thumbnail(InputFile::file_id("2".into()))
+            .cover(InputFile::file_id("3".into()))
+            .into();
+
+        assert_eq!(video.files().count(), 3);
+    }
 }
@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 
 /// Describes the options used for link preview generation.
 #[serde_with::skip_serializing_none]
-#[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Hash, Eq, PartialEq, Serialize, Deserialize)]
 pub struct LinkPreviewOptions {
     /// `true`, if the link preview is disabled
     #[serde(default, skip_serializing_if = "std::ops::Not::not")]
@@ -30,6 +30,53 @@ pub struct LinkPreviewOptions {
     pub show_above_text: bool,
 }
 
+impl LinkPreviewOptions {
+    /// Creates options with all fields set to their defaults, i.e. a preview of
+    /// the first URL in the message shown below the text.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates options that disable the link preview.
+    #[must_use]
+    pub fn disabled() -> Self {
+        Self { is_disabled: true, ..Self::default() }
+    }
+
+    #[must_use]
+    pub fn is_disabled(mut self, val: bool) -> Self {
+        self.is_disabled = val;
+        self
+    }
+
+    pub fn url<S>(mut self, val: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.url = Some(val.into());
+        self
+    }
+
+    #[must_use]
+    pub fn prefer_small_media(mut self, val: bool) -> Self {
+        self.prefer_small_media = val;
+        self
+    }
+
+    #[must_use]
+    pub fn prefer_large_media(mut self, val: bool) -> Self {
+        self.prefer_large_media = val;
+        self
+    }
+
+    #[must_use]
+    pub fn show_above_text(mut self, val: bool) -> Self {
+        self.show_above_text = val;
+        self
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
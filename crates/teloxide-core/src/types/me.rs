@@ -2,7 +2,7 @@ use std::ops::Deref;
 
 use serde::{Deserialize, Serialize};
 
-use crate::types::User;
+use crate::types::{Message, MessageEntityKind, User};
 
 /// Returned only in [`GetMe`].
 ///
@@ -51,6 +51,86 @@ impl Me {
     pub fn tme_url(&self) -> reqwest::Url {
         format!("https://t.me/{}", self.username()).parse().unwrap()
     }
+
+    /// Returns a mention of `command` addressed to this bot, e.g.
+    /// `/start@<username>`.
+    ///
+    /// `command` may be passed with or without the leading `/`.
+    #[must_use]
+    pub fn mention_command(&self, command: &str) -> String {
+        format!("/{}@{}", command.strip_prefix('/').unwrap_or(command), self.username())
+    }
+
+    /// Returns a [deep link] that starts this bot with `payload`, in the form
+    /// of `t.me/<...>?start=<payload>`.
+    ///
+    /// The bot receives the payload as the argument of the `/start` command.
+    /// Note that Telegram only allows up to 64 characters `A-Z`, `a-z`, `0-9`,
+    /// `_` and `-` in the payload.
+    ///
+    /// [deep link]: https://core.telegram.org/bots/features#deep-linking
+    #[must_use]
+    pub fn tme_url_with_start(&self, payload: &str) -> reqwest::Url {
+        let mut url = self.tme_url();
+        url.query_pairs_mut().append_pair("start", payload);
+        url
+    }
+
+    /// Returns `true` if this bot is mentioned in the text or the caption of
+    /// `message`, either by its username (`@<username>`) or by a text
+    /// mention.
+    ///
+    /// Commands addressed to this bot (e.g. `/start@<username>`) and replies
+    /// to its messages aren't considered mentions.
+    #[must_use]
+    pub fn is_bot_mentioned(&self, message: &Message) -> bool {
+        let entities = message.parse_entities().into_iter().chain(message.parse_caption_entities());
+
+        entities.flatten().any(|entity| match entity.kind() {
+            MessageEntityKind::Mention => entity
+                .text()
+                .strip_prefix('@')
+                .is_some_and(|username| username.eq_ignore_ascii_case(self.username())),
+            MessageEntityKind::TextMention { user } => user.id == self.id,
+            _ => false,
+        })
+    }
+
+    /// Strips a mention of this bot from the start of `text`, along with a
+    /// following `,` or `:` and whitespace.
+    ///
+    /// Returns `None` if `text` doesn't start with the mention.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # let me: teloxide_core::types::Me = serde_json::from_str(r#"{"id": 1, "is_bot": true,
+    /// #     "first_name": "Bot", "username": "my_bot", "can_join_groups": true,
+    /// #     "can_read_all_group_messages": false, "supports_inline_queries": false,
+    /// #     "has_main_web_app": false}"#).unwrap();
+    /// assert_eq!(me.strip_mention("@my_bot, what's the weather?"), Some("what's the weather?"));
+    /// assert_eq!(me.strip_mention("@My_Bot"), Some(""));
+    /// assert_eq!(me.strip_mention("@my_bot_2 hi"), None);
+    /// assert_eq!(me.strip_mention("hi @my_bot"), None);
+    /// ```
+    #[must_use]
+    pub fn strip_mention<'a>(&self, text: &'a str) -> Option<&'a str> {
+        let username = self.username();
+
+        let rest = text.trim_start().strip_prefix('@')?;
+        if !rest.get(..username.len())?.eq_ignore_ascii_case(username) {
+            return None;
+        }
+
+        // Usernames consist of `A-Z`, `a-z`, `0-9` and `_`, so the mention
+        // must end here
+        let rest = &rest[username.len()..];
+        if rest.starts_with(|c: char| c.is_ascii_alphanumeric() || c == '_') {
+            return None;
+        }
+
+        Some(rest.trim_start_matches([',', ':']).trim_start())
+    }
 }
 
 impl Deref for Me {
@@ -63,7 +143,7 @@ impl Deref for Me {
 
 #[cfg(test)]
 mod tests {
-    use crate::types::{Me, User, UserId};
+    use crate::types::{Me, Message, User, UserId};
 
     #[test]
     fn convenience_methods_work() {
@@ -88,5 +168,44 @@ mod tests {
         assert_eq!(me.username(), "SomethingSomethingBot");
         assert_eq!(me.mention(), "@SomethingSomethingBot");
         assert_eq!(me.tme_url(), "https://t.me/SomethingSomethingBot".parse().unwrap());
+        assert_eq!(me.mention_command("start"), "/start@SomethingSomethingBot");
+        assert_eq!(me.mention_command("/help"), "/help@SomethingSomethingBot");
+        assert_eq!(
+            me.tme_url_with_start("-100123_abc"),
+            "https://t.me/SomethingSomethingBot?start=-100123_abc".parse().unwrap()
+        );
+    }
+
+    #[test]
+    fn mentions() {
+        let me = crate::test_utils::me();
+        let message = |text: &str, entities: serde_json::Value| -> Message {
+            serde_json::from_value(serde_json::json!({
+                "message_id": 1,
+                "date": 0,
+                "chat": {"id": -1, "type": "group", "title": "G"},
+                "text": text,
+                "entities": entities
+            }))
+            .unwrap()
+        };
+
+        let mention =
+            |length| serde_json::json!([{"type": "mention", "offset": 3, "length": length}]);
+        assert!(me.is_bot_mentioned(&message("hi @Bot", mention(4))));
+        assert!(!me.is_bot_mentioned(&message("hi @bot_2", mention(6))));
+        assert!(!me.is_bot_mentioned(&message("hi @bot", serde_json::json!([]))));
+
+        let text_mention = |id| {
+            serde_json::json!([{"type": "text_mention", "offset": 0, "length": 3,
+                "user": {"id": id, "is_bot": true, "first_name": "Bot"}}])
+        };
+        assert!(me.is_bot_mentioned(&message("Bot, hi", text_mention(42))));
+        assert!(!me.is_bot_mentioned(&message("Bob, hi", text_mention(43))));
+
+        assert_eq!(me.strip_mention("  @bot: hi"), Some("hi"));
+        assert_eq!(me.strip_mention("@bot!"), Some("!"));
+        assert_eq!(me.strip_mention("@bo"), None);
+        assert_eq!(me.strip_mention("@бот"), None);
     }
 }
@@ -1,22 +1,25 @@
 #![allow(clippy::large_enum_variant)]
 
+use std::fmt;
+
 use chrono::{DateTime, Utc};
 use derive_more::derive::From;
 use serde::{Deserialize, Serialize};
 use url::Url;
 
 use crate::types::{
-    Animation, Audio, BareChatId, BusinessConnectionId, Chat, ChatBackground, ChatBoostAdded,
-    ChatId, ChatShared, Checklist, ChecklistTasksAdded, ChecklistTasksDone, Contact, Dice,
-    DirectMessagePriceChanged, Document, ExternalReplyInfo, ForumTopicClosed, ForumTopicCreated,
-    ForumTopicEdited, ForumTopicReopened, Game, GeneralForumTopicHidden, GeneralForumTopicUnhidden,
-    GiftInfo, Giveaway, GiveawayCompleted, GiveawayCreated, GiveawayWinners, InlineKeyboardMarkup,
-    Invoice, LinkPreviewOptions, Location, MaybeInaccessibleMessage, MessageAutoDeleteTimerChanged,
-    MessageEntity, MessageEntityRef, MessageId, MessageOrigin, PaidMediaInfo,
-    PaidMessagePriceChanged, PassportData, PhotoSize, Poll, ProximityAlertTriggered,
-    RefundedPayment, Sticker, Story, SuccessfulPayment, TextQuote, ThreadId, True, UniqueGiftInfo,
-    User, UsersShared, Venue, Video, VideoChatEnded, VideoChatParticipantsInvited,
-    VideoChatScheduled, VideoChatStarted, VideoNote, Voice, WebAppData, WriteAccessAllowed,
+    compact_display, Animation, Audio, BareChatId, BusinessConnectionId, Chat, ChatBackground,
+    ChatBoostAdded, ChatId, ChatShared, Checklist, ChecklistTasksAdded, ChecklistTasksDone,
+    Contact, CopyRestriction, Dice, DirectMessagePriceChanged, Document, ExternalReplyInfo,
+    ForumTopicClosed, ForumTopicCreated, ForumTopicEdited, ForumTopicReopened, Game,
+    GeneralForumTopicHidden, GeneralForumTopicUnhidden, GiftInfo, Giveaway, GiveawayCompleted,
+    GiveawayCreated, GiveawayWinners, InlineKeyboardMarkup, Invoice, LinkPreviewOptions, Location,
+    MaybeInaccessibleMessage, MessageAutoDeleteTimerChanged, MessageEntity, MessageEntityRef,
+    MessageId, MessageOrigin, PaidMediaInfo, PaidMessagePriceChanged, PassportData, PhotoSize,
+    Poll, PollType, ProximityAlertTriggered, RefundedPayment, ReplyParameters, Seconds, Sticker,
+    Story, SuccessfulPayment, TextQuote, ThreadId, True, UniqueGiftInfo, User, UsersShared, Venue,
+    Video, VideoChatEnded, VideoChatParticipantsInvited, VideoChatScheduled, VideoChatStarted,
+    VideoNote, Voice, WebAppData, WriteAccessAllowed,
 };
 
 /// This object represents a message.
@@ -1868,6 +1871,16 @@ mod getters {
             }
         }
 
+        #[must_use]
+        pub fn business_connection_id(&self) -> Option<&types::BusinessConnectionId> {
+            match &self.kind {
+                Common(MessageCommon { business_connection_id, .. }) => {
+                    business_connection_id.as_ref()
+                }
+                _ => None,
+            }
+        }
+
         /// Common message (text, image, etc)
         fn common(&self) -> Option<&MessageCommon> {
             match &self.kind {
@@ -2061,6 +2074,93 @@ impl Message {
         self.caption().zip(self.caption_entities()).map(|(t, e)| MessageEntityRef::parse(t, e))
     }
 
+    /// Returns [`ReplyParameters`] that point to this message.
+    ///
+    /// Unlike `ReplyParameters::new(message.id)`, the returned parameters also
+    /// specify the chat of this message, so they can be used to reply to it
+    /// from a different chat. Use [`ReplyParameters::quote`] to quote a part
+    /// of this message.
+    #[must_use]
+    pub fn reply_parameters(&self) -> ReplyParameters {
+        ReplyParameters::new(self.id).chat_id(self.chat.id.into())
+    }
+
+    /// Returns the id of the forum topic this message was sent to.
+    ///
+    /// Unlike [`Message::thread_id`], this is `None` for replies in
+    /// supergroups without topics and for messages of the "General" topic, so
+    /// the result can be passed to forum topic methods such as
+    /// [`CloseForumTopic`] directly.
+    ///
+    /// [`CloseForumTopic`]: crate::payloads::CloseForumTopic
+    #[must_use]
+    pub fn forum_topic_id(&self) -> Option<ThreadId> {
+        self.thread_id.filter(|_| self.is_topic_message)
+    }
+
+    /// Returns the date when this message will be automatically deleted, if it
+    /// was sent to a chat with the auto-delete `timer`.
+    ///
+    /// This is useful to avoid editing or replying to messages that no longer
+    /// exist. The timer can be obtained from
+    /// [`ChatFullInfo::auto_delete_timer`] or
+    /// [`MessageAutoDeleteTimerChanged::auto_delete_timer`].
+    ///
+    /// [`ChatFullInfo::auto_delete_timer`]: crate::types::ChatFullInfo::auto_delete_timer
+    #[must_use]
+    pub fn auto_delete_date(&self, timer: Seconds) -> DateTime<Utc> {
+        self.date + timer.chrono_duration()
+    }
+
+    /// Checks whether this message can be forwarded with [`ForwardMessage`].
+    ///
+    /// Service messages and messages with protected content can't be
+    /// forwarded.
+    ///
+    /// [`ForwardMessage`]: crate::payloads::ForwardMessage
+    pub fn can_forward(&self) -> Result<(), CopyRestriction> {
+        use MessageKind::*;
+
+        match &self.kind {
+            Common(MessageCommon { has_protected_content: true, .. }) => {
+                Err(CopyRestriction::ProtectedContent)
+            }
+            Common(MessageCommon { media_kind: MediaKind::Migration(_), .. }) => {
+                Err(CopyRestriction::ServiceMessage)
+            }
+            Common(_) | Dice(_) | Invoice(_) | Giveaway(_) | GiveawayWinners(_) => Ok(()),
+            _ => Err(CopyRestriction::ServiceMessage),
+        }
+    }
+
+    /// Checks whether this message can be copied with [`CopyMessage`].
+    ///
+    /// In addition to the restrictions of [`can_forward`], paid media,
+    /// giveaway, giveaway winners and invoice messages can't be copied, and a
+    /// quiz can be copied only if its correct option is known to the bot.
+    ///
+    /// [`CopyMessage`]: crate::payloads::CopyMessage
+    /// [`can_forward`]: Message::can_forward
+    pub fn can_copy(&self) -> Result<(), CopyRestriction> {
+        use MessageKind::*;
+
+        self.can_forward()?;
+
+        match &self.kind {
+            Common(MessageCommon { media_kind: MediaKind::PaidMedia(_), .. }) => {
+                Err(CopyRestriction::PaidMedia)
+            }
+            Common(MessageCommon { media_kind: MediaKind::Poll(MediaPoll { poll }), .. })
+                if poll.poll_type == PollType::Quiz && poll.correct_option_id.is_none() =>
+            {
+                Err(CopyRestriction::UnknownQuizAnswer)
+            }
+            Giveaway(_) | GiveawayWinners(_) => Err(CopyRestriction::Giveaway),
+            Invoice(_) => Err(CopyRestriction::Invoice),
+            _ => Ok(()),
+        }
+    }
+
     /// Returns all users that are "contained" in this `Message` structure.
     ///
     /// This might be useful to track information about users.
@@ -2103,6 +2203,30 @@ impl Message {
     }
 }
 
+/// A short, one-line description of the message, meant for logs.
+///
+/// It includes ids of the message, the chat and the sender, and the beginning
+/// of the text or the caption, e.g.
+/// `message 5 in chat -100123 @group from user 42 @alice: "hello"`.
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "message {} in ", self.id)?;
+        compact_display::chat(f, &self.chat)?;
+
+        if let Some(from) = &self.from {
+            f.write_str(" from ")?;
+            compact_display::user(f, from)?;
+        }
+
+        if let Some(text) = self.text().or_else(|| self.caption()) {
+            f.write_str(": ")?;
+            compact_display::snippet(f, text)?;
+        }
+
+        Ok(())
+    }
+}
+
 /// Implemented for syntax sugar, see issue <https://github.com/teloxide/teloxide/issues/1143>
 impl From<Message> for MessageId {
     fn from(message: Message) -> MessageId {
@@ -2632,7 +2756,17 @@ mod tests {
     fn topic_message() {
         let json = r#"{"chat":{"id":-1001847508954,"is_forum":true,"title":"twest","type":"supergroup"},"date":1675229140,"from":{"first_name":"вафель'","id":1253681278,"is_bot":false,"language_code":"en","username":"wafflelapkin"},"is_topic_message":true,"message_id":5,"message_thread_id":4,"reply_to_message":{"chat":{"id":-1001847508954,"is_forum":true,"title":"twest","type":"supergroup"},"date":1675229139,"forum_topic_created":{"icon_color":9367192,"icon_custom_emoji_id":"5312536423851630001","name":"???"},"from":{"first_name":"вафель'","id":1253681278,"is_bot":false,"language_code":"en","username":"wafflelapkin"},"is_topic_message":true,"message_id":4,"message_thread_id":4},"text":"blah"}"#;
 
-        let _: Message = serde_json::from_str(json).unwrap();
+        let message: Message = serde_json::from_str(json).unwrap();
+        assert_eq!(message.forum_topic_id(), Some(ThreadId(MessageId(4))));
+    }
+
+    #[test]
+    fn reply_thread_is_not_forum_topic() {
+        let json = r#"{"chat":{"id":-1001847508954,"title":"twest","type":"supergroup"},"date":1675229140,"message_id":5,"message_thread_id":4,"text":"blah"}"#;
+
+        let message: Message = serde_json::from_str(json).unwrap();
+        assert_eq!(message.thread_id, Some(ThreadId(MessageId(4))));
+        assert_eq!(message.forum_topic_id(), None);
     }
 
     /// Regression test for <https://github.com/teloxide/teloxide/issues/873>
@@ -3175,4 +3309,82 @@ mod tests {
         let message: Message = from_str(json).unwrap();
         assert!(message.show_caption_above_media())
     }
+
+    #[test]
+    fn reply_parameters() {
+        let json = r#"{
+            "message_id": 7,
+            "chat": { "id": -1001, "title": "Group", "type": "supergroup" },
+            "date": 1739041615,
+            "text": "El Psy Kongroo"
+        }"#;
+        let message: Message = from_str(json).unwrap();
+        let params = message.reply_parameters().quote("Kongroo".to_owned());
+
+        assert_eq!(params.message_id, MessageId(7));
+        assert_eq!(params.chat_id, Some(Recipient::Id(ChatId(-1001))));
+        assert_eq!(params.quote.as_deref(), Some("Kongroo"));
+    }
+
+    #[test]
+    fn auto_delete_date() {
+        let json = r#"{
+            "message_id": 7,
+            "chat": { "id": -1001, "title": "Group", "type": "supergroup" },
+            "date": 1739041615,
+            "text": "El Psy Kongroo"
+        }"#;
+        let message: Message = from_str(json).unwrap();
+
+        assert_eq!(
+            message.auto_delete_date(Seconds::from_seconds(86400)).timestamp(),
+            1739041615 + 86400
+        );
+    }
+
+    #[test]
+    fn can_copy_and_forward() {
+        let message = |rest: &str| -> Message {
+            from_str(&format!(
+                r#"{{
+                    "message_id": 7,
+                    "chat": {{ "id": -1001, "title": "Group", "type": "supergroup" }},
+                    "date": 1739041615,
+                    {rest}
+                }}"#
+            ))
+            .unwrap()
+        };
+        let quiz = |correct_option_id: &str| {
+            message(&format!(
+                r#""poll": {{
+                    "id": "1",
+                    "question": "?",
+                    "options": [{{ "text": "a", "voter_count": 0 }}],
+                    "total_voter_count": 0,
+                    "is_closed": false,
+                    "is_anonymous": true,
+                    "type": "quiz",
+                    "allows_multiple_answers": false
+                    {correct_option_id}
+                }}"#
+            ))
+        };
+
+        let text = message(r#""text": "hi""#);
+        assert_eq!(text.can_forward(), Ok(()));
+        assert_eq!(text.can_copy(), Ok(()));
+
+        let protected = message(r#""text": "hi", "has_protected_content": true"#);
+        assert_eq!(protected.can_forward(), Err(CopyRestriction::ProtectedContent));
+        assert_eq!(protected.can_copy(), Err(CopyRestriction::ProtectedContent));
+
+        let service = message(r#""delete_chat_photo": true"#);
+        assert_eq!(service.can_forward(), Err(CopyRestriction::ServiceMessage));
+        assert_eq!(service.can_copy(), Err(CopyRestriction::ServiceMessage));
+
+        assert_eq!(quiz("").can_forward(), Ok(()));
+        assert_eq!(quiz("").can_copy(), Err(CopyRestriction::UnknownQuizAnswer));
+        assert_eq!(quiz(r#", "correct_option_id": 0"#).can_copy(), Ok(()));
+    }
 }
@@ -10,3 +10,28 @@ pub struct MessageAutoDeleteTimerChanged {
     /// New auto-delete time for messages in the chat
     pub message_auto_delete_time: Seconds,
 }
+
+impl MessageAutoDeleteTimerChanged {
+    /// Returns the new auto-delete time for messages in the chat, or `None` if
+    /// auto-deletion was disabled.
+    #[must_use]
+    pub fn auto_delete_timer(&self) -> Option<Seconds> {
+        Some(self.message_auto_delete_time).filter(|time| time.seconds() != 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disabled_timer() {
+        let changed: MessageAutoDeleteTimerChanged =
+            serde_json::from_str(r#"{"message_auto_delete_time":0}"#).unwrap();
+        assert_eq!(changed.auto_delete_timer(), None);
+
+        let changed: MessageAutoDeleteTimerChanged =
+            serde_json::from_str(r#"{"message_auto_delete_time":86400}"#).unwrap();
+        assert_eq!(changed.auto_delete_timer(), Some(Seconds::from_seconds(86400)));
+    }
+}
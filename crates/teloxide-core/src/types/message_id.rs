@@ -1,3 +1,5 @@
+use std::iter;
+
 use serde::{Deserialize, Serialize};
 
 /// A unique message identifier.
@@ -16,6 +18,71 @@ use serde::{Deserialize, Serialize};
 #[serde(from = "MessageIdRaw", into = "MessageIdRaw")]
 pub struct MessageId(pub i32);
 
+impl MessageId {
+    /// The maximum number of messages which can be deleted, forwarded or
+    /// copied with a single request, e.g. [`DeleteMessages`].
+    ///
+    /// [`DeleteMessages`]: crate::payloads::DeleteMessages
+    pub const MAX_BATCH: usize = 100;
+
+    /// Returns ids of the last `count` messages up to `latest` (inclusive), in
+    /// ascending order.
+    ///
+    /// Message ids in a chat are sequential, so these are the ids of the
+    /// messages sent right before `latest`. Some of them may refer to
+    /// messages which were already deleted. Ids below 1 are skipped.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use teloxide_core::types::MessageId;
+    ///
+    /// let ids: Vec<_> = MessageId::range_to(MessageId(10), 3).collect();
+    /// assert_eq!(ids, [MessageId(8), MessageId(9), MessageId(10)]);
+    ///
+    /// assert_eq!(MessageId::range_to(MessageId(2), 5).len(), 2);
+    /// ```
+    pub fn range_to(
+        latest: MessageId,
+        count: u32,
+    ) -> impl DoubleEndedIterator<Item = MessageId> + ExactSizeIterator {
+        // `u32` fits any non-negative id plus one
+        let end = latest.0.max(0) as u32 + 1;
+        let start = end.saturating_sub(count).max(1);
+
+        (start..end).map(|id| MessageId(id as i32))
+    }
+
+    /// Splits ids into batches of at most [`MAX_BATCH`] ids, which can be
+    /// passed to [`DeleteMessages`], [`ForwardMessages`] or [`CopyMessages`].
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use teloxide_core::types::MessageId;
+    ///
+    /// let batches: Vec<_> = MessageId::batches(MessageId::range_to(MessageId(250), 250)).collect();
+    /// assert_eq!(batches.len(), 3);
+    /// assert_eq!(batches[2].len(), 50);
+    /// ```
+    ///
+    /// [`MAX_BATCH`]: MessageId::MAX_BATCH
+    /// [`DeleteMessages`]: crate::payloads::DeleteMessages
+    /// [`ForwardMessages`]: crate::payloads::ForwardMessages
+    /// [`CopyMessages`]: crate::payloads::CopyMessages
+    pub fn batches<I>(ids: I) -> impl Iterator<Item = Vec<MessageId>>
+    where
+        I: IntoIterator<Item = MessageId>,
+    {
+        let mut ids = ids.into_iter();
+
+        iter::from_fn(move || {
+            let batch: Vec<_> = ids.by_ref().take(Self::MAX_BATCH).collect();
+            (!batch.is_empty()).then_some(batch)
+        })
+    }
+}
+
 // N.B. we [de]serialize `MessageId` as `{"message_id":n}`, which means that if
 //      you want just an integer, you need to special case it with something
 //      like `serde(with = "crate::types::option_msg_id_as_int")]`
@@ -54,6 +121,23 @@ mod tests {
         assert_eq!(mid, MessageId(123));
     }
 
+    #[test]
+    fn range_to() {
+        assert_eq!(MessageId::range_to(MessageId(5), 0).len(), 0);
+        assert_eq!(MessageId::range_to(MessageId(0), 10).len(), 0);
+        assert_eq!(MessageId::range_to(MessageId(5), u32::MAX).next(), Some(MessageId(1)));
+        assert_eq!(
+            MessageId::range_to(MessageId(i32::MAX), 2).collect::<Vec<_>>(),
+            [MessageId(i32::MAX - 1), MessageId(i32::MAX)]
+        );
+        assert_eq!(MessageId::range_to(MessageId(5), 2).next_back(), Some(MessageId(5)));
+
+        let batches: Vec<_> =
+            MessageId::batches(MessageId::range_to(MessageId(200), 200)).collect();
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[1].first(), Some(&MessageId(101)));
+    }
+
     #[test]
     fn smoke_ser() {
         let mid: MessageId = MessageId(123);
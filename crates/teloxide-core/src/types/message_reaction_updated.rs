@@ -42,6 +42,24 @@ impl MessageReactionUpdated {
     pub fn user(&self) -> Option<&User> {
         self.actor.user()
     }
+
+    /// Returns reactions that were added by this change, i.e. those that are in
+    /// [`new_reaction`] but not in [`old_reaction`].
+    ///
+    /// [`new_reaction`]: MessageReactionUpdated::new_reaction
+    /// [`old_reaction`]: MessageReactionUpdated::old_reaction
+    pub fn added_reactions(&self) -> impl Iterator<Item = &ReactionType> {
+        self.new_reaction.iter().filter(|r| !self.old_reaction.contains(r))
+    }
+
+    /// Returns reactions that were removed by this change, i.e. those that are
+    /// in [`old_reaction`] but not in [`new_reaction`].
+    ///
+    /// [`new_reaction`]: MessageReactionUpdated::new_reaction
+    /// [`old_reaction`]: MessageReactionUpdated::old_reaction
+    pub fn removed_reactions(&self) -> impl Iterator<Item = &ReactionType> {
+        self.old_reaction.iter().filter(|r| !self.new_reaction.contains(r))
+    }
 }
 
 #[derive(Deserialize)]
@@ -124,4 +142,38 @@ mod tests {
 
         assert!(message_reaction_update.actor.is_chat())
     }
+
+    #[test]
+    fn added_and_removed_reactions() {
+        let data = r#"{
+            "chat": {
+                "id": -1002199793788,
+                "title": "Test",
+                "type": "supergroup"
+            },
+            "message_id": 2,
+            "user": {
+                "id": 1459074222,
+                "is_bot": false,
+                "first_name": "A"
+            },
+            "date": 1723798597,
+            "old_reaction": [
+                { "type": "emoji", "emoji": "❤" },
+                { "type": "emoji", "emoji": "👍" }
+            ],
+            "new_reaction": [
+                { "type": "emoji", "emoji": "👍" },
+                { "type": "paid" }
+            ]
+        }"#;
+
+        let update = serde_json::from_str::<MessageReactionUpdated>(data).unwrap();
+
+        assert_eq!(update.added_reactions().collect::<Vec<_>>(), [&ReactionType::Paid]);
+        assert_eq!(
+            update.removed_reactions().collect::<Vec<_>>(),
+            [&ReactionType::Emoji { emoji: "❤".to_owned() }]
+        );
+    }
 }
@@ -1,4 +1,4 @@
-use serde::{Deserialize, Deserializer, Serialize};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::types::{Chat, MaybeAnonymousUser, PollId, User};
 
@@ -13,7 +13,7 @@ pub struct PollAnswer {
     ///
     /// If the voter isn't anonymous, stores the user that changed
     /// the answer to the poll
-    #[serde(deserialize_with = "deserialize_voter", flatten)]
+    #[serde(deserialize_with = "deserialize_voter", serialize_with = "serialize_voter", flatten)]
     pub voter: MaybeAnonymousUser,
 
     /// 0-based identifiers of answer options, chosen by the user.
@@ -22,6 +22,22 @@ pub struct PollAnswer {
     pub option_ids: Vec<u8>,
 }
 
+impl PollAnswer {
+    /// Returns the user that changed the answer to the poll, if the voter
+    /// isn't anonymous.
+    #[must_use]
+    pub fn user(&self) -> Option<&User> {
+        self.voter.user()
+    }
+
+    /// Returns the chat that changed the answer to the poll, if the voter is
+    /// anonymous.
+    #[must_use]
+    pub fn voter_chat(&self) -> Option<&Chat> {
+        self.voter.chat()
+    }
+}
+
 /// These fields `chat` and `user` from the original [`PollAnswer`] should be
 /// exclusive, but in cases when the `voter_chat` is presented the `user` isn't
 /// `None`, but rather actual value for backward compatibility, the field `user`
@@ -38,7 +54,21 @@ struct VoterDe {
 
 fn deserialize_voter<'d, D: Deserializer<'d>>(d: D) -> Result<MaybeAnonymousUser, D::Error> {
     let VoterDe { voter_chat, user } = VoterDe::deserialize(d)?;
-    Ok(voter_chat.map(MaybeAnonymousUser::Chat).or(user.map(MaybeAnonymousUser::User)).unwrap())
+    voter_chat
+        .map(MaybeAnonymousUser::Chat)
+        .or(user.map(MaybeAnonymousUser::User))
+        .ok_or_else(|| de::Error::missing_field("user"))
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(Serialize)]
+struct VoterSer<'a> {
+    voter_chat: Option<&'a Chat>,
+    user: Option<&'a User>,
+}
+
+fn serialize_voter<S: Serializer>(voter: &MaybeAnonymousUser, s: S) -> Result<S::Ok, S::Error> {
+    VoterSer { voter_chat: voter.chat(), user: voter.user() }.serialize(s)
 }
 
 #[cfg(test)]
@@ -90,4 +120,24 @@ mod tests {
         let poll_answer: PollAnswer = serde_json::from_str(json).unwrap();
         assert!(poll_answer.voter.is_chat());
     }
+
+    #[test]
+    fn poll_answer_without_voter_de() {
+        let json = r#"{"poll_id": "POLL_ID", "option_ids": []}"#;
+
+        assert!(serde_json::from_str::<PollAnswer>(json).is_err());
+    }
+
+    #[test]
+    fn poll_answer_ser() {
+        let json = r#"{"poll_id":"POLL_ID","voter_chat":{"id":-1001160242915,"title":"a","type":"group"},"option_ids":[1]}"#;
+
+        let poll_answer: PollAnswer = serde_json::from_str(json).unwrap();
+        assert_eq!(poll_answer.voter_chat().map(|chat| chat.id.0), Some(-1001160242915));
+        assert_eq!(poll_answer.user(), None);
+
+        let json = serde_json::to_string(&poll_answer).unwrap();
+        assert_eq!(serde_json::from_str::<PollAnswer>(&json).unwrap(), poll_answer);
+        assert!(!json.contains("\"user\""));
+    }
 }
@@ -29,6 +29,25 @@ pub enum ReactionType {
 }
 
 impl ReactionType {
+    /// Creates an [`Emoji`] reaction, e.g. `ReactionType::from_emoji("👍")`.
+    ///
+    /// [`Emoji`]: ReactionType::Emoji
+    #[must_use]
+    pub fn from_emoji<S>(emoji: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self::Emoji { emoji: emoji.into() }
+    }
+
+    /// Creates a [`CustomEmoji`] reaction.
+    ///
+    /// [`CustomEmoji`]: ReactionType::CustomEmoji
+    #[must_use]
+    pub fn custom(custom_emoji_id: CustomEmojiId) -> Self {
+        Self::CustomEmoji { custom_emoji_id }
+    }
+
     #[must_use]
     pub fn emoji(&self) -> Option<&String> {
         match &self {
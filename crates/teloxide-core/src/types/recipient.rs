@@ -1,13 +1,21 @@
+use std::fmt;
+
 use derive_more::{Display, From};
-use serde::{Deserialize, Serialize};
+use serde::{de::Visitor, Deserialize, Deserializer, Serialize};
 
 use crate::types::{ChatId, UserId};
 
 /// A unique identifier for the target chat or username of the target channel
 /// (in the format `@channelusername`).
+///
+/// [`Recipient::Id`] is always serialized as a number and
+/// [`Recipient::ChannelUsername`] as a string, both in JSON and in multipart
+/// forms. When deserializing, a string containing an integer (e.g. `"-100123"`)
+/// is parsed as [`Recipient::Id`], since usernames can't consist of digits
+/// only.
 #[derive(Clone, PartialEq, Eq, Hash)]
 #[derive(Debug, Display, From)]
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize)]
 #[serde(untagged)]
 pub enum Recipient {
     /// A chat identifier.
@@ -25,6 +33,51 @@ impl From<UserId> for Recipient {
     }
 }
 
+impl<'de> Deserialize<'de> for Recipient {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct RecipientVisitor;
+
+        impl Visitor<'_> for RecipientVisitor {
+            type Value = Recipient;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a chat id or a channel username")
+            }
+
+            fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(Recipient::Id(ChatId(v)))
+            }
+
+            fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i64::try_from(v)
+                    .map(|id| Recipient::Id(ChatId(id)))
+                    .map_err(|_| E::invalid_value(serde::de::Unexpected::Unsigned(v), &self))
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(match v.parse() {
+                    Ok(id) => Recipient::Id(ChatId(id)),
+                    Err(_) => Recipient::ChannelUsername(v.to_owned()),
+                })
+            }
+        }
+
+        deserializer.deserialize_any(RecipientVisitor)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -45,4 +98,26 @@ mod tests {
 
         assert_eq!(expected_json, actual_json)
     }
+
+    #[test]
+    fn deserialization_round_trip() {
+        for recipient in
+            [Recipient::Id(ChatId(-1001234567890)), Recipient::ChannelUsername("@username".into())]
+        {
+            let json = serde_json::to_string(&recipient).unwrap();
+            assert_eq!(serde_json::from_str::<Recipient>(&json).unwrap(), recipient);
+        }
+    }
+
+    #[test]
+    fn stringified_id_deserialization() {
+        assert_eq!(
+            serde_json::from_str::<Recipient>(r#""-100123""#).unwrap(),
+            Recipient::Id(ChatId(-100123))
+        );
+        assert_eq!(
+            serde_json::from_str::<Recipient>(r#""@123abc""#).unwrap(),
+            Recipient::ChannelUsername("@123abc".into())
+        );
+    }
 }
@@ -1,5 +1,8 @@
+use std::{fmt, str::FromStr};
+
 use rgb::RGB8;
 use serde::{de::Visitor, Deserialize, Serialize};
+use thiserror::Error;
 
 /// RGB color format
 #[repr(C)]
@@ -12,6 +15,32 @@ pub struct Rgb {
 }
 
 impl Rgb {
+    /// Blue forum topic icon color (`0x6FB9F0`).
+    pub const TOPIC_BLUE: Self = Self { r: 0x6F, g: 0xB9, b: 0xF0 };
+    /// Yellow forum topic icon color (`0xFFD67E`).
+    pub const TOPIC_YELLOW: Self = Self { r: 0xFF, g: 0xD6, b: 0x7E };
+    /// Violet forum topic icon color (`0xCB86DB`).
+    pub const TOPIC_VIOLET: Self = Self { r: 0xCB, g: 0x86, b: 0xDB };
+    /// Green forum topic icon color (`0x8EEE98`).
+    pub const TOPIC_GREEN: Self = Self { r: 0x8E, g: 0xEE, b: 0x98 };
+    /// Rose forum topic icon color (`0xFF93B2`).
+    pub const TOPIC_ROSE: Self = Self { r: 0xFF, g: 0x93, b: 0xB2 };
+    /// Red forum topic icon color (`0xFB6F5F`).
+    pub const TOPIC_RED: Self = Self { r: 0xFB, g: 0x6F, b: 0x5F };
+
+    /// Colors that can be used for forum topic icons, see
+    /// [`CreateForumTopic::icon_color`].
+    ///
+    /// [`CreateForumTopic::icon_color`]: crate::payloads::CreateForumTopic::icon_color
+    pub const TOPIC_COLORS: [Self; 6] = [
+        Self::TOPIC_BLUE,
+        Self::TOPIC_YELLOW,
+        Self::TOPIC_VIOLET,
+        Self::TOPIC_GREEN,
+        Self::TOPIC_ROSE,
+        Self::TOPIC_RED,
+    ];
+
     /// Convert a [`Rgb`] struct into a big endian `u32` representing the RGB
     /// color.
     ///
@@ -44,6 +73,39 @@ impl Rgb {
     }
 }
 
+/// Formats the color as a hex string, e.g. `#aabbcc`.
+impl fmt::Display for Rgb {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:06x}", self.to_u32())
+    }
+}
+
+/// Parses a color from a hex string, e.g. `#aabbcc` or `AABBCC`.
+///
+/// # Example
+///
+/// ```
+/// use teloxide_core::types::Rgb;
+/// assert_eq!("#6FB9F0".parse(), Ok(Rgb::TOPIC_BLUE));
+/// ```
+impl FromStr for Rgb {
+    type Err = ParseRgbError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let hex = s.strip_prefix('#').unwrap_or(s);
+        if hex.len() != 6 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(ParseRgbError);
+        }
+
+        u32::from_str_radix(hex, 16).map(Self::from_u32).map_err(|_| ParseRgbError)
+    }
+}
+
+/// An error returned when parsing an [`Rgb`] color from a string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("expected a color in the `#RRGGBB` format")]
+pub struct ParseRgbError;
+
 impl Serialize for Rgb {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -190,6 +252,20 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn rgb_hex() {
+        for color in Rgb::TOPIC_COLORS {
+            assert_eq!(color.to_string().parse(), Ok(color));
+        }
+
+        assert_eq!(Rgb::TOPIC_GREEN.to_u32(), 9367192);
+        assert_eq!(Rgb { r: 0, g: 0x0A, b: 0xFF }.to_string(), "#000aff");
+        assert_eq!("8eee98".parse(), Ok(Rgb::TOPIC_GREEN));
+        assert_eq!("#8eee9".parse::<Rgb>(), Err(ParseRgbError));
+        assert_eq!("#+8eee9".parse::<Rgb>(), Err(ParseRgbError));
+        assert_eq!("#8eee98ff".parse::<Rgb>(), Err(ParseRgbError));
+    }
+
     #[test]
     fn rgb() {
         #[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -2,7 +2,7 @@ use std::ops::Deref;
 
 use serde::{Deserialize, Serialize};
 
-use crate::types::{CustomEmojiId, FileMeta, MaskPosition, PhotoSize};
+use crate::types::{CustomEmojiId, FileMeta, MaskPosition, PhotoSize, StickerSetName};
 
 /// This object represents a sticker.
 ///
@@ -55,7 +55,7 @@ pub struct Sticker {
     pub emoji: Option<String>,
 
     /// Name of the sticker set to which the sticker belongs.
-    pub set_name: Option<String>,
+    pub set_name: Option<StickerSetName>,
 
     /// True, if the sticker must be repainted to a text color in messages, the
     /// color of the Telegram Premium badge in emoji status, white color on
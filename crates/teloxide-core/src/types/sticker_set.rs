@@ -1,5 +1,6 @@
 use std::ops::Deref;
 
+use derive_more::From;
 use serde::{Deserialize, Serialize};
 
 use crate::types::{PhotoSize, Sticker, StickerType};
@@ -11,7 +12,7 @@ use crate::types::{PhotoSize, Sticker, StickerType};
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct StickerSet {
     /// Sticker set name.
-    pub name: String,
+    pub name: StickerSetName,
 
     /// Sticker set title.
     pub title: String,
@@ -27,6 +28,42 @@ pub struct StickerSet {
     pub thumbnail: Option<PhotoSize>,
 }
 
+/// Name of a sticker set, used to get or change the set.
+///
+/// Dereferences to `str`, and converts into `String`, so it can be passed to
+/// methods like [`get_sticker_set`] as is.
+///
+/// [`get_sticker_set`]: crate::requests::Requester::get_sticker_set
+#[derive(
+    Default,
+    Clone,
+    Debug,
+    derive_more::Display,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    From
+)]
+#[serde(transparent)]
+#[from(&'static str, String)]
+pub struct StickerSetName(pub String);
+
+impl Deref for StickerSetName {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<StickerSetName> for String {
+    fn from(name: StickerSetName) -> Self {
+        name.0
+    }
+}
+
 /// This allows calling [`StickerType`]'s methods directly on [`StickerSet`].
 ///
 /// ```no_run
@@ -47,7 +84,7 @@ impl Deref for StickerSet {
 
 #[cfg(test)]
 mod tests {
-    use crate::types::StickerSet;
+    use crate::types::{StickerSet, StickerSetName};
 
     #[test]
     fn smoke_serde() {
@@ -106,6 +143,8 @@ mod tests {
         assert!(set.is_regular());
         assert!(set.thumbnail.is_none());
         assert_eq!(set.stickers.len(), 2);
+        assert_eq!(set.name, StickerSetName::from("teloxide_test"));
+        assert_eq!(set.stickers[0].set_name.as_ref(), Some(&set.name));
     }
 
     #[test]
@@ -42,6 +42,18 @@ use serde::{Deserialize, Serialize};
 #[serde(from = "ThreadIdRaw", into = "ThreadIdRaw")]
 pub struct ThreadId(/** Identifier of the root message in a reply thread. */ pub MessageId);
 
+impl From<MessageId> for ThreadId {
+    fn from(id: MessageId) -> Self {
+        Self(id)
+    }
+}
+
+impl From<ThreadId> for MessageId {
+    fn from(ThreadId(id): ThreadId) -> Self {
+        id
+    }
+}
+
 // N.B. this is a hack to [de]serialize `ThreadId` as just a number
 //      we need this since `MessageId` is [de]serialized as `{"message_id":n}`.
 
@@ -77,4 +89,12 @@ mod tests {
         let json = serde_json::to_string(&mid).unwrap();
         assert_eq!(json, "123");
     }
+
+    #[test]
+    fn conversions() {
+        let thread_id: ThreadId = MessageId(17).into();
+        assert_eq!(thread_id, ThreadId(MessageId(17)));
+        assert_eq!(MessageId::from(thread_id), MessageId(17));
+        assert_eq!(thread_id.to_string(), "17");
+    }
 }
@@ -1,12 +1,14 @@
 #![allow(clippy::large_enum_variant)]
+use std::fmt;
+
 use serde::{de::MapAccess, Deserialize, Serialize, Serializer};
 use serde_json::Value;
 
 use crate::types::{
-    BusinessConnection, BusinessMessagesDeleted, CallbackQuery, Chat, ChatBoostRemoved,
-    ChatBoostUpdated, ChatJoinRequest, ChatMemberUpdated, ChosenInlineResult, InlineQuery, Message,
-    MessageReactionCountUpdated, MessageReactionUpdated, PaidMediaPurchased, Poll, PollAnswer,
-    PreCheckoutQuery, ShippingQuery, User,
+    compact_display, BusinessConnection, BusinessMessagesDeleted, CallbackQuery, Chat,
+    ChatBoostRemoved, ChatBoostUpdated, ChatJoinRequest, ChatMemberUpdated, ChosenInlineResult,
+    InlineQuery, Message, MessageReactionCountUpdated, MessageReactionUpdated, PaidMediaPurchased,
+    Poll, PollAnswer, PreCheckoutQuery, ShippingQuery, User,
 };
 
 /// This [object] represents an incoming update.
@@ -333,6 +335,74 @@ impl Update {
     }
 }
 
+/// A short, one-line description of the update, meant for logs.
+///
+/// Unlike the `Debug` output, which may be kilobytes long, it only includes
+/// the kind of the update, the sender and the chat, as well as the beginning
+/// of the text for messages, e.g.
+/// `update 7 (message): message 5 in chat 42 from user 42 @alice: "hello"`.
+impl fmt::Display for Update {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "update {} ({})", self.id.0, self.kind.name())?;
+
+        match &self.kind {
+            UpdateKind::Message(message)
+            | UpdateKind::EditedMessage(message)
+            | UpdateKind::ChannelPost(message)
+            | UpdateKind::EditedChannelPost(message)
+            | UpdateKind::BusinessMessage(message)
+            | UpdateKind::EditedBusinessMessage(message) => write!(f, ": {message}"),
+            UpdateKind::CallbackQuery(query) => write!(f, ": {query}"),
+            _ => {
+                if let Some(from) = self.from() {
+                    f.write_str(" from ")?;
+                    compact_display::user(f, from)?;
+                }
+                if let Some(chat) = self.chat() {
+                    f.write_str(" in ")?;
+                    compact_display::chat(f, chat)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl UpdateKind {
+    /// Returns the name of the field of this kind of updates in the Telegram
+    /// API (e.g. `"edited_message"`), or `"unknown"` for
+    /// [`UpdateKind::Error`].
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        match self {
+            UpdateKind::Message(_) => "message",
+            UpdateKind::EditedMessage(_) => "edited_message",
+            UpdateKind::ChannelPost(_) => "channel_post",
+            UpdateKind::EditedChannelPost(_) => "edited_channel_post",
+            UpdateKind::BusinessConnection(_) => "business_connection",
+            UpdateKind::BusinessMessage(_) => "business_message",
+            UpdateKind::EditedBusinessMessage(_) => "edited_business_message",
+            UpdateKind::DeletedBusinessMessages(_) => "deleted_business_messages",
+            UpdateKind::MessageReaction(_) => "message_reaction",
+            UpdateKind::MessageReactionCount(_) => "message_reaction_count",
+            UpdateKind::InlineQuery(_) => "inline_query",
+            UpdateKind::ChosenInlineResult(_) => "chosen_inline_result",
+            UpdateKind::CallbackQuery(_) => "callback_query",
+            UpdateKind::ShippingQuery(_) => "shipping_query",
+            UpdateKind::PreCheckoutQuery(_) => "pre_checkout_query",
+            UpdateKind::PurchasedPaidMedia(_) => "purchased_paid_media",
+            UpdateKind::Poll(_) => "poll",
+            UpdateKind::PollAnswer(_) => "poll_answer",
+            UpdateKind::MyChatMember(_) => "my_chat_member",
+            UpdateKind::ChatMember(_) => "chat_member",
+            UpdateKind::ChatJoinRequest(_) => "chat_join_request",
+            UpdateKind::ChatBoost(_) => "chat_boost",
+            UpdateKind::RemovedChatBoost(_) => "removed_chat_boost",
+            UpdateKind::Error(_) => "unknown",
+        }
+    }
+}
+
 impl UpdateId {
     /// Returns the offset for the **next** update that can be used for polling.
     ///
@@ -855,6 +925,36 @@ mod test {
         }
     }
 
+    #[test]
+    fn channel_post_giveaway() {
+        let json = r#"{
+            "update_id": 1,
+            "channel_post": {
+                "message_id": 27,
+                "sender_chat": { "id": -1002236736395, "title": "Test", "type": "channel" },
+                "chat": { "id": -1002236736395, "title": "Test", "type": "channel" },
+                "date": 1721162577,
+                "giveaway": {
+                    "chats": [{ "id": -1002236736395, "title": "Test", "type": "channel" }],
+                    "winners_selection_date": 1721162701,
+                    "winner_count": 1,
+                    "has_public_winners": true,
+                    "premium_subscription_month_count": 6
+                }
+            }
+        }"#;
+
+        let Update { kind, .. } = serde_json::from_str(json).unwrap();
+        match kind {
+            UpdateKind::ChannelPost(message) => {
+                let giveaway = message.giveaway().expect("Expected a giveaway");
+                assert_eq!(giveaway.winner_count, 1);
+                assert_eq!(message.chat.id, ChatId(-1002236736395));
+            }
+            _ => panic!("Expected `ChannelPost`"),
+        }
+    }
+
     #[test]
     fn new_update_kind_error() {
         let json = r#"{
@@ -1222,4 +1322,55 @@ mod test {
             _ => panic!("Expected `PurchasedPaidMedia`"),
         }
     }
+
+    #[test]
+    fn compact_display() {
+        let message = r#"{
+            "update_id": 7,
+            "message": {
+                "message_id": 5,
+                "date": 1569518342,
+                "chat": {"id": -100123, "type": "supergroup", "title": "G", "username": "group"},
+                "from": {"id": 42, "is_bot": false, "first_name": "A", "username": "alice"},
+                "text": "hello\nworld"
+            }
+        }"#;
+        let update: Update = serde_json::from_str(message).unwrap();
+        assert_eq!(
+            update.to_string(),
+            r#"update 7 (message): message 5 in chat -100123 @group from user 42 @alice: "hello\nworld""#
+        );
+
+        let query = format!(
+            r#"{{
+                "update_id": 8,
+                "callback_query": {{
+                    "id": "123",
+                    "from": {{"id": 42, "is_bot": false, "first_name": "A"}},
+                    "chat_instance": "1",
+                    "data": "{}"
+                }}
+            }}"#,
+            "a".repeat(100)
+        );
+        let update: Update = serde_json::from_str(&query).unwrap();
+        assert_eq!(
+            update.to_string(),
+            format!(
+                r#"update 8 (callback_query): callback query 123 from user 42: "{}"…"#,
+                "a".repeat(64)
+            )
+        );
+
+        let answer = r#"{
+            "update_id": 9,
+            "poll_answer": {
+                "poll_id": "1",
+                "user": {"id": 42, "is_bot": false, "first_name": "A"},
+                "option_ids": [0]
+            }
+        }"#;
+        let update: Update = serde_json::from_str(answer).unwrap();
+        assert_eq!(update.to_string(), "update 9 (poll_answer) from user 42");
+    }
 }
@@ -0,0 +1,223 @@
+//! Client-side checks of Telegram limits, enabled by the `strict_validation`
+//! feature.
+
+use std::any::Any;
+
+use crate::{
+    errors::ValidationError,
+    payloads::*,
+    types::{InlineKeyboardButtonKind, InlineKeyboardMarkup, InputMedia, ParseMode, ReplyMarkup},
+};
+
+const MAX_CAPTION_LEN: usize = 1024;
+const MAX_TEXT_LEN: usize = 4096;
+const MAX_INVOICE_TITLE_LEN: usize = 32;
+const MAX_CALLBACK_DATA_LEN: usize = 64;
+const MAX_BUTTONS_IN_ROW: usize = 8;
+
+/// Defines [`validate`], which checks the listed payloads with the expression
+/// following them.
+macro_rules! validators {
+    ($( $($Payload:ident)|+ => |$payload:ident| $check:expr ),* $(,)?) => {
+        /// Checks `payload`, if it's one of the payloads with known limits.
+        ///
+        /// Lengths of texts are only checked if they don't have a parse mode,
+        /// since Telegram limits the length of the text after parsing entities.
+        pub(crate) fn validate<P>(payload: &P) -> Result<(), ValidationError>
+        where
+            P: Any,
+        {
+            let payload: &dyn Any = payload;
+            $($(
+                if let Some($payload) = payload.downcast_ref::<$Payload>() {
+                    return $check;
+                }
+            )+)*
+            Ok(())
+        }
+    };
+}
+
+validators! {
+    SendMessage | EditMessageText | EditMessageTextInline => |p| {
+        check_len(Some(&p.text), p.parse_mode, MAX_TEXT_LEN, ValidationError::TextTooLong)?;
+        check_markup(p.reply_markup.as_ref())
+    },
+    CopyMessage
+    | EditMessageCaption
+    | EditMessageCaptionInline
+    | SendAnimation
+    | SendAudio
+    | SendDocument
+    | SendPaidMedia
+    | SendPhoto
+    | SendVideo
+    | SendVoice => |p| {
+        check_caption(p.caption.as_deref(), p.parse_mode)?;
+        check_markup(p.reply_markup.as_ref())
+    },
+    SendMediaGroup => |p| p.media.iter().try_for_each(check_media),
+    EditMessageMedia | EditMessageMediaInline => |p| {
+        check_media(&p.media)?;
+        check_markup(p.reply_markup.as_ref())
+    },
+    SendInvoice => |p| {
+        check_title(&p.title)?;
+        check_markup(p.reply_markup.as_ref())
+    },
+    CreateInvoiceLink => |p| check_title(&p.title),
+    EditMessageChecklist
+    | EditMessageLiveLocation
+    | EditMessageLiveLocationInline
+    | EditMessageReplyMarkup
+    | EditMessageReplyMarkupInline
+    | SendChecklist
+    | SendContact
+    | SendDice
+    | SendGame
+    | SendLocation
+    | SendPoll
+    | SendSticker
+    | SendVenue
+    | SendVideoNote
+    | StopMessageLiveLocation
+    | StopMessageLiveLocationInline
+    | StopPoll => |p| check_markup(p.reply_markup.as_ref()),
+}
+
+/// A reply markup, which may be an inline keyboard.
+trait Markup {
+    fn inline_keyboard(&self) -> Option<&InlineKeyboardMarkup>;
+}
+
+impl Markup for ReplyMarkup {
+    fn inline_keyboard(&self) -> Option<&InlineKeyboardMarkup> {
+        match self {
+            ReplyMarkup::InlineKeyboard(markup) => Some(markup),
+            _ => None,
+        }
+    }
+}
+
+impl Markup for InlineKeyboardMarkup {
+    fn inline_keyboard(&self) -> Option<&InlineKeyboardMarkup> {
+        Some(self)
+    }
+}
+
+fn check_markup<M>(markup: Option<&M>) -> Result<(), ValidationError>
+where
+    M: Markup,
+{
+    let Some(markup) = markup.and_then(M::inline_keyboard) else { return Ok(()) };
+
+    for row in &markup.inline_keyboard {
+        if row.len() > MAX_BUTTONS_IN_ROW {
+            return Err(ValidationError::TooManyButtonsInRow(row.len()));
+        }
+
+        for button in row {
+            if let InlineKeyboardButtonKind::CallbackData(data) = &button.kind {
+                if !(1..=MAX_CALLBACK_DATA_LEN).contains(&data.len()) {
+                    return Err(ValidationError::InvalidCallbackData(data.len()));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn check_media(media: &InputMedia) -> Result<(), ValidationError> {
+    let (caption, parse_mode) = match media {
+        InputMedia::Photo(media) => (&media.caption, media.parse_mode),
+        InputMedia::Video(media) => (&media.caption, media.parse_mode),
+        InputMedia::Animation(media) => (&media.caption, media.parse_mode),
+        InputMedia::Audio(media) => (&media.caption, media.parse_mode),
+        InputMedia::Document(media) => (&media.caption, media.parse_mode),
+    };
+    check_caption(caption.as_deref(), parse_mode)
+}
+
+fn check_caption(
+    caption: Option<&str>,
+    parse_mode: Option<ParseMode>,
+) -> Result<(), ValidationError> {
+    check_len(caption, parse_mode, MAX_CAPTION_LEN, ValidationError::CaptionTooLong)
+}
+
+fn check_title(title: &str) -> Result<(), ValidationError> {
+    let len = title.chars().count();
+    if !(1..=MAX_INVOICE_TITLE_LEN).contains(&len) {
+        return Err(ValidationError::InvalidTitle(len));
+    }
+    Ok(())
+}
+
+fn check_len(
+    text: Option<&str>,
+    parse_mode: Option<ParseMode>,
+    max_len: usize,
+    error: fn(usize) -> ValidationError,
+) -> Result<(), ValidationError> {
+    if parse_mode.is_some() {
+        return Ok(());
+    }
+
+    match text.map(|text| text.chars().count()) {
+        Some(len) if len > max_len => Err(error(len)),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ChatId, InlineKeyboardButton, InputFile, InputMediaPhoto};
+
+    #[test]
+    fn limits() {
+        let mut message = SendMessage::new(ChatId(1), "a".repeat(MAX_TEXT_LEN + 1));
+        assert_eq!(validate(&message), Err(ValidationError::TextTooLong(MAX_TEXT_LEN + 1)));
+
+        message.parse_mode = Some(ParseMode::Html);
+        assert_eq!(validate(&message), Ok(()));
+
+        message.parse_mode = None;
+        message.text = "a".into();
+        message.reply_markup = Some(
+            InlineKeyboardMarkup::new([vec![InlineKeyboardButton::callback("b", "c".repeat(65))]])
+                .into(),
+        );
+        assert_eq!(validate(&message), Err(ValidationError::InvalidCallbackData(65)));
+
+        message.reply_markup = Some(
+            InlineKeyboardMarkup::new([vec![InlineKeyboardButton::callback("b", "c"); 9]]).into(),
+        );
+        assert_eq!(validate(&message), Err(ValidationError::TooManyButtonsInRow(9)));
+
+        let mut photo =
+            SendPhoto::new(ChatId(1), InputFile::url("https://example.com".parse().unwrap()));
+        photo.caption = Some("ф".repeat(MAX_CAPTION_LEN));
+        assert_eq!(validate(&photo), Ok(()));
+        photo.caption = Some("ф".repeat(MAX_CAPTION_LEN + 1));
+        assert_eq!(validate(&photo), Err(ValidationError::CaptionTooLong(MAX_CAPTION_LEN + 1)));
+    }
+
+    #[test]
+    fn nested_captions() {
+        let photo = |caption: String| {
+            InputMedia::Photo(
+                InputMediaPhoto::new(InputFile::file_id("id".into())).caption(caption),
+            )
+        };
+
+        let mut group = SendMediaGroup::new(ChatId(1), [photo("a".into()), photo("b".into())]);
+        assert_eq!(validate(&group), Ok(()));
+        group.media.push(photo("a".repeat(MAX_CAPTION_LEN + 1)));
+        assert_eq!(validate(&group), Err(ValidationError::CaptionTooLong(MAX_CAPTION_LEN + 1)));
+
+        // Payloads without known limits aren't checked
+        assert_eq!(validate(&GetMe::new()), Ok(()));
+    }
+}
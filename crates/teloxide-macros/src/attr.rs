@@ -205,13 +205,13 @@ impl AttrValue {
         })
     }
 
-    // /// Unwraps this value if it's a path.
-    // pub fn expect_path(self) -> Result<Path> {
-    //     self.expect("a path", |this| match this {
-    //         AttrValue::Path(p) => Ok(p),
-    //         _ => Err(this),
-    //     })
-    // }
+    /// Unwraps this value if it's a path.
+    pub fn expect_path(self) -> Result<Path> {
+        self.expect("a path", |this| match this {
+            AttrValue::Path(p) => Ok(p),
+            _ => Err(this),
+        })
+    }
 
     pub fn expect<T>(self, expected: &str, f: impl FnOnce(Self) -> Result<T, Self>) -> Result<T> {
         f(self).map_err(|this| {
@@ -58,14 +58,31 @@ fn impl_commands(infos: &[Command]) -> proc_macro2::TokenStream {
 }
 
 fn impl_descriptions(infos: &[Command], global: &CommandEnum) -> proc_macro2::TokenStream {
-    let command_descriptions = infos
-        .iter()
-        .filter(|command| command.description_is_enabled())
-        .map(|command @ Command { prefix, name, aliases, ..}| {
+    let command_descriptions = infos.iter().filter(|command| command.description_is_enabled()).map(
+        |command @ Command { prefix, name, aliases, category, localized, .. }| {
             let description = command.description().unwrap_or_default();
-            let aliases = if !command.hidden_aliases { aliases.clone().map(|(aliases, _)| aliases).unwrap_or_default() } else { Default::default() };
-            quote! { CommandDescription { prefix: #prefix, command: #name, description: #description, aliases: &[#(#aliases),*]} }
-        });
+            let aliases = if !command.hidden_aliases {
+                aliases.clone().map(|(aliases, _)| aliases).unwrap_or_default()
+            } else {
+                Default::default()
+            };
+            let category = match category {
+                Some(category) => quote! { ::std::option::Option::Some(#category) },
+                None => quote! { ::std::option::Option::None },
+            };
+            let (langs, localized): (Vec<_>, Vec<_>) = localized.iter().cloned().unzip();
+            quote! {
+                CommandDescription {
+                    prefix: #prefix,
+                    command: #name,
+                    description: #description,
+                    aliases: &[#(#aliases),*],
+                    category: #category,
+                    localized: &[#((#langs, #localized)),*],
+                }
+            }
+        },
+    );
 
     let warnings = infos.iter().filter_map(|command| command.deprecated_description_off_span()).map(|span| {
         quote_spanned! {  span =>
@@ -108,7 +125,6 @@ fn impl_parse(
     quote! {
          fn parse(s: &str, bot_name: &str) -> ::std::result::Result<Self, teloxide::utils::command::ParseError> {
               // FIXME: we should probably just call a helper function from `teloxide`, instead of parsing command syntax ourselves
-              use std::str::FromStr;
               use teloxide::utils::command::ParseError;
 
               // 2 is used to only split once (=> in two parts),
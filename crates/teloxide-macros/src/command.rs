@@ -21,6 +21,10 @@ pub(crate) struct Command {
     pub hidden: bool,
     /// Whether the aliases of the command are hidden from the help message.
     pub hidden_aliases: bool,
+    /// The category of the command in the help message.
+    pub category: Option<String>,
+    /// Pairs of a language code and the description in this language.
+    pub localized: Vec<(String, String)>,
 }
 
 impl Command {
@@ -43,6 +47,8 @@ impl Command {
             command_separator: _,
             hide,
             hide_aliases,
+            category,
+            localized,
         } = attrs;
 
         let name = match (rename, rename_rule) {
@@ -61,8 +67,20 @@ impl Command {
         let parser = parser.map(|(p, _)| p).unwrap_or_else(|| global_options.parser_type.clone());
         let hidden = hide.is_some();
         let hidden_aliases = hide_aliases.is_some();
+        let category = category.map(|(c, _)| c);
+        let localized = localized.into_iter().map(|(lang, d, _)| (lang, d)).collect();
 
-        Ok(Self { prefix, description, parser, name, aliases, hidden, hidden_aliases })
+        Ok(Self {
+            prefix,
+            description,
+            parser,
+            name,
+            aliases,
+            hidden,
+            hidden_aliases,
+            category,
+            localized,
+        })
     }
 
     pub fn get_prefixed_command(&self) -> String {
@@ -22,6 +22,9 @@ pub(crate) struct CommandAttrs {
     pub command_separator: Option<(String, Span)>,
     pub hide: Option<((), Span)>,
     pub hide_aliases: Option<((), Span)>,
+    pub category: Option<(String, Span)>,
+    /// Pairs of a language code and the description in this language
+    pub localized: Vec<(String, String, Span)>,
 }
 
 /// A single k/v attribute for `BotCommands` derive macro.
@@ -51,6 +54,9 @@ enum CommandAttrKind {
     CommandSeparator(String),
     Hide,
     HideAliases,
+    Category(String),
+    /// A language code and the description in this language
+    Localized(String, String),
 }
 
 impl CommandAttrs {
@@ -72,6 +78,8 @@ impl CommandAttrs {
                 command_separator: None,
                 hide: None,
                 hide_aliases: None,
+                category: None,
+                localized: Vec::new(),
             },
             |mut this, attr| {
                 fn insert<T>(opt: &mut Option<(T, Span)>, x: T, sp: Span) -> Result<()> {
@@ -119,6 +127,14 @@ impl CommandAttrs {
                     CommandSeparator(s) => insert(&mut this.command_separator, s, attr.sp),
                     Hide => insert(&mut this.hide, (), attr.sp),
                     HideAliases => insert(&mut this.hide_aliases, (), attr.sp),
+                    Category(c) => insert(&mut this.category, c, attr.sp),
+                    Localized(lang, d) => {
+                        if this.localized.iter().any(|(l, ..)| *l == lang) {
+                            return Err(compile_error_at("duplicate attribute", attr.sp));
+                        }
+                        this.localized.push((lang, d, attr.sp));
+                        Ok(())
+                    }
                 }?;
 
                 Ok(this)
@@ -156,6 +172,27 @@ impl CommandAttr {
                     ));
                 };
 
+                if attr == "localized" {
+                    let Some(lang) = key.pop() else {
+                        return Err(compile_error_at(
+                            "expected a language code, e.g. `localized(uk = \"...\")`",
+                            attr.span(),
+                        ));
+                    };
+
+                    if let Some(unexpected_key) = key.last() {
+                        return Err(compile_error_at(
+                            &format!("{lang} can't have nested attributes"),
+                            unexpected_key.span(),
+                        ));
+                    }
+
+                    // Language codes like `pt-br` can't be identifiers, so they are written as
+                    // `pt_br`
+                    let lang = lang.to_string().replace('_', "-");
+                    return Ok(Self { kind: Localized(lang, value.expect_string()?), sp });
+                }
+
                 if let Some(unexpected_key) = key.last() {
                     return Err(compile_error_at(
                         &format!("{attr} can't have nested attributes"),
@@ -175,6 +212,7 @@ impl CommandAttr {
                     "command_separator" => CommandSeparator(value.expect_string()?),
                     "hide" => value.expect_none("hide").map(|_| Hide)?,
                     "hide_aliases" => value.expect_none("hide_aliases").map(|_| HideAliases)?,
+                    "category" => Category(value.expect_string()?),
                     "alias" => Aliases(vec![value.expect_string()?]),
                     "aliases" => Aliases(
                         value
@@ -186,7 +224,8 @@ impl CommandAttr {
                     _ => {
                         return Err(compile_error_at(
                             "unexpected attribute name (expected one of `prefix`, `description`, \
-                             `rename`, `parse_with`, `separator`, `hide`, `alias` and `aliases`",
+                             `rename`, `parse_with`, `separator`, `hide`, `alias`, `aliases`, \
+                             `category` and `localized`",
                             attr.span(),
                         ))
                     }
@@ -41,9 +41,17 @@ impl CommandEnum {
             separator,
             hide,
             hide_aliases,
+            category,
+            localized,
         } = attrs;
 
-        variants_only_attr![rename, hide, hide_aliases, aliases];
+        variants_only_attr![rename, hide, hide_aliases, aliases, category];
+        if let Some(&(.., sp)) = localized.first() {
+            return Err(compile_error_at(
+                "`localized` attribute can only be applied to enums *variants*",
+                sp,
+            ));
+        }
 
         let mut parser = parser.map(|(p, _)| p).unwrap_or(ParserType::Default);
 
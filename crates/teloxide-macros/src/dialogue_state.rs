@@ -0,0 +1,152 @@
+use crate::{
+    attr::{fold_attrs, Attr, AttrValue},
+    error::{compile_error, compile_error_at},
+    Result,
+};
+
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, ToTokens};
+use syn::{Attribute, DeriveInput, Path, Type};
+
+pub(crate) fn dialogue_state_impl(input: DeriveInput) -> Result<TokenStream> {
+    let data_enum = match &input.data {
+        syn::Data::Enum(data) => data,
+        _ => return Err(compile_error("`DialogueState` is only allowed for enums")),
+    };
+
+    let attrs = DialogueAttrs::from_attributes(&input.attrs)?;
+    if let Some((_, sp)) = attrs.on_enter.as_ref().or(attrs.on_exit.as_ref()) {
+        return Err(compile_error_at(
+            "`on_enter` and `on_exit` attributes can only be applied to enums *variants*",
+            *sp,
+        ));
+    }
+
+    let mut on_enter = Vec::new();
+    let mut on_exit = Vec::new();
+    for variant in &data_enum.variants {
+        let variant_attrs = DialogueAttrs::from_attributes(&variant.attrs)?;
+        if let Some((_, sp)) = variant_attrs.context.as_ref().or(variant_attrs.error.as_ref()) {
+            return Err(compile_error_at(
+                "`context` and `error` attributes can only be applied to enums",
+                *sp,
+            ));
+        }
+
+        let variant_name = &variant.ident;
+        if let Some((hook, _)) = variant_attrs.on_enter {
+            on_enter.push(quote! { Self::#variant_name { .. } => ::std::boxed::Box::pin(#hook(ctx, chat_id, ::std::clone::Clone::clone(self))), });
+        }
+        if let Some((hook, _)) = variant_attrs.on_exit {
+            on_exit.push(quote! { Self::#variant_name { .. } => ::std::boxed::Box::pin(#hook(ctx, chat_id, ::std::clone::Clone::clone(self))), });
+        }
+    }
+
+    let type_name = &input.ident;
+    let context = attrs.context.map_or_else(|| quote! { teloxide::Bot }, |(ty, _)| ty);
+    let error = attrs.error.map_or_else(|| quote! { teloxide::RequestError }, |(ty, _)| ty);
+    let fn_on_enter = impl_hook(quote! { on_enter }, &on_enter);
+    let fn_on_exit = impl_hook(quote! { on_exit }, &on_exit);
+
+    Ok(quote! {
+        impl teloxide::dispatching::dialogue::DialogueState for #type_name {
+            type Context = #context;
+            type Error = #error;
+
+            #fn_on_enter
+            #fn_on_exit
+        }
+    })
+}
+
+fn impl_hook(name: TokenStream, arms: &[TokenStream]) -> TokenStream {
+    if arms.is_empty() {
+        return quote! {};
+    }
+
+    quote! {
+        #[allow(unreachable_patterns)]
+        fn #name(
+            &self,
+            ctx: Self::Context,
+            chat_id: teloxide::types::ChatId,
+        ) -> ::std::pin::Pin<::std::boxed::Box<
+            dyn ::std::future::Future<Output = ::std::result::Result<(), Self::Error>>
+                + ::std::marker::Send
+                + 'static,
+        >> {
+            match self {
+                #(#arms)*
+                _ => ::std::boxed::Box::pin(async { ::std::result::Result::Ok(()) }),
+            }
+        }
+    }
+}
+
+/// All attributes that can be used for `derive(DialogueState)`
+struct DialogueAttrs {
+    context: Option<(TokenStream, Span)>,
+    error: Option<(TokenStream, Span)>,
+    on_enter: Option<(Path, Span)>,
+    on_exit: Option<(Path, Span)>,
+}
+
+impl DialogueAttrs {
+    fn from_attributes(attributes: &[Attribute]) -> Result<Self> {
+        fold_attrs(
+            attributes,
+            |attr| attr.path().is_ident("dialogue"),
+            Ok,
+            Self { context: None, error: None, on_enter: None, on_exit: None },
+            |mut this, attr: Attr| {
+                fn insert<T>(opt: &mut Option<(T, Span)>, x: T, sp: Span) -> Result<()> {
+                    match opt {
+                        slot @ None => {
+                            *slot = Some((x, sp));
+                            Ok(())
+                        }
+                        Some(_) => Err(compile_error_at("duplicate attribute", sp)),
+                    }
+                }
+
+                let sp = attr.span();
+                let Attr { mut key, value } = attr;
+                key.pop(); // `dialogue`
+
+                let Some(name) = key.pop() else {
+                    return Err(compile_error_at("expected an attribute name", sp));
+                };
+                if let Some(unexpected_key) = key.last() {
+                    return Err(compile_error_at(
+                        &format!("{name} can't have nested attributes"),
+                        unexpected_key.span(),
+                    ));
+                }
+
+                match &*name.to_string() {
+                    "context" => insert(&mut this.context, parse_type(value)?, sp),
+                    "error" => insert(&mut this.error, parse_type(value)?, sp),
+                    "on_enter" => insert(&mut this.on_enter, value.expect_path()?, sp),
+                    "on_exit" => insert(&mut this.on_exit, value.expect_path()?, sp),
+                    _ => Err(compile_error_at(
+                        "unexpected attribute name (expected one of `context`, `error`, \
+                         `on_enter` and `on_exit`)",
+                        name.span(),
+                    )),
+                }?;
+
+                Ok(this)
+            },
+        )
+    }
+}
+
+/// Parses a type, which is either a path or a string (for types that are not
+/// paths, e.g. `"Box<dyn Error + Send + Sync>"`).
+fn parse_type(value: AttrValue) -> Result<TokenStream> {
+    match value {
+        AttrValue::Path(path) => Ok(path.into_token_stream()),
+        AttrValue::Lit(syn::Lit::Str(s)) => Ok(s.parse::<Type>()?.into_token_stream()),
+        other => other.expect("a type", Err),
+    }
+}
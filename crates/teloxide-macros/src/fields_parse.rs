@@ -84,8 +84,7 @@ fn create_parser<'a>(
                 quote! {
                     (
                         |s: ::std::string::String| {
-                            let res = <#ty>::from_str(&s)
-                                .map_err(|e| teloxide::utils::command::ParseError::IncorrectFormat(e.into()))?;
+                            let res = teloxide::utils::command::parse_arg::<#ty>(&s, 1)?;
 
                             ::std::result::Result::Ok((res,))
                         }
@@ -114,6 +113,7 @@ fn parser_with_separator<'a>(
     let expected = types.len();
     let res = {
         let found = 0usize..;
+        let position = 1usize..;
         quote! {
             (
                 #(
@@ -124,7 +124,7 @@ fn parser_with_separator<'a>(
                             message: format!("Expected but not found arg number {}", #found + 1),
                         })?;
 
-                        <#types>::from_str(s).map_err(|e| teloxide::utils::command::ParseError::IncorrectFormat(e.into()))?
+                        teloxide::utils::command::parse_arg::<#types>(s, #position)?
                     },
                 )*
             )
@@ -134,7 +134,7 @@ fn parser_with_separator<'a>(
     let res = quote! {
         (
             |s: ::std::string::String| {
-                let mut splitted = s.split(#separator);
+                let mut splitted = teloxide::utils::command::split_args(&s, #separator);
 
                 let res = #res;
 
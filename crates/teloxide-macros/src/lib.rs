@@ -5,6 +5,7 @@ mod bot_commands;
 mod command;
 mod command_attr;
 mod command_enum;
+mod dialogue_state;
 mod error;
 mod fields_parse;
 mod rename_rules;
@@ -13,7 +14,7 @@ mod unzip;
 pub(crate) use error::{compile_error, Result};
 use syn::{parse_macro_input, DeriveInput};
 
-use crate::bot_commands::bot_commands_impl;
+use crate::{bot_commands::bot_commands_impl, dialogue_state::dialogue_state_impl};
 use proc_macro::TokenStream;
 
 #[proc_macro_derive(BotCommands, attributes(command))]
@@ -22,3 +23,10 @@ pub fn bot_commands_derive(tokens: TokenStream) -> TokenStream {
 
     bot_commands_impl(input).unwrap_or_else(<_>::into).into()
 }
+
+#[proc_macro_derive(DialogueState, attributes(dialogue))]
+pub fn dialogue_state_derive(tokens: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(tokens as DeriveInput);
+
+    dialogue_state_impl(input).unwrap_or_else(<_>::into).into()
+}
@@ -74,9 +74,8 @@ pub async fn start(
         bot.send_message(
             msg.chat.id,
             format!(
-                "Hello!\n\nThis link allows anyone to message you secretly: {}?start={}",
-                me.tme_url(),
-                msg.chat.id
+                "Hello!\n\nThis link allows anyone to message you secretly: {}",
+                me.tme_url_with_start(&msg.chat.id.to_string())
             ),
         )
         .await?;
@@ -118,9 +117,8 @@ pub async fn send_message(
                 bot.send_message(
                     msg.chat.id,
                     format!(
-                        "Message sent!\n\nYour link is: {}?start={}",
-                        me.tme_url(),
-                        msg.chat.id
+                        "Message sent!\n\nYour link is: {}",
+                        me.tme_url_with_start(&msg.chat.id.to_string())
                     ),
                 )
                 .await?;
@@ -13,6 +13,29 @@ pub fn exponential_backoff_strategy(error_count: u32) -> Duration {
     Duration::from_secs(1_u64 << error_count.min(6))
 }
 
+/// Returns an exponential strategy with base 2, which starts at 1s and is
+/// limited to `max`.
+///
+/// ## Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use teloxide::{backoff::capped_exponential_backoff_strategy, update_listeners::Polling, Bot};
+///
+/// let listener = Polling::builder(Bot::new("TOKEN"))
+///     .backoff_strategy(capped_exponential_backoff_strategy(Duration::from_secs(10)))
+///     .build();
+/// ```
+pub fn capped_exponential_backoff_strategy(
+    max: Duration,
+) -> impl Fn(u32) -> Duration + Send + Sync + Clone + 'static {
+    move |error_count| {
+        // Past 2^31s the duration is larger than any sensible `max`
+        Duration::from_secs(1_u64 << error_count.min(31)).min(max)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -29,4 +52,19 @@ mod tests {
             assert_eq!(exponential_backoff_strategy(error_count), expected);
         }
     }
+
+    #[test]
+    fn test_capped_exponential_backoff_strategy() {
+        let strategy = capped_exponential_backoff_strategy(Duration::from_secs(10));
+        let cases = [
+            (0, Duration::from_secs(1)),
+            (3, Duration::from_secs(8)),
+            (4, Duration::from_secs(10)),
+            (u32::MAX, Duration::from_secs(10)),
+        ];
+
+        for (error_count, expected) in cases {
+            assert_eq!(strategy(error_count), expected);
+        }
+    }
 }
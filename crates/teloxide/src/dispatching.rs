@@ -216,21 +216,46 @@
 
 pub mod dialogue;
 
+mod basic_commands;
+mod chat_events;
+mod cooldown;
 mod dispatcher;
 mod distribution;
 mod filter_ext;
 mod handler_description;
 mod handler_ext;
+mod language;
+mod named;
+mod reactions;
+mod sharding;
+mod task_tracker;
 
 #[cfg(feature = "tracing")]
 mod tracing;
 
 pub use crate::utils::shutdown_token::{IdleShutdownError, ShutdownToken};
-pub use dispatcher::{Dispatcher, DispatcherBuilder, UpdateHandler};
+pub use basic_commands::BasicCommands;
+pub use chat_events::{filter_chat_event, ChatEvent, ChatEventKind};
+pub use cooldown::{cooldown, CommandCooldown, CooldownScope};
+pub use dispatcher::{
+    Dispatcher, DispatcherBuilder, DispatcherConfig, GetMeFallback, HandlerReplacer,
+    ReplaceHandlerError, UnhandledUpdatePolicy, UnhandledUpdates, UpdateHandler, UpdateOutcome,
+};
 pub use distribution::DefaultKey;
-pub use filter_ext::{MessageFilterExt, UpdateFilterExt};
+pub use filter_ext::{
+    ChatMemberUpdatedFilterExt, MessageFilterExt, PollAnswerFilterExt, UpdateFilterExt,
+};
 pub use handler_description::DpHandlerDescription;
-pub use handler_ext::{filter_command, filter_mention_command, HandlerExt};
+pub use handler_ext::{
+    filter_callback_data, filter_command, filter_mention_command, filter_mentions_me, HandlerExt,
+};
+pub use language::{
+    detect_language, DetectedLang, LangSource, LanguageDetection, LanguageDetector,
+};
+pub use named::{current_handler_name, HandlerMetrics, HandlerStats};
+pub use reactions::filter_admin_reaction;
+pub use sharding::{shard_filter, shard_of};
+pub use task_tracker::TaskTracker;
 
 #[cfg(feature = "tracing")]
 pub use self::tracing::UpdateHandlerTracingExt;
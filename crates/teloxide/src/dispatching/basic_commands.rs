@@ -0,0 +1,273 @@
+use std::{future::Future, sync::Arc};
+
+use dptree::Handler;
+use futures::future::BoxFuture;
+
+use crate::{
+    dispatching::DpHandlerDescription,
+    requests::Requester,
+    types::{BotCommand, Me, Message},
+    utils::command::{parse_command, BotCommands},
+};
+
+/// Produces the text of a reply from the message with the command, the bot
+/// and the argument of the command (the deep link payload for `/start`).
+type Reply = Arc<dyn Fn(Message, Me, Option<String>) -> BoxFuture<'static, String> + Send + Sync>;
+
+/// Handlers of the `/start`, `/help` and `/privacy` commands, which Telegram
+/// [asks every bot to support][guidelines].
+///
+/// Replies can be static texts, or computed by async functions. Only the
+/// commands which are configured are handled, so e.g. a bot which has its own
+/// `/start` handler can use this only for `/help` and `/privacy`.
+///
+/// [`BasicCommands::into_handler`] makes a handler out of the commands, which
+/// requires [`Message`], [`Me`] and the bot. Replies are sent as plain text
+/// to the same chat, use the [`DefaultParseMode`] adaptor to format them.
+///
+/// [guidelines]: https://core.telegram.org/bots/features#global-commands
+/// [`DefaultParseMode`]: crate::adaptors::DefaultParseMode
+///
+/// ## Examples
+///
+/// ```no_run
+/// # #[cfg(feature = "macros")] {
+/// use teloxide::{dispatching::BasicCommands, prelude::*, utils::command::BotCommands};
+///
+/// #[derive(BotCommands, Clone)]
+/// #[command(rename_rule = "lowercase")]
+/// enum Command {
+///     /// Show this text.
+///     Help,
+///     /// Roll a die.
+///     Roll,
+/// }
+///
+/// # async fn run() {
+/// let bot = Bot::from_env();
+///
+/// let basic = BasicCommands::new()
+///     .start_with(|_msg, payload| async move {
+///         match payload {
+///             Some(referrer) => format!("Hi! You were invited by {referrer}."),
+///             None => "Hi!".to_owned(),
+///         }
+///     })
+///     .help_from_commands::<Command>()
+///     .privacy("This bot doesn't store any data.");
+///
+/// bot.set_my_commands(basic.bot_commands()).await.unwrap();
+///
+/// let handler = Update::filter_message()
+///     .branch(basic.into_handler::<Bot, teloxide::RequestError>())
+///     .branch(dptree::entry().filter_command::<Command>().endpoint(|| async { respond(()) }));
+///
+/// Dispatcher::builder(bot, handler).build().dispatch().await;
+/// # }
+/// # }
+/// ```
+#[derive(Clone, Default)]
+#[must_use]
+pub struct BasicCommands {
+    start: Option<Reply>,
+    help: Option<Reply>,
+    privacy: Option<Reply>,
+}
+
+impl BasicCommands {
+    /// Creates an empty set of commands, which doesn't handle anything.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replies to `/start` with `text`.
+    pub fn start<T>(self, text: T) -> Self
+    where
+        T: Into<String>,
+    {
+        Self { start: Some(static_reply(text.into())), ..self }
+    }
+
+    /// Replies to `/start` with the text returned by `f`.
+    ///
+    /// `f` receives the message and the [deep link] payload, if there is one.
+    ///
+    /// [deep link]: https://core.telegram.org/bots/features#deep-linking
+    pub fn start_with<F, Fut>(self, f: F) -> Self
+    where
+        F: Fn(Message, Option<String>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = String> + Send + 'static,
+    {
+        Self { start: Some(Arc::new(move |msg, _me, payload| Box::pin(f(msg, payload)))), ..self }
+    }
+
+    /// Replies to `/help` with `text`.
+    pub fn help<T>(self, text: T) -> Self
+    where
+        T: Into<String>,
+    {
+        Self { help: Some(static_reply(text.into())), ..self }
+    }
+
+    /// Replies to `/help` with the descriptions of commands `C`.
+    ///
+    /// The descriptions are in the language of the user's Telegram client, if
+    /// the commands have descriptions in it. See [`BotCommands::descriptions`].
+    pub fn help_from_commands<C>(self) -> Self
+    where
+        C: BotCommands,
+    {
+        Self {
+            help: Some(Arc::new(|msg, me, _payload| {
+                let descriptions = C::descriptions().username_from_me(&me);
+                let text = match msg.from.as_ref().and_then(|user| user.language_code.as_deref()) {
+                    Some(locale) => descriptions.locale(locale).to_string(),
+                    None => descriptions.to_string(),
+                };
+                Box::pin(async move { text })
+            })),
+            ..self
+        }
+    }
+
+    /// Replies to `/help` with the text returned by `f`.
+    pub fn help_with<F, Fut>(self, f: F) -> Self
+    where
+        F: Fn(Message) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = String> + Send + 'static,
+    {
+        Self { help: Some(Arc::new(move |msg, _me, _payload| Box::pin(f(msg)))), ..self }
+    }
+
+    /// Replies to `/privacy` with `text`.
+    pub fn privacy<T>(self, text: T) -> Self
+    where
+        T: Into<String>,
+    {
+        Self { privacy: Some(static_reply(text.into())), ..self }
+    }
+
+    /// Replies to `/privacy` with the text returned by `f`.
+    pub fn privacy_with<F, Fut>(self, f: F) -> Self
+    where
+        F: Fn(Message) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = String> + Send + 'static,
+    {
+        Self { privacy: Some(Arc::new(move |msg, _me, _payload| Box::pin(f(msg)))), ..self }
+    }
+
+    /// Returns the configured commands, to be passed to
+    /// [`Requester::set_my_commands`].
+    #[must_use]
+    pub fn bot_commands(&self) -> Vec<BotCommand> {
+        [
+            (&self.start, "start", "Start the bot"),
+            (&self.help, "help", "Show help"),
+            (&self.privacy, "privacy", "Show the privacy policy"),
+        ]
+        .into_iter()
+        .filter(|(reply, ..)| reply.is_some())
+        .map(|(_, command, description)| BotCommand::new(command, description))
+        .collect()
+    }
+
+    /// Makes a handler which replies to the configured commands, passing other
+    /// messages forwards.
+    ///
+    /// The handler requires [`Message`], [`Me`] and `R`.
+    #[must_use]
+    pub fn into_handler<R, Err>(self) -> Handler<'static, Result<(), Err>, DpHandlerDescription>
+    where
+        R: Requester + Clone + Send + Sync + 'static,
+        Err: From<R::Err> + Send + Sync + 'static,
+    {
+        dptree::filter_map(move |msg: Message, me: Me| {
+            let (command, args) = parse_command(msg.text()?, me.username())?;
+            let reply = match command {
+                "start" => self.start.clone(),
+                "help" => self.help.clone(),
+                "privacy" => self.privacy.clone(),
+                _ => None,
+            }?;
+
+            Some(Matched { reply, arg: args.first().map(|arg| (*arg).to_owned()) })
+        })
+        .endpoint(
+            |Matched { reply, arg }: Matched, bot: R, msg: Message, me: Me| async move {
+                let text = reply(msg.clone(), me, arg).await;
+                bot.send_message(msg.chat.id, text).await?;
+                Ok(())
+            },
+        )
+    }
+}
+
+/// A command that should be replied to.
+#[derive(Clone)]
+struct Matched {
+    reply: Reply,
+    arg: Option<String>,
+}
+
+fn static_reply(text: String) -> Reply {
+    Arc::new(move |_msg, _me, _payload| {
+        let text = text.clone();
+        Box::pin(async move { text })
+    })
+}
+
+#[cfg(test)]
+#[cfg(feature = "macros")]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::{
+        self as teloxide,
+        test_utils::{me, message_from_text, unreachable_bot},
+        Bot, RequestError,
+    };
+
+    #[derive(BotCommands, Clone)]
+    #[command(rename_rule = "lowercase")]
+    enum Command {
+        /// Roll a die.
+        Roll,
+    }
+
+    #[tokio::test]
+    async fn basic_commands() {
+        let payloads = Arc::new(Mutex::new(Vec::new()));
+        let basic = BasicCommands::new()
+            .start_with({
+                let payloads = Arc::clone(&payloads);
+                move |_msg, payload| {
+                    payloads.lock().unwrap().push(payload);
+                    async { "Hi!".to_owned() }
+                }
+            })
+            .help_from_commands::<Command>();
+
+        assert_eq!(
+            basic.bot_commands(),
+            [BotCommand::new("start", "Start the bot"), BotCommand::new("help", "Show help")]
+        );
+
+        let me = me();
+        // Sending replies fails, so handled commands result in errors
+        let bot = unreachable_bot();
+        let handler = basic.into_handler::<Bot, RequestError>();
+        let send = |text: &str| {
+            handler.dispatch(dptree::deps![message_from_text(text), me.clone(), bot.clone()])
+        };
+
+        assert!(send("/start").await.is_break());
+        assert!(send("/start@bot ref_42").await.is_break());
+        assert!(send("/help").await.is_break());
+        assert!(send("/privacy").await.is_continue());
+        assert!(send("/start@other_bot").await.is_continue());
+        assert!(send("start").await.is_continue());
+
+        assert_eq!(*payloads.lock().unwrap(), [None, Some("ref_42".to_owned())]);
+    }
+}
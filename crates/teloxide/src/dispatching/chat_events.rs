@@ -0,0 +1,258 @@
+use chrono::{DateTime, Utc};
+use dptree::Handler;
+use teloxide_core::types::{
+    Chat, ChatMemberUpdated, MaybeAnonymousUser, Message, MessageId, PhotoSize, ReactionType,
+    Seconds, Update, UpdateKind, User,
+};
+
+use crate::dispatching::DpHandlerDescription;
+
+/// An event that happened in a chat, such as a member joining, a reaction
+/// being changed or a message being pinned.
+///
+/// This unifies updates that are interesting for audit logs and moderation
+/// bots, which are otherwise spread between multiple [`UpdateKind`]s and
+/// service messages. Use [`filter_chat_event`] to handle them in a single
+/// branch.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChatEvent {
+    /// The chat where the event happened.
+    pub chat: Chat,
+
+    /// When the event happened.
+    ///
+    /// `None` for events that Telegram doesn't timestamp (deletions of
+    /// business messages).
+    pub date: Option<DateTime<Utc>>,
+
+    /// Who caused the event, if known.
+    pub actor: Option<MaybeAnonymousUser>,
+
+    /// What happened.
+    pub kind: ChatEventKind,
+}
+
+/// A kind of a [`ChatEvent`].
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum ChatEventKind {
+    /// The status of a chat member (including the bot itself) was changed.
+    MemberUpdated(Box<ChatMemberUpdated>),
+
+    /// New members were added to the chat or joined it.
+    MembersJoined(Vec<User>),
+
+    /// A member was removed from the chat or left it.
+    MemberLeft(User),
+
+    /// Reactions to a message were changed by a user.
+    ReactionsChanged { message_id: MessageId, added: Vec<ReactionType>, removed: Vec<ReactionType> },
+
+    /// Messages were deleted from a connected business account.
+    MessagesDeleted(Vec<MessageId>),
+
+    /// A message was pinned.
+    MessagePinned(MessageId),
+
+    /// The chat title was changed.
+    TitleChanged(String),
+
+    /// The chat photo was changed.
+    PhotoChanged(Vec<PhotoSize>),
+
+    /// The chat photo was deleted.
+    PhotoDeleted,
+
+    /// The auto-delete timer was changed. `None` means that it was disabled.
+    AutoDeleteTimerChanged(Option<Seconds>),
+}
+
+impl ChatEvent {
+    /// Extracts a chat event from an update.
+    ///
+    /// Returns `None` if the update doesn't describe a chat event.
+    #[must_use]
+    pub fn from_update(update: &Update) -> Option<Self> {
+        match &update.kind {
+            UpdateKind::ChatMember(upd) | UpdateKind::MyChatMember(upd) => Some(Self {
+                chat: upd.chat.clone(),
+                date: Some(upd.date),
+                actor: Some(MaybeAnonymousUser::User(upd.from.clone())),
+                kind: ChatEventKind::MemberUpdated(Box::new(upd.clone())),
+            }),
+            UpdateKind::MessageReaction(upd) => Some(Self {
+                chat: upd.chat.clone(),
+                date: Some(upd.date),
+                actor: Some(upd.actor.clone()),
+                kind: ChatEventKind::ReactionsChanged {
+                    message_id: upd.message_id,
+                    added: upd.added_reactions().cloned().collect(),
+                    removed: upd.removed_reactions().cloned().collect(),
+                },
+            }),
+            UpdateKind::DeletedBusinessMessages(upd) => Some(Self {
+                chat: upd.chat.clone(),
+                date: None,
+                actor: None,
+                kind: ChatEventKind::MessagesDeleted(upd.message_ids.clone()),
+            }),
+            UpdateKind::Message(message) | UpdateKind::ChannelPost(message) => {
+                Self::from_message(message)
+            }
+            _ => None,
+        }
+    }
+
+    /// Extracts a chat event from a service message.
+    ///
+    /// Returns `None` if the message isn't a service message describing a chat
+    /// event.
+    #[must_use]
+    pub fn from_message(message: &Message) -> Option<Self> {
+        let kind = if let Some(users) = message.new_chat_members() {
+            ChatEventKind::MembersJoined(users.to_vec())
+        } else if let Some(user) = message.left_chat_member() {
+            ChatEventKind::MemberLeft(user.clone())
+        } else if let Some(pinned) = message.pinned_message() {
+            ChatEventKind::MessagePinned(pinned.id())
+        } else if let Some(title) = message.new_chat_title() {
+            ChatEventKind::TitleChanged(title.to_owned())
+        } else if let Some(photo) = message.new_chat_photo() {
+            ChatEventKind::PhotoChanged(photo.to_vec())
+        } else if message.delete_chat_photo().is_some() {
+            ChatEventKind::PhotoDeleted
+        } else if let Some(changed) = message.message_auto_delete_timer_changed() {
+            ChatEventKind::AutoDeleteTimerChanged(changed.auto_delete_timer())
+        } else {
+            return None;
+        };
+
+        let actor = match (&message.sender_chat, &message.from) {
+            (Some(chat), _) => Some(MaybeAnonymousUser::Chat(chat.clone())),
+            (None, Some(user)) => Some(MaybeAnonymousUser::User(user.clone())),
+            (None, None) => None,
+        };
+
+        Some(Self { chat: message.chat.clone(), date: Some(message.date), actor, kind })
+    }
+}
+
+/// Returns a handler that accepts updates describing a [`ChatEvent`] and
+/// passes the event forwards.
+///
+/// See [`ChatEvent::from_update`] for the list of supported updates.
+///
+/// ## Dependency requirements
+///
+///  - [`crate::types::Update`]
+#[must_use]
+pub fn filter_chat_event<Output>() -> Handler<'static, Output, DpHandlerDescription>
+where
+    Output: Send + Sync + 'static,
+{
+    dptree::filter_map(|update: Update| ChatEvent::from_update(&update))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::ControlFlow;
+
+    use dptree::deps;
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::test_utils::{chat_member_updated, message_json, update};
+
+    /// Returns a message of the user `2` in a supergroup, with the fields of
+    /// `extra` instead of the text.
+    fn group_message(extra: Value) -> Update {
+        let mut msg = message_json(2, "");
+        msg.as_object_mut().unwrap().remove("text");
+        msg["chat"] = json!({ "id": -1001, "title": "A", "type": "supergroup" });
+        msg["date"] = 1721306082.into();
+        msg.as_object_mut().unwrap().extend(extra.as_object().unwrap().clone());
+        update(1, "message", msg)
+    }
+
+    #[test]
+    fn member_updated() {
+        let upd = chat_member_updated(json!({ "status": "left" }), json!({ "status": "member" }));
+        let event = ChatEvent::from_update(&Update {
+            id: teloxide_core::types::UpdateId(1),
+            kind: UpdateKind::ChatMember(upd.clone()),
+        })
+        .unwrap();
+
+        assert_eq!(event.chat.id.0, -1);
+        assert_eq!(event.date.unwrap().timestamp(), 1);
+        assert_eq!(event.actor, Some(MaybeAnonymousUser::User(upd.from.clone())));
+        assert_eq!(event.kind, ChatEventKind::MemberUpdated(Box::new(upd)));
+    }
+
+    #[test]
+    fn reactions_changed() {
+        let upd = update(
+            1,
+            "message_reaction",
+            json!({
+                "chat": { "id": -1001, "title": "A", "type": "supergroup" },
+                "message_id": 35,
+                "user": { "id": 2, "is_bot": false, "first_name": "B" },
+                "date": 1721306082,
+                "old_reaction": [{ "type": "emoji", "emoji": "👍" }],
+                "new_reaction": [{ "type": "emoji", "emoji": "🔥" }]
+            }),
+        );
+
+        let event = ChatEvent::from_update(&upd).unwrap();
+        assert_eq!(event.chat.id.0, -1001);
+        assert_eq!(event.date.unwrap().timestamp(), 1721306082);
+        assert!(event.actor.unwrap().is_user());
+        assert_eq!(
+            event.kind,
+            ChatEventKind::ReactionsChanged {
+                message_id: MessageId(35),
+                added: vec![ReactionType::Emoji { emoji: "🔥".to_owned() }],
+                removed: vec![ReactionType::Emoji { emoji: "👍".to_owned() }],
+            }
+        );
+    }
+
+    #[test]
+    fn service_messages() {
+        let upd = group_message(json!({ "new_chat_title": "C" }));
+        let event = ChatEvent::from_update(&upd).unwrap();
+        assert_eq!(event.kind, ChatEventKind::TitleChanged("C".to_owned()));
+
+        let upd = group_message(json!({ "text": "hi" }));
+        assert_eq!(ChatEvent::from_update(&upd), None);
+    }
+
+    #[test]
+    fn deleted_business_messages() {
+        let upd = update(
+            1,
+            "deleted_business_messages",
+            json!({
+                "business_connection_id": "abc",
+                "chat": { "id": 5, "first_name": "B", "type": "private" },
+                "message_ids": [1, 2]
+            }),
+        );
+
+        let event = ChatEvent::from_update(&upd).unwrap();
+        assert_eq!(event.date, None);
+        assert_eq!(event.kind, ChatEventKind::MessagesDeleted(vec![MessageId(1), MessageId(2)]));
+    }
+
+    #[tokio::test]
+    async fn filter() {
+        let upd = group_message(json!({ "delete_chat_photo": true }));
+
+        let handler = filter_chat_event().endpoint(|event: ChatEvent| async move { event.kind });
+        assert_eq!(
+            handler.dispatch(deps![upd]).await,
+            ControlFlow::Break(ChatEventKind::PhotoDeleted)
+        );
+    }
+}
@@ -0,0 +1,256 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use dptree::Handler;
+
+use crate::{
+    dispatching::DpHandlerDescription,
+    requests::Requester,
+    types::{ChatId, Me, Message, UserId},
+    utils::command::parse_command,
+};
+
+/// Creates a cooldown for the command `command`, see [`CommandCooldown`].
+///
+/// `command` may be given with or without the leading slash.
+pub fn cooldown<S>(command: S, duration: Duration) -> CommandCooldown
+where
+    S: Into<String>,
+{
+    let command = command.into();
+    let command = command.strip_prefix('/').map(str::to_owned).unwrap_or(command);
+
+    CommandCooldown {
+        command,
+        duration,
+        scope: CooldownScope::default(),
+        reply: None,
+        last_used: Arc::default(),
+    }
+}
+
+/// Who shares a [`CommandCooldown`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum CooldownScope {
+    /// Each user has their own cooldown in all chats (**default**).
+    #[default]
+    User,
+
+    /// All users of a chat share the cooldown.
+    Chat,
+
+    /// Each user has their own cooldown in each chat.
+    UserInChat,
+}
+
+/// A guard which throttles a command per user or per chat.
+///
+/// The handler created by [`CommandCooldown::into_handler`] must be placed
+/// before the handler of the command. When the command is used, the guard
+/// either starts the cooldown and lets the update through, or, if the
+/// cooldown hasn't expired yet, stops the update, optionally replying with the
+/// remaining time.
+///
+/// This is about the rate at which users may use a command, e.g. in game or
+/// economy bots, and is unrelated to the rate limits of the Bot API (see the
+/// `Throttle` adaptor for these).
+///
+/// Clones share the same state.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use teloxide::{dispatching::cooldown, prelude::*};
+///
+/// # async fn run() {
+/// let bot = Bot::from_env();
+///
+/// let handler = Update::filter_message()
+///     .branch(
+///         cooldown("daily", Duration::from_secs(24 * 60 * 60))
+///             .reply()
+///             .into_handler::<Bot, teloxide::RequestError>(),
+///     )
+///     .branch(dptree::filter(|msg: Message| msg.text() == Some("/daily")).endpoint(
+///         |bot: Bot, msg: Message| async move {
+///             bot.send_message(msg.chat.id, "Here are your 100 coins!").await?;
+///             Ok(())
+///         },
+///     ));
+///
+/// Dispatcher::builder(bot, handler).build().dispatch().await;
+/// # }
+/// ```
+#[derive(Clone)]
+#[must_use]
+pub struct CommandCooldown {
+    command: String,
+    duration: Duration,
+    scope: CooldownScope,
+    reply: Option<Reply>,
+    last_used: Arc<Mutex<HashMap<CooldownKey, Instant>>>,
+}
+
+type Reply = Arc<dyn Fn(Duration) -> String + Send + Sync>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum CooldownKey {
+    User(UserId),
+    Chat(ChatId),
+    UserInChat(ChatId, UserId),
+}
+
+#[derive(Clone)]
+struct OnCooldown {
+    reply: Option<Reply>,
+    remaining: Duration,
+}
+
+/// The number of tracked keys after which expired cooldowns are removed.
+const PRUNE_THRESHOLD: usize = 1024;
+
+impl CommandCooldown {
+    /// Sets who shares the cooldown.
+    pub fn scope(self, scope: CooldownScope) -> Self {
+        Self { scope, ..self }
+    }
+
+    /// Replies to commands used during the cooldown with the remaining time,
+    /// e.g. "Please wait 5 seconds before using /roll again.".
+    pub fn reply(self) -> Self {
+        let command = self.command.clone();
+        self.reply_with(move |remaining| {
+            format!(
+                "Please wait {} seconds before using /{command} again.",
+                remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0)
+            )
+        })
+    }
+
+    /// Replies to commands used during the cooldown with the text returned by
+    /// `f`, which receives the remaining time.
+    pub fn reply_with<F>(self, f: F) -> Self
+    where
+        F: Fn(Duration) -> String + Send + Sync + 'static,
+    {
+        Self { reply: Some(Arc::new(f)), ..self }
+    }
+
+    /// Returns a handler, which stops the command during the cooldown.
+    ///
+    /// ## Dependency requirements
+    ///
+    ///  - `R`
+    ///  - [`crate::types::Message`]
+    ///  - [`crate::types::Me`]
+    pub fn into_handler<R, Err>(self) -> Handler<'static, Result<(), Err>, DpHandlerDescription>
+    where
+        R: Requester + Clone + Send + Sync + 'static,
+        Err: From<R::Err> + Send + Sync + 'static,
+    {
+        dptree::filter_map(move |msg: Message, me: Me| {
+            let (command, _) = parse_command(msg.text()?, me.username())?;
+            if command != self.command {
+                return None;
+            }
+
+            let remaining = self.start(self.key(&msg), Instant::now())?;
+            Some(OnCooldown { reply: self.reply.clone(), remaining })
+        })
+        .endpoint(
+            |OnCooldown { reply, remaining }: OnCooldown, bot: R, msg: Message| async move {
+                if let Some(reply) = reply {
+                    bot.send_message(msg.chat.id, reply(remaining)).await?;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    fn key(&self, msg: &Message) -> CooldownKey {
+        match (self.scope, &msg.from) {
+            (CooldownScope::User, Some(user)) => CooldownKey::User(user.id),
+            (CooldownScope::UserInChat, Some(user)) => {
+                CooldownKey::UserInChat(msg.chat.id, user.id)
+            }
+            // Messages sent on behalf of chats have no sender
+            (CooldownScope::Chat, _) | (_, None) => CooldownKey::Chat(msg.chat.id),
+        }
+    }
+
+    /// Starts the cooldown for `key`, or returns the remaining time if it's
+    /// already active.
+    fn start(&self, key: CooldownKey, now: Instant) -> Option<Duration> {
+        let mut last_used = self.last_used.lock().unwrap();
+
+        if let Some(&used) = last_used.get(&key) {
+            let elapsed = now.saturating_duration_since(used);
+            if elapsed < self.duration {
+                return Some(self.duration - elapsed);
+            }
+        }
+
+        if last_used.len() >= PRUNE_THRESHOLD {
+            last_used.retain(|_, &mut used| now.saturating_duration_since(used) < self.duration);
+        }
+        last_used.insert(key, now);
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::me, Bot, RequestError};
+
+    #[tokio::test]
+    async fn command_cooldown() {
+        let me = me();
+        let bot = Bot::new("TOKEN");
+        let handler = cooldown("/roll", Duration::from_millis(100))
+            .scope(CooldownScope::UserInChat)
+            .into_handler::<Bot, RequestError>();
+        let send = |chat: i64, user: u64, text: &str| {
+            let msg: Message = serde_json::from_value(serde_json::json!({
+                "message_id": 1,
+                "date": 0,
+                "chat": {"id": chat, "type": "group", "title": "G"},
+                "from": {"id": user, "is_bot": false, "first_name": "A"},
+                "text": text
+            }))
+            .unwrap();
+            handler.dispatch(dptree::deps![msg, me.clone(), bot.clone()])
+        };
+
+        assert!(send(1, 1, "/roll").await.is_continue());
+        assert!(send(1, 1, "/roll@bot").await.is_break());
+        assert!(send(1, 1, "/help").await.is_continue());
+        assert!(send(1, 2, "/roll").await.is_continue());
+        assert!(send(2, 1, "/roll").await.is_continue());
+
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert!(send(1, 1, "/roll").await.is_continue());
+        assert!(send(1, 1, "/roll").await.is_break());
+    }
+
+    #[test]
+    fn remaining() {
+        let cooldown = cooldown("roll", Duration::from_secs(10));
+        let key = CooldownKey::Chat(ChatId(1));
+        let now = Instant::now();
+
+        assert_eq!(cooldown.start(key, now), None);
+        assert_eq!(cooldown.start(key, now + Duration::from_secs(4)), Some(Duration::from_secs(6)));
+        assert_eq!(cooldown.clone().start(key, now + Duration::from_secs(10)), None);
+        assert_eq!(
+            cooldown.start(key, now + Duration::from_secs(15)),
+            Some(Duration::from_secs(5))
+        );
+    }
+}
@@ -102,7 +102,11 @@ pub use self::{SqliteStorage, SqliteStorageError};
 #[cfg(any(feature = "postgres-storage-nativetls", feature = "postgres-storage-rustls"))]
 pub use self::{PostgresStorage, PostgresStorageError};
 
+pub use config::{enter_with_config, DialogueConfig};
+pub use dialogue_state::{DialogueState, TransitionError};
 pub use get_chat_id::GetChatId;
+pub use scene::{Scene, SceneContext, SceneState};
+pub use state_stack::{enter_stack, StateStack};
 pub use storage::*;
 
 use dptree::Handler;
@@ -112,7 +116,11 @@ use std::{fmt::Debug, marker::PhantomData, sync::Arc};
 
 use super::DpHandlerDescription;
 
+mod config;
+mod dialogue_state;
 mod get_chat_id;
+mod scene;
+mod state_stack;
 mod storage;
 
 const TELOXIDE_DIALOGUE_BEHAVIOUR: &str = "TELOXIDE_DIALOGUE_BEHAVIOUR";
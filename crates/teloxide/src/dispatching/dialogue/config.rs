@@ -0,0 +1,304 @@
+use std::{
+    fmt::Debug,
+    future::Future,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use dptree::Handler;
+use futures::future::BoxFuture;
+use teloxide_core::types::ChatId;
+
+use crate::{
+    dispatching::{
+        dialogue::{self, Dialogue, GetChatId, Storage},
+        DpHandlerDescription,
+    },
+    utils::recent::RecentMap,
+};
+
+/// How many chats are tracked by default, see
+/// [`DialogueConfig::max_tracked_chats`].
+const DEFAULT_MAX_TRACKED_CHATS: usize = 10_000;
+
+/// Additional behaviour of dialogues entered with [`enter_with_config`].
+///
+/// Currently, this allows resetting dialogues which didn't receive updates for
+/// some time, so that users don't get stuck in half-finished flows they have
+/// forgotten about.
+///
+/// Clones share the same state, so the same config should be used for all
+/// handlers entering the same dialogues.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use teloxide::{
+///     dispatching::dialogue::{self, DialogueConfig, InMemStorage},
+///     prelude::*,
+/// };
+///
+/// #[derive(Clone, Default)]
+/// enum State {
+///     #[default]
+///     Start,
+///     ReceiveName,
+/// }
+///
+/// # async fn run() {
+/// let bot = Bot::from_env();
+/// let config = DialogueConfig::new()
+///     .idle_timeout(Duration::from_secs(10 * 60), State::Start)
+///     .on_idle_timeout({
+///         let bot = bot.clone();
+///         move |chat_id, _stale: State| {
+///             let bot = bot.clone();
+///             async move {
+///                 let _ = bot.send_message(chat_id, "Your session has expired.").await;
+///             }
+///         }
+///     });
+///
+/// let handler = Update::filter_message()
+///     .chain(dialogue::enter_with_config::<Update, InMemStorage<State>, State, _>(config))
+///     .endpoint(|| async { respond(()) });
+///
+/// Dispatcher::builder(bot, handler)
+///     .dependencies(dptree::deps![InMemStorage::<State>::new()])
+///     .build()
+///     .dispatch()
+///     .await;
+/// # }
+/// ```
+pub struct DialogueConfig<D> {
+    idle_timeout: Option<(Duration, D)>,
+    on_idle_timeout: Option<OnIdleTimeout<D>>,
+    last_seen: Arc<Mutex<RecentMap<ChatId, Instant>>>,
+}
+
+type OnIdleTimeout<D> = Arc<dyn Fn(ChatId, D) -> BoxFuture<'static, ()> + Send + Sync>;
+
+impl<D> DialogueConfig<D>
+where
+    D: Clone + Send + Sync + 'static,
+{
+    /// Creates a config without any additional behaviour.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            idle_timeout: None,
+            on_idle_timeout: None,
+            last_seen: Arc::new(Mutex::new(RecentMap::new(DEFAULT_MAX_TRACKED_CHATS))),
+        }
+    }
+
+    /// Makes dialogues that didn't receive updates for `timeout` switch to
+    /// `fallback` when the next update arrives, before it's handled.
+    ///
+    /// Use the default state as `fallback` to reset stale dialogues.
+    ///
+    /// The time of the last update is kept in memory, so dialogues don't time
+    /// out across restarts of the bot. Only the [most recently active] chats
+    /// are tracked, dialogues of the other chats don't time out either.
+    ///
+    /// [most recently active]: DialogueConfig::max_tracked_chats
+    #[must_use]
+    pub fn idle_timeout(self, timeout: Duration, fallback: D) -> Self {
+        Self { idle_timeout: Some((timeout, fallback)), ..self }
+    }
+
+    /// Sets how many of the most recently active chats are tracked for the
+    /// [idle timeout].
+    ///
+    /// When there are more chats, the least recently active half of them is
+    /// forgotten.
+    ///
+    /// Default - 10000.
+    ///
+    /// ## Panics
+    ///
+    /// If `max` is zero.
+    ///
+    /// [idle timeout]: DialogueConfig::idle_timeout
+    #[must_use]
+    #[track_caller]
+    pub fn max_tracked_chats(self, max: usize) -> Self {
+        Self { last_seen: Arc::new(Mutex::new(RecentMap::new(max))), ..self }
+    }
+
+    /// Specifies a function that is called with the stale state after a
+    /// dialogue has [timed out], e.g. to notify the user.
+    ///
+    /// [timed out]: DialogueConfig::idle_timeout
+    #[must_use]
+    pub fn on_idle_timeout<F, Fut>(self, f: F) -> Self
+    where
+        F: Fn(ChatId, D) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        Self {
+            on_idle_timeout: Some(Arc::new(move |chat_id, state| Box::pin(f(chat_id, state)))),
+            ..self
+        }
+    }
+
+    async fn reset_if_idle<S>(&self, dialogue: Dialogue<D, S>)
+    where
+        S: Storage<D> + ?Sized,
+        S::Error: Debug,
+    {
+        let Some((timeout, fallback)) = &self.idle_timeout else { return };
+
+        let chat_id = dialogue.chat_id();
+        let last_seen = self.last_seen.lock().unwrap().insert(chat_id, Instant::now());
+        if last_seen.is_none_or(|last_seen| last_seen.elapsed() < *timeout) {
+            return;
+        }
+
+        let stale = match dialogue.get().await {
+            Ok(Some(stale)) => stale,
+            Ok(None) => return,
+            Err(err) => {
+                log::error!("dialogue.get() failed: {err:?}");
+                return;
+            }
+        };
+        if let Err(err) = dialogue.update(fallback.clone()).await {
+            log::error!("dialogue.update() failed: {err:?}");
+            return;
+        }
+
+        if let Some(on_idle_timeout) = &self.on_idle_timeout {
+            on_idle_timeout(chat_id, stale).await;
+        }
+    }
+}
+
+impl<D> Clone for DialogueConfig<D>
+where
+    D: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            idle_timeout: self.idle_timeout.clone(),
+            on_idle_timeout: self.on_idle_timeout.clone(),
+            last_seen: Arc::clone(&self.last_seen),
+        }
+    }
+}
+
+impl<D> Default for DialogueConfig<D>
+where
+    D: Clone + Send + Sync + 'static,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Enters a dialogue context, applying `config` before the dialogue state is
+/// retrieved.
+///
+/// This is the same as [`dialogue::enter`], except that stale dialogues are
+/// handled as specified by [`DialogueConfig`].
+///
+/// A call to this function is the same as
+/// `dptree::entry().enter_dialogue_with_config(config)`.
+///
+/// See [`HandlerExt::enter_dialogue_with_config`].
+///
+/// ## Dependency requirements
+///
+///  - `Arc<S>`
+///  - `Upd`
+///
+/// [`HandlerExt::enter_dialogue_with_config`]: crate::dispatching::HandlerExt::enter_dialogue_with_config
+#[must_use]
+pub fn enter_with_config<Upd, S, D, Output>(
+    config: DialogueConfig<D>,
+) -> Handler<'static, Output, DpHandlerDescription>
+where
+    S: Storage<D> + ?Sized + Send + Sync + 'static,
+    <S as Storage<D>>::Error: Debug + Send,
+    D: Default + Clone + Send + Sync + 'static,
+    Upd: GetChatId + Clone + Send + Sync + 'static,
+    Output: Send + Sync + 'static,
+{
+    dptree::inspect_async(move |storage: Arc<S>, upd: Upd| {
+        let config = config.clone();
+        async move {
+            if let Some(chat_id) = upd.chat_id() {
+                config.reset_if_idle(Dialogue::new(storage, chat_id)).await;
+            }
+        }
+    })
+    .chain(dialogue::enter::<Upd, S, D, Output>())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::ControlFlow;
+
+    use teloxide_core::types::Chat;
+
+    use super::*;
+    use crate::{dispatching::dialogue::InMemStorage, test_utils::message_from_text};
+
+    #[derive(Clone, Debug, Default, PartialEq)]
+    enum State {
+        #[default]
+        Start,
+        ReceiveName,
+        Expired,
+    }
+
+    #[tokio::test]
+    async fn idle_timeout() {
+        let storage: Arc<InMemStorage<State>> = InMemStorage::new();
+        let chat = message_from_text("hi").chat;
+        let dialogue = Dialogue::new(storage.clone(), chat.id);
+
+        let notified = Arc::new(Mutex::new(Vec::new()));
+        let handler = |timeout| {
+            let notified = Arc::clone(&notified);
+            let config = DialogueConfig::new()
+                .idle_timeout(timeout, State::Expired)
+                .on_idle_timeout(move |chat_id, stale| {
+                    notified.lock().unwrap().push((chat_id, stale));
+                    async {}
+                });
+            enter_with_config::<Chat, InMemStorage<State>, State, _>(config)
+                .endpoint(|state: State| async move { state })
+        };
+        let deps = || dptree::deps![storage.clone(), chat.clone()];
+
+        let not_expiring = handler(Duration::from_secs(60));
+        dialogue.update(State::ReceiveName).await.unwrap();
+        assert_eq!(not_expiring.dispatch(deps()).await, ControlFlow::Break(State::ReceiveName));
+        assert_eq!(not_expiring.dispatch(deps()).await, ControlFlow::Break(State::ReceiveName));
+
+        let expiring = handler(Duration::ZERO);
+        // The first update of a dialogue can't time out
+        assert_eq!(expiring.dispatch(deps()).await, ControlFlow::Break(State::ReceiveName));
+        assert_eq!(expiring.dispatch(deps()).await, ControlFlow::Break(State::Expired));
+        assert_eq!(*notified.lock().unwrap(), [(chat.id, State::ReceiveName)]);
+
+        // Dialogues that don't exist aren't updated
+        dialogue.exit().await.unwrap();
+        assert_eq!(expiring.dispatch(deps()).await, ControlFlow::Break(State::Start));
+        assert_eq!(notified.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn tracked_chats_are_bounded() {
+        let config = DialogueConfig::<State>::new().max_tracked_chats(10);
+        let mut last_seen = config.last_seen.lock().unwrap();
+        for id in 0..100 {
+            last_seen.insert(ChatId(id), Instant::now());
+        }
+        assert!(last_seen.len() <= 10);
+        assert!(last_seen.get(&ChatId(99)).is_some());
+    }
+}
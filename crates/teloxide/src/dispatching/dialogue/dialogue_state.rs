@@ -0,0 +1,176 @@
+use futures::future::BoxFuture;
+use teloxide_core::types::ChatId;
+use thiserror::Error;
+
+use crate::dispatching::dialogue::{Dialogue, Storage};
+
+#[cfg(feature = "macros")]
+pub use teloxide_macros::DialogueState;
+
+/// A dialogue state with hooks that are run when a dialogue enters or exits
+/// it via [`Dialogue::transition_to`].
+///
+/// Hooks are useful for sending prompts automatically when entering a state,
+/// and for cleaning up (e.g. deleting messages or keyboards) when leaving one.
+/// Both hooks do nothing by default.
+///
+/// This trait can be derived with `#[derive(DialogueState)]` (requires the
+/// `macros` feature), specifying hooks per variant. The hooks are async
+/// functions taking the context, the chat ID and the state (cloned):
+///
+/// ```
+/// # #[cfg(feature = "macros")] {
+/// use teloxide::{dispatching::dialogue::DialogueState, prelude::*};
+///
+/// #[derive(Clone, Default, DialogueState)]
+/// // Defaults to `Bot` and `RequestError`
+/// #[dialogue(context = Bot, error = teloxide::RequestError)]
+/// enum State {
+///     #[default]
+///     Start,
+///     #[dialogue(on_enter = ask_name)]
+///     ReceiveName,
+///     #[dialogue(on_enter = ask_age, on_exit = thank)]
+///     ReceiveAge { name: String },
+/// }
+///
+/// async fn ask_name(bot: Bot, chat_id: ChatId, _: State) -> ResponseResult<()> {
+///     bot.send_message(chat_id, "What's your name?").await?;
+///     Ok(())
+/// }
+///
+/// async fn ask_age(bot: Bot, chat_id: ChatId, state: State) -> ResponseResult<()> {
+///     if let State::ReceiveAge { name } = state {
+///         bot.send_message(chat_id, format!("How old are you, {name}?")).await?;
+///     }
+///     Ok(())
+/// }
+///
+/// async fn thank(bot: Bot, chat_id: ChatId, _: State) -> ResponseResult<()> {
+///     bot.send_message(chat_id, "Thanks!").await?;
+///     Ok(())
+/// }
+/// # }
+/// ```
+pub trait DialogueState: Clone + Send + Sync + 'static {
+    /// The context passed to the hooks, usually a bot.
+    type Context: Clone + Send + 'static;
+
+    /// The error returned from the hooks.
+    type Error: Send + 'static;
+
+    /// Called after the dialogue in `chat_id` has entered this state.
+    fn on_enter(
+        &self,
+        ctx: Self::Context,
+        chat_id: ChatId,
+    ) -> BoxFuture<'static, Result<(), Self::Error>> {
+        let _ = (ctx, chat_id);
+        Box::pin(async { Ok(()) })
+    }
+
+    /// Called before the dialogue in `chat_id` exits this state.
+    fn on_exit(
+        &self,
+        ctx: Self::Context,
+        chat_id: ChatId,
+    ) -> BoxFuture<'static, Result<(), Self::Error>> {
+        let _ = (ctx, chat_id);
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// An error returned from [`Dialogue::transition_to`].
+#[derive(Debug, Error)]
+pub enum TransitionError<S, H> {
+    /// The state couldn't be loaded from or saved to the storage.
+    #[error(transparent)]
+    Storage(S),
+
+    /// A hook returned an error.
+    #[error(transparent)]
+    Hook(H),
+}
+
+impl<D, S> Dialogue<D, S>
+where
+    D: DialogueState,
+    S: Storage<D> + ?Sized,
+{
+    /// Updates the dialogue state, calling [`on_exit`] of the current state
+    /// (if any) before, and [`on_enter`] of the new state after.
+    ///
+    /// If `on_exit` fails, the state isn't changed.
+    ///
+    /// [`on_exit`]: DialogueState::on_exit
+    /// [`on_enter`]: DialogueState::on_enter
+    pub async fn transition_to<State>(
+        &self,
+        ctx: D::Context,
+        state: State,
+    ) -> Result<(), TransitionError<S::Error, D::Error>>
+    where
+        D: From<State>,
+    {
+        let chat_id = self.chat_id();
+
+        if let Some(current) = self.get().await.map_err(TransitionError::Storage)? {
+            current.on_exit(ctx.clone(), chat_id).await.map_err(TransitionError::Hook)?;
+        }
+
+        let state = D::from(state);
+        self.update::<D>(state.clone()).await.map_err(TransitionError::Storage)?;
+        state.on_enter(ctx, chat_id).await.map_err(TransitionError::Hook)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::dispatching::dialogue::InMemStorage;
+
+    type Log = Arc<Mutex<Vec<String>>>;
+
+    #[derive(Clone, Debug)]
+    enum State {
+        A,
+        B,
+    }
+
+    impl DialogueState for State {
+        type Context = Log;
+        type Error = ();
+
+        fn on_enter(&self, log: Log, _: ChatId) -> BoxFuture<'static, Result<(), ()>> {
+            log.lock().unwrap().push(format!("enter {self:?}"));
+            Box::pin(async { Ok(()) })
+        }
+
+        fn on_exit(&self, log: Log, _: ChatId) -> BoxFuture<'static, Result<(), ()>> {
+            let res = match self {
+                State::A => Ok(()),
+                State::B => Err(()),
+            };
+            log.lock().unwrap().push(format!("exit {self:?}"));
+            Box::pin(async move { res })
+        }
+    }
+
+    #[tokio::test]
+    async fn hooks() {
+        let log = Log::default();
+        let dialogue = Dialogue::new(InMemStorage::<State>::new(), ChatId(1));
+
+        dialogue.transition_to(log.clone(), State::A).await.unwrap();
+        dialogue.transition_to(log.clone(), State::B).await.unwrap();
+        assert!(matches!(
+            dialogue.transition_to(log.clone(), State::A).await,
+            Err(TransitionError::Hook(()))
+        ));
+
+        assert!(matches!(dialogue.get().await.unwrap(), Some(State::B)));
+        assert_eq!(*log.lock().unwrap(), ["enter A", "exit A", "enter B", "exit B"]);
+    }
+}
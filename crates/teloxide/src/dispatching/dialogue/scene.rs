@@ -0,0 +1,544 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Debug,
+    marker::PhantomData,
+    ops::ControlFlow,
+    panic::Location,
+    sync::Arc,
+};
+
+use dptree::{di::DependencyMap, Handler, HandlerDescription, HandlerSignature, Type};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    dispatching::{
+        dialogue::{Dialogue, Storage},
+        DpHandlerDescription,
+    },
+    types::{Me, Message},
+    utils::command::parse_command,
+};
+
+type SceneHandler<Err> = Handler<'static, Result<(), Err>, DpHandlerDescription>;
+
+/// A step handler and its prompt.
+type Step<Err> = (SceneHandler<Err>, Option<SceneHandler<Err>>);
+
+/// A multi-step conversation, such as a form, built on top of dialogues.
+///
+/// A scene is entered with a command, after which the messages of the chat
+/// are passed to the handler of the current step. Step handlers receive a
+/// [`SceneContext`], which is used to move to the next step (or to finish the
+/// scene after the last one), and to store the data collected so far. Each
+/// step can have a [prompt], which is run when the step is entered, e.g. to
+/// ask a question. A scene can also have commands to cancel it and to return
+/// to the previous step.
+///
+/// [`Scene::into_handler`] makes a handler out of a scene, which requires
+/// [`Message`], [`Me`] and `Arc<S>`, where `S` is a [`Storage`] of
+/// [`SceneState<D>`]. Multiple scenes can share the same storage.
+///
+/// [prompt]: Scene::prompt
+///
+/// ## Examples
+///
+/// ```no_run
+/// use teloxide::{
+///     dispatching::dialogue::{InMemStorage, Scene, SceneContext, SceneState},
+///     prelude::*,
+/// };
+///
+/// type Storage = InMemStorage<SceneState<Form>>;
+/// type HandlerResult = Result<(), Box<dyn std::error::Error + Send + Sync>>;
+///
+/// #[derive(Clone, Default)]
+/// struct Form {
+///     name: String,
+/// }
+///
+/// # async fn run() {
+/// let register = Scene::<Form, Storage, _>::new("register")
+///     .step(dptree::endpoint(receive_name))
+///     .prompt(dptree::endpoint(|bot: Bot, msg: Message| async move {
+///         bot.send_message(msg.chat.id, "What's your name?").await?;
+///         HandlerResult::Ok(())
+///     }))
+///     .step(dptree::endpoint(receive_age))
+///     .prompt(dptree::endpoint(|bot: Bot, msg: Message| async move {
+///         bot.send_message(msg.chat.id, "How old are you?").await?;
+///         Ok(())
+///     }))
+///     .cancel_command("cancel")
+///     .back_command("back");
+///
+/// let handler = Update::filter_message().chain(register.into_handler());
+///
+/// Dispatcher::builder(Bot::from_env(), handler)
+///     .dependencies(dptree::deps![Storage::new()])
+///     .build()
+///     .dispatch()
+///     .await;
+/// # }
+///
+/// async fn receive_name(msg: Message, ctx: SceneContext<Form, Storage>) -> HandlerResult {
+///     if let Some(name) = msg.text() {
+///         ctx.next(Form { name: name.to_owned() }).await?;
+///     }
+///     Ok(())
+/// }
+///
+/// async fn receive_age(
+///     bot: Bot,
+///     msg: Message,
+///     ctx: SceneContext<Form, Storage>,
+/// ) -> HandlerResult {
+///     match msg.text().map(str::parse::<u8>) {
+///         Some(Ok(age)) => {
+///             let text = format!("{}, {age} years old", ctx.data().name);
+///             bot.send_message(msg.chat.id, text).await?;
+///             // This is the last step, so this finishes the scene
+///             ctx.next(ctx.data().clone()).await?;
+///         }
+///         _ => {
+///             bot.send_message(msg.chat.id, "Send me a number.").await?;
+///         }
+///     }
+///     Ok(())
+/// }
+/// ```
+#[must_use]
+pub struct Scene<D, S: ?Sized, Err> {
+    command: String,
+    steps: Vec<Step<Err>>,
+    cancel_command: Option<String>,
+    on_cancel: Option<SceneHandler<Err>>,
+    back_command: Option<String>,
+    _phantom: PhantomData<(D, Arc<S>)>,
+}
+
+impl<D, S, Err> Scene<D, S, Err>
+where
+    D: Default + Clone + Send + Sync + 'static,
+    S: Storage<SceneState<D>> + ?Sized + Send + Sync + 'static,
+    S::Error: Debug + Send,
+    Err: Send + Sync + 'static,
+{
+    /// Creates a scene without steps, which is entered with `command`
+    /// (without the leading `/`).
+    ///
+    /// The command also serves as the name of the scene, so it must be unique.
+    pub fn new<C>(command: C) -> Self
+    where
+        C: Into<String>,
+    {
+        Self {
+            command: command.into(),
+            steps: Vec::new(),
+            cancel_command: None,
+            on_cancel: None,
+            back_command: None,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Adds a step, which handles messages while the scene is at this step.
+    pub fn step(mut self, handler: SceneHandler<Err>) -> Self {
+        self.steps.push((handler, None));
+        self
+    }
+
+    /// Sets the prompt of the last added step, which is run when the scene
+    /// enters that step.
+    ///
+    /// The prompt receives the same dependencies as the step handlers,
+    /// including the message that caused the transition.
+    ///
+    /// ## Panics
+    ///
+    /// If no steps were added.
+    #[track_caller]
+    pub fn prompt(mut self, handler: SceneHandler<Err>) -> Self {
+        let (_, prompt) = self.steps.last_mut().expect("`prompt` must be called after `step`");
+        *prompt = Some(handler);
+        self
+    }
+
+    /// Specifies a command (without the leading `/`) which exits the scene at
+    /// any step.
+    pub fn cancel_command<C>(self, command: C) -> Self
+    where
+        C: Into<String>,
+    {
+        Self { cancel_command: Some(command.into()), ..self }
+    }
+
+    /// Specifies a handler which is run after the scene was cancelled with
+    /// the [cancel command].
+    ///
+    /// [cancel command]: Scene::cancel_command
+    pub fn on_cancel(self, handler: SceneHandler<Err>) -> Self {
+        Self { on_cancel: Some(handler), ..self }
+    }
+
+    /// Specifies a command (without the leading `/`) which returns the scene to
+    /// the previous step, running its prompt again.
+    ///
+    /// At the first step, the command just runs its prompt again.
+    pub fn back_command<C>(self, command: C) -> Self
+    where
+        C: Into<String>,
+    {
+        Self { back_command: Some(command.into()), ..self }
+    }
+
+    /// Makes a handler out of this scene.
+    ///
+    /// The handler requires [`Message`], [`Me`] and `Arc<S>`. It passes
+    /// messages that are not part of the scene forwards.
+    ///
+    /// ## Panics
+    ///
+    /// If no steps were added.
+    #[must_use]
+    #[track_caller]
+    pub fn into_handler(self) -> SceneHandler<Err> {
+        assert!(!self.steps.is_empty(), "a scene must have at least one step");
+        let location = Location::caller();
+
+        let Self { command, steps, cancel_command, on_cancel, back_command, _phantom } = self;
+        let len = steps.len();
+        let scene: Arc<str> = command.clone().into();
+
+        let (steps, prompts): (Vec<_>, Vec<_>) = steps.into_iter().unzip();
+        let prompt =
+            prompts.into_iter().enumerate().fold(dptree::entry(), |handler, (step, prompt)| {
+                handler.branch(
+                    dptree::filter(move |ctx: SceneContext<D, S>| ctx.step() == step)
+                        .chain(prompt.unwrap_or_else(|| dptree::endpoint(|| async { Ok(()) }))),
+                )
+            });
+
+        let enter = filter_command(command)
+            .filter_map_async({
+                let scene = Arc::clone(&scene);
+                move |msg: Message, storage: Arc<S>| {
+                    let state =
+                        SceneState { scene: scene.to_string(), step: 0, data: D::default() };
+                    SceneContext::save(Dialogue::new(storage, msg.chat.id), state, len)
+                }
+            })
+            .chain(prompt.clone());
+
+        let mut in_scene = dptree::filter_map_async(move |msg: Message, storage: Arc<S>| {
+            let scene = Arc::clone(&scene);
+            async move {
+                let dialogue = Dialogue::new(storage, msg.chat.id);
+                match dialogue.get().await {
+                    Ok(Some(state)) if *state.scene == *scene => {
+                        Some(SceneContext { dialogue, state, len })
+                    }
+                    Ok(_) => None,
+                    Err(err) => {
+                        log::error!("dialogue.get() failed: {err:?}");
+                        None
+                    }
+                }
+            }
+        });
+        if let Some(command) = cancel_command {
+            in_scene = in_scene.branch(
+                filter_command(command)
+                    .inspect_async(|ctx: SceneContext<D, S>| async move {
+                        if let Err(err) = ctx.exit().await {
+                            log::error!("dialogue.exit() failed: {err:?}");
+                        }
+                    })
+                    .chain(on_cancel.unwrap_or_else(|| dptree::endpoint(|| async { Ok(()) }))),
+            );
+        }
+        if let Some(command) = back_command {
+            in_scene = in_scene.branch(
+                filter_command(command)
+                    .filter_map_async(|ctx: SceneContext<D, S>| async move {
+                        let state =
+                            SceneState { step: ctx.step().saturating_sub(1), ..ctx.state.clone() };
+                        SceneContext::save(ctx.dialogue, state, ctx.len).await
+                    })
+                    .chain(prompt.clone()),
+            );
+        }
+
+        dptree::entry()
+            .branch(enter)
+            .branch(in_scene.chain(run_step::<D, S, Err>(steps, prompt, location)))
+    }
+}
+
+/// Runs the handler of the current step, and then the prompt of the next
+/// step, if the step has changed.
+fn run_step<D, S, Err>(
+    steps: Vec<SceneHandler<Err>>,
+    prompt: SceneHandler<Err>,
+    location: &'static Location<'static>,
+) -> SceneHandler<Err>
+where
+    D: Clone + Send + Sync + 'static,
+    S: Storage<SceneState<D>> + ?Sized + Send + Sync + 'static,
+    S::Error: Debug + Send,
+    Err: Send + Sync + 'static,
+{
+    let mut obligations = BTreeMap::from([(Type::of::<SceneContext<D, S>>(), location)]);
+    for handler in steps.iter().chain([&prompt]) {
+        if let HandlerSignature::Other { obligations: required, .. } = handler.sig() {
+            obligations.extend(required.keys().map(|ty| (*ty, location)));
+        }
+    }
+    let steps = Arc::new(steps);
+
+    dptree::from_fn_with_description(
+        DpHandlerDescription::user_defined(),
+        move |container: DependencyMap, cont| {
+            let steps = Arc::clone(&steps);
+            let prompt = prompt.clone();
+            async move {
+                let ctx = container.get::<SceneContext<D, S>>();
+                let Some(step) = steps.get(ctx.step()) else {
+                    log::error!("scene `{}` doesn't have step {}", ctx.state.scene, ctx.step());
+                    return cont(container).await;
+                };
+
+                match step.dispatch(container.clone()).await {
+                    ControlFlow::Break(Ok(())) => {}
+                    ControlFlow::Break(Err(err)) => return ControlFlow::Break(Err(err)),
+                    ControlFlow::Continue(_) => return cont(container).await,
+                }
+
+                let state = match ctx.dialogue.get().await {
+                    Ok(Some(state))
+                        if state.scene == ctx.state.scene && state.step != ctx.step() =>
+                    {
+                        state
+                    }
+                    Ok(_) => return ControlFlow::Break(Ok(())),
+                    Err(err) => {
+                        log::error!("dialogue.get() failed: {err:?}");
+                        return ControlFlow::Break(Ok(()));
+                    }
+                };
+
+                let mut container = container;
+                container.insert(SceneContext {
+                    dialogue: ctx.dialogue.clone(),
+                    state,
+                    len: ctx.len,
+                });
+                match prompt.dispatch(container).await {
+                    ControlFlow::Break(res) => ControlFlow::Break(res),
+                    ControlFlow::Continue(_) => ControlFlow::Break(Ok(())),
+                }
+            }
+        },
+        HandlerSignature::Other {
+            obligations,
+            guaranteed_outcomes: BTreeSet::new(),
+            conditional_outcomes: BTreeSet::new(),
+            continues: true,
+        },
+    )
+}
+
+/// Passes messages with `/command` (addressed to this bot, if there is a
+/// mention) forwards.
+fn filter_command<Err>(command: String) -> SceneHandler<Err>
+where
+    Err: Send + Sync + 'static,
+{
+    dptree::filter(move |msg: Message, me: Me| {
+        msg.text()
+            .and_then(|text| parse_command(text, me.username()))
+            .is_some_and(|(name, _)| name == command)
+    })
+}
+
+/// The dialogue state of a [`Scene`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SceneState<D> {
+    scene: String,
+    step: usize,
+    data: D,
+}
+
+impl<D> SceneState<D> {
+    /// Returns the name of the scene, i.e. its command.
+    #[must_use]
+    pub fn scene(&self) -> &str {
+        &self.scene
+    }
+
+    /// Returns the index of the current step.
+    #[must_use]
+    pub fn step(&self) -> usize {
+        self.step
+    }
+
+    /// Returns the data collected so far.
+    #[must_use]
+    pub fn data(&self) -> &D {
+        &self.data
+    }
+}
+
+/// The state of a [`Scene`] in a chat, passed to step handlers and prompts.
+pub struct SceneContext<D, S: ?Sized> {
+    dialogue: Dialogue<SceneState<D>, S>,
+    state: SceneState<D>,
+    len: usize,
+}
+
+impl<D, S> SceneContext<D, S>
+where
+    D: Clone + Send + 'static,
+    S: Storage<SceneState<D>> + ?Sized,
+{
+    /// Returns the underlying dialogue.
+    #[must_use]
+    pub fn dialogue(&self) -> &Dialogue<SceneState<D>, S> {
+        &self.dialogue
+    }
+
+    /// Returns the index of the current step.
+    #[must_use]
+    pub fn step(&self) -> usize {
+        self.state.step
+    }
+
+    /// Returns `true` if the current step is the last one.
+    #[must_use]
+    pub fn is_last_step(&self) -> bool {
+        self.state.step + 1 == self.len
+    }
+
+    /// Returns the data collected so far.
+    #[must_use]
+    pub fn data(&self) -> &D {
+        &self.state.data
+    }
+
+    /// Moves to the next step, storing `data`, or exits the scene if the
+    /// current step is the last one.
+    ///
+    /// The prompt of the next step is run after the current step handler
+    /// returns.
+    pub async fn next(&self, data: D) -> Result<(), S::Error> {
+        if self.is_last_step() {
+            return self.exit().await;
+        }
+
+        let state = SceneState { step: self.state.step + 1, data, ..self.state.clone() };
+        self.dialogue.update(state).await
+    }
+
+    /// Stores `data` without changing the step.
+    pub async fn set_data(&self, data: D) -> Result<(), S::Error> {
+        self.dialogue.update(SceneState { data, ..self.state.clone() }).await
+    }
+
+    /// Exits the scene.
+    pub async fn exit(&self) -> Result<(), S::Error> {
+        self.dialogue.exit().await
+    }
+
+    /// Stores `state` and returns the context with it.
+    async fn save(
+        dialogue: Dialogue<SceneState<D>, S>,
+        state: SceneState<D>,
+        len: usize,
+    ) -> Option<Self>
+    where
+        S::Error: Debug,
+    {
+        match dialogue.update(state.clone()).await {
+            Ok(()) => Some(Self { dialogue, state, len }),
+            Err(err) => {
+                log::error!("dialogue.update() failed: {err:?}");
+                None
+            }
+        }
+    }
+}
+
+impl<D, S> Clone for SceneContext<D, S>
+where
+    D: Clone,
+    S: ?Sized,
+{
+    fn clone(&self) -> Self {
+        Self { dialogue: self.dialogue.clone(), state: self.state.clone(), len: self.len }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::{
+        dispatching::dialogue::InMemStorage,
+        test_utils::{me, message_from_text},
+    };
+
+    type Storage = InMemStorage<SceneState<Vec<String>>>;
+    type Log = Arc<Mutex<Vec<String>>>;
+
+    #[tokio::test]
+    async fn scene() {
+        let log = Log::default();
+        let step = |log: Log| {
+            dptree::endpoint(move |msg: Message, ctx: SceneContext<Vec<String>, Storage>| {
+                let log = Arc::clone(&log);
+                async move {
+                    let mut data = ctx.data().clone();
+                    data.push(msg.text().unwrap().to_owned());
+                    if ctx.is_last_step() {
+                        log.lock().unwrap().push(data.join(" "));
+                    }
+                    ctx.next(data).await.unwrap();
+                    Ok::<_, ()>(())
+                }
+            })
+        };
+        let prompt = |log: Log, text: &'static str| {
+            dptree::endpoint(move || {
+                log.lock().unwrap().push(text.to_owned());
+                async { Ok(()) }
+            })
+        };
+
+        let handler = Scene::<Vec<String>, Storage, ()>::new("form")
+            .step(step(log.clone()))
+            .prompt(prompt(log.clone(), "first?"))
+            .step(step(log.clone()))
+            .prompt(prompt(log.clone(), "second?"))
+            .cancel_command("cancel")
+            .on_cancel(prompt(log.clone(), "cancelled"))
+            .back_command("back")
+            .into_handler();
+
+        let me = me();
+        let storage = Storage::new();
+        let send = |text: &str| {
+            handler.dispatch(dptree::deps![message_from_text(text), me.clone(), storage.clone()])
+        };
+
+        assert!(send("a").await.is_continue());
+        for text in ["/form@bot", "a", "/back", "b", "c", "d", "/form", "/cancel", "/cancel"] {
+            let _ = send(text).await;
+        }
+        assert!(send("e").await.is_continue());
+
+        assert_eq!(
+            *log.lock().unwrap(),
+            ["first?", "second?", "first?", "second?", "a b c", "first?", "cancelled",]
+        );
+    }
+}
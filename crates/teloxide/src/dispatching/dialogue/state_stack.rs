@@ -0,0 +1,223 @@
+use std::fmt::Debug;
+
+use dptree::Handler;
+use serde::{Deserialize, Serialize};
+
+use crate::dispatching::{
+    dialogue::{self, Dialogue, GetChatId, Storage},
+    DpHandlerDescription,
+};
+
+/// A dialogue state that remembers the states it was entered from.
+///
+/// This allows implementing reusable sub-flows (e.g. a "collect an address"
+/// wizard), which can be started from multiple parent flows and return control
+/// to the parent when finished: a parent [pushes] the first state of the
+/// sub-flow, and the sub-flow [pops] its state when it's done, restoring the
+/// state of the parent.
+///
+/// Use [`enter_stack`] (or [`HandlerExt::enter_stacked_dialogue`]) to enter a
+/// dialogue with this state.
+///
+/// [pushes]: Dialogue::push
+/// [pops]: Dialogue::pop
+/// [`HandlerExt::enter_stacked_dialogue`]: crate::dispatching::HandlerExt::enter_stacked_dialogue
+///
+/// ## Examples
+///
+/// ```
+/// use teloxide::{
+///     dispatching::dialogue::{InMemStorage, StateStack},
+///     prelude::*,
+///     types::ChatId,
+/// };
+///
+/// #[derive(Clone, Debug, Default, PartialEq)]
+/// enum State {
+///     #[default]
+///     Start,
+///     Checkout,
+///     ReceiveAddress,
+/// }
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let storage = InMemStorage::<StateStack<State>>::new();
+/// let dialogue = Dialogue::new(storage, ChatId(1));
+///
+/// dialogue.replace(State::Checkout).await.unwrap();
+/// // The address wizard can be started from any state...
+/// dialogue.push(State::ReceiveAddress).await.unwrap();
+/// // ...and returns to it when finished
+/// assert_eq!(dialogue.pop().await.unwrap(), State::Checkout);
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct StateStack<D> {
+    current: D,
+    parents: Vec<D>,
+}
+
+impl<D> StateStack<D> {
+    /// Creates a stack with a single state.
+    #[must_use]
+    pub fn new(state: D) -> Self {
+        Self { current: state, parents: Vec::new() }
+    }
+
+    /// Returns the current state.
+    #[must_use]
+    pub fn current(&self) -> &D {
+        &self.current
+    }
+
+    /// Returns the states the current sub-flow was entered from, the innermost
+    /// one last.
+    #[must_use]
+    pub fn parents(&self) -> &[D] {
+        &self.parents
+    }
+
+    /// Returns the current state, dropping the parents.
+    #[must_use]
+    pub fn into_current(self) -> D {
+        self.current
+    }
+}
+
+impl<D, S> Dialogue<StateStack<D>, S>
+where
+    D: Default + Send + 'static,
+    S: Storage<StateStack<D>> + ?Sized,
+{
+    /// Enters a sub-flow, remembering the current state and switching to
+    /// `state`.
+    ///
+    /// The remembered state is restored by [`Dialogue::pop`].
+    pub async fn push<State>(&self, state: State) -> Result<(), S::Error>
+    where
+        D: From<State>,
+    {
+        let mut stack = self.get().await?.unwrap_or_default();
+        let parent = std::mem::replace(&mut stack.current, state.into());
+        stack.parents.push(parent);
+
+        self.update(stack).await
+    }
+
+    /// Finishes the current sub-flow, restoring the state it was entered from,
+    /// and returns the restored state.
+    ///
+    /// If the current state wasn't entered with [`Dialogue::push`], the state
+    /// is reset to the default one.
+    pub async fn pop(&self) -> Result<D, S::Error>
+    where
+        D: Clone,
+    {
+        let mut stack = self.get().await?.unwrap_or_default();
+        stack.current = stack.parents.pop().unwrap_or_default();
+        let current = stack.current.clone();
+
+        self.update(stack).await?;
+        Ok(current)
+    }
+
+    /// Replaces the current state, leaving the states of the parent flows
+    /// intact.
+    pub async fn replace<State>(&self, state: State) -> Result<(), S::Error>
+    where
+        D: From<State>,
+    {
+        let mut stack = self.get().await?.unwrap_or_default();
+        stack.current = state.into();
+
+        self.update(stack).await
+    }
+}
+
+/// Enters a dialogue context with a [`StateStack`].
+///
+/// This is the same as [`dialogue::enter`] with `StateStack<D>` as the
+/// dialogue state, except that the current state `D` is also passed forwards,
+/// so it can be matched with [`case!`].
+///
+/// A call to this function is the same as
+/// `dptree::entry().enter_stacked_dialogue()`.
+///
+/// See [`HandlerExt::enter_stacked_dialogue`].
+///
+/// ## Dependency requirements
+///
+///  - `Arc<S>`
+///  - `Upd`
+///
+/// [`case!`]: crate::dptree::case
+/// [`HandlerExt::enter_stacked_dialogue`]: crate::dispatching::HandlerExt::enter_stacked_dialogue
+#[must_use]
+pub fn enter_stack<Upd, S, D, Output>() -> Handler<'static, Output, DpHandlerDescription>
+where
+    S: Storage<StateStack<D>> + ?Sized + Send + Sync + 'static,
+    <S as Storage<StateStack<D>>>::Error: Debug + Send,
+    D: Default + Clone + Send + Sync + 'static,
+    Upd: GetChatId + Clone + Send + Sync + 'static,
+    Output: Send + Sync + 'static,
+{
+    dialogue::enter::<Upd, S, StateStack<D>, Output>()
+        .map(|stack: StateStack<D>| stack.into_current())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use teloxide_core::types::{Chat, ChatId};
+
+    use super::*;
+    use crate::{dispatching::dialogue::InMemStorage, test_utils::message_from_text};
+
+    #[derive(Clone, Debug, Default, PartialEq)]
+    enum State {
+        #[default]
+        Start,
+        Order,
+        Feedback,
+        Address {
+            street: Option<String>,
+        },
+    }
+
+    #[tokio::test]
+    async fn push_and_pop() {
+        let storage: Arc<InMemStorage<StateStack<State>>> = InMemStorage::new();
+        let dialogue = Dialogue::new(storage, ChatId(1));
+
+        // Popping an empty stack resets the dialogue
+        assert_eq!(dialogue.pop().await.unwrap(), State::Start);
+
+        dialogue.replace(State::Order).await.unwrap();
+        dialogue.push(State::Feedback).await.unwrap();
+        dialogue.push(State::Address { street: None }).await.unwrap();
+        dialogue.replace(State::Address { street: Some("Baker".to_owned()) }).await.unwrap();
+
+        let stack = dialogue.get().await.unwrap().unwrap();
+        assert_eq!(stack.current(), &State::Address { street: Some("Baker".to_owned()) });
+        assert_eq!(stack.parents(), [State::Order, State::Feedback]);
+
+        assert_eq!(dialogue.pop().await.unwrap(), State::Feedback);
+        assert_eq!(dialogue.pop().await.unwrap(), State::Order);
+        assert_eq!(dialogue.pop().await.unwrap(), State::Start);
+    }
+
+    #[tokio::test]
+    async fn enter_stack_passes_current_state() {
+        let storage: Arc<InMemStorage<StateStack<State>>> = InMemStorage::new();
+        let chat = message_from_text("hi").chat;
+        Dialogue::new(storage.clone(), chat.id).push(State::Feedback).await.unwrap();
+
+        let handler = enter_stack::<Chat, InMemStorage<StateStack<State>>, State, _>()
+            .endpoint(|state: State| async move { state });
+
+        let res = handler.dispatch(dptree::deps![storage, chat]).await;
+        assert_eq!(res, std::ops::ControlFlow::Break(State::Feedback));
+    }
+}
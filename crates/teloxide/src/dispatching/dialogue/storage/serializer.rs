@@ -77,3 +77,136 @@ where
         bincode::deserialize(data)
     }
 }
+
+/// A serializer that encrypts the output of another serializer.
+///
+/// The data is encrypted with AES-256-GCM, using a random nonce for each
+/// record. This is useful when dialogue states contain sensitive data and are
+/// stored in a shared database.
+///
+/// Note that the key must be kept the same between restarts of the bot,
+/// otherwise the stored dialogues can't be decrypted.
+///
+/// Can be used with any storage that accepts a serializer, e.g.
+/// `RedisStorage::open(url, Encrypted::new(Json, key))`.
+///
+/// ## Examples
+///
+/// ```
+/// use teloxide::dispatching::dialogue::serializer::{Encrypted, Json, Serializer};
+///
+/// let key = [42; 32]; // Load it from a secure place instead
+/// let serializer = Encrypted::new(Json, key);
+///
+/// let data = serializer.serialize(&"secret".to_owned()).unwrap();
+/// let secret: String = serializer.deserialize(&data).unwrap();
+/// assert_eq!(secret, "secret");
+/// ```
+#[cfg(feature = "encrypted-serializer")]
+pub struct Encrypted<S> {
+    inner: S,
+    cipher: aes_gcm::Aes256Gcm,
+}
+
+#[cfg(feature = "encrypted-serializer")]
+impl<S> Encrypted<S> {
+    /// Creates a new serializer, which encrypts the output of `inner` with a
+    /// 256-bit `key`.
+    #[must_use]
+    pub fn new(inner: S, key: [u8; 32]) -> Self {
+        use aes_gcm::KeyInit;
+
+        Self { inner, cipher: aes_gcm::Aes256Gcm::new(&key.into()) }
+    }
+}
+
+/// An error returned from [`Encrypted`].
+#[cfg(feature = "encrypted-serializer")]
+#[derive(Debug, thiserror::Error)]
+pub enum EncryptedError<E> {
+    /// An error from the inner serializer.
+    #[error("inner serializer error: {0}")]
+    Serializer(E),
+
+    /// The data could not be encrypted, or was malformed or tampered with, or
+    /// was encrypted with a different key.
+    #[error("encryption error")]
+    Encryption,
+}
+
+#[cfg(feature = "encrypted-serializer")]
+impl<S, D> Serializer<D> for Encrypted<S>
+where
+    S: Serializer<D>,
+{
+    type Error = EncryptedError<S::Error>;
+
+    fn serialize(&self, val: &D) -> Result<Vec<u8>, Self::Error> {
+        use aes_gcm::{
+            aead::{Aead, OsRng},
+            AeadCore, Aes256Gcm,
+        };
+
+        let plaintext = self.inner.serialize(val).map_err(EncryptedError::Serializer)?;
+
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, plaintext.as_slice())
+            .map_err(|_| EncryptedError::Encryption)?;
+
+        // The nonce is not secret, so it's stored alongside the ciphertext
+        let mut data = nonce.to_vec();
+        data.extend(ciphertext);
+        Ok(data)
+    }
+
+    fn deserialize(&self, data: &[u8]) -> Result<D, Self::Error> {
+        use aes_gcm::{aead::Aead, Nonce};
+
+        const NONCE_LEN: usize = 12;
+
+        if data.len() < NONCE_LEN {
+            return Err(EncryptedError::Encryption);
+        }
+        let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+
+        let plaintext = self
+            .cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| EncryptedError::Encryption)?;
+
+        self.inner.deserialize(&plaintext).map_err(EncryptedError::Serializer)
+    }
+}
+
+#[cfg(all(test, feature = "encrypted-serializer"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encrypted_roundtrip() {
+        let serializer = Encrypted::new(Json, [1; 32]);
+
+        let data = Serializer::<String>::serialize(&serializer, &"hello".to_owned()).unwrap();
+        assert!(!data.windows(5).any(|w| w == b"hello"));
+
+        // Nonces are random, so the same value is encrypted differently
+        let data2 = Serializer::<String>::serialize(&serializer, &"hello".to_owned()).unwrap();
+        assert_ne!(data, data2);
+
+        let value: String = serializer.deserialize(&data).unwrap();
+        assert_eq!(value, "hello");
+    }
+
+    #[test]
+    fn encrypted_wrong_key() {
+        let data = Serializer::<u32>::serialize(&Encrypted::new(Json, [1; 32]), &42).unwrap();
+
+        let res: Result<u32, _> = Encrypted::new(Json, [2; 32]).deserialize(&data);
+        assert!(matches!(res, Err(EncryptedError::Encryption)));
+
+        let res: Result<u32, _> = Encrypted::new(Json, [1; 32]).deserialize(&data[..5]);
+        assert!(matches!(res, Err(EncryptedError::Encryption)));
+    }
+}
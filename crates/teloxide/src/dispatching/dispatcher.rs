@@ -1,37 +1,48 @@
 use crate::{
     dispatching::{
         distribution::default_distribution_function, DefaultKey, DpHandlerDescription,
-        ShutdownToken,
+        ShutdownToken, TaskTracker,
+    },
+    error_handlers::{
+        ErrorHandler, IgnoringUpdate, LoggingErrorHandler, Retries, RetryErrorHandler,
+        ScheduledRetries, UpdateErrorHandler,
     },
-    error_handlers::{ErrorHandler, LoggingErrorHandler},
     requests::{Request, Requester},
     stop::StopToken,
-    types::{Update, UpdateKind},
-    update_listeners::{self, UpdateListener},
+    types::{Me, Update, UpdateKind},
+    update_listeners::{self, ProcessedCallback, UpdateListener},
 };
 
-use dptree::di::DependencyMap;
+use dptree::{di::DependencyMap, HandlerSignature};
 use either::Either;
 use futures::{
     future::{self, BoxFuture},
     stream::FuturesUnordered,
     FutureExt as _, StreamExt as _,
 };
+use tokio::sync::mpsc;
 use tokio_stream::wrappers::ReceiverStream;
 
 use std::{
+    any::Any,
     collections::HashMap,
     fmt::Debug,
     future::Future,
-    hash::Hash,
+    hash::{BuildHasher, Hash},
     ops::{ControlFlow, Deref},
     pin::pin,
     sync::{
-        atomic::{AtomicBool, AtomicU32, Ordering},
-        Arc,
+        atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering},
+        Arc, RwLock,
     },
+    time::Duration,
 };
 
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+const DEFAULT_WORKER_QUEUE_SIZE: usize = 64;
+
 /// The builder for [`Dispatcher`].
 ///
 /// See also: ["Dispatching or
@@ -39,12 +50,18 @@ use std::{
 pub struct DispatcherBuilder<R, Err, Key> {
     bot: R,
     dependencies: DependencyMap,
+    request_dependencies: Vec<RequestDependency>,
     handler: Arc<UpdateHandler<Err>>,
     default_handler: DefaultHandler,
-    error_handler: Arc<dyn ErrorHandler<Err> + Send + Sync>,
+    error_handler: Arc<dyn UpdateErrorHandler<Err> + Send + Sync>,
+    retries: Option<Retries>,
+    update_interceptor: Option<UpdateInterceptor>,
+    outcome_hook: Option<OutcomeHook>,
     ctrlc_handler: bool,
     distribution_f: fn(&Update) -> Option<Key>,
     worker_queue_size: usize,
+    worker_pool_size: Option<usize>,
+    get_me: GetMeConfig,
 }
 
 impl<R, Err, Key> DispatcherBuilder<R, Err, Key>
@@ -54,20 +71,113 @@ where
 {
     /// Specifies a handler that will be called for an unhandled update.
     ///
-    /// By default, it is a mere [`log::warn`].
+    /// This is the same as
+    /// [`unhandled_update_policy`]`(`[`UnhandledUpdatePolicy::custom`]`(handler))`.
+    ///
+    /// [`unhandled_update_policy`]: DispatcherBuilder::unhandled_update_policy
     #[must_use]
     pub fn default_handler<H, Fut>(self, handler: H) -> Self
     where
         H: Fn(Arc<Update>) -> Fut + Send + Sync + 'static,
         Fut: Future<Output = ()> + Send + 'static,
     {
-        let handler = Arc::new(handler);
+        self.unhandled_update_policy(UnhandledUpdatePolicy::custom(handler))
+    }
+
+    /// Specifies what to do with updates which aren't handled by the handler.
+    ///
+    /// By default, it's [`UnhandledUpdatePolicy::Log`].
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use teloxide::{
+    ///     dispatching::{Dispatcher, UnhandledUpdatePolicy, UnhandledUpdates},
+    ///     dptree, Bot,
+    /// };
+    ///
+    /// let unhandled = UnhandledUpdates::new();
+    ///
+    /// let bot = Bot::new("TOKEN");
+    /// let handler = dptree::entry() /* ... */;
+    /// let dp = Dispatcher::builder(bot, handler)
+    ///     .unhandled_update_policy(UnhandledUpdatePolicy::Metric(unhandled.clone()))
+    ///     .build();
+    /// # let _: Dispatcher<_, (), _> = dp;
+    ///
+    /// // Later, e.g. in a metrics endpoint
+    /// let edited_messages = unhandled.count("edited_message");
+    /// # assert_eq!(edited_messages, 0);
+    /// ```
+    #[must_use]
+    pub fn unhandled_update_policy(self, policy: UnhandledUpdatePolicy) -> Self {
+        Self { default_handler: policy.into_default_handler(), ..self }
+    }
+
+    /// Specifies an interceptor that will be called for each update before it
+    /// reaches the handler.
+    ///
+    /// The interceptor can inspect or modify the update, or drop it entirely
+    /// by returning `None`, in which case neither the handler nor the default
+    /// handler will be called. This is useful for global concerns like
+    /// banning users, rate limiting, logging, or routing updates between
+    /// different versions of the handler.
+    ///
+    /// Note that the interceptor is called after the update is assigned to a
+    /// worker (see [update grouping]), so modifying the chat of the update
+    /// doesn't affect the order in which updates are processed.
+    ///
+    /// By default, there is no interceptor.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use teloxide::{dispatching::Dispatcher, dptree, types::UserId, Bot};
+    ///
+    /// const BANNED: UserId = UserId(42);
+    ///
+    /// let bot = Bot::new("TOKEN");
+    /// let handler = dptree::entry() /* ... */;
+    /// let dp = Dispatcher::builder(bot, handler)
+    ///     .update_interceptor(|upd| async move {
+    ///         match upd.from() {
+    ///             Some(user) if user.id == BANNED => None,
+    ///             _ => Some(upd),
+    ///         }
+    ///     })
+    ///     .build();
+    /// # let _: Dispatcher<_, (), _> = dp;
+    /// ```
+    ///
+    /// [update grouping]: DispatcherBuilder#update-grouping
+    #[must_use]
+    pub fn update_interceptor<I, Fut>(self, interceptor: I) -> Self
+    where
+        I: Fn(Update) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Option<Update>> + Send + 'static,
+    {
+        Self { update_interceptor: Some(Arc::new(move |upd| Box::pin(interceptor(upd)))), ..self }
+    }
 
+    /// Specifies a hook that will be called after an update was processed,
+    /// with the [outcome] of the processing.
+    ///
+    /// The hook is called after the error handler or the default handler (if
+    /// they were called), and is not called for updates dropped by the
+    /// [interceptor].
+    ///
+    /// By default, there is no hook.
+    ///
+    /// [outcome]: UpdateOutcome
+    /// [interceptor]: DispatcherBuilder::update_interceptor
+    #[must_use]
+    pub fn outcome_hook<H, Fut>(self, hook: H) -> Self
+    where
+        H: Fn(Arc<Update>, UpdateOutcome) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
         Self {
-            default_handler: Arc::new(move |upd| {
-                let handler = Arc::clone(&handler);
-                Box::pin(handler(upd))
-            }),
+            outcome_hook: Some(Arc::new(move |upd, outcome| Box::pin(hook(upd, outcome)))),
             ..self
         }
     }
@@ -75,19 +185,115 @@ where
     /// Specifies a handler that will be called on a handler error.
     ///
     /// By default, it is [`LoggingErrorHandler`].
+    ///
+    /// See also [`DispatcherBuilder::update_error_handler`] for a handler
+    /// which receives the update that caused the error.
     #[must_use]
     pub fn error_handler(self, handler: Arc<dyn ErrorHandler<Err> + Send + Sync>) -> Self {
-        Self { error_handler: handler, ..self }
+        Self { error_handler: Arc::new(IgnoringUpdate(handler)), retries: None, ..self }
+    }
+
+    /// Specifies a handler that will be called on a handler error, with the
+    /// update that caused the error.
+    ///
+    /// This replaces the handler set by [`DispatcherBuilder::error_handler`].
+    #[must_use]
+    pub fn update_error_handler(
+        self,
+        handler: Arc<dyn UpdateErrorHandler<Err> + Send + Sync>,
+    ) -> Self {
+        Self { error_handler: handler, retries: None, ..self }
+    }
+
+    /// Specifies a handler that will be called on a handler error, which
+    /// handles updates again after retryable errors.
+    ///
+    /// This replaces the handler set by [`DispatcherBuilder::error_handler`].
+    /// See [`RetryErrorHandler`] for details.
+    #[must_use]
+    pub fn retry_error_handler(self, mut handler: RetryErrorHandler<Err>) -> Self {
+        let retries = handler.register();
+        Self { error_handler: Arc::new(handler), retries: Some(retries), ..self }
     }
 
     /// Specifies dependencies that can be used inside of handlers.
     ///
+    /// These dependencies are singletons: they are created once and shared by
+    /// all updates. For dependencies that must not be shared, see
+    /// [`request_dependency`].
+    ///
     /// By default, there is no dependencies.
+    ///
+    /// [`request_dependency`]: DispatcherBuilder::request_dependency
     #[must_use]
     pub fn dependencies(self, dependencies: DependencyMap) -> Self {
         Self { dependencies, ..self }
     }
 
+    /// Specifies a request-scoped dependency, which is created by `factory`
+    /// for each update before the update is passed to the handler.
+    ///
+    /// Unlike [dependencies], request-scoped dependencies are never shared
+    /// between updates, so they are suitable for per-update state, e.g. a
+    /// buffer of replies which must not leak into handling of other updates.
+    ///
+    /// ## Override rules
+    ///
+    ///  - A request-scoped dependency overrides a [dependency] of the same
+    ///    type, including the bot and [`Me`].
+    ///  - A request-scoped dependency overrides a previously specified
+    ///    request-scoped dependency of the same type.
+    ///
+    /// ## Panics
+    ///
+    /// If `T` is [`Update`] or `Arc<Update>`, which always refer to the update
+    /// being processed.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// use teloxide::{dispatching::Dispatcher, dptree, Bot};
+    ///
+    /// #[derive(Clone, Default)]
+    /// struct Replies(Arc<Mutex<Vec<String>>>);
+    ///
+    /// let bot = Bot::new("TOKEN");
+    /// let handler = dptree::endpoint(|replies: Replies| async move {
+    ///     replies.0.lock().unwrap().push("Hi!".to_owned());
+    ///     Ok::<_, ()>(())
+    /// });
+    /// let dp =
+    ///     Dispatcher::builder(bot, handler).request_dependency(|_upd| Replies::default()).build();
+    /// # let _: Dispatcher<_, (), _> = dp;
+    /// ```
+    ///
+    /// [dependencies]: DispatcherBuilder::dependencies
+    /// [dependency]: DispatcherBuilder::dependencies
+    #[must_use]
+    #[track_caller]
+    pub fn request_dependency<T, F>(mut self, factory: F) -> Self
+    where
+        T: Send + Sync + 'static,
+        F: Fn(&Update) -> T + Send + Sync + 'static,
+    {
+        let ty = dptree::Type::of::<T>();
+        assert!(
+            ty != dptree::Type::of::<Update>() && ty != dptree::Type::of::<Arc<Update>>(),
+            "the update being processed can't be overridden by a request-scoped dependency"
+        );
+
+        self.request_dependencies.retain(|dependency| dependency.ty != ty);
+        self.request_dependencies.push(RequestDependency {
+            ty,
+            factory: Arc::new(move |upd, deps| {
+                deps.insert(factory(upd));
+            }),
+        });
+        self
+    }
+
     /// Enables the `^C` handler that [`shutdown`]s dispatching.
     ///
     /// [`shutdown`]: ShutdownToken::shutdown
@@ -100,11 +306,140 @@ where
     /// Specifies size of the queue for workers.
     ///
     /// By default it's 64.
+    ///
+    /// ## Panics
+    ///
+    /// If `size` is zero.
     #[must_use]
     pub fn worker_queue_size(self, size: usize) -> Self {
+        assert!(size > 0, "worker queue size must be greater than zero");
+
         Self { worker_queue_size: size, ..self }
     }
 
+    /// Makes the dispatcher process updates with a fixed pool of `size`
+    /// workers, instead of spawning a worker per [distribution key] (and a
+    /// task per update without a key).
+    ///
+    /// Updates with the same distribution key are always sent to the same
+    /// worker, so they are still processed sequentially. Updates without a
+    /// key are distributed among the workers in a round-robin fashion. Each
+    /// worker processes its updates one at a time, so at most `size` updates
+    /// are processed concurrently, and at most `size` times
+    /// [`worker_queue_size`] updates are queued.
+    ///
+    /// This trades some latency for predictable memory usage, which may be
+    /// useful for bots with very high traffic.
+    ///
+    /// By default, there is no worker pool.
+    ///
+    /// ## Panics
+    ///
+    /// If `size` is zero.
+    ///
+    /// [distribution key]: DispatcherBuilder#update-grouping
+    /// [`worker_queue_size`]: DispatcherBuilder::worker_queue_size
+    #[must_use]
+    pub fn worker_pool(self, size: usize) -> Self {
+        assert!(size > 0, "worker pool size must be greater than zero");
+
+        Self { worker_pool_size: Some(size), ..self }
+    }
+
+    /// Specifies how many times the initial [`GetMe`] request is retried
+    /// before giving up.
+    ///
+    /// By default, it's retried 3 times.
+    ///
+    /// [`GetMe`]: crate::payloads::GetMe
+    #[must_use]
+    pub fn get_me_retries(self, retries: u32) -> Self {
+        Self { get_me: GetMeConfig { retries, ..self.get_me }, ..self }
+    }
+
+    /// Specifies the delay before retrying the initial [`GetMe`] request,
+    /// based on the number of failed attempts so far.
+    ///
+    /// By default, it's [`exponential_backoff_strategy`].
+    ///
+    /// [`GetMe`]: crate::payloads::GetMe
+    /// [`exponential_backoff_strategy`]: crate::backoff::exponential_backoff_strategy
+    #[must_use]
+    pub fn get_me_backoff<F>(self, backoff: F) -> Self
+    where
+        F: Fn(u32) -> Duration + Send + Sync + 'static,
+    {
+        Self { get_me: GetMeConfig { backoff: Arc::new(backoff), ..self.get_me }, ..self }
+    }
+
+    /// Specifies what to do if the initial [`GetMe`] request fails even after
+    /// [retries].
+    ///
+    /// By default, it's [`GetMeFallback::Fail`].
+    ///
+    /// [`GetMe`]: crate::payloads::GetMe
+    /// [retries]: DispatcherBuilder::get_me_retries
+    #[must_use]
+    pub fn get_me_fallback(self, fallback: GetMeFallback) -> Self {
+        Self { get_me: GetMeConfig { fallback, ..self.get_me }, ..self }
+    }
+
+    /// Specifies a handler of the error of the initial [`GetMe`] request,
+    /// which is used with [`GetMeFallback::ErrorHandler`].
+    ///
+    /// By default, it's [`LoggingErrorHandler`].
+    ///
+    /// [`GetMe`]: crate::payloads::GetMe
+    #[must_use]
+    pub fn get_me_error_handler(self, handler: Arc<dyn ErrorHandler<R::Err> + Send + Sync>) -> Self
+    where
+        R::Err: 'static,
+    {
+        let error_handler: Arc<dyn Any + Send + Sync> = Arc::new(handler);
+        Self { get_me: GetMeConfig { error_handler: Some(error_handler), ..self.get_me }, ..self }
+    }
+
+    /// Specifies information about the bot, so that the initial [`GetMe`]
+    /// request is skipped.
+    ///
+    /// This is useful when [`Me`] was already requested, e.g. to set up a
+    /// webhook, or when many dispatchers are started for the same bot.
+    ///
+    /// [`GetMe`]: crate::payloads::GetMe
+    #[must_use]
+    pub fn me(self, me: Me) -> Self {
+        Self { get_me: GetMeConfig { me: Some(me), ..self.get_me }, ..self }
+    }
+
+    /// Applies options loaded from a config file, see [`DispatcherConfig`].
+    ///
+    /// Options which aren't in [`DispatcherConfig`] are left as they are.
+    ///
+    /// ## Panics
+    ///
+    /// If the config has invalid values, which can only happen if it wasn't
+    /// deserialized.
+    #[must_use]
+    pub fn config(self, config: DispatcherConfig) -> Self {
+        let DispatcherConfig {
+            worker_queue_size,
+            worker_pool_size,
+            get_me_retries,
+            get_me_fallback,
+            ctrlc_handler,
+        } = config;
+
+        let mut this = self
+            .worker_queue_size(worker_queue_size)
+            .get_me_retries(get_me_retries)
+            .get_me_fallback(get_me_fallback);
+        if let Some(size) = worker_pool_size {
+            this = this.worker_pool(size);
+        }
+
+        Self { ctrlc_handler: this.ctrlc_handler || ctrlc_handler, ..this }
+    }
+
     /// Specifies the stack size available to the dispatcher.
     ///
     /// By default, it's 8 * 1024 * 1024 bytes (8 MiB).
@@ -180,23 +515,35 @@ where
         let Self {
             bot,
             dependencies,
+            request_dependencies,
             handler,
             default_handler,
             error_handler,
+            retries,
+            update_interceptor,
+            outcome_hook,
             ctrlc_handler,
             distribution_f: _,
             worker_queue_size,
+            worker_pool_size,
+            get_me,
         } = self;
 
         DispatcherBuilder {
             bot,
             dependencies,
+            request_dependencies,
             handler,
             default_handler,
             error_handler,
+            retries,
+            update_interceptor,
+            outcome_hook,
             ctrlc_handler,
             distribution_f: f,
             worker_queue_size,
+            worker_pool_size,
+            get_me,
         }
     }
 
@@ -205,28 +552,70 @@ where
     /// ## Panics
     /// This function will panic at run-time if [`dptree`] fails to type-check
     /// the provided handler. An appropriate error message will be emitted.
+    ///
+    /// [`Me`] is provided to the handler only if it was passed to
+    /// [`DispatcherBuilder::me`] or [`DispatcherBuilder::dependencies`], or if
+    /// the [fallback] of the initial [`GetMe`] request is
+    /// [`GetMeFallback::Fail`].
+    ///
+    /// [fallback]: DispatcherBuilder::get_me_fallback
+    /// [`GetMe`]: crate::payloads::GetMe
     #[must_use]
     pub fn build(self) -> Dispatcher<R, Err, Key> {
         let Self {
             bot,
             dependencies,
+            request_dependencies,
             handler,
             default_handler,
             error_handler,
+            retries,
+            update_interceptor,
+            outcome_hook,
             distribution_f,
             worker_queue_size,
+            worker_pool_size,
             ctrlc_handler,
+            mut get_me,
         } = self;
 
-        dptree::type_check(
-            handler.sig(),
-            &dependencies,
-            &[
-                dptree::Type::of::<R>(),
-                dptree::Type::of::<teloxide_core::types::Update>(),
-                dptree::Type::of::<teloxide_core::types::Me>(),
-            ],
+        let mut dependencies = dependencies;
+        if let Some(me) = get_me.me.take() {
+            dependencies.insert(me);
+        }
+
+        let tasks = TaskTracker::new();
+        dependencies.insert(tasks.clone());
+
+        let (retries, scheduled_retries) = match retries {
+            Some(Retries { updates, scheduled }) => (Some(updates), Some(scheduled)),
+            None => (None, None),
+        };
+
+        let mut provided_types =
+            vec![dptree::Type::of::<R>(), dptree::Type::of::<teloxide_core::types::Update>()];
+        // Otherwise, dispatching may start without `Me`
+        if get_me.fallback == GetMeFallback::Fail {
+            provided_types.push(dptree::Type::of::<Me>());
+        }
+        let request_types = request_dependencies.iter().map(|dependency| dependency.ty);
+        let provided_types: Arc<[_]> = provided_types.into_iter().chain(request_types).collect();
+
+        let me = dptree::Type::of::<Me>();
+        assert!(
+            !missing_types(handler.sig(), &dependencies, &provided_types).contains(&me.name),
+            "the handler requires `Me`, which isn't provided if `getMe` fails with \
+             `GetMeFallback::{:?}`, pass it to `DispatcherBuilder::me`",
+            get_me.fallback
         );
+        dptree::type_check(handler.sig(), &dependencies, &provided_types);
+
+        if handler.description().allowed_updates().is_empty() {
+            log::warn!(
+                "The handler doesn't accept any kind of updates, all updates will be passed to \
+                 the default handler"
+            );
+        }
 
         // If the `ctrlc_handler` feature is not enabled, don't emit a warning.
         let _ = ctrlc_handler;
@@ -234,16 +623,31 @@ where
         let dp = Dispatcher {
             bot,
             dependencies,
-            handler,
-            default_handler,
-            error_handler,
+            handlers: Handlers {
+                handler: Arc::new(RwLock::new(handler)),
+                request_dependencies: request_dependencies.into_iter().map(|d| d.factory).collect(),
+                default_handler,
+                error_handler,
+                update_interceptor,
+                outcome_hook,
+                // Set by the update listener, see `UpdateListener::processed_callback`
+                processed_callback: None,
+                scheduled_retries,
+            },
+            provided_types,
+            retries,
             state: ShutdownToken::new(),
+            tasks,
             distribution_f,
             worker_queue_size,
             workers: HashMap::new(),
             default_worker: None,
+            worker_pool_size,
+            worker_pool: Vec::new(),
+            next_pool_worker: 0,
             current_number_of_active_workers: Default::default(),
             max_number_of_active_workers: Default::default(),
+            get_me,
         };
 
         #[cfg(feature = "ctrlc_handler")]
@@ -267,16 +671,31 @@ where
 /// updates from the same chat are processed sequentially. Learn more about
 /// [update grouping].
 ///
+/// ## Dependencies
+///
+/// Besides the [dependencies] passed to the builder, handlers receive the bot
+/// and the update being processed, both as [`Update`] and `Arc<Update>`. The
+/// latter refers to the same value, which is shared with the
+/// [outcome hook], so prefer it over [`Update`] in handlers that only need to
+/// read the update, to avoid cloning it. Background tasks can be spawned with
+/// the [`TaskTracker`] dependency, so that they aren't dropped on shutdown.
+///
+/// Dependencies that must not be shared between updates can be created for
+/// each update with [`DispatcherBuilder::request_dependency`].
+///
 /// See also: ["Dispatching or
 /// REPLs?"](../dispatching/index.html#dispatching-or-repls)
 ///
 /// [update grouping]: DispatcherBuilder#update-grouping
+/// [dependencies]: DispatcherBuilder::dependencies
+/// [outcome hook]: DispatcherBuilder::outcome_hook
 pub struct Dispatcher<R, Err, Key> {
     bot: R,
     dependencies: DependencyMap,
-
-    handler: Arc<UpdateHandler<Err>>,
-    default_handler: DefaultHandler,
+    // Passed to every spawned worker.
+    handlers: Handlers<Err>,
+    // Types provided by the dispatcher, used to type check replaced handlers.
+    provided_types: Arc<[dptree::Type]>,
 
     distribution_f: fn(&Update) -> Option<Key>,
     worker_queue_size: usize,
@@ -286,25 +705,383 @@ pub struct Dispatcher<R, Err, Key> {
     workers: HashMap<Key, Worker>,
     // The default TX part that consume updates concurrently.
     default_worker: Option<Worker>,
+    // If set, all updates are consumed by a fixed number of sequential workers instead.
+    worker_pool_size: Option<usize>,
+    worker_pool: Vec<Worker>,
+    // The pool worker that receives the next update without a distribution key.
+    next_pool_worker: usize,
+
+    // Updates to be handled again, see `RetryErrorHandler`.
+    retries: Option<mpsc::UnboundedReceiver<Update>>,
 
-    error_handler: Arc<dyn ErrorHandler<Err> + Send + Sync>,
+    get_me: GetMeConfig,
 
     state: ShutdownToken,
+    tasks: TaskTracker,
 }
 
 struct Worker {
     tx: tokio::sync::mpsc::Sender<Update>,
     handle: tokio::task::JoinHandle<()>,
     is_waiting: Arc<AtomicBool>,
+    // The number of updates sent to this worker, which handling is not yet finished.
+    pending: Arc<AtomicUsize>,
+}
+
+// TODO: it is allowed to return message as response on telegram request in
+// webhooks, so we can allow this too. See more there: https://core.telegram.org/bots/api#making-requests-when-getting-updates
+
+/// A handler that processes updates from Telegram.
+pub type UpdateHandler<Err> = dptree::Handler<'static, Result<(), Err>, DpHandlerDescription>;
+
+type HandlerSlot<Err> = Arc<RwLock<Arc<UpdateHandler<Err>>>>;
+
+/// A handle which replaces the handler of a [`Dispatcher`], see
+/// [`Dispatcher::handler_replacer`].
+pub struct HandlerReplacer<Err> {
+    handler: HandlerSlot<Err>,
+    dependencies: Arc<DependencyMap>,
+    provided_types: Arc<[dptree::Type]>,
+}
+
+impl<Err> HandlerReplacer<Err>
+where
+    Err: 'static,
+{
+    /// Replaces the handler of the dispatcher, see
+    /// [`Dispatcher::replace_handler`].
+    ///
+    /// Returns an error and keeps the previous handler if the new handler
+    /// requires dependencies which aren't provided by the dispatcher.
+    pub fn replace(&self, handler: UpdateHandler<Err>) -> Result<(), ReplaceHandlerError> {
+        let missing = missing_types(handler.sig(), &self.dependencies, &self.provided_types);
+        if !missing.is_empty() {
+            return Err(ReplaceHandlerError { missing });
+        }
+
+        *self.handler.write().unwrap() = Arc::new(handler);
+        Ok(())
+    }
+}
+
+/// Returns the types required by a handler with the signature `sig`, which
+/// are neither in `dependencies` nor in `provided_types`.
+///
+/// Unlike [`dptree::type_check`], this doesn't panic.
+fn missing_types(
+    sig: &HandlerSignature,
+    dependencies: &DependencyMap,
+    provided_types: &[dptree::Type],
+) -> Vec<&'static str> {
+    let HandlerSignature::Other { obligations, .. } = sig else { return Vec::new() };
+
+    // `DependencyMap` doesn't expose its types other than via `Debug`, which
+    // lists the type ids of its dependencies
+    let dependencies = format!("{dependencies:?}");
+    obligations
+        .keys()
+        .filter(|ty| !provided_types.contains(ty))
+        .filter(|ty| !dependencies.contains(&format!("{:?}", ty.id)))
+        .map(|ty| ty.name)
+        .collect()
+}
+
+/// An error returned from [`Dispatcher::replace_handler`] and
+/// [`HandlerReplacer::replace`] when the new handler requires dependencies
+/// which aren't provided by the dispatcher.
+#[derive(Clone, Debug, Error)]
+#[error("the new handler requires dependencies which aren't provided: {}", missing.join(", "))]
+pub struct ReplaceHandlerError {
+    missing: Vec<&'static str>,
+}
+
+impl ReplaceHandlerError {
+    /// Returns the names of the types which aren't provided.
+    #[must_use]
+    pub fn missing_types(&self) -> &[&'static str] {
+        &self.missing
+    }
+}
+
+impl<Err> Clone for HandlerReplacer<Err> {
+    fn clone(&self) -> Self {
+        Self {
+            handler: Arc::clone(&self.handler),
+            dependencies: Arc::clone(&self.dependencies),
+            provided_types: Arc::clone(&self.provided_types),
+        }
+    }
+}
+
+type DefaultHandler = Arc<dyn Fn(Arc<Update>) -> BoxFuture<'static, ()> + Send + Sync>;
+
+type UpdateInterceptor = Arc<dyn Fn(Update) -> BoxFuture<'static, Option<Update>> + Send + Sync>;
+
+type OutcomeHook = Arc<dyn Fn(Arc<Update>, UpdateOutcome) -> BoxFuture<'static, ()> + Send + Sync>;
+
+type DependencyFactory = Arc<dyn Fn(&Update, &mut DependencyMap) + Send + Sync>;
+
+/// A dependency specified with [`DispatcherBuilder::request_dependency`].
+struct RequestDependency {
+    ty: dptree::Type,
+    factory: DependencyFactory,
+}
+
+/// The outcome of processing an update, passed to the
+/// [`DispatcherBuilder::outcome_hook`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum UpdateOutcome {
+    /// The handler has successfully processed the update.
+    Handled,
+    /// The handler has returned an error (which was passed to the error
+    /// handler).
+    Failed,
+    /// No handler has processed the update (so it was passed to the default
+    /// handler).
+    Unhandled,
+}
+
+/// What [`Dispatcher`] does with updates which aren't handled by the handler,
+/// see [`DispatcherBuilder::unhandled_update_policy`].
+#[derive(Clone, Default)]
+#[non_exhaustive]
+pub enum UnhandledUpdatePolicy {
+    /// Do nothing.
+    Ignore,
+    /// Log updates with [`log::warn`], using their compact [`Display`]
+    /// representation.
+    ///
+    /// [`Display`]: std::fmt::Display
+    #[default]
+    Log,
+    /// Count updates per kind, see [`UnhandledUpdates`].
+    Metric(UnhandledUpdates),
+    /// Call a function, see [`UnhandledUpdatePolicy::custom`].
+    Custom(Arc<dyn Fn(Arc<Update>) -> BoxFuture<'static, ()> + Send + Sync>),
+}
+
+impl UnhandledUpdatePolicy {
+    /// Creates [`UnhandledUpdatePolicy::Custom`], which calls `handler` with
+    /// unhandled updates.
+    #[must_use]
+    pub fn custom<H, Fut>(handler: H) -> Self
+    where
+        H: Fn(Arc<Update>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        Self::Custom(Arc::new(move |upd| Box::pin(handler(upd))))
+    }
+
+    fn into_default_handler(self) -> DefaultHandler {
+        match self {
+            Self::Ignore => Arc::new(|_| Box::pin(async {})),
+            Self::Log => Arc::new(|upd| {
+                log::warn!("Unhandled update: {upd}");
+                Box::pin(async {})
+            }),
+            Self::Metric(counters) => Arc::new(move |upd| {
+                counters.record(&upd);
+                Box::pin(async {})
+            }),
+            Self::Custom(handler) => handler,
+        }
+    }
+}
+
+impl Debug for UnhandledUpdatePolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ignore => f.write_str("Ignore"),
+            Self::Log => f.write_str("Log"),
+            Self::Metric(counters) => f.debug_tuple("Metric").field(counters).finish(),
+            Self::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+/// Counters of unhandled updates per kind, used with
+/// [`UnhandledUpdatePolicy::Metric`].
+///
+/// Clones share the same counters, so they can be exported to a monitoring
+/// system while the dispatcher is running.
+#[derive(Clone, Debug, Default)]
+pub struct UnhandledUpdates {
+    counters: Arc<std::sync::Mutex<HashMap<&'static str, u64>>>,
+}
+
+impl UnhandledUpdates {
+    /// Creates counters, which are all zero.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of unhandled updates of the kind `kind`, which is
+    /// the name of the field in the Telegram API (e.g. `edited_message`), see
+    /// [`UpdateKind::name`].
+    #[must_use]
+    pub fn count(&self, kind: &str) -> u64 {
+        self.counters.lock().unwrap().get(kind).copied().unwrap_or(0)
+    }
+
+    /// Returns the number of all unhandled updates.
+    #[must_use]
+    pub fn total(&self) -> u64 {
+        self.counters.lock().unwrap().values().sum()
+    }
+
+    /// Returns the numbers of unhandled updates per kind, only including
+    /// kinds which were counted at least once.
+    #[must_use]
+    pub fn snapshot(&self) -> HashMap<&'static str, u64> {
+        self.counters.lock().unwrap().clone()
+    }
+
+    fn record(&self, update: &Update) {
+        *self.counters.lock().unwrap().entry(update.kind.name()).or_default() += 1;
+    }
+}
+
+/// What [`Dispatcher`] does if the initial [`GetMe`] request fails, see
+/// [`DispatcherBuilder::get_me_fallback`].
+///
+/// [`GetMe`]: crate::payloads::GetMe
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum GetMeFallback {
+    /// Stop dispatching: [`Dispatcher::try_dispatch_with_listener`] returns
+    /// the error, while other dispatching methods panic.
+    #[default]
+    Fail,
+    /// Log the error and dispatch without [`Me`] in the dependencies.
+    ///
+    /// [`DispatcherBuilder::build`] panics if the handler requires [`Me`]
+    /// (e.g. [`filter_command`]), unless it's passed to
+    /// [`DispatcherBuilder::me`] or [`DispatcherBuilder::dependencies`].
+    ///
+    /// [`filter_command`]: crate::dispatching::HandlerExt::filter_command
+    Skip,
+    /// Pass the error to the handler set with
+    /// [`DispatcherBuilder::get_me_error_handler`] and dispatch without
+    /// [`Me`], like [`GetMeFallback::Skip`].
+    ErrorHandler,
+}
+
+/// Options of a [`Dispatcher`], which can be loaded from a config file.
+///
+/// Missing fields are set to their defaults, and invalid values are rejected
+/// when deserializing. See [`DispatcherBuilder::config`].
+///
+/// ## Examples
+///
+/// ```
+/// use teloxide::dispatching::{DispatcherConfig, GetMeFallback};
+///
+/// let config: DispatcherConfig =
+///     serde_json::from_str(r#"{ "worker_queue_size": 128, "get_me_fallback": "skip" }"#).unwrap();
+/// assert_eq!(config.worker_queue_size, 128);
+/// assert_eq!(config.get_me_fallback, GetMeFallback::Skip);
+/// assert_eq!(config.worker_pool_size, None);
+///
+/// assert!(serde_json::from_str::<DispatcherConfig>(r#"{ "worker_pool_size": 0 }"#).is_err());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+#[non_exhaustive]
+pub struct DispatcherConfig {
+    /// See [`DispatcherBuilder::worker_queue_size`].
+    ///
+    /// Default - 64.
+    #[serde(deserialize_with = "crate::utils::config::positive")]
+    pub worker_queue_size: usize,
+
+    /// See [`DispatcherBuilder::worker_pool`].
+    ///
+    /// Default - None.
+    #[serde(deserialize_with = "crate::utils::config::positive_opt")]
+    pub worker_pool_size: Option<usize>,
+
+    /// See [`DispatcherBuilder::get_me_retries`].
+    ///
+    /// Default - 3.
+    pub get_me_retries: u32,
+
+    /// See [`DispatcherBuilder::get_me_fallback`].
+    ///
+    /// Default - [`GetMeFallback::Fail`].
+    pub get_me_fallback: GetMeFallback,
+
+    /// See [`DispatcherBuilder::enable_ctrlc_handler`]. Ignored without the
+    /// `ctrlc_handler` feature.
+    ///
+    /// Default - false.
+    pub ctrlc_handler: bool,
+}
+
+impl Default for DispatcherConfig {
+    fn default() -> Self {
+        let get_me = GetMeConfig::default();
+
+        Self {
+            worker_queue_size: DEFAULT_WORKER_QUEUE_SIZE,
+            worker_pool_size: None,
+            get_me_retries: get_me.retries,
+            get_me_fallback: get_me.fallback,
+            ctrlc_handler: false,
+        }
+    }
+}
+
+#[derive(Clone)]
+struct GetMeConfig {
+    retries: u32,
+    backoff: Arc<dyn Fn(u32) -> Duration + Send + Sync>,
+    fallback: GetMeFallback,
+    // `Arc<dyn ErrorHandler<R::Err> + Send + Sync>`, see
+    // `DispatcherBuilder::get_me_error_handler`
+    error_handler: Option<Arc<dyn Any + Send + Sync>>,
+    me: Option<Me>,
 }
 
-// TODO: it is allowed to return message as response on telegram request in
-// webhooks, so we can allow this too. See more there: https://core.telegram.org/bots/api#making-requests-when-getting-updates
+impl Default for GetMeConfig {
+    fn default() -> Self {
+        Self {
+            retries: 3,
+            backoff: Arc::new(crate::backoff::exponential_backoff_strategy),
+            fallback: GetMeFallback::Fail,
+            error_handler: None,
+            me: None,
+        }
+    }
+}
 
-/// A handler that processes updates from Telegram.
-pub type UpdateHandler<Err> = dptree::Handler<'static, Result<(), Err>, DpHandlerDescription>;
+/// Everything that is needed by workers to handle updates.
+struct Handlers<Err> {
+    handler: HandlerSlot<Err>,
+    request_dependencies: Arc<[DependencyFactory]>,
+    default_handler: DefaultHandler,
+    error_handler: Arc<dyn UpdateErrorHandler<Err> + Send + Sync>,
+    update_interceptor: Option<UpdateInterceptor>,
+    outcome_hook: Option<OutcomeHook>,
+    processed_callback: Option<ProcessedCallback>,
+    scheduled_retries: Option<ScheduledRetries>,
+}
 
-type DefaultHandler = Arc<dyn Fn(Arc<Update>) -> BoxFuture<'static, ()> + Send + Sync>;
+impl<Err> Clone for Handlers<Err> {
+    fn clone(&self) -> Self {
+        Self {
+            handler: Arc::clone(&self.handler),
+            request_dependencies: Arc::clone(&self.request_dependencies),
+            default_handler: Arc::clone(&self.default_handler),
+            error_handler: Arc::clone(&self.error_handler),
+            update_interceptor: self.update_interceptor.clone(),
+            outcome_hook: self.outcome_hook.clone(),
+            processed_callback: self.processed_callback.clone(),
+            scheduled_retries: self.scheduled_retries.clone(),
+        }
+    }
+}
 
 impl<R, Err> Dispatcher<R, Err, DefaultKey>
 where
@@ -317,20 +1094,21 @@ where
     where
         Err: Debug,
     {
-        const DEFAULT_WORKER_QUEUE_SIZE: usize = 64;
-
         DispatcherBuilder {
             bot,
             dependencies: DependencyMap::new(),
+            request_dependencies: Vec::new(),
             handler: Arc::new(handler),
-            default_handler: Arc::new(|upd| {
-                log::warn!("Unhandled update: {upd:?}");
-                Box::pin(async {})
-            }),
-            error_handler: LoggingErrorHandler::new(),
+            default_handler: UnhandledUpdatePolicy::default().into_default_handler(),
+            error_handler: Arc::new(IgnoringUpdate(LoggingErrorHandler::new())),
+            retries: None,
+            update_interceptor: None,
+            outcome_hook: None,
             ctrlc_handler: false,
             worker_queue_size: DEFAULT_WORKER_QUEUE_SIZE,
+            worker_pool_size: None,
             distribution_f: default_distribution_function,
+            get_me: GetMeConfig::default(),
         }
     }
 }
@@ -388,10 +1166,16 @@ where
     /// Same as `dispatch_with_listener` but returns a `Err(_)` instead of
     /// panicking when the initial telegram api call (`get_me`) fails.
     ///
+    /// The call is [retried] first, and skipped if [`Me`] was passed to
+    /// [`DispatcherBuilder::me`] or [`DispatcherBuilder::dependencies`]. See
+    /// also [`DispatcherBuilder::get_me_fallback`].
+    ///
     /// Starts your bot with custom `update_listener` and
     /// `update_listener_error_handler`.
     ///
     /// This method adds the same dependencies as [`Dispatcher::dispatch`].
+    ///
+    /// [retried]: DispatcherBuilder::get_me_retries
     pub async fn try_dispatch_with_listener<'a, UListener, Eh>(
         &'a mut self,
         mut update_listener: UListener,
@@ -402,15 +1186,40 @@ where
         Eh: ErrorHandler<UListener::Err> + Send + Sync + 'a,
         UListener::Err: Debug,
     {
-        // FIXME: there should be a way to check if dependency is already inserted
-        let me = self.bot.get_me().send().await?;
-        self.dependencies.insert(me);
+        if self.dependencies.try_get::<Me>().is_none() {
+            match self.get_me().await {
+                Ok(me) => {
+                    self.dependencies.insert(me);
+                }
+                Err(err) => match self.get_me.fallback {
+                    GetMeFallback::Fail => return Err(err),
+                    GetMeFallback::Skip => {
+                        log::error!(
+                            "Couldn't get information about the bot, dispatching without `Me`: \
+                             {err}"
+                        );
+                    }
+                    GetMeFallback::ErrorHandler => {
+                        let handler = self.get_me.error_handler.as_ref().and_then(|handler| {
+                            handler.downcast_ref::<Arc<dyn ErrorHandler<R::Err> + Send + Sync>>()
+                        });
+                        let handler = handler.cloned().unwrap_or_else(|| {
+                            LoggingErrorHandler::with_custom_text(
+                                "Couldn't get information about the bot, dispatching without `Me`",
+                            )
+                        });
+                        handler.handle_error(err).await;
+                    }
+                },
+            }
+        }
         self.dependencies.insert(self.bot.clone());
 
-        let description = self.handler.description();
+        let description = self.handlers.handler.read().unwrap().description().clone();
         let allowed_updates = description.allowed_updates();
         log::debug!("hinting allowed updates: {allowed_updates:?}");
         update_listener.hint_allowed_updates(&mut allowed_updates.into_iter());
+        self.handlers.processed_callback = update_listener.processed_callback();
 
         let stop_token = Some(update_listener.stop_token());
         self.start_listening(update_listener, update_listener_error_handler, stop_token).await;
@@ -418,6 +1227,22 @@ where
         Ok(())
     }
 
+    async fn get_me(&self) -> Result<Me, R::Err> {
+        let mut errors = 0;
+        loop {
+            match self.bot.get_me().send().await {
+                Ok(me) => return Ok(me),
+                Err(err) if errors < self.get_me.retries => {
+                    let delay = (self.get_me.backoff)(errors);
+                    log::warn!("`getMe` failed, retrying in {delay:?}: {err}");
+                    errors += 1;
+                    tokio::time::sleep(delay).await;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
     async fn start_listening<'a, UListener, Eh>(
         &'a mut self,
         mut update_listener: UListener,
@@ -429,6 +1254,7 @@ where
         UListener::Err: Debug,
     {
         self.state.start_dispatching();
+        self.tasks.reset();
 
         let stream = update_listener.as_stream();
         tokio::pin!(stream);
@@ -436,10 +1262,18 @@ where
         loop {
             self.remove_inactive_workers_if_needed().await;
 
-            let res = future::select(stream.next(), pin!(self.state.wait_for_changes()))
-                .map(either)
-                .await
-                .map_either(|l| l.0, |r| r.0);
+            let res = {
+                let retry = pin!(next_retry(&mut self.retries));
+                let next_update = future::select(stream.next(), retry).map(|res| match res {
+                    future::Either::Left((upd, _)) => upd,
+                    future::Either::Right((upd, _)) => Some(Ok(upd)),
+                });
+
+                future::select(pin!(next_update), pin!(self.state.wait_for_changes()))
+                    .map(either)
+                    .await
+                    .map_either(|l| l.0, |r| r.0)
+            };
 
             match res {
                 Either::Left(upd) => match upd {
@@ -457,17 +1291,65 @@ where
             }
         }
 
-        self.workers
+        self.wait_for_workers().await;
+        self.wait_for_tasks().await;
+
+        self.state.done();
+    }
+
+    /// Waits for all workers to finish, aborting them if
+    /// [`ShutdownToken::shutdown_with_timeout`] asks to.
+    async fn wait_for_workers(&mut self) {
+        let (handles, pending): (FuturesUnordered<_>, Vec<_>) = self
+            .workers
             .drain()
-            .map(|(_chat_id, worker)| worker.handle)
-            .chain(self.default_worker.take().map(|worker| worker.handle))
-            .collect::<FuturesUnordered<_>>()
-            .for_each(|res| async {
-                res.expect("Failed to wait for a worker.");
+            .map(|(_chat_id, worker)| worker)
+            .chain(self.default_worker.take())
+            .chain(self.worker_pool.drain(..))
+            .map(|Worker { tx, handle, pending, .. }| {
+                // Close the channel, so that the worker stops after handling queued updates
+                drop(tx);
+
+                let abort_handle = handle.abort_handle();
+                (handle, (abort_handle, pending))
             })
-            .await;
+            .unzip();
 
-        self.state.done();
+        let all_finished = handles.for_each(|res| async {
+            if let Err(err) = res {
+                assert!(err.is_cancelled(), "Failed to wait for a worker: {err}");
+            }
+        });
+        let mut all_finished = pin!(all_finished);
+
+        if let future::Either::Right(((), _)) =
+            future::select(all_finished.as_mut(), pin!(self.state.wait_for_abort())).await
+        {
+            let cancelled = pending
+                .iter()
+                .filter(|(handle, _)| !handle.is_finished())
+                .map(|(handle, pending)| {
+                    handle.abort();
+                    pending.load(Ordering::Relaxed)
+                })
+                .sum();
+
+            all_finished.await;
+
+            log::warn!("Aborted handling of {cancelled} update(s).");
+            self.state.set_cancelled(cancelled);
+        }
+    }
+
+    /// Cancels or waits for the tasks spawned with the [`TaskTracker`], unless
+    /// [`ShutdownToken::shutdown_with_timeout`] asks to abort the handlers.
+    async fn wait_for_tasks(&self) {
+        if let future::Either::Right(((), _)) =
+            future::select(pin!(self.tasks.cancel_and_wait()), pin!(self.state.wait_for_abort()))
+                .await
+        {
+            log::warn!("Stopped waiting for {} background task(s).", self.tasks.len());
+        }
     }
 
     async fn process_update<LErr, LErrHandler>(
@@ -485,42 +1367,62 @@ where
                             This is a bug in teloxide-core, please open an issue here: \
                             https://github.com/teloxide/teloxide/issues.",
                     );
+                    if let Some(callback) = &self.handlers.processed_callback {
+                        callback(upd.id).await;
+                    }
                     return;
                 }
 
-                let worker = match (self.distribution_f)(&upd) {
-                    Some(key) => self.workers.entry(key).or_insert_with(|| {
+                let key = (self.distribution_f)(&upd);
+
+                let worker = match (self.worker_pool_size, key) {
+                    (Some(size), key) => {
+                        if self.worker_pool.is_empty() {
+                            self.worker_pool = (0..size)
+                                .map(|_| {
+                                    spawn_worker(
+                                        self.dependencies.clone(),
+                                        self.handlers.clone(),
+                                        Arc::clone(&self.current_number_of_active_workers),
+                                        Arc::clone(&self.max_number_of_active_workers),
+                                        self.worker_queue_size,
+                                    )
+                                })
+                                .collect();
+                        }
+
+                        let idx = match key {
+                            Some(key) => {
+                                (self.workers.hasher().hash_one(&key) % size as u64) as usize
+                            }
+                            None => {
+                                let idx = self.next_pool_worker;
+                                self.next_pool_worker = (idx + 1) % size;
+                                idx
+                            }
+                        };
+
+                        &mut self.worker_pool[idx]
+                    }
+                    (None, Some(key)) => self.workers.entry(key).or_insert_with(|| {
                         let deps = self.dependencies.clone();
-                        let handler = Arc::clone(&self.handler);
-                        let default_handler = Arc::clone(&self.default_handler);
-                        let error_handler = Arc::clone(&self.error_handler);
 
                         spawn_worker(
                             deps,
-                            handler,
-                            default_handler,
-                            error_handler,
+                            self.handlers.clone(),
                             Arc::clone(&self.current_number_of_active_workers),
                             Arc::clone(&self.max_number_of_active_workers),
                             self.worker_queue_size,
                         )
                     }),
-                    None => self.default_worker.get_or_insert_with(|| {
+                    (None, None) => self.default_worker.get_or_insert_with(|| {
                         let deps = self.dependencies.clone();
-                        let handler = Arc::clone(&self.handler);
-                        let default_handler = Arc::clone(&self.default_handler);
-                        let error_handler = Arc::clone(&self.error_handler);
 
-                        spawn_default_worker(
-                            deps,
-                            handler,
-                            default_handler,
-                            error_handler,
-                            self.worker_queue_size,
-                        )
+                        spawn_default_worker(deps, self.handlers.clone(), self.worker_queue_size)
                     }),
                 };
 
+                worker.pending.fetch_add(1, Ordering::Relaxed);
                 worker.tx.send(upd).await.expect("TX is dead");
             }
             Err(err) => err_handler.clone().handle_error(err).await,
@@ -569,11 +1471,86 @@ where
         }
     }
 
+    /// Returns the tracker of background tasks, which is also available to
+    /// handlers as a dependency, see [`TaskTracker`].
+    pub fn task_tracker(&self) -> TaskTracker {
+        self.tasks.clone()
+    }
+
+    /// Spawns a background task, which is waited for when dispatching stops.
+    ///
+    /// This is a shortcut for `self.task_tracker().spawn(task)`, see
+    /// [`TaskTracker::spawn`].
+    pub fn spawn_tracked<F>(&self, task: F) -> tokio::task::JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        self.tasks.spawn(task)
+    }
+
     /// Returns a shutdown token, which can later be used to
     /// [`ShutdownToken::shutdown`].
     pub fn shutdown_token(&self) -> ShutdownToken {
         self.state.clone()
     }
+
+    /// Replaces the handler, without stopping dispatching.
+    ///
+    /// Updates which are being handled at the moment are finished by the
+    /// previous handler. To replace the handler while dispatching is running,
+    /// use [`Dispatcher::handler_replacer`].
+    ///
+    /// Note that the set of allowed updates is hinted to the update listener
+    /// only once, when dispatching starts. If the new handler accepts kinds of
+    /// updates which the previous one didn't, these updates may not be
+    /// received until dispatching is restarted.
+    ///
+    /// Returns an error and keeps the previous handler if the new handler
+    /// requires dependencies which aren't provided by the dispatcher.
+    pub fn replace_handler(&self, handler: UpdateHandler<Err>) -> Result<(), ReplaceHandlerError> {
+        self.handler_replacer().replace(handler)
+    }
+
+    /// Returns a handle, which can be used to [replace the handler] while
+    /// dispatching is running.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use teloxide::{dispatching::UpdateHandler, prelude::*};
+    ///
+    /// fn handler(maintenance: bool) -> UpdateHandler<teloxide::RequestError> {
+    ///     Update::filter_message().endpoint(move |bot: Bot, msg: Message| async move {
+    ///         let text = if maintenance { "Under maintenance" } else { "Hello!" };
+    ///         bot.send_message(msg.chat.id, text).await?;
+    ///         Ok(())
+    ///     })
+    /// }
+    ///
+    /// # async fn run() {
+    /// let mut dp = Dispatcher::builder(Bot::from_env(), handler(false)).build();
+    /// let replacer = dp.handler_replacer();
+    ///
+    /// tokio::spawn(async move {
+    ///     tokio::time::sleep(std::time::Duration::from_secs(60)).await;
+    ///     if let Err(err) = replacer.replace(handler(true)) {
+    ///         log::error!("{err}");
+    ///     }
+    /// });
+    ///
+    /// dp.dispatch().await;
+    /// # }
+    /// ```
+    ///
+    /// [replace the handler]: Dispatcher::replace_handler
+    pub fn handler_replacer(&self) -> HandlerReplacer<Err> {
+        HandlerReplacer {
+            handler: Arc::clone(&self.handlers.handler),
+            dependencies: Arc::new(self.dependencies.clone()),
+            provided_types: Arc::clone(&self.provided_types),
+        }
+    }
 }
 
 impl<R, Err, Key> Dispatcher<R, Err, Key> {
@@ -601,9 +1578,7 @@ impl<R, Err, Key> Dispatcher<R, Err, Key> {
 
 fn spawn_worker<Err>(
     deps: DependencyMap,
-    handler: Arc<UpdateHandler<Err>>,
-    default_handler: DefaultHandler,
-    error_handler: Arc<dyn ErrorHandler<Err> + Send + Sync>,
+    handlers: Handlers<Err>,
     current_number_of_active_workers: Arc<AtomicU32>,
     max_number_of_active_workers: Arc<AtomicU32>,
     queue_size: usize,
@@ -614,6 +1589,8 @@ where
     let (tx, mut rx) = tokio::sync::mpsc::channel(queue_size);
     let is_waiting = Arc::new(AtomicBool::new(true));
     let is_waiting_local = Arc::clone(&is_waiting);
+    let pending = Arc::new(AtomicUsize::new(0));
+    let pending_local = Arc::clone(&pending);
 
     let deps = Arc::new(deps);
 
@@ -626,65 +1603,123 @@ where
             }
 
             let deps = Arc::clone(&deps);
-            let handler = Arc::clone(&handler);
-            let default_handler = Arc::clone(&default_handler);
-            let error_handler = Arc::clone(&error_handler);
+            let handlers = handlers.clone();
 
-            handle_update(update, deps, handler, default_handler, error_handler).await;
+            handle_update(update, deps, handlers).await;
 
+            pending_local.fetch_sub(1, Ordering::Relaxed);
             current_number_of_active_workers.fetch_sub(1, Ordering::Relaxed);
             is_waiting_local.store(true, Ordering::Relaxed);
         }
     });
 
-    Worker { tx, handle, is_waiting }
+    Worker { tx, handle, is_waiting, pending }
 }
 
 fn spawn_default_worker<Err>(
     deps: DependencyMap,
-    handler: Arc<UpdateHandler<Err>>,
-    default_handler: DefaultHandler,
-    error_handler: Arc<dyn ErrorHandler<Err> + Send + Sync>,
+    handlers: Handlers<Err>,
     queue_size: usize,
 ) -> Worker
 where
     Err: Send + Sync + 'static,
 {
     let (tx, rx) = tokio::sync::mpsc::channel(queue_size);
+    let pending = Arc::new(AtomicUsize::new(0));
+    let pending_local = Arc::clone(&pending);
 
     let deps = Arc::new(deps);
 
     let handle = tokio::spawn(ReceiverStream::new(rx).for_each_concurrent(None, move |update| {
         let deps = Arc::clone(&deps);
-        let handler = Arc::clone(&handler);
-        let default_handler = Arc::clone(&default_handler);
-        let error_handler = Arc::clone(&error_handler);
+        let handlers = handlers.clone();
+        let pending = Arc::clone(&pending_local);
 
-        handle_update(update, deps, handler, default_handler, error_handler)
+        async move {
+            handle_update(update, deps, handlers).await;
+            pending.fetch_sub(1, Ordering::Relaxed);
+        }
     }));
 
-    Worker { tx, handle, is_waiting: Arc::new(AtomicBool::new(true)) }
+    Worker { tx, handle, is_waiting: Arc::new(AtomicBool::new(true)), pending }
 }
 
-async fn handle_update<Err>(
-    update: Update,
-    deps: Arc<DependencyMap>,
-    handler: Arc<UpdateHandler<Err>>,
-    default_handler: DefaultHandler,
-    error_handler: Arc<dyn ErrorHandler<Err> + Send + Sync>,
-) where
+async fn handle_update<Err>(update: Update, deps: Arc<DependencyMap>, handlers: Handlers<Err>)
+where
+    Err: Send + Sync + 'static,
+{
+    let id = update.id;
+    let processed_callback = handlers.processed_callback.clone();
+    let scheduled_retries = handlers.scheduled_retries.clone();
+
+    dispatch_update(update, deps, handlers).await;
+
+    // The update isn't processed yet, if it's going to be handled again
+    let retried = scheduled_retries.is_some_and(|retries| retries.lock().unwrap().remove(&id));
+    if let (Some(callback), false) = (processed_callback, retried) {
+        callback(id).await;
+    }
+}
+
+async fn dispatch_update<Err>(update: Update, deps: Arc<DependencyMap>, handlers: Handlers<Err>)
+where
     Err: Send + Sync + 'static,
 {
+    let Handlers {
+        handler,
+        request_dependencies,
+        default_handler,
+        error_handler,
+        update_interceptor,
+        outcome_hook,
+        processed_callback: _,
+        scheduled_retries: _,
+    } = handlers;
+
+    let update = match update_interceptor {
+        Some(interceptor) => match interceptor(update).await {
+            Some(update) => update,
+            None => return,
+        },
+        None => update,
+    };
+
     let mut deps = deps.deref().clone();
+    for factory in request_dependencies.iter() {
+        factory(&update, &mut deps);
+    }
     deps.insert(update);
-
-    match handler.dispatch(deps).await {
-        ControlFlow::Break(Ok(())) => {}
-        ControlFlow::Break(Err(err)) => error_handler.clone().handle_error(err).await,
-        ControlFlow::Continue(deps) => {
-            let update = deps.get();
-            (default_handler)(update).await;
+    // Handlers that don't need an owned copy of the update can take it as
+    // `Arc<Update>`, sharing the same allocation
+    let update = deps.get::<Update>();
+    deps.insert(Arc::clone(&update));
+
+    let handler = Arc::clone(&handler.read().unwrap());
+    let outcome = match handler.dispatch(deps).await {
+        ControlFlow::Break(Ok(())) => UpdateOutcome::Handled,
+        ControlFlow::Break(Err(err)) => {
+            error_handler.clone().handle_update_error(err, Arc::clone(&update)).await;
+            UpdateOutcome::Failed
         }
+        ControlFlow::Continue(_) => {
+            (default_handler)(Arc::clone(&update)).await;
+            UpdateOutcome::Unhandled
+        }
+    };
+
+    if let Some(hook) = outcome_hook {
+        hook(update, outcome).await;
+    }
+}
+
+/// Waits for an update to be handled again, forever if there are none.
+async fn next_retry(retries: &mut Option<mpsc::UnboundedReceiver<Update>>) -> Update {
+    match retries {
+        Some(retries) => match retries.recv().await {
+            Some(update) => update,
+            None => future::pending().await,
+        },
+        None => future::pending().await,
     }
 }
 
@@ -698,9 +1733,33 @@ fn either<L, R>(x: future::Either<L, R>) -> Either<L, R> {
 mod tests {
     use std::convert::Infallible;
 
+    use serde_json::json;
     use teloxide_core::Bot;
 
     use super::*;
+    use crate::{
+        test_utils::{
+            capture_logs, fake_api, me, message_json, message_update, unreachable_bot, update,
+        },
+        types::UpdateId,
+    };
+
+    /// Returns handlers which only pass updates to `handler`, logging errors.
+    fn handlers_of<Err>(handler: UpdateHandler<Err>) -> Handlers<Err>
+    where
+        Err: Debug + Send + Sync + 'static,
+    {
+        Handlers {
+            handler: Arc::new(RwLock::new(Arc::new(handler))),
+            request_dependencies: Arc::new([]),
+            default_handler: Arc::new(|_| Box::pin(async {})),
+            error_handler: Arc::new(IgnoringUpdate(LoggingErrorHandler::new())),
+            update_interceptor: None,
+            outcome_hook: None,
+            processed_callback: None,
+            scheduled_retries: None,
+        }
+    }
 
     #[tokio::test]
     async fn test_tokio_spawn() {
@@ -716,4 +1775,575 @@ mod tests {
         .await
         .unwrap();
     }
+
+    #[test]
+    #[should_panic(expected = "worker queue size must be greater than zero")]
+    fn zero_worker_queue_size() {
+        let _ = Dispatcher::<_, Infallible, _>::builder(Bot::new("TOKEN"), dptree::entry())
+            .worker_queue_size(0);
+    }
+
+    #[test]
+    fn handler_without_updates() {
+        use crate::{dispatching::UpdateFilterExt, types::AllowedUpdate};
+
+        const WARNING: &str = "The handler doesn't accept any kind of updates, all updates will \
+                               be passed to the default handler";
+
+        let handler: UpdateHandler<Infallible> = dptree::entry();
+        assert!(handler.description().allowed_updates().is_empty());
+        let logs = capture_logs(|| drop(Dispatcher::builder(unreachable_bot(), handler).build()));
+        assert!(logs.iter().any(|log| log == WARNING), "{logs:?}");
+
+        let handler: UpdateHandler<Infallible> =
+            dptree::entry().branch(Update::filter_message().endpoint(|| async { Ok(()) }));
+        assert_eq!(handler.description().allowed_updates(), [AllowedUpdate::Message]);
+        let logs = capture_logs(|| drop(Dispatcher::builder(unreachable_bot(), handler).build()));
+        assert!(!logs.iter().any(|log| log == WARNING), "{logs:?}");
+    }
+
+    #[tokio::test]
+    async fn shutdown_with_timeout_aborts_stuck_handlers() {
+        use std::time::Duration;
+
+        use tokio_stream::wrappers::UnboundedReceiverStream;
+
+        use crate::update_listeners::StatefulListener;
+
+        let update = message_update(1, 1, "hi");
+
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<Result<Update, Infallible>>();
+        let mut listener = StatefulListener::from_stream(UnboundedReceiverStream::new(rx));
+
+        tx.send(Ok(update)).unwrap();
+
+        let handler = dptree::endpoint(future::pending::<Result<(), Infallible>>);
+        let mut dp = Dispatcher::builder(Bot::new("TOKEN"), handler).build();
+        let token = dp.shutdown_token();
+
+        let dispatching = tokio::spawn(async move {
+            let stop_token = Some(listener.stop_token());
+            dp.start_listening(listener, LoggingErrorHandler::new(), stop_token).await;
+        });
+
+        let cancelled = loop {
+            match token.shutdown_with_timeout(Duration::from_millis(100)) {
+                Ok(shutdown) => break shutdown.await,
+                Err(_) => tokio::task::yield_now().await,
+            }
+        };
+
+        dispatching.await.unwrap();
+        assert_eq!(cancelled, 1);
+    }
+
+    #[tokio::test]
+    async fn update_interceptor_and_outcome_hook() {
+        use std::sync::Mutex;
+
+        let update = |id: u32| message_update(id, id.into(), "hi");
+
+        let outcomes = Arc::new(Mutex::new(Vec::new()));
+        let processed = Arc::new(Mutex::new(Vec::new()));
+        let handler = dptree::endpoint(|upd: Update| async move {
+            match upd.chat() {
+                Some(chat) if chat.id.0 == 2 => Err("failed"),
+                _ => Ok(()),
+            }
+        });
+        let handlers = Handlers {
+            update_interceptor: Some(Arc::new(|upd: Update| {
+                Box::pin(async move {
+                    match upd.from() {
+                        Some(user) if user.id.0 == 42 => None,
+                        _ => Some(upd),
+                    }
+                })
+            })),
+            outcome_hook: Some({
+                let outcomes = Arc::clone(&outcomes);
+                Arc::new(move |upd: Arc<Update>, outcome| {
+                    outcomes.lock().unwrap().push((upd.chat().unwrap().id.0, outcome));
+                    Box::pin(async {})
+                })
+            }),
+            processed_callback: Some({
+                let processed = Arc::clone(&processed);
+                Arc::new(move |id: crate::types::UpdateId| {
+                    processed.lock().unwrap().push(id.0);
+                    Box::pin(async {})
+                })
+            }),
+            ..handlers_of(handler)
+        };
+
+        let deps = Arc::new(DependencyMap::new());
+        for upd in [update(1), update(42), update(2)] {
+            handle_update(upd, Arc::clone(&deps), handlers.clone()).await;
+        }
+
+        assert_eq!(
+            *outcomes.lock().unwrap(),
+            [(1, UpdateOutcome::Handled), (2, UpdateOutcome::Failed)]
+        );
+        // Updates dropped by the interceptor are processed too
+        assert_eq!(*processed.lock().unwrap(), [1, 42, 2]);
+    }
+
+    #[tokio::test]
+    async fn unhandled_update_policy() {
+        let update = |kind: &str| update(1, kind, message_json(1, "hi"));
+
+        let unhandled = UnhandledUpdates::new();
+        let handler = dptree::filter(|upd: Update| matches!(upd.kind, UpdateKind::Message(_)))
+            .endpoint(|| async { Ok::<_, ()>(()) });
+        let handlers = Handlers {
+            default_handler: UnhandledUpdatePolicy::Metric(unhandled.clone())
+                .into_default_handler(),
+            ..handlers_of(handler)
+        };
+
+        let deps = Arc::new(DependencyMap::new());
+        for kind in ["message", "edited_message", "channel_post", "edited_message"] {
+            handle_update(update(kind), Arc::clone(&deps), handlers.clone()).await;
+        }
+
+        assert_eq!(unhandled.count("message"), 0);
+        assert_eq!(unhandled.count("edited_message"), 2);
+        assert_eq!(unhandled.total(), 3);
+        assert_eq!(
+            unhandled.snapshot(),
+            HashMap::from([("edited_message", 2), ("channel_post", 1)])
+        );
+    }
+
+    #[tokio::test]
+    async fn update_error_handler() {
+        use std::sync::Mutex;
+
+        let update = message_update(7, 1, "hi");
+
+        let errors = Arc::new(Mutex::new(Vec::new()));
+        let dp = Dispatcher::builder(
+            Bot::new("TOKEN"),
+            dptree::endpoint(|| async { Err::<(), _>("failed") }),
+        )
+        .update_error_handler({
+            let errors = Arc::clone(&errors);
+            Arc::new(move |err: &'static str, upd: Arc<Update>| {
+                errors.lock().unwrap().push((err, upd.id.0));
+                async {}
+            })
+        })
+        .build();
+        let handlers = dp.handlers.clone();
+
+        handle_update(update, Arc::new(DependencyMap::new()), handlers).await;
+
+        assert_eq!(*errors.lock().unwrap(), [("failed", 7)]);
+    }
+
+    #[tokio::test]
+    async fn retry_error_handler() {
+        use std::{sync::Mutex, time::Duration};
+
+        use tokio_stream::wrappers::UnboundedReceiverStream;
+
+        use crate::update_listeners::StatefulListener;
+
+        let update = |id: u32| message_update(id, id.into(), "hi");
+
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<Result<Update, Infallible>>();
+        let listener = StatefulListener::from_stream(UnboundedReceiverStream::new(rx));
+        tx.send(Ok(update(1))).unwrap();
+        tx.send(Ok(update(2))).unwrap();
+
+        let attempts = Arc::new(Mutex::new(HashMap::<u32, u32>::new()));
+        let handler = dptree::endpoint({
+            let attempts = Arc::clone(&attempts);
+            move |upd: Update| {
+                let mut attempts = attempts.lock().unwrap();
+                let attempt = attempts.entry(upd.id.0).or_default();
+                *attempt += 1;
+                let res = match (upd.id.0, *attempt) {
+                    (1, 1..=2) => Err("retry"),
+                    (2, _) => Err("fatal"),
+                    _ => Ok(()),
+                };
+                async move { res }
+            }
+        });
+
+        let failed = Arc::new(Mutex::new(Vec::new()));
+        let retry = RetryErrorHandler::new(|err: &&str| *err == "retry")
+            .backoff(|_| Duration::ZERO)
+            .fallback({
+                let failed = Arc::clone(&failed);
+                Arc::new(move |err: &'static str, upd: Arc<Update>| {
+                    failed.lock().unwrap().push((err, upd.id.0));
+                    async {}
+                })
+            });
+        let mut dp =
+            Dispatcher::builder(Bot::new("TOKEN"), handler).retry_error_handler(retry).build();
+        let dispatching = tokio::spawn(async move {
+            dp.start_listening(listener, LoggingErrorHandler::new(), None).await;
+        });
+
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while attempts.lock().unwrap().get(&1) != Some(&3) {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+        drop(tx);
+        dispatching.await.unwrap();
+
+        assert_eq!(*attempts.lock().unwrap(), HashMap::from([(1, 3), (2, 1)]));
+        assert_eq!(*failed.lock().unwrap(), [("fatal", 2)]);
+    }
+
+    #[tokio::test]
+    async fn retry_error_handler_offset_storage() {
+        use std::{
+            sync::atomic::{AtomicU32, Ordering},
+            time::Duration,
+        };
+
+        use crate::update_listeners::{
+            offset_storage::{FileOffsetStorage, OffsetStorage},
+            AsUpdateStream, Polling,
+        };
+
+        let path =
+            std::env::temp_dir().join(format!("teloxide-retry-offset-{}", std::process::id()));
+        let storage = FileOffsetStorage::new(&path);
+        let saved = || async { Arc::clone(&storage).load().await.unwrap() };
+
+        let (url, _) = fake_api(vec![Some(json!([{
+            "update_id": 5,
+            "message": message_json(1, "hi")
+        }]))])
+        .await;
+        let mut polling = Polling::builder(Bot::new("TOKEN").set_api_url(url))
+            .offset_storage(Arc::clone(&storage))
+            .build();
+        let processed_callback = polling.processed_callback();
+        let upd = pin!(polling.as_stream()).next().await.unwrap().unwrap();
+
+        let attempts = Arc::new(AtomicU32::new(0));
+        let handler = dptree::endpoint({
+            let attempts = Arc::clone(&attempts);
+            move || {
+                let first = attempts.fetch_add(1, Ordering::Relaxed) == 0;
+                async move {
+                    if first {
+                        Err("retry")
+                    } else {
+                        Ok(())
+                    }
+                }
+            }
+        });
+        let retry = RetryErrorHandler::new(|_: &&str| true).backoff(|_| Duration::ZERO);
+        let mut dp =
+            Dispatcher::builder(Bot::new("TOKEN"), handler).retry_error_handler(retry).build();
+        let handlers = Handlers { processed_callback, ..dp.handlers.clone() };
+        let deps = Arc::new(DependencyMap::new());
+
+        // The offset isn't saved while the update waits for a retry
+        handle_update(upd, Arc::clone(&deps), handlers.clone()).await;
+        assert_eq!(saved().await, None);
+
+        let upd = dp.retries.as_mut().unwrap().recv().await.unwrap();
+        handle_update(upd, deps, handlers).await;
+        assert_eq!(attempts.load(Ordering::Relaxed), 2);
+        assert_eq!(saved().await, Some(UpdateId(5)));
+
+        tokio::fs::remove_file(&path).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn task_tracker() {
+        use std::{sync::Mutex, time::Duration};
+
+        use tokio_stream::wrappers::UnboundedReceiverStream;
+
+        use crate::update_listeners::StatefulListener;
+
+        let update = message_update(1, 1, "hi");
+
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<Result<Update, Infallible>>();
+        let listener = StatefulListener::from_stream(UnboundedReceiverStream::new(rx));
+        tx.send(Ok(update)).unwrap();
+        drop(tx);
+
+        let finished = Arc::new(Mutex::new(Vec::new()));
+        let handler = dptree::endpoint({
+            let finished = Arc::clone(&finished);
+            move |tasks: TaskTracker| {
+                let finished = Arc::clone(&finished);
+                tasks.spawn(async move {
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    finished.lock().unwrap().push("tracked");
+                });
+                tasks.spawn_cancellable(std::future::pending::<()>());
+                async { Ok::<_, Infallible>(()) }
+            }
+        });
+
+        let mut dp = Dispatcher::builder(Bot::new("TOKEN"), handler).build();
+        let tasks = dp.task_tracker();
+        let finished_ = Arc::clone(&finished);
+        dp.spawn_tracked(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            finished_.lock().unwrap().push("dispatcher");
+        });
+
+        tokio::time::timeout(
+            Duration::from_secs(5),
+            dp.start_listening(listener, LoggingErrorHandler::new(), None),
+        )
+        .await
+        .unwrap();
+
+        assert!(tasks.is_cancelled());
+        assert!(tasks.is_empty());
+        finished.lock().unwrap().sort();
+        assert_eq!(*finished.lock().unwrap(), ["dispatcher", "tracked"]);
+    }
+
+    #[tokio::test]
+    async fn replace_handler() {
+        use std::sync::Mutex;
+
+        let update = message_update(1, 1, "hi");
+
+        let errors = Arc::new(Mutex::new(Vec::new()));
+        let dp = Dispatcher::builder(
+            Bot::new("TOKEN"),
+            dptree::endpoint(|| async { Err::<(), _>("old") }),
+        )
+        .dependencies(dptree::deps![1u32])
+        .update_error_handler({
+            let errors = Arc::clone(&errors);
+            Arc::new(move |err: &'static str, _: Arc<Update>| {
+                errors.lock().unwrap().push(err);
+                async {}
+            })
+        })
+        .build();
+        let handlers = dp.handlers.clone();
+        let deps = Arc::new(dp.dependencies.clone());
+
+        handle_update(update.clone(), Arc::clone(&deps), handlers.clone()).await;
+        dp.handler_replacer()
+            .replace(dptree::endpoint(|_: Update, _: u32| async { Err("new") }))
+            .unwrap();
+        handle_update(update.clone(), Arc::clone(&deps), handlers.clone()).await;
+
+        // The handler requires a `String`, which isn't provided
+        let res =
+            dp.replace_handler(dptree::endpoint(|_: String, _: Bot| async { Err("invalid") }));
+        assert_eq!(res.unwrap_err().missing_types(), [std::any::type_name::<String>()]);
+        handle_update(update, deps, handlers).await;
+
+        assert_eq!(*errors.lock().unwrap(), ["old", "new", "new"]);
+    }
+
+    #[tokio::test]
+    async fn shared_update() {
+        use std::sync::Mutex;
+
+        let update = message_update(1, 1, "hi");
+
+        let shared = Arc::new(Mutex::new(Vec::new()));
+        let handler = dptree::endpoint({
+            let shared = Arc::clone(&shared);
+            move |upd: Arc<Update>| {
+                shared.lock().unwrap().push(upd);
+                async { Ok::<_, Infallible>(()) }
+            }
+        });
+        let handlers = Handlers {
+            outcome_hook: Some({
+                let shared = Arc::clone(&shared);
+                Arc::new(move |upd: Arc<Update>, _| {
+                    shared.lock().unwrap().push(upd);
+                    Box::pin(async {})
+                })
+            }),
+            ..handlers_of(handler)
+        };
+
+        handle_update(update.clone(), Arc::new(DependencyMap::new()), handlers).await;
+
+        let shared = shared.lock().unwrap();
+        assert_eq!(*shared[0], update);
+        assert!(Arc::ptr_eq(&shared[0], &shared[1]));
+    }
+
+    #[tokio::test]
+    async fn request_dependencies() {
+        use std::sync::Mutex;
+
+        #[derive(Clone, Default)]
+        struct Replies(Arc<Mutex<Vec<u32>>>);
+
+        let update = |id| -> Update {
+            serde_json::from_str(&format!(
+                r#"{{"update_id": {id}, "poll": {{"id": "1", "question": "?", "options": [],
+                    "total_voter_count": 0, "is_closed": false, "is_anonymous": true,
+                    "type": "regular", "allows_multiple_answers": false}}}}"#
+            ))
+            .unwrap()
+        };
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let builder = Dispatcher::builder(
+            Bot::new("TOKEN"),
+            dptree::endpoint({
+                let seen = Arc::clone(&seen);
+                move |id: u32, replies: Replies| {
+                    replies.0.lock().unwrap().push(id);
+                    seen.lock().unwrap().push(replies.0.lock().unwrap().clone());
+                    async { Ok::<_, Infallible>(()) }
+                }
+            }),
+        )
+        .dependencies(dptree::deps![0u32])
+        .request_dependency(|_| Replies::default())
+        .request_dependency(|_| 1u32)
+        .request_dependency(|upd: &Update| upd.id.0);
+        assert_eq!(builder.request_dependencies.len(), 2);
+
+        let dp = builder.build();
+        let handlers = dp.handlers.clone();
+
+        let deps = Arc::new(dp.dependencies.clone());
+        for id in [1, 2] {
+            handle_update(update(id), Arc::clone(&deps), handlers.clone()).await;
+        }
+
+        assert_eq!(*seen.lock().unwrap(), [vec![1], vec![2]]);
+    }
+
+    #[tokio::test]
+    async fn worker_pool_keeps_per_chat_order() {
+        use std::{sync::Mutex, time::Duration};
+
+        use crate::{
+            dispatching::UpdateFilterExt, types::Message, update_listeners::StatefulListener,
+        };
+
+        let updates = [(1, 1), (2, 2), (3, 1), (4, 3), (5, 1), (6, 2), (7, 4)].map(|(id, chat)| {
+            let mut msg = message_json(chat, "hi");
+            msg["message_id"] = id.into();
+            Ok::<_, Infallible>(update(id, "message", msg))
+        });
+
+        let listener = StatefulListener::from_stream(futures::stream::iter(updates));
+
+        let handled = Arc::new(Mutex::new(Vec::new()));
+        let handler = dptree::entry().branch(Update::filter_message().endpoint({
+            let handled = Arc::clone(&handled);
+            move |msg: Message| {
+                let handled = Arc::clone(&handled);
+                async move {
+                    // Later updates finish faster, to catch reordering
+                    tokio::time::sleep(Duration::from_millis(10 - msg.id.0 as u64)).await;
+                    handled.lock().unwrap().push((msg.chat.id.0, msg.id.0));
+                    Ok::<_, Infallible>(())
+                }
+            }
+        }));
+
+        let mut dp = Dispatcher::builder(Bot::new("TOKEN"), handler).worker_pool(2).build();
+        dp.start_listening(listener, LoggingErrorHandler::new(), None).await;
+
+        let handled = handled.lock().unwrap();
+        assert_eq!(handled.len(), 7);
+        for chat in 1..=4 {
+            let ids: Vec<_> =
+                handled.iter().filter(|(c, _)| *c == chat).map(|&(_, id)| id).collect();
+            assert!(ids.is_sorted(), "updates from chat {chat} were reordered: {ids:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn get_me_fallback() {
+        use crate::update_listeners::StatefulListener;
+
+        let attempts = Arc::new(AtomicU32::new(0));
+        // `getMe` always fails
+        let bot = unreachable_bot();
+        let builder = || {
+            let attempts = Arc::clone(&attempts);
+            Dispatcher::<_, Infallible, _>::builder(bot.clone(), dptree::entry())
+                .get_me_retries(2)
+                .get_me_backoff(move |_| {
+                    attempts.fetch_add(1, Ordering::Relaxed);
+                    Duration::ZERO
+                })
+        };
+        let listener = || StatefulListener::from_stream(futures::stream::empty::<Result<_, ()>>());
+
+        let res = builder()
+            .build()
+            .try_dispatch_with_listener(listener(), LoggingErrorHandler::new())
+            .await;
+        assert!(res.is_err());
+        assert_eq!(attempts.load(Ordering::Relaxed), 2);
+
+        let res = builder()
+            .get_me_fallback(GetMeFallback::Skip)
+            .build()
+            .try_dispatch_with_listener(listener(), LoggingErrorHandler::new())
+            .await;
+        assert!(res.is_ok());
+
+        let errors = Arc::new(AtomicU32::new(0));
+        let res = builder()
+            .get_me_fallback(GetMeFallback::ErrorHandler)
+            .get_me_error_handler({
+                let errors = Arc::clone(&errors);
+                Arc::new(move |_| {
+                    errors.fetch_add(1, Ordering::Relaxed);
+                    async {}
+                })
+            })
+            .build()
+            .try_dispatch_with_listener(listener(), LoggingErrorHandler::new())
+            .await;
+        assert!(res.is_ok());
+        assert_eq!(errors.load(Ordering::Relaxed), 1);
+
+        let res = builder()
+            .me(me())
+            .build()
+            .try_dispatch_with_listener(listener(), LoggingErrorHandler::new())
+            .await;
+        assert!(res.is_ok());
+        assert_eq!(attempts.load(Ordering::Relaxed), 6);
+    }
+
+    #[test]
+    #[should_panic(expected = "the handler requires `Me`")]
+    fn get_me_fallback_requires_me() {
+        let handler: UpdateHandler<Infallible> = dptree::endpoint(|_: Me| async { Ok(()) });
+        let _ = Dispatcher::builder(Bot::new("TOKEN"), handler)
+            .get_me_fallback(GetMeFallback::Skip)
+            .build();
+    }
+
+    #[test]
+    fn get_me_fallback_with_me() {
+        let handler = || dptree::endpoint(|_: Me| async { Ok::<_, Infallible>(()) });
+        let dp = Dispatcher::builder(Bot::new("TOKEN"), handler())
+            .get_me_fallback(GetMeFallback::Skip)
+            .me(me())
+            .build();
+        assert!(dp.replace_handler(handler()).is_ok());
+    }
 }
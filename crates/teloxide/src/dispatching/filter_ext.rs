@@ -1,12 +1,17 @@
-#![allow(clippy::redundant_closure_call)]
 // Required for the `filter_from` currently
 #![allow(deprecated)]
 
-use dptree::Handler;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ops::ControlFlow,
+    panic::Location,
+};
+
+use dptree::{di::DependencyMap, Handler, HandlerDescription, HandlerSignature, Type};
 
 use crate::{
     dispatching::DpHandlerDescription,
-    types::{AllowedUpdate, Message, Update, UpdateKind},
+    types::{AllowedUpdate, ChatMemberUpdated, Message, PollAnswer, Update, UpdateKind},
 };
 
 macro_rules! define_ext {
@@ -31,28 +36,63 @@ macro_rules! define_ext {
 
     (@impl $for_ty:ty, $func:ident, $proj_fn:expr, $Allowed:ident) => {
         fn $func() -> Handler<'static, Out, DpHandlerDescription> {
-            dptree::filter_map_with_description(DpHandlerDescription::of(AllowedUpdate::$Allowed), move |input: $for_ty| {
-                $proj_fn(input)
-            })
+            filter_map_ref(DpHandlerDescription::of(AllowedUpdate::$Allowed), $proj_fn)
         }
     };
 
     (@impl $for_ty:ty, $func:ident, $proj_fn:expr) => {
         fn $func() -> Handler<'static, Out, DpHandlerDescription> {
-            dptree::filter_map(move |input: $for_ty| {
-                $proj_fn(input)
-            })
+            filter_map_ref(DpHandlerDescription::filter_map(), $proj_fn)
         }
     };
 }
 
+/// Like [`dptree::filter_map`], but passes the input by reference instead of
+/// cloning it, so that only the projected value is cloned.
+#[track_caller]
+fn filter_map_ref<In, NewType, Out>(
+    description: DpHandlerDescription,
+    proj: fn(&In) -> Option<NewType>,
+) -> Handler<'static, Out, DpHandlerDescription>
+where
+    In: Send + Sync + 'static,
+    NewType: Send + Sync + 'static,
+    Out: Send + Sync + 'static,
+{
+    let location = Location::caller();
+
+    dptree::from_fn_with_description(
+        description,
+        move |container: DependencyMap, cont| async move {
+            let Some(new_type) = proj(&container.get::<In>()) else {
+                return ControlFlow::Continue(container);
+            };
+
+            let mut intermediate = container.clone();
+            intermediate.insert(new_type);
+            match cont(intermediate).await {
+                ControlFlow::Continue(_) => ControlFlow::Continue(container),
+                ControlFlow::Break(result) => ControlFlow::Break(result),
+            }
+        },
+        HandlerSignature::Other {
+            obligations: BTreeMap::from([(Type::of::<In>(), location)]),
+            guaranteed_outcomes: BTreeSet::from([Type::of::<NewType>()]),
+            conditional_outcomes: BTreeSet::new(),
+            continues: true,
+        },
+    )
+}
+
 mod private {
-    use teloxide_core::types::{Message, Update};
+    use teloxide_core::types::{ChatMemberUpdated, Message, PollAnswer, Update};
 
     pub trait Sealed {}
 
     impl Sealed for Update {}
     impl Sealed for Message {}
+    impl Sealed for PollAnswer {}
+    impl Sealed for ChatMemberUpdated {}
 }
 
 // FIXME: rewrite this macro to allow the usage of functions returning small
@@ -63,7 +103,7 @@ macro_rules! define_message_ext {
             MessageFilterExt, Message =>
             $((
                 $func,
-                (|x| $fn_name(&x).map(ToOwned::to_owned)),
+                (|x: &Message| $fn_name(x).map(ToOwned::to_owned)),
                 concat!("Applies the [`", stringify!($fn_name), "`] filter.")
             ),)*
         }
@@ -142,14 +182,60 @@ define_message_ext! {
     (filter_web_app_data, Message::web_app_data),
 }
 
+define_ext! {
+    PollAnswerFilterExt, PollAnswer =>
+    (
+        filter_voter_user,
+        |answer: &PollAnswer| answer.user().cloned(),
+        "Filters out answers of non-anonymous voters, passing the [`User`] forwards.\n\n\
+         [`User`]: crate::types::User"
+    ),
+    (
+        filter_voter_chat,
+        |answer: &PollAnswer| answer.voter_chat().cloned(),
+        "Filters out answers of anonymous voters, passing the [`Chat`] forwards.\n\n\
+         [`Chat`]: crate::types::Chat"
+    ),
+}
+
+macro_rules! define_chat_member_updated_ext {
+    ($( ($func:ident, $fn_name:ident) ,)*) => {
+        define_ext! {
+            ChatMemberUpdatedFilterExt, ChatMemberUpdated =>
+            $((
+                $func,
+                |upd: &ChatMemberUpdated| upd.$fn_name().then(|| upd.clone()),
+                concat!(
+                    "Filters out changes for which [`ChatMemberUpdated::",
+                    stringify!($fn_name),
+                    "`] returns `false`.\n\n\
+                     [`ChatMemberUpdated::",
+                    stringify!($fn_name),
+                    "`]: crate::types::ChatMemberUpdated::",
+                    stringify!($fn_name)
+                )
+            ),)*
+        }
+    }
+}
+
+define_chat_member_updated_ext! {
+    (filter_member_joined, is_join),
+    (filter_member_left, is_leave),
+    (filter_member_banned, is_ban),
+    (filter_member_unbanned, is_unban),
+    (filter_member_promoted, is_promotion),
+    (filter_member_demoted, is_demotion),
+}
+
 macro_rules! define_update_ext {
     ($( ($func:ident, $kind:path, $Allowed:ident) ,)*) => {
         define_ext! {
             UpdateFilterExt, Update =>
             $((
                 $func,
-                |update: Update| match update.kind {
-                    $kind(x) => Some(x),
+                |update: &Update| match &update.kind {
+                    $kind(x) => Some(x.clone()),
                     _ => None,
                 },
                 concat!("Filters out [`", stringify!($kind), "`] objects."),
@@ -184,3 +270,91 @@ define_update_ext! {
     (filter_chat_boost, UpdateKind::ChatBoost, ChatBoost),
     (filter_removed_chat_boost, UpdateKind::RemovedChatBoost, RemovedChatBoost),
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::test_utils::chat_member_updated;
+
+    /// Returns the names of member filters which pass `upd`.
+    async fn passed_member_filters(upd: ChatMemberUpdated) -> Vec<&'static str> {
+        let filters: [(_, Handler<'static, (), DpHandlerDescription>); 6] = [
+            ("joined", ChatMemberUpdated::filter_member_joined()),
+            ("left", ChatMemberUpdated::filter_member_left()),
+            ("banned", ChatMemberUpdated::filter_member_banned()),
+            ("unbanned", ChatMemberUpdated::filter_member_unbanned()),
+            ("promoted", ChatMemberUpdated::filter_member_promoted()),
+            ("demoted", ChatMemberUpdated::filter_member_demoted()),
+        ];
+
+        let mut passed = Vec::new();
+        for (name, filter) in filters {
+            let handler = filter.endpoint(|| async {});
+            if handler.dispatch(dptree::deps![upd.clone()]).await.is_break() {
+                passed.push(name);
+            }
+        }
+        passed
+    }
+
+    #[tokio::test]
+    async fn member_filters() {
+        let left = || json!({ "status": "left" });
+        let member = || json!({ "status": "member" });
+        let banned = || json!({ "status": "kicked", "until_date": 0 });
+        let admin = || {
+            json!({
+                "status": "administrator",
+                "can_be_edited": true,
+                "is_anonymous": false,
+                "can_manage_chat": true,
+                "can_delete_messages": false,
+                "can_manage_video_chats": false,
+                "can_restrict_members": false,
+                "can_promote_members": false,
+                "can_change_info": false,
+                "can_invite_users": false,
+                "can_post_stories": false,
+                "can_edit_stories": false,
+                "can_delete_stories": false
+            })
+        };
+        let restricted = || {
+            json!({
+                "status": "restricted",
+                "until_date": 0,
+                "is_member": true,
+                "can_send_messages": false,
+                "can_send_audios": false,
+                "can_send_documents": false,
+                "can_send_photos": false,
+                "can_send_videos": false,
+                "can_send_video_notes": false,
+                "can_send_voice_notes": false,
+                "can_send_other_messages": false,
+                "can_add_web_page_previews": false,
+                "can_change_info": false,
+                "can_invite_users": false,
+                "can_pin_messages": false,
+                "can_manage_topics": false,
+                "can_send_polls": false
+            })
+        };
+
+        let cases = [
+            (left(), member(), &["joined"][..]),
+            (member(), left(), &["left"]),
+            (member(), banned(), &["left", "banned"]),
+            (banned(), left(), &["unbanned"]),
+            (member(), admin(), &["promoted"]),
+            (admin(), member(), &["demoted"]),
+            (member(), restricted(), &[]),
+        ];
+        for (old, new, expected) in cases {
+            let upd = chat_member_updated(old.clone(), new.clone());
+            assert_eq!(passed_member_filters(upd).await, expected, "{old} -> {new}");
+        }
+    }
+}
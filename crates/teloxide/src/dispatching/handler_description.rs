@@ -21,7 +21,33 @@ impl DpHandlerDescription {
         Self { allowed: InterestSet::new_filter(set) }
     }
 
-    pub(crate) fn allowed_updates(&self) -> Vec<AllowedUpdate> {
+    /// Returns the minimal set of update kinds the handler is interested in.
+    ///
+    /// [`Dispatcher`] automatically passes this set to the update listener via
+    /// [`UpdateListener::hint_allowed_updates`]. This function is useful for
+    /// listeners that don't support hints (such as webhooks), where you need to
+    /// pass the set to `set_webhook` yourself:
+    ///
+    /// ```
+    /// use teloxide::{
+    ///     dispatching::UpdateFilterExt,
+    ///     dptree,
+    ///     types::{AllowedUpdate, Update},
+    /// };
+    ///
+    /// let handler = dptree::entry()
+    ///     .branch(Update::filter_message().endpoint(|| async { Ok::<_, ()>(()) }))
+    ///     .branch(Update::filter_callback_query().endpoint(|| async { Ok(()) }));
+    ///
+    /// let mut allowed_updates = handler.description().allowed_updates();
+    /// allowed_updates.sort_by_key(|&a| a as u8);
+    /// assert_eq!(allowed_updates, [AllowedUpdate::Message, AllowedUpdate::CallbackQuery]);
+    /// ```
+    ///
+    /// [`Dispatcher`]: crate::dispatching::Dispatcher
+    /// [`UpdateListener::hint_allowed_updates`]: crate::update_listeners::UpdateListener::hint_allowed_updates
+    #[must_use]
+    pub fn allowed_updates(&self) -> Vec<AllowedUpdate> {
         self.allowed.observed.iter().map(|&Kind(x)| x).collect()
     }
 }
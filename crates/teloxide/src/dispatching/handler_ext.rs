@@ -1,10 +1,10 @@
 use crate::{
     dispatching::{
-        dialogue::{GetChatId, Storage},
+        dialogue::{DialogueConfig, GetChatId, StateStack, Storage},
         DpHandlerDescription,
     },
-    types::{Me, Message},
-    utils::command::BotCommands,
+    types::{CallbackQuery, Me, Message},
+    utils::{callback_data::CallbackData, command::BotCommands},
 };
 use dptree::Handler;
 
@@ -35,6 +35,68 @@ pub trait HandlerExt<Output> {
     where
         C: BotCommands + Send + Sync + 'static;
 
+    /// Returns a handler that accepts messages which mention the bot.
+    ///
+    /// See [`Me::is_bot_mentioned`] for details.
+    ///
+    /// ## Dependency requirements
+    ///
+    ///  - [`crate::types::Message`]
+    ///  - [`crate::types::Me`]
+    #[must_use]
+    fn filter_mentions_me(self) -> Self;
+
+    /// Returns a handler that accepts callback queries with data that can be
+    /// decoded as `T`, and passes the decoded `T` forwards.
+    ///
+    /// ## Dependency requirements
+    ///
+    ///  - [`crate::types::CallbackQuery`]
+    #[must_use]
+    fn filter_callback_data<T>(self) -> Self
+    where
+        T: CallbackData + Send + Sync + 'static;
+
+    /// Returns a handler that only accepts updates that belong to the shard
+    /// `my_shard` out of `total_shards`.
+    ///
+    /// See [`shard_filter`] for details.
+    ///
+    /// ## Dependency requirements
+    ///
+    ///  - [`crate::types::Update`]
+    ///
+    /// [`shard_filter`]: crate::dispatching::shard_filter
+    #[must_use]
+    fn filter_shard(self, total_shards: u32, my_shard: u32) -> Self;
+
+    /// Tags the handler with `name`, for diagnostics.
+    ///
+    /// While the handler (and the handlers chained after it) runs, the name is
+    /// available via [`current_handler_name`], joined with the names of the
+    /// named handlers it's nested in, e.g. `admin/ban`. Panics of the handler
+    /// are logged with the name, and if [`HandlerMetrics`] is in the
+    /// dependencies, statistics of the handler are collected and slow
+    /// handling is logged.
+    ///
+    /// [`current_handler_name`]: crate::dispatching::current_handler_name
+    /// [`HandlerMetrics`]: crate::dispatching::HandlerMetrics
+    #[must_use]
+    fn named(self, name: &'static str) -> Self;
+
+    /// Returns a handler that accepts updates describing a [`ChatEvent`].
+    ///
+    /// See [`filter_chat_event`] for details.
+    ///
+    /// ## Dependency requirements
+    ///
+    ///  - [`crate::types::Update`]
+    ///
+    /// [`ChatEvent`]: crate::dispatching::ChatEvent
+    /// [`filter_chat_event`]: crate::dispatching::filter_chat_event
+    #[must_use]
+    fn filter_chat_event(self) -> Self;
+
     /// Passes [`Dialogue<D, S>`] and `D` as handler dependencies.
     ///
     /// It does so by the following steps:
@@ -65,6 +127,43 @@ pub trait HandlerExt<Output> {
         <S as Storage<D>>::Error: Debug + Send,
         D: Default + Clone + Send + Sync + 'static,
         Upd: GetChatId + Clone + Send + Sync + 'static;
+
+    /// Same as [`HandlerExt::enter_dialogue`], but stale dialogues are handled
+    /// as specified by `config`.
+    ///
+    /// ## Dependency requirements
+    ///
+    ///  - `Arc<S>`
+    ///  - `Upd`
+    #[must_use]
+    fn enter_dialogue_with_config<Upd, S, D>(self, config: DialogueConfig<D>) -> Self
+    where
+        S: Storage<D> + ?Sized + Send + Sync + 'static,
+        <S as Storage<D>>::Error: Debug + Send,
+        D: Default + Clone + Send + Sync + 'static,
+        Upd: GetChatId + Clone + Send + Sync + 'static;
+
+    /// Passes [`Dialogue<StateStack<D>, S>`] and the current state `D` as
+    /// handler dependencies.
+    ///
+    /// This is the same as [`HandlerExt::enter_dialogue`] with
+    /// [`StateStack<D>`] as the dialogue state, but the current state is
+    /// passed instead of the whole stack.
+    ///
+    /// ## Dependency requirements
+    ///
+    ///  - `Arc<S>`
+    ///  - `Upd`
+    ///
+    /// [`Dialogue<StateStack<D>, S>`]: super::dialogue::Dialogue
+    /// [`StateStack<D>`]: super::dialogue::StateStack
+    #[must_use]
+    fn enter_stacked_dialogue<Upd, S, D>(self) -> Self
+    where
+        S: Storage<StateStack<D>> + ?Sized + Send + Sync + 'static,
+        <S as Storage<StateStack<D>>>::Error: Debug + Send,
+        D: Default + Clone + Send + Sync + 'static,
+        Upd: GetChatId + Clone + Send + Sync + 'static;
 }
 
 impl<Output> HandlerExt<Output> for Handler<'static, Output, DpHandlerDescription>
@@ -85,6 +184,29 @@ where
         self.chain(filter_mention_command::<C, Output>())
     }
 
+    fn filter_mentions_me(self) -> Self {
+        self.chain(filter_mentions_me::<Output>())
+    }
+
+    fn filter_callback_data<T>(self) -> Self
+    where
+        T: CallbackData + Send + Sync + 'static,
+    {
+        self.chain(filter_callback_data::<T, Output>())
+    }
+
+    fn filter_shard(self, total_shards: u32, my_shard: u32) -> Self {
+        self.chain(super::shard_filter::<Output>(total_shards, my_shard))
+    }
+
+    fn named(self, name: &'static str) -> Self {
+        super::named::named(self, name)
+    }
+
+    fn filter_chat_event(self) -> Self {
+        self.chain(super::filter_chat_event::<Output>())
+    }
+
     fn enter_dialogue<Upd, S, D>(self) -> Self
     where
         S: Storage<D> + ?Sized + Send + Sync + 'static,
@@ -94,6 +216,26 @@ where
     {
         self.chain(super::dialogue::enter::<Upd, S, D, Output>())
     }
+
+    fn enter_dialogue_with_config<Upd, S, D>(self, config: DialogueConfig<D>) -> Self
+    where
+        S: Storage<D> + ?Sized + Send + Sync + 'static,
+        <S as Storage<D>>::Error: Debug + Send,
+        D: Default + Clone + Send + Sync + 'static,
+        Upd: GetChatId + Clone + Send + Sync + 'static,
+    {
+        self.chain(super::dialogue::enter_with_config::<Upd, S, D, Output>(config))
+    }
+
+    fn enter_stacked_dialogue<Upd, S, D>(self) -> Self
+    where
+        S: Storage<StateStack<D>> + ?Sized + Send + Sync + 'static,
+        <S as Storage<StateStack<D>>>::Error: Debug + Send,
+        D: Default + Clone + Send + Sync + 'static,
+        Upd: GetChatId + Clone + Send + Sync + 'static,
+    {
+        self.chain(super::dialogue::enter_stack::<Upd, S, D, Output>())
+    }
 }
 
 /// Returns a handler that accepts a parsed command `C`.
@@ -153,6 +295,50 @@ where
     })
 }
 
+/// Returns a handler that accepts messages which mention the bot.
+///
+/// A call to this function is the same as
+/// `dptree::entry().filter_mentions_me()`.
+///
+/// See [`HandlerExt::filter_mentions_me`].
+///
+/// ## Dependency requirements
+///
+///  - [`crate::types::Message`]
+///  - [`crate::types::Me`]
+#[must_use]
+pub fn filter_mentions_me<Output>() -> Handler<'static, Output, DpHandlerDescription>
+where
+    Output: Send + Sync + 'static,
+{
+    dptree::filter(|message: Message, me: Me| me.is_bot_mentioned(&message))
+}
+
+/// Returns a handler that accepts callback queries with data that can be
+/// decoded as `T`, and passes the decoded `T` forwards.
+///
+/// Callback queries with data that is missing or can't be decoded (e.g. because
+/// it has another [version]) are not accepted.
+///
+/// A call to this function is the same as
+/// `dptree::entry().filter_callback_data()`.
+///
+/// See [`HandlerExt::filter_callback_data`].
+///
+/// ## Dependency requirements
+///
+///  - [`crate::types::CallbackQuery`]
+///
+/// [version]: CallbackData::VERSION
+#[must_use]
+pub fn filter_callback_data<T, Output>() -> Handler<'static, Output, DpHandlerDescription>
+where
+    T: CallbackData + Send + Sync + 'static,
+    Output: Send + Sync + 'static,
+{
+    dptree::filter_map(|query: CallbackQuery| T::decode(query.data.as_deref()?).ok())
+}
+
 #[cfg(test)]
 #[cfg(feature = "macros")]
 mod tests {
@@ -0,0 +1,346 @@
+use std::sync::{Arc, Mutex};
+
+use dptree::Handler;
+
+use crate::{
+    dispatching::DpHandlerDescription,
+    types::{ChatId, Update, UpdateKind, UserId},
+    utils::recent::RecentMap,
+};
+
+/// How many users and chats are cached by default, see
+/// [`LanguageDetection::cache_capacity`].
+const DEFAULT_CACHE_CAPACITY: usize = 10_000;
+
+/// Detects the language of a text.
+///
+/// This is implemented for closures `Fn(&str) -> Option<String>`, so any
+/// language detection library can be plugged into [`detect_language`].
+pub trait LanguageDetector: Send + Sync + 'static {
+    /// Returns the language of `text` (e.g. an [IETF language tag] such as
+    /// `en`), or `None` if it can't be detected.
+    ///
+    /// [IETF language tag]: https://en.wikipedia.org/wiki/IETF_language_tag
+    fn detect(&self, text: &str) -> Option<String>;
+}
+
+impl<F> LanguageDetector for F
+where
+    F: Fn(&str) -> Option<String> + Send + Sync + 'static,
+{
+    fn detect(&self, text: &str) -> Option<String> {
+        self(text)
+    }
+}
+
+/// The language of an update, injected by [`LanguageDetection::into_handler`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DetectedLang {
+    /// The language, in the format returned by the [`LanguageDetector`].
+    pub code: String,
+    /// Where the language is taken from.
+    pub source: LangSource,
+}
+
+/// Where a [`DetectedLang`] is taken from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LangSource {
+    /// The language is detected from the text of the update.
+    Text,
+
+    /// The update has no text suitable for detection, the language previously
+    /// detected for the user is used.
+    User,
+
+    /// The update has no text suitable for detection, the language previously
+    /// detected in the chat is used.
+    Chat,
+
+    /// Nothing was detected yet, the language of the user's Telegram client
+    /// ([`User::language_code`]) is used.
+    ///
+    /// [`User::language_code`]: crate::types::User::language_code
+    Client,
+
+    /// The language is unknown, the [default] one is used.
+    ///
+    /// [default]: LanguageDetection::default_lang
+    Default,
+}
+
+/// Creates a [`LanguageDetection`] using `detector`.
+pub fn detect_language<D>(detector: D) -> LanguageDetection
+where
+    D: LanguageDetector,
+{
+    LanguageDetection {
+        detector: Arc::new(detector),
+        min_len: 16,
+        default_lang: "en".to_owned(),
+        cache: Arc::new(Mutex::new(LangCache::new(DEFAULT_CACHE_CAPACITY))),
+    }
+}
+
+/// An enrichment which detects the language of updates and caches it per user
+/// and per chat.
+///
+/// The handler created by [`LanguageDetection::into_handler`] detects the
+/// language of the text or the caption of messages, and injects it as
+/// [`DetectedLang`] into the handlers chained after it. Texts shorter than
+/// [`min_len`] (e.g. "ok") are unreliable to detect, so for them, as well as
+/// for updates without a text (e.g. stickers or callback queries), the
+/// language last detected for the user, then for the chat, then the language
+/// of the user's Telegram client, and finally the [default language] is used.
+///
+/// The cache is kept in memory and holds the languages most recently
+/// detected for a [limited] number of users and chats, clones share the same
+/// cache.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use teloxide::{
+///     dispatching::{detect_language, DetectedLang},
+///     prelude::*,
+/// };
+///
+/// # fn detect(_: &str) -> Option<String> { None }
+/// # async fn run() {
+/// let bot = Bot::from_env();
+///
+/// // `detect` may call any language detection library
+/// let handler = detect_language(detect).into_handler().branch(Update::filter_message().endpoint(
+///     |bot: Bot, msg: Message, lang: DetectedLang| async move {
+///         let text = if lang.code == "uk" { "Привіт!" } else { "Hi!" };
+///         bot.send_message(msg.chat.id, text).await?;
+///         respond(())
+///     },
+/// ));
+///
+/// Dispatcher::builder(bot, handler).build().dispatch().await;
+/// # }
+/// ```
+///
+/// [`min_len`]: LanguageDetection::min_len
+/// [default language]: LanguageDetection::default_lang
+/// [limited]: LanguageDetection::cache_capacity
+#[derive(Clone)]
+#[must_use]
+pub struct LanguageDetection {
+    detector: Arc<dyn LanguageDetector>,
+    min_len: usize,
+    default_lang: String,
+    cache: Arc<Mutex<LangCache>>,
+}
+
+struct LangCache {
+    users: RecentMap<UserId, String>,
+    chats: RecentMap<ChatId, String>,
+}
+
+impl LangCache {
+    #[track_caller]
+    fn new(capacity: usize) -> Self {
+        Self { users: RecentMap::new(capacity), chats: RecentMap::new(capacity) }
+    }
+}
+
+impl LanguageDetection {
+    /// Sets the minimal number of characters of a text for its language to be
+    /// detected.
+    ///
+    /// Default - `16`.
+    pub fn min_len(self, min_len: usize) -> Self {
+        Self { min_len, ..self }
+    }
+
+    /// Sets the language used when it's unknown.
+    ///
+    /// Default - `en`.
+    pub fn default_lang<S>(self, default_lang: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self { default_lang: default_lang.into(), ..self }
+    }
+
+    /// Sets how many users, and separately chats, are cached.
+    ///
+    /// When there are more, the older half of the detected languages is
+    /// forgotten. Setting the capacity clears the cache.
+    ///
+    /// Default - 10000.
+    ///
+    /// ## Panics
+    ///
+    /// If `capacity` is zero.
+    #[track_caller]
+    pub fn cache_capacity(self, capacity: usize) -> Self {
+        Self { cache: Arc::new(Mutex::new(LangCache::new(capacity))), ..self }
+    }
+
+    /// Returns the language last detected for `user`.
+    #[must_use]
+    pub fn user_lang(&self, user: UserId) -> Option<String> {
+        self.cache.lock().unwrap().users.get(&user).cloned()
+    }
+
+    /// Returns the language last detected in `chat`.
+    #[must_use]
+    pub fn chat_lang(&self, chat: ChatId) -> Option<String> {
+        self.cache.lock().unwrap().chats.get(&chat).cloned()
+    }
+
+    /// Returns a handler, which injects [`DetectedLang`].
+    ///
+    /// ## Dependency requirements
+    ///
+    ///  - [`crate::types::Update`]
+    pub fn into_handler<Output>(self) -> Handler<'static, Output, DpHandlerDescription>
+    where
+        Output: Send + Sync + 'static,
+    {
+        dptree::map(move |update: Update| self.detect(&update))
+    }
+
+    /// Detects the language of `update`, updating the cache.
+    fn detect(&self, update: &Update) -> DetectedLang {
+        let user = update.from().map(|user| user.id);
+        let chat = update.chat().map(|chat| chat.id);
+
+        let detected = text(update)
+            .filter(|text| text.chars().count() >= self.min_len)
+            .and_then(|text| self.detector.detect(text));
+
+        let mut cache = self.cache.lock().unwrap();
+        if let Some(code) = detected {
+            if let Some(user) = user {
+                cache.users.insert(user, code.clone());
+            }
+            if let Some(chat) = chat {
+                cache.chats.insert(chat, code.clone());
+            }
+            return DetectedLang { code, source: LangSource::Text };
+        }
+
+        let cached = |source, code: Option<&String>| {
+            code.map(|code| DetectedLang { code: code.clone(), source })
+        };
+        cached(LangSource::User, user.and_then(|user| cache.users.get(&user)))
+            .or_else(|| cached(LangSource::Chat, chat.and_then(|chat| cache.chats.get(&chat))))
+            .or_else(|| {
+                let code = update.from()?.language_code.clone()?;
+                Some(DetectedLang { code, source: LangSource::Client })
+            })
+            .unwrap_or_else(|| DetectedLang {
+                code: self.default_lang.clone(),
+                source: LangSource::Default,
+            })
+    }
+}
+
+/// Returns the text or the caption of a message in `update`.
+fn text(update: &Update) -> Option<&str> {
+    match &update.kind {
+        UpdateKind::Message(msg)
+        | UpdateKind::EditedMessage(msg)
+        | UpdateKind::ChannelPost(msg)
+        | UpdateKind::EditedChannelPost(msg)
+        | UpdateKind::BusinessMessage(msg)
+        | UpdateKind::EditedBusinessMessage(msg) => msg.text().or_else(|| msg.caption()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::ControlFlow;
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::test_utils::{message_json, update};
+
+    /// Returns a message of `user` with `language_code` `de` in the group
+    /// `chat`, with `text` or a sticker.
+    fn message(chat: i64, user: u64, text: Option<&str>) -> Update {
+        let mut message = message_json(user, text.unwrap_or_default());
+        message["chat"] = json!({ "id": chat, "type": "group", "title": "G" });
+        message["from"]["language_code"] = "de".into();
+        if text.is_none() {
+            message.as_object_mut().unwrap().remove("text");
+            message["sticker"] = json!({
+                "file_id": "id",
+                "file_unique_id": "",
+                "type": "regular",
+                "width": 512,
+                "height": 512,
+                "is_animated": false,
+                "is_video": false,
+                "file_size": 1
+            });
+        }
+        update(1, "message", message)
+    }
+
+    #[tokio::test]
+    async fn detect_language() {
+        let detection = super::detect_language(|text: &str| {
+            text.starts_with("Привіт").then(|| "uk".to_owned())
+        })
+        .min_len(8);
+        let handler = detection
+            .clone()
+            .into_handler()
+            .endpoint(|lang: DetectedLang| async move { (lang.code, lang.source) });
+        let lang = |update: Update| {
+            let handler = handler.clone();
+            async move {
+                match handler.dispatch(dptree::deps![update]).await {
+                    ControlFlow::Break(lang) => lang,
+                    ControlFlow::Continue(_) => panic!("no language"),
+                }
+            }
+        };
+        let lang_of = |code: &str, source| (code.to_owned(), source);
+
+        // Nothing detected yet
+        assert_eq!(
+            lang(message(1, 1, Some("Hello there, how are you?"))).await,
+            lang_of("de", LangSource::Client)
+        );
+        assert_eq!(
+            lang(message(1, 1, Some("Привіт усім у чаті"))).await,
+            lang_of("uk", LangSource::Text)
+        );
+        assert_eq!(lang(message(1, 1, None)).await, lang_of("uk", LangSource::User));
+        assert_eq!(lang(message(1, 1, Some("Привіт"))).await, lang_of("uk", LangSource::User));
+        assert_eq!(lang(message(1, 2, None)).await, lang_of("uk", LangSource::Chat));
+        assert_eq!(lang(message(2, 2, None)).await, lang_of("de", LangSource::Client));
+
+        let mut channel_post = message_json(3, "ok");
+        channel_post["chat"] = json!({ "id": 3, "type": "channel", "title": "C" });
+        channel_post.as_object_mut().unwrap().remove("from");
+        let channel_post = update(1, "channel_post", channel_post);
+        assert_eq!(lang(channel_post).await, lang_of("en", LangSource::Default));
+
+        assert_eq!(detection.user_lang(UserId(1)).as_deref(), Some("uk"));
+        assert_eq!(detection.user_lang(UserId(2)), None);
+        assert_eq!(detection.chat_lang(ChatId(1)).as_deref(), Some("uk"));
+    }
+
+    #[test]
+    fn cache_capacity() {
+        let detection =
+            super::detect_language(|_: &str| Some("uk".to_owned())).min_len(0).cache_capacity(4);
+        for id in 0..10 {
+            detection.detect(&message(id, id as u64, Some("text")));
+        }
+
+        assert_eq!(detection.cache.lock().unwrap().users.len(), 4);
+        assert_eq!(detection.user_lang(UserId(9)).as_deref(), Some("uk"));
+        assert_eq!(detection.user_lang(UserId(0)), None);
+        assert_eq!(detection.chat_lang(ChatId(9)).as_deref(), Some("uk"));
+        assert_eq!(detection.chat_lang(ChatId(0)), None);
+    }
+}
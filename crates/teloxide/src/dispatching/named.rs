@@ -0,0 +1,243 @@
+use std::{
+    collections::HashMap,
+    panic::AssertUnwindSafe,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use dptree::Handler;
+use futures::FutureExt as _;
+
+use crate::{dispatching::DpHandlerDescription, types::Update};
+
+tokio::task_local! {
+    static HANDLER_NAME: Arc<str>;
+}
+
+/// Returns the name of the [named] handler which is currently running, if
+/// any.
+///
+/// Names of nested named handlers are joined with `/`, e.g. `admin/ban`. This
+/// is useful to include the name in logs of handlers and of functions called
+/// from them.
+///
+/// [named]: crate::dispatching::HandlerExt::named
+#[must_use]
+pub fn current_handler_name() -> Option<Arc<str>> {
+    HANDLER_NAME.try_with(Arc::clone).ok()
+}
+
+/// Statistics of a [named] handler, collected by [`HandlerMetrics`].
+///
+/// [named]: crate::dispatching::HandlerExt::named
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct HandlerStats {
+    /// How many times the handler was called.
+    pub calls: u64,
+    /// How many updates the handler has handled, i.e. hasn't passed further.
+    pub handled: u64,
+    /// How many times the handler has panicked.
+    pub panics: u64,
+    /// The total time of handling updates.
+    pub total_time: Duration,
+    /// The longest time of handling an update.
+    pub max_time: Duration,
+}
+
+/// Statistics of [named] handlers, per name.
+///
+/// Add it to the dependencies of the dispatcher to collect [`HandlerStats`]
+/// of all named handlers. Clones share the same statistics, so they can be
+/// exported to a monitoring system while the dispatcher is running.
+///
+/// If a [`slow_threshold`] is set, named handlers which take longer to handle
+/// an update are logged with a warning.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use teloxide::{
+///     dispatching::{HandlerExt, HandlerMetrics},
+///     prelude::*,
+/// };
+///
+/// # async fn run() {
+/// let bot = Bot::from_env();
+/// let metrics = HandlerMetrics::new().slow_threshold(Duration::from_secs(5));
+///
+/// let handler = Update::filter_message()
+///     .branch(
+///         dptree::filter(|msg: Message| msg.photo().is_some())
+///             .endpoint(|| async { respond(()) })
+///             .named("photos"),
+///     )
+///     .branch(dptree::endpoint(|| async { respond(()) }).named("fallback"));
+///
+/// Dispatcher::builder(bot, handler)
+///     .dependencies(dptree::deps![metrics.clone()])
+///     .build()
+///     .dispatch()
+///     .await;
+///
+/// println!("photos: {:?}", metrics.get("photos"));
+/// # }
+/// ```
+///
+/// [named]: crate::dispatching::HandlerExt::named
+/// [`slow_threshold`]: HandlerMetrics::slow_threshold
+#[derive(Clone, Debug, Default)]
+#[must_use]
+pub struct HandlerMetrics {
+    slow_threshold: Option<Duration>,
+    stats: Arc<Mutex<HashMap<Arc<str>, HandlerStats>>>,
+}
+
+impl HandlerMetrics {
+    /// Creates empty statistics, without a slow threshold.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the time after which handling of an update is considered slow and
+    /// logged with a warning.
+    pub fn slow_threshold(self, threshold: Duration) -> Self {
+        Self { slow_threshold: Some(threshold), ..self }
+    }
+
+    /// Returns the statistics of the handler named `name` (including the
+    /// names of the handlers it's nested in, see [`current_handler_name`]).
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<HandlerStats> {
+        self.stats.lock().unwrap().get(name).copied()
+    }
+
+    /// Returns the statistics of all handlers which were called at least
+    /// once.
+    #[must_use]
+    pub fn snapshot(&self) -> HashMap<Arc<str>, HandlerStats> {
+        self.stats.lock().unwrap().clone()
+    }
+
+    fn record(&self, name: &Arc<str>, elapsed: Duration, handled: bool, panicked: bool) {
+        let mut stats = self.stats.lock().unwrap();
+        let stats = stats.entry(Arc::clone(name)).or_default();
+
+        stats.calls += 1;
+        stats.handled += u64::from(handled);
+        stats.panics += u64::from(panicked);
+        stats.total_time += elapsed;
+        stats.max_time = stats.max_time.max(elapsed);
+    }
+}
+
+pub(crate) fn named<Output>(
+    handler: Handler<'static, Output, DpHandlerDescription>,
+    name: &'static str,
+) -> Handler<'static, Output, DpHandlerDescription>
+where
+    Output: Send + Sync + 'static,
+{
+    let sig = handler.sig().clone();
+
+    dptree::from_fn_with_description(
+        handler.description().clone(),
+        move |deps, cont| {
+            let handler = handler.clone();
+
+            async move {
+                let name: Arc<str> = match current_handler_name() {
+                    Some(parent) => format!("{parent}/{name}").into(),
+                    None => name.into(),
+                };
+                let metrics = deps.try_get::<HandlerMetrics>();
+                let update = deps.try_get::<Update>();
+
+                let start = Instant::now();
+                let result = HANDLER_NAME
+                    .scope(
+                        Arc::clone(&name),
+                        AssertUnwindSafe(handler.execute(deps, cont)).catch_unwind(),
+                    )
+                    .await;
+                let elapsed = start.elapsed();
+
+                let describe = || match &update {
+                    Some(update) => update.to_string(),
+                    None => "an update".to_owned(),
+                };
+
+                let handled = matches!(result, Ok(std::ops::ControlFlow::Break(_)));
+                if let Some(metrics) = &metrics {
+                    metrics.record(&name, elapsed, handled, result.is_err());
+
+                    if let Some(threshold) =
+                        metrics.slow_threshold.filter(|&t| handled && elapsed > t)
+                    {
+                        log::warn!(
+                            "Handler `{name}` took {elapsed:?} (more than {threshold:?}) to \
+                             handle {}",
+                            describe()
+                        );
+                    }
+                }
+
+                match result {
+                    Ok(flow) => flow,
+                    Err(panic) => {
+                        log::error!("Handler `{name}` panicked while handling {}", describe());
+                        std::panic::resume_unwind(panic)
+                    }
+                }
+            }
+        },
+        sig,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::ControlFlow;
+
+    use super::*;
+    use crate::dispatching::HandlerExt;
+
+    #[tokio::test]
+    async fn named() {
+        let metrics = HandlerMetrics::new();
+        let handler = dptree::entry()
+            .branch(
+                dptree::filter(|x: i32| x > 0)
+                    .map(|| current_handler_name().unwrap())
+                    .endpoint(|name: Arc<str>| async move { name.to_string() })
+                    .named("positive"),
+            )
+            .branch(
+                dptree::filter(|x: i32| x == 0).endpoint(|| async { panic!("zero") }).named("zero"),
+            )
+            .named("numbers");
+
+        assert_eq!(
+            handler.dispatch(dptree::deps![1, metrics.clone()]).await,
+            ControlFlow::Break("numbers/positive".to_owned())
+        );
+        assert!(handler.dispatch(dptree::deps![-1, metrics.clone()]).await.is_continue());
+
+        let panicked = tokio::spawn({
+            let handler = handler.clone();
+            let metrics = metrics.clone();
+            async move { handler.dispatch(dptree::deps![0, metrics]).await }
+        });
+        assert!(panicked.await.unwrap_err().is_panic());
+
+        let stats = |name| metrics.get(name).unwrap();
+        assert_eq!((stats("numbers").calls, stats("numbers").handled), (3, 1));
+        assert_eq!((stats("numbers").panics, stats("numbers/zero").panics), (1, 1));
+        assert_eq!(stats("numbers/positive").calls, 3);
+        assert_eq!(stats("numbers/positive").handled, 1);
+        assert_eq!(metrics.snapshot().len(), 3);
+        assert!(current_handler_name().is_none());
+    }
+}
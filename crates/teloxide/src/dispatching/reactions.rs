@@ -0,0 +1,163 @@
+use std::fmt::Display;
+
+use dptree::Handler;
+
+use crate::{
+    dispatching::DpHandlerDescription,
+    requests::Requester,
+    types::{MaybeAnonymousUser, MessageReactionUpdated},
+    utils::chat_members::ChatMemberCache,
+};
+
+/// Returns a handler that accepts changes of reactions which add a reaction
+/// by an administrator (or the owner) of the chat.
+///
+/// This is useful for moderation bots, which let administrators act on
+/// messages by reacting to them. The added reactions are available via
+/// [`MessageReactionUpdated::added_reactions`]. Anonymous administrators react
+/// on behalf of the chat itself, so their reactions are accepted too.
+///
+/// Members are fetched with the [`ChatMemberCache`], so that not every
+/// reaction results in a request. Errors of fetching are logged, and the
+/// reaction isn't accepted.
+///
+/// Note that the bot must be an administrator of the chat and request
+/// [`AllowedUpdate::MessageReaction`] to receive reactions.
+///
+/// ## Dependency requirements
+///
+///  - `R`
+///  - [`crate::types::MessageReactionUpdated`]
+///  - [`crate::utils::chat_members::ChatMemberCache`]
+///
+/// ## Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use teloxide::{
+///     dispatching::filter_admin_reaction,
+///     prelude::*,
+///     types::{MessageReactionUpdated, ReactionType},
+///     utils::chat_members::ChatMemberCache,
+/// };
+///
+/// # async fn run() {
+/// let bot = Bot::from_env();
+///
+/// // Administrators delete messages by reacting with 💩
+/// let handler = Update::filter_message_reaction_updated()
+///     .chain(filter_admin_reaction::<Bot, _>())
+///     .filter(|upd: MessageReactionUpdated| {
+///         upd.added_reactions().any(|r| *r == ReactionType::from_emoji("💩"))
+///     })
+///     .endpoint(|bot: Bot, upd: MessageReactionUpdated| async move {
+///         bot.delete_message(upd.chat.id, upd.message_id).await?;
+///         respond(())
+///     });
+///
+/// Dispatcher::builder(bot, handler)
+///     .dependencies(dptree::deps![ChatMemberCache::new(Duration::from_secs(300))])
+///     .build()
+///     .dispatch()
+///     .await;
+/// # }
+/// ```
+///
+/// [`AllowedUpdate::MessageReaction`]: crate::types::AllowedUpdate::MessageReaction
+#[must_use]
+pub fn filter_admin_reaction<R, Output>() -> Handler<'static, Output, DpHandlerDescription>
+where
+    R: Requester + Clone + Send + Sync + 'static,
+    R::Err: Display,
+    Output: Send + Sync + 'static,
+{
+    dptree::filter_async(|upd: MessageReactionUpdated, bot: R, cache: ChatMemberCache| async move {
+        if upd.added_reactions().next().is_none() {
+            return false;
+        }
+
+        match &upd.actor {
+            MaybeAnonymousUser::Chat(chat) => chat.id == upd.chat.id,
+            MaybeAnonymousUser::User(user) => {
+                match cache.get_chat_member(&bot, upd.chat.id, user.id).await {
+                    Ok(member) => member.is_privileged(),
+                    Err(err) => {
+                        log::error!("Failed to get a chat member: {err}");
+                        false
+                    }
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        test_utils::{chat_member_updated, unreachable_bot},
+        types::{ReactionType, UserId},
+        Bot,
+    };
+
+    #[tokio::test]
+    async fn admin_reaction() {
+        // Members which aren't cached are fetched, which fails
+        let bot = unreachable_bot();
+        let cache = ChatMemberCache::new(Duration::from_secs(60));
+        let admin = json!({
+            "status": "administrator",
+            "can_be_edited": false,
+            "can_manage_chat": true,
+            "can_change_info": true,
+            "can_delete_messages": true,
+            "can_manage_video_chats": true,
+            "can_invite_users": true,
+            "can_restrict_members": true,
+            "can_pin_messages": true,
+            "can_promote_members": false,
+            "can_post_stories": false,
+            "can_edit_stories": false,
+            "can_delete_stories": false,
+            "is_anonymous": false
+        });
+        for (id, status) in [(1, admin), (2, json!({ "status": "member" }))] {
+            let mut update = chat_member_updated(json!({ "status": "left" }), status);
+            update.new_chat_member.user.id = UserId(id);
+            cache.observe(&update);
+        }
+
+        let handler = filter_admin_reaction::<Bot, ()>().endpoint(|| async {});
+        let react = |actor: serde_json::Value, old: &[&str], new: &[&str]| {
+            let emoji = |emoji: &[&str]| {
+                emoji.iter().map(|&e| ReactionType::from_emoji(e)).collect::<Vec<_>>()
+            };
+            let mut upd = json!({
+                "chat": { "id": -1, "title": "G", "type": "supergroup" },
+                "message_id": 1,
+                "date": 1,
+                "old_reaction": emoji(old),
+                "new_reaction": emoji(new),
+            });
+            upd.as_object_mut().unwrap().extend(actor.as_object().unwrap().clone());
+            let upd: MessageReactionUpdated = serde_json::from_value(upd).unwrap();
+            handler.dispatch(dptree::deps![upd, bot.clone(), cache.clone()])
+        };
+        let user = |id: u64| json!({"user": { "id": id, "is_bot": false, "first_name": "B" }});
+
+        assert!(react(user(1), &[], &["👍"]).await.is_break());
+        assert!(react(user(1), &["👍"], &[]).await.is_continue());
+        assert!(react(user(2), &[], &["👍"]).await.is_continue());
+        assert!(react(user(3), &[], &["👍"]).await.is_continue());
+
+        let chat =
+            |id: i64| json!({"actor_chat": { "id": id, "title": "G", "type": "supergroup" }});
+        assert!(react(chat(-1), &[], &["👍"]).await.is_break());
+        assert!(react(chat(-2), &[], &["👍"]).await.is_continue());
+    }
+}
@@ -0,0 +1,134 @@
+use dptree::Handler;
+use teloxide_core::types::Update;
+
+use crate::dispatching::DpHandlerDescription;
+
+/// Returns a handler that only accepts updates that belong to the shard
+/// `my_shard` out of `total_shards`.
+///
+/// This is useful when multiple instances of a bot receive the same updates
+/// (for example, from a shared queue): if every instance uses a different
+/// `my_shard`, each update is handled by exactly one of them. Updates from the
+/// same chat always belong to the same shard, so dialogues work as usual. See
+/// [`shard_of`] for details.
+///
+/// A call to this function is the same as
+/// `dptree::entry().filter_shard(total_shards, my_shard)`.
+///
+/// See [`HandlerExt::filter_shard`].
+///
+/// ## Dependency requirements
+///
+///  - [`crate::types::Update`]
+///
+/// ## Panics
+///
+/// If `my_shard` is not less than `total_shards`.
+///
+/// [`HandlerExt::filter_shard`]: crate::dispatching::HandlerExt::filter_shard
+#[must_use]
+pub fn shard_filter<Output>(
+    total_shards: u32,
+    my_shard: u32,
+) -> Handler<'static, Output, DpHandlerDescription>
+where
+    Output: Send + Sync + 'static,
+{
+    assert!(
+        my_shard < total_shards,
+        "shard number ({my_shard}) must be less than the number of shards ({total_shards})"
+    );
+
+    dptree::filter(move |update: Update| shard_of(&update, total_shards) == my_shard)
+}
+
+/// Returns the shard (a number in `0..total_shards`) an update belongs to.
+///
+/// The shard is computed from the hash of the chat id of the update, or of the
+/// id of the user that caused the update, if it has no chat (e.g. inline
+/// queries). Updates with neither belong to the shard `0`.
+///
+/// The result is deterministic: it only depends on the ids and `total_shards`,
+/// so it's the same in all processes and between restarts.
+///
+/// ## Panics
+///
+/// If `total_shards` is zero.
+#[must_use]
+pub fn shard_of(update: &Update, total_shards: u32) -> u32 {
+    assert!(total_shards > 0, "the number of shards must be greater than zero");
+
+    let id = match (update.chat(), update.from()) {
+        (Some(chat), _) => chat.id.0,
+        (None, Some(user)) => user.id.0 as i64,
+        (None, None) => return 0,
+    };
+
+    (mix(id as u64) % u64::from(total_shards)) as u32
+}
+
+/// The finalizer of [SplitMix64], which spreads sequential ids evenly.
+///
+/// [SplitMix64]: https://prng.di.unimi.it/splitmix64.c
+fn mix(mut x: u64) -> u64 {
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d049bb133111eb);
+    x ^ (x >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use dptree::deps;
+    use serde_json::json;
+
+    use super::*;
+
+    use crate::{
+        test_utils::{chat_member_updated, message_update},
+        types::{UpdateId, UpdateKind},
+    };
+
+    fn message(chat_id: u64) -> Update {
+        message_update(1, chat_id, "hi")
+    }
+
+    #[test]
+    fn shards_are_disjoint_and_stable() {
+        const TOTAL: u32 = 4;
+
+        let mut counts = [0; TOTAL as usize];
+        for chat_id in 0..1000 {
+            let upd = message(chat_id);
+            let shard = shard_of(&upd, TOTAL);
+            assert_eq!(shard, shard_of(&message(chat_id), TOTAL));
+            counts[shard as usize] += 1;
+        }
+
+        // Every shard gets a fair share of chats
+        assert!(counts.iter().all(|&c| c > 200), "{counts:?}");
+        // Chat ids can be negative
+        let member =
+            chat_member_updated(json!({ "status": "left" }), json!({ "status": "member" }));
+        let member = Update { id: UpdateId(1), kind: UpdateKind::ChatMember(member) };
+        assert!(shard_of(&member, TOTAL) < TOTAL);
+    }
+
+    #[tokio::test]
+    async fn shard_filter_accepts_only_own_shard() {
+        const TOTAL: u32 = 3;
+
+        for chat_id in 0..30 {
+            let upd = message(chat_id);
+            let mut accepted = 0;
+
+            for shard in 0..TOTAL {
+                let handler = shard_filter(TOTAL, shard).endpoint(|| async {});
+                if handler.dispatch(deps![upd.clone()]).await.is_break() {
+                    accepted += 1;
+                }
+            }
+
+            assert_eq!(accepted, 1);
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use std::{
+    future::Future,
+    sync::{Arc, Mutex},
+};
+
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+/// Tracks background tasks spawned by handlers, so that they aren't silently
+/// dropped when the [`Dispatcher`] shuts down.
+///
+/// Each dispatcher has a tracker, which is available to handlers as a
+/// dependency, and via [`Dispatcher::task_tracker`]. When dispatching stops
+/// (e.g. after [`ShutdownToken::shutdown`]) and all handlers are finished, the
+/// dispatcher cancels the tasks spawned with [`spawn_cancellable`] and waits
+/// for all the other tasks to finish. If [`shutdown_with_timeout`] decides to
+/// abort the handlers, the tasks aren't waited for anymore.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use teloxide::{dispatching::TaskTracker, prelude::*};
+///
+/// async fn handler(bot: Bot, msg: Message, tasks: TaskTracker) -> ResponseResult<()> {
+///     let reply = bot.send_message(msg.chat.id, "This message disappears in a minute").await?;
+///
+///     // The message isn't deleted if the bot is shut down before
+///     tasks.spawn_cancellable(async move {
+///         tokio::time::sleep(Duration::from_secs(60)).await;
+///         let _ = bot.delete_message(reply.chat.id, reply.id).await;
+///     });
+///
+///     Ok(())
+/// }
+/// ```
+///
+/// [`Dispatcher`]: crate::dispatching::Dispatcher
+/// [`Dispatcher::task_tracker`]: crate::dispatching::Dispatcher::task_tracker
+/// [`ShutdownToken::shutdown`]: crate::dispatching::ShutdownToken::shutdown
+/// [`spawn_cancellable`]: TaskTracker::spawn_cancellable
+/// [`shutdown_with_timeout`]: crate::dispatching::ShutdownToken::shutdown_with_timeout
+#[derive(Clone, Debug, Default)]
+pub struct TaskTracker {
+    tasks: tokio_util::task::TaskTracker,
+    // Replaced when dispatching starts again after it was cancelled, since it
+    // can't be reset.
+    cancel: Arc<Mutex<CancellationToken>>,
+}
+
+impl TaskTracker {
+    /// Creates a tracker without tasks.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Spawns a task, which the dispatcher waits for when it shuts down.
+    pub fn spawn<F>(&self, task: F) -> JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        self.tasks.spawn(task)
+    }
+
+    /// Spawns a task, which is cancelled when the dispatcher shuts down.
+    ///
+    /// The task resolves to `None` if it was cancelled.
+    pub fn spawn_cancellable<F>(&self, task: F) -> JoinHandle<Option<F::Output>>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let cancel = self.cancellation_token();
+        self.tasks.spawn(async move { cancel.run_until_cancelled(task).await })
+    }
+
+    /// Resolves when the dispatcher shuts down and cancels the tasks.
+    ///
+    /// This can be used by tasks spawned with [`spawn`] to stop gracefully.
+    ///
+    /// [`spawn`]: TaskTracker::spawn
+    pub async fn cancelled(&self) {
+        self.cancellation_token().cancelled_owned().await;
+    }
+
+    /// Returns `true` if the dispatcher has shut down and cancelled the tasks.
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.lock().unwrap().is_cancelled()
+    }
+
+    /// Returns the number of tasks which are still running.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    /// Returns `true` if there are no running tasks.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+
+    fn cancellation_token(&self) -> CancellationToken {
+        self.cancel.lock().unwrap().clone()
+    }
+
+    /// Prepares the tracker for a new run of the dispatcher.
+    pub(crate) fn reset(&self) {
+        // Tasks spawned before dispatching started hold the current token, so
+        // it's kept unless it was cancelled by the previous run
+        let mut cancel = self.cancel.lock().unwrap();
+        if cancel.is_cancelled() {
+            *cancel = CancellationToken::new();
+        }
+        self.tasks.reopen();
+    }
+
+    /// Cancels the cancellable tasks and waits for the other ones.
+    pub(crate) async fn cancel_and_wait(&self) {
+        self.cancel.lock().unwrap().cancel();
+        self.tasks.close();
+
+        if !self.tasks.is_empty() {
+            log::debug!("Waiting for {} background task(s)...", self.tasks.len());
+        }
+        self.tasks.wait().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn spawned_before_dispatching() {
+        let tracker = TaskTracker::new();
+        let task = tracker.spawn_cancellable(futures::future::pending::<()>());
+
+        // Dispatching starts and then shuts down
+        tracker.reset();
+        tokio::time::timeout(Duration::from_secs(1), tracker.cancel_and_wait()).await.unwrap();
+        assert_eq!(task.await.unwrap(), None);
+
+        // The next run doesn't cancel new tasks right away
+        tracker.reset();
+        assert!(!tracker.is_cancelled());
+        let task = tracker.spawn_cancellable(async { 1 });
+        assert_eq!(task.await.unwrap(), Some(1));
+    }
+}
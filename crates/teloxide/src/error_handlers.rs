@@ -3,6 +3,21 @@
 use futures::future::BoxFuture;
 use std::{convert::Infallible, fmt::Debug, future::Future, sync::Arc};
 
+use crate::types::Update;
+
+#[cfg(feature = "opentelemetry")]
+mod opentelemetry;
+mod retry;
+#[cfg(feature = "sentry")]
+mod sentry;
+
+#[cfg(feature = "opentelemetry")]
+pub use self::opentelemetry::OpenTelemetryErrorHandler;
+pub use self::retry::RetryErrorHandler;
+pub(crate) use self::retry::{Retries, ScheduledRetries};
+#[cfg(feature = "sentry")]
+pub use self::sentry::SentryErrorHandler;
+
 /// An asynchronous handler of an error.
 ///
 /// See [the module-level documentation for the design
@@ -23,6 +38,72 @@ where
     }
 }
 
+/// An asynchronous handler of an error, which also receives the update that
+/// caused the error.
+///
+/// This is useful to reply to the user with an error message or to attach
+/// the update to an error report. See
+/// [`DispatcherBuilder::update_error_handler`].
+///
+/// ## Examples
+///
+/// ```no_run
+/// use std::sync::Arc;
+///
+/// use teloxide::{prelude::*, types::Update};
+///
+/// # async fn run(bot: Bot, handler: teloxide::dispatching::UpdateHandler<teloxide::RequestError>) {
+/// let error_bot = bot.clone();
+/// Dispatcher::builder(bot, handler)
+///     .update_error_handler(Arc::new(move |err: teloxide::RequestError, update: Arc<Update>| {
+///         let bot = error_bot.clone();
+///         async move {
+///             log::error!("Failed to handle update {}: {err}", update.id.0);
+///             if let Some(chat) = update.chat() {
+///                 let _ = bot.send_message(chat.id, "Something went wrong").await;
+///             }
+///         }
+///     }))
+///     .build()
+///     .dispatch()
+///     .await;
+/// # }
+/// ```
+///
+/// [`DispatcherBuilder::update_error_handler`]: crate::dispatching::DispatcherBuilder::update_error_handler
+pub trait UpdateErrorHandler<E> {
+    #[must_use]
+    fn handle_update_error(
+        self: Arc<Self>,
+        error: E,
+        update: Arc<Update>,
+    ) -> BoxFuture<'static, ()>;
+}
+
+impl<E, F, Fut> UpdateErrorHandler<E> for F
+where
+    F: Fn(E, Arc<Update>) -> Fut + Send + Sync + 'static,
+    E: Send + 'static,
+    Fut: Future<Output = ()> + Send,
+{
+    fn handle_update_error(
+        self: Arc<Self>,
+        error: E,
+        update: Arc<Update>,
+    ) -> BoxFuture<'static, ()> {
+        Box::pin(async move { self(error, update).await })
+    }
+}
+
+/// Adapts an [`ErrorHandler`] to [`UpdateErrorHandler`], ignoring updates.
+pub(crate) struct IgnoringUpdate<E>(pub(crate) Arc<dyn ErrorHandler<E> + Send + Sync>);
+
+impl<E> UpdateErrorHandler<E> for IgnoringUpdate<E> {
+    fn handle_update_error(self: Arc<Self>, error: E, _: Arc<Update>) -> BoxFuture<'static, ()> {
+        Arc::clone(&self.0).handle_error(error)
+    }
+}
+
 /// Something that can be handled by an error handler.
 ///
 /// ## Examples
@@ -0,0 +1,170 @@
+use std::{error::Error, sync::Arc};
+
+use futures::future::BoxFuture;
+use opentelemetry::{
+    global,
+    trace::{Span, Status, Tracer},
+    KeyValue,
+};
+
+use crate::{
+    error_handlers::{ErrorHandler, UpdateErrorHandler},
+    types::Update,
+};
+
+/// An error handler which exports errors to [OpenTelemetry].
+///
+/// Every error is recorded as a `handler error` span of the global tracer
+/// provider (see [`opentelemetry::global::set_tracer_provider`]), with the
+/// error status and an `exception` event for the error and each of its
+/// [`Error::source`]s. When used as an [`UpdateErrorHandler`], the span also
+/// has the following attributes:
+/// - `telegram.update_id`: the id of the update that caused the error.
+/// - `telegram.chat_id`: the id of the chat of the update, if any.
+/// - `enduser.id`: the id of the sender of the update, if any. It's personal
+///   data, so it's only exported by handlers created with
+///   [`with_personal_data`].
+///
+/// The span is a child of the current context, so it's a part of the trace
+/// of the update if the update handler is instrumented.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use teloxide::{error_handlers::OpenTelemetryErrorHandler, prelude::*};
+///
+/// # async fn run(bot: Bot, handler: teloxide::dispatching::UpdateHandler<teloxide::RequestError>) {
+/// Dispatcher::builder(bot, handler)
+///     .update_error_handler(OpenTelemetryErrorHandler::new())
+///     .build()
+///     .dispatch()
+///     .await;
+/// # }
+/// ```
+///
+/// [OpenTelemetry]: https://opentelemetry.io
+/// [`with_personal_data`]: OpenTelemetryErrorHandler::with_personal_data
+#[derive(Clone, Copy, Debug)]
+pub struct OpenTelemetryErrorHandler {
+    personal_data: bool,
+}
+
+impl OpenTelemetryErrorHandler {
+    #[must_use]
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self { personal_data: false })
+    }
+
+    /// Creates `OpenTelemetryErrorHandler` which also exports the id of the
+    /// sender of the update as `enduser.id`.
+    #[must_use]
+    pub fn with_personal_data() -> Arc<Self> {
+        Arc::new(Self { personal_data: true })
+    }
+
+    fn export(&self, error: &(dyn Error + Send + Sync), update: Option<&Update>) {
+        self.export_to(&global::tracer("teloxide"), error, update);
+    }
+
+    fn export_to<T>(&self, tracer: &T, error: &(dyn Error + Send + Sync), update: Option<&Update>)
+    where
+        T: Tracer,
+    {
+        let mut span = tracer.start("handler error");
+
+        if let Some(update) = update {
+            span.set_attribute(KeyValue::new("telegram.update_id", i64::from(update.id.0)));
+            if let Some(chat) = update.chat() {
+                span.set_attribute(KeyValue::new("telegram.chat_id", chat.id.0));
+            }
+            if let Some(user) = update.from().filter(|_| self.personal_data) {
+                span.set_attribute(KeyValue::new("enduser.id", user.id.to_string()));
+            }
+        }
+
+        let mut source: Option<&dyn Error> = Some(error);
+        while let Some(error) = source {
+            span.record_error(error);
+            source = error.source();
+        }
+
+        span.set_status(Status::error(error.to_string()));
+        span.end();
+    }
+}
+
+impl<E> ErrorHandler<E> for OpenTelemetryErrorHandler
+where
+    E: Into<Box<dyn Error + Send + Sync>>,
+{
+    fn handle_error(self: Arc<Self>, error: E) -> BoxFuture<'static, ()> {
+        self.export(&*error.into(), None);
+        Box::pin(async {})
+    }
+}
+
+impl<E> UpdateErrorHandler<E> for OpenTelemetryErrorHandler
+where
+    E: Into<Box<dyn Error + Send + Sync>>,
+{
+    fn handle_update_error(
+        self: Arc<Self>,
+        error: E,
+        update: Arc<Update>,
+    ) -> BoxFuture<'static, ()> {
+        self.export(&*error.into(), Some(&update));
+        Box::pin(async {})
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use opentelemetry::{trace::TracerProvider as _, Value};
+    use opentelemetry_sdk::trace::{InMemorySpanExporter, SdkTracerProvider, SpanData};
+
+    use super::*;
+    use crate::test_utils::{message_json, update};
+
+    fn export(handler: &OpenTelemetryErrorHandler) -> SpanData {
+        let exporter = InMemorySpanExporter::default();
+        let provider = SdkTracerProvider::builder().with_simple_exporter(exporter.clone()).build();
+        let mut msg = message_json(1, "hi");
+        msg["from"]["id"] = 2.into();
+        let update = update(7, "message", msg);
+
+        #[derive(Debug, thiserror::Error)]
+        #[error("failed")]
+        struct Failed(#[source] std::io::Error);
+
+        let error: Box<dyn Error + Send + Sync> = Box::new(Failed(std::io::Error::other("cause")));
+        handler.export_to(&provider.tracer("test"), &*error, Some(&update));
+
+        let mut spans = exporter.get_finished_spans().unwrap();
+        assert_eq!(spans.len(), 1);
+        spans.pop().unwrap()
+    }
+
+    fn attribute(span: &SpanData, key: &str) -> Option<Value> {
+        span.attributes.iter().find(|kv| kv.key.as_str() == key).map(|kv| kv.value.clone())
+    }
+
+    #[test]
+    fn export_error() {
+        let span = export(&OpenTelemetryErrorHandler::new());
+        assert_eq!(span.name, "handler error");
+        assert!(matches!(span.status, Status::Error { .. }));
+
+        assert_eq!(attribute(&span, "telegram.update_id"), Some(Value::I64(7)));
+        assert_eq!(attribute(&span, "telegram.chat_id"), Some(Value::I64(1)));
+        assert_eq!(attribute(&span, "enduser.id"), None);
+
+        // The error and its source
+        assert_eq!(span.events.len(), 2);
+    }
+
+    #[test]
+    fn personal_data() {
+        let span = export(&OpenTelemetryErrorHandler::with_personal_data());
+        assert_eq!(attribute(&span, "enduser.id"), Some(Value::from("2")));
+    }
+}
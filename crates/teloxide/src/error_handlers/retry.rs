@@ -0,0 +1,216 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use futures::future::BoxFuture;
+use tokio::sync::mpsc;
+
+use crate::{
+    backoff::exponential_backoff_strategy,
+    error_handlers::{IgnoringUpdate, LoggingErrorHandler, UpdateErrorHandler},
+    types::{Update, UpdateId},
+};
+
+/// An error handler which handles updates again after retryable errors.
+///
+/// Which errors are retryable is decided by the function passed to
+/// [`RetryErrorHandler::new`]. When a handler fails with such an error, the
+/// update is sent to the dispatcher again after a delay, which grows
+/// exponentially with each attempt (see [`RetryErrorHandler::backoff`]). Other
+/// errors, and errors of the last [attempt], are passed to the [fallback]
+/// handler, which logs them by default.
+///
+/// This handler must be set with [`DispatcherBuilder::retry_error_handler`],
+/// since it needs the dispatcher to receive the updates which are handled
+/// again. An update is reported as processed to the update listener (see
+/// [`UpdateListener::processed_callback`]) only after its last attempt.
+/// Retries that are still waiting when dispatching stops are dropped, without
+/// being reported as processed.
+///
+/// Note that handlers must be ready to handle an update several times, e.g. a
+/// handler which fails after sending a message will send it again.
+///
+/// [attempt]: RetryErrorHandler::max_attempts
+/// [fallback]: RetryErrorHandler::fallback
+/// [`DispatcherBuilder::retry_error_handler`]: crate::dispatching::DispatcherBuilder::retry_error_handler
+/// [`UpdateListener::processed_callback`]: crate::update_listeners::UpdateListener::processed_callback
+///
+/// ## Examples
+///
+/// ```no_run
+/// use teloxide::{error_handlers::RetryErrorHandler, prelude::*, RequestError};
+///
+/// # async fn run(bot: Bot, handler: teloxide::dispatching::UpdateHandler<RequestError>) {
+/// let retry = RetryErrorHandler::new(RequestError::is_retryable).max_attempts(5);
+///
+/// Dispatcher::builder(bot, handler).retry_error_handler(retry).build().dispatch().await;
+/// # }
+/// ```
+#[must_use]
+pub struct RetryErrorHandler<E> {
+    is_retryable: Box<dyn Fn(&E) -> bool + Send + Sync>,
+    max_attempts: u32,
+    backoff: Box<dyn Fn(u32) -> Duration + Send + Sync>,
+    fallback: Arc<dyn UpdateErrorHandler<E> + Send + Sync>,
+    // The number of failed attempts of updates which are being retried.
+    attempts: Mutex<HashMap<UpdateId, (u32, Instant)>>,
+    // Set when the handler is registered in a dispatcher.
+    retries: Option<mpsc::UnboundedSender<Update>>,
+    scheduled: ScheduledRetries,
+}
+
+/// Ids of updates which failed and will be handled again.
+pub(crate) type ScheduledRetries = Arc<Mutex<HashSet<UpdateId>>>;
+
+/// The parts of a [`RetryErrorHandler`] used by the dispatcher.
+pub(crate) struct Retries {
+    /// Updates which must be handled again.
+    pub(crate) updates: mpsc::UnboundedReceiver<Update>,
+    pub(crate) scheduled: ScheduledRetries,
+}
+
+/// The number of tracked updates after which stale attempts are removed.
+const PRUNE_THRESHOLD: usize = 1024;
+
+/// Attempts older than this are removed when pruning, e.g. of updates which
+/// were handled successfully after a retry.
+const STALE_AFTER: Duration = Duration::from_secs(60 * 60);
+
+impl<E> RetryErrorHandler<E>
+where
+    E: Debug + Send + 'static,
+{
+    /// Creates a handler, which retries updates if `is_retryable` returns
+    /// `true` for the error.
+    ///
+    /// By default, updates are handled at most 3 times, with the delays of
+    /// [`exponential_backoff_strategy`] between attempts.
+    ///
+    /// [`exponential_backoff_strategy`]: crate::backoff::exponential_backoff_strategy
+    pub fn new<F>(is_retryable: F) -> Self
+    where
+        F: Fn(&E) -> bool + Send + Sync + 'static,
+    {
+        Self {
+            is_retryable: Box::new(is_retryable),
+            max_attempts: 3,
+            backoff: Box::new(exponential_backoff_strategy),
+            fallback: Arc::new(IgnoringUpdate(LoggingErrorHandler::new())),
+            attempts: Mutex::default(),
+            retries: None,
+            scheduled: ScheduledRetries::default(),
+        }
+    }
+}
+
+impl<E> RetryErrorHandler<E> {
+    /// Sets the maximum number of times an update is handled, including the
+    /// first attempt.
+    ///
+    /// ## Panics
+    ///
+    /// If `max_attempts` is `0`.
+    pub fn max_attempts(self, max_attempts: u32) -> Self {
+        assert!(max_attempts > 0, "`max_attempts` must be at least 1");
+        Self { max_attempts, ..self }
+    }
+
+    /// Sets the delay before a retry, given the number of previous retries of
+    /// the update.
+    pub fn backoff<F>(self, backoff: F) -> Self
+    where
+        F: Fn(u32) -> Duration + Send + Sync + 'static,
+    {
+        Self { backoff: Box::new(backoff), ..self }
+    }
+
+    /// Sets the handler of errors which aren't retried.
+    pub fn fallback(self, fallback: Arc<dyn UpdateErrorHandler<E> + Send + Sync>) -> Self {
+        Self { fallback, ..self }
+    }
+
+    /// Returns the receiver of updates which must be handled again.
+    pub(crate) fn register(&mut self) -> Retries {
+        let (tx, rx) = mpsc::unbounded_channel();
+        self.retries = Some(tx);
+        Retries { updates: rx, scheduled: Arc::clone(&self.scheduled) }
+    }
+
+    /// Returns the number of previous retries of the update, if it must be
+    /// retried after the current failed attempt.
+    fn next_retry(&self, id: UpdateId, retryable: bool, now: Instant) -> Option<u32> {
+        let mut attempts = self.attempts.lock().unwrap();
+
+        let failed = attempts.get(&id).map_or(0, |&(failed, _)| failed) + 1;
+        if !retryable || failed >= self.max_attempts {
+            attempts.remove(&id);
+            return None;
+        }
+
+        if attempts.len() >= PRUNE_THRESHOLD {
+            attempts.retain(|_, &mut (_, last)| now.saturating_duration_since(last) < STALE_AFTER);
+        }
+        attempts.insert(id, (failed, now));
+
+        Some(failed - 1)
+    }
+}
+
+impl<E> UpdateErrorHandler<E> for RetryErrorHandler<E>
+where
+    E: Debug + Send + 'static,
+{
+    fn handle_update_error(
+        self: Arc<Self>,
+        error: E,
+        update: Arc<Update>,
+    ) -> BoxFuture<'static, ()> {
+        let Some(retries) = self.retries.clone() else {
+            log::warn!(
+                "`RetryErrorHandler` must be set with `DispatcherBuilder::retry_error_handler`, \
+                 not retrying"
+            );
+            return Arc::clone(&self.fallback).handle_update_error(error, update);
+        };
+
+        let retryable = (self.is_retryable)(&error);
+        let Some(retried) = self.next_retry(update.id, retryable, Instant::now()) else {
+            return Arc::clone(&self.fallback).handle_update_error(error, update);
+        };
+
+        let delay = (self.backoff)(retried);
+        log::warn!("Failed to handle update {}, retrying in {delay:?}: {error:?}", update.id.0);
+        self.scheduled.lock().unwrap().insert(update.id);
+
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            // The dispatcher might have stopped in the meantime
+            let _ = retries.send(Update::clone(&update));
+        });
+
+        Box::pin(async {})
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_retry() {
+        let handler = RetryErrorHandler::new(|_: &()| true).max_attempts(3);
+        let now = Instant::now();
+
+        assert_eq!(handler.next_retry(UpdateId(1), true, now), Some(0));
+        assert_eq!(handler.next_retry(UpdateId(2), false, now), None);
+        assert_eq!(handler.next_retry(UpdateId(1), true, now), Some(1));
+        assert_eq!(handler.next_retry(UpdateId(1), true, now), None);
+        assert!(handler.attempts.lock().unwrap().is_empty());
+
+        // Attempts are counted anew after the last one
+        assert_eq!(handler.next_retry(UpdateId(1), true, now), Some(0));
+    }
+}
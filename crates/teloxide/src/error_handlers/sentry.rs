@@ -0,0 +1,132 @@
+use std::{error::Error, sync::Arc};
+
+use futures::future::BoxFuture;
+use sentry_core::protocol;
+
+use crate::{
+    error_handlers::{ErrorHandler, UpdateErrorHandler},
+    types::Update,
+};
+
+/// An error handler which reports errors to [Sentry].
+///
+/// Errors are captured with [`sentry_core::event_from_error`], so the chain of
+/// [`Error::source`]s is reported as well. When used as an
+/// [`UpdateErrorHandler`], events also include the id of the update that
+/// caused the error and the id of its chat as tags.
+///
+/// The sender of the update (as the user of the event) and the update itself
+/// (as extra data) contain personal data, so they are only included if
+/// `send_default_pii` is enabled in the options of the Sentry client.
+///
+/// Events are captured by the current Sentry hub, so the Sentry client must be
+/// initialized (e.g. with `sentry::init`) for them to be sent. Stack traces are
+/// attached by the client, if `attach_stacktrace` is enabled in its options.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use teloxide::{error_handlers::SentryErrorHandler, prelude::*};
+///
+/// # async fn run(bot: Bot, handler: teloxide::dispatching::UpdateHandler<teloxide::RequestError>) {
+/// Dispatcher::builder(bot, handler)
+///     .update_error_handler(SentryErrorHandler::new())
+///     .build()
+///     .dispatch()
+///     .await;
+/// # }
+/// ```
+///
+/// [Sentry]: https://sentry.io
+#[derive(Clone, Copy, Debug)]
+pub struct SentryErrorHandler;
+
+impl SentryErrorHandler {
+    #[must_use]
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self)
+    }
+
+    fn capture(error: &(dyn Error + Send + Sync), update: Option<&Update>) {
+        let mut event = sentry_core::event_from_error(error);
+
+        if let Some(update) = update {
+            event.tags.insert("update_id".to_owned(), update.id.0.to_string());
+            if let Some(chat) = update.chat() {
+                event.tags.insert("chat_id".to_owned(), chat.id.to_string());
+            }
+
+            let send_pii = sentry_core::Hub::with_active(|hub| {
+                hub.client().is_some_and(|client| client.options().send_default_pii)
+            });
+            if send_pii {
+                event.user = update.from().map(|user| protocol::User {
+                    id: Some(user.id.to_string()),
+                    username: user.username.clone(),
+                    ..Default::default()
+                });
+                if let Ok(update) = serde_json::to_value(update) {
+                    event.extra.insert("update".to_owned(), update);
+                }
+            }
+        }
+
+        sentry_core::capture_event(event);
+    }
+}
+
+impl<E> ErrorHandler<E> for SentryErrorHandler
+where
+    E: Into<Box<dyn Error + Send + Sync>>,
+{
+    fn handle_error(self: Arc<Self>, error: E) -> BoxFuture<'static, ()> {
+        Self::capture(&*error.into(), None);
+        Box::pin(async {})
+    }
+}
+
+impl<E> UpdateErrorHandler<E> for SentryErrorHandler
+where
+    E: Into<Box<dyn Error + Send + Sync>>,
+{
+    fn handle_update_error(
+        self: Arc<Self>,
+        error: E,
+        update: Arc<Update>,
+    ) -> BoxFuture<'static, ()> {
+        Self::capture(&*error.into(), Some(&update));
+        Box::pin(async {})
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sentry_core::{test::with_captured_events_options, ClientOptions};
+
+    use super::*;
+    use crate::test_utils::message_update;
+
+    fn capture(send_default_pii: bool) -> protocol::Event<'static> {
+        let options = ClientOptions { send_default_pii, ..Default::default() };
+        let error: Box<dyn Error + Send + Sync> = "failed".into();
+        let mut events = with_captured_events_options(
+            || SentryErrorHandler::capture(&*error, Some(&message_update(7, 1, "hi"))),
+            options,
+        );
+        assert_eq!(events.len(), 1);
+        events.pop().unwrap()
+    }
+
+    #[test]
+    fn personal_data() {
+        let event = capture(false);
+        assert_eq!(event.tags["update_id"], "7");
+        assert_eq!(event.tags["chat_id"], "1");
+        assert_eq!(event.user, None);
+        assert!(!event.extra.contains_key("update"));
+
+        let event = capture(true);
+        assert_eq!(event.user.unwrap().id.as_deref(), Some("1"));
+        assert_eq!(event.extra["update"]["update_id"], 7);
+    }
+}
@@ -144,9 +144,14 @@ pub mod prelude;
 pub mod repls;
 pub mod stop;
 pub mod sugar;
+pub mod tasks;
+pub mod test;
 pub mod update_listeners;
 pub mod utils;
 
+#[cfg(test)]
+mod test_utils;
+
 #[doc(inline)]
 pub use teloxide_core::*;
 
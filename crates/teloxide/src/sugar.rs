@@ -1,4 +1,6 @@
 //! Some syntax sugar support for TBA functionality.
 
 pub mod bot;
+pub mod callback_query;
+pub mod message;
 pub mod request;
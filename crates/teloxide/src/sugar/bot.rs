@@ -1,9 +1,22 @@
 //! Additions to [`Bot`].
 //!
 //! [`Bot`]: crate::Bot
-use futures::stream::{self, Stream, StreamExt};
+use std::{future::Future, pin::pin, time::Duration};
 
-use crate::{prelude::*, types::*};
+use futures::{
+    future::{self, Either},
+    stream::{self, Stream, StreamExt},
+};
+
+use crate::{prelude::*, sugar::message::MessageExt as _, types::*};
+
+/// How often [`BotChatActionExt::send_chat_action_while`] repeats the chat
+/// action, Telegram shows it for 5 seconds at most.
+const CHAT_ACTION_INTERVAL: Duration = Duration::from_secs(4);
+
+/// How many transactions [`BotMessagesExt::iter_star_transactions`] requests
+/// at once, the maximum allowed by Telegram.
+const STAR_TRANSACTIONS_PAGE_SIZE: u8 = 100;
 
 /// Syntax sugar for [`Message`] manipulations.
 ///
@@ -111,7 +124,46 @@ pub trait BotMessagesExt: Requester {
     where
         C: Into<Recipient>;
 
+    /// This function is the same as [`Bot::send_message`], but replies to
+    /// [`Message`], which may be in a different chat than `to_chat_id`.
+    ///
+    /// To quote a part of the message, use
+    /// `.reply_parameters(message.reply_parameters().quote(...))` instead.
+    ///
+    /// [`Bot::send_message`]: crate::Bot::send_message
+    /// [`Message`]: crate::types::Message
+    fn reply_in<C, T>(&self, to_chat_id: C, message: &Message, text: T) -> Self::SendMessage
+    where
+        C: Into<Recipient>,
+        T: Into<String>;
+
+    /// This function is the same as [`Bot::send_message`], but replies to
+    /// [`Message`] in its chat, forum topic and business connection.
+    ///
+    /// See [`MessageExt::reply`].
+    ///
+    /// [`Bot::send_message`]: crate::Bot::send_message
+    /// [`Message`]: crate::types::Message
+    /// [`MessageExt::reply`]: crate::sugar::message::MessageExt::reply
+    fn reply_to<T>(&self, message: &Message, text: T) -> Self::SendMessage
+    where
+        T: Into<String>;
+
+    /// Returns a stream of all Telegram Star transactions of the bot in
+    /// chronological order, requesting them page by page with
+    /// [`Bot::get_star_transactions`].
+    ///
+    /// The stream ends after the last transaction or after the first error.
+    ///
+    /// [`Bot::get_star_transactions`]: crate::Bot::get_star_transactions
     fn iter_star_transactions(&self) -> impl Stream<Item = StarTransaction>;
+
+    /// This function is the same as [`Bot::refund_star_payment`],
+    /// but can take in [`SuccessfulPayment`] to refund it.
+    ///
+    /// [`Bot::refund_star_payment`]: crate::Bot::refund_star_payment
+    /// [`SuccessfulPayment`]: crate::types::SuccessfulPayment
+    fn refund(&self, user_id: UserId, payment: &SuccessfulPayment) -> Self::RefundStarPayment;
 }
 
 impl<R> BotMessagesExt for R
@@ -184,16 +236,189 @@ where
         self.copy_message(to_chat_id, message.chat.id, message.id)
     }
 
+    fn reply_in<C, T>(&self, to_chat_id: C, message: &Message, text: T) -> Self::SendMessage
+    where
+        C: Into<Recipient>,
+        T: Into<String>,
+    {
+        self.send_message(to_chat_id, text).reply_parameters(message.reply_parameters())
+    }
+
+    fn reply_to<T>(&self, message: &Message, text: T) -> Self::SendMessage
+    where
+        T: Into<String>,
+    {
+        message.reply(self, text)
+    }
+
     fn iter_star_transactions(&self) -> impl Stream<Item = StarTransaction> {
-        stream::unfold(0, move |state| async move {
-            let transactions: Result<StarTransactions, <R as Requester>::Err> =
-                self.get_star_transactions().offset(state).await;
+        stream::unfold(Some(0), move |offset| async move {
+            let offset = offset?;
+            let page = self
+                .get_star_transactions()
+                .offset(offset)
+                .limit(STAR_TRANSACTIONS_PAGE_SIZE)
+                .await
+                .ok()?;
 
-            match transactions {
-                Ok(transactions) => Some((stream::iter(transactions.transactions), state + 100)),
-                Err(_) => None,
-            }
+            // A partially filled page is the last one
+            let next_offset = (page.transactions.len() == usize::from(STAR_TRANSACTIONS_PAGE_SIZE))
+                .then(|| offset + u32::from(STAR_TRANSACTIONS_PAGE_SIZE));
+
+            Some((stream::iter(page.transactions), next_offset))
         })
         .flatten()
     }
+
+    fn refund(&self, user_id: UserId, payment: &SuccessfulPayment) -> Self::RefundStarPayment {
+        self.refund_star_payment(user_id, payment.telegram_payment_charge_id.clone())
+    }
+}
+
+/// Syntax sugar for [chat actions].
+///
+/// [chat actions]: crate::types::ChatAction
+pub trait BotChatActionExt: Requester {
+    /// Shows `action` (e.g. "typing...") in the chat while `work` is running,
+    /// and returns the output of `work`.
+    ///
+    /// The action is sent with [`Bot::send_chat_action`] right away, and then
+    /// every 4 seconds, since Telegram shows it for 5 seconds at most. It
+    /// stops being sent as soon as `work` completes. Errors of sending the
+    /// action are ignored.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use teloxide::{prelude::*, sugar::bot::BotChatActionExt, types::ChatAction};
+    ///
+    /// # async fn slow_answer(_: &str) -> String { String::new() }
+    /// async fn handler(bot: Bot, msg: Message) -> ResponseResult<()> {
+    ///     let answer = bot
+    ///         .send_chat_action_while(
+    ///             msg.chat.id,
+    ///             ChatAction::Typing,
+    ///             slow_answer(msg.text().unwrap_or_default()),
+    ///         )
+    ///         .await;
+    ///     bot.send_message(msg.chat.id, answer).await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// [`Bot::send_chat_action`]: crate::Bot::send_chat_action
+    fn send_chat_action_while<C, F>(
+        &self,
+        chat_id: C,
+        action: ChatAction,
+        work: F,
+    ) -> impl Future<Output = F::Output>
+    where
+        C: Into<Recipient>,
+        F: Future;
+}
+
+impl<R> BotChatActionExt for R
+where
+    R: Requester,
+{
+    fn send_chat_action_while<C, F>(
+        &self,
+        chat_id: C,
+        action: ChatAction,
+        work: F,
+    ) -> impl Future<Output = F::Output>
+    where
+        C: Into<Recipient>,
+        F: Future,
+    {
+        let chat_id = chat_id.into();
+        let keep_alive = async move {
+            loop {
+                let _ = self.send_chat_action(chat_id.clone(), action).await;
+                tokio::time::sleep(CHAT_ACTION_INTERVAL).await;
+            }
+        };
+
+        async move {
+            match future::select(pin!(work), pin!(keep_alive)).await {
+                Either::Left((output, _)) => output,
+                Either::Right(((), _)) => unreachable!("the chat action is sent forever"),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::fake_api_calls;
+
+    #[tokio::test]
+    async fn send_chat_action_while() {
+        let (url, mut calls) = fake_api_calls(vec![Some(true.into())]).await;
+        let bot = Bot::new("TOKEN").set_api_url(url);
+
+        // The work completes once the action is sent
+        let (method, payload) =
+            bot.send_chat_action_while(ChatId(1), ChatAction::Typing, calls.recv()).await.unwrap();
+
+        assert_eq!(method, "SendChatAction");
+        assert_eq!(payload["chat_id"], 1);
+        assert_eq!(payload["action"], "typing");
+    }
+
+    /// Starts a server which responds to `get_star_transactions` calls with
+    /// pages of the given number of transactions (or an error for `None`), and
+    /// returns its URL and the received payloads.
+    async fn fake_star_transactions(
+        pages: Vec<Option<usize>>,
+    ) -> (url::Url, tokio::sync::mpsc::UnboundedReceiver<serde_json::Value>) {
+        let responses = pages
+            .into_iter()
+            .map(|page| {
+                page.map(|len| {
+                    let transactions: Vec<_> = (0..len)
+                        .map(|i| serde_json::json!({"id": i.to_string(), "amount": 1, "date": 0}))
+                        .collect();
+                    serde_json::json!({"transactions": transactions})
+                })
+            })
+            .collect();
+        crate::test_utils::fake_api(responses).await
+    }
+
+    #[tokio::test]
+    async fn iter_star_transactions() {
+        let (url, mut payloads) = fake_star_transactions(vec![Some(100), Some(30)]).await;
+        let bot = Bot::new("TOKEN").set_api_url(url);
+
+        let transactions: Vec<_> = bot.iter_star_transactions().collect().await;
+        assert_eq!(transactions.len(), 130);
+        assert_eq!(transactions[100].id.0, "0");
+
+        // The partially filled page is the last one
+        assert_eq!(payloads.recv().await.unwrap(), serde_json::json!({"offset": 0, "limit": 100}));
+        assert_eq!(
+            payloads.recv().await.unwrap(),
+            serde_json::json!({"offset": 100, "limit": 100})
+        );
+        assert!(payloads.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn iter_star_transactions_end() {
+        // A full last page is followed by an empty one
+        let (url, mut payloads) = fake_star_transactions(vec![Some(100), Some(0)]).await;
+        let bot = Bot::new("TOKEN").set_api_url(url);
+        assert_eq!(bot.iter_star_transactions().count().await, 100);
+        assert_eq!(payloads.recv().await.unwrap()["offset"], 0);
+        assert_eq!(payloads.recv().await.unwrap()["offset"], 100);
+        assert!(payloads.recv().await.is_none());
+
+        // The stream ends after an error
+        let (url, _) = fake_star_transactions(vec![Some(100), None]).await;
+        let bot = Bot::new("TOKEN").set_api_url(url);
+        assert_eq!(bot.iter_star_transactions().count().await, 100);
+    }
 }
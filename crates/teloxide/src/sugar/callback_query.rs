@@ -0,0 +1,246 @@
+//! Additions to [`CallbackQuery`].
+//!
+//! [`CallbackQuery`]: crate::types::CallbackQuery
+use std::borrow::Cow;
+
+use thiserror::Error;
+use url::Url;
+
+use crate::{payloads::AnswerCallbackQuerySetters as _, requests::Requester, types::CallbackQuery};
+
+/// Syntax sugar for answering [`CallbackQuery`]s.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use teloxide::{
+///     prelude::*,
+///     sugar::callback_query::{AnswerText, CallbackQueryExt},
+/// };
+///
+/// async fn on_callback(bot: Bot, q: CallbackQuery) -> ResponseResult<()> {
+///     // ...
+///     q.answer_toast(&bot, AnswerText::new("Saved!")).await?;
+///     Ok(())
+/// }
+/// ```
+///
+/// [`CallbackQuery`]: crate::types::CallbackQuery
+pub trait CallbackQueryExt {
+    /// Answers the query with a notification at the top of the chat screen.
+    ///
+    /// This is the same as [`Bot::answer_callback_query`] with [`text`].
+    ///
+    /// [`Bot::answer_callback_query`]: crate::Bot::answer_callback_query
+    /// [`text`]: crate::payloads::AnswerCallbackQuery::text
+    fn answer_toast<R>(&self, bot: &R, text: AnswerText) -> R::AnswerCallbackQuery
+    where
+        R: Requester;
+
+    /// Answers the query with an alert, which the user has to close.
+    ///
+    /// This is the same as [`Bot::answer_callback_query`] with [`text`] and
+    /// [`show_alert`].
+    ///
+    /// [`Bot::answer_callback_query`]: crate::Bot::answer_callback_query
+    /// [`text`]: crate::payloads::AnswerCallbackQuery::text
+    /// [`show_alert`]: crate::payloads::AnswerCallbackQuery::show_alert
+    fn answer_alert<R>(&self, bot: &R, text: AnswerText) -> R::AnswerCallbackQuery
+    where
+        R: Requester;
+
+    /// Answers the query by opening `url`, which must be a link to the game
+    /// of the query or a `t.me/your_bot?start=XXXX` link.
+    ///
+    /// This is the same as [`Bot::answer_callback_query`] with [`url`].
+    ///
+    /// [`Bot::answer_callback_query`]: crate::Bot::answer_callback_query
+    /// [`url`]: crate::payloads::AnswerCallbackQuery::url
+    fn answer_url<R>(&self, bot: &R, url: Url) -> R::AnswerCallbackQuery
+    where
+        R: Requester;
+}
+
+impl CallbackQueryExt for CallbackQuery {
+    fn answer_toast<R>(&self, bot: &R, text: AnswerText) -> R::AnswerCallbackQuery
+    where
+        R: Requester,
+    {
+        bot.answer_callback_query(self.id.clone()).text(text.0)
+    }
+
+    fn answer_alert<R>(&self, bot: &R, text: AnswerText) -> R::AnswerCallbackQuery
+    where
+        R: Requester,
+    {
+        bot.answer_callback_query(self.id.clone()).text(text.0).show_alert(true)
+    }
+
+    fn answer_url<R>(&self, bot: &R, url: Url) -> R::AnswerCallbackQuery
+    where
+        R: Requester,
+    {
+        bot.answer_callback_query(self.id.clone()).url(url)
+    }
+}
+
+/// The text of an answer to a callback query, which is at most
+/// [`AnswerText::MAX_LEN`] characters long.
+///
+/// Texts known at compile time can be checked at compile time, by creating
+/// them in a constant:
+///
+/// ```
+/// use teloxide::sugar::callback_query::AnswerText;
+///
+/// const SAVED: AnswerText = AnswerText::new("Saved!");
+/// ```
+///
+/// ```compile_fail
+/// use teloxide::sugar::callback_query::AnswerText;
+///
+/// const TOO_LONG: AnswerText = AnswerText::new(
+///     "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor \
+///      incididunt ut labore et dolore magna aliqua. Ut enim ad minim veniam, quis nostrud \
+///      exercitation ullamco laboris nisi ut aliquip ex ea commodo consequat.",
+/// );
+/// ```
+///
+/// Other texts are checked by [`AnswerText::try_from`], which accepts both
+/// `&'static str` and `String`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AnswerText(Cow<'static, str>);
+
+/// An error returned when a text is longer than [`AnswerText::MAX_LEN`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Error)]
+#[error("the text of a callback query answer is {len} characters long, the maximum is 200")]
+pub struct AnswerTextTooLong {
+    /// The length of the text in characters.
+    pub len: usize,
+}
+
+impl AnswerText {
+    /// The maximum length of the text in characters.
+    pub const MAX_LEN: usize = 200;
+
+    /// Creates a text from a string literal.
+    ///
+    /// ## Panics
+    ///
+    /// If `text` is longer than [`AnswerText::MAX_LEN`] characters. This is a
+    /// compile time error if this is called in a constant.
+    #[must_use]
+    pub const fn new(text: &'static str) -> Self {
+        assert!(
+            char_count(text) <= Self::MAX_LEN,
+            "the text of a callback query answer must be at most 200 characters long"
+        );
+
+        Self(Cow::Borrowed(text))
+    }
+
+    /// Returns the text.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl TryFrom<&'static str> for AnswerText {
+    type Error = AnswerTextTooLong;
+
+    fn try_from(text: &'static str) -> Result<Self, Self::Error> {
+        check_len(Cow::Borrowed(text))
+    }
+}
+
+impl TryFrom<String> for AnswerText {
+    type Error = AnswerTextTooLong;
+
+    fn try_from(text: String) -> Result<Self, Self::Error> {
+        check_len(Cow::Owned(text))
+    }
+}
+
+fn check_len(text: Cow<'static, str>) -> Result<AnswerText, AnswerTextTooLong> {
+    match text.chars().count() {
+        len if len > AnswerText::MAX_LEN => Err(AnswerTextTooLong { len }),
+        _ => Ok(AnswerText(text)),
+    }
+}
+
+const fn char_count(s: &str) -> usize {
+    let bytes = s.as_bytes();
+    let mut count = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        // Count all bytes except UTF-8 continuation bytes
+        if bytes[i] & 0b1100_0000 != 0b1000_0000 {
+            count += 1;
+        }
+        i += 1;
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        payloads::AnswerCallbackQuery,
+        requests::HasPayload,
+        types::{CallbackQueryId, User, UserId},
+        Bot,
+    };
+
+    #[test]
+    fn answers() {
+        let bot = Bot::new("TOKEN");
+        let query = CallbackQuery {
+            id: CallbackQueryId("42".to_owned()),
+            from: User {
+                id: UserId(1),
+                is_bot: false,
+                first_name: "A".to_owned(),
+                last_name: None,
+                username: None,
+                language_code: None,
+                is_premium: false,
+                added_to_attachment_menu: false,
+            },
+            message: None,
+            inline_message_id: None,
+            chat_instance: "1".to_owned(),
+            data: None,
+            game_short_name: None,
+        };
+
+        let payload = |request: <Bot as Requester>::AnswerCallbackQuery| -> AnswerCallbackQuery {
+            request.payload_ref().clone()
+        };
+
+        let toast = payload(query.answer_toast(&bot, AnswerText::new("Saved!")));
+        assert_eq!(toast.callback_query_id, query.id);
+        assert_eq!(toast.text.as_deref(), Some("Saved!"));
+        assert_eq!(toast.show_alert, None);
+
+        let alert =
+            payload(query.answer_alert(&bot, AnswerText::try_from("No!".to_owned()).unwrap()));
+        assert_eq!(alert.text.as_deref(), Some("No!"));
+        assert_eq!(alert.show_alert, Some(true));
+
+        let url = "https://t.me/bot?start=game".parse::<Url>().unwrap();
+        assert_eq!(payload(query.answer_url(&bot, url.clone())).url, Some(url));
+    }
+
+    #[test]
+    fn answer_text() {
+        assert_eq!(char_count("ёжик"), 4);
+        assert!(AnswerText::try_from("ё".repeat(200)).is_ok());
+        assert_eq!(AnswerText::try_from("ё".repeat(201)), Err(AnswerTextTooLong { len: 201 }));
+
+        let long: &'static str = "ё".repeat(201).leak();
+        assert_eq!(AnswerText::try_from(long), Err(AnswerTextTooLong { len: 201 }));
+        assert_eq!(AnswerText::try_from("Saved!").unwrap().as_str(), "Saved!");
+    }
+}
@@ -0,0 +1,242 @@
+//! Additions to [`Message`].
+//!
+//! [`Message`]: crate::types::Message
+use crate::{
+    payloads::{CopyMessageSetters as _, SendMessageSetters as _, SetMessageReactionSetters as _},
+    requests::Requester,
+    types::{Message, ReactionType, Recipient, ReplyParameters},
+};
+
+/// Syntax sugar for answering and copying [`Message`]s.
+///
+/// Answers are sent to the chat of the message, to the same [forum topic] and
+/// on behalf of the same [business connection], so these don't have to be
+/// copied from the message by hand.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use teloxide::{prelude::*, sugar::message::MessageExt};
+///
+/// async fn handler(bot: Bot, msg: Message) -> ResponseResult<()> {
+///     msg.reply(&bot, "Pong!").await?;
+///     Ok(())
+/// }
+/// ```
+///
+/// [`Message`]: crate::types::Message
+/// [forum topic]: crate::types::Message::forum_topic_id
+/// [business connection]: crate::types::Message::business_connection_id
+pub trait MessageExt {
+    /// Sends a text message to the chat (and the forum topic) of this
+    /// message.
+    ///
+    /// This is the same as [`Bot::send_message`] with [`message_thread_id`]
+    /// and [`business_connection_id`] taken from this message.
+    ///
+    /// [`Bot::send_message`]: crate::Bot::send_message
+    /// [`message_thread_id`]: crate::payloads::SendMessage::message_thread_id
+    /// [`business_connection_id`]: crate::payloads::SendMessage::business_connection_id
+    fn answer<R, T>(&self, bot: &R, text: T) -> R::SendMessage
+    where
+        R: Requester,
+        T: Into<String>;
+
+    /// Sends a text message replying to this message.
+    ///
+    /// This is the same as [`MessageExt::answer`] with [`reply_parameters`]
+    /// pointing to this message.
+    ///
+    /// [`reply_parameters`]: crate::payloads::SendMessage::reply_parameters
+    fn reply<R, T>(&self, bot: &R, text: T) -> R::SendMessage
+    where
+        R: Requester,
+        T: Into<String>;
+
+    /// Copies this message to `chat_id`, keeping its inline keyboard and the
+    /// position of its caption.
+    ///
+    /// [`Bot::copy_message`] doesn't copy the inline keyboard of a message,
+    /// and doesn't keep the caption above the media unless the caption is
+    /// passed again, so this sets [`reply_markup`], [`caption`],
+    /// [`caption_entities`] and [`show_caption_above_media`] from this
+    /// message. All of them can be overridden with the setters of the
+    /// returned request. Note that when overriding the caption, its entities
+    /// have to be overridden too, e.g. with `.caption_entities([])`.
+    ///
+    /// [`Bot::copy_message`]: crate::Bot::copy_message
+    /// [`reply_markup`]: crate::payloads::CopyMessage::reply_markup
+    /// [`caption`]: crate::payloads::CopyMessage::caption
+    /// [`caption_entities`]: crate::payloads::CopyMessage::caption_entities
+    /// [`show_caption_above_media`]: crate::payloads::CopyMessage::show_caption_above_media
+    fn copy_to<R, C>(&self, bot: &R, chat_id: C) -> R::CopyMessage
+    where
+        R: Requester,
+        C: Into<Recipient>;
+
+    /// Sets the reaction of the bot to this message, replacing the previous
+    /// one.
+    ///
+    /// This is the same as [`Bot::set_message_reaction`] with [`reaction`].
+    ///
+    /// [`Bot::set_message_reaction`]: crate::Bot::set_message_reaction
+    /// [`reaction`]: crate::payloads::SetMessageReaction::reaction
+    fn react<R>(&self, bot: &R, reaction: ReactionType) -> R::SetMessageReaction
+    where
+        R: Requester;
+}
+
+impl MessageExt for Message {
+    fn answer<R, T>(&self, bot: &R, text: T) -> R::SendMessage
+    where
+        R: Requester,
+        T: Into<String>,
+    {
+        let mut request = bot.send_message(self.chat.id, text);
+        // Thread ids of replies in supergroups without topics can't be used
+        if let Some(thread_id) = self.forum_topic_id() {
+            request = request.message_thread_id(thread_id);
+        }
+        if let Some(business_connection_id) = self.business_connection_id() {
+            request = request.business_connection_id(business_connection_id.clone());
+        }
+        request
+    }
+
+    fn reply<R, T>(&self, bot: &R, text: T) -> R::SendMessage
+    where
+        R: Requester,
+        T: Into<String>,
+    {
+        self.answer(bot, text).reply_parameters(ReplyParameters::new(self.id))
+    }
+
+    fn copy_to<R, C>(&self, bot: &R, chat_id: C) -> R::CopyMessage
+    where
+        R: Requester,
+        C: Into<Recipient>,
+    {
+        let mut request = bot.copy_message(chat_id, self.chat.id, self.id);
+        if let Some(markup) = self.reply_markup() {
+            request = request.reply_markup(markup.clone());
+        }
+        if let Some(caption) = self.caption() {
+            request = request
+                .caption(caption)
+                .caption_entities(self.caption_entities().unwrap_or_default().iter().cloned())
+                .show_caption_above_media(self.show_caption_above_media());
+        }
+        request
+    }
+
+    fn react<R>(&self, bot: &R, reaction: ReactionType) -> R::SetMessageReaction
+    where
+        R: Requester,
+    {
+        bot.set_message_reaction(self.chat.id, self.id).reaction([reaction])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Deref;
+
+    use super::*;
+    use crate::{
+        test_utils::message_from_text,
+        types::{BusinessConnectionId, ChatId, MessageId, ThreadId},
+        Bot,
+    };
+
+    #[test]
+    fn answer() {
+        let bot = Bot::new("TOKEN");
+        let msg = |json| serde_json::from_str::<Message>(json).unwrap();
+
+        let private = msg(r#"{
+            "message_id": 1,
+            "date": 0,
+            "chat": { "id": 1, "type": "private", "first_name": "A" },
+            "text": "hi"
+        }"#);
+        assert_eq!(
+            private.answer(&bot, "hello").deref(),
+            bot.send_message(ChatId(1), "hello").deref()
+        );
+
+        let topic = msg(r#"{
+            "message_id": 2,
+            "message_thread_id": 3,
+            "is_topic_message": true,
+            "business_connection_id": "conn",
+            "date": 0,
+            "chat": { "id": -1, "type": "supergroup", "title": "G", "is_forum": true },
+            "text": "hi"
+        }"#);
+        assert_eq!(
+            topic.reply(&bot, "hello").deref(),
+            bot.send_message(ChatId(-1), "hello")
+                .message_thread_id(ThreadId(MessageId(3)))
+                .business_connection_id(BusinessConnectionId("conn".to_owned()))
+                .reply_parameters(ReplyParameters::new(MessageId(2)))
+                .deref()
+        );
+
+        // A reply in a supergroup without topics
+        let reply = msg(r#"{
+            "message_id": 2,
+            "message_thread_id": 1,
+            "date": 0,
+            "chat": { "id": -1, "type": "supergroup", "title": "G" },
+            "text": "hi"
+        }"#);
+        assert_eq!(
+            reply.answer(&bot, "hello").deref(),
+            bot.send_message(ChatId(-1), "hello").deref()
+        );
+    }
+
+    #[test]
+    fn copy_to() {
+        let bot = Bot::new("TOKEN");
+        let msg: Message = serde_json::from_str(
+            r#"{
+                "message_id": 2,
+                "date": 0,
+                "chat": { "id": 1, "type": "private", "first_name": "A" },
+                "photo": [{ "file_id": "id", "file_unique_id": "", "width": 1, "height": 1 }],
+                "caption": "bold",
+                "caption_entities": [{ "type": "bold", "offset": 0, "length": 4 }],
+                "show_caption_above_media": true,
+                "reply_markup": {
+                    "inline_keyboard": [[{ "text": "button", "callback_data": "data" }]]
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            msg.copy_to(&bot, ChatId(3)).deref(),
+            bot.copy_message(ChatId(3), ChatId(1), MessageId(2))
+                .reply_markup(msg.reply_markup().unwrap().clone())
+                .caption("bold")
+                .caption_entities(msg.caption_entities().unwrap().to_vec())
+                .show_caption_above_media(true)
+                .deref()
+        );
+        assert_eq!(msg.caption_entities().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn react() {
+        let bot = Bot::new("TOKEN");
+        let msg = message_from_text("hi");
+
+        assert_eq!(
+            msg.react(&bot, ReactionType::from_emoji("👍")).deref(),
+            bot.set_message_reaction(ChatId(1), MessageId(1))
+                .reaction([ReactionType::Emoji { emoji: "👍".to_owned() }])
+                .deref()
+        );
+    }
+}
@@ -0,0 +1,435 @@
+//! Scheduling messages to be sent, edited or deleted later.
+//!
+//! See [`Scheduler`].
+
+use std::{
+    fmt::Display,
+    pin::pin,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use futures::future;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{Mutex, Notify};
+
+use crate::{
+    dispatching::dialogue::Storage,
+    requests::Requester,
+    types::{ChatId, MessageId},
+};
+
+/// For how long [`Scheduler::run`] waits before retrying after a storage
+/// error.
+const STORAGE_RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// A job which can be scheduled with [`Scheduler`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum Job {
+    /// Sends a text message with [`SendMessage`].
+    ///
+    /// [`SendMessage`]: crate::payloads::SendMessage
+    SendMessage { chat_id: ChatId, text: String },
+
+    /// Replaces the text of a message with [`EditMessageText`].
+    ///
+    /// [`EditMessageText`]: crate::payloads::EditMessageText
+    EditMessageText { chat_id: ChatId, message_id: MessageId, text: String },
+
+    /// Deletes a message with [`DeleteMessage`].
+    ///
+    /// [`DeleteMessage`]: crate::payloads::DeleteMessage
+    DeleteMessage { chat_id: ChatId, message_id: MessageId },
+}
+
+/// An identifier of a scheduled job, which can be used to
+/// [cancel](Scheduler::cancel) it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct JobId(pub u64);
+
+/// A [`Job`] with the time it's scheduled for.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScheduledJob {
+    pub id: JobId,
+    /// When the job is executed.
+    pub at: SystemTime,
+    pub job: Job,
+}
+
+/// An entry of a [`Scheduler`] in a [`Storage`]: either a single job or the
+/// index of all jobs.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchedulerEntry(Entry);
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+enum Entry {
+    Index(JobIndex),
+    Job(ScheduledJob),
+}
+
+/// Ids of the jobs with the times they are scheduled for.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct JobIndex {
+    next_id: u64,
+    jobs: Vec<(JobId, SystemTime)>,
+}
+
+/// Sends, edits and deletes messages at a scheduled time.
+///
+/// Jobs are kept in a dialogue [`Storage`], so with a persistent storage (e.g.
+/// [`RedisStorage`] or [`SqliteStorage`]) they survive restarts. Each job is
+/// stored as a separate entry, along with an index entry holding the ids and
+/// the times of all jobs. Jobs which became due while the bot wasn't running
+/// are executed right after a restart.
+///
+/// Jobs are executed by [`Scheduler::run`], which needs to be spawned once.
+/// A job is removed from the storage before it's executed, so it's executed
+/// at most once. Due jobs are executed concurrently, their errors are logged.
+///
+/// Clones share the same state, so a scheduler can be passed to handlers as a
+/// dependency. Modifications of the stored jobs are only synchronized between
+/// clones, so the jobs of a [`namespace`] must not be used by several
+/// processes (or separately created schedulers) at the same time.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use teloxide::{
+///     dispatching::dialogue::InMemStorage,
+///     prelude::*,
+///     tasks::{Job, Scheduler, SchedulerEntry},
+/// };
+///
+/// type MyScheduler = Scheduler<Bot, InMemStorage<SchedulerEntry>>;
+/// type HandlerResult = Result<(), Box<dyn std::error::Error + Send + Sync>>;
+///
+/// # async fn run() {
+/// let bot = Bot::from_env();
+/// let scheduler = Scheduler::new(bot.clone(), InMemStorage::<SchedulerEntry>::new());
+/// tokio::spawn(scheduler.clone().run());
+///
+/// // Deletes service messages after 5 minutes
+/// let handler = Update::filter_message()
+///     .filter(|msg: Message| msg.new_chat_members().is_some())
+///     .endpoint(|msg: Message, scheduler: MyScheduler| async move {
+///         let job = Job::DeleteMessage { chat_id: msg.chat.id, message_id: msg.id };
+///         scheduler.schedule_in(Duration::from_secs(5 * 60), job).await?;
+///         HandlerResult::Ok(())
+///     });
+///
+/// Dispatcher::builder(bot, handler)
+///     .dependencies(dptree::deps![scheduler])
+///     .build()
+///     .dispatch()
+///     .await;
+/// # }
+/// ```
+///
+/// [`RedisStorage`]: crate::dispatching::dialogue::RedisStorage
+/// [`SqliteStorage`]: crate::dispatching::dialogue::SqliteStorage
+/// [`namespace`]: Scheduler::namespace
+pub struct Scheduler<R, S: ?Sized> {
+    bot: R,
+    storage: Arc<S>,
+    namespace: u8,
+    // Serializes modifications of the stored jobs
+    lock: Arc<Mutex<()>>,
+    // Wakes up `run` when a job is scheduled
+    scheduled: Arc<Notify>,
+}
+
+impl<R, S> Scheduler<R, S>
+where
+    R: Requester + Clone + Send + Sync + 'static,
+    R::Err: Display,
+    S: Storage<SchedulerEntry> + ?Sized + Send + Sync + 'static,
+    S::Error: Display,
+{
+    /// Creates a scheduler, which keeps the jobs in `storage`.
+    #[must_use]
+    pub fn new(bot: R, storage: Arc<S>) -> Self {
+        Self { bot, storage, namespace: 0, lock: <_>::default(), scheduled: <_>::default() }
+    }
+
+    /// Sets the namespace of the keys, under which the jobs are kept in the
+    /// storage.
+    ///
+    /// This allows to use the same storage for the jobs of multiple bots. The
+    /// keys are far below the ids of real chats, so the storage can also be
+    /// shared with dialogues.
+    ///
+    /// Default - `0`.
+    #[must_use]
+    pub fn namespace(self, namespace: u8) -> Self {
+        Self { namespace, ..self }
+    }
+
+    /// Schedules `job` to be executed at `at`.
+    ///
+    /// Jobs scheduled for the past are executed right away.
+    pub async fn schedule_at(&self, at: SystemTime, job: Job) -> Result<JobId, S::Error> {
+        let _lock = self.lock.lock().await;
+
+        let mut index = self.load_index().await?;
+        let id = JobId(index.next_id);
+        index.next_id += 1;
+        index.jobs.push((id, at));
+
+        // The job is saved first, so that the index never refers to a missing job
+        self.save(self.job_key(id), Entry::Job(ScheduledJob { id, at, job })).await?;
+        self.save(self.index_key(), Entry::Index(index)).await?;
+
+        self.scheduled.notify_waiters();
+        Ok(id)
+    }
+
+    /// Schedules `job` to be executed after `delay`.
+    pub async fn schedule_in(&self, delay: Duration, job: Job) -> Result<JobId, S::Error> {
+        self.schedule_at(SystemTime::now() + delay, job).await
+    }
+
+    /// Cancels the job, returns `false` if there is no such job (e.g. it was
+    /// already executed).
+    pub async fn cancel(&self, id: JobId) -> Result<bool, S::Error> {
+        let _lock = self.lock.lock().await;
+
+        let mut index = self.load_index().await?;
+        let len = index.jobs.len();
+        index.jobs.retain(|&(job, _)| job != id);
+        if index.jobs.len() == len {
+            return Ok(false);
+        }
+
+        self.save(self.index_key(), Entry::Index(index)).await?;
+        self.remove(self.job_key(id)).await?;
+        Ok(true)
+    }
+
+    /// Returns the jobs which weren't executed yet.
+    pub async fn jobs(&self) -> Result<Vec<ScheduledJob>, S::Error> {
+        let index = self.load_index().await?;
+
+        let mut jobs = Vec::with_capacity(index.jobs.len());
+        for (id, _) in index.jobs {
+            jobs.extend(self.load_job(id).await?);
+        }
+        Ok(jobs)
+    }
+
+    /// Executes jobs as they become due, never returns.
+    ///
+    /// This should be spawned once, e.g. with [`tokio::spawn`].
+    pub async fn run(self) {
+        loop {
+            let mut scheduled = pin!(self.scheduled.notified());
+            scheduled.as_mut().enable();
+
+            let next = match self.take_due().await {
+                Ok((due, next)) => {
+                    for job in due {
+                        let this = self.clone();
+                        tokio::spawn(async move { this.execute(job).await });
+                    }
+                    next
+                }
+                Err(err) => {
+                    log::error!("Failed to load scheduled jobs: {err}");
+                    Some(SystemTime::now() + STORAGE_RETRY_DELAY)
+                }
+            };
+
+            match next {
+                Some(at) => {
+                    let delay = at.duration_since(SystemTime::now()).unwrap_or_default();
+                    future::select(pin!(tokio::time::sleep(delay)), scheduled).await;
+                }
+                None => scheduled.await,
+            }
+        }
+    }
+
+    /// Removes the jobs which are due from the storage, returns them and the
+    /// time of the next job.
+    async fn take_due(&self) -> Result<(Vec<ScheduledJob>, Option<SystemTime>), S::Error> {
+        let _lock = self.lock.lock().await;
+
+        let mut index = self.load_index().await?;
+        let now = SystemTime::now();
+        let (due, pending): (Vec<_>, _) = index.jobs.into_iter().partition(|&(_, at)| at <= now);
+        index.jobs = pending;
+
+        let next = index.jobs.iter().map(|&(_, at)| at).min();
+        if due.is_empty() {
+            return Ok((Vec::new(), next));
+        }
+        self.save(self.index_key(), Entry::Index(index)).await?;
+
+        let mut jobs = Vec::with_capacity(due.len());
+        for (id, _) in due {
+            match self.load_job(id).await? {
+                Some(job) => {
+                    self.remove(self.job_key(id)).await?;
+                    jobs.push(job);
+                }
+                None => log::warn!("Scheduled job {} is missing from the storage", id.0),
+            }
+        }
+
+        Ok((jobs, next))
+    }
+
+    async fn execute(&self, ScheduledJob { id, job, .. }: ScheduledJob) {
+        let res = match job {
+            Job::SendMessage { chat_id, text } => {
+                self.bot.send_message(chat_id, text).await.map(drop)
+            }
+            Job::EditMessageText { chat_id, message_id, text } => {
+                self.bot.edit_message_text(chat_id, message_id, text).await.map(drop)
+            }
+            Job::DeleteMessage { chat_id, message_id } => {
+                self.bot.delete_message(chat_id, message_id).await.map(drop)
+            }
+        };
+
+        if let Err(err) = res {
+            log::error!("Scheduled job {} failed: {err}", id.0);
+        }
+    }
+
+    /// Returns the key of the index in the storage.
+    fn index_key(&self) -> ChatId {
+        self.storage_key(0)
+    }
+
+    /// Returns the key of the job `id` in the storage.
+    fn job_key(&self, id: JobId) -> ChatId {
+        self.storage_key(id.0 + 1)
+    }
+
+    fn storage_key(&self, slot: u64) -> ChatId {
+        // Each namespace has 2^55 keys, starting at `i64::MIN`
+        ChatId(i64::MIN + (i64::from(self.namespace) << 55) + (slot & ((1 << 55) - 1)) as i64)
+    }
+
+    async fn load_index(&self) -> Result<JobIndex, S::Error> {
+        match self.load(self.index_key()).await? {
+            Some(Entry::Index(index)) => Ok(index),
+            _ => Ok(JobIndex::default()),
+        }
+    }
+
+    async fn load_job(&self, id: JobId) -> Result<Option<ScheduledJob>, S::Error> {
+        match self.load(self.job_key(id)).await? {
+            Some(Entry::Job(job)) => Ok(Some(job)),
+            _ => Ok(None),
+        }
+    }
+
+    async fn load(&self, key: ChatId) -> Result<Option<Entry>, S::Error> {
+        let entry = Arc::clone(&self.storage).get_dialogue(key).await?;
+        Ok(entry.map(|SchedulerEntry(entry)| entry))
+    }
+
+    async fn save(&self, key: ChatId, entry: Entry) -> Result<(), S::Error> {
+        Arc::clone(&self.storage).update_dialogue(key, SchedulerEntry(entry)).await
+    }
+
+    async fn remove(&self, key: ChatId) -> Result<(), S::Error> {
+        Arc::clone(&self.storage).remove_dialogue(key).await
+    }
+}
+
+impl<R, S> Clone for Scheduler<R, S>
+where
+    R: Clone,
+    S: ?Sized,
+{
+    fn clone(&self) -> Self {
+        Self {
+            bot: self.bot.clone(),
+            storage: Arc::clone(&self.storage),
+            namespace: self.namespace,
+            lock: Arc::clone(&self.lock),
+            scheduled: Arc::clone(&self.scheduled),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dispatching::dialogue::InMemStorage, test_utils::unreachable_bot};
+
+    #[tokio::test]
+    async fn scheduler() {
+        // Requests fail, which is only logged
+        let bot = unreachable_bot();
+        let scheduler = Scheduler::new(bot, InMemStorage::<SchedulerEntry>::new());
+        let ids = |jobs: Vec<ScheduledJob>| jobs.into_iter().map(|job| job.id).collect::<Vec<_>>();
+
+        let delete = Job::DeleteMessage { chat_id: ChatId(1), message_id: MessageId(1) };
+        let later = scheduler.schedule_in(Duration::from_secs(60 * 60), delete).await.unwrap();
+        let running = tokio::spawn(scheduler.clone().run());
+
+        let send = Job::SendMessage { chat_id: ChatId(1), text: "hi".to_owned() };
+        let soon = scheduler.schedule_in(Duration::from_millis(10), send).await.unwrap();
+        assert_ne!(later, soon);
+
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while scheduler.jobs().await.unwrap().len() > 1 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(ids(scheduler.jobs().await.unwrap()), [later]);
+
+        assert!(scheduler.cancel(later).await.unwrap());
+        assert!(!scheduler.cancel(later).await.unwrap());
+        assert_eq!(scheduler.jobs().await.unwrap(), []);
+
+        running.abort();
+    }
+
+    #[tokio::test]
+    async fn storage_layout() {
+        let bot = unreachable_bot();
+        let storage = InMemStorage::<SchedulerEntry>::new();
+        let scheduler = Scheduler::new(bot.clone(), Arc::clone(&storage));
+        let other = Scheduler::new(bot, Arc::clone(&storage)).namespace(1);
+        let stored = |key: ChatId| {
+            let storage = Arc::clone(&storage);
+            async move { storage.get_dialogue(key).await.unwrap().map(|SchedulerEntry(e)| e) }
+        };
+
+        let send = |text: &str| Job::SendMessage { chat_id: ChatId(1), text: text.to_owned() };
+        let first = scheduler.schedule_in(Duration::from_secs(60), send("a")).await.unwrap();
+        let second = scheduler.schedule_in(Duration::from_secs(60), send("b")).await.unwrap();
+        let third = other.schedule_in(Duration::from_secs(60), send("c")).await.unwrap();
+        assert_eq!(first, third);
+
+        // Each job has its own entry
+        assert!(matches!(
+            stored(scheduler.job_key(second)).await,
+            Some(Entry::Job(ScheduledJob { id, .. })) if id == second
+        ));
+        assert_ne!(scheduler.job_key(first), other.job_key(third));
+        assert!(scheduler.index_key().0 < -(1 << 54));
+
+        assert!(scheduler.cancel(first).await.unwrap());
+        assert_eq!(stored(scheduler.job_key(first)).await, None);
+        let Some(Entry::Index(index)) = stored(scheduler.index_key()).await else {
+            panic!("no index");
+        };
+        assert_eq!(index.next_id, 2);
+        assert_eq!(index.jobs.iter().map(|&(id, _)| id).collect::<Vec<_>>(), [second]);
+
+        let jobs =
+            |jobs: Vec<ScheduledJob>| jobs.into_iter().map(|job| job.job).collect::<Vec<_>>();
+        assert_eq!(jobs(scheduler.jobs().await.unwrap()), [send("b")]);
+        assert_eq!(jobs(other.jobs().await.unwrap()), [send("c")]);
+    }
+}
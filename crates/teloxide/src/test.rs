@@ -0,0 +1,122 @@
+//! Utilities for testing handlers.
+
+use std::ops::ControlFlow;
+
+use dptree::{di::DependencyMap, Handler};
+
+use crate::{dispatching::DpHandlerDescription, types::Update};
+
+/// Creates a [`HandlerTest`] for `handler`.
+///
+/// This allows testing individual `dptree` branches in isolation, without
+/// building a [`Dispatcher`].
+///
+/// [`Dispatcher`]: crate::dispatching::Dispatcher
+///
+/// ## Examples
+///
+/// ```
+/// use std::ops::ControlFlow;
+///
+/// use teloxide::{prelude::*, test::handler_test, types::Update};
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let branch = Update::filter_message()
+///     .filter(|msg: Message| msg.text() == Some("ping"))
+///     .endpoint(|prefix: &'static str| async move { format!("{prefix}pong") });
+///
+/// let update: Update = serde_json::from_str(
+///     r#"{
+///         "update_id": 1,
+///         "message": {
+///             "message_id": 1,
+///             "date": 1721306082,
+///             "chat": { "id": 1, "first_name": "A", "type": "private" },
+///             "text": "ping"
+///         }
+///     }"#,
+/// )
+/// .unwrap();
+///
+/// let test = handler_test(branch).with_deps(dptree::deps!["> "]);
+/// assert_eq!(test.dispatch(update).await, ControlFlow::Break("> pong".to_owned()));
+/// # }
+/// ```
+pub fn handler_test<Output>(
+    handler: Handler<'static, Output, DpHandlerDescription>,
+) -> HandlerTest<Output>
+where
+    Output: Send + Sync + 'static,
+{
+    HandlerTest { handler, deps: DependencyMap::new() }
+}
+
+/// A handler with a set of dependencies, ready to be fed with updates.
+///
+/// Created by [`handler_test`].
+///
+/// Requests the handler sends to Telegram can be inspected by passing a mock
+/// implementation of [`Requester`] as a dependency, instead of [`Bot`].
+///
+/// [`Requester`]: crate::requests::Requester
+/// [`Bot`]: crate::Bot
+#[must_use]
+pub struct HandlerTest<Output> {
+    handler: Handler<'static, Output, DpHandlerDescription>,
+    deps: DependencyMap,
+}
+
+impl<Output> HandlerTest<Output>
+where
+    Output: Send + Sync + 'static,
+{
+    /// Adds dependencies that are passed to the handler with each update,
+    /// such as a bot, [`Me`] or storages.
+    ///
+    /// [`Me`]: crate::types::Me
+    pub fn with_deps(mut self, deps: DependencyMap) -> Self {
+        self.deps.insert_container(deps);
+        self
+    }
+
+    /// Passes `update` and the dependencies to the handler.
+    ///
+    /// Like [`Dispatcher`], the update is passed both as [`Update`] and
+    /// `Arc<Update>`.
+    ///
+    /// [`Dispatcher`]: crate::dispatching::Dispatcher
+    ///
+    /// Returns [`ControlFlow::Break`] with the output of the endpoint that
+    /// handled the update, or [`ControlFlow::Continue`] with the dependencies
+    /// if no endpoint did.
+    pub async fn dispatch(&self, update: Update) -> ControlFlow<Output, DependencyMap> {
+        let mut deps = self.deps.clone();
+        deps.insert(update);
+        deps.insert(deps.get::<Update>());
+
+        self.handler.dispatch(deps).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        dispatching::UpdateFilterExt,
+        test_utils::{message_json, update},
+    };
+
+    #[tokio::test]
+    async fn unhandled() {
+        let mut msg = message_json(1, "ping");
+        msg["edit_date"] = 2.into();
+        let update = update(1, "edited_message", msg);
+
+        let test = handler_test(Update::filter_message().endpoint(|| async {}));
+        let ControlFlow::Continue(deps) = test.dispatch(update.clone()).await else {
+            panic!("the update must not be handled");
+        };
+        assert_eq!(*deps.get::<Update>(), update);
+    }
+}
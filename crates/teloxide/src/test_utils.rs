@@ -0,0 +1,224 @@
+//! Fixtures shared by unit tests.
+
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    sync::{Arc, Mutex, Once},
+};
+
+use serde_json::{json, Value};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::TcpStream,
+    sync::mpsc::{UnboundedReceiver, UnboundedSender},
+};
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+    types::{ChatMemberUpdated, Me, Message, Update},
+    Bot,
+};
+
+/// Returns a bot whose requests fail, since nothing listens on its API URL.
+pub(crate) fn unreachable_bot() -> Bot {
+    Bot::new("TOKEN").set_api_url("http://127.0.0.1:1".parse().unwrap())
+}
+
+/// Returns the bot `@bot` with id `42`.
+pub(crate) fn me() -> Me {
+    serde_json::from_value(json!({
+        "id": 42,
+        "is_bot": true,
+        "first_name": "Bot",
+        "username": "bot",
+        "can_join_groups": false,
+        "can_read_all_group_messages": false,
+        "supports_inline_queries": false,
+        "can_connect_to_business": false,
+        "has_main_web_app": false
+    }))
+    .unwrap()
+}
+
+/// Returns the JSON of a text message sent by the user `user` in the private
+/// chat with the bot.
+pub(crate) fn message_json(user: u64, text: &str) -> Value {
+    json!({
+        "message_id": 1,
+        "date": 1,
+        "chat": { "id": user, "type": "private", "first_name": "A" },
+        "from": { "id": user, "is_bot": false, "first_name": "A" },
+        "text": text
+    })
+}
+
+/// Returns a text message sent by the user `1` in the private chat with the
+/// bot.
+pub(crate) fn message_from_text(text: &str) -> Message {
+    serde_json::from_value(message_json(1, text)).unwrap()
+}
+
+/// Returns the update `id` with `value` of `kind` (e.g. `"message"`).
+pub(crate) fn update(id: u32, kind: &str, value: Value) -> Update {
+    // `from_value` can't be used, since it deserializes into `UpdateKind::Error`
+    serde_json::from_str(&json!({ "update_id": id, kind: value }).to_string()).unwrap()
+}
+
+/// Returns the update `id` with a text message, see [`message_json`].
+pub(crate) fn message_update(id: u32, user: u64, text: &str) -> Update {
+    update(id, "message", message_json(user, text))
+}
+
+/// Returns a change of the status of the user `2` in a supergroup from `old`
+/// to `new` (JSONs of `ChatMember` without the `user` field).
+pub(crate) fn chat_member_updated(mut old: Value, mut new: Value) -> ChatMemberUpdated {
+    let user = json!({ "id": 2, "is_bot": false, "first_name": "B" });
+    old["user"] = user.clone();
+    new["user"] = user;
+
+    serde_json::from_value(json!({
+        "chat": { "id": -1, "title": "G", "type": "supergroup" },
+        "from": { "id": 1, "is_bot": false, "first_name": "A" },
+        "date": 1,
+        "old_chat_member": old,
+        "new_chat_member": new
+    }))
+    .unwrap()
+}
+
+/// Returns the messages logged by the current thread while running `f`.
+pub(crate) fn capture_logs(f: impl FnOnce()) -> Vec<String> {
+    thread_local! {
+        static CAPTURED: RefCell<Option<Vec<String>>> = const { RefCell::new(None) };
+    }
+
+    struct Logger;
+
+    impl log::Log for Logger {
+        fn enabled(&self, _: &log::Metadata<'_>) -> bool {
+            true
+        }
+
+        fn log(&self, record: &log::Record<'_>) {
+            CAPTURED.with(|captured| {
+                if let Some(captured) = captured.borrow_mut().as_mut() {
+                    captured.push(record.args().to_string());
+                }
+            });
+        }
+
+        fn flush(&self) {}
+    }
+
+    static INIT: Once = Once::new();
+    INIT.call_once(|| {
+        log::set_logger(&Logger).unwrap();
+        log::set_max_level(log::LevelFilter::Trace);
+    });
+
+    CAPTURED.with(|captured| *captured.borrow_mut() = Some(Vec::new()));
+    f();
+    CAPTURED.with(|captured| captured.borrow_mut().take().unwrap())
+}
+
+/// Starts a server which responds to API calls with the given results (or an
+/// error for `None`), and returns its URL and the received payloads.
+///
+/// The server stops once all results are sent, so that the receiver of the
+/// payloads is closed.
+pub(crate) async fn fake_api(results: Vec<Option<Value>>) -> (url::Url, UnboundedReceiver<Value>) {
+    let (url, mut calls) = fake_api_calls(results).await;
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        while let Some((_, payload)) = calls.recv().await {
+            let _ = tx.send(payload);
+        }
+    });
+
+    (url, rx)
+}
+
+/// Same as [`fake_api`], but receives the names of the called methods along
+/// with the payloads.
+pub(crate) async fn fake_api_calls(
+    results: Vec<Option<Value>>,
+) -> (url::Url, UnboundedReceiver<(String, Value)>) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap()).parse().unwrap();
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+    let results = Arc::new(Mutex::new(VecDeque::from(results)));
+    let done = CancellationToken::new();
+
+    tokio::spawn(async move {
+        // Clients may open new connections, e.g. if the previous one isn't
+        // returned to their pool yet
+        loop {
+            let stream = tokio::select! {
+                res = listener.accept() => res.unwrap().0,
+                () = done.cancelled() => break,
+            };
+
+            let (results, tx, done) = (Arc::clone(&results), tx.clone(), done.clone());
+            tokio::spawn(async move {
+                tokio::select! {
+                    () = serve(stream, &results, &tx, &done) => {}
+                    () = done.cancelled() => {}
+                }
+            });
+        }
+    });
+
+    (url, rx)
+}
+
+/// Responds to the API calls of a connection of [`fake_api`].
+async fn serve(
+    stream: TcpStream,
+    results: &Mutex<VecDeque<Option<Value>>>,
+    calls: &UnboundedSender<(String, Value)>,
+    done: &CancellationToken,
+) {
+    let mut stream = BufReader::new(stream);
+    loop {
+        let mut method = None;
+        let mut content_length = 0;
+        loop {
+            let mut line = String::new();
+            if stream.read_line(&mut line).await.unwrap() == 0 {
+                // The connection is closed
+                return;
+            }
+            if line == "\r\n" {
+                break;
+            }
+            // The request line, e.g. `POST /botTOKEN/GetMe HTTP/1.1`
+            if method.is_none() {
+                let path = line.split(' ').nth(1).unwrap();
+                method = Some(path.rsplit('/').next().unwrap().to_owned());
+            }
+            if let Some(len) = line.to_lowercase().strip_prefix("content-length: ") {
+                content_length = len.trim().parse().unwrap();
+            }
+        }
+        let mut payload = vec![0; content_length];
+        stream.read_exact(&mut payload).await.unwrap();
+
+        let Some(result) = results.lock().unwrap().pop_front() else { return };
+        let _ = calls.send((method.unwrap(), serde_json::from_slice(&payload).unwrap()));
+
+        let body = match result {
+            Some(result) => json!({ "ok": true, "result": result }),
+            None => json!({ "ok": false, "error_code": 400, "description": "Bad Request" }),
+        };
+        let body = body.to_string();
+        let response = format!(
+            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{body}",
+            body.len()
+        );
+        stream.get_mut().write_all(response.as_bytes()).await.unwrap();
+
+        if results.lock().unwrap().is_empty() {
+            done.cancel();
+        }
+    }
+}
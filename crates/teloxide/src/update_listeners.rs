@@ -8,6 +8,9 @@
 //! - [`Polling`] function, which returns a long polling listener with your
 //!   configuration.
 //! - Various functions in the [`webhooks`] module that return webhook listeners
+//! - [`queue::RedisQueue::listener`], which receives updates from a queue
+//!   filled by another process
+//! - [`from_file`] function, which replays updates recorded to a file
 //!
 //! And then you can extract updates from it or pass them directly to a
 //! [`Dispatcher`].
@@ -29,22 +32,46 @@
 #[cfg(feature = "webhooks")]
 pub mod webhooks;
 
-use futures::Stream;
+/// Update listeners that receive updates from a message queue, allowing to
+/// split the work of a bot between multiple processes.
+#[cfg(feature = "redis-queue")]
+pub mod queue;
+
+/// Storages of the progress of [`Polling`] listeners, see
+/// [`PollingBuilder::offset_storage`].
+pub mod offset_storage;
+
+/// Notifying systemd (or another service manager) about the state of the bot.
+///
+/// See also [`PollingBuilder::sd_notify`].
+#[cfg(all(unix, feature = "sd-notify"))]
+pub mod sd_notify;
+
+use std::sync::Arc;
+
+use futures::{future::BoxFuture, Stream};
 
 use crate::{
     stop::StopToken,
-    types::{AllowedUpdate, Update},
+    types::{AllowedUpdate, Update, UpdateId},
 };
 
 mod polling;
+mod replay;
 mod stateful_listener;
 
 #[allow(deprecated)]
 pub use self::{
-    polling::{polling_default, Polling, PollingBuilder, PollingStream},
+    polling::{
+        polling_default, Polling, PollingBuilder, PollingConfig, PollingHooks, PollingState,
+        PollingStream,
+    },
+    replay::{from_file, Pace, ReplayBuilder, ReplayError},
     stateful_listener::StatefulListener,
 };
 
+pub(crate) use self::replay::date_of;
+
 /// An update listener.
 ///
 /// Implementors of this trait allow getting updates from Telegram. See
@@ -91,8 +118,28 @@ pub trait UpdateListener:
     fn hint_allowed_updates(&mut self, hint: &mut dyn Iterator<Item = AllowedUpdate>) {
         let _ = hint;
     }
+
+    /// Returns a callback, which should be called with the id of each update
+    /// returned by this listener, once the update is processed.
+    ///
+    /// An update is processed when it was handled, failed to be handled, or
+    /// was dropped. The [`Dispatcher`] calls the callback after processing
+    /// each update, and waits for the returned future.
+    ///
+    /// For example [`Polling`] with an [offset storage] saves update ids only
+    /// once they are processed. By default, no callback is returned.
+    ///
+    /// [`Dispatcher`]: crate::dispatching::Dispatcher
+    /// [offset storage]: PollingBuilder::offset_storage
+    fn processed_callback(&mut self) -> Option<ProcessedCallback> {
+        None
+    }
 }
 
+/// A callback which is called once an update is processed, see
+/// [`UpdateListener::processed_callback`].
+pub type ProcessedCallback = Arc<dyn Fn(UpdateId) -> BoxFuture<'static, ()> + Send + Sync>;
+
 /// [`UpdateListener`]'s supertrait/extension.
 ///
 /// This trait is a workaround to not require GAT.
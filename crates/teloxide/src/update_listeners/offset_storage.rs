@@ -0,0 +1,100 @@
+mod file;
+
+#[cfg(feature = "redis-storage")]
+mod redis;
+
+#[cfg(any(feature = "sqlite-storage-nativetls", feature = "sqlite-storage-rustls"))]
+mod sqlite;
+
+#[cfg(any(feature = "postgres-storage-nativetls", feature = "postgres-storage-rustls"))]
+mod postgres;
+
+use std::sync::Arc;
+
+use futures::future::BoxFuture;
+
+use crate::types::UpdateId;
+
+pub use self::file::{FileOffsetStorage, FileOffsetStorageError};
+
+#[cfg(feature = "redis-storage")]
+pub use self::redis::{RedisOffsetStorage, RedisOffsetStorageError};
+
+#[cfg(any(feature = "sqlite-storage-nativetls", feature = "sqlite-storage-rustls"))]
+pub use self::sqlite::SqliteOffsetStorage;
+
+#[cfg(any(feature = "postgres-storage-nativetls", feature = "postgres-storage-rustls"))]
+pub use self::postgres::PostgresOffsetStorage;
+
+/// An offset storage with an erased error type.
+pub type ErasedOffsetStorage =
+    dyn OffsetStorage<Error = Box<dyn std::error::Error + Send + Sync>> + Send + Sync;
+
+/// A storage of the id of the last update processed by a [`Polling`] listener.
+///
+/// Telegram forgets updates once they are confirmed by the next `get_updates`
+/// call, which happens only after the whole previous batch of updates was
+/// passed to the dispatcher. If the bot crashes in between, updates which
+/// were already handled are received again after a restart. With an offset
+/// storage, the listener saves the id of each update once it's processed, and
+/// starts after the saved id when it's restarted.
+///
+/// When the listener is passed to a [`Dispatcher`], an update id is saved
+/// only once the update and all updates before it are processed, so updates
+/// which were being handled during a crash are received again. Otherwise,
+/// update ids are saved once they are returned from the stream.
+///
+/// Currently we support the following storages out of the box:
+///
+/// - [`FileOffsetStorage`] -- a storage based on a file.
+/// - [`RedisOffsetStorage`] -- a Redis-based storage.
+/// - [`SqliteOffsetStorage`] -- an SQLite-based storage.
+/// - [`PostgresOffsetStorage`] -- a PostgreSQL-based storage.
+///
+/// See [`PollingBuilder::offset_storage`].
+///
+/// [`Polling`]: crate::update_listeners::Polling
+/// [`Dispatcher`]: crate::dispatching::Dispatcher
+/// [`PollingBuilder::offset_storage`]: crate::update_listeners::PollingBuilder::offset_storage
+/// [`RedisOffsetStorage`]: crate::update_listeners::offset_storage::RedisOffsetStorage
+/// [`SqliteOffsetStorage`]: crate::update_listeners::offset_storage::SqliteOffsetStorage
+/// [`PostgresOffsetStorage`]: crate::update_listeners::offset_storage::PostgresOffsetStorage
+pub trait OffsetStorage {
+    type Error;
+
+    /// Returns the id of the last saved update, if any.
+    #[must_use = "Futures are lazy and do nothing unless polled with .await"]
+    fn load(self: Arc<Self>) -> BoxFuture<'static, Result<Option<UpdateId>, Self::Error>>;
+
+    /// Saves `update_id` as the id of the last processed update.
+    #[must_use = "Futures are lazy and do nothing unless polled with .await"]
+    fn save(self: Arc<Self>, update_id: UpdateId) -> BoxFuture<'static, Result<(), Self::Error>>;
+
+    /// Erases [`Self::Error`] to [`std::error::Error`].
+    #[must_use]
+    fn erase(self: Arc<Self>) -> Arc<ErasedOffsetStorage>
+    where
+        Self: Sized + Send + Sync + 'static,
+        Self::Error: std::error::Error + Send + Sync + 'static,
+    {
+        Arc::new(Eraser(self))
+    }
+}
+
+struct Eraser<S>(Arc<S>);
+
+impl<S> OffsetStorage for Eraser<S>
+where
+    S: OffsetStorage + Send + Sync + 'static,
+    S::Error: std::error::Error + Send + Sync + 'static,
+{
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    fn load(self: Arc<Self>) -> BoxFuture<'static, Result<Option<UpdateId>, Self::Error>> {
+        Box::pin(async move { Arc::clone(&self.0).load().await.map_err(|e| e.into()) })
+    }
+
+    fn save(self: Arc<Self>, update_id: UpdateId) -> BoxFuture<'static, Result<(), Self::Error>> {
+        Box::pin(async move { Arc::clone(&self.0).save(update_id).await.map_err(|e| e.into()) })
+    }
+}
@@ -0,0 +1,98 @@
+use std::{
+    io,
+    num::ParseIntError,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use futures::future::BoxFuture;
+use thiserror::Error;
+
+use super::OffsetStorage;
+use crate::types::UpdateId;
+
+/// An error returned from [`FileOffsetStorage`].
+#[derive(Debug, Error)]
+pub enum FileOffsetStorageError {
+    #[error("file error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("invalid update id: {0}")]
+    Parse(#[from] ParseIntError),
+}
+
+/// An offset storage, which keeps the update id in a file.
+///
+/// The file is replaced atomically, by writing the id to a temporary file
+/// next to it first.
+pub struct FileOffsetStorage {
+    path: PathBuf,
+}
+
+impl FileOffsetStorage {
+    /// Creates a storage, which keeps the update id in the file at `path`.
+    ///
+    /// The file is created when the first update id is saved.
+    #[must_use]
+    pub fn new<P>(path: P) -> Arc<Self>
+    where
+        P: Into<PathBuf>,
+    {
+        Arc::new(Self { path: path.into() })
+    }
+
+    /// Returns the path of the file.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl OffsetStorage for FileOffsetStorage {
+    type Error = FileOffsetStorageError;
+
+    fn load(self: Arc<Self>) -> BoxFuture<'static, Result<Option<UpdateId>, Self::Error>> {
+        Box::pin(async move {
+            match tokio::fs::read_to_string(&self.path).await {
+                Ok(id) => Ok(Some(UpdateId(id.trim().parse()?))),
+                Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+                Err(err) => Err(err.into()),
+            }
+        })
+    }
+
+    fn save(self: Arc<Self>, update_id: UpdateId) -> BoxFuture<'static, Result<(), Self::Error>> {
+        Box::pin(async move {
+            let mut tmp = self.path.clone().into_os_string();
+            tmp.push(".tmp");
+
+            tokio::fs::write(&tmp, update_id.0.to_string()).await?;
+            tokio::fs::rename(&tmp, &self.path).await?;
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn file_offset_storage() {
+        let path = std::env::temp_dir().join(format!("teloxide-offset-{}", std::process::id()));
+        let storage = FileOffsetStorage::new(&path);
+
+        assert_eq!(Arc::clone(&storage).load().await.unwrap(), None);
+        Arc::clone(&storage).save(UpdateId(41)).await.unwrap();
+        Arc::clone(&storage).save(UpdateId(42)).await.unwrap();
+        assert_eq!(Arc::clone(&storage).load().await.unwrap(), Some(UpdateId(42)));
+
+        tokio::fs::write(&path, "garbage").await.unwrap();
+        assert!(matches!(
+            storage.erase().load().await.unwrap_err().downcast_ref(),
+            Some(FileOffsetStorageError::Parse(_))
+        ));
+
+        tokio::fs::remove_file(&path).await.unwrap();
+    }
+}
@@ -0,0 +1,59 @@
+use std::sync::Arc;
+
+use deadpool_redis::{redis, CreatePoolError, PoolError, Runtime};
+use futures::future::BoxFuture;
+use redis::AsyncCommands;
+use thiserror::Error;
+
+use super::OffsetStorage;
+use crate::types::UpdateId;
+
+/// An error returned from [`RedisOffsetStorage`].
+#[derive(Debug, Error)]
+pub enum RedisOffsetStorageError {
+    #[error("error from Redis: {0}")]
+    RedisError(#[from] redis::RedisError),
+
+    #[error("error creating redis pool: {0}")]
+    CreatePoolError(#[from] CreatePoolError),
+
+    #[error("redis pool error: {0}")]
+    PoolError(#[from] PoolError),
+}
+
+/// An offset storage based on [Redis](https://redis.io/).
+pub struct RedisOffsetStorage {
+    pool: deadpool_redis::Pool,
+    key: String,
+}
+
+impl RedisOffsetStorage {
+    /// Opens a connection pool to Redis, the update id is stored under `key`.
+    pub async fn open<K>(url: &str, key: K) -> Result<Arc<Self>, RedisOffsetStorageError>
+    where
+        K: Into<String>,
+    {
+        let config = deadpool_redis::Config::from_url(url);
+        let pool = config.create_pool(Some(Runtime::Tokio1))?;
+
+        Ok(Arc::new(Self { pool, key: key.into() }))
+    }
+}
+
+impl OffsetStorage for RedisOffsetStorage {
+    type Error = RedisOffsetStorageError;
+
+    fn load(self: Arc<Self>) -> BoxFuture<'static, Result<Option<UpdateId>, Self::Error>> {
+        Box::pin(async move {
+            let id = self.pool.get().await?.get::<_, Option<u32>>(&self.key).await?;
+            Ok(id.map(UpdateId))
+        })
+    }
+
+    fn save(self: Arc<Self>, update_id: UpdateId) -> BoxFuture<'static, Result<(), Self::Error>> {
+        Box::pin(async move {
+            self.pool.get().await?.set::<_, u32, ()>(&self.key, update_id.0).await?;
+            Ok(())
+        })
+    }
+}
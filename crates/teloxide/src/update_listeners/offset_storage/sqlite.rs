@@ -0,0 +1,70 @@
+use std::sync::Arc;
+
+use futures::future::BoxFuture;
+use sqlx::sqlite::SqlitePool;
+
+use super::OffsetStorage;
+use crate::types::UpdateId;
+
+/// A persistent offset storage based on [SQLite](https://www.sqlite.org/).
+pub struct SqliteOffsetStorage {
+    pool: SqlitePool,
+    key: String,
+}
+
+impl SqliteOffsetStorage {
+    /// Opens the database at `path` and creates the table for storing update
+    /// ids, the update id is stored under `key`.
+    ///
+    /// The table may be shared with other bots, using different keys.
+    pub async fn open<K>(path: &str, key: K) -> Result<Arc<Self>, sqlx::Error>
+    where
+        K: Into<String>,
+    {
+        let pool = SqlitePool::connect(format!("sqlite:{path}?mode=rwc").as_str()).await?;
+        sqlx::query(
+            "
+CREATE TABLE IF NOT EXISTS teloxide_update_offsets (
+    key TEXT PRIMARY KEY,
+    update_id BIGINT NOT NULL
+);
+        ",
+        )
+        .execute(&pool)
+        .await?;
+
+        Ok(Arc::new(Self { pool, key: key.into() }))
+    }
+}
+
+impl OffsetStorage for SqliteOffsetStorage {
+    type Error = sqlx::Error;
+
+    fn load(self: Arc<Self>) -> BoxFuture<'static, Result<Option<UpdateId>, Self::Error>> {
+        Box::pin(async move {
+            let id: Option<(i64,)> =
+                sqlx::query_as("SELECT update_id FROM teloxide_update_offsets WHERE key = ?")
+                    .bind(&self.key)
+                    .fetch_optional(&self.pool)
+                    .await?;
+
+            Ok(id.map(|(id,)| UpdateId(id as u32)))
+        })
+    }
+
+    fn save(self: Arc<Self>, update_id: UpdateId) -> BoxFuture<'static, Result<(), Self::Error>> {
+        Box::pin(async move {
+            sqlx::query(
+                "
+            INSERT INTO teloxide_update_offsets VALUES (?, ?)
+            ON CONFLICT(key) DO UPDATE SET update_id=excluded.update_id
+                ",
+            )
+            .bind(&self.key)
+            .bind(i64::from(update_id.0))
+            .execute(&self.pool)
+            .await?;
+            Ok(())
+        })
+    }
+}
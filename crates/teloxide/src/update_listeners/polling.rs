@@ -1,16 +1,19 @@
 use std::{
+    collections::BTreeSet,
     future::Future,
     mem,
     pin::Pin,
+    sync::{Arc, Mutex},
     task::{
         self,
         Poll::{self, Ready},
     },
-    time::Duration,
+    time::{Duration, Instant},
     vec,
 };
 
-use futures::{ready, stream::Stream};
+use futures::{future::BoxFuture, ready, stream::Stream};
+use serde::{Deserialize, Serialize};
 use tokio::time::{sleep, Sleep};
 
 use teloxide_core::errors::AsResponseParameters;
@@ -19,8 +22,12 @@ use crate::{
     backoff::{exponential_backoff_strategy, BackoffStrategy},
     requests::{HasPayload, Request, Requester},
     stop::{mk_stop_token, StopFlag, StopToken},
-    types::{AllowedUpdate, Update},
-    update_listeners::{assert_update_listener, AsUpdateStream, UpdateListener},
+    types::{AllowedUpdate, Update, UpdateId},
+    update_listeners::{
+        assert_update_listener,
+        offset_storage::{ErasedOffsetStorage, OffsetStorage},
+        AsUpdateStream, ProcessedCallback, UpdateListener,
+    },
 };
 
 /// Builder for polling update listener.
@@ -34,7 +41,91 @@ pub struct PollingBuilder<R> {
     pub limit: Option<u8>,
     pub allowed_updates: Option<Vec<AllowedUpdate>>,
     pub drop_pending_updates: bool,
+    pub delete_webhook_first: bool,
     pub backoff_strategy: BackoffStrategy,
+    pub hooks: PollingHooks,
+    pub offset_storage: Option<Arc<ErasedOffsetStorage>>,
+}
+
+/// Options of a [`Polling`] listener, which can be loaded from a config file.
+///
+/// Missing fields are set to their defaults, and invalid values are rejected
+/// when deserializing. See [`PollingBuilder::config`].
+///
+/// ## Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use teloxide::{types::AllowedUpdate, update_listeners::PollingConfig};
+///
+/// let config: PollingConfig = serde_json::from_str(
+///     r#"{ "timeout": 30, "allowed_updates": ["message"], "delete_webhook_first": true }"#,
+/// )
+/// .unwrap();
+/// assert_eq!(config.timeout, Some(Duration::from_secs(30)));
+/// assert_eq!(config.allowed_updates, Some(vec![AllowedUpdate::Message]));
+/// assert!(!config.drop_pending_updates);
+///
+/// assert!(serde_json::from_str::<PollingConfig>(r#"{ "limit": 101 }"#).is_err());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+#[non_exhaustive]
+pub struct PollingConfig {
+    /// See [`PollingBuilder::timeout`], in seconds.
+    ///
+    /// Default - None.
+    #[serde(with = "crate::utils::config::secs_opt")]
+    pub timeout: Option<Duration>,
+
+    /// See [`PollingBuilder::limit`].
+    ///
+    /// Default - None.
+    #[serde(deserialize_with = "crate::utils::config::limit_opt")]
+    pub limit: Option<u8>,
+
+    /// See [`PollingBuilder::allowed_updates`].
+    ///
+    /// Default - None.
+    pub allowed_updates: Option<Vec<AllowedUpdate>>,
+
+    /// See [`PollingBuilder::drop_pending_updates`].
+    ///
+    /// Default - false.
+    pub drop_pending_updates: bool,
+
+    /// See [`PollingBuilder::delete_webhook_first`].
+    ///
+    /// Default - false.
+    pub delete_webhook_first: bool,
+}
+
+/// Callbacks which report the health of a [`Polling`] listener, e.g. to a
+/// process supervisor.
+///
+/// See [`PollingBuilder::on_ready`], [`PollingBuilder::on_alive`] and
+/// [`PollingBuilder::on_unhealthy`].
+#[derive(Clone)]
+#[non_exhaustive]
+pub struct PollingHooks {
+    pub on_ready: Option<Arc<dyn Fn() + Send + Sync>>,
+    pub on_alive: Option<Arc<dyn Fn() + Send + Sync>>,
+    pub on_unhealthy: Option<Arc<dyn Fn(u32) + Send + Sync>>,
+    pub unhealthy_after: u32,
+    pub on_error: Option<Arc<dyn Fn(u32, Duration) + Send + Sync>>,
+}
+
+impl Default for PollingHooks {
+    fn default() -> Self {
+        Self {
+            on_ready: None,
+            on_alive: None,
+            on_unhealthy: None,
+            unhealthy_after: 3,
+            on_error: None,
+        }
+    }
 }
 
 impl<R> PollingBuilder<R>
@@ -88,6 +179,55 @@ where
         Self { drop_pending_updates: true, ..self }
     }
 
+    /// Deletes the webhook before the first [`get_updates`] call, since
+    /// updates can't be received with [`get_updates`] while a webhook is set
+    /// up.
+    ///
+    /// Unlike [`delete_webhook`], the webhook is deleted when the listener
+    /// starts, so this doesn't need to be awaited. Errors are logged.
+    ///
+    /// [`get_updates`]: crate::requests::Requester::get_updates
+    /// [`delete_webhook`]: PollingBuilder::delete_webhook
+    pub fn delete_webhook_first(self) -> Self {
+        Self { delete_webhook_first: true, ..self }
+    }
+
+    /// Applies options loaded from a config file, see [`PollingConfig`].
+    ///
+    /// Options which aren't set in the config are left as they are.
+    ///
+    /// ## Panics
+    ///
+    /// If the config has invalid values, which can only happen if it wasn't
+    /// deserialized.
+    #[track_caller]
+    pub fn config(self, config: PollingConfig) -> Self {
+        let PollingConfig {
+            timeout,
+            limit,
+            allowed_updates,
+            drop_pending_updates,
+            delete_webhook_first,
+        } = config;
+
+        let mut this = self;
+        if let Some(timeout) = timeout {
+            this = this.timeout(timeout);
+        }
+        if let Some(limit) = limit {
+            this = this.limit(limit);
+        }
+        if let Some(allowed_updates) = allowed_updates {
+            this = this.allowed_updates(allowed_updates);
+        }
+
+        Self {
+            drop_pending_updates: this.drop_pending_updates || drop_pending_updates,
+            delete_webhook_first: this.delete_webhook_first || delete_webhook_first,
+            ..this
+        }
+    }
+
     /// The backoff strategy that will be used for delay calculation between
     /// reconnections caused by network errors.
     ///
@@ -99,6 +239,141 @@ where
         Self { backoff_strategy: Box::new(backoff_strategy), ..self }
     }
 
+    /// Sets a callback that is called when the first [`get_updates`] call
+    /// succeeds, and again when [`get_updates`] succeeds after the listener
+    /// was [unhealthy].
+    ///
+    /// [`get_updates`]: crate::requests::Requester::get_updates
+    /// [unhealthy]: PollingBuilder::on_unhealthy
+    pub fn on_ready(mut self, callback: impl Fn() + Send + Sync + 'static) -> Self {
+        self.hooks.on_ready = Some(Arc::new(callback));
+        self
+    }
+
+    /// Sets a callback that is called after each successful [`get_updates`]
+    /// call, even if there were no updates.
+    ///
+    /// Since [`get_updates`] waits for updates for up to [`timeout`], this is
+    /// called at least once per `timeout` while the listener works.
+    ///
+    /// [`get_updates`]: crate::requests::Requester::get_updates
+    /// [`timeout`]: PollingBuilder::timeout
+    pub fn on_alive(mut self, callback: impl Fn() + Send + Sync + 'static) -> Self {
+        self.hooks.on_alive = Some(Arc::new(callback));
+        self
+    }
+
+    /// Sets a callback that is called when [`get_updates`] fails
+    /// `unhealthy_after` times in a row, with the number of failures.
+    ///
+    /// The callback is called once per series of failures, [`on_ready`] is
+    /// called when the listener recovers.
+    ///
+    /// [`get_updates`]: crate::requests::Requester::get_updates
+    /// [`on_ready`]: PollingBuilder::on_ready
+    ///
+    /// ## Panics
+    ///
+    /// If `unhealthy_after` is 0.
+    #[track_caller]
+    pub fn on_unhealthy(
+        mut self,
+        unhealthy_after: u32,
+        callback: impl Fn(u32) + Send + Sync + 'static,
+    ) -> Self {
+        assert_ne!(unhealthy_after, 0, "unhealthy_after can't be 0");
+
+        self.hooks.unhealthy_after = unhealthy_after;
+        self.hooks.on_unhealthy = Some(Arc::new(callback));
+        self
+    }
+
+    /// Sets a callback that is called each time [`get_updates`] fails, with
+    /// the number of failures in a row and the delay before the next attempt.
+    ///
+    /// The delay is calculated by the [backoff strategy], or taken from the
+    /// error if Telegram asks to retry later. Such errors aren't counted as
+    /// failures, so the number of failures is `0` for them.
+    ///
+    /// [`get_updates`]: crate::requests::Requester::get_updates
+    /// [backoff strategy]: PollingBuilder::backoff_strategy
+    pub fn on_error(mut self, callback: impl Fn(u32, Duration) + Send + Sync + 'static) -> Self {
+        self.hooks.on_error = Some(Arc::new(callback));
+        self
+    }
+
+    /// Reports the health of the listener to systemd (or another service
+    /// manager supporting the [`sd_notify`] protocol).
+    ///
+    /// Sends `READY=1` when the listener is [ready], `WATCHDOG=1` when it's
+    /// [alive] and updates the status when it's [unhealthy] (after 3 failures
+    /// in a row). When using `WatchdogSec=`, make sure it's longer than the
+    /// polling [`timeout`].
+    ///
+    /// This replaces the callbacks set by [`on_ready`], [`on_alive`] and
+    /// [`on_unhealthy`].
+    ///
+    /// [`sd_notify`]: crate::update_listeners::sd_notify
+    /// [ready]: PollingBuilder::on_ready
+    /// [alive]: PollingBuilder::on_alive
+    /// [unhealthy]: PollingBuilder::on_unhealthy
+    /// [`timeout`]: PollingBuilder::timeout
+    /// [`on_ready`]: PollingBuilder::on_ready
+    /// [`on_alive`]: PollingBuilder::on_alive
+    /// [`on_unhealthy`]: PollingBuilder::on_unhealthy
+    #[cfg(all(unix, feature = "sd-notify"))]
+    pub fn sd_notify(self) -> Self {
+        use crate::update_listeners::sd_notify;
+
+        fn log_error(res: std::io::Result<bool>) {
+            if let Err(err) = res {
+                log::warn!("failed to notify the service manager: {err}");
+            }
+        }
+
+        self.on_ready(|| {
+            log_error(sd_notify::status("polling"));
+            log_error(sd_notify::ready());
+        })
+        .on_alive(|| log_error(sd_notify::watchdog()))
+        .on_unhealthy(3, |errors| {
+            log_error(sd_notify::status(&format!("getting updates failed {errors} times")))
+        })
+    }
+
+    /// Persists the id of the last processed update to `storage`, so that
+    /// updates processed before a restart aren't received again.
+    ///
+    /// The listener starts after the saved update id. See [`OffsetStorage`]
+    /// for details. Errors of the storage are logged.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use teloxide::{
+    ///     prelude::*,
+    ///     update_listeners::{offset_storage::FileOffsetStorage, Polling},
+    /// };
+    ///
+    /// # async fn run(bot: Bot, handler: teloxide::dispatching::UpdateHandler<teloxide::RequestError>) {
+    /// let listener = Polling::builder(bot.clone())
+    ///     .offset_storage(FileOffsetStorage::new("offset.txt"))
+    ///     .build();
+    ///
+    /// Dispatcher::builder(bot, handler)
+    ///     .build()
+    ///     .dispatch_with_listener(listener, LoggingErrorHandler::new())
+    ///     .await;
+    /// # }
+    /// ```
+    pub fn offset_storage<S>(self, storage: Arc<S>) -> Self
+    where
+        S: OffsetStorage + Send + Sync + 'static,
+        S::Error: std::error::Error + Send + Sync + 'static,
+    {
+        Self { offset_storage: Some(storage.erase()), ..self }
+    }
+
     /// Deletes webhook if it was set up.
     pub async fn delete_webhook(self) -> Self {
         delete_webhook_if_setup(&self.bot).await;
@@ -111,8 +386,17 @@ where
     ///
     /// See also: [`polling_default`], [`Polling`].
     pub fn build(self) -> Polling<R> {
-        let Self { bot, timeout, limit, allowed_updates, drop_pending_updates, backoff_strategy } =
-            self;
+        let Self {
+            bot,
+            timeout,
+            limit,
+            allowed_updates,
+            drop_pending_updates,
+            delete_webhook_first,
+            backoff_strategy,
+            hooks,
+            offset_storage,
+        } = self;
         let (token, flag) = mk_stop_token();
         let polling = Polling {
             bot,
@@ -120,10 +404,15 @@ where
             limit,
             allowed_updates,
             drop_pending_updates,
+            delete_webhook_first,
             flag: Some(flag),
             token,
             stop_token_cloned: false,
             backoff_strategy,
+            hooks,
+            offset_storage,
+            processed: None,
+            state: PollingState::default(),
         };
 
         assert_update_listener(polling)
@@ -247,10 +536,110 @@ pub struct Polling<B: Requester> {
     limit: Option<u8>,
     allowed_updates: Option<Vec<AllowedUpdate>>,
     drop_pending_updates: bool,
+    delete_webhook_first: bool,
     flag: Option<StopFlag>,
     token: StopToken,
     stop_token_cloned: bool,
     backoff_strategy: BackoffStrategy,
+    hooks: PollingHooks,
+    offset_storage: Option<Arc<ErasedOffsetStorage>>,
+    // Set once the dispatcher asks for `UpdateListener::processed_callback`.
+    processed: Option<Arc<ProcessedUpdates>>,
+    state: PollingState,
+}
+
+/// The progress of a [`Polling`] listener, which can be used to monitor the
+/// lag of update processing.
+///
+/// This is a handle returned by [`Polling::state`], which stays up to date
+/// after the listener is passed to a dispatcher. Clones share the same state.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use teloxide::{prelude::*, update_listeners::Polling};
+///
+/// # async fn run(bot: Bot, handler: teloxide::dispatching::UpdateHandler<teloxide::RequestError>) {
+/// let listener = Polling::builder(bot.clone()).build();
+/// let state = listener.state();
+///
+/// tokio::spawn(async move {
+///     loop {
+///         tokio::time::sleep(Duration::from_secs(60)).await;
+///         if state.last_poll().map_or(true, |at| at.elapsed() > Duration::from_secs(120)) {
+///             log::error!("no successful polls for 2 minutes");
+///         }
+///     }
+/// });
+///
+/// Dispatcher::builder(bot, handler)
+///     .build()
+///     .dispatch_with_listener(listener, LoggingErrorHandler::new())
+///     .await;
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct PollingState {
+    inner: Arc<Mutex<PollingStateInner>>,
+}
+
+#[derive(Debug, Default)]
+struct PollingStateInner {
+    last_confirmed: Option<UpdateId>,
+    last_poll: Option<Instant>,
+    buffered: usize,
+    backlogged: bool,
+}
+
+impl PollingState {
+    /// Returns the id of the last update confirmed to Telegram.
+    ///
+    /// Updates are confirmed by the next `get_updates` call after they were
+    /// received, so Telegram won't return them again.
+    #[must_use]
+    pub fn last_confirmed_update_id(&self) -> Option<UpdateId> {
+        self.inner.lock().unwrap().last_confirmed
+    }
+
+    /// Returns the time of the last successful `get_updates` call.
+    #[must_use]
+    pub fn last_poll(&self) -> Option<Instant> {
+        self.inner.lock().unwrap().last_poll
+    }
+
+    /// Returns the number of updates which were received from Telegram, but
+    /// not yet passed to the consumer of the listener (e.g. a dispatcher).
+    #[must_use]
+    pub fn buffered_updates(&self) -> usize {
+        self.inner.lock().unwrap().buffered
+    }
+
+    /// Returns `true` if the last `get_updates` call returned as many updates
+    /// as it could, i.e. Telegram likely has more pending updates.
+    ///
+    /// The exact number of pending updates can be checked with
+    /// [`get_webhook_info`], which works when polling as well.
+    ///
+    /// [`get_webhook_info`]: crate::requests::Requester::get_webhook_info
+    #[must_use]
+    pub fn is_backlogged(&self) -> bool {
+        self.inner.lock().unwrap().backlogged
+    }
+
+    /// Records a successful `get_updates` call with `offset`, which returned
+    /// `buffered` updates to be yielded.
+    fn polled(&self, offset: i32, buffered: usize, backlogged: bool) {
+        let mut state = self.inner.lock().unwrap();
+        // Negative offsets are used to drop pending updates
+        if offset > 0 {
+            state.last_confirmed = Some(UpdateId(offset as u32 - 1));
+        }
+        state.last_poll = Some(Instant::now());
+        state.buffered = buffered;
+        state.backlogged = backlogged;
+    }
 }
 
 impl<R> Polling<R>
@@ -269,10 +658,20 @@ where
             limit: None,
             allowed_updates: None,
             drop_pending_updates: false,
+            delete_webhook_first: false,
             backoff_strategy: Box::new(exponential_backoff_strategy),
+            hooks: PollingHooks::default(),
+            offset_storage: None,
         }
     }
 
+    /// Returns a handle to the progress of this listener, see
+    /// [`PollingState`].
+    #[must_use]
+    pub fn state(&self) -> PollingState {
+        self.state.clone()
+    }
+
     /// Returns true if re-initialization happened *and*
     /// the previous token was cloned.
     fn reinit_stop_flag_if_needed(&mut self) -> bool {
@@ -287,7 +686,7 @@ where
     }
 }
 
-#[pin_project::pin_project]
+#[pin_project::pin_project(project = PollingStreamProj)]
 pub struct PollingStream<'a, B: Requester> {
     /// Parent structure
     polling: &'a mut Polling<B>,
@@ -301,6 +700,8 @@ pub struct PollingStream<'a, B: Requester> {
     allowed_updates: Option<Vec<AllowedUpdate>>,
     /// Offset parameter  for normal `get_updates()` calls.
     offset: i32,
+    /// Offset parameter of the in-flight `get_updates()` call.
+    requested_offset: i32,
 
     /// If this is set, return `None` from `poll_next` immediately.
     force_stop: bool,
@@ -314,6 +715,10 @@ pub struct PollingStream<'a, B: Requester> {
     #[pin]
     in_flight: Option<<B::GetUpdates as Request>::Send>,
 
+    /// `delete_webhook()` call made before the first `get_updates()` call.
+    #[pin]
+    deleting_webhook: Option<<B::DeleteWebhook as Request>::Send>,
+
     /// The flag that notifies polling to stop polling.
     #[pin]
     flag: StopFlag,
@@ -325,8 +730,22 @@ pub struct PollingStream<'a, B: Requester> {
     /// Counter for network errors occured during the current series of
     /// reconnections
     error_count: u32,
+
+    /// Whether `on_ready` should be called after the next successful
+    /// `get_updates()` call.
+    not_ready: bool,
+
+    /// Loading of the update id from the offset storage, before the first
+    /// `get_updates()` call.
+    loading: Option<BoxFuture<'static, Result<Option<UpdateId>, OffsetStorageError>>>,
+    /// In-flight saving of an update id to the offset storage.
+    saving: Option<BoxFuture<'static, Result<(), OffsetStorageError>>>,
+    /// The id of the last yielded update, if it's not yet being saved.
+    unsaved: Option<UpdateId>,
 }
 
+type OffsetStorageError = Box<dyn std::error::Error + Send + Sync>;
+
 impl<B: Requester + Send + 'static> UpdateListener for Polling<B> {
     type Err = B::Err;
 
@@ -341,6 +760,81 @@ impl<B: Requester + Send + 'static> UpdateListener for Polling<B> {
         // before
         self.allowed_updates = Some(hint.collect());
     }
+
+    fn processed_callback(&mut self) -> Option<ProcessedCallback> {
+        let storage = self.offset_storage.as_ref()?;
+        let processed = self
+            .processed
+            .get_or_insert_with(|| Arc::new(ProcessedUpdates::new(Arc::clone(storage))));
+
+        let processed = Arc::clone(processed);
+        Some(Arc::new(move |id| Box::pin(Arc::clone(&processed).processed(id))))
+    }
+}
+
+/// Saves ids of updates to an offset storage once they are processed.
+///
+/// Updates are processed concurrently, so an id is saved only once all updates
+/// before it are processed too.
+struct ProcessedUpdates {
+    storage: Arc<ErasedOffsetStorage>,
+    pending: Mutex<PendingUpdates>,
+    // The last saved update id, locked while saving
+    saved: tokio::sync::Mutex<Option<UpdateId>>,
+}
+
+#[derive(Default)]
+struct PendingUpdates {
+    /// Updates which were yielded, but aren't processed yet.
+    in_flight: BTreeSet<UpdateId>,
+    /// Processed updates, which can't be saved until updates before them are
+    /// processed.
+    processed: BTreeSet<UpdateId>,
+    /// The last update id which can be saved.
+    committed: Option<UpdateId>,
+}
+
+impl ProcessedUpdates {
+    fn new(storage: Arc<ErasedOffsetStorage>) -> Self {
+        Self { storage, pending: Mutex::default(), saved: tokio::sync::Mutex::new(None) }
+    }
+
+    /// Marks `id` as passed to the dispatcher.
+    fn yielded(&self, id: UpdateId) {
+        self.pending.lock().unwrap().in_flight.insert(id);
+    }
+
+    /// Marks `id` as processed and saves the last update id, before which all
+    /// updates are processed.
+    async fn processed(self: Arc<Self>, id: UpdateId) {
+        {
+            let mut pending = self.pending.lock().unwrap();
+            // The update was already processed, or wasn't yielded by this listener
+            if !pending.in_flight.remove(&id) {
+                return;
+            }
+            pending.processed.insert(id);
+
+            let committed = match pending.in_flight.first() {
+                Some(&first) => pending.processed.range(..first).next_back().copied(),
+                None => pending.processed.last().copied(),
+            };
+            if let Some(committed) = committed {
+                pending.processed.retain(|&id| id > committed);
+                pending.committed = Some(committed);
+            }
+        }
+
+        let mut saved = self.saved.lock().await;
+        // Another update may have been committed while waiting for the lock
+        let committed = self.pending.lock().unwrap().committed;
+        let Some(committed) = committed.filter(|&id| Some(id) > *saved) else { return };
+
+        match Arc::clone(&self.storage).save(committed).await {
+            Ok(()) => *saved = Some(committed),
+            Err(err) => log::error!("failed to save the update offset: {err}"),
+        }
+    }
 }
 
 impl<'a, B: Requester + Send + 'a> AsUpdateStream<'a> for Polling<B> {
@@ -366,19 +860,93 @@ impl<'a, B: Requester + Send + 'a> AsUpdateStream<'a> for Polling<B> {
 
         // Unwrap: just called reinit
         let flag = self.flag.take().unwrap();
+        let loading = self.offset_storage.as_ref().map(|storage| Arc::clone(storage).load());
+        let deleting_webhook = self.delete_webhook_first.then(|| self.bot.delete_webhook().send());
         PollingStream {
             polling: self,
             drop_pending_updates,
             timeout,
             allowed_updates,
             offset: 0,
+            requested_offset: 0,
             force_stop: false,
             stopping: false,
             buffer: Vec::new().into_iter(),
             in_flight: None,
+            deleting_webhook,
             flag,
             eepy: None,
             error_count: 0,
+            not_ready: true,
+            loading,
+            saving: None,
+            unsaved: None,
+        }
+    }
+}
+
+impl<B: Requester> PollingStreamProj<'_, '_, B> {
+    /// Handles updates returned by a successful `get_updates()` call.
+    fn received(&mut self, updates: Vec<Update>) {
+        // Once we got the update the backoff reconnection strategy worked
+        *self.error_count = 0;
+
+        let hooks = &self.polling.hooks;
+        if mem::replace(self.not_ready, false) {
+            if let Some(on_ready) = &hooks.on_ready {
+                on_ready();
+            }
+        }
+        if let Some(on_alive) = &hooks.on_alive {
+            on_alive();
+        }
+
+        if let Some(upd) = updates.last() {
+            *self.offset = upd.id.as_offset();
+        }
+
+        let buffered = if *self.drop_pending_updates { 0 } else { updates.len() };
+        let limit = usize::from(self.polling.limit.unwrap_or(100));
+        self.polling.state.polled(*self.requested_offset, buffered, buffered >= limit);
+
+        match *self.drop_pending_updates {
+            false => *self.buffer = updates.into_iter(),
+            true => *self.drop_pending_updates = false,
+        }
+    }
+
+    /// Yields the next buffered update, if any.
+    fn next_buffered(&mut self, cx: &mut task::Context<'_>) -> Option<Update> {
+        let upd = self.buffer.next()?;
+        self.polling.state.inner.lock().unwrap().buffered = self.buffer.len();
+
+        if let Some(processed) = &self.polling.processed {
+            processed.yielded(upd.id);
+        } else if self.polling.offset_storage.is_some() {
+            *self.unsaved = Some(upd.id);
+            // Start saving right away, if the previous save has completed
+            let _ = self.poll_save(cx);
+        }
+
+        Some(upd)
+    }
+
+    /// Saves the id of the last yielded update to the offset storage, one
+    /// save at a time. Returns `Ready` when everything is saved.
+    fn poll_save(&mut self, cx: &mut task::Context<'_>) -> Poll<()> {
+        loop {
+            if let Some(saving) = self.saving.as_mut() {
+                let res = ready!(saving.as_mut().poll(cx));
+                *self.saving = None;
+                if let Err(err) = res {
+                    log::error!("failed to save the update offset: {err}");
+                }
+            }
+
+            match (self.unsaved.take(), &self.polling.offset_storage) {
+                (Some(id), Some(storage)) => *self.saving = Some(Arc::clone(storage).save(id)),
+                _ => return Ready(()),
+            }
         }
     }
 }
@@ -391,11 +959,38 @@ impl<B: Requester> Stream for PollingStream<'_, B> {
         let mut this = self.as_mut().project();
 
         if *this.force_stop {
+            // Make sure the last update id is saved before stopping
+            ready!(this.poll_save(cx));
             return Ready(None);
         }
 
+        // Keep saving update ids in the background
+        let _ = this.poll_save(cx);
+
+        if let Some(deleting) = this.deleting_webhook.as_mut().as_pin_mut() {
+            let res = ready!(deleting.poll(cx));
+            this.deleting_webhook.set(None);
+            if let Err(err) = res {
+                log::error!("Failed to delete a webhook: {err:?}");
+            }
+        }
+
+        // Start after the saved update id
+        if let Some(loading) = this.loading.as_mut() {
+            let res = ready!(loading.as_mut().poll(cx));
+            *this.loading = None;
+            match res {
+                Ok(Some(id)) => {
+                    log::debug!("starting after the saved update id {}", id.0);
+                    *this.offset = id.as_offset();
+                }
+                Ok(None) => {}
+                Err(err) => log::error!("failed to load the update offset: {err}"),
+            }
+        }
+
         // If there are any buffered updates, return one
-        if let Some(upd) = this.buffer.next() {
+        if let Some(upd) = this.next_buffered(cx) {
             return Ready(Some(Ok(upd)));
         }
 
@@ -404,11 +999,24 @@ impl<B: Requester> Stream for PollingStream<'_, B> {
         //
         // N.B.: it's important to use `poll` and not `is_stopped` here,
         //       so that *this stream* is polled when the flag is set to stop
-        if !*this.stopping && matches!(this.flag.poll(cx), Poll::Ready(())) {
+        if !*this.stopping && matches!(this.flag.as_mut().poll(cx), Poll::Ready(())) {
             *this.stopping = true;
 
+            // ...unless the request has already completed, in which case its updates are
+            // yielded and then confirmed by the graceful shutdown call
+            if let Some(in_flight) = this.in_flight.as_mut().as_pin_mut() {
+                if let Poll::Ready(Ok(updates)) = in_flight.poll(cx) {
+                    log::trace!("in-flight request completed before stopping");
+                    this.received(updates);
+                }
+            }
+
             log::trace!("dropping in-flight request");
             this.in_flight.set(None);
+
+            if let Some(upd) = this.next_buffered(cx) {
+                return Ready(Some(Ok(upd)));
+            }
         }
         // Poll in-flight future until completion
         else if let Some(in_flight) = this.in_flight.as_mut().as_pin_mut() {
@@ -417,26 +1025,20 @@ impl<B: Requester> Stream for PollingStream<'_, B> {
             this.in_flight.set(None);
 
             match res {
-                Ok(_) if *this.stopping => return Ready(None),
+                Ok(_) if *this.stopping => {
+                    this.polling.state.polled(*this.requested_offset, 0, false);
+
+                    *this.force_stop = true;
+                    ready!(this.poll_save(cx));
+                    return Ready(None);
+                }
                 Err(err) if *this.stopping => {
                     // Prevents infinite retries, see https://github.com/teloxide/teloxide/issues/496
                     *this.force_stop = true;
 
                     return Ready(Some(Err(err)));
                 }
-                Ok(updates) => {
-                    // Once we got the update the backoff reconnection strategy worked
-                    *this.error_count = 0;
-
-                    if let Some(upd) = updates.last() {
-                        *this.offset = upd.id.as_offset();
-                    }
-
-                    match *this.drop_pending_updates {
-                        false => *this.buffer = updates.into_iter(),
-                        true => *this.drop_pending_updates = false,
-                    }
-                }
+                Ok(updates) => this.received(updates),
                 Err(err) => {
                     /*
                        In case of RetryAfter(..) error we pause the polling for the specified amount
@@ -454,9 +1056,20 @@ impl<B: Requester> Stream for PollingStream<'_, B> {
                             let delay = (this.polling.backoff_strategy)(*this.error_count);
                             *this.error_count = this.error_count.saturating_add(1);
                             log::trace!("current error count: {}", *this.error_count);
+
+                            let hooks = &this.polling.hooks;
+                            if *this.error_count == hooks.unhealthy_after {
+                                *this.not_ready = true;
+                                if let Some(on_unhealthy) = &hooks.on_unhealthy {
+                                    on_unhealthy(*this.error_count);
+                                }
+                            }
                             delay
                         }
                     };
+                    if let Some(on_error) = &this.polling.hooks.on_error {
+                        on_error(*this.error_count, delay);
+                    }
                     log::info!("retrying getting updates in {}s", delay.as_secs());
                     this.eepy.set(Some(sleep(delay)));
 
@@ -487,6 +1100,7 @@ impl<B: Requester> Stream for PollingStream<'_, B> {
             (_, true) => (-1, Some(1), Some(0)),
         };
 
+        *this.requested_offset = offset;
         let req = this
             .polling
             .bot
@@ -519,3 +1133,219 @@ fn polling_is_send() {
 
     fn assert_send(_: &impl Send) {}
 }
+
+#[cfg(test)]
+#[tokio::test]
+async fn unhealthy_hook() {
+    use futures::StreamExt;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    let bot = crate::test_utils::unreachable_bot();
+
+    let reported = Arc::new(AtomicU32::new(0));
+    let errors = Arc::new(Mutex::new(Vec::new()));
+    let mut polling = Polling::builder(bot)
+        .backoff_strategy(|errors| Duration::from_millis(u64::from(errors)))
+        .on_error({
+            let errors = Arc::clone(&errors);
+            move |count, delay| errors.lock().unwrap().push((count, delay))
+        })
+        .on_ready(|| panic!("the listener can't be ready"))
+        .on_unhealthy(2, {
+            let reported = Arc::clone(&reported);
+            move |errors| reported.store(errors, Ordering::Relaxed)
+        })
+        .build();
+
+    let mut stream = std::pin::pin!(polling.as_stream());
+    assert!(stream.next().await.unwrap().is_err());
+    assert_eq!(reported.load(Ordering::Relaxed), 0);
+    assert!(stream.next().await.unwrap().is_err());
+    assert_eq!(reported.load(Ordering::Relaxed), 2);
+    assert_eq!(*errors.lock().unwrap(), [(1, Duration::ZERO), (2, Duration::from_millis(1))]);
+}
+
+/// Starts a server which responds to `get_updates` calls with batches of
+/// updates with given ids, see [`fake_api`].
+///
+/// [`fake_api`]: crate::test_utils::fake_api
+#[cfg(test)]
+async fn fake_get_updates(
+    batches: Vec<Vec<u32>>,
+) -> (url::Url, tokio::sync::mpsc::UnboundedReceiver<serde_json::Value>) {
+    crate::test_utils::fake_api(batches.into_iter().map(|ids| Some(updates_json(ids))).collect())
+        .await
+}
+
+/// Returns the JSON of text messages with given update ids.
+#[cfg(test)]
+fn updates_json(ids: Vec<u32>) -> serde_json::Value {
+    let updates: Vec<_> = ids
+        .into_iter()
+        .map(|id| {
+            serde_json::json!({ "update_id": id, "message": crate::test_utils::message_json(1, "hi") })
+        })
+        .collect();
+    updates.into()
+}
+
+/// Polls `stream` once and waits until it's woken, e.g. when the response to
+/// the in-flight request is received.
+#[cfg(test)]
+async fn poll_until_woken<S: Stream + ?Sized>(stream: Pin<&mut S>) {
+    struct NotifyWaker(tokio::sync::Notify);
+
+    impl futures::task::ArcWake for NotifyWaker {
+        fn wake_by_ref(arc_self: &Arc<Self>) {
+            arc_self.0.notify_one();
+        }
+    }
+
+    let notify = Arc::new(NotifyWaker(tokio::sync::Notify::new()));
+    let waker = futures::task::waker(Arc::clone(&notify));
+    assert!(stream.poll_next(&mut task::Context::from_waker(&waker)).is_pending());
+    notify.0.notified().await;
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn polling_state() {
+    use futures::StreamExt;
+
+    let (url, _) = fake_get_updates(vec![vec![5, 6], vec![7]]).await;
+    let mut polling = Polling::builder(crate::Bot::new("TOKEN").set_api_url(url)).limit(2).build();
+    let state = polling.state();
+    assert_eq!(state.last_poll(), None);
+
+    let mut stream = std::pin::pin!(polling.as_stream());
+    assert_eq!(stream.next().await.unwrap().unwrap().id, UpdateId(5));
+    assert!(state.last_poll().is_some());
+    assert_eq!(state.last_confirmed_update_id(), None);
+    assert_eq!(state.buffered_updates(), 1);
+    assert!(state.is_backlogged());
+
+    assert_eq!(stream.next().await.unwrap().unwrap().id, UpdateId(6));
+    assert_eq!(state.buffered_updates(), 0);
+
+    assert_eq!(stream.next().await.unwrap().unwrap().id, UpdateId(7));
+    assert_eq!(state.last_confirmed_update_id(), Some(UpdateId(6)));
+    assert!(!state.is_backlogged());
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn stop_keeps_completed_batch() {
+    use futures::StreamExt;
+
+    let (url, mut payloads) = fake_get_updates(vec![vec![5], vec![6, 7], vec![]]).await;
+    let mut polling = Polling::builder(crate::Bot::new("TOKEN").set_api_url(url)).build();
+    let token = polling.stop_token();
+
+    let mut stream = std::pin::pin!(polling.as_stream());
+    assert_eq!(stream.next().await.unwrap().unwrap().id, UpdateId(5));
+
+    // Send the next request and let it complete before stopping
+    poll_until_woken(stream.as_mut()).await;
+    poll_until_woken(stream.as_mut()).await;
+    assert_eq!(payloads.recv().await.unwrap()["offset"], 0);
+    assert_eq!(payloads.recv().await.unwrap()["offset"], 6);
+    token.stop();
+
+    assert_eq!(stream.next().await.unwrap().unwrap().id, UpdateId(6));
+    assert_eq!(stream.next().await.unwrap().unwrap().id, UpdateId(7));
+    assert!(stream.next().await.is_none());
+
+    // The batch is confirmed by the graceful shutdown call
+    assert_eq!(payloads.recv().await.unwrap()["offset"], 8);
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn offset_storage() {
+    use futures::StreamExt;
+
+    use crate::update_listeners::offset_storage::FileOffsetStorage;
+
+    let path = std::env::temp_dir().join(format!("teloxide-polling-offset-{}", std::process::id()));
+    let storage = FileOffsetStorage::new(&path);
+    Arc::clone(&storage).save(UpdateId(4)).await.unwrap();
+
+    let (url, mut payloads) = fake_get_updates(vec![vec![5, 6], vec![], vec![]]).await;
+    let mut polling = Polling::builder(crate::Bot::new("TOKEN").set_api_url(url))
+        .offset_storage(Arc::clone(&storage))
+        .build();
+    let token = polling.stop_token();
+
+    // Starts after the saved update id
+    let mut stream = std::pin::pin!(polling.as_stream());
+    assert_eq!(stream.next().await.unwrap().unwrap().id, UpdateId(5));
+    assert_eq!(payloads.recv().await.unwrap()["offset"], 5);
+    assert_eq!(stream.next().await.unwrap().unwrap().id, UpdateId(6));
+
+    poll_until_woken(stream.as_mut()).await;
+    poll_until_woken(stream.as_mut()).await;
+    assert_eq!(payloads.recv().await.unwrap()["offset"], 7);
+    token.stop();
+
+    // The id of the last update is saved before stopping
+    assert!(stream.next().await.is_none());
+    assert_eq!(Arc::clone(&storage).load().await.unwrap(), Some(UpdateId(6)));
+
+    tokio::fs::remove_file(&path).await.unwrap();
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn offset_storage_processed() {
+    use futures::StreamExt;
+
+    use crate::update_listeners::offset_storage::FileOffsetStorage;
+
+    let path = std::env::temp_dir()
+        .join(format!("teloxide-polling-processed-offset-{}", std::process::id()));
+    let storage = FileOffsetStorage::new(&path);
+
+    let (url, _payloads) = fake_get_updates(vec![vec![5, 6, 7], vec![], vec![]]).await;
+    let mut polling = Polling::builder(crate::Bot::new("TOKEN").set_api_url(url))
+        .offset_storage(Arc::clone(&storage))
+        .build();
+    let processed = polling.processed_callback().unwrap();
+
+    let mut stream = std::pin::pin!(polling.as_stream());
+    for id in [5, 6, 7] {
+        assert_eq!(stream.next().await.unwrap().unwrap().id, UpdateId(id));
+    }
+    let saved = || async { Arc::clone(&storage).load().await.unwrap() };
+
+    // Yielded updates aren't saved until they are processed
+    assert_eq!(saved().await, None);
+
+    processed(UpdateId(6)).await;
+    assert_eq!(saved().await, None);
+
+    processed(UpdateId(5)).await;
+    assert_eq!(saved().await, Some(UpdateId(6)));
+
+    // Processing an update again doesn't change anything
+    processed(UpdateId(5)).await;
+    processed(UpdateId(7)).await;
+    assert_eq!(saved().await, Some(UpdateId(7)));
+
+    tokio::fs::remove_file(&path).await.unwrap();
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn delete_webhook_first() {
+    use futures::StreamExt;
+
+    let (url, mut payloads) =
+        crate::test_utils::fake_api(vec![Some(true.into()), Some(updates_json(vec![5]))]).await;
+    let mut polling =
+        Polling::builder(crate::Bot::new("TOKEN").set_api_url(url)).delete_webhook_first().build();
+
+    let mut stream = std::pin::pin!(polling.as_stream());
+    assert_eq!(stream.next().await.unwrap().unwrap().id, UpdateId(5));
+    assert_eq!(payloads.recv().await.unwrap(), serde_json::json!({}));
+    assert_eq!(payloads.recv().await.unwrap()["offset"], 0);
+}
@@ -0,0 +1,505 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::Debug,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use deadpool_redis::{redis, CreatePoolError, PoolError, Runtime};
+use futures::{stream, stream::BoxStream, StreamExt};
+use thiserror::Error;
+
+use crate::{
+    dispatching::shard_of,
+    stop::{mk_stop_token, StopFlag, StopToken},
+    types::{Update, UpdateId},
+    update_listeners::{AsUpdateStream, ProcessedCallback, UpdateListener},
+};
+
+/// The name of the stream entry field that contains the serialized update.
+const UPDATE_FIELD: &str = "update";
+/// The consumer group used by [`RedisQueue::listener`].
+const GROUP: &str = "teloxide";
+/// The maximum number of updates read from Redis at once.
+const BATCH_SIZE: usize = 100;
+/// For how long a single read blocks waiting for new updates.
+const BLOCK_TIMEOUT: Duration = Duration::from_secs(5);
+/// For how long to wait before retrying after a Redis error.
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// An error returned from [`RedisQueue`].
+#[derive(Debug, Error)]
+pub enum RedisQueueError {
+    #[error("parsing/serializing error: {0}")]
+    SerdeError(#[from] serde_json::Error),
+
+    #[error("error from Redis: {0}")]
+    RedisError(#[from] redis::RedisError),
+
+    #[error("error creating redis pool: {0}")]
+    CreatePoolError(#[from] CreatePoolError),
+
+    #[error("redis pool error: {0}")]
+    PoolError(#[from] PoolError),
+}
+
+/// A queue of updates based on [Redis streams].
+///
+/// This allows splitting the work of a bot between multiple processes:
+///
+/// - A single producer receives updates from Telegram (e.g. via a [webhook])
+///   and puts them into the queue with [`RedisQueue::forward`] or
+///   [`RedisQueue::push`].
+/// - Multiple consumers run [`Dispatcher`]s with update listeners returned by
+///   [`RedisQueue::listener`], each one with a different shard number.
+///
+/// Updates are split between [`shards`] based on their chat, the same way
+/// [`shard_filter`] does, so updates from a single chat are always handled by
+/// the same consumer, in order.
+///
+/// Updates are acknowledged once the [`Dispatcher`] processes them (or as soon
+/// as they are received, if the listener is used without a dispatcher). When a
+/// listener is stopped, it first yields all updates it has already received.
+/// If a consumer crashes, updates it didn't process are received again by the
+/// next listener with the same [consumer name], so an update can be handled
+/// twice, but is not lost.
+///
+/// [Redis streams]: https://redis.io/docs/latest/develop/data-types/streams/
+/// [webhook]: crate::update_listeners::webhooks
+/// [`Dispatcher`]: crate::dispatching::Dispatcher
+/// [`shards`]: RedisQueue::shards
+/// [`shard_filter`]: crate::dispatching::shard_filter
+/// [consumer name]: RedisQueue::consumer_name
+///
+/// ## Examples
+///
+/// ```no_run
+/// use teloxide::{prelude::*, update_listeners::queue::RedisQueue};
+///
+/// # async fn run(shard: u32) -> Result<(), Box<dyn std::error::Error>> {
+/// let queue = RedisQueue::open("redis://127.0.0.1:6379", "my_bot:updates").await?.shards(4);
+///
+/// let bot = Bot::from_env();
+/// let handler = Update::filter_message().endpoint(|bot: Bot, msg: Message| async move {
+///     bot.send_message(msg.chat.id, "pong").await?;
+///     respond(())
+/// });
+///
+/// Dispatcher::builder(bot, handler)
+///     .build()
+///     .dispatch_with_listener(
+///         queue.listener(shard),
+///         LoggingErrorHandler::with_custom_text("An error from the update queue"),
+///     )
+///     .await;
+/// # Ok(()) }
+/// ```
+#[derive(Clone)]
+pub struct RedisQueue {
+    pool: deadpool_redis::Pool,
+    key: String,
+    shards: u32,
+    max_len: usize,
+    consumer: String,
+}
+
+impl RedisQueue {
+    /// Connects to Redis at `url`, using streams with names starting with
+    /// `key`.
+    pub async fn open(url: &str, key: impl Into<String>) -> Result<Self, RedisQueueError> {
+        let config = deadpool_redis::Config::from_url(url);
+        let pool = config.create_pool(Some(Runtime::Tokio1))?;
+
+        Ok(Self { pool, key: key.into(), shards: 1, max_len: 10_000, consumer: default_consumer() })
+    }
+
+    /// Sets the number of shards updates are split between.
+    ///
+    /// Each shard is a separate Redis stream, which should be consumed by a
+    /// single [`listener`] at a time. The producer and all consumers must use
+    /// the same number of shards.
+    ///
+    /// By default there is only one shard.
+    ///
+    /// ## Panics
+    ///
+    /// If `shards` is zero.
+    ///
+    /// [`listener`]: RedisQueue::listener
+    #[must_use]
+    pub fn shards(self, shards: u32) -> Self {
+        assert!(shards > 0, "the number of shards must be greater than zero");
+        Self { shards, ..self }
+    }
+
+    /// Sets the approximate maximum number of updates stored in a single shard.
+    ///
+    /// When the limit is reached, the oldest updates are removed, even if they
+    /// were not received by a consumer yet.
+    ///
+    /// By default it's `10000`.
+    #[must_use]
+    pub fn max_len(self, max_len: usize) -> Self {
+        Self { max_len, ..self }
+    }
+
+    /// Sets the name of the consumer used by [`listener`]s to read updates
+    /// from the consumer group.
+    ///
+    /// Redis remembers updates delivered to each consumer, so consumers of
+    /// different processes must have different names. By default the name is
+    /// unique, based on the process id and the time the queue was opened.
+    ///
+    /// Set a name which persists across restarts (e.g. based on the shard
+    /// number), so that updates which weren't processed before a crash are
+    /// received again.
+    ///
+    /// [`listener`]: RedisQueue::listener
+    #[must_use]
+    pub fn consumer_name(self, name: impl Into<String>) -> Self {
+        Self { consumer: name.into(), ..self }
+    }
+
+    /// Puts an update into the queue.
+    pub async fn push(&self, update: &Update) -> Result<(), RedisQueueError> {
+        let key = self.shard_key(shard_of(update, self.shards));
+        let payload = serde_json::to_string(update)?;
+
+        let mut conn = self.pool.get().await?;
+        redis::cmd("XADD")
+            .arg(key)
+            .arg("MAXLEN")
+            .arg("~")
+            .arg(self.max_len)
+            .arg("*")
+            .arg(UPDATE_FIELD)
+            .arg(payload)
+            .query_async::<()>(&mut conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Puts all updates received from `listener` into the queue.
+    ///
+    /// This function returns when `listener` is stopped. Errors are logged and
+    /// otherwise ignored.
+    pub async fn forward<L>(&self, mut listener: L)
+    where
+        L: UpdateListener,
+        L::Err: Debug,
+    {
+        let stream = listener.as_stream();
+        tokio::pin!(stream);
+
+        while let Some(res) = stream.next().await {
+            match res {
+                Ok(update) => {
+                    if let Err(err) = self.push(&update).await {
+                        log::error!("Failed to put an update into the queue: {err}");
+                    }
+                }
+                Err(err) => log::error!("An error from the update listener: {err:?}"),
+            }
+        }
+    }
+
+    /// Returns an update listener that receives updates of the shard `shard`
+    /// from the queue.
+    ///
+    /// ## Panics
+    ///
+    /// If `shard` is not less than the number of [`shards`].
+    ///
+    /// [`shards`]: RedisQueue::shards
+    pub fn listener(&self, shard: u32) -> impl UpdateListener<Err = RedisQueueError> {
+        self.queue_listener(shard)
+    }
+
+    fn queue_listener(&self, shard: u32) -> QueueListener {
+        assert!(
+            shard < self.shards,
+            "shard number ({shard}) must be less than the number of shards ({})",
+            self.shards
+        );
+
+        let key = self.shard_key(shard);
+        let (token, flag) = mk_stop_token();
+        let acks = Arc::new(Acks {
+            pool: self.pool.clone(),
+            key: key.clone(),
+            deferred: AtomicBool::new(false),
+            unacked: Mutex::default(),
+        });
+
+        let consumer = Consumer {
+            pool: self.pool.clone(),
+            key,
+            consumer: self.consumer.clone(),
+            group_created: false,
+            failed: false,
+            history: Some("0".to_owned()),
+            buffered: VecDeque::new(),
+            acks,
+            flag,
+        };
+
+        // FIXME: this should support `hint_allowed_updates()`
+        QueueListener { consumer, token }
+    }
+
+    fn shard_key(&self, shard: u32) -> String {
+        format!("{}:{shard}", self.key)
+    }
+}
+
+/// The listener returned by [`RedisQueue::listener`].
+struct QueueListener {
+    consumer: Consumer,
+    token: StopToken,
+}
+
+impl UpdateListener for QueueListener {
+    type Err = RedisQueueError;
+
+    fn stop_token(&mut self) -> StopToken {
+        self.token.clone()
+    }
+
+    fn processed_callback(&mut self) -> Option<ProcessedCallback> {
+        let acks = Arc::clone(&self.consumer.acks);
+        acks.deferred.store(true, Ordering::Relaxed);
+
+        Some(Arc::new(move |id| Box::pin(Arc::clone(&acks).processed(id))))
+    }
+}
+
+impl<'a> AsUpdateStream<'a> for QueueListener {
+    type StreamErr = RedisQueueError;
+    type Stream = BoxStream<'a, Result<Update, RedisQueueError>>;
+
+    fn as_stream(&'a mut self) -> Self::Stream {
+        Box::pin(stream::unfold(&mut self.consumer, |consumer| async move {
+            let item = consumer.next().await?;
+            Some((item, consumer))
+        }))
+    }
+}
+
+/// State of a [`RedisQueue::listener`].
+struct Consumer {
+    pool: deadpool_redis::Pool,
+    key: String,
+    consumer: String,
+    group_created: bool,
+    failed: bool,
+    /// The id after which entries delivered to this consumer before (e.g.
+    /// before a crash) are read, or `None` once they are all read.
+    history: Option<String>,
+    /// Entries which were read, but not yielded yet.
+    buffered: VecDeque<(String, Update)>,
+    acks: Arc<Acks>,
+    flag: StopFlag,
+}
+
+/// An entry of a stream, fields are `None` if the entry was trimmed.
+type StreamEntry = (String, Option<HashMap<String, String>>);
+
+type StreamReply = Vec<(String, Vec<StreamEntry>)>;
+
+impl Consumer {
+    async fn next(&mut self) -> Option<Result<Update, RedisQueueError>> {
+        loop {
+            if let Some((entry, update)) = self.buffered.pop_front() {
+                if self.acks.deferred.load(Ordering::Relaxed) {
+                    self.acks.unacked.lock().unwrap().insert(update.id, entry);
+                }
+                return Some(Ok(update));
+            }
+
+            // All updates which were read are yielded, so none are lost
+            if self.flag.is_stopped() {
+                return None;
+            }
+
+            if self.failed {
+                tokio::time::sleep(RETRY_DELAY).await;
+            }
+
+            let res = self.read_batch().await;
+            self.failed = res.is_err();
+            if let Err(err) = res {
+                return Some(Err(err));
+            }
+        }
+    }
+
+    async fn read_batch(&mut self) -> Result<(), RedisQueueError> {
+        let mut conn = self.pool.get().await?;
+
+        if !self.group_created {
+            let res = redis::cmd("XGROUP")
+                .arg("CREATE")
+                .arg(&self.key)
+                .arg(GROUP)
+                .arg("0")
+                .arg("MKSTREAM")
+                .query_async::<()>(&mut conn)
+                .await;
+
+            match res {
+                Ok(()) => {}
+                // The group was already created by a previous run
+                Err(err) if err.code() == Some("BUSYGROUP") => {}
+                Err(err) => return Err(err.into()),
+            }
+
+            self.group_created = true;
+        }
+
+        let reply: Option<StreamReply> = redis::cmd("XREADGROUP")
+            .arg("GROUP")
+            .arg(GROUP)
+            .arg(&self.consumer)
+            .arg("COUNT")
+            .arg(BATCH_SIZE)
+            .arg("BLOCK")
+            .arg(BLOCK_TIMEOUT.as_millis() as u64)
+            .arg("STREAMS")
+            .arg(&self.key)
+            .arg(self.history.as_deref().unwrap_or(">"))
+            .query_async(&mut conn)
+            .await?;
+
+        let entries: Vec<_> =
+            reply.into_iter().flatten().flat_map(|(_stream, entries)| entries).collect();
+
+        if self.history.is_some() {
+            self.history = entries.last().map(|(id, _)| id.clone());
+        }
+
+        // Entries which aren't yielded are acknowledged right away, others are
+        // acknowledged once processed, if the dispatcher reports that
+        let deferred = self.acks.deferred.load(Ordering::Relaxed);
+        let mut acked = Vec::new();
+        for (id, fields) in entries {
+            match parse_entry(&id, fields) {
+                Some(update) if deferred => self.buffered.push_back((id, update)),
+                Some(update) => {
+                    self.buffered.push_back((id.clone(), update));
+                    acked.push(id);
+                }
+                None => acked.push(id),
+            }
+        }
+
+        if !acked.is_empty() {
+            redis::cmd("XACK")
+                .arg(&self.key)
+                .arg(GROUP)
+                .arg(&acked)
+                .query_async::<()>(&mut conn)
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the update of the entry `id`, logging why it can't be read.
+fn parse_entry(id: &str, fields: Option<HashMap<String, String>>) -> Option<Update> {
+    let Some(payload) = fields.as_ref().and_then(|fields| fields.get(UPDATE_FIELD)) else {
+        log::warn!("Queue entry {id} doesn't contain an update");
+        return None;
+    };
+
+    serde_json::from_str(payload)
+        .map_err(|err| log::error!("Cannot parse an update from the queue: {err}"))
+        .ok()
+}
+
+/// Acknowledges entries once their updates are processed, see
+/// [`UpdateListener::processed_callback`].
+struct Acks {
+    pool: deadpool_redis::Pool,
+    key: String,
+    /// Whether the dispatcher reports processed updates, otherwise entries are
+    /// acknowledged as soon as they are read.
+    deferred: AtomicBool,
+    /// Ids of entries of yielded updates, which aren't processed yet.
+    unacked: Mutex<HashMap<UpdateId, String>>,
+}
+
+impl Acks {
+    async fn processed(self: Arc<Self>, id: UpdateId) {
+        let Some(entry) = self.unacked.lock().unwrap().remove(&id) else { return };
+
+        if let Err(err) = self.ack(&entry).await {
+            log::error!("Failed to acknowledge queue entry {entry}: {err}");
+        }
+    }
+
+    async fn ack(&self, entry: &str) -> Result<(), RedisQueueError> {
+        let mut conn = self.pool.get().await?;
+        redis::cmd("XACK")
+            .arg(&self.key)
+            .arg(GROUP)
+            .arg(entry)
+            .query_async::<()>(&mut conn)
+            .await?;
+        Ok(())
+    }
+}
+
+/// Returns a consumer name which is unique for the current process.
+fn default_consumer() -> String {
+    let started = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    format!("{GROUP}-{}-{}", std::process::id(), started.as_nanos())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{message_json, update};
+
+    #[test]
+    fn update_survives_serialization() {
+        let update = update(1, "message", message_json(4, "hi"));
+
+        let payload = serde_json::to_string(&update).unwrap();
+        assert_eq!(serde_json::from_str::<Update>(&payload).unwrap(), update);
+    }
+
+    #[test]
+    fn consumer_name_is_not_the_group() {
+        assert!(default_consumer().starts_with(&format!("{GROUP}-{}-", std::process::id())));
+        assert_ne!(default_consumer(), GROUP);
+    }
+
+    #[tokio::test]
+    async fn stop_yields_received_updates() {
+        // Nothing listens on the port, so reading from Redis would fail
+        let queue = RedisQueue::open("redis://127.0.0.1:1", "test").await.unwrap();
+        let mut listener = queue.queue_listener(0);
+        assert!(listener.processed_callback().is_some());
+
+        let updates = [1, 2].map(|id| update(id, "message", message_json(1, "hi")));
+        for (i, update) in updates.iter().enumerate() {
+            listener.consumer.buffered.push_back((format!("{i}-0"), update.clone()));
+        }
+        listener.stop_token().stop();
+
+        let received: Vec<_> = listener.as_stream().map(Result::unwrap).collect().await;
+        assert_eq!(received, updates);
+
+        // Entries are acknowledged only once processed
+        let unacked = listener.consumer.acks.unacked.lock().unwrap().clone();
+        assert_eq!(
+            unacked,
+            HashMap::from([(UpdateId(1), "0-0".to_owned()), (UpdateId(2), "1-0".to_owned())])
+        );
+    }
+}
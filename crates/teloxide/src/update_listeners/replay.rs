@@ -0,0 +1,218 @@
+use std::{io, path::PathBuf};
+
+use chrono::{DateTime, Utc};
+use futures::stream;
+use thiserror::Error;
+use tokio::{
+    fs::File,
+    io::{AsyncBufReadExt, BufReader, Lines},
+};
+
+use crate::{
+    types::{Update, UpdateKind},
+    update_listeners::{StatefulListener, UpdateListener},
+};
+
+/// An error returned from the listener built by [`from_file`].
+#[derive(Debug, Error)]
+pub enum ReplayError {
+    #[error("error reading the file: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("invalid update on line {line}: {source}")]
+    Parse { line: usize, source: serde_json::Error },
+}
+
+/// The pace at which [`from_file`] replays updates.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Pace {
+    /// Replay updates one after another, without delays.
+    #[default]
+    Instant,
+
+    /// Replay updates with the same delays between them as when they were
+    /// sent, according to their dates.
+    Original,
+
+    /// Like [`Pace::Original`], but with delays shortened by the given factor
+    /// (e.g. `10.0` replays updates 10 times faster).
+    Accelerated(f64),
+}
+
+impl Pace {
+    fn speed(self) -> Option<f64> {
+        match self {
+            Pace::Instant => None,
+            Pace::Original => Some(1.0),
+            Pace::Accelerated(factor) => Some(factor),
+        }
+    }
+}
+
+/// Returns a builder of a listener which replays updates from a file.
+///
+/// The file must contain one JSON-serialized [`Update`] per line, empty lines
+/// are ignored. The file is read line by line, so it can be arbitrarily
+/// large. This is useful to reproduce bugs in handlers offline, with
+/// updates recorded from a running bot.
+///
+/// The listener stops when all updates are replayed. Lines that aren't valid
+/// updates are reported as errors and skipped.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use teloxide::{
+///     prelude::*,
+///     update_listeners::{self, Pace},
+/// };
+///
+/// # async fn run() {
+/// let bot = Bot::from_env();
+/// let listener =
+///     update_listeners::from_file("updates.jsonl").pace(Pace::Accelerated(10.0)).build();
+///
+/// Dispatcher::builder(bot, Update::filter_message().endpoint(|| async { respond(()) }))
+///     .build()
+///     .dispatch_with_listener(listener, LoggingErrorHandler::new())
+///     .await;
+/// # }
+/// ```
+pub fn from_file<P>(path: P) -> ReplayBuilder
+where
+    P: Into<PathBuf>,
+{
+    ReplayBuilder { path: path.into(), pace: Pace::default() }
+}
+
+/// A builder of a listener which replays updates from a file, see
+/// [`from_file`].
+#[derive(Clone, Debug)]
+#[must_use]
+pub struct ReplayBuilder {
+    path: PathBuf,
+    pace: Pace,
+}
+
+impl ReplayBuilder {
+    /// Sets the pace at which updates are replayed.
+    ///
+    /// By default, it's [`Pace::Instant`].
+    ///
+    /// Delays are computed from the dates of updates. Updates without a date
+    /// (e.g. callback queries) are replayed right after the previous one.
+    ///
+    /// ## Panics
+    ///
+    /// If the factor of [`Pace::Accelerated`] isn't positive.
+    #[track_caller]
+    pub fn pace(self, pace: Pace) -> Self {
+        if let Pace::Accelerated(factor) = pace {
+            assert!(factor > 0.0, "the acceleration factor must be positive");
+        }
+        Self { pace, ..self }
+    }
+
+    /// Creates the listener.
+    pub fn build(self) -> impl UpdateListener<Err = ReplayError> {
+        let Self { path, pace } = self;
+
+        let stream = stream::unfold(Replay::Unopened(path), move |replay| async move {
+            let (mut lines, mut line, mut prev_date) = match replay {
+                Replay::Unopened(path) => match File::open(path).await {
+                    Ok(file) => (Box::new(BufReader::new(file).lines()), 0, None),
+                    Err(err) => return Some((Err(err.into()), Replay::Finished)),
+                },
+                Replay::Reading { lines, line, prev_date } => (lines, line, prev_date),
+                Replay::Finished => return None,
+            };
+
+            let update = loop {
+                line += 1;
+                match lines.next_line().await {
+                    Ok(Some(text)) if text.trim().is_empty() => {}
+                    Ok(Some(text)) => {
+                        break serde_json::from_str(&text)
+                            .map_err(|source| ReplayError::Parse { line, source })
+                    }
+                    Ok(None) => return None,
+                    Err(err) => return Some((Err(err.into()), Replay::Finished)),
+                }
+            };
+
+            if let (Some(speed), Some(date)) =
+                (pace.speed(), update.as_ref().ok().and_then(date_of))
+            {
+                if let Some(delay) = prev_date.and_then(|prev| (date - prev).to_std().ok()) {
+                    tokio::time::sleep(delay.div_f64(speed)).await;
+                }
+                prev_date = Some(date);
+            }
+
+            Some((update, Replay::Reading { lines, line, prev_date }))
+        });
+
+        StatefulListener::from_stream(Box::pin(stream))
+    }
+}
+
+/// State of the listener built by [`ReplayBuilder::build`].
+enum Replay {
+    Unopened(PathBuf),
+    Reading { lines: Box<Lines<BufReader<File>>>, line: usize, prev_date: Option<DateTime<Utc>> },
+    Finished,
+}
+
+/// Returns the date of `update`, if it has one.
+pub(crate) fn date_of(update: &Update) -> Option<DateTime<Utc>> {
+    match &update.kind {
+        UpdateKind::Message(message)
+        | UpdateKind::EditedMessage(message)
+        | UpdateKind::ChannelPost(message)
+        | UpdateKind::EditedChannelPost(message)
+        | UpdateKind::BusinessMessage(message)
+        | UpdateKind::EditedBusinessMessage(message) => Some(message.date),
+        UpdateKind::MessageReaction(reaction) => Some(reaction.date),
+        UpdateKind::MessageReactionCount(reaction) => Some(reaction.date),
+        UpdateKind::MyChatMember(member) | UpdateKind::ChatMember(member) => Some(member.date),
+        UpdateKind::ChatJoinRequest(request) => Some(request.date),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use serde_json::json;
+
+    use super::*;
+    use crate::{test_utils::message_json, types::UpdateId, update_listeners::AsUpdateStream};
+
+    #[tokio::test]
+    async fn replay() {
+        let update = |id: u32, date: i64| {
+            let mut msg = message_json(1, "hi");
+            msg["message_id"] = id.into();
+            msg["date"] = date.into();
+            json!({ "update_id": id, "message": msg }).to_string()
+        };
+        let contents = [update(1, 1000), String::new(), "{".to_owned(), update(2, 1001)].join("\n");
+
+        let path =
+            std::env::temp_dir().join(format!("teloxide-replay-{}.jsonl", std::process::id()));
+        tokio::fs::write(&path, contents).await.unwrap();
+
+        let mut listener = from_file(&path).pace(Pace::Accelerated(1000.0)).build();
+        let results: Vec<_> = listener.as_stream().collect().await;
+        tokio::fs::remove_file(&path).await.unwrap();
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_ref().unwrap().id, UpdateId(1));
+        assert!(matches!(results[1], Err(ReplayError::Parse { line: 3, .. })));
+        assert_eq!(results[2].as_ref().unwrap().id, UpdateId(2));
+
+        let mut listener = from_file(&path).build();
+        let results: Vec<_> = listener.as_stream().collect().await;
+        assert!(matches!(&*results, [Err(ReplayError::Io(_))]));
+    }
+}
@@ -0,0 +1,84 @@
+use std::{env, io, os::unix::net::UnixDatagram};
+
+/// Sends `state` (e.g. `READY=1`) to the service manager.
+///
+/// Returns `Ok(false)` if the process isn't run by a service manager that
+/// supports notifications, i.e. if the `NOTIFY_SOCKET` environment variable
+/// isn't set.
+///
+/// See [`sd_notify(3)`] for the list of supported states.
+///
+/// [`sd_notify(3)`]: https://www.freedesktop.org/software/systemd/man/latest/sd_notify.html
+pub fn notify(state: &str) -> io::Result<bool> {
+    let Some(path) = env::var_os("NOTIFY_SOCKET") else {
+        return Ok(false);
+    };
+
+    let socket = UnixDatagram::unbound()?;
+    let bytes = path.as_encoded_bytes();
+
+    match bytes.strip_prefix(b"@") {
+        #[cfg(target_os = "linux")]
+        Some(name) => {
+            use std::os::{linux::net::SocketAddrExt, unix::net::SocketAddr};
+
+            let addr = SocketAddr::from_abstract_name(name)?;
+            socket.send_to_addr(state.as_bytes(), &addr)?;
+        }
+        #[cfg(not(target_os = "linux"))]
+        Some(_) => {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "abstract notification sockets are only supported on Linux",
+            ))
+        }
+        None => {
+            socket.send_to(state.as_bytes(), path)?;
+        }
+    }
+
+    Ok(true)
+}
+
+/// Tells the service manager that the bot has started up.
+pub fn ready() -> io::Result<bool> {
+    notify("READY=1")
+}
+
+/// Tells the service manager that the bot is alive, resetting the watchdog
+/// timer (see `WatchdogSec=` in [`systemd.service(5)`]).
+///
+/// [`systemd.service(5)`]: https://www.freedesktop.org/software/systemd/man/latest/systemd.service.html
+pub fn watchdog() -> io::Result<bool> {
+    notify("WATCHDOG=1")
+}
+
+/// Sets the status of the bot shown by the service manager (e.g. in
+/// `systemctl status`).
+pub fn status(status: &str) -> io::Result<bool> {
+    notify(&format!("STATUS={status}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn notify_socket() {
+        let path = env::temp_dir().join(format!("teloxide-sd-notify-{}", std::process::id()));
+        let receiver = UnixDatagram::bind(&path).unwrap();
+
+        // This is the only test that touches `NOTIFY_SOCKET`
+        env::set_var("NOTIFY_SOCKET", &path);
+        let sent = ready();
+        env::remove_var("NOTIFY_SOCKET");
+
+        assert!(sent.unwrap());
+        let mut buf = [0; 16];
+        let len = receiver.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..len], b"READY=1");
+        assert!(!ready().unwrap());
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -1,7 +1,7 @@
-use futures::Stream;
+use futures::{stream::TakeUntil, Stream, StreamExt as _};
 
 use crate::{
-    stop::StopToken,
+    stop::{mk_stop_token, StopFlag, StopToken},
     types::{AllowedUpdate, Update},
     update_listeners::{AsUpdateStream, UpdateListener},
 };
@@ -45,6 +45,66 @@ impl<St, Assf, Sf> StatefulListener<St, Assf, Sf, Haufn<St>> {
     }
 }
 
+type FromStreamState<S> = (TakeUntil<S, StopFlag>, StopToken);
+
+impl<S, E>
+    StatefulListener<
+        FromStreamState<S>,
+        for<'a> fn(&'a mut FromStreamState<S>) -> &'a mut TakeUntil<S, StopFlag>,
+        fn(&mut FromStreamState<S>) -> StopToken,
+        Haufn<FromStreamState<S>>,
+    >
+where
+    S: Stream<Item = Result<Update, E>> + Unpin,
+{
+    /// Creates a new stateful listener from a stream of updates.
+    ///
+    /// This is useful for feeding updates from arbitrary sources (e.g. a
+    /// message queue) to the [`Dispatcher`]. When the listener is
+    /// [stopped], the stream ends, without waiting for its next item.
+    ///
+    /// Use [`StatefulListener::new_with_hints`] if you need more control, e.g.
+    /// to handle [allowed updates hints].
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use std::convert::Infallible;
+    ///
+    /// use teloxide::{types::Update, update_listeners::StatefulListener};
+    /// use tokio::sync::mpsc;
+    /// use tokio_stream::wrappers::UnboundedReceiverStream;
+    ///
+    /// let (tx, rx) = mpsc::unbounded_channel::<Result<Update, Infallible>>();
+    /// let listener = StatefulListener::from_stream(UnboundedReceiverStream::new(rx));
+    /// // Send updates via `tx` and pass `listener` to the dispatcher...
+    /// # drop((tx, listener));
+    /// ```
+    ///
+    /// [`Dispatcher`]: crate::dispatching::Dispatcher
+    /// [stopped]: crate::stop::StopToken::stop
+    /// [allowed updates hints]: UpdateListener::hint_allowed_updates
+    pub fn from_stream(stream: S) -> Self {
+        fn stream_of<S>(state: &mut FromStreamState<S>) -> &mut TakeUntil<S, StopFlag>
+        where
+            S: Stream,
+        {
+            &mut state.0
+        }
+
+        fn stop_token_of<S>(state: &mut FromStreamState<S>) -> StopToken
+        where
+            S: Stream,
+        {
+            state.1.clone()
+        }
+
+        let (stop_token, stop_flag) = mk_stop_token();
+
+        Self::new((stream.take_until(stop_flag), stop_token), stream_of, stop_token_of)
+    }
+}
+
 impl<St, Assf, Sf, Hauf> StatefulListener<St, Assf, Sf, Hauf> {
     /// Creates a new stateful listener from its components.
     pub fn new_with_hints(
@@ -1,10 +1,34 @@
 //!
 use std::net::SocketAddr;
 
+use serde::{Deserialize, Serialize};
+
 use crate::{requests::Requester, types::InputFile};
 
 /// Options related to setting up webhooks.
+///
+/// The options can be loaded from a config file, in which case invalid values
+/// are rejected when deserializing. [`Options::certificate`] can't be loaded,
+/// it needs to be set separately.
+///
+/// ## Examples
+///
+/// ```
+/// use teloxide::update_listeners::webhooks::Options;
+///
+/// let options: Options = serde_json::from_str(
+///     r#"{ "address": "127.0.0.1:8443", "url": "https://example.com/webhook" }"#,
+/// )
+/// .unwrap();
+/// assert_eq!(options.path, "/webhook");
+///
+/// let invalid =
+///     r#"{ "address": "127.0.0.1:8443", "url": "https://example.com", "max_connections": 0 }"#;
+/// assert!(serde_json::from_str::<Options>(invalid).is_err());
+/// ```
 #[must_use]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(try_from = "RawOptions")]
 pub struct Options {
     /// Local address to listen to.
     pub address: SocketAddr,
@@ -36,6 +60,7 @@ pub struct Options {
     /// [self-signed guide]: https://core.telegram.org/bots/self-signed
     ///
     /// Default - None.
+    #[serde(skip_serializing)]
     pub certificate: Option<InputFile>,
 
     /// Maximum allowed number of simultaneous HTTPS connections to the webhook
@@ -131,13 +156,30 @@ impl Options {
     }
 }
 
+pub use self::watchdog::Watchdog;
+
 #[cfg(feature = "webhooks-axum")]
 pub use self::axum::{axum, axum_no_setup, axum_to_router};
 
+#[cfg(feature = "webhooks-warp")]
+pub use self::warp::{warp_no_setup, warp_to_filter};
+
+#[cfg(feature = "webhooks-actix")]
+pub use self::actix::{actix_no_setup, actix_to_config};
+
+mod watchdog;
+
 #[cfg(feature = "webhooks-axum")]
 mod axum;
 
-// TODO: add different implementation (for example: warp)
+#[cfg(feature = "webhooks-warp")]
+mod warp;
+
+#[cfg(feature = "webhooks-actix")]
+mod actix;
+
+#[cfg(any(feature = "webhooks-axum", feature = "webhooks-warp", feature = "webhooks-actix"))]
+mod state;
 
 /// Calls `set_webhook` with arguments from `options`.
 ///
@@ -165,6 +207,39 @@ where
     Ok(())
 }
 
+/// [`Options`], as they are deserialized before validation.
+#[derive(Deserialize)]
+struct RawOptions {
+    address: SocketAddr,
+    url: url::Url,
+    path: Option<String>,
+    #[serde(default, deserialize_with = "crate::utils::config::limit_opt")]
+    max_connections: Option<u8>,
+    #[serde(default)]
+    drop_pending_updates: bool,
+    secret_token: Option<String>,
+}
+
+impl TryFrom<RawOptions> for Options {
+    type Error = &'static str;
+
+    fn try_from(raw: RawOptions) -> Result<Self, Self::Error> {
+        let RawOptions { address, url, path, max_connections, drop_pending_updates, secret_token } =
+            raw;
+
+        if let Some(token) = &secret_token {
+            check_secret(token.as_bytes())?;
+        }
+
+        let mut options = Options::new(address, url);
+        if let Some(path) = path {
+            options.path = path;
+        }
+
+        Ok(Self { max_connections, drop_pending_updates, secret_token, ..options })
+    }
+}
+
 /// Generates a random string consisting of 32 characters (`a-z`, `A-Z`, `0-9`,
 /// `_` and `-`).
 fn gen_secret_token() -> String {
@@ -0,0 +1,156 @@
+use std::{convert::Infallible, future::Future};
+
+use actix_web::web::ServiceConfig;
+
+use crate::{
+    requests::Requester,
+    update_listeners::{webhooks::Options, UpdateListener},
+};
+
+/// Webhook implementation based on the [mod@actix_web] framework that can
+/// reuse existing [mod@actix_web] server.
+///
+/// This function does most of the work necessary for webhook to work, it:
+/// - Calls [`set_webhook`], so telegram starts sending updates our way
+/// - When the update listener is [`stop`]ped, calls [`delete_webhook`]
+///
+/// The only missing part is registering the webhook route in your
+/// [`App`]s, by passing (a clone of) the returned function to
+/// [`App::configure`].
+///
+/// **Note**: in order for webhooks to work, you need to use the returned
+/// function in an [mod@actix_web] server that is bound to
+/// [`options.address`].
+///
+/// It may also be desired to stop the server (see [`ServerHandle::stop`])
+/// after the returned future resolves, in order to shutdown the server with
+/// the [`stop`] of the listener.
+///
+/// [`set_webhook`]: crate::payloads::SetWebhook
+/// [`delete_webhook`]: crate::payloads::DeleteWebhook
+/// [`stop`]: crate::stop::StopToken::stop
+/// [`options.address`]: Options::address
+/// [`App`]: actix_web::App
+/// [`App::configure`]: actix_web::App::configure
+/// [`ServerHandle::stop`]: actix_web::dev::ServerHandle::stop
+///
+/// ## Returns
+///
+/// A update listener, stop-future, configuration function triplet on success.
+///
+/// The "stop-future" is resolved after [`stop`] is called on the stop token of
+/// the returned update listener.
+///
+/// ## Fails
+///
+/// If `set_webhook()` fails.
+///
+/// ## See also
+///
+/// [`actix_no_setup`] for a lower-level version of this function.
+pub async fn actix_to_config<R>(
+    bot: R,
+    mut options: Options,
+) -> Result<
+    (
+        impl UpdateListener<Err = Infallible>,
+        impl Future<Output = ()> + Send,
+        impl Fn(&mut ServiceConfig) + Clone + Send + 'static,
+    ),
+    R::Err,
+>
+where
+    R: Requester + Send,
+    <R as Requester>::DeleteWebhook: Send,
+{
+    use crate::update_listeners::webhooks::{setup_webhook, state::delete_webhook_on_stop};
+
+    setup_webhook(&bot, &mut options).await?;
+
+    let (listener, stop_flag, config) = actix_no_setup(options);
+    let stop_flag = delete_webhook_on_stop(bot, stop_flag);
+
+    Ok((listener, stop_flag, config))
+}
+
+/// Webhook implementation based on the [mod@actix_web] framework that doesn't
+/// perform any setup work.
+///
+/// The returned function registers a route that only accepts `POST` requests
+/// to [`options.path`].
+///
+/// ## Note about the stop-future
+///
+/// This function returns a future that is resolved when `.stop()` is called on
+/// a stop token of the update listener. Note that even if the future is not
+/// used, after `.stop()` is called, update listener will not produce new
+/// updates.
+///
+/// [`options.path`]: Options::path
+///
+/// ## See also
+///
+/// [`actix_to_config`] for a higher-level version of this function.
+pub fn actix_no_setup(
+    options: Options,
+) -> (
+    impl UpdateListener<Err = Infallible>,
+    impl Future<Output = ()>,
+    impl Fn(&mut ServiceConfig) + Clone + Send + 'static,
+) {
+    use crate::update_listeners::webhooks::state::{self, SECRET_HEADER};
+    use actix_web::{http::StatusCode, web, HttpRequest, HttpResponse};
+
+    let (listener, stop_flag, state) = state::listener(options.secret_token);
+    let path = options.path;
+
+    let config = move |config: &mut ServiceConfig| {
+        let state = state.clone();
+
+        config.route(
+            &path,
+            web::post().to(move |req: HttpRequest, input: web::Bytes| {
+                let mut state = state.clone();
+
+                async move {
+                    let status = match std::str::from_utf8(&input) {
+                        Ok(input) => state.handle_request(
+                            req.headers().get(SECRET_HEADER).map(|h| h.as_bytes()),
+                            input,
+                        ),
+                        Err(_) => StatusCode::BAD_REQUEST.as_u16(),
+                    };
+
+                    // UNWRAP: `handle_request` only returns valid status codes
+                    HttpResponse::new(StatusCode::from_u16(status).unwrap())
+                }
+            }),
+        );
+    };
+
+    (listener, stop_flag, config)
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{App, HttpServer};
+
+    use super::*;
+    use crate::update_listeners::webhooks::state::tests::{check_webhook, options};
+
+    #[tokio::test]
+    async fn webhook() {
+        let (listener, _, config) = actix_no_setup(options());
+
+        let tcp = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = tcp.local_addr().unwrap();
+        let server = HttpServer::new(move || App::new().configure(config.clone()))
+            .workers(1)
+            .listen(tcp)
+            .unwrap()
+            .run();
+        tokio::spawn(server);
+
+        check_webhook(listener, address).await;
+    }
+}
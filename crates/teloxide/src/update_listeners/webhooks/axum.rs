@@ -1,17 +1,13 @@
 use std::{convert::Infallible, future::Future};
 
-use axum::{
-    extract::{FromRequestParts, State},
-    http::{request::Parts, status::StatusCode},
-};
-use tokio::sync::mpsc;
-
 use crate::{
     requests::Requester,
-    stop::StopFlag,
-    types::{Update, UpdateKind},
     update_listeners::{webhooks::Options, UpdateListener},
 };
+use axum::{
+    extract::{FromRequestParts, State},
+    http::{request::Parts, status::StatusCode},
+};
 
 /// Webhook implementation based on the [mod@axum] framework.
 ///
@@ -118,22 +114,12 @@ where
     R: Requester + Send,
     <R as Requester>::DeleteWebhook: Send,
 {
-    use crate::{requests::Request, update_listeners::webhooks::setup_webhook};
-    use futures::FutureExt;
+    use crate::update_listeners::webhooks::{setup_webhook, state::delete_webhook_on_stop};
 
     setup_webhook(&bot, &mut options).await?;
 
     let (listener, stop_flag, router) = axum_no_setup(options);
-
-    let stop_flag = stop_flag.then(move |()| async move {
-        // This assignment is needed to not require `R: Sync` since without it `&bot`
-        // temporary lives across `.await` points.
-        let req = bot.delete_webhook().send();
-        let res = req.await;
-        if let Err(err) = res {
-            log::error!("Couldn't delete webhook: {err}");
-        }
-    });
+    let stop_flag = delete_webhook_on_stop(bot, stop_flag);
 
     Ok((listener, stop_flag, router))
 }
@@ -155,117 +141,31 @@ where
 pub fn axum_no_setup(
     options: Options,
 ) -> (impl UpdateListener<Err = Infallible>, impl Future<Output = ()>, axum::Router) {
-    use crate::{
-        stop::{mk_stop_token, StopToken},
-        update_listeners::{webhooks::tuple_first_mut, StatefulListener},
-    };
+    use crate::update_listeners::webhooks::state::{self, WebhookState};
     use axum::{response::IntoResponse, routing::post};
-    use tokio_stream::wrappers::UnboundedReceiverStream;
     use tower_http::trace::TraceLayer;
 
-    let (tx, rx): (UpdateSender, _) = mpsc::unbounded_channel();
-
     async fn telegram_request(
-        State(WebhookState { secret, flag, mut tx }): State<WebhookState>,
+        State(mut state): State<WebhookState>,
         secret_header: XTelegramBotApiSecretToken,
         input: String,
     ) -> impl IntoResponse {
-        // FIXME: use constant time comparison here
-        if secret_header.0.as_deref() != secret.as_deref().map(str::as_bytes) {
-            return StatusCode::UNAUTHORIZED;
-        }
-
-        let tx = match tx.get() {
-            None => return StatusCode::SERVICE_UNAVAILABLE,
-            // Do not process updates after `.stop()` is called even if the server is still
-            // running (useful for when you need to stop the bot but can't stop the server).
-            _ if flag.is_stopped() => {
-                tx.close();
-                return StatusCode::SERVICE_UNAVAILABLE;
-            }
-            Some(tx) => tx,
-        };
-
-        match serde_json::from_str::<Update>(&input) {
-            Ok(mut update) => {
-                // See HACK comment in
-                // `teloxide_core::net::request::process_response::{closure#0}`
-                if let UpdateKind::Error(value) = &mut update.kind {
-                    *value = serde_json::from_str(&input).unwrap_or_default();
-                }
-
-                tx.send(Ok(update)).expect("Cannot send an incoming update from the webhook")
-            }
-            Err(error) => {
-                log::error!(
-                    "Cannot parse an update.\nError: {error:?}\nValue: {input}\n\
-                     This is a bug in teloxide-core, please open an issue here: \
-                     https://github.com/teloxide/teloxide/issues."
-                );
-            }
-        };
+        let status = state.handle_request(secret_header.0.as_deref(), &input);
 
-        StatusCode::OK
+        // UNWRAP: `handle_request` only returns valid status codes
+        StatusCode::from_u16(status).unwrap()
     }
 
-    let (stop_token, stop_flag) = mk_stop_token();
+    let (listener, stop_flag, state) = state::listener(options.secret_token);
 
     let app = axum::Router::new()
         .route(&options.path, post(telegram_request))
         .layer(TraceLayer::new_for_http())
-        .with_state(WebhookState {
-            tx: ClosableSender::new(tx),
-            flag: stop_flag.clone(),
-            secret: options.secret_token,
-        });
-
-    let stream = UnboundedReceiverStream::new(rx);
-
-    // FIXME: this should support `hint_allowed_updates()`
-    let listener = StatefulListener::new(
-        (stream, stop_token),
-        tuple_first_mut,
-        |state: &mut (_, StopToken)| state.1.clone(),
-    );
+        .with_state(state);
 
     (listener, stop_flag, app)
 }
 
-type UpdateSender = mpsc::UnboundedSender<Result<Update, std::convert::Infallible>>;
-type UpdateCSender = ClosableSender<Result<Update, std::convert::Infallible>>;
-
-#[derive(Clone)]
-struct WebhookState {
-    tx: UpdateCSender,
-    flag: StopFlag,
-    secret: Option<String>,
-}
-
-/// A terrible workaround to drop axum extension
-struct ClosableSender<T> {
-    origin: std::sync::Arc<std::sync::RwLock<Option<mpsc::UnboundedSender<T>>>>,
-}
-
-impl<T> Clone for ClosableSender<T> {
-    fn clone(&self) -> Self {
-        Self { origin: self.origin.clone() }
-    }
-}
-
-impl<T> ClosableSender<T> {
-    fn new(sender: mpsc::UnboundedSender<T>) -> Self {
-        Self { origin: std::sync::Arc::new(std::sync::RwLock::new(Some(sender))) }
-    }
-
-    fn get(&self) -> Option<mpsc::UnboundedSender<T>> {
-        self.origin.read().unwrap().clone()
-    }
-
-    fn close(&mut self) {
-        self.origin.write().unwrap().take();
-    }
-}
-
 struct XTelegramBotApiSecretToken(Option<Vec<u8>>);
 
 impl<S> FromRequestParts<S> for XTelegramBotApiSecretToken {
@@ -275,19 +175,28 @@ impl<S> FromRequestParts<S> for XTelegramBotApiSecretToken {
         req: &mut Parts,
         _state: &S,
     ) -> impl Future<Output = Result<Self, Self::Rejection>> + Send {
-        use crate::update_listeners::webhooks::check_secret;
+        use crate::update_listeners::webhooks::state::SECRET_HEADER;
 
-        let res = req
-            .headers
-            .remove("x-telegram-bot-api-secret-token")
-            .map(|header| {
-                check_secret(header.as_bytes())
-                    .map(<_>::to_owned)
-                    .map_err(|_| StatusCode::BAD_REQUEST)
-            })
-            .transpose()
-            .map(Self);
+        let res =
+            Ok(Self(req.headers.remove(SECRET_HEADER).map(|header| header.as_bytes().to_owned())));
 
         async { res }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::update_listeners::webhooks::state::tests::{check_webhook, options};
+
+    #[tokio::test]
+    async fn webhook() {
+        let (listener, _, app) = axum_no_setup(options());
+
+        let tcp = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = tcp.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(tcp, app).await.unwrap() });
+
+        check_webhook(listener, address).await;
+    }
+}
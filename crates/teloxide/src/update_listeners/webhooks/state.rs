@@ -0,0 +1,220 @@
+//! Framework-independent parts of webhook implementations.
+use std::{
+    convert::Infallible,
+    future::Future,
+    sync::{Arc, RwLock},
+};
+
+use tokio::sync::mpsc;
+
+use crate::{
+    requests::{Request, Requester},
+    stop::StopFlag,
+    types::{Update, UpdateKind},
+    update_listeners::UpdateListener,
+};
+
+/// The name of the header in which Telegram sends the secret token.
+pub(super) const SECRET_HEADER: &str = "x-telegram-bot-api-secret-token";
+
+type UpdateSender = mpsc::UnboundedSender<Result<Update, Infallible>>;
+type UpdateCSender = ClosableSender<Result<Update, Infallible>>;
+
+/// State shared by the request handlers of a webhook.
+#[derive(Clone)]
+pub(super) struct WebhookState {
+    tx: UpdateCSender,
+    flag: StopFlag,
+    secret: Option<String>,
+}
+
+impl WebhookState {
+    /// Handles a request from Telegram, returning the status code of the
+    /// response.
+    ///
+    /// `secret_header` is the value of the [`SECRET_HEADER`] header, if any.
+    pub(super) fn handle_request(&mut self, secret_header: Option<&[u8]>, input: &str) -> u16 {
+        use super::check_secret;
+
+        const OK: u16 = 200;
+        const BAD_REQUEST: u16 = 400;
+        const UNAUTHORIZED: u16 = 401;
+        const SERVICE_UNAVAILABLE: u16 = 503;
+
+        let secret_header = match secret_header.map(check_secret).transpose() {
+            Ok(secret_header) => secret_header,
+            Err(_) => return BAD_REQUEST,
+        };
+
+        // FIXME: use constant time comparison here
+        if secret_header != self.secret.as_deref().map(str::as_bytes) {
+            return UNAUTHORIZED;
+        }
+
+        let tx = match self.tx.get() {
+            None => return SERVICE_UNAVAILABLE,
+            // Do not process updates after `.stop()` is called even if the server is still
+            // running (useful for when you need to stop the bot but can't stop the server).
+            _ if self.flag.is_stopped() => {
+                self.tx.close();
+                return SERVICE_UNAVAILABLE;
+            }
+            Some(tx) => tx,
+        };
+
+        match serde_json::from_str::<Update>(input) {
+            Ok(mut update) => {
+                // See HACK comment in
+                // `teloxide_core::net::request::process_response::{closure#0}`
+                if let UpdateKind::Error(value) = &mut update.kind {
+                    *value = serde_json::from_str(input).unwrap_or_default();
+                }
+
+                tx.send(Ok(update)).expect("Cannot send an incoming update from the webhook")
+            }
+            Err(error) => {
+                log::error!(
+                    "Cannot parse an update.\nError: {error:?}\nValue: {input}\n\
+                     This is a bug in teloxide-core, please open an issue here: \
+                     https://github.com/teloxide/teloxide/issues."
+                );
+            }
+        };
+
+        OK
+    }
+}
+
+/// Creates an update listener that receives updates from request handlers
+/// sharing the returned state.
+///
+/// The returned stop flag is resolved when the listener is stopped.
+pub(super) fn listener(
+    secret: Option<String>,
+) -> (impl UpdateListener<Err = Infallible>, StopFlag, WebhookState) {
+    use crate::{
+        stop::{mk_stop_token, StopToken},
+        update_listeners::{webhooks::tuple_first_mut, StatefulListener},
+    };
+    use tokio_stream::wrappers::UnboundedReceiverStream;
+
+    let (tx, rx): (UpdateSender, _) = mpsc::unbounded_channel();
+    let (stop_token, stop_flag) = mk_stop_token();
+
+    let state = WebhookState { tx: ClosableSender::new(tx), flag: stop_flag.clone(), secret };
+
+    let stream = UnboundedReceiverStream::new(rx);
+
+    // FIXME: this should support `hint_allowed_updates()`
+    let listener = StatefulListener::new(
+        (stream, stop_token),
+        tuple_first_mut,
+        |state: &mut (_, StopToken)| state.1.clone(),
+    );
+
+    (listener, stop_flag, state)
+}
+
+/// Makes `stop_flag` call `delete_webhook` after it is resolved.
+pub(super) fn delete_webhook_on_stop<R, F>(bot: R, stop_flag: F) -> impl Future<Output = ()> + Send
+where
+    F: Future<Output = ()> + Send,
+    R: Requester + Send,
+    <R as Requester>::DeleteWebhook: Send,
+{
+    use futures::FutureExt;
+
+    stop_flag.then(move |()| async move {
+        // This assignment is needed to not require `R: Sync` since without it `&bot`
+        // temporary lives across `.await` points.
+        let req = bot.delete_webhook().send();
+        let res = req.await;
+        if let Err(err) = res {
+            log::error!("Couldn't delete webhook: {err}");
+        }
+    })
+}
+
+/// A terrible workaround to close the sender shared by all request handlers
+struct ClosableSender<T> {
+    origin: Arc<RwLock<Option<mpsc::UnboundedSender<T>>>>,
+}
+
+impl<T> Clone for ClosableSender<T> {
+    fn clone(&self) -> Self {
+        Self { origin: self.origin.clone() }
+    }
+}
+
+impl<T> ClosableSender<T> {
+    fn new(sender: mpsc::UnboundedSender<T>) -> Self {
+        Self { origin: Arc::new(RwLock::new(Some(sender))) }
+    }
+
+    fn get(&self) -> Option<mpsc::UnboundedSender<T>> {
+        self.origin.read().unwrap().clone()
+    }
+
+    fn close(&mut self) {
+        self.origin.write().unwrap().take();
+    }
+}
+
+/// Backend-agnostic checks of webhook implementations.
+#[cfg(test)]
+pub(super) mod tests {
+    use std::{net::SocketAddr, pin::pin};
+
+    use futures::StreamExt;
+
+    use super::*;
+    use crate::{types::UpdateId, update_listeners::webhooks::Options};
+
+    const SECRET: &str = "secret";
+
+    /// Returns options of a webhook at `/webhook` with a known secret token.
+    pub(in crate::update_listeners::webhooks) fn options() -> Options {
+        let url = "https://example.com/webhook".parse().unwrap();
+        Options::new(([127, 0, 0, 1], 0).into(), url).secret_token(SECRET.to_owned())
+    }
+
+    /// Checks the webhook served at `address`, with `listener` created from
+    /// [`options`].
+    pub(in crate::update_listeners::webhooks) async fn check_webhook<L>(
+        mut listener: L,
+        address: SocketAddr,
+    ) where
+        L: UpdateListener<Err = Infallible>,
+    {
+        let client = reqwest::Client::new();
+        let post = |path: &str, secret: Option<&str>, id: u32| {
+            let mut request = client.post(format!("http://{address}{path}")).body(
+                serde_json::json!({"update_id": id, "poll": {"id": "1", "question": "?",
+                    "options": [], "total_voter_count": 0, "is_closed": false,
+                    "is_anonymous": true, "type": "regular", "allows_multiple_answers": false}})
+                .to_string(),
+            );
+            if let Some(secret) = secret {
+                request = request.header(SECRET_HEADER, secret);
+            }
+            async move { request.send().await.unwrap().status().as_u16() }
+        };
+
+        assert_eq!(post("/webhook", Some(SECRET), 1).await, 200);
+        assert_eq!(post("/webhook", Some("other"), 2).await, 401);
+        assert_eq!(post("/webhook", None, 3).await, 401);
+        assert_eq!(post("/other", Some(SECRET), 4).await, 404);
+        assert_eq!(post("/webhook", Some(SECRET), 5).await, 200);
+
+        let stop_token = listener.stop_token();
+        {
+            let mut stream = pin!(listener.as_stream());
+            assert_eq!(stream.next().await.unwrap().unwrap().id, UpdateId(1));
+            assert_eq!(stream.next().await.unwrap().unwrap().id, UpdateId(5));
+        }
+
+        // Updates aren't accepted once the listener is stopped
+        stop_token.stop();
+        assert_eq!(post("/webhook", Some(SECRET), 6).await, 503);
+    }
+}
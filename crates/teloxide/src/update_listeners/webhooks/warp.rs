@@ -0,0 +1,152 @@
+use std::{convert::Infallible, future::Future};
+
+use warp::{Filter, Rejection, Reply};
+
+use crate::{
+    requests::Requester,
+    update_listeners::{webhooks::Options, UpdateListener},
+};
+
+/// Webhook implementation based on the [mod@warp] framework that can reuse
+/// existing [mod@warp] server.
+///
+/// This function does most of the work necessary for webhook to work, it:
+/// - Calls [`set_webhook`], so telegram starts sending updates our way
+/// - When the update listener is [`stop`]ped, calls [`delete_webhook`]
+///
+/// The only missing part is serving the returned [`Filter`], for example by
+/// combining it with the filters of your server using [`Filter::or`].
+///
+/// **Note**: in order for webhooks to work, you need to serve the returned
+/// [`Filter`] on a [mod@warp] server that is bound to [`options.address`].
+///
+/// It may also be desired to use [`graceful`] with the returned future in
+/// order to shutdown the server with the [`stop`] of the listener.
+///
+/// [`set_webhook`]: crate::payloads::SetWebhook
+/// [`delete_webhook`]: crate::payloads::DeleteWebhook
+/// [`stop`]: crate::stop::StopToken::stop
+/// [`options.address`]: Options::address
+/// [`graceful`]: warp::Server::graceful
+///
+/// ## Returns
+///
+/// A update listener, stop-future, warp filter triplet on success.
+///
+/// The "stop-future" is resolved after [`stop`] is called on the stop token of
+/// the returned update listener.
+///
+/// ## Fails
+///
+/// If `set_webhook()` fails.
+///
+/// ## See also
+///
+/// [`warp_no_setup`] for a lower-level version of this function.
+pub async fn warp_to_filter<R>(
+    bot: R,
+    mut options: Options,
+) -> Result<
+    (
+        impl UpdateListener<Err = Infallible>,
+        impl Future<Output = ()> + Send,
+        impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone + Send + Sync + 'static,
+    ),
+    R::Err,
+>
+where
+    R: Requester + Send,
+    <R as Requester>::DeleteWebhook: Send,
+{
+    use crate::update_listeners::webhooks::{setup_webhook, state::delete_webhook_on_stop};
+
+    setup_webhook(&bot, &mut options).await?;
+
+    let (listener, stop_flag, filter) = warp_no_setup(options);
+    let stop_flag = delete_webhook_on_stop(bot, stop_flag);
+
+    Ok((listener, stop_flag, filter))
+}
+
+/// Webhook implementation based on the [mod@warp] framework that doesn't
+/// perform any setup work.
+///
+/// The returned filter only accepts `POST` requests to [`options.path`].
+///
+/// ## Note about the stop-future
+///
+/// This function returns a future that is resolved when `.stop()` is called on
+/// a stop token of the update listener. Note that even if the future is not
+/// used, after `.stop()` is called, update listener will not produce new
+/// updates.
+///
+/// [`options.path`]: Options::path
+///
+/// ## See also
+///
+/// [`warp_to_filter`] for a higher-level version of this function.
+pub fn warp_no_setup(
+    options: Options,
+) -> (
+    impl UpdateListener<Err = Infallible>,
+    impl Future<Output = ()>,
+    impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone + Send + Sync + 'static,
+) {
+    use crate::update_listeners::webhooks::state::{self, SECRET_HEADER};
+    use bytes::Bytes;
+    use warp::{
+        http::{HeaderMap, StatusCode},
+        path::FullPath,
+    };
+
+    let (listener, stop_flag, state) = state::listener(options.secret_token);
+    let path = options.path;
+
+    let filter = warp::post()
+        .and(warp::path::full())
+        .and_then(move |full_path: FullPath| {
+            let matches = full_path.as_str() == path;
+            async move {
+                if matches {
+                    Ok(())
+                } else {
+                    Err(warp::reject::not_found())
+                }
+            }
+        })
+        .untuple_one()
+        .and(warp::header::headers_cloned())
+        .and(warp::body::bytes())
+        .map(move |headers: HeaderMap, input: Bytes| {
+            let mut state = state.clone();
+
+            let status = match std::str::from_utf8(&input) {
+                Ok(input) => {
+                    state.handle_request(headers.get(SECRET_HEADER).map(|h| h.as_bytes()), input)
+                }
+                Err(_) => StatusCode::BAD_REQUEST.as_u16(),
+            };
+
+            // UNWRAP: `handle_request` only returns valid status codes
+            StatusCode::from_u16(status).unwrap()
+        });
+
+    (listener, stop_flag, filter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::update_listeners::webhooks::state::tests::{check_webhook, options};
+
+    #[tokio::test]
+    async fn webhook() {
+        let (listener, _, filter) = warp_no_setup(options());
+
+        let tcp = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = tcp.local_addr().unwrap();
+        tokio::spawn(warp::serve(filter).incoming(tcp).run());
+
+        check_webhook(listener, address).await;
+    }
+}
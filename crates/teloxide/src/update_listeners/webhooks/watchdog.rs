@@ -0,0 +1,235 @@
+use std::{
+    future::Future,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use futures::future::BoxFuture;
+
+use crate::{
+    requests::{Request, Requester},
+    types::WebhookInfo,
+    update_listeners::webhooks::{setup_webhook, Options},
+};
+
+type FailureCallback = Arc<dyn Fn(WebhookInfo) -> BoxFuture<'static, ()> + Send + Sync>;
+
+/// A watchdog that periodically checks the webhook status with
+/// [`get_webhook_info`] and tries to recover the webhook if Telegram can't
+/// deliver updates to it.
+///
+/// The webhook is considered failing when:
+/// - Telegram reported an error (see [`WebhookInfo::last_error_date`]) since
+///   the previous check, or
+/// - If [`reset_webhook`] was used, the webhook url differs from
+///   [`Options::url`] (e.g. the webhook was deleted).
+///
+/// If the webhook keeps failing for at least [`threshold`], the watchdog calls
+/// the [`on_failure`] callback and re-runs [`set_webhook`] (if
+/// [`reset_webhook`] was used).
+///
+/// [`get_webhook_info`]: crate::payloads::GetWebhookInfo
+/// [`set_webhook`]: crate::payloads::SetWebhook
+/// [`reset_webhook`]: Watchdog::reset_webhook
+/// [`threshold`]: Watchdog::threshold
+/// [`on_failure`]: Watchdog::on_failure
+///
+/// ## Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use teloxide::{prelude::*, update_listeners::webhooks};
+///
+/// # async fn run() {
+/// let bot = Bot::from_env();
+/// let addr = ([127, 0, 0, 1], 8443).into();
+/// let url = "https://example.com/webhook".parse().unwrap();
+/// let options = webhooks::Options::new(addr, url).secret_token("my_secret".to_owned());
+///
+/// let watchdog = webhooks::Watchdog::new(bot.clone())
+///     .interval(Duration::from_secs(30))
+///     .reset_webhook(options.clone())
+///     .on_failure(|info| async move {
+///         log::error!("Webhook is failing: {:?}", info.last_error_message);
+///     });
+/// tokio::spawn(watchdog.run());
+/// # }
+/// ```
+#[must_use]
+pub struct Watchdog<R> {
+    bot: R,
+    interval: Duration,
+    threshold: Duration,
+    options: Option<Options>,
+    on_failure: Option<FailureCallback>,
+}
+
+impl<R> Watchdog<R>
+where
+    R: Requester,
+{
+    /// Creates a new watchdog, which only logs webhook failures.
+    pub fn new(bot: R) -> Self {
+        Self {
+            bot,
+            interval: Duration::from_secs(60),
+            threshold: Duration::from_secs(5 * 60),
+            options: None,
+            on_failure: None,
+        }
+    }
+
+    /// Sets how often the webhook status is checked.
+    ///
+    /// Default - 1 minute.
+    ///
+    /// ## Panics
+    ///
+    /// If `interval` is zero.
+    pub fn interval(self, interval: Duration) -> Self {
+        assert!(!interval.is_zero(), "watchdog interval must not be zero");
+        Self { interval, ..self }
+    }
+
+    /// Sets for how long the webhook must keep failing before the watchdog
+    /// tries to recover it.
+    ///
+    /// Default - 5 minutes.
+    pub fn threshold(self, threshold: Duration) -> Self {
+        Self { threshold, ..self }
+    }
+
+    /// Re-runs [`set_webhook`] with `options` when the webhook is failing.
+    ///
+    /// `options` must be the same as the ones used to set up the webhook
+    /// listener.
+    ///
+    /// [`set_webhook`]: crate::payloads::SetWebhook
+    ///
+    /// ## Panics
+    ///
+    /// If [`Options::secret_token`] is not set, since otherwise the new webhook
+    /// would use a secret unknown to the listener.
+    #[track_caller]
+    pub fn reset_webhook(self, options: Options) -> Self {
+        assert!(
+            options.secret_token.is_some(),
+            "`Options::secret_token` must be set to reset the webhook from the watchdog"
+        );
+
+        Self { options: Some(options), ..self }
+    }
+
+    /// Sets a callback that is called with the latest webhook info when the
+    /// webhook is failing, e.g. to notify an operator.
+    pub fn on_failure<F, Fut>(self, callback: F) -> Self
+    where
+        F: Fn(WebhookInfo) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        Self { on_failure: Some(Arc::new(move |info| Box::pin(callback(info)))), ..self }
+    }
+
+    /// Runs the watchdog.
+    ///
+    /// The returned future never resolves, so you probably want to
+    /// [`tokio::spawn`] it.
+    pub async fn run(self) {
+        let mut interval = tokio::time::interval(self.interval);
+        let mut last_check = unix_now();
+        let mut failing_since = None;
+
+        loop {
+            interval.tick().await;
+
+            let info = match self.bot.get_webhook_info().send().await {
+                Ok(info) => info,
+                Err(err) => {
+                    log::error!("Watchdog failed to get webhook info: {err}");
+                    continue;
+                }
+            };
+
+            let now = unix_now();
+            let expected_url = self.options.as_ref().map(|o| &o.url);
+            let failing = is_failing(&info, last_check, expected_url);
+            last_check = now;
+
+            if !failing {
+                failing_since = None;
+                continue;
+            }
+
+            let since = *failing_since.get_or_insert_with(Instant::now);
+            log::warn!(
+                "Webhook is failing: {}",
+                info.last_error_message.as_deref().unwrap_or("unexpected webhook url")
+            );
+
+            if since.elapsed() < self.threshold {
+                continue;
+            }
+
+            failing_since = None;
+
+            if let Some(callback) = &self.on_failure {
+                callback(info).await;
+            }
+
+            if let Some(options) = &self.options {
+                // `setup_webhook` takes out the certificate, so give it a copy
+                let mut options = options.clone();
+
+                match setup_webhook(&self.bot, &mut options).await {
+                    Ok(()) => log::info!("Watchdog has reset the webhook"),
+                    Err(err) => log::error!("Watchdog failed to reset the webhook: {err}"),
+                }
+            }
+        }
+    }
+}
+
+/// Returns `true` if the webhook described by `info` has failed since
+/// `last_check` (a unix timestamp) or doesn't point to `expected_url`.
+fn is_failing(info: &WebhookInfo, last_check: i64, expected_url: Option<&url::Url>) -> bool {
+    let recent_error = info.last_error_date.is_some_and(|date| date.timestamp() >= last_check);
+    let wrong_url = expected_url.is_some_and(|url| info.url.as_ref() != Some(url));
+
+    recent_error || wrong_url
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(url: &str, last_error_date: Option<i64>) -> WebhookInfo {
+        let last_error_date = match last_error_date {
+            Some(date) => format!(r#", "last_error_date": {date}, "last_error_message": "521""#),
+            None => String::new(),
+        };
+
+        serde_json::from_str(&format!(
+            r#"{{"url": "{url}", "has_custom_certificate": false, "pending_update_count": 0{last_error_date}}}"#
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn failing_webhook_detection() {
+        let url: url::Url = "https://example.com/webhook".parse().unwrap();
+
+        assert!(!is_failing(&info(url.as_str(), None), 100, Some(&url)));
+        assert!(!is_failing(&info(url.as_str(), Some(50)), 100, Some(&url)));
+        assert!(is_failing(&info(url.as_str(), Some(150)), 100, Some(&url)));
+        assert!(is_failing(&info(url.as_str(), Some(150)), 100, None));
+
+        // The webhook was deleted or replaced
+        assert!(is_failing(&info("", None), 100, Some(&url)));
+        assert!(is_failing(&info("https://example.org/", None), 100, Some(&url)));
+        assert!(!is_failing(&info("", None), 100, None));
+    }
+}
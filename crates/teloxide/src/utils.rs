@@ -1,9 +1,27 @@
 //! Some useful utilities.
 
+pub mod callback_data;
+pub mod chat_members;
+pub mod cloud_events;
 pub mod command;
+pub(crate) mod config;
+pub mod debounce;
 pub mod html;
+pub mod inline;
+pub mod keyboard;
 pub mod markdown;
+pub mod media_group;
+pub mod media_pipeline;
+pub mod mirror;
+pub mod purge;
+pub(crate) mod recent;
 pub mod render;
 pub(crate) mod shutdown_token;
+pub mod text;
+pub mod topics;
+
+mod markup;
+
+pub use markup::{validate_markup, MarkupError};
 
 pub use teloxide_core::net::client_from_env;
@@ -0,0 +1,141 @@
+//! Typed callback data of inline keyboard buttons.
+
+use serde::{de::DeserializeOwned, Serialize};
+use thiserror::Error;
+
+/// The maximum length of callback data in bytes, as allowed by Telegram.
+pub const MAX_CALLBACK_DATA_LEN: usize = 64;
+
+/// A type that can be stored in the callback data of an inline keyboard
+/// button.
+///
+/// Data is stored as `<version>:<json>`, e.g. `0:{"Buy":[5,2]}`. Bump
+/// [`VERSION`] when changing the type incompatibly, so that buttons of
+/// messages sent by previous versions of the bot are rejected instead of being
+/// misinterpreted. Prefer tuple variants and short names, since the encoded
+/// data must fit into [`MAX_CALLBACK_DATA_LEN`] bytes.
+///
+/// Use [`filter_callback_data`] to decode the data of incoming callback
+/// queries.
+///
+/// [`VERSION`]: CallbackData::VERSION
+/// [`filter_callback_data`]: crate::dispatching::filter_callback_data
+///
+/// ## Examples
+///
+/// ```
+/// use serde::{Deserialize, Serialize};
+/// use teloxide::utils::callback_data::CallbackData;
+///
+/// #[derive(Serialize, Deserialize, Debug, PartialEq)]
+/// enum Action {
+///     Buy(u32, u8),
+///     Cancel,
+/// }
+///
+/// impl CallbackData for Action {
+///     const VERSION: u8 = 1;
+/// }
+///
+/// let data = Action::Buy(5, 2).encode().unwrap();
+/// assert_eq!(data, r#"1:{"Buy":[5,2]}"#);
+/// assert_eq!(Action::decode(&data).unwrap(), Action::Buy(5, 2));
+/// ```
+pub trait CallbackData: Serialize + DeserializeOwned {
+    /// The version of the format of this type.
+    const VERSION: u8 = 0;
+
+    /// Encodes `self` to be used as callback data.
+    fn encode(&self) -> Result<String, CallbackDataError> {
+        let json = serde_json::to_string(self).map_err(CallbackDataError::Serde)?;
+        let data = format!("{}:{json}", Self::VERSION);
+
+        if data.len() > MAX_CALLBACK_DATA_LEN {
+            return Err(CallbackDataError::TooLong(data.len()));
+        }
+        Ok(data)
+    }
+
+    /// Decodes callback data produced by [`CallbackData::encode`].
+    fn decode(data: &str) -> Result<Self, CallbackDataError> {
+        let (version, json) = data.split_once(':').ok_or(CallbackDataError::Malformed)?;
+        let version: u8 = version.parse().map_err(|_| CallbackDataError::Malformed)?;
+
+        if version != Self::VERSION {
+            return Err(CallbackDataError::Version { expected: Self::VERSION, found: version });
+        }
+        serde_json::from_str(json).map_err(CallbackDataError::Serde)
+    }
+}
+
+/// An error returned from [`CallbackData`] methods.
+#[derive(Debug, Error)]
+pub enum CallbackDataError {
+    /// The encoded data is longer than [`MAX_CALLBACK_DATA_LEN`] bytes.
+    #[error("callback data is {0} bytes long, while at most {MAX_CALLBACK_DATA_LEN} is allowed")]
+    TooLong(usize),
+
+    /// The data doesn't start with a version.
+    #[error("callback data is malformed")]
+    Malformed,
+
+    /// The data was encoded with another version of the type.
+    #[error("callback data has version {found}, expected {expected}")]
+    Version { expected: u8, found: u8 },
+
+    /// The value couldn't be serialized or deserialized.
+    #[error("{0}")]
+    Serde(#[source] serde_json::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+    enum Action {
+        Page(usize),
+        Note(String),
+    }
+
+    impl CallbackData for Action {}
+
+    #[test]
+    fn errors() {
+        assert!(matches!(
+            Action::Note("a".repeat(64)).encode(),
+            Err(CallbackDataError::TooLong(77))
+        ));
+        assert!(matches!(Action::decode("page"), Err(CallbackDataError::Malformed)));
+        assert!(matches!(
+            Action::decode(r#"3:{"Page":1}"#),
+            Err(CallbackDataError::Version { expected: 0, found: 3 })
+        ));
+        assert!(matches!(Action::decode(r#"0:{"Other":1}"#), Err(CallbackDataError::Serde(_))));
+        assert_eq!(Action::decode(r#"0:{"Page":1}"#).unwrap(), Action::Page(1));
+    }
+
+    #[tokio::test]
+    async fn filter() {
+        let query = |data: &str| -> teloxide_core::types::CallbackQuery {
+            serde_json::from_value(serde_json::json!({
+                "id": "1",
+                "from": { "id": 2, "is_bot": false, "first_name": "B" },
+                "chat_instance": "3",
+                "data": data,
+            }))
+            .unwrap()
+        };
+        let handler = crate::dispatching::filter_callback_data::<Action, _>()
+            .endpoint(|action: Action| async move { action });
+
+        let data = Action::Page(2).encode().unwrap();
+        assert_eq!(
+            handler.dispatch(dptree::deps![query(&data)]).await,
+            std::ops::ControlFlow::Break(Action::Page(2))
+        );
+        assert!(handler.dispatch(dptree::deps![query("page:2")]).await.is_continue());
+    }
+}
@@ -0,0 +1,249 @@
+//! Caching of chat members.
+
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use futures::{stream, StreamExt, TryStreamExt};
+use teloxide_core::{
+    requests::Requester,
+    types::{ChatId, ChatMember, ChatMemberUpdated, UserId},
+};
+
+use crate::utils::recent::RecentMap;
+
+/// A cache of [`ChatMember`]s, for bots that check roles of many users.
+///
+/// Members are fetched with [`GetChatMember`] and cached for a fixed time
+/// (TTL). Feed [`ChatMemberUpdated`] updates to [`observe`] to keep the cache
+/// up to date when members change before their entries expire. At most
+/// [`max_members`] members are kept, the least recently fetched ones are
+/// forgotten first.
+///
+/// Clones share the same state, so a cache can be passed to handlers as a
+/// dependency.
+///
+/// [`GetChatMember`]: crate::payloads::GetChatMember
+/// [`observe`]: ChatMemberCache::observe
+/// [`max_members`]: ChatMemberCache::max_members
+///
+/// ## Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use teloxide::{prelude::*, types::ChatMemberUpdated, utils::chat_members::ChatMemberCache};
+///
+/// # async fn run() {
+/// let bot = Bot::from_env();
+/// let cache = ChatMemberCache::new(Duration::from_secs(300));
+///
+/// let handler = dptree::entry()
+///     .branch(Update::filter_chat_member().endpoint(
+///         |upd: ChatMemberUpdated, cache: ChatMemberCache| async move {
+///             cache.observe(&upd);
+///             respond(())
+///         },
+///     ))
+///     .branch(Update::filter_message().endpoint(
+///         |bot: Bot, msg: Message, cache: ChatMemberCache| async move {
+///             let users = msg.mentioned_users().map(|user| user.id).collect::<Vec<_>>();
+///             let members = cache.get_chat_members(&bot, msg.chat.id, users).await?;
+///
+///             if members.iter().any(|member| member.is_privileged()) {
+///                 bot.send_message(msg.chat.id, "Admins were mentioned").await?;
+///             }
+///             respond(())
+///         },
+///     ));
+///
+/// Dispatcher::builder(bot, handler).dependencies(dptree::deps![cache]).build().dispatch().await;
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct ChatMemberCache {
+    ttl: Duration,
+    concurrency: usize,
+    members: Arc<Mutex<Members>>,
+}
+
+type Members = RecentMap<(ChatId, UserId), (ChatMember, Instant)>;
+
+impl ChatMemberCache {
+    /// The default maximum number of concurrent requests made by
+    /// [`ChatMemberCache::get_chat_members`].
+    pub const DEFAULT_CONCURRENCY: usize = 8;
+
+    /// The default maximum number of cached members, see
+    /// [`ChatMemberCache::max_members`].
+    pub const DEFAULT_MAX_MEMBERS: usize = 10_000;
+
+    /// Creates an empty cache, which keeps members for `ttl`.
+    #[must_use]
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            concurrency: Self::DEFAULT_CONCURRENCY,
+            members: Arc::new(Mutex::new(RecentMap::new(Self::DEFAULT_MAX_MEMBERS))),
+        }
+    }
+
+    /// Sets the maximum number of cached members.
+    ///
+    /// When the cache is full, the least recently fetched half of the members
+    /// is removed. This discards the members cached so far.
+    ///
+    /// By default, it's [`ChatMemberCache::DEFAULT_MAX_MEMBERS`].
+    ///
+    /// ## Panics
+    ///
+    /// If `max` is `0`.
+    #[must_use]
+    #[track_caller]
+    pub fn max_members(self, max: usize) -> Self {
+        Self { members: Arc::new(Mutex::new(RecentMap::new(max))), ..self }
+    }
+
+    /// Sets the maximum number of concurrent requests made by
+    /// [`ChatMemberCache::get_chat_members`].
+    ///
+    /// ## Panics
+    ///
+    /// If `concurrency` is `0`.
+    #[must_use]
+    #[track_caller]
+    pub fn concurrency(self, concurrency: usize) -> Self {
+        assert!(concurrency > 0, "`concurrency` must be positive");
+        Self { concurrency, ..self }
+    }
+
+    /// Returns the cached member, if there is one that hasn't expired yet.
+    #[must_use]
+    pub fn cached(&self, chat_id: ChatId, user_id: UserId) -> Option<ChatMember> {
+        let members = self.members.lock().unwrap();
+        let (member, fetched_at) = members.get(&(chat_id, user_id))?;
+
+        (fetched_at.elapsed() < self.ttl).then(|| member.clone())
+    }
+
+    /// Returns the member of `chat_id`, fetching it if it's not cached.
+    pub async fn get_chat_member<R>(
+        &self,
+        bot: &R,
+        chat_id: ChatId,
+        user_id: UserId,
+    ) -> Result<ChatMember, R::Err>
+    where
+        R: Requester,
+    {
+        if let Some(member) = self.cached(chat_id, user_id) {
+            return Ok(member);
+        }
+
+        let member = bot.get_chat_member(chat_id, user_id).await?;
+        self.insert(chat_id, member.clone());
+        Ok(member)
+    }
+
+    /// Returns the members of `chat_id`, in the same order as `users`.
+    ///
+    /// Members that aren't cached are fetched concurrently, with at most
+    /// [`concurrency`] requests at a time.
+    ///
+    /// [`concurrency`]: ChatMemberCache::concurrency
+    pub async fn get_chat_members<R, I>(
+        &self,
+        bot: &R,
+        chat_id: ChatId,
+        users: I,
+    ) -> Result<Vec<ChatMember>, R::Err>
+    where
+        R: Requester,
+        I: IntoIterator<Item = UserId>,
+    {
+        stream::iter(users)
+            .map(|user_id| self.get_chat_member(bot, chat_id, user_id))
+            .buffered(self.concurrency)
+            .try_collect()
+            .await
+    }
+
+    /// Caches the new state of the member from `update`.
+    pub fn observe(&self, update: &ChatMemberUpdated) {
+        self.insert(update.chat.id, update.new_chat_member.clone());
+    }
+
+    /// Removes the member from the cache.
+    pub fn invalidate(&self, chat_id: ChatId, user_id: UserId) {
+        self.members.lock().unwrap().remove(&(chat_id, user_id));
+    }
+
+    /// Removes all members of `chat_id` from the cache.
+    pub fn invalidate_chat(&self, chat_id: ChatId) {
+        self.members.lock().unwrap().retain(|&(chat, _), _| chat != chat_id);
+    }
+
+    /// Removes expired members from the cache.
+    ///
+    /// Expired members are never returned, but they are kept in memory until
+    /// they are fetched again, pushed out by [newer members], or this method
+    /// is called.
+    ///
+    /// [newer members]: ChatMemberCache::max_members
+    pub fn remove_expired(&self) {
+        let ttl = self.ttl;
+        self.members.lock().unwrap().retain(|_, (_, fetched_at)| fetched_at.elapsed() < ttl);
+    }
+
+    fn insert(&self, chat_id: ChatId, member: ChatMember) {
+        let key = (chat_id, member.user.id);
+        self.members.lock().unwrap().insert(key, (member, Instant::now()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::test_utils::chat_member_updated;
+
+    #[test]
+    fn observe_and_expire() {
+        let update = chat_member_updated(
+            json!({ "status": "member" }),
+            json!({ "status": "kicked", "until_date": 0 }),
+        );
+
+        let cache = ChatMemberCache::new(Duration::from_secs(60));
+        cache.clone().observe(&update);
+
+        let member = cache.cached(ChatId(-1), UserId(2)).unwrap();
+        assert!(member.is_banned());
+        assert!(cache.cached(ChatId(-2), UserId(2)).is_none());
+
+        cache.invalidate_chat(ChatId(-1));
+        assert!(cache.cached(ChatId(-1), UserId(2)).is_none());
+
+        let expired = ChatMemberCache::new(Duration::ZERO);
+        expired.observe(&update);
+        assert!(expired.cached(ChatId(-1), UserId(2)).is_none());
+    }
+
+    #[test]
+    fn max_members() {
+        let cache = ChatMemberCache::new(Duration::from_secs(60)).max_members(2);
+        for chat in 1..=3 {
+            let mut update =
+                chat_member_updated(json!({ "status": "left" }), json!({ "status": "member" }));
+            update.chat.id = ChatId(-chat);
+            cache.observe(&update);
+        }
+
+        // The newest half is kept
+        assert!(cache.cached(ChatId(-1), UserId(2)).is_none());
+        assert!(cache.cached(ChatId(-2), UserId(2)).is_none());
+        assert!(cache.cached(ChatId(-3), UserId(2)).is_some());
+    }
+}
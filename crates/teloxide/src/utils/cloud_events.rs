@@ -0,0 +1,352 @@
+//! Exporting updates as [CloudEvents].
+//!
+//! [`CloudEventsExporter`] wraps every [`Update`] into a [`CloudEvent`] and
+//! publishes it to an [`EventSink`] (a message broker, a log, an HTTP
+//! endpoint, ...), so Telegram traffic can be fed into existing event-driven
+//! pipelines. Events serialize to the [JSON event format] of CloudEvents 1.0.
+//!
+//! [CloudEvents]: https://cloudevents.io
+//! [JSON event format]: https://github.com/cloudevents/spec/blob/v1.0.2/cloudevents/formats/json-format.md
+
+use std::{
+    fmt::Display,
+    future::Future,
+    sync::{Arc, OnceLock},
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use dptree::Handler;
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+use crate::{dispatching::DpHandlerDescription, types::Update, update_listeners::date_of};
+
+/// The version of the CloudEvents specification implemented by
+/// [`CloudEvent`].
+pub const SPEC_VERSION: &str = "1.0";
+
+/// A CloudEvents 1.0 envelope of an [`Update`].
+///
+/// Create it with [`CloudEventsExporter::event`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CloudEvent {
+    /// The version of the CloudEvents specification, always
+    /// [`SPEC_VERSION`].
+    pub specversion: String,
+
+    /// The id of the event, which is the id of the update.
+    ///
+    /// It's unique within the [`source`](CloudEvent::source).
+    pub id: String,
+
+    /// The source of the event, as set by [`CloudEventsExporter::new`].
+    pub source: String,
+
+    /// The type of the event, which is the [type prefix] followed by the
+    /// [kind] of the update, e.g. `org.telegram.update.message`.
+    ///
+    /// [type prefix]: CloudEventsExporter::type_prefix
+    /// [kind]: crate::types::UpdateKind::name
+    #[serde(rename = "type")]
+    pub ty: String,
+
+    /// The id of the chat of the update, if it has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+
+    /// The date of the update, if it has one (e.g. the date of a message).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time: Option<DateTime<Utc>>,
+
+    /// The content type of [`data`](CloudEvent::data), always
+    /// `application/json`.
+    pub datacontenttype: String,
+
+    /// The update itself.
+    pub data: Update,
+}
+
+/// A destination of [`CloudEvent`]s.
+///
+/// This trait is implemented for async functions taking a [`CloudEvent`] and
+/// returning a `Result<(), E>`, where `E` is [`Display`], e.g.
+///
+/// ```
+/// use teloxide::utils::cloud_events::CloudEvent;
+///
+/// async fn print(event: CloudEvent) -> Result<(), serde_json::Error> {
+///     println!("{}", serde_json::to_string(&event)?);
+///     Ok(())
+/// }
+/// ```
+pub trait EventSink: Send + Sync + 'static {
+    /// An error returned from [`EventSink::publish`].
+    type Err: Display + Send;
+
+    /// Publishes `event`.
+    fn publish(&self, event: CloudEvent) -> BoxFuture<'static, Result<(), Self::Err>>;
+}
+
+impl<F, Fut, E> EventSink for F
+where
+    F: Fn(CloudEvent) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<(), E>> + Send + 'static,
+    E: Display + Send,
+{
+    type Err = E;
+
+    fn publish(&self, event: CloudEvent) -> BoxFuture<'static, Result<(), E>> {
+        Box::pin(self(event))
+    }
+}
+
+/// Publishes updates as [`CloudEvent`]s to an [`EventSink`].
+///
+/// The handler returned by [`CloudEventsExporter::into_handler`] publishes
+/// every update passing through it and then lets it through, so it can be put
+/// in front of the rest of the handler tree. Events are published one by one
+/// by a background task, in the order the handler is called, so a slow sink
+/// doesn't delay handling of updates. If more than [`queue_size`] events are
+/// waiting to be published, new events are dropped. Errors and timeouts of
+/// publishing are logged, and don't prevent the update from being handled.
+///
+/// [`queue_size`]: CloudEventsExporter::queue_size
+///
+/// ## Examples
+///
+/// ```no_run
+/// use teloxide::{prelude::*, utils::cloud_events::CloudEventsExporter};
+///
+/// # async fn run() {
+/// let bot = Bot::from_env();
+///
+/// let exporter = CloudEventsExporter::new("https://t.me/my_bot", |event| async move {
+///     // Send `event` to a message broker here
+///     println!("{}", serde_json::to_string(&event)?);
+///     Ok::<_, serde_json::Error>(())
+/// });
+///
+/// let handler =
+///     exporter.into_handler().branch(Update::filter_message().endpoint(|| async { respond(()) }));
+///
+/// Dispatcher::builder(bot, handler).build().dispatch().await;
+/// # }
+/// ```
+#[must_use]
+pub struct CloudEventsExporter<S> {
+    source: String,
+    type_prefix: String,
+    sink: Arc<S>,
+    queue_size: usize,
+    timeout: Duration,
+}
+
+impl<S> Clone for CloudEventsExporter<S> {
+    fn clone(&self) -> Self {
+        Self {
+            source: self.source.clone(),
+            type_prefix: self.type_prefix.clone(),
+            sink: Arc::clone(&self.sink),
+            queue_size: self.queue_size,
+            timeout: self.timeout,
+        }
+    }
+}
+
+impl<S> CloudEventsExporter<S>
+where
+    S: EventSink,
+{
+    /// Creates an exporter publishing events with the given `source` (a URI
+    /// reference identifying the bot, e.g. `https://t.me/my_bot`) to `sink`.
+    pub fn new<T>(source: T, sink: S) -> Self
+    where
+        T: Into<String>,
+    {
+        Self {
+            source: source.into(),
+            type_prefix: "org.telegram.update".to_owned(),
+            sink: Arc::new(sink),
+            queue_size: 1024,
+            timeout: Duration::from_secs(10),
+        }
+    }
+
+    /// Sets the prefix of the [types] of events.
+    ///
+    /// Default - `org.telegram.update`.
+    ///
+    /// [types]: CloudEvent::ty
+    pub fn type_prefix<T>(self, type_prefix: T) -> Self
+    where
+        T: Into<String>,
+    {
+        Self { type_prefix: type_prefix.into(), ..self }
+    }
+
+    /// Sets how many events can wait to be published by the background task,
+    /// before new events are dropped.
+    ///
+    /// Default - 1024.
+    ///
+    /// ## Panics
+    ///
+    /// If `queue_size` is zero.
+    #[track_caller]
+    pub fn queue_size(self, queue_size: usize) -> Self {
+        assert!(queue_size > 0, "queue size must be greater than zero");
+        Self { queue_size, ..self }
+    }
+
+    /// Sets for how long the background task waits for an event to be
+    /// published, before giving up on it and moving to the next one.
+    ///
+    /// Default - 10 seconds.
+    pub fn timeout(self, timeout: Duration) -> Self {
+        Self { timeout, ..self }
+    }
+
+    /// Wraps `update` into a [`CloudEvent`].
+    #[must_use]
+    pub fn event(&self, update: Update) -> CloudEvent {
+        CloudEvent {
+            specversion: SPEC_VERSION.to_owned(),
+            id: update.id.0.to_string(),
+            source: self.source.clone(),
+            ty: format!("{}.{}", self.type_prefix, update.kind.name()),
+            subject: update.chat().map(|chat| chat.id.to_string()),
+            time: date_of(&update),
+            datacontenttype: "application/json".to_owned(),
+            data: update,
+        }
+    }
+
+    /// Publishes `update` to the sink.
+    pub async fn export(&self, update: Update) -> Result<(), S::Err> {
+        self.sink.publish(self.event(update)).await
+    }
+
+    /// Returns a handler, which queues updates to be published in the
+    /// background and passes them further.
+    ///
+    /// The background task is spawned when the first update is handled.
+    ///
+    /// ## Dependency requirements
+    ///
+    ///  - [`crate::types::Update`]
+    pub fn into_handler<Output>(self) -> Handler<'static, Output, DpHandlerDescription>
+    where
+        Output: Send + Sync + 'static,
+    {
+        let queue = OnceLock::new();
+
+        dptree::inspect(move |update: Update| {
+            let queue = queue.get_or_init(|| self.clone().spawn_publisher());
+            if let Err(err) = queue.try_send(self.event(update)) {
+                log::warn!(
+                    "Dropping a CloudEvent, since the queue is full: {}",
+                    err.into_inner().id
+                );
+            }
+        })
+    }
+
+    /// Spawns a task publishing events sent to the returned queue.
+    fn spawn_publisher(self) -> mpsc::Sender<CloudEvent> {
+        let (tx, mut rx) = mpsc::channel::<CloudEvent>(self.queue_size);
+
+        tokio::spawn(async move {
+            while let Some(event) = rx.recv().await {
+                let id = event.id.clone();
+                match tokio::time::timeout(self.timeout, self.sink.publish(event)).await {
+                    Ok(Ok(())) => {}
+                    Ok(Err(err)) => log::error!("Failed to publish a CloudEvent: {err}"),
+                    Err(_) => log::error!("Publishing CloudEvent {id} timed out"),
+                }
+            }
+        });
+
+        tx
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{message_json, update};
+
+    fn edited_message(id: u32) -> Update {
+        let mut message = message_json(42, "hi");
+        message["edit_date"] = 2.into();
+        update(id, "edited_message", message)
+    }
+
+    #[tokio::test]
+    async fn export() {
+        let (tx, mut events) = mpsc::unbounded_channel();
+        let sink = move |event: CloudEvent| {
+            tx.send(event).unwrap();
+            async { Ok::<_, std::convert::Infallible>(()) }
+        };
+        let handler = CloudEventsExporter::new("https://t.me/bot", sink)
+            .type_prefix("bot")
+            .into_handler()
+            .endpoint(|| async {});
+
+        assert!(handler.dispatch(dptree::deps![edited_message(7)]).await.is_break());
+
+        let event = events.recv().await.unwrap();
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            serde_json::json!({
+                "specversion": "1.0",
+                "id": "7",
+                "source": "https://t.me/bot",
+                "type": "bot.edited_message",
+                "subject": "42",
+                "time": "1970-01-01T00:00:01Z",
+                "datacontenttype": "application/json",
+                "data": serde_json::to_value(&event.data).unwrap(),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn slow_sink() {
+        let (tx, mut events) = mpsc::unbounded_channel();
+        // Publishing the first event never completes
+        let sink = move |event: CloudEvent| {
+            let stuck = event.id == "1";
+            tx.send(event.id).unwrap();
+            async move {
+                if stuck {
+                    futures::future::pending::<()>().await;
+                }
+                Ok::<_, std::convert::Infallible>(())
+            }
+        };
+        let handler = CloudEventsExporter::new("https://t.me/bot", sink)
+            .queue_size(1)
+            .timeout(Duration::from_millis(50))
+            .into_handler()
+            .endpoint(|| async {});
+
+        // Updates are handled right away, even though the sink is stuck
+        assert!(handler.dispatch(dptree::deps![edited_message(1)]).await.is_break());
+        assert_eq!(events.recv().await.unwrap(), "1");
+        for id in 2..=4 {
+            assert!(handler.dispatch(dptree::deps![edited_message(id)]).await.is_break());
+        }
+
+        // Only one event fits in the queue, the rest are dropped
+        assert_eq!(events.recv().await.unwrap(), "2");
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(events.try_recv().is_err());
+
+        // The sink isn't waited for once it timed out
+        assert!(handler.dispatch(dptree::deps![edited_message(5)]).await.is_break());
+        assert_eq!(events.recv().await.unwrap(), "5");
+    }
+}
@@ -51,14 +51,22 @@
 
 use core::fmt;
 use std::{
+    borrow::Cow,
     error::Error,
     fmt::{Display, Formatter, Write},
+    str::FromStr,
 };
 
 use teloxide_core::types::{BotCommand, Me};
 #[cfg(feature = "macros")]
 pub use teloxide_macros::BotCommands;
 
+pub use menu::{fix, verify, ChangedDescription, CommandMenu, CommandMenuDiff};
+
+use crate::utils::{html, markdown};
+
+mod menu;
+
 /// An enumeration of bot's commands.
 ///
 /// # Example
@@ -96,7 +104,7 @@ pub use teloxide_macros::BotCommands;
 ///     Possible values:
 ///       - `default` - the same as the unspecified parser. It only puts all
 ///         text after the first space into the first argument, which must
-///         implement [`FromStr`].
+///         implement [`CommandArg`] (e.g. any type implementing [`FromStr`]).
 ///
 /// ## Example
 /// ```
@@ -116,7 +124,8 @@ pub use teloxide_macros::BotCommands;
 ///
 ///  - `split` - separates a message by a given separator (the default is the
 ///    space character) and parses each part into the corresponding arguments,
-///    which must implement [`FromStr`].
+///    which must implement [`CommandArg`]. Arguments in double quotes can
+///    contain the separator, see [`split_args`].
 ///
 /// ## Example
 /// ```
@@ -208,6 +217,14 @@ pub use teloxide_macros::BotCommands;
 /// 8. `#[command(hide_aliases)]` Hide all aliases of a command from the help
 ///    message.
 ///
+/// 9. `#[command(category = "category")]` Show a command under the `category`
+///    heading in the help message. See [`CommandDescriptions`].
+///
+/// 10. `#[command(localized(uk = "description"))]` Give your command a
+///     description in another language, selected with
+///     [`CommandDescriptions::locale`]. Write `_` instead of `-` in language
+///     codes, e.g. `pt_br` for `pt-br`.
+///
 /// ## Example
 /// ```
 /// # #[cfg(feature = "macros")] {
@@ -288,6 +305,9 @@ pub enum ParseError {
     /// [`FromStr::from_str`]: https://doc.rust-lang.org/std/str/trait.FromStr.html#tymethod.from_str
     IncorrectFormat(Box<dyn Error + Send + Sync + 'static>),
 
+    /// An argument parsed with [`CommandArg`] is invalid.
+    InvalidArgument(ArgumentError),
+
     UnknownCommand(PrefixedBotCommand),
     WrongBotName(BotName),
 
@@ -295,16 +315,154 @@ pub enum ParseError {
     Custom(Box<dyn Error + Send + Sync + 'static>),
 }
 
+/// An argument of a command, parsed by the parsers generated by
+/// [`BotCommands`].
+///
+/// This is implemented for all types implementing [`FromStr`], so e.g.
+/// integers or [`humantime::Duration`] can be used as arguments. Implement it
+/// for your own types to control the name of the format shown in errors.
+///
+/// ## Examples
+///
+/// ```
+/// use std::{borrow::Cow, error::Error};
+///
+/// use teloxide::utils::command::{parse_arg, CommandArg};
+///
+/// #[derive(Debug)]
+/// struct Percent(u8);
+///
+/// impl CommandArg for Percent {
+///     fn parse_arg(s: &str) -> Result<Self, Box<dyn Error + Send + Sync>> {
+///         let n = s.strip_suffix('%').ok_or("no `%`")?.parse()?;
+///         if n > 100 {
+///             return Err("more than 100%".into());
+///         }
+///         Ok(Percent(n))
+///     }
+///
+///     fn expected() -> Cow<'static, str> {
+///         "percentage, e.g. `50%`".into()
+///     }
+/// }
+///
+/// assert!(parse_arg::<Percent>("42%", 1).is_ok());
+/// assert_eq!(
+///     parse_arg::<Percent>("42", 2).unwrap_err().to_string(),
+///     "argument 2: expected percentage, e.g. `50%`, found `42` (no `%`)"
+/// );
+/// assert_eq!(
+///     parse_arg::<u8>("x", 1).unwrap_err().to_string(),
+///     "argument 1: expected u8, found `x` (invalid digit found in string)"
+/// );
+/// ```
+///
+/// [`FromStr`]: std::str::FromStr
+/// [`humantime::Duration`]: https://docs.rs/humantime/latest/humantime/struct.Duration.html
+pub trait CommandArg: Sized {
+    /// Parses the argument.
+    fn parse_arg(s: &str) -> Result<Self, Box<dyn Error + Send + Sync + 'static>>;
+
+    /// Returns a human-readable name of the format of the argument, e.g.
+    /// `duration`.
+    fn expected() -> Cow<'static, str>;
+}
+
+impl<T> CommandArg for T
+where
+    T: FromStr,
+    T::Err: Into<Box<dyn Error + Send + Sync + 'static>>,
+{
+    fn parse_arg(s: &str) -> Result<Self, Box<dyn Error + Send + Sync + 'static>> {
+        T::from_str(s).map_err(Into::into)
+    }
+
+    /// Returns the name of the type, without the module path and split into
+    /// lowercase words, e.g. `naive date` for `chrono::NaiveDate`.
+    fn expected() -> Cow<'static, str> {
+        let name = std::any::type_name::<T>();
+        let name = name.split('<').next().unwrap_or(name);
+        let name = name.rsplit("::").next().unwrap_or(name);
+
+        let mut expected = String::with_capacity(name.len());
+        let mut prev_lowercase = false;
+        for c in name.chars() {
+            if c.is_uppercase() && prev_lowercase {
+                expected.push(' ');
+            }
+            prev_lowercase = c.is_lowercase() || c.is_ascii_digit();
+            expected.extend(c.to_lowercase());
+        }
+        expected.into()
+    }
+}
+
+/// An error of parsing an argument with [`CommandArg`].
+#[derive(Debug)]
+pub struct ArgumentError {
+    /// The position of the argument, starting from 1.
+    pub position: usize,
+    /// The expected format, see [`CommandArg::expected`].
+    pub expected: Cow<'static, str>,
+    /// The text of the argument.
+    pub found: String,
+    /// The error returned from [`CommandArg::parse_arg`].
+    pub source: Box<dyn Error + Send + Sync + 'static>,
+}
+
+impl Display for ArgumentError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let Self { position, expected, found, source } = self;
+        write!(f, "argument {position}: expected {expected}, found `{found}` ({source})")
+    }
+}
+
+impl Error for ArgumentError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&*self.source)
+    }
+}
+
+/// Parses `s`, the argument number `position` (starting from 1), with
+/// [`CommandArg`].
+///
+/// This is used by the parsers generated by [`BotCommands`], and can be used
+/// in custom parsers to report errors the same way.
+pub fn parse_arg<T>(s: &str, position: usize) -> Result<T, ParseError>
+where
+    T: CommandArg,
+{
+    T::parse_arg(s).map_err(|source| {
+        ParseError::InvalidArgument(ArgumentError {
+            position,
+            expected: T::expected(),
+            found: s.to_owned(),
+            source,
+        })
+    })
+}
+
 /// Command descriptions that can be shown to the user (e.g. as a part of
 /// `/help` message)
 ///
 /// Most of the time you don't need to create this struct yourself as it's
 /// returned from [`BotCommands::descriptions`].
+///
+/// Commands are grouped by their [categories], commands without a category
+/// are shown first. Besides the plain text returned by the [`Display`]
+/// implementation, the descriptions can be rendered with [`to_html`] and
+/// [`to_markdown`].
+///
+/// [categories]: CommandDescription::category
+/// [`to_html`]: CommandDescriptions::to_html
+/// [`to_markdown`]: CommandDescriptions::to_markdown
 #[derive(Debug, Clone)]
 pub struct CommandDescriptions<'a> {
     global_description: Option<&'a str>,
     descriptions: &'a [CommandDescription<'a>],
     bot_username: Option<&'a str>,
+    locale: Option<&'a str>,
+    hidden_categories: &'a [&'a str],
 }
 
 /// Description of a particular command, used in [`CommandDescriptions`].
@@ -318,13 +476,45 @@ pub struct CommandDescription<'a> {
     pub aliases: &'a [&'a str],
     /// Human-readable description of the command.
     pub description: &'a str,
+    /// The category of the command, e.g. `Admin`, under which it's shown in
+    /// the help message.
+    pub category: Option<&'a str>,
+    /// Pairs of a language code and the description in this language, e.g.
+    /// `[("uk", "показати це повідомлення")]`.
+    pub localized: &'a [(&'a str, &'a str)],
+}
+
+impl<'a> CommandDescription<'a> {
+    /// Returns the description in the language `locale`, or the default
+    /// [`description`] if there is no such translation.
+    ///
+    /// If there is no translation to e.g. `pt-br`, the one to `pt` is used.
+    ///
+    /// [`description`]: CommandDescription::description
+    #[must_use]
+    pub fn description_in(&self, locale: &str) -> &'a str {
+        let find = |locale: &str| {
+            self.localized
+                .iter()
+                .find(|(lang, _)| lang.eq_ignore_ascii_case(locale))
+                .map(|&(_, description)| description)
+        };
+
+        find(locale).or_else(|| find(locale.split('-').next()?)).unwrap_or(self.description)
+    }
 }
 
 impl<'a> CommandDescriptions<'a> {
     /// Creates new [`CommandDescriptions`] from a list of command descriptions.
     #[must_use]
     pub const fn new(descriptions: &'a [CommandDescription<'a>]) -> Self {
-        Self { global_description: None, descriptions, bot_username: None }
+        Self {
+            global_description: None,
+            descriptions,
+            bot_username: None,
+            locale: None,
+            hidden_categories: &[],
+        }
     }
 
     /// Sets the global description of these commands.
@@ -350,12 +540,16 @@ impl<'a> CommandDescriptions<'a> {
     ///         command: "start",
     ///         description: "start this bot",
     ///         aliases: &[],
+    ///         category: None,
+    ///         localized: &[],
     ///     },
     ///     CommandDescription {
     ///         prefix: "/",
     ///         command: "help",
     ///         description: "show this message",
     ///         aliases: &[],
+    ///         category: None,
+    ///         localized: &[],
     ///     },
     /// ]);
     ///
@@ -381,6 +575,178 @@ impl<'a> CommandDescriptions<'a> {
     pub fn username_from_me(self, me: &'a Me) -> CommandDescriptions<'a> {
         self.username(me.user.username.as_deref().expect("Bots must have usernames"))
     }
+
+    /// Selects the language of the descriptions.
+    ///
+    /// `locale` is a language code such as `uk` or `pt-br`, e.g. the
+    /// [`language_code`] of the user asking for help. Commands without a
+    /// description in this language are shown with the default one. See
+    /// [`CommandDescription::description_in`].
+    ///
+    /// [`language_code`]: crate::types::User::language_code
+    #[must_use]
+    pub fn locale(self, locale: &'a str) -> Self {
+        Self { locale: Some(locale), ..self }
+    }
+
+    /// Hides commands of the given categories.
+    ///
+    /// This is useful to show e.g. admin commands only to admins.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use teloxide::utils::command::{CommandDescription, CommandDescriptions};
+    ///
+    /// let descriptions = CommandDescriptions::new(&[
+    ///     CommandDescription {
+    ///         prefix: "/",
+    ///         command: "help",
+    ///         description: "show this message",
+    ///         aliases: &[],
+    ///         category: None,
+    ///         localized: &[("uk", "показати це повідомлення")],
+    ///     },
+    ///     CommandDescription {
+    ///         prefix: "/",
+    ///         command: "ban",
+    ///         description: "ban a user",
+    ///         aliases: &[],
+    ///         category: Some("Admin"),
+    ///         localized: &[],
+    ///     },
+    /// ]);
+    ///
+    /// assert_eq!(descriptions.to_string(), "/help — show this message\n\nAdmin\n/ban — ban a user");
+    /// assert_eq!(
+    ///     descriptions.hide_categories(&["Admin"]).locale("uk").to_string(),
+    ///     "/help — показати це повідомлення"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn hide_categories(self, categories: &'a [&'a str]) -> Self {
+        Self { hidden_categories: categories, ..self }
+    }
+
+    /// Renders the descriptions for the [HTML] parse mode, with category
+    /// names in bold.
+    ///
+    /// [HTML]: crate::types::ParseMode::Html
+    #[must_use]
+    pub fn to_html(&self) -> String {
+        let mut out = String::new();
+        // Unwrap: writing to a `String` never fails
+        self.render(&mut out, Style::Html).unwrap();
+        out
+    }
+
+    /// Renders the descriptions for the [MarkdownV2] parse mode, with category
+    /// names in bold.
+    ///
+    /// [MarkdownV2]: crate::types::ParseMode::MarkdownV2
+    #[must_use]
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        // Unwrap: writing to a `String` never fails
+        self.render(&mut out, Style::Markdown).unwrap();
+        out
+    }
+
+    /// Returns visible commands grouped by category, commands without a
+    /// category first and the rest in the order of the first appearance of
+    /// their category.
+    fn sections(&self) -> Vec<(Option<&'a str>, Vec<&'a CommandDescription<'a>>)> {
+        let mut sections: Vec<(Option<&str>, Vec<_>)> = vec![(None, Vec::new())];
+        for descr in self.descriptions {
+            if descr.category.is_some_and(|c| self.hidden_categories.contains(&c)) {
+                continue;
+            }
+
+            match sections.iter_mut().find(|(category, _)| *category == descr.category) {
+                Some((_, commands)) => commands.push(descr),
+                None => sections.push((descr.category, vec![descr])),
+            }
+        }
+
+        sections.retain(|(_, commands)| !commands.is_empty());
+        sections
+    }
+
+    fn render(&self, f: &mut dyn Write, style: Style) -> fmt::Result {
+        if let Some(global_description) = self.global_description {
+            f.write_str(&style.escape(global_description))?;
+            f.write_str("\n\n")?;
+        }
+
+        let format_command = |command: &str, prefix: &str, f: &mut dyn Write| {
+            let mut full = format!("{prefix}{command}");
+            if let Some(username) = self.bot_username {
+                full.push('@');
+                full.push_str(username);
+            }
+            f.write_str(&style.escape(&full))
+        };
+
+        for (i, (category, commands)) in self.sections().into_iter().enumerate() {
+            if i != 0 {
+                f.write_str("\n\n")?;
+            }
+
+            if let Some(category) = category {
+                f.write_str(&style.bold(category))?;
+                f.write_char('\n')?;
+            }
+
+            for (j, descr) in commands.into_iter().enumerate() {
+                if j != 0 {
+                    f.write_char('\n')?;
+                }
+
+                format_command(descr.command, descr.prefix, f)?;
+                for alias in descr.aliases {
+                    f.write_str(", ")?;
+                    format_command(alias, descr.prefix, f)?;
+                }
+
+                let description = match self.locale {
+                    Some(locale) => descr.description_in(locale),
+                    None => descr.description,
+                };
+                if !description.is_empty() {
+                    f.write_str(" — ")?;
+                    f.write_str(&style.escape(description))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The markup [`CommandDescriptions`] are rendered with.
+#[derive(Clone, Copy)]
+enum Style {
+    Plain,
+    Html,
+    Markdown,
+}
+
+impl Style {
+    fn escape<'s>(self, s: &'s str) -> Cow<'s, str> {
+        match self {
+            Style::Plain => Cow::Borrowed(s),
+            Style::Html => Cow::Owned(html::escape(s)),
+            Style::Markdown => Cow::Owned(markdown::escape(s)),
+        }
+    }
+
+    fn bold(self, s: &str) -> String {
+        match self {
+            Style::Plain => s.to_owned(),
+            Style::Html => html::bold(&html::escape(s)),
+            Style::Markdown => markdown::bold(&markdown::escape(s)),
+        }
+    }
 }
 
 /// Parses a string into a command with args.
@@ -419,6 +785,10 @@ where
 ///
 /// `prefix`: symbols, which denote start of a command.
 ///
+/// Arguments are separated by whitespace, except for arguments in double
+/// quotes, which can contain whitespace (the quotes are removed). Use
+/// [`parse_command_raw`] to get the arguments as they are.
+///
 /// ## Example
 /// ```
 /// use teloxide::utils::command::parse_command_with_prefix;
@@ -427,6 +797,11 @@ where
 /// let (command, args) = parse_command_with_prefix("!", text, "").unwrap();
 /// assert_eq!(command, "mute");
 /// assert_eq!(args, vec!["5", "hours"]);
+///
+/// let text = r#"!say@MyBot "hello world" twice"#;
+/// let (command, args) = parse_command_with_prefix("!", text, "mybot").unwrap();
+/// assert_eq!(command, "say");
+/// assert_eq!(args, vec!["hello world", "twice"]);
 /// ```
 ///
 /// If the name of a bot does not match, it will return `None`:
@@ -444,19 +819,136 @@ pub fn parse_command_with_prefix<'a, N>(
 where
     N: AsRef<str>,
 {
-    if !text.starts_with(prefix) {
-        return None;
+    let (command, mut rest) = parse_command_raw(prefix, text, bot_name)?;
+
+    let mut args = Vec::new();
+    while !rest.is_empty() {
+        let (arg, tail) = rest
+            .strip_prefix('"')
+            .and_then(|quoted| {
+                closing_quote(quoted, |tail| {
+                    tail.is_empty() || tail.starts_with(char::is_whitespace)
+                })
+            })
+            .unwrap_or_else(|| rest.split_once(char::is_whitespace).unwrap_or((rest, "")));
+
+        args.push(arg);
+        rest = tail.trim_start();
     }
-    let mut words = text.split_whitespace();
-    let mut split = words.next()?[prefix.len()..].split('@');
+
+    Some((command, args))
+}
+
+/// Parses a string into a command and the rest of the text (custom prefix).
+///
+/// Unlike [`parse_command_with_prefix`], this doesn't split the arguments, so
+/// the remainder is returned as it is, only without the whitespace after the
+/// command. The `@bot_name` suffix of the command is compared with `bot_name`
+/// case-insensitively.
+///
+/// ## Example
+/// ```
+/// use teloxide::utils::command::parse_command_raw;
+///
+/// let text = "/say@MyBot  \"hello\"   world";
+/// let (command, rest) = parse_command_raw("/", text, "mybot").unwrap();
+/// assert_eq!(command, "say");
+/// assert_eq!(rest, "\"hello\"   world");
+///
+/// assert!(parse_command_raw("/", "/say@OtherBot hi", "MyBot").is_none());
+/// ```
+pub fn parse_command_raw<'a, N>(
+    prefix: &str,
+    text: &'a str,
+    bot_name: N,
+) -> Option<(&'a str, &'a str)>
+where
+    N: AsRef<str>,
+{
+    let text = text.strip_prefix(prefix)?;
+    let (full_command, rest) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+
+    let mut split = full_command.split('@');
     let command = split.next()?;
-    let bot = split.next();
-    match bot {
+    match split.next() {
         Some(name) if name.eq_ignore_ascii_case(bot_name.as_ref()) => {}
         None => {}
         _ => return None,
     }
-    Some((command, words.collect()))
+
+    Some((command, rest.trim_start()))
+}
+
+/// Splits arguments of a command by `separator`, keeping arguments in double
+/// quotes together.
+///
+/// An argument which starts with `"` and ends with `"` right before the
+/// separator (or the end of the text) is returned without the quotes, even if
+/// it contains the separator. Other quotes are returned as they are. This is
+/// used by the `split` parser generated by [`BotCommands`].
+///
+/// Like [`str::split`], this returns an empty argument for an empty text and
+/// for consecutive separators.
+///
+/// ## Example
+/// ```
+/// use teloxide::utils::command::split_args;
+///
+/// let args: Vec<_> = split_args(r#""hello world" 5 "unterminated"#, " ").collect();
+/// assert_eq!(args, ["hello world", "5", "\"unterminated"]);
+///
+/// let args: Vec<_> = split_args(r#"a|"b|c"|d"#, "|").collect();
+/// assert_eq!(args, ["a", "b|c", "d"]);
+/// ```
+pub fn split_args<'a>(text: &'a str, separator: &'a str) -> SplitArgs<'a> {
+    SplitArgs { rest: Some(text), separator }
+}
+
+/// An iterator over arguments of a command, see [`split_args`].
+#[derive(Debug, Clone)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct SplitArgs<'a> {
+    rest: Option<&'a str>,
+    separator: &'a str,
+}
+
+impl<'a> Iterator for SplitArgs<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        let rest = self.rest?;
+        let separator = self.separator;
+
+        if separator.is_empty() {
+            self.rest = None;
+            return Some(rest);
+        }
+
+        let quoted = rest.strip_prefix('"').and_then(|quoted| {
+            closing_quote(quoted, |tail| tail.is_empty() || tail.starts_with(separator))
+        });
+        let (arg, tail) = match quoted {
+            Some((arg, tail)) => (arg, tail.strip_prefix(separator)),
+            None => match rest.split_once(separator) {
+                Some((arg, tail)) => (arg, Some(tail)),
+                None => (rest, None),
+            },
+        };
+
+        self.rest = tail;
+        Some(arg)
+    }
+}
+
+/// Finds the closing quote in `quoted` (a text after an opening quote), which
+/// is followed by a text satisfying `is_end`.
+///
+/// Returns the text between the quotes and the text after the closing quote.
+fn closing_quote(quoted: &str, is_end: impl Fn(&str) -> bool) -> Option<(&str, &str)> {
+    quoted
+        .match_indices('"')
+        .map(|(i, _)| (&quoted[..i], &quoted[i + 1..]))
+        .find(|(_, tail)| is_end(tail))
 }
 
 impl Display for ParseError {
@@ -471,6 +963,7 @@ impl Display for ParseError {
                 "Too many arguments (expected {expected}, found {found}, message = '{message}')"
             ),
             ParseError::IncorrectFormat(e) => write!(f, "Incorrect format of command args: {e}"),
+            ParseError::InvalidArgument(e) => write!(f, "{e}"),
             ParseError::UnknownCommand(e) => write!(f, "Unknown command: {e}"),
             ParseError::WrongBotName(n) => write!(f, "Wrong bot name: {n}"),
             ParseError::Custom(e) => write!(f, "{e}"),
@@ -482,48 +975,7 @@ impl std::error::Error for ParseError {}
 
 impl Display for CommandDescriptions<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if let Some(global_description) = self.global_description {
-            f.write_str(global_description)?;
-            f.write_str("\n\n")?;
-        }
-
-        let format_command = |command: &str, prefix: &str, formater: &mut fmt::Formatter<'_>| {
-            formater.write_str(prefix)?;
-            formater.write_str(command)?;
-            if let Some(username) = self.bot_username {
-                formater.write_char('@')?;
-                formater.write_str(username)?;
-            }
-            fmt::Result::Ok(())
-        };
-
-        let mut write = |&CommandDescription { prefix, command, aliases, description }, nls| {
-            if nls {
-                f.write_char('\n')?;
-            }
-
-            format_command(command, prefix, f)?;
-            for alias in aliases {
-                f.write_str(", ")?;
-                format_command(alias, prefix, f)?;
-            }
-
-            if !description.is_empty() {
-                f.write_str(" — ")?;
-                f.write_str(description)?;
-            }
-
-            fmt::Result::Ok(())
-        };
-
-        if let Some(descr) = self.descriptions.first() {
-            write(descr, false)?;
-            for descr in &self.descriptions[1..] {
-                write(descr, true)?;
-            }
-        }
-
-        Ok(())
+        self.render(f, Style::Plain)
     }
 }
 
@@ -541,6 +993,14 @@ mod tests {
         assert_eq!(actual, expected)
     }
 
+    #[test]
+    fn parse_command_with_quoted_args() {
+        let data = r#"/command@Bot  "arg 1"  arg"2 "arg 3"#;
+        let expected = Some(("command", vec!["arg 1", "arg\"2", "\"arg", "3"]));
+        let actual = parse_command(data, "bot");
+        assert_eq!(actual, expected)
+    }
+
     #[test]
     fn parse_command_with_args_without_args() {
         let data = "/command";
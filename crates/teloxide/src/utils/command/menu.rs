@@ -0,0 +1,333 @@
+use std::fmt;
+
+use teloxide_core::{
+    requests::{HasPayload, Requester},
+    types::{BotCommand, BotCommandScope},
+};
+
+use crate::utils::command::BotCommands;
+
+/// A list of commands expected to be shown to users for a given scope and
+/// language.
+///
+/// See [`verify`] and [`fix`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommandMenu {
+    /// The expected commands, in the order they should be shown.
+    pub commands: Vec<BotCommand>,
+
+    /// The scope of users the menu is shown to. `None` means
+    /// [`BotCommandScope::Default`].
+    pub scope: Option<BotCommandScope>,
+
+    /// The language of users the menu is shown to. `None` means all users
+    /// from the given scope, for whose language there are no dedicated
+    /// commands.
+    pub language_code: Option<String>,
+}
+
+impl CommandMenu {
+    /// Creates a menu with the given commands for the default scope and
+    /// language.
+    #[must_use]
+    pub fn new<C>(commands: C) -> Self
+    where
+        C: IntoIterator<Item = BotCommand>,
+    {
+        Self { commands: commands.into_iter().collect(), scope: None, language_code: None }
+    }
+
+    /// Creates a menu with the commands of `C`, as returned by
+    /// [`BotCommands::bot_commands`].
+    #[must_use]
+    pub fn of<C>() -> Self
+    where
+        C: BotCommands,
+    {
+        Self::new(C::bot_commands())
+    }
+
+    /// Sets the scope of users the menu is shown to.
+    #[must_use]
+    pub fn scope(self, scope: BotCommandScope) -> Self {
+        Self { scope: Some(scope), ..self }
+    }
+
+    /// Sets the language of users the menu is shown to.
+    #[must_use]
+    pub fn language_code<S>(self, language_code: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self { language_code: Some(language_code.into()), ..self }
+    }
+}
+
+/// A difference between an expected [`CommandMenu`] and the commands that are
+/// actually set for the bot.
+///
+/// Commands are compared by name, ignoring the leading `/`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CommandMenuDiff {
+    /// The scope of the compared menu.
+    pub scope: Option<BotCommandScope>,
+
+    /// The language of the compared menu.
+    pub language_code: Option<String>,
+
+    /// Expected commands that are not set.
+    pub missing: Vec<BotCommand>,
+
+    /// Set commands that are not expected.
+    pub unexpected: Vec<BotCommand>,
+
+    /// Commands that are set with a different description.
+    pub changed: Vec<ChangedDescription>,
+
+    /// `true` if the commands that are both expected and set are shown in a
+    /// different order.
+    pub reordered: bool,
+}
+
+/// A command whose description differs from the expected one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChangedDescription {
+    /// The name of the command, without the leading `/`.
+    pub command: String,
+
+    /// The expected description.
+    pub expected: String,
+
+    /// The description that is actually set.
+    pub actual: String,
+}
+
+impl CommandMenuDiff {
+    /// Compares `expected` commands with the `actual` ones.
+    #[must_use]
+    pub fn compute(expected: &[BotCommand], actual: &[BotCommand]) -> Self {
+        let find = |commands: &[BotCommand], name: &str| {
+            commands.iter().find(|c| command_name(c) == name).cloned()
+        };
+
+        let missing =
+            expected.iter().filter(|c| find(actual, command_name(c)).is_none()).cloned().collect();
+        let unexpected =
+            actual.iter().filter(|c| find(expected, command_name(c)).is_none()).cloned().collect();
+        let changed = expected
+            .iter()
+            .filter_map(|e| {
+                let a = find(actual, command_name(e))?;
+                (a.description != e.description).then(|| ChangedDescription {
+                    command: command_name(e).to_owned(),
+                    expected: e.description.clone(),
+                    actual: a.description,
+                })
+            })
+            .collect();
+
+        let common = |of: &[BotCommand], other: &[BotCommand]| {
+            of.iter()
+                .map(command_name)
+                .filter(|&name| other.iter().any(|c| command_name(c) == name))
+                .map(str::to_owned)
+                .collect::<Vec<_>>()
+        };
+        let reordered = common(expected, actual) != common(actual, expected);
+
+        Self { missing, unexpected, changed, reordered, ..Self::default() }
+    }
+
+    /// Returns `true` if the set commands match the expected ones.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.missing.is_empty()
+            && self.unexpected.is_empty()
+            && self.changed.is_empty()
+            && !self.reordered
+    }
+}
+
+impl fmt::Display for CommandMenuDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.scope {
+            Some(scope) => write!(f, "commands for {scope:?}")?,
+            None => f.write_str("commands for the default scope")?,
+        }
+        if let Some(language_code) = &self.language_code {
+            write!(f, " ({language_code})")?;
+        }
+        f.write_str(":")?;
+
+        if self.is_empty() {
+            return f.write_str(" up to date");
+        }
+        for c in &self.missing {
+            write!(f, "\n  + /{}: {}", command_name(c), c.description)?;
+        }
+        for c in &self.unexpected {
+            write!(f, "\n  - /{}: {}", command_name(c), c.description)?;
+        }
+        for c in &self.changed {
+            write!(f, "\n  ~ /{}: {:?} -> {:?}", c.command, c.actual, c.expected)?;
+        }
+        if self.reordered {
+            f.write_str("\n  commands are in a different order")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Compares the commands set for the bot with the expected menus.
+///
+/// For each menu this calls [`GetMyCommands`] with the scope and language of
+/// the menu and returns the differences for the menus that don't match. This
+/// is useful for smoke tests against a staging bot, to check that
+/// `/setcommands` is up to date with the commands the bot actually supports.
+///
+/// Use [`fix`] to also update the mismatching menus.
+///
+/// ## Examples
+///
+/// ```no_run
+/// # #[cfg(feature = "macros")] {
+/// use teloxide::{
+///     prelude::*,
+///     types::BotCommandScope,
+///     utils::command::{self, BotCommands, CommandMenu},
+/// };
+///
+/// #[derive(BotCommands)]
+/// #[command(rename_rule = "lowercase")]
+/// enum Command {
+///     /// Display this text.
+///     Help,
+/// }
+///
+/// #[derive(BotCommands)]
+/// #[command(rename_rule = "lowercase")]
+/// enum AdminCommand {
+///     /// Ban a user.
+///     Ban,
+/// }
+///
+/// # #[tokio::main] async fn main() -> Result<(), teloxide::RequestError> {
+/// let bot = Bot::from_env();
+/// let menus = [
+///     CommandMenu::of::<Command>(),
+///     CommandMenu::of::<AdminCommand>().scope(BotCommandScope::AllChatAdministrators),
+/// ];
+///
+/// let diffs = command::verify(&bot, &menus).await?;
+/// for diff in &diffs {
+///     eprintln!("{diff}");
+/// }
+/// assert!(diffs.is_empty());
+/// # Ok(()) }
+/// # }
+/// ```
+///
+/// [`GetMyCommands`]: crate::payloads::GetMyCommands
+pub async fn verify<R>(bot: &R, expected: &[CommandMenu]) -> Result<Vec<CommandMenuDiff>, R::Err>
+where
+    R: Requester,
+{
+    let mut diffs = Vec::new();
+
+    for menu in expected {
+        let mut request = bot.get_my_commands();
+        request.payload_mut().scope = menu.scope.clone();
+        request.payload_mut().language_code = menu.language_code.clone();
+        let actual = request.await?;
+
+        let diff = CommandMenuDiff {
+            scope: menu.scope.clone(),
+            language_code: menu.language_code.clone(),
+            ..CommandMenuDiff::compute(&menu.commands, &actual)
+        };
+        if !diff.is_empty() {
+            diffs.push(diff);
+        }
+    }
+
+    Ok(diffs)
+}
+
+/// Same as [`verify`], but also updates the menus that don't match.
+///
+/// Mismatching menus are replaced with [`SetMyCommands`], or removed with
+/// [`DeleteMyCommands`] if they are expected to be empty. Returns the
+/// differences that were fixed.
+///
+/// [`SetMyCommands`]: crate::payloads::SetMyCommands
+/// [`DeleteMyCommands`]: crate::payloads::DeleteMyCommands
+pub async fn fix<R>(bot: &R, expected: &[CommandMenu]) -> Result<Vec<CommandMenuDiff>, R::Err>
+where
+    R: Requester,
+{
+    let diffs = verify(bot, expected).await?;
+
+    for diff in &diffs {
+        let menu = expected
+            .iter()
+            .find(|m| m.scope == diff.scope && m.language_code == diff.language_code)
+            .expect("every diff is produced from one of the menus");
+
+        if menu.commands.is_empty() {
+            let mut request = bot.delete_my_commands();
+            request.payload_mut().scope = menu.scope.clone();
+            request.payload_mut().language_code = menu.language_code.clone();
+            request.await?;
+        } else {
+            let mut request = bot.set_my_commands(menu.commands.clone());
+            request.payload_mut().scope = menu.scope.clone();
+            request.payload_mut().language_code = menu.language_code.clone();
+            request.await?;
+        }
+    }
+
+    Ok(diffs)
+}
+
+fn command_name(command: &BotCommand) -> &str {
+    command.command.strip_prefix('/').unwrap_or(&command.command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commands(list: &[(&str, &str)]) -> Vec<BotCommand> {
+        list.iter().map(|&(c, d)| BotCommand::new(c, d)).collect()
+    }
+
+    #[test]
+    fn up_to_date() {
+        let expected = commands(&[("/help", "Help"), ("/start", "Start")]);
+        let actual = commands(&[("help", "Help"), ("start", "Start")]);
+
+        let diff = CommandMenuDiff::compute(&expected, &actual);
+        assert!(diff.is_empty());
+        assert_eq!(diff.to_string(), "commands for the default scope: up to date");
+    }
+
+    #[test]
+    fn differences() {
+        let expected = commands(&[("/help", "Help"), ("/start", "Start"), ("/ban", "Ban")]);
+        let actual = commands(&[("start", "Start the bot"), ("help", "Help"), ("old", "Old")]);
+
+        let diff = CommandMenuDiff::compute(&expected, &actual);
+        assert_eq!(diff.missing, commands(&[("/ban", "Ban")]));
+        assert_eq!(diff.unexpected, commands(&[("old", "Old")]));
+        assert_eq!(
+            diff.changed,
+            [ChangedDescription {
+                command: "start".to_owned(),
+                expected: "Start".to_owned(),
+                actual: "Start the bot".to_owned(),
+            }]
+        );
+        assert!(diff.reordered);
+    }
+}
@@ -0,0 +1,65 @@
+//! Helpers for loading options from config files with [`serde`].
+
+use serde::{de::Error, Deserialize, Deserializer};
+
+/// Deserializes a number greater than zero.
+pub(crate) fn positive<'de, D>(deserializer: D) -> Result<usize, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match usize::deserialize(deserializer)? {
+        0 => Err(D::Error::custom("expected a number greater than zero")),
+        n => Ok(n),
+    }
+}
+
+/// Deserializes an optional number greater than zero.
+pub(crate) fn positive_opt<'de, D>(deserializer: D) -> Result<Option<usize>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<usize>::deserialize(deserializer)? {
+        Some(0) => Err(D::Error::custom("expected a number greater than zero")),
+        n => Ok(n),
+    }
+}
+
+/// Deserializes an optional number in the range 1-100, which Telegram accepts
+/// for limits.
+pub(crate) fn limit_opt<'de, D>(deserializer: D) -> Result<Option<u8>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<u8>::deserialize(deserializer)? {
+        Some(n @ (0 | 101..)) => {
+            Err(D::Error::custom(format!("expected a number in range 1-100, got {n}")))
+        }
+        n => Ok(n),
+    }
+}
+
+/// (De)serializes an optional [`Duration`] as a number of seconds.
+///
+/// [`Duration`]: std::time::Duration
+pub(crate) mod secs_opt {
+    use std::time::Duration;
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub(crate) fn serialize<S>(
+        duration: &Option<Duration>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        duration.map(|d| d.as_secs()).serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<Option<Duration>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(Option::<u64>::deserialize(deserializer)?.map(Duration::from_secs))
+    }
+}
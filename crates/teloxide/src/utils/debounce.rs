@@ -0,0 +1,168 @@
+//! Coalescing of rapid successive updates.
+
+use std::{
+    collections::HashMap,
+    future::Future,
+    hash::Hash,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+/// Coalesces rapid successive events with the same key, delivering only the
+/// last one after no new events arrived for a given time window.
+///
+/// This is useful for noisy updates, such as repeated `edited_message`
+/// updates while a user is fixing typos, or storms of reaction changes, when
+/// only the final state matters.
+///
+/// Delivery happens in a separate task, so [`Debouncer::debounce`] returns
+/// immediately and doesn't block processing of other updates from the same
+/// chat.
+///
+/// Clones share the same state, so a debouncer can be passed to handlers as a
+/// dependency.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use teloxide::{prelude::*, types::MessageId, utils::debounce::Debouncer};
+///
+/// # async fn run() {
+/// let bot = Bot::from_env();
+/// let debouncer = Debouncer::<(ChatId, MessageId)>::new(Duration::from_secs(3));
+///
+/// let handler = Update::filter_edited_message().endpoint(
+///     |bot: Bot, msg: Message, debouncer: Debouncer<(ChatId, MessageId)>| async move {
+///         debouncer.debounce((msg.chat.id, msg.id), async move {
+///             // Only the last edit in a 3 seconds window gets here
+///             let _ = bot.send_message(msg.chat.id, "Edited!").await;
+///         });
+///         respond(())
+///     },
+/// );
+///
+/// Dispatcher::builder(bot, handler)
+///     .dependencies(dptree::deps![debouncer])
+///     .build()
+///     .dispatch()
+///     .await;
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Debouncer<K> {
+    window: Duration,
+    generation: Arc<AtomicU64>,
+    pending: Arc<Mutex<HashMap<K, u64>>>,
+}
+
+impl<K> Debouncer<K>
+where
+    K: Eq + Hash + Clone + Send + 'static,
+{
+    /// Creates a debouncer that waits for `window` of silence before
+    /// delivering an event.
+    #[must_use]
+    pub fn new(window: Duration) -> Self {
+        Self { window, generation: Arc::default(), pending: Arc::default() }
+    }
+
+    /// Returns the time window of this debouncer.
+    #[must_use]
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// Schedules `deliver` to run after the window closes, unless another
+    /// event with the same `key` arrives before that.
+    ///
+    /// Each new event restarts the window for its key and cancels the
+    /// delivery of the previous one.
+    ///
+    /// ## Panics
+    ///
+    /// If called outside of a tokio runtime.
+    pub fn debounce<F>(&self, key: K, deliver: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let generation = self.generation.fetch_add(1, Ordering::Relaxed);
+        self.pending.lock().unwrap().insert(key.clone(), generation);
+
+        let window = self.window;
+        let pending = Arc::clone(&self.pending);
+        tokio::spawn(async move {
+            tokio::time::sleep(window).await;
+
+            let is_last = {
+                let mut pending = pending.lock().unwrap();
+                let is_last = pending.get(&key) == Some(&generation);
+                if is_last {
+                    pending.remove(&key);
+                }
+                is_last
+            };
+
+            if is_last {
+                deliver.await;
+            }
+        });
+    }
+
+    /// Cancels the pending delivery for `key`.
+    ///
+    /// Returns `true` if there was a pending delivery.
+    pub fn cancel(&self, key: &K) -> bool {
+        self.pending.lock().unwrap().remove(key).is_some()
+    }
+
+    /// Returns `true` if there is a pending delivery for `key`.
+    #[must_use]
+    pub fn is_pending(&self, key: &K) -> bool {
+        self.pending.lock().unwrap().contains_key(key)
+    }
+}
+
+impl<K> Clone for Debouncer<K> {
+    fn clone(&self) -> Self {
+        Self {
+            window: self.window,
+            generation: Arc::clone(&self.generation),
+            pending: Arc::clone(&self.pending),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::mpsc;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn delivers_only_the_last_event() {
+        let debouncer = Debouncer::new(Duration::from_millis(50));
+        let (tx, mut rx) = mpsc::unbounded_channel();
+
+        for (key, value) in [(1, "a"), (2, "b"), (1, "c"), (3, "d")] {
+            let tx = tx.clone();
+            debouncer.debounce(key, async move { tx.send(value).unwrap() });
+        }
+        assert!(debouncer.cancel(&3));
+        assert!(debouncer.is_pending(&1));
+        drop(tx);
+
+        let mut delivered = Vec::new();
+        while let Some(value) = rx.recv().await {
+            delivered.push(value);
+        }
+        delivered.sort_unstable();
+
+        assert_eq!(delivered, ["b", "c"]);
+        assert!(!debouncer.is_pending(&1));
+    }
+}
@@ -0,0 +1,332 @@
+//! Building answers to inline queries.
+
+use std::{collections::HashSet, mem};
+
+use teloxide_core::{
+    payloads::AnswerInlineQuerySetters as _,
+    requests::Requester,
+    types::{
+        FileId, InlineQuery, InlineQueryResult, InlineQueryResultArticle,
+        InlineQueryResultCachedPhoto, InlineQueryResultCachedSticker, InlineQueryResultGif,
+        InlineQueryResultLocation, InlineQueryResultPhoto, InputMessageContent,
+        InputMessageContentText,
+    },
+};
+use url::Url;
+
+/// The maximum number of results in an answer to an inline query, as allowed
+/// by Telegram.
+pub const MAX_RESULTS_PER_PAGE: usize = 50;
+
+/// A builder of results of an inline query, answered with
+/// [`answerInlineQuery`].
+///
+/// Results added with the convenience constructors (e.g. [`article`]) get ids
+/// automatically when the results are built: consecutive numbers, skipping ids
+/// of results added with [`push`] (before or after them), which keep their own
+/// ids. Ids are stable as long as the same results are added in the same order.
+///
+/// If there are more results than fit in a [page], [`answer`] sends the page
+/// requested by the query's [`offset`], and sets [`next_offset`] so that
+/// Telegram requests the next one when the user scrolls down.
+///
+/// [`answerInlineQuery`]: crate::payloads::AnswerInlineQuery
+/// [`article`]: ResultsBuilder::article
+/// [`push`]: ResultsBuilder::push
+/// [page]: ResultsBuilder::page_size
+/// [`answer`]: ResultsBuilder::answer
+/// [`offset`]: InlineQuery::offset
+/// [`next_offset`]: crate::payloads::AnswerInlineQuery::next_offset
+///
+/// ## Examples
+///
+/// ```no_run
+/// use teloxide::{prelude::*, utils::inline::ResultsBuilder};
+///
+/// # async fn run(bot: Bot, query: InlineQuery) -> ResponseResult<()> {
+/// let words = ["apple", "banana", "cherry"];
+///
+/// words
+///     .iter()
+///     .filter(|word| word.starts_with(&query.query))
+///     .fold(ResultsBuilder::new(), |results, word| {
+///         results.article(*word, format!("I like {word}s")).description("Send this fruit")
+///     })
+///     .answer(&bot, &query)
+///     .await?;
+/// # Ok(()) }
+/// ```
+#[derive(Clone, Debug)]
+#[must_use]
+pub struct ResultsBuilder {
+    results: Vec<InlineQueryResult>,
+    page_size: usize,
+    /// Indices of results which get ids automatically.
+    auto_ids: Vec<usize>,
+}
+
+impl ResultsBuilder {
+    /// Creates an empty builder.
+    pub fn new() -> Self {
+        Self { results: Vec::new(), page_size: MAX_RESULTS_PER_PAGE, auto_ids: Vec::new() }
+    }
+
+    /// Sets the maximum number of results sent at once.
+    ///
+    /// By default, it's [`MAX_RESULTS_PER_PAGE`].
+    ///
+    /// ## Panics
+    ///
+    /// If `page_size` is zero or greater than [`MAX_RESULTS_PER_PAGE`].
+    #[track_caller]
+    pub fn page_size(self, page_size: usize) -> Self {
+        assert!(
+            (1..=MAX_RESULTS_PER_PAGE).contains(&page_size),
+            "`page_size` must be in 1..={MAX_RESULTS_PER_PAGE}"
+        );
+        Self { page_size, ..self }
+    }
+
+    /// Adds a result, keeping its id.
+    pub fn push<R>(mut self, result: R) -> Self
+    where
+        R: Into<InlineQueryResult>,
+    {
+        self.results.push(result.into());
+        self
+    }
+
+    /// Adds an article, which sends `text` when chosen.
+    pub fn article<T, S>(self, title: T, text: S) -> Self
+    where
+        T: Into<String>,
+        S: Into<String>,
+    {
+        let content = InputMessageContent::Text(InputMessageContentText::new(text));
+        self.push_auto(InlineQueryResultArticle::new("", title, content))
+    }
+
+    /// Adds a photo, by the URLs of the photo and its thumbnail.
+    pub fn photo(self, photo_url: Url, thumbnail_url: Url) -> Self {
+        self.push_auto(InlineQueryResultPhoto::new("", photo_url, thumbnail_url))
+    }
+
+    /// Adds a photo stored on the Telegram servers.
+    pub fn cached_photo(self, file_id: FileId) -> Self {
+        self.push_auto(InlineQueryResultCachedPhoto::new("", file_id))
+    }
+
+    /// Adds a GIF, by the URLs of the GIF and its thumbnail.
+    pub fn gif(self, gif_url: Url, thumbnail_url: Url) -> Self {
+        self.push_auto(InlineQueryResultGif::new("", gif_url, thumbnail_url))
+    }
+
+    /// Adds a sticker stored on the Telegram servers.
+    pub fn cached_sticker(self, file_id: FileId) -> Self {
+        self.push_auto(InlineQueryResultCachedSticker::new("", file_id))
+    }
+
+    /// Adds a location.
+    pub fn location<T>(self, title: T, latitude: f64, longitude: f64) -> Self
+    where
+        T: Into<String>,
+    {
+        self.push_auto(InlineQueryResultLocation::new("", title, latitude, longitude))
+    }
+
+    /// Sets the description of the last added result.
+    ///
+    /// Does nothing if there are no results, or if the last result doesn't
+    /// have a description (e.g. a sticker).
+    pub fn description<S>(mut self, description: S) -> Self
+    where
+        S: Into<String>,
+    {
+        let description = Some(description.into());
+        match self.results.last_mut() {
+            Some(InlineQueryResult::Article(result)) => result.description = description,
+            Some(InlineQueryResult::Photo(result)) => result.description = description,
+            Some(InlineQueryResult::CachedPhoto(result)) => result.description = description,
+            Some(InlineQueryResult::Document(result)) => result.description = description,
+            Some(InlineQueryResult::CachedDocument(result)) => result.description = description,
+            Some(InlineQueryResult::Video(result)) => result.description = description,
+            Some(InlineQueryResult::CachedVideo(result)) => result.description = description,
+            _ => {}
+        }
+        self
+    }
+
+    /// Returns the number of added results.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.results.len()
+    }
+
+    /// Returns `true` if no results were added.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.results.is_empty()
+    }
+
+    /// Returns all added results.
+    #[must_use]
+    pub fn build(mut self) -> Vec<InlineQueryResult> {
+        self.assign_ids();
+        self.results
+    }
+
+    /// Returns the page of results starting at `offset`, and the offset of the
+    /// next page (empty if there are no more results).
+    ///
+    /// An invalid `offset` (e.g. an empty one) is treated as the first page.
+    #[must_use]
+    pub fn into_page(mut self, offset: &str) -> (Vec<InlineQueryResult>, String) {
+        self.assign_ids();
+        let start = offset.parse().unwrap_or(0).min(self.results.len());
+        let end = (start + self.page_size).min(self.results.len());
+        let next_offset = if end < self.results.len() { end.to_string() } else { String::new() };
+
+        let mut results = self.results;
+        results.truncate(end);
+        results.drain(..start);
+        (results, next_offset)
+    }
+
+    /// Answers `query` with the page of results it requests.
+    pub async fn answer<R>(self, bot: &R, query: &InlineQuery) -> Result<(), R::Err>
+    where
+        R: Requester,
+    {
+        let (results, next_offset) = self.into_page(&query.offset);
+        bot.answer_inline_query(query.id.clone(), results).next_offset(next_offset).await?;
+        Ok(())
+    }
+
+    /// Adds a result, which gets its id in [`assign_ids`].
+    ///
+    /// [`assign_ids`]: ResultsBuilder::assign_ids
+    fn push_auto<R>(mut self, result: R) -> Self
+    where
+        R: Into<InlineQueryResult>,
+    {
+        self.auto_ids.push(self.results.len());
+        self.push(result)
+    }
+
+    /// Assigns ids to the results added by the convenience constructors.
+    fn assign_ids(&mut self) {
+        let auto_ids = mem::take(&mut self.auto_ids);
+        let taken: HashSet<String> = (self.results.iter().enumerate())
+            .filter(|(i, _)| auto_ids.binary_search(i).is_err())
+            .map(|(_, result)| result_id(result).to_owned())
+            .collect();
+
+        let mut ids = (0usize..).map(|id| id.to_string()).filter(|id| !taken.contains(id));
+        for i in auto_ids {
+            *result_id_mut(&mut self.results[i]) = ids.next().unwrap();
+        }
+    }
+}
+
+/// Evaluates `$e` with `$result` bound to the result of any kind.
+macro_rules! with_result {
+    ($value:expr, $result:ident => $e:expr) => {
+        match $value {
+            InlineQueryResult::CachedAudio($result) => $e,
+            InlineQueryResult::CachedDocument($result) => $e,
+            InlineQueryResult::CachedGif($result) => $e,
+            InlineQueryResult::CachedMpeg4Gif($result) => $e,
+            InlineQueryResult::CachedPhoto($result) => $e,
+            InlineQueryResult::CachedSticker($result) => $e,
+            InlineQueryResult::CachedVideo($result) => $e,
+            InlineQueryResult::CachedVoice($result) => $e,
+            InlineQueryResult::Article($result) => $e,
+            InlineQueryResult::Audio($result) => $e,
+            InlineQueryResult::Contact($result) => $e,
+            InlineQueryResult::Game($result) => $e,
+            InlineQueryResult::Document($result) => $e,
+            InlineQueryResult::Gif($result) => $e,
+            InlineQueryResult::Location($result) => $e,
+            InlineQueryResult::Mpeg4Gif($result) => $e,
+            InlineQueryResult::Photo($result) => $e,
+            InlineQueryResult::Venue($result) => $e,
+            InlineQueryResult::Video($result) => $e,
+            InlineQueryResult::Voice($result) => $e,
+        }
+    };
+}
+
+fn result_id(result: &InlineQueryResult) -> &str {
+    with_result!(result, result => &result.id)
+}
+
+fn result_id_mut(result: &mut InlineQueryResult) -> &mut String {
+    with_result!(result, result => &mut result.id)
+}
+
+impl Default for ResultsBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(results: &[InlineQueryResult]) -> Vec<&str> {
+        results.iter().map(result_id).collect()
+    }
+
+    #[test]
+    fn pages() {
+        let builder = (0..5)
+            .fold(ResultsBuilder::new().page_size(2), |builder, i| {
+                builder.article(format!("{i}"), "text")
+            })
+            .description("last");
+
+        let InlineQueryResult::Article(last) = builder.clone().build().pop().unwrap() else {
+            unreachable!()
+        };
+        assert_eq!(last.description.as_deref(), Some("last"));
+
+        let (page, next_offset) = builder.clone().into_page("");
+        assert_eq!((ids(&page), &*next_offset), (vec!["0", "1"], "2"));
+
+        let (page, next_offset) = builder.clone().into_page("2");
+        assert_eq!((ids(&page), &*next_offset), (vec!["2", "3"], "4"));
+
+        let (page, next_offset) = builder.clone().into_page("4");
+        assert_eq!((ids(&page), &*next_offset), (vec!["4"], ""));
+
+        let (page, next_offset) = builder.into_page("10");
+        assert_eq!((ids(&page), &*next_offset), (vec![], ""));
+    }
+
+    #[test]
+    fn unique_ids() {
+        let content = || InputMessageContent::Text(InputMessageContentText::new("text"));
+        let builder = ResultsBuilder::new()
+            .push(InlineQueryResultArticle::new("1", "pushed", content()))
+            .article("a", "text")
+            .article("b", "text")
+            .push(InlineQueryResultArticle::new("custom", "pushed", content()))
+            .article("c", "text");
+
+        assert_eq!(ids(&builder.build()), ["1", "0", "2", "custom", "3"]);
+    }
+
+    #[test]
+    fn unique_ids_pushed_after_auto_ids() {
+        let content = || InputMessageContent::Text(InputMessageContentText::new("text"));
+        let builder = ResultsBuilder::new()
+            .article("a", "text")
+            .article("b", "text")
+            .push(InlineQueryResultArticle::new("0", "pushed", content()))
+            .page_size(2);
+
+        assert_eq!(ids(&builder.clone().build()), ["1", "2", "0"]);
+        assert_eq!(ids(&builder.into_page("").0), ["1", "2"]);
+    }
+}
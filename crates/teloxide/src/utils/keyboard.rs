@@ -0,0 +1,380 @@
+//! Utils for building and tracking keyboards.
+
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+};
+
+use teloxide_core::{
+    payloads::SendMessageSetters,
+    requests::Requester,
+    types::{
+        ChatId, InlineKeyboardButton, InlineKeyboardMarkup, KeyboardMarkup, KeyboardRemove,
+        Message, ReplyMarkup,
+    },
+};
+use thiserror::Error;
+use url::Url;
+
+use crate::dispatching::dialogue::{Dialogue, Storage};
+
+/// A builder for [`InlineKeyboardMarkup`].
+///
+/// Buttons are added to the current row, until [`row`] starts a new one.
+///
+/// [`row`]: InlineKeyboardBuilder::row
+///
+/// ## Examples
+///
+/// ```
+/// use teloxide::{types::InlineKeyboardButton, utils::keyboard::InlineKeyboardBuilder};
+///
+/// let keyboard = InlineKeyboardBuilder::new()
+///     .button("Yes", "yes")
+///     .button("No", "no")
+///     .row()
+///     .url_button("Docs", "https://docs.rs/teloxide".parse().unwrap())
+///     .build();
+///
+/// assert_eq!(keyboard.inline_keyboard.len(), 2);
+/// assert_eq!(keyboard.inline_keyboard[0][1], InlineKeyboardButton::callback("No", "no"));
+/// ```
+#[derive(Clone, Debug)]
+#[must_use]
+pub struct InlineKeyboardBuilder {
+    rows: Vec<Vec<InlineKeyboardButton>>,
+    current: Vec<InlineKeyboardButton>,
+    page_prefix: String,
+}
+
+impl InlineKeyboardBuilder {
+    /// Creates an empty builder.
+    pub fn new() -> Self {
+        Self { rows: Vec::new(), current: Vec::new(), page_prefix: "page:".to_owned() }
+    }
+
+    /// Adds a button to the current row.
+    pub fn push(mut self, button: InlineKeyboardButton) -> Self {
+        self.current.push(button);
+        self
+    }
+
+    /// Adds a [callback button] to the current row.
+    ///
+    /// [callback button]: InlineKeyboardButton::callback
+    pub fn button<T, C>(self, text: T, callback_data: C) -> Self
+    where
+        T: Into<String>,
+        C: Into<String>,
+    {
+        self.push(InlineKeyboardButton::callback(text, callback_data))
+    }
+
+    /// Adds a [URL button] to the current row.
+    ///
+    /// [URL button]: InlineKeyboardButton::url
+    pub fn url_button<T>(self, text: T, url: Url) -> Self
+    where
+        T: Into<String>,
+    {
+        self.push(InlineKeyboardButton::url(text, url))
+    }
+
+    /// Finishes the current row, so that the following buttons are added to a
+    /// new one.
+    ///
+    /// Does nothing if the current row is empty.
+    pub fn row(mut self) -> Self {
+        if !self.current.is_empty() {
+            self.rows.push(std::mem::take(&mut self.current));
+        }
+        self
+    }
+
+    /// Sets the prefix of the callback data of the navigation buttons added by
+    /// [`paginate`]. Defaults to `"page:"`.
+    ///
+    /// [`paginate`]: InlineKeyboardBuilder::paginate
+    pub fn page_prefix<S>(self, prefix: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self { page_prefix: prefix.into(), ..self }
+    }
+
+    /// Adds one page of `items`, a button per row, followed by a row with
+    /// buttons to go to the previous and the next pages.
+    ///
+    /// Pages are numbered from `0`. Navigation buttons have callback data
+    /// consisting of the [page prefix] followed by the number of the page
+    /// they lead to (e.g. `page:2`), use [`parse_page`] to extract it. Buttons
+    /// that would lead out of bounds are omitted.
+    ///
+    /// ## Panics
+    ///
+    /// If `per_page` is `0`.
+    ///
+    /// [page prefix]: InlineKeyboardBuilder::page_prefix
+    /// [`parse_page`]: InlineKeyboardBuilder::parse_page
+    pub fn paginate<I>(mut self, items: I, page: usize, per_page: usize) -> Self
+    where
+        I: IntoIterator<Item = InlineKeyboardButton>,
+    {
+        assert!(per_page > 0, "`per_page` must be positive");
+
+        self = self.row();
+
+        let mut items = items.into_iter().skip(page.saturating_mul(per_page));
+        self.rows.extend(items.by_ref().take(per_page).map(|button| vec![button]));
+        let has_next = items.next().is_some();
+
+        if page > 0 {
+            let data = format!("{}{}", self.page_prefix, page - 1);
+            self = self.button("«", data);
+        }
+        if has_next {
+            let data = format!("{}{}", self.page_prefix, page + 1);
+            self = self.button("»", data);
+        }
+
+        self.row()
+    }
+
+    /// Extracts the page number from the callback data of a navigation button
+    /// added by [`paginate`].
+    ///
+    /// Returns `None` if `data` doesn't start with `prefix`, or if the rest
+    /// isn't a number.
+    ///
+    /// [`paginate`]: InlineKeyboardBuilder::paginate
+    #[must_use]
+    pub fn parse_page(data: &str, prefix: &str) -> Option<usize> {
+        data.strip_prefix(prefix)?.parse().ok()
+    }
+
+    /// Builds the keyboard.
+    #[must_use]
+    pub fn build(self) -> InlineKeyboardMarkup {
+        InlineKeyboardMarkup { inline_keyboard: self.row().rows }
+    }
+}
+
+impl Default for InlineKeyboardBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<InlineKeyboardBuilder> for InlineKeyboardMarkup {
+    fn from(builder: InlineKeyboardBuilder) -> Self {
+        builder.build()
+    }
+}
+
+impl From<InlineKeyboardBuilder> for ReplyMarkup {
+    fn from(builder: InlineKeyboardBuilder) -> Self {
+        builder.build().into()
+    }
+}
+
+/// Tracks chats where the bot has shown a custom reply keyboard, so that the
+/// keyboards can be removed when they are no longer needed, instead of
+/// lingering on users' screens.
+///
+/// Clones share the same state, so a tracker can be passed to handlers as a
+/// dependency.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use teloxide::{
+///     dispatching::dialogue::{InMemStorage, InMemStorageError},
+///     prelude::*,
+///     types::{KeyboardButton, KeyboardMarkup},
+///     utils::keyboard::{ExitDialogueError, KeyboardTracker},
+///     RequestError,
+/// };
+///
+/// type MyDialogue = Dialogue<(), InMemStorage<()>>;
+///
+/// async fn ask(bot: Bot, msg: Message, keyboards: KeyboardTracker) -> ResponseResult<()> {
+///     let keyboard =
+///         KeyboardMarkup::new([[KeyboardButton::new("Yes"), KeyboardButton::new("No")]]);
+///     keyboards.send_keyboard(&bot, msg.chat.id, "Are you sure?", keyboard).await?;
+///     Ok(())
+/// }
+///
+/// async fn finish(
+///     bot: Bot,
+///     dialogue: MyDialogue,
+///     keyboards: KeyboardTracker,
+/// ) -> Result<(), ExitDialogueError<InMemStorageError, RequestError>> {
+///     // Removes the dialogue and the keyboard shown by `ask`
+///     keyboards.exit_dialogue(&bot, &dialogue, "Done!").await
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct KeyboardTracker {
+    chats: Arc<Mutex<HashSet<ChatId>>>,
+}
+
+impl KeyboardTracker {
+    /// Creates a tracker without tracked chats.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records that a reply keyboard was shown in `chat_id`.
+    pub fn track(&self, chat_id: ChatId) {
+        self.chats.lock().unwrap().insert(chat_id);
+    }
+
+    /// Forgets about the keyboard shown in `chat_id`, without removing it.
+    ///
+    /// Returns `true` if the chat was tracked.
+    pub fn untrack(&self, chat_id: ChatId) -> bool {
+        self.chats.lock().unwrap().remove(&chat_id)
+    }
+
+    /// Returns `true` if a reply keyboard is shown in `chat_id`.
+    #[must_use]
+    pub fn is_tracked(&self, chat_id: ChatId) -> bool {
+        self.chats.lock().unwrap().contains(&chat_id)
+    }
+
+    /// Sends a message with a reply keyboard and tracks it.
+    pub async fn send_keyboard<R, T>(
+        &self,
+        bot: &R,
+        chat_id: ChatId,
+        text: T,
+        keyboard: KeyboardMarkup,
+    ) -> Result<Message, R::Err>
+    where
+        R: Requester,
+        T: Into<String>,
+    {
+        let message = bot.send_message(chat_id, text).reply_markup(keyboard).await?;
+        self.track(chat_id);
+        Ok(message)
+    }
+
+    /// Removes the reply keyboard shown in `chat_id`, if any.
+    ///
+    /// Telegram only allows to remove a keyboard together with sending a
+    /// message, so `text` is sent with [`KeyboardRemove`]. Returns the
+    /// sent message, or `None` if there was no keyboard to remove.
+    pub async fn clear_keyboards<R, T>(
+        &self,
+        bot: &R,
+        chat_id: ChatId,
+        text: T,
+    ) -> Result<Option<Message>, R::Err>
+    where
+        R: Requester,
+        T: Into<String>,
+    {
+        if !self.untrack(chat_id) {
+            return Ok(None);
+        }
+
+        match bot.send_message(chat_id, text).reply_markup(KeyboardRemove::new()).await {
+            Ok(message) => Ok(Some(message)),
+            Err(err) => {
+                self.track(chat_id);
+                Err(err)
+            }
+        }
+    }
+
+    /// Exits `dialogue` and removes the reply keyboard shown in its chat, if
+    /// any.
+    ///
+    /// `text` is sent only if there is a keyboard to remove, see
+    /// [`clear_keyboards`].
+    ///
+    /// [`clear_keyboards`]: KeyboardTracker::clear_keyboards
+    pub async fn exit_dialogue<R, D, S, T>(
+        &self,
+        bot: &R,
+        dialogue: &Dialogue<D, S>,
+        text: T,
+    ) -> Result<(), ExitDialogueError<S::Error, R::Err>>
+    where
+        R: Requester,
+        D: Send + 'static,
+        S: Storage<D> + ?Sized,
+        T: Into<String>,
+    {
+        dialogue.exit().await.map_err(ExitDialogueError::Storage)?;
+        self.clear_keyboards(bot, dialogue.chat_id(), text)
+            .await
+            .map_err(ExitDialogueError::Request)?;
+        Ok(())
+    }
+}
+
+/// An error returned from [`KeyboardTracker::exit_dialogue`].
+#[derive(Debug, Error)]
+pub enum ExitDialogueError<S, R> {
+    /// The dialogue couldn't be removed from the storage.
+    #[error(transparent)]
+    Storage(S),
+
+    /// The keyboard couldn't be removed.
+    #[error(transparent)]
+    Request(R),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn items(n: usize) -> impl Iterator<Item = InlineKeyboardButton> {
+        (0..n).map(|i| InlineKeyboardButton::callback(i.to_string(), i.to_string()))
+    }
+
+    #[test]
+    fn rows() {
+        let keyboard = InlineKeyboardBuilder::new().row().button("a", "a").row().row().build();
+        assert_eq!(keyboard.inline_keyboard, [[InlineKeyboardButton::callback("a", "a")]]);
+    }
+
+    #[test]
+    fn paginate() {
+        let keyboard = InlineKeyboardBuilder::new().paginate(items(5), 0, 2).build();
+        assert_eq!(keyboard.inline_keyboard.len(), 3);
+        assert_eq!(keyboard.inline_keyboard[2], [InlineKeyboardButton::callback("»", "page:1")]);
+
+        let keyboard = InlineKeyboardBuilder::new()
+            .page_prefix("p")
+            .button("Back", "back")
+            .paginate(items(5), 1, 2)
+            .build();
+        assert_eq!(keyboard.inline_keyboard[0], [InlineKeyboardButton::callback("Back", "back")]);
+        assert_eq!(keyboard.inline_keyboard[1], [InlineKeyboardButton::callback("2", "2")]);
+        assert_eq!(
+            keyboard.inline_keyboard[3],
+            [InlineKeyboardButton::callback("«", "p0"), InlineKeyboardButton::callback("»", "p2")]
+        );
+
+        let keyboard = InlineKeyboardBuilder::new().paginate(items(5), 2, 2).build();
+        assert_eq!(keyboard.inline_keyboard.len(), 2);
+        assert_eq!(keyboard.inline_keyboard[1], [InlineKeyboardButton::callback("«", "page:1")]);
+
+        assert_eq!(InlineKeyboardBuilder::parse_page("page:12", "page:"), Some(12));
+        assert_eq!(InlineKeyboardBuilder::parse_page("12", "page:"), None);
+    }
+
+    #[test]
+    fn tracker() {
+        let tracker = KeyboardTracker::new();
+        tracker.clone().track(ChatId(1));
+
+        assert!(tracker.is_tracked(ChatId(1)));
+        assert!(!tracker.is_tracked(ChatId(2)));
+        assert!(tracker.untrack(ChatId(1)));
+        assert!(!tracker.untrack(ChatId(1)));
+    }
+}
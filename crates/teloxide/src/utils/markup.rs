@@ -0,0 +1,667 @@
+//! Local validation of formatted text.
+
+use teloxide_core::types::ParseMode;
+use thiserror::Error;
+
+use crate::utils::markdown::ESCAPE_CHARS;
+
+/// An error returned from [`validate_markup`].
+///
+/// All offsets are byte offsets into the validated text.
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum MarkupError {
+    /// A reserved character appears outside of the markup and isn't escaped.
+    #[error("character {character:?} at byte {offset} must be escaped")]
+    UnescapedCharacter { offset: usize, character: char },
+
+    /// An entity is opened, but never closed.
+    #[error("{entity} entity opened at byte {offset} is never closed")]
+    UnclosedEntity { offset: usize, entity: &'static str },
+
+    /// An entity is nested into an entity which can't contain other entities
+    /// (e.g. `code`), or two entities overlap without one containing the
+    /// other.
+    #[error("{entity} entity at byte {offset} can't be nested inside {parent} entity")]
+    InvalidNesting { offset: usize, entity: &'static str, parent: &'static str },
+
+    /// A closing HTML tag doesn't match the last opened tag.
+    #[error("unexpected closing tag `</{tag}>` at byte {offset}")]
+    UnexpectedClosingTag { offset: usize, tag: String },
+
+    /// An HTML tag is not supported by Telegram.
+    #[error("unsupported tag `<{tag}>` at byte {offset}")]
+    UnsupportedTag { offset: usize, tag: String },
+
+    /// An HTML tag lacks an attribute required by Telegram.
+    #[error("tag `<{tag}>` at byte {offset} is missing the `{attribute}` attribute")]
+    MissingAttribute { offset: usize, tag: &'static str, attribute: &'static str },
+
+    /// An HTML tag can't be parsed.
+    #[error("malformed tag at byte {offset}")]
+    MalformedTag { offset: usize },
+
+    /// A named HTML entity is not supported by Telegram.
+    #[error("unsupported HTML entity `{entity}` at byte {offset}")]
+    UnsupportedHtmlEntity { offset: usize, entity: String },
+}
+
+/// Validates `text` against the formatting rules of `parse_mode`.
+///
+/// This catches the most common formatting bugs (unescaped reserved
+/// characters, unclosed entities, unsupported HTML tags, etc.) locally, so that
+/// they can be found in tests instead of as `Bad Request: can't parse entities`
+/// errors at run-time.
+///
+/// Note that a successful validation doesn't guarantee that Telegram will
+/// accept the text, as some checks (e.g. validity of URLs or custom emoji ids)
+/// can only be done server-side.
+///
+/// ## Examples
+///
+/// ```
+/// use teloxide::{
+///     types::ParseMode,
+///     utils::{validate_markup, MarkupError},
+/// };
+///
+/// assert_eq!(validate_markup("*Hello*, world\\!", ParseMode::MarkdownV2), Ok(()));
+/// assert_eq!(
+///     validate_markup("Hello, world!", ParseMode::MarkdownV2),
+///     Err(MarkupError::UnescapedCharacter { offset: 12, character: '!' })
+/// );
+///
+/// assert_eq!(validate_markup("<b>bold</b> &amp; <i>italic</i>", ParseMode::Html), Ok(()));
+/// assert_eq!(
+///     validate_markup("<b>bold", ParseMode::Html),
+///     Err(MarkupError::UnclosedEntity { offset: 0, entity: "bold" })
+/// );
+/// ```
+pub fn validate_markup(text: &str, parse_mode: ParseMode) -> Result<(), MarkupError> {
+    #[allow(deprecated)]
+    match parse_mode {
+        ParseMode::MarkdownV2 => validate_markdown_v2(text),
+        ParseMode::Html => validate_html(text),
+        ParseMode::Markdown => validate_markdown_legacy(text),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Entity {
+    Bold,
+    Italic,
+    Underline,
+    Strikethrough,
+    Spoiler,
+    Code,
+    Pre,
+    Link,
+    CustomEmoji,
+    Blockquote,
+}
+
+impl Entity {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Bold => "bold",
+            Self::Italic => "italic",
+            Self::Underline => "underline",
+            Self::Strikethrough => "strikethrough",
+            Self::Spoiler => "spoiler",
+            Self::Code => "code",
+            Self::Pre => "pre",
+            Self::Link => "text link",
+            Self::CustomEmoji => "custom emoji",
+            Self::Blockquote => "blockquote",
+        }
+    }
+}
+
+struct Open<T> {
+    offset: usize,
+    entity: Entity,
+    tag: T,
+}
+
+fn validate_markdown_v2(text: &str) -> Result<(), MarkupError> {
+    let mut stack: Vec<Open<()>> = Vec::new();
+    let mut in_blockquote = false;
+    let mut pos = 0;
+
+    while let Some(c) = text[pos..].chars().next() {
+        let rest = &text[pos..];
+        let at_line_start = pos == 0 || text[..pos].ends_with('\n');
+
+        // Code and pre entities can only contain escaped '`' and '\'
+        if let Some(top) = stack.last().filter(|o| matches!(o.entity, Entity::Code | Entity::Pre)) {
+            match c {
+                '\\' => pos += escaped_len(text, pos)?,
+                '`' if top.entity == Entity::Pre && rest.starts_with("```") => {
+                    stack.pop();
+                    pos += 3;
+                }
+                '`' if top.entity == Entity::Code => {
+                    stack.pop();
+                    pos += 1;
+                }
+                '`' => return Err(MarkupError::UnescapedCharacter { offset: pos, character: c }),
+                _ => pos += c.len_utf8(),
+            }
+            continue;
+        }
+
+        if c == '\n' {
+            in_blockquote = false;
+        }
+
+        match c {
+            '\\' => {
+                pos += escaped_len(text, pos)?;
+                continue;
+            }
+            '`' if rest.starts_with("```") => {
+                check_nesting(&stack, pos, Entity::Pre)?;
+                stack.push(Open { offset: pos, entity: Entity::Pre, tag: () });
+                pos += 3;
+                continue;
+            }
+            '`' => {
+                check_nesting(&stack, pos, Entity::Code)?;
+                stack.push(Open { offset: pos, entity: Entity::Code, tag: () });
+            }
+            '*' if at_line_start && rest.starts_with("**>") => {
+                in_blockquote = true;
+                pos += 3;
+                continue;
+            }
+            '>' if at_line_start => in_blockquote = true,
+            '*' => toggle(&mut stack, pos, Entity::Bold)?,
+            '_' if rest.starts_with("__") => {
+                toggle(&mut stack, pos, Entity::Underline)?;
+                pos += 2;
+                continue;
+            }
+            '_' => toggle(&mut stack, pos, Entity::Italic)?,
+            '~' => toggle(&mut stack, pos, Entity::Strikethrough)?,
+            '|' if rest.starts_with("||") => {
+                let is_expandability_mark = in_blockquote
+                    && !stack.iter().any(|o| o.entity == Entity::Spoiler)
+                    && matches!(rest[2..].chars().next(), None | Some('\n'));
+
+                if !is_expandability_mark {
+                    toggle(&mut stack, pos, Entity::Spoiler)?;
+                }
+                pos += 2;
+                continue;
+            }
+            '!' if rest.starts_with("![") => {
+                check_nesting(&stack, pos, Entity::CustomEmoji)?;
+                stack.push(Open { offset: pos, entity: Entity::CustomEmoji, tag: () });
+                pos += 2;
+                continue;
+            }
+            '[' => {
+                check_nesting(&stack, pos, Entity::Link)?;
+                stack.push(Open { offset: pos, entity: Entity::Link, tag: () });
+            }
+            ']' if stack
+                .last()
+                .is_some_and(|o| matches!(o.entity, Entity::Link | Entity::CustomEmoji)) =>
+            {
+                let Open { offset, entity, .. } = stack.pop().unwrap();
+                pos += 1;
+
+                if !text[pos..].starts_with('(') {
+                    return Err(MarkupError::UnclosedEntity { offset, entity: entity.name() });
+                }
+                pos += 1;
+
+                // Inside the URL only ')' and '\' must be escaped
+                loop {
+                    match text[pos..].chars().next() {
+                        None => {
+                            return Err(MarkupError::UnclosedEntity {
+                                offset,
+                                entity: entity.name(),
+                            })
+                        }
+                        Some('\\') => pos += escaped_len(text, pos)?,
+                        Some(')') => break,
+                        Some(c) => pos += c.len_utf8(),
+                    }
+                }
+            }
+            c if ESCAPE_CHARS.contains(&c) => {
+                return Err(MarkupError::UnescapedCharacter { offset: pos, character: c })
+            }
+            _ => {}
+        }
+
+        pos += c.len_utf8();
+    }
+
+    match stack.pop() {
+        Some(Open { offset, entity, .. }) => {
+            Err(MarkupError::UnclosedEntity { offset, entity: entity.name() })
+        }
+        None => Ok(()),
+    }
+}
+
+fn validate_markdown_legacy(text: &str) -> Result<(), MarkupError> {
+    let mut pos = 0;
+
+    while let Some(c) = text[pos..].chars().next() {
+        let rest = &text[pos..];
+
+        let (entity, closing) = match c {
+            '\\' => {
+                pos += escaped_len(text, pos)?;
+                continue;
+            }
+            '`' if rest.starts_with("```") => (Entity::Pre, "```"),
+            '`' => (Entity::Code, "`"),
+            '*' => (Entity::Bold, "*"),
+            '_' => (Entity::Italic, "_"),
+            '[' => (Entity::Link, "]"),
+            _ => {
+                pos += c.len_utf8();
+                continue;
+            }
+        };
+
+        // Entities can't be nested and escaping inside of them is not allowed,
+        // so just look for the closing sequence
+        let start = pos;
+        let body = start + closing.len().max(1);
+        let Some(len) = text[body..].find(closing) else {
+            return Err(MarkupError::UnclosedEntity { offset: start, entity: entity.name() });
+        };
+        pos = body + len + closing.len();
+
+        if entity == Entity::Link {
+            let Some(len) = text[pos..].strip_prefix('(').and_then(|url| url.find(')')) else {
+                return Err(MarkupError::UnclosedEntity { offset: start, entity: entity.name() });
+            };
+            pos += len + 2;
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_html(text: &str) -> Result<(), MarkupError> {
+    let mut stack: Vec<Open<&str>> = Vec::new();
+    let mut pos = 0;
+
+    while let Some(c) = text[pos..].chars().next() {
+        match c {
+            '<' => {
+                let Some(len) = text[pos..].find('>') else {
+                    return Err(MarkupError::UnescapedCharacter { offset: pos, character: c });
+                };
+                let tag = &text[pos + 1..pos + len];
+
+                match tag.strip_prefix('/') {
+                    Some(name) => {
+                        let name = name.trim();
+                        match stack.pop() {
+                            Some(open) if open.tag.eq_ignore_ascii_case(name) => {}
+                            _ => {
+                                return Err(MarkupError::UnexpectedClosingTag {
+                                    offset: pos,
+                                    tag: name.to_owned(),
+                                })
+                            }
+                        }
+                    }
+                    None => {
+                        let (name, entity) = parse_html_tag(tag, pos)?;
+                        check_nesting(&stack, pos, entity)?;
+                        stack.push(Open { offset: pos, entity, tag: name });
+                    }
+                }
+
+                pos += len + 1;
+            }
+            '&' => {
+                pos += html_entity_len(text, pos)?;
+            }
+            _ => pos += c.len_utf8(),
+        }
+    }
+
+    match stack.pop() {
+        Some(Open { offset, entity, .. }) => {
+            Err(MarkupError::UnclosedEntity { offset, entity: entity.name() })
+        }
+        None => Ok(()),
+    }
+}
+
+/// Parses an opening HTML tag (without `<` and `>`), returning its name and
+/// the entity it corresponds to.
+fn parse_html_tag(tag: &str, offset: usize) -> Result<(&str, Entity), MarkupError> {
+    let name_len = tag.find(char::is_whitespace).unwrap_or(tag.len());
+    let (name, attributes) = tag.split_at(name_len);
+    let attributes =
+        parse_html_attributes(attributes).ok_or(MarkupError::MalformedTag { offset })?;
+    let attribute = |attr: &str| attributes.iter().find(|(n, _)| n.eq_ignore_ascii_case(attr));
+
+    let entity = match name.to_ascii_lowercase().as_str() {
+        "b" | "strong" => Entity::Bold,
+        "i" | "em" => Entity::Italic,
+        "u" | "ins" => Entity::Underline,
+        "s" | "strike" | "del" => Entity::Strikethrough,
+        "tg-spoiler" => Entity::Spoiler,
+        "span" if attribute("class").is_some_and(|(_, v)| *v == Some("tg-spoiler")) => {
+            Entity::Spoiler
+        }
+        "span" => {
+            return Err(MarkupError::MissingAttribute {
+                offset,
+                tag: "span",
+                attribute: "class=\"tg-spoiler\"",
+            })
+        }
+        "code" => Entity::Code,
+        "pre" => Entity::Pre,
+        "blockquote" => Entity::Blockquote,
+        "a" if attribute("href").is_some() => Entity::Link,
+        "a" => return Err(MarkupError::MissingAttribute { offset, tag: "a", attribute: "href" }),
+        "tg-emoji" if attribute("emoji-id").is_some() => Entity::CustomEmoji,
+        "tg-emoji" => {
+            return Err(MarkupError::MissingAttribute {
+                offset,
+                tag: "tg-emoji",
+                attribute: "emoji-id",
+            })
+        }
+        _ => return Err(MarkupError::UnsupportedTag { offset, tag: name.to_owned() }),
+    };
+
+    Ok((name, entity))
+}
+
+/// Parses `name="value"`, `name='value'`, `name=value` and `name` attributes.
+fn parse_html_attributes(mut s: &str) -> Option<Vec<(&str, Option<&str>)>> {
+    let mut attributes = Vec::new();
+
+    loop {
+        s = s.trim_start();
+        if s.is_empty() {
+            return Some(attributes);
+        }
+
+        let name_len = s.find(|c: char| c == '=' || c.is_whitespace()).unwrap_or(s.len());
+        let (name, rest) = s.split_at(name_len);
+        if name.is_empty() {
+            return None;
+        }
+
+        match rest.trim_start().strip_prefix('=') {
+            Some(rest) => {
+                let rest = rest.trim_start();
+                let (value, rest) = match rest.chars().next()? {
+                    quote @ ('"' | '\'') => {
+                        let len = rest[1..].find(quote)?;
+                        (&rest[1..len + 1], &rest[len + 2..])
+                    }
+                    _ => rest.split_at(rest.find(char::is_whitespace).unwrap_or(rest.len())),
+                };
+                attributes.push((name, Some(value)));
+                s = rest;
+            }
+            None => {
+                attributes.push((name, None));
+                s = rest;
+            }
+        }
+    }
+}
+
+/// Returns the length of an HTML entity starting at `pos`, or `1` if the `&`
+/// at `pos` doesn't start an entity and is shown as is.
+fn html_entity_len(text: &str, pos: usize) -> Result<usize, MarkupError> {
+    let rest = &text[pos + 1..];
+    let Some(len) = rest
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '#'))
+        .filter(|&len| len > 0 && rest[len..].starts_with(';'))
+    else {
+        return Ok(1);
+    };
+    let entity = &rest[..len];
+
+    let is_supported = match entity.strip_prefix('#') {
+        Some(code) => match code.strip_prefix(['x', 'X']) {
+            Some(hex) => !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit()),
+            None => !code.is_empty() && code.chars().all(|c| c.is_ascii_digit()),
+        },
+        None => matches!(entity, "lt" | "gt" | "amp" | "quot"),
+    };
+
+    if is_supported {
+        Ok(len + 2)
+    } else {
+        Err(MarkupError::UnsupportedHtmlEntity { offset: pos, entity: format!("&{entity};") })
+    }
+}
+
+/// Returns the length of a `\`-escape sequence starting at `pos`.
+fn escaped_len(text: &str, pos: usize) -> Result<usize, MarkupError> {
+    match text[pos + 1..].chars().next() {
+        Some(c) => Ok(1 + c.len_utf8()),
+        None => Err(MarkupError::UnescapedCharacter { offset: pos, character: '\\' }),
+    }
+}
+
+/// Checks that `entity` can be opened inside of the currently opened entities.
+fn check_nesting<T>(stack: &[Open<T>], offset: usize, entity: Entity) -> Result<(), MarkupError> {
+    let Some(parent) = stack.last() else { return Ok(()) };
+
+    let is_allowed = match parent.entity {
+        // `<pre><code class="language-rust">...</code></pre>` is the only allowed nesting
+        Entity::Pre => entity == Entity::Code,
+        Entity::Code => false,
+        Entity::Link | Entity::CustomEmoji => {
+            !matches!(entity, Entity::Link | Entity::CustomEmoji | Entity::Code | Entity::Pre)
+        }
+        _ => true,
+    };
+
+    match is_allowed {
+        true => Ok(()),
+        false => Err(MarkupError::InvalidNesting {
+            offset,
+            entity: entity.name(),
+            parent: parent.entity.name(),
+        }),
+    }
+}
+
+/// Opens or closes a MarkdownV2 entity which uses the same delimiter on both
+/// sides.
+fn toggle(stack: &mut Vec<Open<()>>, offset: usize, entity: Entity) -> Result<(), MarkupError> {
+    match stack.iter().rposition(|o| o.entity == entity) {
+        // Closing the innermost entity
+        Some(i) if i == stack.len() - 1 => {
+            stack.pop();
+            Ok(())
+        }
+        // Closing an entity while some inner entity is still open, which makes them
+        // overlap
+        Some(_) => {
+            let inner = stack.last().unwrap();
+            Err(MarkupError::InvalidNesting {
+                offset: inner.offset,
+                entity: inner.entity.name(),
+                parent: entity.name(),
+            })
+        }
+        None => {
+            check_nesting(stack, offset, entity)?;
+            stack.push(Open { offset, entity, tag: () });
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{html, markdown};
+
+    fn md(text: &str) -> Result<(), MarkupError> {
+        validate_markup(text, ParseMode::MarkdownV2)
+    }
+
+    fn html(text: &str) -> Result<(), MarkupError> {
+        validate_markup(text, ParseMode::Html)
+    }
+
+    #[test]
+    fn markdown_v2_valid() {
+        let cases = [
+            "plain text",
+            r"*bold \*text*",
+            r"_italic \*text_",
+            "__underline__",
+            "~strikethrough~",
+            "||spoiler||",
+            "*bold _italic bold ~italic bold strikethrough ||italic bold strikethrough spoiler||~ \
+             __underline italic bold___ bold*",
+            "[inline URL](http://www.example.com/)",
+            "[inline mention of a user](tg://user?id=123456789)",
+            "![👍](tg://emoji?id=5368324170671202286)",
+            "`inline fixed-width code`",
+            "```\npre-formatted fixed-width code block\n```",
+            "```rust\nfn main() { println!(\"a.b\") }\n```",
+            ">Block quotation started\n>Block quotation continued",
+            "**>The expandable block quotation\n>The last line||",
+            r"___italic underline_\r__",
+            r"1\.5 \+ 2 \= 3\.5\!",
+        ];
+
+        for text in cases {
+            assert_eq!(md(text), Ok(()), "{text:?}");
+        }
+    }
+
+    #[test]
+    fn markdown_v2_invalid() {
+        use MarkupError::*;
+
+        assert_eq!(md("Hello, world!"), Err(UnescapedCharacter { offset: 12, character: '!' }));
+        assert_eq!(md("a.b"), Err(UnescapedCharacter { offset: 1, character: '.' }));
+        assert_eq!(md("a > b"), Err(UnescapedCharacter { offset: 2, character: '>' }));
+        assert_eq!(md("trailing \\"), Err(UnescapedCharacter { offset: 9, character: '\\' }));
+        assert_eq!(md("*bold"), Err(UnclosedEntity { offset: 0, entity: "bold" }));
+        assert_eq!(md("a `code"), Err(UnclosedEntity { offset: 2, entity: "code" }));
+        assert_eq!(md("```\npre"), Err(UnclosedEntity { offset: 0, entity: "pre" }));
+        assert_eq!(md("[link](url"), Err(UnclosedEntity { offset: 0, entity: "text link" }));
+        assert_eq!(md("[link] (url)"), Err(UnclosedEntity { offset: 0, entity: "text link" }));
+        assert_eq!(md("link]"), Err(UnescapedCharacter { offset: 4, character: ']' }));
+        assert_eq!(
+            md("*bold _italic* still italic_"),
+            Err(InvalidNesting { offset: 6, entity: "italic", parent: "bold" })
+        );
+    }
+
+    #[test]
+    fn markdown_v2_utils_output_is_valid() {
+        let escaped = markdown::escape("Hi! (a.k.a. *not* bold) [x] 1+1=2 #tag {}|~`>_-");
+        let text = format!(
+            "{} {} {} {}",
+            markdown::bold(&escaped),
+            markdown::link("https://example.com/(a)", &escaped),
+            markdown::code_inline("let x = `y`;"),
+            markdown::code_block("fn main() {}"),
+        );
+
+        assert_eq!(md(&text), Ok(()));
+    }
+
+    #[test]
+    fn html_valid() {
+        let cases = [
+            "plain text",
+            "<b>bold</b>, <strong>bold</strong>",
+            "<i>italic</i>, <em>italic</em>",
+            "<u>underline</u>, <ins>underline</ins>",
+            "<s>s</s>, <strike>s</strike>, <del>s</del>",
+            "<span class=\"tg-spoiler\">spoiler</span>, <tg-spoiler>spoiler</tg-spoiler>",
+            "<b>bold <i>italic bold <s>italic bold strikethrough <span \
+             class=\"tg-spoiler\">italic bold strikethrough spoiler</span></s> <u>underline \
+             italic bold</u></i> bold</b>",
+            "<a href=\"http://www.example.com/\">inline URL</a>",
+            "<tg-emoji emoji-id=\"5368324170671202286\">👍</tg-emoji>",
+            "<code>inline fixed-width code</code>",
+            "<pre><code class=\"language-rust\">fn main() {}</code></pre>",
+            "<blockquote expandable>quote</blockquote>",
+            "&lt;&gt;&amp;&quot; &#60; &#x3C;",
+            // Telegram shows bare `>` and `&` as is
+            "a > b, a & b, a &b, &; &",
+        ];
+
+        for text in cases {
+            assert_eq!(html(text), Ok(()), "{text:?}");
+        }
+    }
+
+    #[test]
+    fn html_invalid() {
+        use MarkupError::*;
+
+        assert_eq!(html("a < b"), Err(UnescapedCharacter { offset: 2, character: '<' }));
+        assert_eq!(html("a <"), Err(UnescapedCharacter { offset: 2, character: '<' }));
+        assert_eq!(
+            html("&nbsp;"),
+            Err(UnsupportedHtmlEntity { offset: 0, entity: "&nbsp;".to_owned() })
+        );
+        assert_eq!(
+            html("a &#x; b"),
+            Err(UnsupportedHtmlEntity { offset: 2, entity: "&#x;".to_owned() })
+        );
+        assert_eq!(html("<b>bold"), Err(UnclosedEntity { offset: 0, entity: "bold" }));
+        assert_eq!(
+            html("<b><i>x</b></i>"),
+            Err(UnexpectedClosingTag { offset: 7, tag: "b".to_owned() })
+        );
+        assert_eq!(html("</b>"), Err(UnexpectedClosingTag { offset: 0, tag: "b".to_owned() }));
+        assert_eq!(html("<div>x</div>"), Err(UnsupportedTag { offset: 0, tag: "div".to_owned() }));
+        assert_eq!(
+            html("<a>link</a>"),
+            Err(MissingAttribute { offset: 0, tag: "a", attribute: "href" })
+        );
+        assert_eq!(html("<a href=\"x>y</a>"), Err(MalformedTag { offset: 0 }));
+        assert_eq!(
+            html("<code><b>x</b></code>"),
+            Err(InvalidNesting { offset: 6, entity: "bold", parent: "code" })
+        );
+    }
+
+    #[test]
+    fn html_utils_output_is_valid() {
+        let escaped = html::escape("<script> & \"quotes\"");
+        let text = format!(
+            "{} {} {}",
+            html::bold(&escaped),
+            html::link("https://example.com/?a=1&b=2", &escaped),
+            html::code_block_with_lang("a < b", "rust"),
+        );
+
+        assert_eq!(html(&text), Ok(()));
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn markdown_legacy() {
+        use MarkupError::*;
+
+        let md = |text| validate_markup(text, ParseMode::Markdown);
+
+        assert_eq!(md("*bold* _italic_ `code` [link](http://x.y) snake\\_case 1.5!"), Ok(()));
+        assert_eq!(md("```\na*b\n```"), Ok(()));
+        assert_eq!(md("snake_case"), Err(UnclosedEntity { offset: 5, entity: "italic" }));
+        assert_eq!(md("[link](url"), Err(UnclosedEntity { offset: 0, entity: "text link" }));
+    }
+}
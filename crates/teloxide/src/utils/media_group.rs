@@ -0,0 +1,240 @@
+//! Building and sending media groups (albums).
+
+use teloxide_core::{
+    requests::Requester,
+    types::{
+        InputFile, InputMedia, InputMediaAudio, InputMediaDocument, InputMediaPhoto,
+        InputMediaVideo, Message, ParseMode, Recipient,
+    },
+};
+use thiserror::Error;
+
+/// The minimum number of items in a media group, as allowed by Telegram.
+pub const MIN_MEDIA_GROUP_LEN: usize = 2;
+
+/// The maximum number of items in a media group, as allowed by Telegram.
+pub const MAX_MEDIA_GROUP_LEN: usize = 10;
+
+/// A builder of a media group, sent with [`sendMediaGroup`].
+///
+/// Items are added one by one, [`caption`] sets the caption of the last added
+/// item. Files that need to be uploaded are attached to the request with
+/// unique `attach://` names automatically.
+///
+/// [`sendMediaGroup`]: crate::payloads::SendMediaGroup
+/// [`caption`]: MediaGroupBuilder::caption
+///
+/// ## Examples
+///
+/// ```no_run
+/// use teloxide::{prelude::*, types::InputFile, utils::media_group::MediaGroupBuilder};
+///
+/// # async fn run(bot: Bot, chat_id: ChatId) -> Result<(), Box<dyn std::error::Error>> {
+/// let messages = MediaGroupBuilder::new()
+///     .photo(InputFile::file("cat.jpg"))
+///     .caption("A cat")
+///     .photo(InputFile::url("https://example.com/dog.jpg".parse()?))
+///     .video(InputFile::file("cat_and_dog.mp4"))
+///     .send(&bot, chat_id)
+///     .await?;
+///
+/// assert_eq!(messages.len(), 3);
+/// # Ok(()) }
+/// ```
+#[derive(Clone, Debug, Default)]
+#[must_use]
+pub struct MediaGroupBuilder {
+    media: Vec<InputMedia>,
+    parse_mode: Option<ParseMode>,
+}
+
+impl MediaGroupBuilder {
+    /// Creates an empty builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the parse mode of all captions.
+    pub fn parse_mode(self, parse_mode: ParseMode) -> Self {
+        Self { parse_mode: Some(parse_mode), ..self }
+    }
+
+    /// Adds an item.
+    pub fn push(mut self, media: InputMedia) -> Self {
+        self.media.push(media);
+        self
+    }
+
+    /// Adds a photo.
+    pub fn photo(self, file: InputFile) -> Self {
+        self.push(InputMedia::Photo(InputMediaPhoto::new(file)))
+    }
+
+    /// Adds a video.
+    pub fn video(self, file: InputFile) -> Self {
+        self.push(InputMedia::Video(InputMediaVideo::new(file)))
+    }
+
+    /// Adds a document.
+    pub fn document(self, file: InputFile) -> Self {
+        self.push(InputMedia::Document(InputMediaDocument::new(file)))
+    }
+
+    /// Adds an audio.
+    pub fn audio(self, file: InputFile) -> Self {
+        self.push(InputMedia::Audio(InputMediaAudio::new(file)))
+    }
+
+    /// Sets the caption of the last added item.
+    ///
+    /// Does nothing if there are no items.
+    pub fn caption<S>(mut self, caption: S) -> Self
+    where
+        S: Into<String>,
+    {
+        let caption = Some(caption.into());
+        match self.media.last_mut() {
+            Some(InputMedia::Photo(media)) => media.caption = caption,
+            Some(InputMedia::Video(media)) => media.caption = caption,
+            Some(InputMedia::Animation(media)) => media.caption = caption,
+            Some(InputMedia::Audio(media)) => media.caption = caption,
+            Some(InputMedia::Document(media)) => media.caption = caption,
+            None => {}
+        }
+        self
+    }
+
+    /// Returns the number of added items.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.media.len()
+    }
+
+    /// Returns `true` if no items were added.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.media.is_empty()
+    }
+
+    /// Checks that the items can be sent as a media group and returns them.
+    ///
+    /// A media group must consist of [`MIN_MEDIA_GROUP_LEN`] to
+    /// [`MAX_MEDIA_GROUP_LEN`] photos and videos, documents or audios.
+    pub fn build(self) -> Result<Vec<InputMedia>, MediaGroupError> {
+        let len = self.media.len();
+        if !(MIN_MEDIA_GROUP_LEN..=MAX_MEDIA_GROUP_LEN).contains(&len) {
+            return Err(MediaGroupError::Len(len));
+        }
+
+        let kind = |media: &InputMedia| match media {
+            InputMedia::Photo(_) | InputMedia::Video(_) => Some(0),
+            InputMedia::Document(_) => Some(1),
+            InputMedia::Audio(_) => Some(2),
+            InputMedia::Animation(_) => None,
+        };
+        let first = kind(&self.media[0]);
+        if first.is_none() || self.media.iter().any(|media| kind(media) != first) {
+            return Err(MediaGroupError::Mixed);
+        }
+
+        let mut media = self.media;
+        if let Some(parse_mode) = self.parse_mode {
+            for media in &mut media {
+                match media {
+                    InputMedia::Photo(media) => media.parse_mode = Some(parse_mode),
+                    InputMedia::Video(media) => media.parse_mode = Some(parse_mode),
+                    InputMedia::Animation(media) => media.parse_mode = Some(parse_mode),
+                    InputMedia::Audio(media) => media.parse_mode = Some(parse_mode),
+                    InputMedia::Document(media) => media.parse_mode = Some(parse_mode),
+                }
+            }
+        }
+
+        Ok(media)
+    }
+
+    /// Sends the media group to `chat_id`.
+    ///
+    /// Returns the sent messages, one per item.
+    pub async fn send<R, C>(
+        self,
+        bot: &R,
+        chat_id: C,
+    ) -> Result<Vec<Message>, SendMediaGroupError<R::Err>>
+    where
+        R: Requester,
+        C: Into<Recipient>,
+    {
+        let media = self.build().map_err(SendMediaGroupError::Invalid)?;
+        bot.send_media_group(chat_id, media).await.map_err(SendMediaGroupError::Request)
+    }
+}
+
+/// An error returned from [`MediaGroupBuilder::build`].
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MediaGroupError {
+    /// The number of items is out of [`MIN_MEDIA_GROUP_LEN`] to
+    /// [`MAX_MEDIA_GROUP_LEN`] range.
+    #[error(
+        "a media group must contain {MIN_MEDIA_GROUP_LEN} to {MAX_MEDIA_GROUP_LEN} items, got {0}"
+    )]
+    Len(usize),
+
+    /// The items can't be grouped together.
+    ///
+    /// Documents and audios can only be grouped with items of the same type,
+    /// animations can't be grouped at all.
+    #[error("these kinds of media can't be grouped together")]
+    Mixed,
+}
+
+/// An error returned from [`MediaGroupBuilder::send`].
+#[derive(Debug, Error)]
+pub enum SendMediaGroupError<E> {
+    /// The media group is invalid.
+    #[error(transparent)]
+    Invalid(MediaGroupError),
+
+    /// Telegram returned an error.
+    #[error(transparent)]
+    Request(E),
+}
+
+#[cfg(test)]
+mod tests {
+    use teloxide_core::types::FileId;
+
+    use super::*;
+
+    #[test]
+    fn build() {
+        let media = MediaGroupBuilder::new()
+            .photo(InputFile::file_id(FileId("a".into())))
+            .caption("*a*")
+            .video(InputFile::file_id(FileId("b".into())))
+            .parse_mode(ParseMode::MarkdownV2)
+            .build()
+            .unwrap();
+
+        let InputMedia::Photo(photo) = &media[0] else { panic!("expected a photo") };
+        assert_eq!(photo.caption.as_deref(), Some("*a*"));
+        assert_eq!(photo.parse_mode, Some(ParseMode::MarkdownV2));
+
+        assert_eq!(
+            MediaGroupBuilder::new()
+                .photo(InputFile::file_id(FileId("a".into())))
+                .build()
+                .unwrap_err(),
+            MediaGroupError::Len(1)
+        );
+        assert_eq!(
+            MediaGroupBuilder::new()
+                .photo(InputFile::file_id(FileId("a".into())))
+                .document(InputFile::file_id(FileId("b".into())))
+                .build()
+                .unwrap_err(),
+            MediaGroupError::Mixed
+        );
+    }
+}
@@ -0,0 +1,379 @@
+//! Downloading files, processing them and uploading the results.
+//!
+//! Many media bots download a file sent by a user, convert it (resize an
+//! image, transcode a video, ...) and send the result back. [`MediaPipeline`]
+//! implements this flow: it downloads a file into a temporary directory,
+//! passes it through a sequence of [`MediaTransformer`]s and returns a
+//! [`ProcessedFile`], which can be uploaded with [`ProcessedFile::input_file`]
+//! and removes the temporary files when dropped.
+
+use std::{
+    convert::Infallible,
+    future::Future,
+    hash::{BuildHasher, RandomState},
+    io,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use futures::future::BoxFuture;
+use thiserror::Error;
+
+use crate::{
+    errors::DownloadError,
+    net::Download,
+    requests::Requester,
+    types::{FileId, InputFile},
+};
+
+/// A step of a [`MediaPipeline`].
+///
+/// A transformer reads the file at `input` and writes the result to `output`.
+/// This trait is implemented for async functions taking both paths, e.g.
+///
+/// ```
+/// use std::path::PathBuf;
+///
+/// async fn to_png(input: PathBuf, output: PathBuf) -> std::io::Result<()> {
+///     let status = tokio::task::spawn_blocking(move || {
+///         std::process::Command::new("convert").arg(&input).arg(&output).status()
+///     })
+///     .await??;
+///
+///     if status.success() {
+///         Ok(())
+///     } else {
+///         Err(std::io::Error::other(format!("convert failed: {status}")))
+///     }
+/// }
+/// ```
+pub trait MediaTransformer: Send + Sync {
+    /// Transforms the file at `input`, writing the result to `output`.
+    fn transform(&self, input: PathBuf, output: PathBuf) -> BoxFuture<'static, io::Result<()>>;
+}
+
+impl<F, Fut> MediaTransformer for F
+where
+    F: Fn(PathBuf, PathBuf) -> Fut + Send + Sync,
+    Fut: Future<Output = io::Result<()>> + Send + 'static,
+{
+    fn transform(&self, input: PathBuf, output: PathBuf) -> BoxFuture<'static, io::Result<()>> {
+        Box::pin(self(input, output))
+    }
+}
+
+/// An error returned from [`MediaPipeline`].
+///
+/// Processing local files with [`MediaPipeline::run_local`] doesn't make
+/// requests, so the request error is [`Infallible`] there.
+#[derive(Debug, Error)]
+pub enum MediaPipelineError<E = Infallible> {
+    /// Getting information about the file failed.
+    #[error("request error: {0}")]
+    Request(E),
+
+    /// Downloading the file failed.
+    #[error("download error: {0}")]
+    Download(#[from] DownloadError),
+
+    /// Creating, copying or removing temporary files failed.
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+
+    /// A transformer failed.
+    #[error("transformer #{index} failed: {source}")]
+    Transform {
+        /// The index of the transformer, counting from zero.
+        index: usize,
+        source: io::Error,
+    },
+}
+
+/// Downloads files into a temporary directory and passes them through a
+/// sequence of [`MediaTransformer`]s.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use std::path::PathBuf;
+///
+/// use teloxide::{prelude::*, utils::media_pipeline::MediaPipeline};
+///
+/// async fn grayscale(input: PathBuf, output: PathBuf) -> std::io::Result<()> {
+///     // Run an image converter here
+///     tokio::fs::copy(input, output).await.map(drop)
+/// }
+///
+/// # async fn run(bot: Bot, msg: Message) -> Result<(), Box<dyn std::error::Error>> {
+/// let pipeline = MediaPipeline::new().stage(grayscale).file_name("gray.jpg");
+///
+/// if let Some(photo) = msg.photo().and_then(|sizes| sizes.last()) {
+///     let processed = pipeline.run(&bot, &photo.file.id).await?;
+///     bot.send_photo(msg.chat.id, processed.input_file()).await?;
+/// }
+/// # Ok(()) }
+/// ```
+#[derive(Clone)]
+#[must_use]
+pub struct MediaPipeline {
+    stages: Vec<Arc<dyn MediaTransformer>>,
+    temp_dir: PathBuf,
+    file_name: Option<String>,
+}
+
+impl MediaPipeline {
+    /// Creates a pipeline without transformers, which uses
+    /// [`std::env::temp_dir`] for temporary files.
+    pub fn new() -> Self {
+        Self { stages: Vec::new(), temp_dir: std::env::temp_dir(), file_name: None }
+    }
+
+    /// Adds a transformer to the end of the pipeline.
+    pub fn stage<T>(mut self, transformer: T) -> Self
+    where
+        T: MediaTransformer + 'static,
+    {
+        self.stages.push(Arc::new(transformer));
+        self
+    }
+
+    /// Sets the directory in which temporary directories for processed files
+    /// are created.
+    pub fn temp_dir<P>(self, dir: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        Self { temp_dir: dir.into(), ..self }
+    }
+
+    /// Sets the name of the uploaded file.
+    ///
+    /// By default, the name of the original file is used.
+    pub fn file_name<S>(self, name: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self { file_name: Some(name.into()), ..self }
+    }
+
+    /// Downloads the file `file_id` and processes it.
+    pub async fn run<B>(
+        &self,
+        bot: &B,
+        file_id: &FileId,
+    ) -> Result<ProcessedFile, MediaPipelineError<<B as Requester>::Err>>
+    where
+        B: Requester + for<'dst> Download<Err<'dst> = DownloadError>,
+    {
+        let file = bot.get_file(file_id.clone()).await.map_err(MediaPipelineError::Request)?;
+        let name = self.file_name.clone().unwrap_or_else(|| file_name_of(Path::new(&file.path)));
+
+        let dir = TempDir::create(&self.temp_dir).await?;
+        let input = dir.path().join("input");
+        let mut dst = tokio::fs::File::create(&input).await?;
+        bot.download_file(&file.path, &mut dst).await?;
+        drop(dst);
+
+        self.process(dir, input, name).await
+    }
+
+    /// Processes a local file.
+    ///
+    /// The file is copied into a temporary directory first, so it's left
+    /// intact.
+    pub async fn run_local<P>(&self, path: P) -> Result<ProcessedFile, MediaPipelineError>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let name = self.file_name.clone().unwrap_or_else(|| file_name_of(path));
+
+        let dir = TempDir::create(&self.temp_dir).await?;
+        let input = dir.path().join("input");
+        tokio::fs::copy(path, &input).await?;
+
+        self.process(dir, input, name).await
+    }
+
+    async fn process<E>(
+        &self,
+        dir: TempDir,
+        mut current: PathBuf,
+        name: String,
+    ) -> Result<ProcessedFile, MediaPipelineError<E>> {
+        for (index, stage) in self.stages.iter().enumerate() {
+            let output = dir.path().join(format!("stage-{index}"));
+            stage
+                .transform(current, output.clone())
+                .await
+                .map_err(|source| MediaPipelineError::Transform { index, source })?;
+            current = output;
+        }
+
+        Ok(ProcessedFile { path: current, name, dir })
+    }
+}
+
+impl Default for MediaPipeline {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A file processed by a [`MediaPipeline`].
+///
+/// The temporary files are removed when this is dropped, so it must be kept
+/// alive until the file is uploaded. Inside of a Tokio runtime, they are
+/// removed in the background, use [`ProcessedFile::into_bytes`] to wait for
+/// the removal.
+#[derive(Debug)]
+pub struct ProcessedFile {
+    path: PathBuf,
+    name: String,
+    dir: TempDir,
+}
+
+impl ProcessedFile {
+    /// Returns the path of the processed file.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns an [`InputFile`] uploading the processed file.
+    #[must_use]
+    pub fn input_file(&self) -> InputFile {
+        InputFile::file(&self.path).file_name(self.name.clone())
+    }
+
+    /// Reads the processed file into memory, removing the temporary files.
+    pub async fn into_bytes(self) -> io::Result<Vec<u8>> {
+        let data = tokio::fs::read(&self.path).await?;
+        self.dir.close().await;
+        Ok(data)
+    }
+}
+
+/// A directory which is removed with its contents when dropped.
+///
+/// Inside of a Tokio runtime, the directory is removed in the background with
+/// [`tokio::task::spawn_blocking`], so dropping doesn't block the runtime.
+#[derive(Debug)]
+struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    /// Creates a new directory with an unpredictable name in `parent`.
+    async fn create(parent: &Path) -> io::Result<Self> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        const ATTEMPTS: usize = 16;
+
+        tokio::fs::create_dir_all(parent).await?;
+
+        let mut attempt = 0;
+        loop {
+            let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+            // `RandomState` is randomly seeded, which is enough to not let
+            // other users of `parent` guess the name
+            let suffix = RandomState::new().hash_one((std::process::id(), n));
+            let path = parent.join(format!("teloxide-media-{suffix:016x}"));
+
+            // Unlike `create_dir_all`, this fails if the directory exists
+            match tokio::fs::create_dir(&path).await {
+                Ok(()) => return Ok(Self { path }),
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists && attempt < ATTEMPTS => {
+                    attempt += 1;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Removes the directory, waiting for it to be removed.
+    async fn close(mut self) {
+        let path = std::mem::take(&mut self.path);
+        if let Err(err) = tokio::fs::remove_dir_all(&path).await {
+            if err.kind() != io::ErrorKind::NotFound {
+                log::warn!("failed to remove {}: {err}", path.display());
+            }
+        }
+    }
+
+    fn remove(path: &Path) {
+        if let Err(err) = std::fs::remove_dir_all(path) {
+            if err.kind() != io::ErrorKind::NotFound {
+                log::warn!("failed to remove {}: {err}", path.display());
+            }
+        }
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let path = std::mem::take(&mut self.path);
+        if path.as_os_str().is_empty() {
+            // Already removed by `close`
+            return;
+        }
+
+        match tokio::runtime::Handle::try_current() {
+            Ok(runtime) => drop(runtime.spawn_blocking(move || Self::remove(&path))),
+            Err(_) => Self::remove(&path),
+        }
+    }
+}
+
+fn file_name_of(path: &Path) -> String {
+    path.file_name().map_or_else(|| "file".to_owned(), |name| name.to_string_lossy().into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn append(input: PathBuf, output: PathBuf, suffix: &str) -> io::Result<()> {
+        let mut data = tokio::fs::read(input).await?;
+        data.extend_from_slice(suffix.as_bytes());
+        tokio::fs::write(output, data).await
+    }
+
+    #[tokio::test]
+    async fn media_pipeline() {
+        let source =
+            std::env::temp_dir().join(format!("teloxide-source-{}.txt", std::process::id()));
+        tokio::fs::write(&source, "a").await.unwrap();
+
+        let pipeline = MediaPipeline::new()
+            .stage(|input, output| append(input, output, "b"))
+            .stage(|input, output| append(input, output, "c"));
+
+        let processed = pipeline.run_local(&source).await.unwrap();
+        let dir = processed.path().parent().unwrap().to_owned();
+        assert!(dir.exists());
+        assert_eq!(tokio::fs::read(processed.path()).await.unwrap(), b"abc");
+        assert_eq!(processed.into_bytes().await.unwrap(), b"abc");
+        assert!(!dir.exists());
+
+        // The source is left intact
+        assert_eq!(tokio::fs::read(&source).await.unwrap(), b"a");
+
+        // Each file gets its own directory
+        let first = pipeline.run_local(&source).await.unwrap();
+        let second = pipeline.run_local(&source).await.unwrap();
+        assert_ne!(first.path().parent(), second.path().parent());
+
+        let failing = pipeline.stage(|_, _| async { Err(io::Error::other("failed")) });
+        let err = failing.run_local(&source).await.unwrap_err();
+        assert!(matches!(err, MediaPipelineError::Transform { index: 2, .. }));
+
+        tokio::fs::remove_file(&source).await.unwrap();
+    }
+}
@@ -0,0 +1,497 @@
+//! Mirroring messages to another chat.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Display,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    dispatching::dialogue::Storage,
+    payloads::setters::*,
+    requests::Requester,
+    types::{ChatId, MediaGroupId, Message, MessageId, Recipient},
+    utils::purge,
+};
+
+/// How many mirrored messages are remembered per source chat.
+const MAX_TRACKED: usize = 1000;
+
+/// How [`MirrorSink`] re-posts messages.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum MirrorMode {
+    /// Copy messages, i.e. send them without a link to the original message.
+    ///
+    /// Edits of text and captions are applied to the copies.
+    #[default]
+    Copy,
+
+    /// Forward messages.
+    ///
+    /// Since forwarded messages can't be edited, edited messages are deleted
+    /// from the target chat and forwarded again.
+    Forward,
+}
+
+/// An error returned from [`MirrorSink`].
+#[derive(Debug, Error)]
+pub enum MirrorError<R, S> {
+    #[error("request error: {0}")]
+    Request(R),
+
+    #[error("storage error: {0}")]
+    Storage(S),
+}
+
+/// Ids of the mirrored messages of a single source chat.
+///
+/// This is the dialogue type stored in the [`Storage`] of a [`MirrorSink`],
+/// indexed by the id of the source chat. Only the latest 1000 messages of each
+/// chat are remembered.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MirroredMessages {
+    ids: BTreeMap<i32, MessageId>,
+}
+
+impl MirroredMessages {
+    /// Returns the id of the mirror of the message `source`.
+    #[must_use]
+    pub fn get(&self, source: MessageId) -> Option<MessageId> {
+        self.ids.get(&source.0).copied()
+    }
+
+    fn insert(&mut self, source: MessageId, mirror: MessageId) {
+        self.ids.insert(source.0, mirror);
+
+        while self.ids.len() > MAX_TRACKED {
+            self.ids.pop_first();
+        }
+    }
+
+    fn remove(&mut self, source: MessageId) -> Option<MessageId> {
+        self.ids.remove(&source.0)
+    }
+
+    /// Remembers the mirrors of a batch of messages, returning `false` if
+    /// some of them weren't mirrored.
+    ///
+    /// Telegram skips messages which can't be copied or forwarded in batch
+    /// requests and doesn't say which, so the ids can only be matched if none
+    /// were skipped.
+    fn insert_batch(&mut self, sources: Vec<MessageId>, mirrors: Vec<MessageId>) -> bool {
+        if sources.len() != mirrors.len() {
+            return false;
+        }
+
+        for (source, mirror) in sources.into_iter().zip(mirrors) {
+            self.insert(source, mirror);
+        }
+        true
+    }
+}
+
+/// Re-posts messages to another chat, keeping the copies up to date.
+///
+/// Pass messages you want to mirror to [`MirrorSink::mirror`], and their new
+/// versions to [`MirrorSink::mirror_edit`]. The ids of the mirrored messages
+/// are kept in a [`Storage`] (indexed by the source chat id), so edits and
+/// deletions can be propagated after a restart.
+///
+/// Messages of a media group are collected for [`media_group_delay`] and
+/// re-posted as a single album.
+///
+/// Note that Telegram doesn't notify bots about deleted messages, so
+/// deletions must be propagated manually with [`MirrorSink::mirror_delete`].
+///
+/// [`media_group_delay`]: MirrorSink::media_group_delay
+///
+/// ## Examples
+///
+/// ```no_run
+/// use teloxide::{
+///     dispatching::dialogue::InMemStorage,
+///     prelude::*,
+///     utils::mirror::{MirrorSink, MirroredMessages},
+/// };
+///
+/// type Sink = MirrorSink<Bot, InMemStorage<MirroredMessages>>;
+/// type HandlerResult = Result<(), Box<dyn std::error::Error + Send + Sync>>;
+///
+/// # async fn run() {
+/// let bot = Bot::from_env();
+/// let backup = ChatId(-1001234567890);
+/// let sink = MirrorSink::new(bot.clone(), backup, InMemStorage::<MirroredMessages>::new());
+///
+/// let handler = dptree::entry()
+///     .branch(Update::filter_channel_post().endpoint(|sink: Sink, msg: Message| async move {
+///         sink.mirror(&msg).await?;
+///         HandlerResult::Ok(())
+///     }))
+///     .branch(Update::filter_edited_channel_post().endpoint(
+///         |sink: Sink, msg: Message| async move {
+///             sink.mirror_edit(&msg).await?;
+///             HandlerResult::Ok(())
+///         },
+///     ));
+///
+/// Dispatcher::builder(bot, handler).dependencies(dptree::deps![sink]).build().dispatch().await;
+/// # }
+/// ```
+pub struct MirrorSink<R, S: ?Sized> {
+    bot: R,
+    target: Recipient,
+    storage: Arc<S>,
+    mode: MirrorMode,
+    media_group_delay: Duration,
+    pending_groups: Arc<Mutex<HashMap<MediaGroupId, Vec<MessageId>>>>,
+    // Serialize updates of the stored ids of each chat, see `MirrorSink::lock`
+    locks: Arc<Mutex<HashMap<ChatId, Arc<tokio::sync::Mutex<()>>>>>,
+}
+
+impl<R, S> MirrorSink<R, S>
+where
+    R: Requester + Clone + Send + Sync + 'static,
+    R::Err: Display,
+    S: Storage<MirroredMessages> + ?Sized + Send + Sync + 'static,
+    S::Error: Display,
+{
+    /// Creates a sink that copies messages to `target`.
+    #[must_use]
+    pub fn new(bot: R, target: impl Into<Recipient>, storage: Arc<S>) -> Self {
+        Self {
+            bot,
+            target: target.into(),
+            storage,
+            mode: MirrorMode::default(),
+            media_group_delay: Duration::from_secs(1),
+            pending_groups: <_>::default(),
+            locks: <_>::default(),
+        }
+    }
+
+    /// Sets how messages are re-posted.
+    ///
+    /// Default - [`MirrorMode::Copy`].
+    #[must_use]
+    pub fn mode(self, mode: MirrorMode) -> Self {
+        Self { mode, ..self }
+    }
+
+    /// Sets for how long messages of a media group are collected before they
+    /// are re-posted.
+    ///
+    /// Default - 1 second.
+    #[must_use]
+    pub fn media_group_delay(self, delay: Duration) -> Self {
+        Self { media_group_delay: delay, ..self }
+    }
+
+    /// Re-posts `message` to the target chat.
+    ///
+    /// Messages that can't be copied (or forwarded, depending on the [`mode`])
+    /// are skipped.
+    ///
+    /// If `message` is a part of a media group, it is re-posted in the
+    /// background together with the rest of the group, after the
+    /// [`media_group_delay`]. Errors that happen then are logged.
+    ///
+    /// [`mode`]: MirrorSink::mode
+    /// [`media_group_delay`]: MirrorSink::media_group_delay
+    pub async fn mirror(&self, message: &Message) -> Result<(), MirrorError<R::Err, S::Error>> {
+        let allowed = match self.mode {
+            MirrorMode::Copy => message.can_copy(),
+            MirrorMode::Forward => message.can_forward(),
+        };
+        if let Err(reason) = allowed {
+            log::debug!("Not mirroring message {}: {reason}", message.id);
+            return Ok(());
+        }
+
+        let Some(group) = message.media_group_id() else {
+            return self.mirror_batch(message.chat.id, vec![message.id]).await;
+        };
+
+        {
+            let mut pending = self.pending_groups.lock().unwrap();
+            if let Some(ids) = pending.get_mut(group) {
+                ids.push(message.id);
+                return Ok(());
+            }
+
+            pending.insert(group.clone(), vec![message.id]);
+        }
+
+        // Messages of a chat are handled sequentially, so the rest of the group can
+        // only be received after this call returns
+        let this = self.clone();
+        let chat_id = message.chat.id;
+        let group = group.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(this.media_group_delay).await;
+
+            let ids = this.pending_groups.lock().unwrap().remove(&group).unwrap_or_default();
+            if let Err(err) = this.mirror_batch(chat_id, ids).await {
+                log::error!("Failed to mirror media group {group}: {err}");
+            }
+        });
+
+        Ok(())
+    }
+
+    /// Applies an edit of `message` to its mirror.
+    ///
+    /// Does nothing if `message` wasn't mirrored.
+    pub async fn mirror_edit(
+        &self,
+        message: &Message,
+    ) -> Result<(), MirrorError<R::Err, S::Error>> {
+        let chat_id = message.chat.id;
+        let _lock = self.lock(chat_id).await;
+        let mut mirrored = self.load(chat_id).await?;
+        let Some(mirror_id) = mirrored.get(message.id) else {
+            return Ok(());
+        };
+
+        match self.mode {
+            MirrorMode::Copy => {
+                if let Some(text) = message.text() {
+                    self.bot
+                        .edit_message_text(self.target.clone(), mirror_id, text)
+                        .entities(message.entities().unwrap_or_default().to_vec())
+                        .await
+                        .map_err(MirrorError::Request)?;
+                } else if message.caption().is_some() || message.media_group_id().is_some() {
+                    let mut edit = self.bot.edit_message_caption(self.target.clone(), mirror_id);
+                    if let Some(caption) = message.caption() {
+                        edit = edit.caption(caption).caption_entities(
+                            message.caption_entities().unwrap_or_default().to_vec(),
+                        );
+                    }
+
+                    edit.await.map_err(MirrorError::Request)?;
+                }
+            }
+            MirrorMode::Forward => {
+                self.bot
+                    .delete_message(self.target.clone(), mirror_id)
+                    .await
+                    .map_err(MirrorError::Request)?;
+                let forwarded = self
+                    .bot
+                    .forward_message(self.target.clone(), chat_id, message.id)
+                    .await
+                    .map_err(MirrorError::Request)?;
+
+                mirrored.insert(message.id, forwarded.id);
+                self.save(chat_id, mirrored).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Deletes the mirrors of the messages `message_ids` of the chat
+    /// `chat_id`.
+    ///
+    /// Messages that weren't mirrored are ignored.
+    pub async fn mirror_delete<I>(
+        &self,
+        chat_id: ChatId,
+        message_ids: I,
+    ) -> Result<(), MirrorError<R::Err, S::Error>>
+    where
+        I: IntoIterator<Item = MessageId>,
+    {
+        let _lock = self.lock(chat_id).await;
+        let mut mirrored = self.load(chat_id).await?;
+        let mirror_ids: Vec<_> =
+            message_ids.into_iter().filter_map(|id| mirrored.remove(id)).collect();
+        if mirror_ids.is_empty() {
+            return Ok(());
+        }
+
+        purge::delete_messages(&self.bot, self.target.clone(), mirror_ids)
+            .await
+            .map_err(MirrorError::Request)?;
+        self.save(chat_id, mirrored).await
+    }
+
+    async fn mirror_batch(
+        &self,
+        chat_id: ChatId,
+        mut message_ids: Vec<MessageId>,
+    ) -> Result<(), MirrorError<R::Err, S::Error>> {
+        let mirror_ids = match (self.mode, message_ids.as_slice()) {
+            (_, []) => return Ok(()),
+            (MirrorMode::Copy, &[id]) => vec![self
+                .bot
+                .copy_message(self.target.clone(), chat_id, id)
+                .await
+                .map_err(MirrorError::Request)?],
+            (MirrorMode::Forward, &[id]) => vec![
+                self.bot
+                    .forward_message(self.target.clone(), chat_id, id)
+                    .await
+                    .map_err(MirrorError::Request)?
+                    .id,
+            ],
+            // Batch requests require increasing ids and keep media groups intact
+            (MirrorMode::Copy, _) => {
+                message_ids.sort_unstable_by_key(|id| id.0);
+                self.bot
+                    .copy_messages(self.target.clone(), chat_id, message_ids.clone())
+                    .await
+                    .map_err(MirrorError::Request)?
+            }
+            (MirrorMode::Forward, _) => {
+                message_ids.sort_unstable_by_key(|id| id.0);
+                self.bot
+                    .forward_messages(self.target.clone(), chat_id, message_ids.clone())
+                    .await
+                    .map_err(MirrorError::Request)?
+            }
+        };
+
+        let _lock = self.lock(chat_id).await;
+        let mut mirrored = self.load(chat_id).await?;
+        if !mirrored.insert_batch(message_ids, mirror_ids) {
+            log::warn!(
+                "Some messages of chat {chat_id} weren't mirrored, so edits and deletions of the \
+                 batch won't be propagated"
+            );
+        }
+
+        self.save(chat_id, mirrored).await
+    }
+
+    /// Locks the stored ids of `chat_id`, so that concurrent updates of them
+    /// aren't lost.
+    ///
+    /// Media groups are mirrored in the background, concurrently with other
+    /// messages of the chat.
+    async fn lock(&self, chat_id: ChatId) -> tokio::sync::OwnedMutexGuard<()> {
+        let lock = {
+            let mut locks = self.locks.lock().unwrap();
+            // Forget locks which aren't held or waited for
+            locks.retain(|_, lock| Arc::strong_count(lock) > 1);
+            Arc::clone(locks.entry(chat_id).or_default())
+        };
+
+        lock.lock_owned().await
+    }
+
+    async fn load(
+        &self,
+        chat_id: ChatId,
+    ) -> Result<MirroredMessages, MirrorError<R::Err, S::Error>> {
+        let mirrored =
+            Arc::clone(&self.storage).get_dialogue(chat_id).await.map_err(MirrorError::Storage)?;
+
+        Ok(mirrored.unwrap_or_default())
+    }
+
+    async fn save(
+        &self,
+        chat_id: ChatId,
+        mirrored: MirroredMessages,
+    ) -> Result<(), MirrorError<R::Err, S::Error>> {
+        Arc::clone(&self.storage)
+            .update_dialogue(chat_id, mirrored)
+            .await
+            .map_err(MirrorError::Storage)
+    }
+}
+
+impl<R, S> Clone for MirrorSink<R, S>
+where
+    R: Clone,
+    S: ?Sized,
+{
+    fn clone(&self) -> Self {
+        Self {
+            bot: self.bot.clone(),
+            target: self.target.clone(),
+            storage: Arc::clone(&self.storage),
+            mode: self.mode,
+            media_group_delay: self.media_group_delay,
+            pending_groups: Arc::clone(&self.pending_groups),
+            locks: Arc::clone(&self.locks),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mirrored_messages_are_bounded() {
+        let mut mirrored = MirroredMessages::default();
+        for id in 0..MAX_TRACKED as i32 + 10 {
+            mirrored.insert(MessageId(id), MessageId(id + 100_000));
+        }
+
+        assert_eq!(mirrored.ids.len(), MAX_TRACKED);
+        assert_eq!(mirrored.get(MessageId(9)), None);
+        assert_eq!(mirrored.get(MessageId(10)), Some(MessageId(100_010)));
+
+        assert_eq!(mirrored.remove(MessageId(10)), Some(MessageId(100_010)));
+        assert_eq!(mirrored.get(MessageId(10)), None);
+    }
+
+    #[test]
+    fn batches() {
+        let ids = |ids: &[i32]| ids.iter().copied().map(MessageId).collect::<Vec<_>>();
+        let mut mirrored = MirroredMessages::default();
+
+        assert!(mirrored.insert_batch(ids(&[1, 2, 3]), ids(&[11, 12, 13])));
+        assert_eq!(mirrored.get(MessageId(2)), Some(MessageId(12)));
+        assert_eq!(mirrored.get(MessageId(3)), Some(MessageId(13)));
+
+        // A message of the batch was skipped, so it's unknown which mirror is which
+        assert!(!mirrored.insert_batch(ids(&[4, 5, 6]), ids(&[14, 15])));
+        assert_eq!(mirrored.get(MessageId(4)), None);
+        assert_eq!(mirrored.get(MessageId(5)), None);
+        assert_eq!(mirrored.ids.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn concurrent_updates() {
+        use crate::dispatching::dialogue::InMemStorage;
+
+        let storage = InMemStorage::<MirroredMessages>::new();
+        let sink = MirrorSink::new(crate::Bot::new("TOKEN"), ChatId(2), storage);
+
+        let tasks: Vec<_> = (0..20)
+            .map(|id| {
+                let sink = sink.clone();
+                tokio::spawn(async move {
+                    let _lock = sink.lock(ChatId(1)).await;
+                    let mut mirrored = sink.load(ChatId(1)).await.unwrap();
+                    tokio::task::yield_now().await;
+                    mirrored.insert(MessageId(id), MessageId(id + 100));
+                    sink.save(ChatId(1), mirrored).await.unwrap();
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        assert_eq!(sink.load(ChatId(1)).await.unwrap().ids.len(), 20);
+        assert_eq!(sink.locks.lock().unwrap().values().map(Arc::strong_count).sum::<usize>(), 1);
+    }
+
+    #[test]
+    fn mirrored_messages_serialization() {
+        let mut mirrored = MirroredMessages::default();
+        mirrored.insert(MessageId(1), MessageId(2));
+
+        let json = serde_json::to_string(&mirrored).unwrap();
+        assert_eq!(serde_json::from_str::<MirroredMessages>(&json).unwrap(), mirrored);
+    }
+}
@@ -0,0 +1,97 @@
+//! Deleting many messages at once, for cleanup bots.
+
+use teloxide_core::{
+    requests::Requester,
+    types::{MessageId, Recipient},
+};
+
+/// Deletes the last `count` messages in a chat, up to `latest` (inclusive).
+///
+/// The Bot API doesn't provide the id of the last message in a chat, so it
+/// has to be passed, e.g. the id of the command which asked for a cleanup.
+/// See [`delete_messages`] for details.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use teloxide::{prelude::*, utils::purge::purge_last};
+///
+/// async fn purge(bot: Bot, msg: Message, count: u32) -> ResponseResult<()> {
+///     // Deletes the command and `count` messages before it
+///     purge_last(&bot, msg.chat.id, msg.id, count + 1).await
+/// }
+/// ```
+pub async fn purge_last<R, C>(
+    bot: &R,
+    chat_id: C,
+    latest: MessageId,
+    count: u32,
+) -> Result<(), R::Err>
+where
+    R: Requester,
+    C: Into<Recipient>,
+{
+    delete_messages(bot, chat_id, MessageId::range_to(latest, count)).await
+}
+
+/// Deletes messages with [`DeleteMessages`], in batches of
+/// [`MessageId::MAX_BATCH`] messages.
+///
+/// Messages which can't be found are skipped by Telegram. Batches are deleted
+/// one by one, stopping at the first error.
+///
+/// [`DeleteMessages`]: crate::payloads::DeleteMessages
+pub async fn delete_messages<R, C, I>(bot: &R, chat_id: C, message_ids: I) -> Result<(), R::Err>
+where
+    R: Requester,
+    C: Into<Recipient>,
+    I: IntoIterator<Item = MessageId>,
+{
+    let chat_id = chat_id.into();
+    for batch in MessageId::batches(message_ids) {
+        bot.delete_messages(chat_id.clone(), batch).await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{test_utils::fake_api, types::ChatId, Bot, RequestError};
+
+    #[tokio::test]
+    async fn batches() {
+        let (url, mut payloads) = fake_api(vec![Some(json!(true)); 3]).await;
+        let bot = Bot::new("TOKEN").set_api_url(url);
+
+        purge_last(&bot, ChatId(1), MessageId(250), 300).await.unwrap();
+
+        let ids = |range: std::ops::RangeInclusive<i32>| range.collect::<Vec<_>>();
+        assert_eq!(
+            payloads.recv().await.unwrap(),
+            json!({ "chat_id": 1, "message_ids": ids(1..=100) })
+        );
+        assert_eq!(payloads.recv().await.unwrap()["message_ids"], json!(ids(101..=200)));
+        assert_eq!(payloads.recv().await.unwrap()["message_ids"], json!(ids(201..=250)));
+        assert!(payloads.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn stops_at_error() {
+        let (url, mut payloads) = fake_api(vec![Some(json!(true)), None]).await;
+        let bot = Bot::new("TOKEN").set_api_url(url);
+
+        // The last batch would fail with a network error, since the server
+        // closes the connection
+        let ids = MessageId::range_to(MessageId(300), 300);
+        let res = delete_messages(&bot, ChatId(1), ids).await;
+        assert!(matches!(res, Err(RequestError::Api(_))), "{res:?}");
+
+        assert_eq!(payloads.recv().await.unwrap()["message_ids"][0], 1);
+        assert_eq!(payloads.recv().await.unwrap()["message_ids"][0], 101);
+        assert!(payloads.recv().await.is_none());
+    }
+}
@@ -0,0 +1,98 @@
+//! A map which remembers only the most recently updated entries.
+
+use std::{collections::HashMap, hash::Hash};
+
+/// A map which keeps at most `capacity` entries, forgetting the least recently
+/// inserted or updated ones.
+///
+/// Once the map is over capacity, the older half of the entries is removed at
+/// once, so that inserting stays cheap on average.
+#[derive(Debug)]
+pub(crate) struct RecentMap<K, V> {
+    entries: HashMap<K, (u64, V)>,
+    capacity: usize,
+    // Incremented on each insertion, to order the entries
+    clock: u64,
+}
+
+impl<K, V> RecentMap<K, V>
+where
+    K: Eq + Hash,
+{
+    /// Creates an empty map.
+    ///
+    /// ## Panics
+    ///
+    /// If `capacity` is zero.
+    #[track_caller]
+    pub(crate) fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "capacity must be greater than zero");
+        Self { entries: HashMap::new(), capacity, clock: 0 }
+    }
+
+    /// Returns the value of `key`, without marking the entry as recent.
+    pub(crate) fn get(&self, key: &K) -> Option<&V> {
+        self.entries.get(key).map(|(_, value)| value)
+    }
+
+    /// Inserts or updates the entry of `key`, marking it as the most recent
+    /// one, and returns the previous value.
+    pub(crate) fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.clock += 1;
+        let previous = self.entries.insert(key, (self.clock, value)).map(|(_, value)| value);
+
+        if self.entries.len() > self.capacity {
+            self.evict();
+        }
+        previous
+    }
+
+    /// Removes the entry of `key`, returning its value.
+    pub(crate) fn remove(&mut self, key: &K) -> Option<V> {
+        self.entries.remove(key).map(|(_, value)| value)
+    }
+
+    /// Keeps only the entries for which `f` returns `true`.
+    pub(crate) fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        self.entries.retain(|key, (_, value)| f(key, value));
+    }
+
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Keeps the newest half of the entries.
+    fn evict(&mut self) {
+        let keep = self.capacity.div_ceil(2);
+        let mut times: Vec<_> = self.entries.values().map(|&(time, _)| time).collect();
+        let (_, &mut oldest_kept, _) = times.select_nth_unstable(self.entries.len() - keep);
+
+        self.entries.retain(|_, &mut (time, _)| time >= oldest_kept);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_recent_entries() {
+        let mut map = RecentMap::new(4);
+        for key in 0..4 {
+            assert_eq!(map.insert(key, key * 10), None);
+        }
+        assert_eq!(map.insert(0, 1), Some(0));
+        assert_eq!(map.len(), 4);
+
+        // The newest half is kept, 0 was updated recently
+        map.insert(4, 40);
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(&0), Some(&1));
+        assert_eq!(map.get(&3), None);
+        assert_eq!(map.get(&4), Some(&40));
+    }
+}
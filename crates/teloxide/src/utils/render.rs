@@ -169,6 +169,26 @@ impl<'a> Renderer<'a> {
     }
 }
 
+/// Renders `text` with `entities` as an **HTML-formatted** string.
+///
+/// This is a shorthand for `Renderer::new(text, entities).as_html()`, useful
+/// for re-sending user messages with [`ParseMode::Html`] without losing their
+/// formatting.
+///
+/// [`ParseMode::Html`]: crate::types::ParseMode::Html
+#[must_use]
+pub fn to_html(text: &str, entities: &[MessageEntity]) -> String {
+    Renderer::new(text, entities).as_html()
+}
+
+/// Renders `text` with `entities` as a **MarkdownV2-formatted** string.
+///
+/// This is a shorthand for `Renderer::new(text, entities).as_markdown()`.
+#[must_use]
+pub fn to_markdown(text: &str, entities: &[MessageEntity]) -> String {
+    Renderer::new(text, entities).as_markdown()
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -186,6 +206,8 @@ mod test {
 
         assert_eq!(render.as_html(), "<b>Bold</b> <i>italic</i> <u>&lt;underline</u>_");
         assert_eq!(render.as_markdown(), "*Bold* _\ritalic_\r __\r<underline__\r\\_");
+        assert_eq!(to_html(text, &entities), render.as_html());
+        assert_eq!(to_markdown(text, &entities), render.as_markdown());
     }
 
     #[test]
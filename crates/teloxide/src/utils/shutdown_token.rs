@@ -1,10 +1,12 @@
 use std::{
     fmt,
     future::Future,
+    pin::pin,
     sync::{
-        atomic::{AtomicU8, Ordering},
+        atomic::{AtomicBool, AtomicU8, AtomicUsize, Ordering},
         Arc,
     },
+    time::Duration,
 };
 
 use tokio::sync::Notify;
@@ -43,11 +45,56 @@ impl ShutdownToken {
         }
     }
 
+    /// Tries to shutdown dispatching, aborting handlers that are still running
+    /// after `timeout`.
+    ///
+    /// This works like [`shutdown`], but if dispatching hasn't finished within
+    /// `timeout` (e.g. because some handler is stuck), all remaining handler
+    /// tasks are aborted, including the updates that are queued for them.
+    ///
+    /// The returned future resolves to the number of updates which handling
+    /// was cancelled (`0` if dispatching was shut down gracefully).
+    ///
+    /// Returns an error if the dispatcher is idle at the moment.
+    ///
+    /// [`shutdown`]: ShutdownToken::shutdown
+    pub fn shutdown_with_timeout(
+        &self,
+        timeout: Duration,
+    ) -> Result<impl Future<Output = usize> + '_, IdleShutdownError> {
+        let shutdown = self.shutdown()?;
+
+        Ok(async move {
+            if tokio::time::timeout(timeout, shutdown).await.is_ok() {
+                return 0;
+            }
+
+            let mut done = pin!(self.shutdown_notify_back.notified());
+            done.as_mut().enable();
+
+            // Dispatching could have finished right after the timeout has expired
+            if matches!(self.dispatcher_state.load(), ShutdownState::Idle) {
+                return 0;
+            }
+
+            log::warn!(
+                "Dispatching wasn't shut down in {timeout:?}, aborting the remaining handlers..."
+            );
+            self.dispatcher_state.abort_requested.store(true, Ordering::Relaxed);
+            self.dispatcher_state.notify.notify_waiters();
+
+            done.await;
+            self.dispatcher_state.cancelled.load(Ordering::Relaxed)
+        })
+    }
+
     pub(crate) fn new() -> Self {
         Self {
             dispatcher_state: Arc::new(DispatcherState {
                 inner: AtomicU8::new(ShutdownState::Idle as _),
                 notify: <_>::default(),
+                abort_requested: AtomicBool::new(false),
+                cancelled: AtomicUsize::new(0),
             }),
             shutdown_notify_back: <_>::default(),
         }
@@ -57,6 +104,27 @@ impl ShutdownToken {
         self.dispatcher_state.notify.notified().await;
     }
 
+    /// Resolves when [`shutdown_with_timeout`] decides to abort the handlers.
+    ///
+    /// [`shutdown_with_timeout`]: ShutdownToken::shutdown_with_timeout
+    pub(crate) async fn wait_for_abort(&self) {
+        loop {
+            let mut notified = pin!(self.dispatcher_state.notify.notified());
+            notified.as_mut().enable();
+
+            if self.dispatcher_state.abort_requested.load(Ordering::Relaxed) {
+                return;
+            }
+
+            notified.await;
+        }
+    }
+
+    /// Records the number of updates which handling was cancelled.
+    pub(crate) fn set_cancelled(&self, cancelled: usize) {
+        self.dispatcher_state.cancelled.store(cancelled, Ordering::Relaxed);
+    }
+
     pub(crate) fn start_dispatching(&self) {
         if let Err(actual) =
             self.dispatcher_state.compare_exchange(ShutdownState::Idle, ShutdownState::Running)
@@ -67,6 +135,9 @@ impl ShutdownToken {
                 actual
             );
         }
+
+        self.dispatcher_state.abort_requested.store(false, Ordering::Relaxed);
+        self.dispatcher_state.cancelled.store(0, Ordering::Relaxed);
     }
 
     pub(crate) fn is_shutting_down(&self) -> bool {
@@ -99,6 +170,8 @@ impl std::error::Error for IdleShutdownError {}
 struct DispatcherState {
     inner: AtomicU8,
     notify: Notify,
+    abort_requested: AtomicBool,
+    cancelled: AtomicUsize,
 }
 
 impl DispatcherState {
@@ -0,0 +1,210 @@
+//! Composing formatted messages.
+
+use teloxide_core::types::{ParseMode, UserId};
+
+use crate::utils::{html, markdown};
+
+/// A builder of formatted message text, which escapes everything passed to it
+/// according to the chosen parse mode.
+///
+/// Unlike the functions in [`html`] and [`markdown`] modules, all methods
+/// (except [`raw`]) take plain text, so user input can be passed as is.
+///
+/// [`raw`]: Builder::raw
+///
+/// ## Examples
+///
+/// ```
+/// use teloxide::{types::ParseMode, utils::text::Builder};
+///
+/// let user_input = "<script>";
+/// let (text, parse_mode) = Builder::html().bold(user_input).text(" said ").code("1 < 2").build();
+///
+/// assert_eq!(text, "<b>&lt;script&gt;</b> said <code>1 &lt; 2</code>");
+/// assert_eq!(parse_mode, ParseMode::Html);
+///
+/// let (text, _) = Builder::markdown().bold(user_input).text(" said ").code("1 < 2").build();
+/// assert_eq!(text, r"*<script\>* said `1 < 2`");
+/// ```
+#[derive(Clone, Debug)]
+#[must_use]
+pub struct Builder {
+    parse_mode: ParseMode,
+    text: String,
+}
+
+impl Builder {
+    /// Creates an empty builder for the given parse mode.
+    ///
+    /// ## Panics
+    ///
+    /// If `parse_mode` is the deprecated [`ParseMode::Markdown`].
+    #[allow(deprecated)]
+    #[track_caller]
+    pub fn new(parse_mode: ParseMode) -> Self {
+        assert!(
+            parse_mode != ParseMode::Markdown,
+            "legacy `ParseMode::Markdown` is not supported, use `ParseMode::MarkdownV2`"
+        );
+
+        Self { parse_mode, text: String::new() }
+    }
+
+    /// Creates an empty builder for [`ParseMode::Html`].
+    pub fn html() -> Self {
+        Self::new(ParseMode::Html)
+    }
+
+    /// Creates an empty builder for [`ParseMode::MarkdownV2`].
+    pub fn markdown() -> Self {
+        Self::new(ParseMode::MarkdownV2)
+    }
+
+    /// Returns the parse mode of this builder.
+    #[must_use]
+    pub fn parse_mode(&self) -> ParseMode {
+        self.parse_mode
+    }
+
+    /// Appends plain text.
+    pub fn text(self, s: &str) -> Self {
+        let escaped = self.escape(s);
+        self.raw(&escaped)
+    }
+
+    /// Appends already formatted text, which is not escaped.
+    pub fn raw(mut self, markup: &str) -> Self {
+        self.text.push_str(markup);
+        self
+    }
+
+    /// Appends bold text.
+    pub fn bold(self, s: &str) -> Self {
+        self.styled(s, html::bold, markdown::bold)
+    }
+
+    /// Appends italic text.
+    pub fn italic(self, s: &str) -> Self {
+        // `\r` is ignored by Telegram, but separates `_` from a following `__`
+        self.styled(s, html::italic, |s| format!("_{s}_\r"))
+    }
+
+    /// Appends underlined text.
+    pub fn underline(self, s: &str) -> Self {
+        self.styled(s, html::underline, markdown::underline)
+    }
+
+    /// Appends strikethrough text.
+    pub fn strike(self, s: &str) -> Self {
+        self.styled(s, html::strike, markdown::strike)
+    }
+
+    /// Appends text hidden by a spoiler.
+    pub fn spoiler(self, s: &str) -> Self {
+        self.styled(s, |s| format!("<tg-spoiler>{s}</tg-spoiler>"), |s| format!("||{s}||"))
+    }
+
+    /// Appends a block quotation.
+    pub fn blockquote(self, s: &str) -> Self {
+        self.styled(s, html::blockquote, |s| format!(">{}", s.replace('\n', "\n>")))
+    }
+
+    /// Appends inline code.
+    pub fn code(self, s: &str) -> Self {
+        let markup = match self.parse_mode {
+            ParseMode::Html => html::code_inline(s),
+            _ => markdown::code_inline(s),
+        };
+        self.raw(&markup)
+    }
+
+    /// Appends a code block, optionally highlighted as `lang`.
+    pub fn code_block(self, code: &str, lang: Option<&str>) -> Self {
+        let markup = match (self.parse_mode, lang) {
+            (ParseMode::Html, None) => html::code_block(code),
+            (ParseMode::Html, Some(lang)) => html::code_block_with_lang(code, lang),
+            (_, None) => markdown::code_block(code),
+            (_, Some(lang)) => markdown::code_block_with_lang(code, lang),
+        };
+        self.raw(&markup)
+    }
+
+    /// Appends a link to `url` with `text`.
+    pub fn link(self, url: &str, text: &str) -> Self {
+        let markup = match self.parse_mode {
+            ParseMode::Html => format!(
+                "<a href=\"{}\">{}</a>",
+                html::escape(url).replace('"', "&quot;"),
+                html::escape(text)
+            ),
+            _ => markdown::link(url, &markdown::escape(text)),
+        };
+        self.raw(&markup)
+    }
+
+    /// Appends a mention of a user with `text`.
+    pub fn user_mention(self, user_id: UserId, text: &str) -> Self {
+        self.link(&format!("tg://user?id={user_id}"), text)
+    }
+
+    /// Returns the formatted text and the parse mode to send it with.
+    #[must_use]
+    pub fn build(self) -> (String, ParseMode) {
+        (self.text, self.parse_mode)
+    }
+
+    fn escape(&self, s: &str) -> String {
+        match self.parse_mode {
+            ParseMode::Html => html::escape(s),
+            _ => markdown::escape(s),
+        }
+    }
+
+    fn styled(
+        self,
+        s: &str,
+        html: impl FnOnce(&str) -> String,
+        markdown: impl FnOnce(&str) -> String,
+    ) -> Self {
+        let escaped = self.escape(s);
+        let markup = match self.parse_mode {
+            ParseMode::Html => html(&escaped),
+            _ => markdown(&escaped),
+        };
+        self.raw(&markup)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markdown() {
+        let (text, parse_mode) = Builder::markdown()
+            .italic("a_b")
+            .underline("c")
+            .text(" 1.5! ")
+            .link("https://example.com/(x)", "[docs]")
+            .build();
+
+        assert_eq!(parse_mode, ParseMode::MarkdownV2);
+        assert_eq!(text, "_a\\_b_\r__c__ 1\\.5\\! [\\[docs\\]](https://example.com/(x\\))");
+    }
+
+    #[test]
+    fn html() {
+        let (text, _) = Builder::html()
+            .spoiler("a & b")
+            .link("https://example.com/?q=\"x\"", "<x>")
+            .code_block("fn main() {}", Some("rust"))
+            .build();
+
+        assert_eq!(
+            text,
+            "<tg-spoiler>a &amp; b</tg-spoiler>\
+             <a href=\"https://example.com/?q=&quot;x&quot;\">&lt;x&gt;</a>\
+             <pre><code class=\"language-rust\">fn main() {}</code></pre>"
+        );
+    }
+}
@@ -0,0 +1,321 @@
+//! Caching of forum topics.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use dptree::Handler;
+use teloxide_core::types::{
+    ChatId, CustomEmojiId, ForumTopic, Message, Rgb, ThreadId, Update, UpdateKind,
+};
+
+use crate::dispatching::DpHandlerDescription;
+
+/// A forum topic known to a [`TopicRegistry`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Topic {
+    /// Unique identifier of the forum topic.
+    pub thread_id: ThreadId,
+
+    /// Name of the topic.
+    pub name: String,
+
+    /// Color of the topic icon, if it's known.
+    ///
+    /// The color is unknown for topics that were only seen being edited.
+    pub icon_color: Option<Rgb>,
+
+    /// Unique identifier of the custom emoji shown as the topic icon.
+    pub icon_custom_emoji_id: Option<CustomEmojiId>,
+}
+
+impl From<ForumTopic> for Topic {
+    fn from(topic: ForumTopic) -> Self {
+        let ForumTopic { thread_id, name, icon_color, icon_custom_emoji_id } = topic;
+        Self { thread_id, name, icon_color: Some(icon_color), icon_custom_emoji_id }
+    }
+}
+
+/// A registry of forum topics, for bots that need names of topics without
+/// making extra requests.
+///
+/// Telegram doesn't provide a method to get a forum topic by its id, so the
+/// registry learns topics from messages passed to [`observe`]:
+/// [`ForumTopicCreated`] and [`ForumTopicEdited`] service messages, and
+/// regular topic messages (which reply to the service message that created
+/// their topic). Topics created by the bot itself can be added with
+/// [`insert`], using the [`ForumTopic`] returned by [`CreateForumTopic`].
+///
+/// Clones share the same state, so a registry can be passed to handlers as a
+/// dependency. The handler returned by [`TopicRegistry::into_handler`]
+/// observes all messages passing through it.
+///
+/// [`observe`]: TopicRegistry::observe
+/// [`insert`]: TopicRegistry::insert
+/// [`ForumTopicCreated`]: crate::types::ForumTopicCreated
+/// [`ForumTopicEdited`]: crate::types::ForumTopicEdited
+/// [`CreateForumTopic`]: crate::payloads::CreateForumTopic
+///
+/// ## Examples
+///
+/// ```no_run
+/// use teloxide::{
+///     prelude::*,
+///     utils::topics::{Topic, TopicRegistry},
+/// };
+///
+/// # async fn run() {
+/// let bot = Bot::from_env();
+/// let topics = TopicRegistry::new();
+///
+/// let handler = topics.clone().into_handler().branch(Update::filter_message().endpoint(
+///     |bot: Bot, msg: Message, topic: Option<Topic>| async move {
+///         if let Some(topic) = topic {
+///             bot.send_message(msg.chat.id, format!("Posted in «{}»", topic.name))
+///                 .message_thread_id(topic.thread_id)
+///                 .await?;
+///         }
+///         respond(())
+///     },
+/// ));
+///
+/// Dispatcher::builder(bot, handler).dependencies(dptree::deps![topics]).build().dispatch().await;
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+#[must_use]
+pub struct TopicRegistry {
+    topics: Arc<Mutex<HashMap<(ChatId, ThreadId), Topic>>>,
+}
+
+impl TopicRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the topic `thread_id` of `chat_id`, if it's known.
+    #[must_use]
+    pub fn topic(&self, chat_id: ChatId, thread_id: ThreadId) -> Option<Topic> {
+        self.topics.lock().unwrap().get(&(chat_id, thread_id)).cloned()
+    }
+
+    /// Returns the name of the topic `thread_id` of `chat_id`, if it's known.
+    #[must_use]
+    pub fn topic_name(&self, chat_id: ChatId, thread_id: ThreadId) -> Option<String> {
+        self.topic(chat_id, thread_id).map(|topic| topic.name)
+    }
+
+    /// Returns the known topic `msg` was sent to.
+    #[must_use]
+    pub fn topic_of(&self, msg: &Message) -> Option<Topic> {
+        self.topic(msg.chat.id, msg.forum_topic_id()?)
+    }
+
+    /// Returns all known topics of `chat_id`, in no particular order.
+    #[must_use]
+    pub fn topics(&self, chat_id: ChatId) -> Vec<Topic> {
+        let topics = self.topics.lock().unwrap();
+        topics.iter().filter(|((chat, _), _)| *chat == chat_id).map(|(_, t)| t.clone()).collect()
+    }
+
+    /// Adds or replaces a topic of `chat_id`, e.g. one returned by
+    /// [`CreateForumTopic`].
+    ///
+    /// [`CreateForumTopic`]: crate::payloads::CreateForumTopic
+    pub fn insert<T>(&self, chat_id: ChatId, topic: T)
+    where
+        T: Into<Topic>,
+    {
+        let topic = topic.into();
+        self.topics.lock().unwrap().insert((chat_id, topic.thread_id), topic);
+    }
+
+    /// Removes the topic from the registry, e.g. after it was deleted with
+    /// [`DeleteForumTopic`].
+    ///
+    /// [`DeleteForumTopic`]: crate::payloads::DeleteForumTopic
+    pub fn remove(&self, chat_id: ChatId, thread_id: ThreadId) {
+        self.topics.lock().unwrap().remove(&(chat_id, thread_id));
+    }
+
+    /// Removes all topics of `chat_id` from the registry.
+    pub fn remove_chat(&self, chat_id: ChatId) {
+        self.topics.lock().unwrap().retain(|&(chat, _), _| chat != chat_id);
+    }
+
+    /// Learns topics from `msg`.
+    pub fn observe(&self, msg: &Message) {
+        let Some(thread_id) = msg.forum_topic_id() else { return };
+        let key = (msg.chat.id, thread_id);
+
+        if let Some(created) = msg.forum_topic_created() {
+            let topic = Topic {
+                thread_id,
+                name: created.name.clone(),
+                icon_color: Some(created.icon_color),
+                icon_custom_emoji_id: created.icon_custom_emoji_id.clone(),
+            };
+            self.topics.lock().unwrap().insert(key, topic);
+        } else if let Some(edited) = msg.forum_topic_edited() {
+            let mut topics = self.topics.lock().unwrap();
+            match topics.get_mut(&key) {
+                Some(topic) => {
+                    if let Some(name) = &edited.name {
+                        topic.name = name.clone();
+                    }
+                    if let Some(icon) = &edited.icon_custom_emoji_id {
+                        topic.icon_custom_emoji_id =
+                            Some(icon.clone()).filter(|id| !id.0.is_empty());
+                    }
+                }
+                // Without a name the topic can't be labeled, so don't add it
+                None => {
+                    if let Some(name) = edited.name.clone() {
+                        let icon_custom_emoji_id =
+                            edited.icon_custom_emoji_id.clone().filter(|id| !id.0.is_empty());
+                        topics.insert(
+                            key,
+                            Topic { thread_id, name, icon_color: None, icon_custom_emoji_id },
+                        );
+                    }
+                }
+            }
+        } else if let Some(reply) = msg.reply_to_message() {
+            // Don't overwrite edits with the original name of the topic
+            if reply.forum_topic_created().is_some() && self.topic(msg.chat.id, thread_id).is_none()
+            {
+                self.observe(reply);
+            }
+        }
+    }
+
+    /// Returns a handler, which [observes] messages and injects the topic of
+    /// the message as `Option<Topic>`.
+    ///
+    /// The topic is `None` for updates that aren't messages in a forum topic,
+    /// and for messages in topics that aren't known.
+    ///
+    /// ## Dependency requirements
+    ///
+    ///  - [`crate::types::Update`]
+    ///
+    /// [observes]: TopicRegistry::observe
+    pub fn into_handler<Output>(self) -> Handler<'static, Output, DpHandlerDescription>
+    where
+        Output: Send + Sync + 'static,
+    {
+        dptree::map(move |update: Update| {
+            let msg = message(&update)?;
+            self.observe(msg);
+            self.topic_of(msg)
+        })
+    }
+}
+
+/// Returns the message of `update`, if it has one.
+fn message(update: &Update) -> Option<&Message> {
+    match &update.kind {
+        UpdateKind::Message(msg) | UpdateKind::EditedMessage(msg) => Some(msg),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::ControlFlow;
+
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::test_utils;
+
+    /// Returns the JSON of a message in the topic `thread_id` of a forum, with
+    /// the fields of `extra` instead of the text.
+    fn message_json(thread_id: i32, extra: Value) -> Value {
+        let mut msg = test_utils::message_json(1, "");
+        msg.as_object_mut().unwrap().remove("text");
+        msg["chat"] = json!({ "id": -100, "type": "supergroup", "title": "G", "is_forum": true });
+        msg["is_topic_message"] = true.into();
+        msg["message_thread_id"] = thread_id.into();
+        msg.as_object_mut().unwrap().extend(extra.as_object().unwrap().clone());
+        msg
+    }
+
+    fn message(thread_id: i32, extra: Value) -> Message {
+        serde_json::from_value(message_json(thread_id, extra)).unwrap()
+    }
+
+    fn created(thread_id: i32, name: &str) -> Value {
+        let mut msg = message_json(
+            thread_id,
+            json!({ "forum_topic_created": { "name": name, "icon_color": 7322096 } }),
+        );
+        msg["message_id"] = thread_id.into();
+        msg
+    }
+
+    #[test]
+    fn observe() {
+        let registry = TopicRegistry::new();
+        let chat = ChatId(-100);
+        let thread = |id| ThreadId(teloxide_core::types::MessageId(id));
+
+        registry.observe(&message(
+            4,
+            json!({ "forum_topic_created": { "name": "News", "icon_color": 7322096 } }),
+        ));
+        assert_eq!(registry.topic_name(chat, thread(4)).as_deref(), Some("News"));
+
+        registry.observe(&message(
+            4,
+            json!({
+                "forum_topic_edited": { "name": "Updates", "icon_custom_emoji_id": "5312536423851630001" }
+            }),
+        ));
+        let topic = registry.topic(chat, thread(4)).unwrap();
+        assert_eq!(topic.name, "Updates");
+        assert_eq!(topic.icon_color, Some(Rgb { r: 0x6F, g: 0xB9, b: 0xF0 }));
+        assert!(topic.icon_custom_emoji_id.is_some());
+
+        // The reply to the creation message doesn't restore the old name
+        registry
+            .observe(&message(4, json!({ "text": "hi", "reply_to_message": created(4, "News") })));
+        assert_eq!(registry.topic_name(chat, thread(4)).as_deref(), Some("Updates"));
+
+        registry
+            .observe(&message(8, json!({ "text": "hi", "reply_to_message": created(8, "Chat") })));
+        assert_eq!(registry.topic_name(chat, thread(8)).as_deref(), Some("Chat"));
+        assert_eq!(registry.topics(chat).len(), 2);
+
+        registry
+            .observe(&message(9, json!({ "forum_topic_edited": { "icon_custom_emoji_id": "" } })));
+        assert!(registry.topic(chat, thread(9)).is_none());
+
+        registry.remove(chat, thread(8));
+        assert!(registry.topic(chat, thread(8)).is_none());
+        registry.remove_chat(chat);
+        assert!(registry.topics(chat).is_empty());
+    }
+
+    #[tokio::test]
+    async fn into_handler() {
+        let registry = TopicRegistry::new();
+        let handler = registry
+            .clone()
+            .into_handler()
+            .endpoint(|topic: Option<Topic>| async move { topic.map(|t| t.name) });
+
+        let update = |msg: Value| test_utils::update(1, "message", msg);
+
+        let msg = message_json(4, json!({ "text": "hi", "reply_to_message": created(4, "News") }));
+        assert_eq!(
+            handler.dispatch(dptree::deps![update(msg)]).await,
+            ControlFlow::Break(Some("News".to_owned()))
+        );
+
+        let msg = message_json(5, json!({ "text": "hi" }));
+        assert_eq!(handler.dispatch(dptree::deps![update(msg)]).await, ControlFlow::Break(None));
+    }
+}
@@ -166,6 +166,36 @@ fn parse_with_split4() {
     assert_eq!(DefaultCommands::Start(), DefaultCommands::parse("/start", "").unwrap(),);
 }
 
+#[test]
+#[cfg(feature = "macros")]
+fn parse_with_split_quoted() {
+    use teloxide::utils::command::ParseError;
+
+    #[derive(BotCommands, Debug, PartialEq)]
+    #[command(rename_rule = "lowercase", parse_with = "split")]
+    enum DefaultCommands {
+        Say(String, u8),
+        Echo(String),
+    }
+
+    assert_eq!(
+        DefaultCommands::Say("hello world".to_owned(), 2),
+        DefaultCommands::parse(r#"/say@MyBot "hello world" 2"#, "mybot").unwrap()
+    );
+    assert_eq!(
+        DefaultCommands::Say(r#"say "hi""#.to_owned(), 1),
+        DefaultCommands::parse(r#"/say "say "hi"" 1"#, "").unwrap()
+    );
+    assert_eq!(
+        DefaultCommands::Echo("hello world".to_owned()),
+        DefaultCommands::parse(r#"/echo "hello world""#, "").unwrap()
+    );
+    assert!(matches!(
+        DefaultCommands::parse(r#"/echo "hello world"#, ""),
+        Err(ParseError::TooManyArguments { expected: 1, found: 2, .. })
+    ));
+}
+
 #[test]
 #[cfg(feature = "macros")]
 fn parse_with_command_separator1() {
@@ -576,6 +606,100 @@ fn hide_aliases_with_aliases() {
     assert_eq!("/start, /s, /старт\n/help", DefaultCommands::descriptions().to_string());
 }
 
+#[test]
+#[cfg(feature = "macros")]
+fn categories_and_localization() {
+    #[derive(BotCommands, Debug, PartialEq)]
+    #[command(rename_rule = "lowercase", description = "Bot <commands>:")]
+    enum DefaultCommands {
+        /// Ban a user.
+        #[command(category = "Admin", localized(uk = "Заблокувати користувача."))]
+        Ban,
+        /// Show this text.
+        #[command(localized(uk = "Показати цей текст.", pt_br = "Mostrar este texto."))]
+        Help,
+        /// Mute a user.
+        #[command(category = "Admin")]
+        Mute,
+        #[command(category = "Fun", alias = "d")]
+        Dice,
+    }
+
+    let descriptions = DefaultCommands::descriptions();
+    assert_eq!(
+        descriptions.to_string(),
+        "Bot <commands>:\n\n/help — Show this text.\n\nAdmin\n/ban — Ban a user.\n/mute — Mute a \
+         user.\n\nFun\n/dice, /d"
+    );
+    assert_eq!(
+        descriptions.clone().locale("uk").hide_categories(&["Fun"]).to_string(),
+        "Bot <commands>:\n\n/help — Показати цей текст.\n\nAdmin\n/ban — Заблокувати \
+         користувача.\n/mute — Mute a user."
+    );
+    assert_eq!(
+        descriptions.clone().locale("pt-BR").hide_categories(&["Admin", "Fun"]).to_string(),
+        "Bot <commands>:\n\n/help — Mostrar este texto."
+    );
+    assert_eq!(
+        descriptions.clone().hide_categories(&["Admin"]).to_html(),
+        "Bot &lt;commands&gt;:\n\n/help — Show this text.\n\n<b>Fun</b>\n/dice, /d"
+    );
+    assert_eq!(
+        descriptions.hide_categories(&["Admin"]).username("my_bot").to_markdown(),
+        "Bot <commands\\>:\n\n/help@my\\_bot — Show this text\\.\n\n*Fun*\n/dice@my\\_bot, \
+         /d@my\\_bot"
+    );
+}
+
+#[test]
+#[cfg(feature = "macros")]
+fn command_arg() {
+    use std::{borrow::Cow, error::Error};
+
+    use teloxide::utils::command::{ArgumentError, CommandArg, ParseError};
+
+    #[derive(Debug, PartialEq)]
+    struct Minutes(u32);
+
+    impl CommandArg for Minutes {
+        fn parse_arg(s: &str) -> Result<Self, Box<dyn Error + Send + Sync>> {
+            Ok(Minutes(s.strip_suffix('m').ok_or("no unit")?.parse()?))
+        }
+
+        fn expected() -> Cow<'static, str> {
+            "duration".into()
+        }
+    }
+
+    #[derive(BotCommands, Debug, PartialEq)]
+    #[command(rename_rule = "lowercase", parse_with = "split")]
+    enum DefaultCommands {
+        Remind(Minutes, String),
+        Repeat(u8, Minutes),
+    }
+
+    assert_eq!(
+        DefaultCommands::parse("/remind 10m milk", "").unwrap(),
+        DefaultCommands::Remind(Minutes(10), "milk".to_owned())
+    );
+
+    let err = DefaultCommands::parse("/remind 10 milk", "").unwrap_err();
+    assert_eq!(err.to_string(), "argument 1: expected duration, found `10` (no unit)");
+
+    match DefaultCommands::parse("/repeat 3 x", "").unwrap_err() {
+        ParseError::InvalidArgument(ArgumentError { position, expected, found, .. }) => {
+            assert_eq!((position, &*expected, &*found), (2, "duration", "x"));
+        }
+        err => panic!("unexpected error: {err}"),
+    }
+
+    let err = DefaultCommands::parse("/repeat x 1m", "").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "argument 1: expected u8, found `x` (invalid digit found in string)"
+    );
+}
+
 #[test]
 #[cfg(feature = "macros")]
 fn custom_result() {
@@ -0,0 +1,59 @@
+#[cfg(feature = "macros")]
+use std::sync::{Arc, Mutex};
+
+#[cfg(feature = "macros")]
+use teloxide::{
+    dispatching::dialogue::{Dialogue, DialogueState, InMemStorage},
+    types::ChatId,
+};
+
+#[cfg(feature = "macros")]
+type Log = Arc<Mutex<Vec<String>>>;
+
+#[cfg(feature = "macros")]
+#[derive(Clone, Debug, DialogueState)]
+#[dialogue(context = Log, error = "Box<dyn std::error::Error + Send + Sync>")]
+enum State {
+    Start,
+    #[dialogue(on_enter = log_enter)]
+    ReceiveName,
+    #[dialogue(on_enter = log_enter, on_exit = log_exit)]
+    ReceiveAge(#[allow(dead_code)] String),
+}
+
+#[cfg(feature = "macros")]
+async fn log_enter(
+    log: Log,
+    chat_id: ChatId,
+    state: State,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    log.lock().unwrap().push(format!("{chat_id}: enter {state:?}"));
+    Ok(())
+}
+
+#[cfg(feature = "macros")]
+async fn log_exit(
+    log: Log,
+    chat_id: ChatId,
+    state: State,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    log.lock().unwrap().push(format!("{chat_id}: exit {state:?}"));
+    Ok(())
+}
+
+#[tokio::test]
+#[cfg(feature = "macros")]
+async fn derived_hooks() {
+    let log = Log::default();
+    let dialogue = Dialogue::new(InMemStorage::<State>::new(), ChatId(1));
+
+    for state in [State::Start, State::ReceiveName, State::ReceiveAge("A".to_owned()), State::Start]
+    {
+        dialogue.transition_to(log.clone(), state).await.unwrap();
+    }
+
+    assert_eq!(
+        *log.lock().unwrap(),
+        ["1: enter ReceiveName", "1: enter ReceiveAge(\"A\")", "1: exit ReceiveAge(\"A\")"]
+    );
+}
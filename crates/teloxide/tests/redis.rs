@@ -4,7 +4,8 @@ use std::{
 };
 use teloxide::{
     dispatching::dialogue::{RedisStorage, RedisStorageError, Serializer, Storage},
-    types::ChatId,
+    types::{ChatId, UpdateId},
+    update_listeners::offset_storage::{OffsetStorage, RedisOffsetStorage},
 };
 
 #[tokio::test]
@@ -83,3 +84,30 @@ where
         RedisStorageError::DialogueNotFound
     ));
 }
+
+#[tokio::test]
+#[cfg_attr(not(CI_REDIS), ignore)]
+async fn test_redis_offset_storage() {
+    let url = "redis://127.0.0.1:7777";
+    let storage = RedisOffsetStorage::open(url, "test_redis_offset_storage").await.unwrap();
+    let other = RedisOffsetStorage::open(url, "test_redis_offset_storage_other").await.unwrap();
+    test_offset_storage(storage, other).await;
+}
+
+async fn test_offset_storage<S>(storage: Arc<S>, other: Arc<S>)
+where
+    S: OffsetStorage,
+    S::Error: Debug,
+{
+    assert_eq!(Arc::clone(&storage).load().await.unwrap(), None);
+
+    Arc::clone(&storage).save(UpdateId(5)).await.unwrap();
+    assert_eq!(Arc::clone(&storage).load().await.unwrap(), Some(UpdateId(5)));
+    Arc::clone(&storage).save(UpdateId(7)).await.unwrap();
+    assert_eq!(Arc::clone(&storage).load().await.unwrap(), Some(UpdateId(7)));
+
+    // Keys don't affect each other
+    assert_eq!(Arc::clone(&other).load().await.unwrap(), None);
+    Arc::clone(&other).save(UpdateId(1)).await.unwrap();
+    assert_eq!(Arc::clone(&storage).load().await.unwrap(), Some(UpdateId(7)));
+}
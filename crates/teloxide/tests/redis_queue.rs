@@ -0,0 +1,53 @@
+use teloxide::{
+    dispatching::shard_of,
+    types::Update,
+    update_listeners::{queue::RedisQueue, AsUpdateStream},
+};
+use tokio_stream::StreamExt;
+
+const SHARDS: u32 = 2;
+
+fn update(id: i32, chat_id: i64) -> Update {
+    serde_json::from_str(&format!(
+        r#"{{
+            "update_id": {id},
+            "message": {{
+                "message_id": {id},
+                "date": 1,
+                "chat": {{ "id": {chat_id}, "type": "private", "first_name": "A" }},
+                "text": "hi"
+            }}
+        }}"#
+    ))
+    .unwrap()
+}
+
+#[tokio::test]
+#[cfg_attr(not(CI_REDIS), ignore)]
+async fn test_redis_queue() {
+    let key = format!("teloxide:test_redis_queue:{}", std::process::id());
+    let queue = RedisQueue::open("redis://127.0.0.1:7777", key).await.unwrap().shards(SHARDS);
+
+    let updates: Vec<_> = (0..20).map(|i| update(i, i64::from(i % 5))).collect();
+
+    for update in &updates {
+        queue.push(update).await.unwrap();
+    }
+
+    for shard in 0..SHARDS {
+        // Consumers start from the beginning of the stream, so updates pushed
+        // before they were created are not lost
+        let mut listener = queue.listener(shard);
+        let expected: Vec<_> =
+            updates.iter().filter(|u| shard_of(u, SHARDS) == shard).map(|u| u.id).collect();
+
+        let stream = listener.as_stream();
+        tokio::pin!(stream);
+        let mut received = Vec::new();
+        while received.len() < expected.len() {
+            received.push(stream.next().await.unwrap().unwrap().id);
+        }
+
+        assert_eq!(received, expected);
+    }
+}
@@ -5,7 +5,8 @@ use std::{
 };
 use teloxide::{
     dispatching::dialogue::{Serializer, SqliteStorage, SqliteStorageError, Storage},
-    types::ChatId,
+    types::{ChatId, UpdateId},
+    update_listeners::offset_storage::{OffsetStorage, SqliteOffsetStorage},
 };
 
 #[tokio::test(flavor = "multi_thread")]
@@ -87,3 +88,31 @@ where
         SqliteStorageError::DialogueNotFound
     ));
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_sqlite_offset_storage() {
+    fs::create_dir("./test_db4").unwrap();
+    let path = "./test_db4/test_db4.sqlite";
+    let storage = SqliteOffsetStorage::open(path, "bot").await.unwrap();
+    let other = SqliteOffsetStorage::open(path, "other_bot").await.unwrap();
+    test_offset_storage(storage, other).await;
+    fs::remove_dir_all("./test_db4").unwrap();
+}
+
+async fn test_offset_storage<S>(storage: Arc<S>, other: Arc<S>)
+where
+    S: OffsetStorage,
+    S::Error: Debug,
+{
+    assert_eq!(Arc::clone(&storage).load().await.unwrap(), None);
+
+    Arc::clone(&storage).save(UpdateId(5)).await.unwrap();
+    assert_eq!(Arc::clone(&storage).load().await.unwrap(), Some(UpdateId(5)));
+    Arc::clone(&storage).save(UpdateId(7)).await.unwrap();
+    assert_eq!(Arc::clone(&storage).load().await.unwrap(), Some(UpdateId(7)));
+
+    // Keys don't affect each other
+    assert_eq!(Arc::clone(&other).load().await.unwrap(), None);
+    Arc::clone(&other).save(UpdateId(1)).await.unwrap();
+    assert_eq!(Arc::clone(&storage).load().await.unwrap(), Some(UpdateId(7)));
+}